@@ -1,15 +1,18 @@
+use core::ops::Range;
+use core::sync::atomic::{AtomicU64, Ordering};
 use core::time::Duration;
 use core::{fmt::Display, hash::Hash};
-use std::{
-    io::Read,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
 use alloc::vec::Vec;
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
-use crate::cache_file::CacheFile;
+use crate::cache_file::{CacheFile, CacheFileContent};
+
+/// The file is compacted when it is at least that big and more than half of it is made of
+/// replaced or removed entries.
+const COMPACTION_MIN_SIZE: u64 = 64 * 1024;
 
 #[derive(Debug)]
 /// An in-memory key-value cache that is automatically synced to disk.
@@ -17,24 +20,33 @@ use crate::cache_file::CacheFile;
 /// The goal is simplicity, ease of use, and ease of distribution. All data is stored in a single
 /// file, which is automatically loaded into memory when using the cache.
 ///
-/// # Warning
-///
-/// ## No Edits
+/// # File Format
 ///
-/// The biggest constraint for the cache is that values should never change for a given key.
-/// There is no update possible; if a value is reinserted a second time with a different value but
-/// the same key, an error will arise.
+/// The file is a log of entries: inserting, replacing or removing an entry only appends a record
+/// to the file, and the last record for a key wins when loading the cache. The file is compacted
+/// automatically once most of it is made of outdated records, or manually with
+/// [compact](Cache::compact). Multiple processes can share the same cache file.
 ///
-/// This is important to keep the file format simple: there is no metadata, no headers, just a plain
-/// separator between each cache entry. Therefore, it isn’t possible to edit previously saved content.
+/// Values are encoded with `serde_json` by default, or with `bincode` when using
+/// [CacheFormat::Binary], which is a lot more efficient for big values like compiled binaries.
 ///
-/// ## No Big Files
+/// # Eviction
 ///
-/// The cache isn’t optimized for space; use it for small caches.
+/// The cache is unbounded by default. When a [maximum size](CacheOption::max_size) or a
+/// [maximum number of entries](CacheOption::max_entries) is set, the least recently used entries
+/// are evicted when the limit is exceeded.
 pub struct Cache<K, V> {
-    in_memory_cache: HashMap<K, V>,
+    in_memory_cache: HashMap<K, CacheSlot<V>>,
     file: CacheFile,
-    separator: Vec<u8>,
+    encoding: RecordEncoding,
+    location: CacheLocation,
+    lock_max_duration: Duration,
+    max_size: Option<u64>,
+    max_entries: Option<usize>,
+    /// The size of the records of all entries currently in the cache.
+    live_size: u64,
+    /// Logical clock used to track when entries were last used.
+    clock: AtomicU64,
 }
 
 /// Define the option to create a cache.
@@ -45,6 +57,24 @@ pub struct CacheOption {
     name: Option<String>,
     root: Option<PathBuf>,
     lock_max_duration: Option<Duration>,
+    format: CacheFormat,
+    max_size: Option<u64>,
+    max_entries: Option<usize>,
+    migrate_from: Vec<String>,
+}
+
+/// How the entries of a cache are encoded on disk.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheFormat {
+    /// Each entry is a JSON object followed by the separator.
+    #[default]
+    Json,
+    /// Each entry is a length-prefixed record where the key is encoded in JSON and the value with
+    /// `bincode`.
+    ///
+    /// The value type must not rely on self-describing formats, e.g. `#[serde(untagged)]` or
+    /// `#[serde(flatten)]` aren't supported.
+    Binary,
 }
 
 /// Error related to caching.
@@ -70,13 +100,16 @@ pub enum CacheError<K: Serialize, V: Serialize> {
 impl CacheOption {
     /// The separator used between each entry in the cache.
     ///
-    /// It should not be used in both the keys and the values.
+    /// It should not be used in both the keys and the values. Only used with [CacheFormat::Json].
     pub fn separator<S: Into<Vec<u8>>>(mut self, separator: S) -> Self {
         self.separator = Some(separator.into());
         self
     }
 
     /// The version used for the cache.
+    ///
+    /// Each version is stored in its own directory, see [migrate_from](Self::migrate_from) to keep
+    /// the entries of a previous version.
     pub fn version<V: Into<String>>(mut self, version: V) -> Self {
         self.version = Some(version.into());
         self
@@ -98,13 +131,42 @@ impl CacheOption {
         self
     }
 
-    fn resolve(self) -> (Vec<u8>, String, String, PathBuf, Duration) {
+    /// How the entries are encoded on disk.
+    pub fn format(mut self, format: CacheFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// The maximum size in bytes of the entries stored on disk.
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// The maximum number of entries in the cache.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// A previous version of the cache to import the entries from when the cache of the current
+    /// version doesn't exist yet.
+    ///
+    /// Can be called multiple times, the first version found on disk is used. Only the entries
+    /// that can still be decoded with the current key and value types are kept, use
+    /// [Cache::migrate] to convert entries between different types.
+    pub fn migrate_from<V: Into<String>>(mut self, version: V) -> Self {
+        self.migrate_from.push(version.into());
+        self
+    }
+
+    fn resolve(self) -> ResolvedCacheOption {
         let separator = self.separator.unwrap_or_else(|| b"\n".to_vec());
         let version = self
             .version
             .unwrap_or_else(|| std::env!("CARGO_PKG_VERSION").to_string());
         let name = self.name.unwrap_or_else(|| "cubecl".to_string());
-        let duration = self
+        let lock_max_duration = self
             .lock_max_duration
             .unwrap_or_else(|| Duration::from_secs(30));
         let root = match self.root {
@@ -114,10 +176,33 @@ impl CacheOption {
                 .join(".cache"),
         };
 
-        (separator, name, version, root, duration)
+        ResolvedCacheOption {
+            encoding: RecordEncoding {
+                format: self.format,
+                separator,
+            },
+            name,
+            version,
+            root,
+            lock_max_duration,
+            max_size: self.max_size,
+            max_entries: self.max_entries,
+            migrate_from: self.migrate_from,
+        }
     }
 }
 
+struct ResolvedCacheOption {
+    encoding: RecordEncoding,
+    name: String,
+    version: String,
+    root: PathBuf,
+    lock_max_duration: Duration,
+    max_size: Option<u64>,
+    max_entries: Option<usize>,
+    migrate_from: Vec<String>,
+}
+
 /// Trait to be implemented for cache keys.
 pub trait CacheKey: Serialize + DeserializeOwned + PartialEq + Eq + Hash + Clone {}
 /// Trait to be implemented for cache value.
@@ -133,25 +218,41 @@ impl<K: CacheKey, V: CacheValue> Cache<K, V> {
         skip(path),
         fields(path = ?path.as_ref())))]
     pub fn new<P: AsRef<Path>>(path: P, option: CacheOption) -> Self {
-        let (separator, name, version, root, lock_max_duration) = option.resolve();
-        let path = get_persistent_cache_file_path(path, root, name, version);
+        let option = option.resolve();
+        let location = CacheLocation {
+            path: path.as_ref().to_path_buf(),
+            root: option.root,
+            name: option.name,
+        };
+        let path = location.file_path(&option.version, option.encoding.format);
 
         let mut this = Self {
             in_memory_cache: HashMap::new(),
-            file: CacheFile::new(&path, lock_max_duration),
-            separator,
+            file: CacheFile::new(&path, option.lock_max_duration),
+            encoding: option.encoding,
+            location,
+            lock_max_duration: option.lock_max_duration,
+            max_size: option.max_size,
+            max_entries: option.max_entries,
+            live_size: 0,
+            clock: AtomicU64::new(0),
         };
 
-        if let Some(mut reader) = this.file.lock() {
-            let mut buffer = Vec::new();
-            reader
-                .read_to_end(&mut buffer)
-                .expect("Can read the cache content");
-            this.sync_content(&buffer, None).ok();
-        }
-
+        this.sync(None).ok();
+        let is_new = this.file.size() == 0;
+        this.maintain();
         this.file.unlock();
 
+        if is_new {
+            for version in option.migrate_from.iter() {
+                let migrated = this.migrate::<K, V, _>(version, |key, value| Some((key, value)));
+                if migrated > 0 {
+                    log::info!("Migrated {migrated} entries from version {version} of the cache");
+                    break;
+                }
+            }
+        }
+
         this
     }
 
@@ -161,14 +262,10 @@ impl<K: CacheKey, V: CacheValue> Cache<K, V> {
         tracing::instrument(level = "trace", skip(self, func))
     )]
     pub fn for_each<F: FnMut(&K, &V)>(&mut self, mut func: F) {
-        if let Some(mut reader) = self.file.lock() {
-            let mut buffer = Vec::new();
-            reader.read_to_end(&mut buffer).unwrap();
-            self.sync_content(&buffer, None).ok();
-        }
+        self.sync(None).ok();
 
-        for (key, value) in self.in_memory_cache.iter() {
-            func(key, value);
+        for (key, slot) in self.in_memory_cache.iter() {
+            func(key, &slot.value);
         }
 
         self.file.unlock();
@@ -176,7 +273,9 @@ impl<K: CacheKey, V: CacheValue> Cache<K, V> {
 
     /// Fetch an item from the cache.
     pub fn get(&self, key: &K) -> Option<&V> {
-        self.in_memory_cache.get(key)
+        let slot = self.in_memory_cache.get(key)?;
+        slot.last_used.store(self.tick(), Ordering::Relaxed);
+        Some(&slot.value)
     }
 
     /// The size of the cache.
@@ -191,25 +290,21 @@ impl<K: CacheKey, V: CacheValue> Cache<K, V> {
 
     /// Insert a new item to the cache.
     ///
-    /// Panic if an item with a different value exists in the cache.
+    /// Returns an error if an item with a different value exists in the cache, use
+    /// [replace](Self::replace) to update an item.
     pub fn insert(&mut self, key: K, value: V) -> Result<(), CacheError<K, V>> {
-        if let Some(mut reader) = self.file.lock() {
-            let mut buffer = Vec::new();
-            reader.read_to_end(&mut buffer).unwrap();
-
-            if let Err(err) = self.sync_content(&buffer, Some((&key, &value))) {
-                self.file.unlock();
-                return Err(err);
-            }
+        if let Err(err) = self.sync(Some((&key, &value))) {
+            self.file.unlock();
+            return Err(err);
         }
 
         if let Some(existing) = self.in_memory_cache.get(&key) {
-            if existing != &value {
+            if existing.value != value {
                 self.file.unlock();
 
                 return Err(CacheError::DuplicatedKey {
                     key,
-                    value_previous: existing.clone(),
+                    value_previous: existing.value.clone(),
                     value_updated: value,
                 });
             } else {
@@ -219,63 +314,257 @@ impl<K: CacheKey, V: CacheValue> Cache<K, V> {
         }
 
         self.insert_unchecked(key, value);
+        self.maintain();
 
         self.file.unlock();
         Ok(())
     }
 
+    /// Insert an item to the cache, replacing the previous value for the same key.
+    ///
+    /// Returns the previous value if any.
+    pub fn replace(&mut self, key: K, value: V) -> Option<V> {
+        self.sync(None).ok();
+
+        let previous = match self.in_memory_cache.get(&key) {
+            Some(existing) if existing.value == value => {
+                existing.last_used.store(self.tick(), Ordering::Relaxed);
+                Some(value)
+            }
+            _ => {
+                let previous = self.insert_unchecked(key, value);
+                self.maintain();
+                previous
+            }
+        };
+
+        self.file.unlock();
+        previous
+    }
+
+    /// Remove an item from the cache, returning its value if it existed.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.sync(None).ok();
+
+        let removed = self.in_memory_cache.remove(key).map(|slot| {
+            self.live_size -= slot.size;
+            slot.value
+        });
+
+        if removed.is_some() {
+            let bytes = self.encoding.encode_removed(key);
+            self.file.write(&bytes);
+            self.maintain();
+        }
+
+        self.file.unlock();
+        removed
+    }
+
+    /// Rewrite the cache file with only the current entries.
+    ///
+    /// This is done automatically when most of the file is made of outdated entries.
+    pub fn compact(&mut self) {
+        self.sync(None).ok();
+        self.compact_locked();
+        self.file.unlock();
+    }
+
+    /// Import the entries of a previous version of the cache, converting them with `func`.
+    ///
+    /// Entries returning `None` or already present in the cache are skipped. Returns the number
+    /// of migrated entries.
+    pub fn migrate<KP, VP, F>(&mut self, version: &str, mut func: F) -> usize
+    where
+        KP: CacheKey,
+        VP: CacheValue,
+        F: FnMut(KP, VP) -> Option<(K, V)>,
+    {
+        let Some((path, encoding)) = self.location.find(version, &self.encoding) else {
+            return 0;
+        };
+
+        let mut file = CacheFile::new(&path, self.lock_max_duration);
+        let content = match file.lock() {
+            Some(CacheFileContent::Appended(bytes) | CacheFileContent::Rewritten(bytes)) => bytes,
+            None => Vec::new(),
+        };
+        file.unlock();
+
+        let mut previous = Vec::<(KP, VP)>::new();
+        for (record, range) in encoding.decode::<KP, VP>(&content) {
+            match record {
+                Ok(Record::Entry { key, value }) => {
+                    previous.retain(|(k, _)| k != &key);
+                    previous.push((key, value));
+                }
+                Ok(Record::Removed { removed }) => previous.retain(|(k, _)| k != &removed),
+                Err(err) => {
+                    log::debug!("Skipping entry ({range:?}) of cache file {file} : {err}")
+                }
+            }
+        }
+
+        self.sync(None).ok();
+
+        let mut migrated = 0;
+        for (key, value) in previous {
+            if let Some((key, value)) = func(key, value)
+                && !self.in_memory_cache.contains_key(&key)
+            {
+                self.insert_unchecked(key, value);
+                migrated += 1;
+            }
+        }
+
+        self.maintain();
+        self.file.unlock();
+        migrated
+    }
+
+    /// Lock the file and load the entries written by other processes.
+    fn sync(&mut self, new_insert: Option<(&K, &V)>) -> Result<(), CacheError<K, V>> {
+        match self.file.lock() {
+            Some(CacheFileContent::Appended(bytes)) => self.sync_content(&bytes, new_insert),
+            Some(CacheFileContent::Rewritten(bytes)) => {
+                self.in_memory_cache.clear();
+                self.live_size = 0;
+                self.sync_content(&bytes, new_insert)
+            }
+            None => Ok(()),
+        }
+    }
+
     fn sync_content(
         &mut self,
         bytes: &[u8],
         new_insert: Option<(&K, &V)>,
     ) -> Result<(), CacheError<K, V>> {
-        let mut start = 0;
         let mut result = Ok(());
 
-        while let Some(pos) = bytes[start..]
-            .windows(self.separator.len())
-            .position(|w| w == self.separator)
-        {
-            match serde_json::from_slice::<Entry<K, V>>(&bytes[start..start + pos]) {
-                Ok(entry) => {
+        for (record, range) in self.encoding.decode::<K, V>(bytes) {
+            match record {
+                Ok(Record::Entry { key, value }) => {
                     if let Some(insert) = &new_insert
                         && result.is_ok()
-                        && insert.0 == &entry.key
-                        && insert.1 != &entry.value
+                        && insert.0 == &key
+                        && insert.1 != &value
                     {
                         result = Err(CacheError::KeyOutOfSync {
-                            key: entry.key.clone(),
-                            value_previous: entry.value.clone(),
+                            key: key.clone(),
+                            value_previous: value.clone(),
                             value_updated: insert.1.clone(),
                         })
                     }
-                    self.in_memory_cache.insert(entry.key, entry.value);
+                    self.store(key, value, range.len() as u64);
+                }
+                Ok(Record::Removed { removed }) => {
+                    if let Some(slot) = self.in_memory_cache.remove(&removed) {
+                        self.live_size -= slot.size;
+                    }
                 }
                 Err(err) => {
                     log::warn!(
                         "Corrupted cache file {}, ignoring entry ({}..{}) : {err}",
                         self.file,
-                        start,
-                        start + pos,
+                        range.start,
+                        range.end,
                     );
                 }
             };
-            start += pos + self.separator.len();
         }
 
         result
     }
 
-    fn insert_unchecked(&mut self, key: K, value: V) {
-        let entry = Entry { key, value };
-        let mut bytes = serde_json::to_vec(&entry).expect("Can serialize data");
+    fn insert_unchecked(&mut self, key: K, value: V) -> Option<V> {
+        let bytes = self.encoding.encode_entry(&key, &value);
+
+        self.file.write(&bytes);
+        self.store(key, value, bytes.len() as u64)
+    }
+
+    fn store(&mut self, key: K, value: V, size: u64) -> Option<V> {
+        let slot = CacheSlot {
+            value,
+            size,
+            last_used: AtomicU64::new(self.tick()),
+        };
+        self.live_size += size;
+
+        let previous = self.in_memory_cache.insert(key, slot)?;
+        self.live_size -= previous.size;
+        Some(previous.value)
+    }
 
-        for b in self.separator.iter() {
-            bytes.push(*b);
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Evict entries when the cache is over its limits and compact the file when needed.
+    ///
+    /// The file must be locked.
+    fn maintain(&mut self) {
+        let over_size = self.max_size.is_some_and(|max| self.live_size > max);
+        let over_entries = self
+            .max_entries
+            .is_some_and(|max| self.in_memory_cache.len() > max);
+
+        if over_size || over_entries {
+            self.evict();
+            self.compact_locked();
+        } else if self.file.size() >= COMPACTION_MIN_SIZE && self.file.size() > 2 * self.live_size {
+            self.compact_locked();
         }
+    }
 
-        self.file.write(&bytes);
-        self.in_memory_cache.insert(entry.key, entry.value);
+    /// Evict the least recently used entries until the cache is a quarter below its limits, so
+    /// the file isn't rewritten on every insert once the cache is full.
+    fn evict(&mut self) {
+        let max_size = self.max_size.map(|max| max - max / 4).unwrap_or(u64::MAX);
+        let max_entries = self
+            .max_entries
+            .map(|max| max - max / 4)
+            .unwrap_or(usize::MAX);
+
+        let mut entries = self
+            .in_memory_cache
+            .iter()
+            .map(|(key, slot)| (slot.last_used.load(Ordering::Relaxed), key.clone()))
+            .collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|(last_used, _)| *last_used);
+
+        let mut evicted = 0;
+        for (_, key) in entries {
+            if self.live_size <= max_size && self.in_memory_cache.len() <= max_entries {
+                break;
+            }
+            if let Some(slot) = self.in_memory_cache.remove(&key) {
+                self.live_size -= slot.size;
+                evicted += 1;
+            }
+        }
+
+        log::debug!("Evicted {evicted} entries from cache file {}", self.file);
+    }
+
+    /// Rewrite the file with the current entries, from the least to the most recently used so the
+    /// order is kept when loading the file again.
+    ///
+    /// The file must be locked.
+    fn compact_locked(&mut self) {
+        let mut entries = self.in_memory_cache.iter_mut().collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|(_, slot)| slot.last_used.load(Ordering::Relaxed));
+
+        let mut content = Vec::new();
+        for (key, slot) in entries {
+            let bytes = self.encoding.encode_entry(key, &slot.value);
+            slot.size = bytes.len() as u64;
+            content.extend_from_slice(&bytes);
+        }
+
+        self.live_size = content.len() as u64;
+        self.file.rewrite(&content);
     }
 }
 
@@ -283,9 +572,9 @@ impl<K: CacheKey, V: CacheValue> Display for Cache<K, V> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.file)?;
 
-        for (key, value) in self.in_memory_cache.iter() {
+        for (key, slot) in self.in_memory_cache.iter() {
             let key = serde_json::to_string_pretty(key).unwrap();
-            let value = serde_json::to_string_pretty(value).unwrap();
+            let value = serde_json::to_string_pretty(&slot.value).unwrap();
 
             writeln!(f, "  [{key}] => {value}")?;
         }
@@ -294,18 +583,68 @@ impl<K: CacheKey, V: CacheValue> Display for Cache<K, V> {
     }
 }
 
+#[derive(Debug)]
+struct CacheSlot<V> {
+    value: V,
+    /// The size of the record of the entry in the file.
+    size: u64,
+    last_used: AtomicU64,
+}
+
+/// Where the files of a cache are stored, used to find previous versions of the cache.
+#[derive(Debug)]
+struct CacheLocation {
+    path: PathBuf,
+    root: PathBuf,
+    name: String,
+}
+
+impl CacheLocation {
+    fn file_path(&self, version: &str, format: CacheFormat) -> PathBuf {
+        get_persistent_cache_file_path(
+            &self.path,
+            self.root.clone(),
+            &self.name,
+            version,
+            format.extension(),
+        )
+    }
+
+    /// Find the file of the given version, in either format.
+    fn find(&self, version: &str, encoding: &RecordEncoding) -> Option<(PathBuf, RecordEncoding)> {
+        [CacheFormat::Json, CacheFormat::Binary]
+            .into_iter()
+            .map(|format| RecordEncoding {
+                format,
+                separator: encoding.separator.clone(),
+            })
+            .map(|encoding| (self.file_path(version, encoding.format), encoding))
+            .find(|(path, _)| std::fs::exists(path).unwrap_or(false))
+    }
+}
+
+impl CacheFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            CacheFormat::Json => "json.log",
+            CacheFormat::Binary => "bin.log",
+        }
+    }
+}
+
 fn get_persistent_cache_file_path<P: AsRef<Path>>(
     path_partial: P,
     root: PathBuf,
-    name: String,
-    version: String,
+    name: &str,
+    version: &str,
+    extension: &str,
 ) -> PathBuf {
     let path_partial: &Path = path_partial.as_ref();
-    let add_extension = !path_partial.ends_with("json.log");
+    let add_extension = !path_partial.ends_with(extension);
 
     let mut path = root
-        .join(sanitize_path_segment(&name))
-        .join(sanitize_path_segment(&version));
+        .join(sanitize_path_segment(name))
+        .join(sanitize_path_segment(version));
 
     for segment in path_partial.iter() {
         // Skip the name directory since it resets the previous path segments.
@@ -316,25 +655,163 @@ fn get_persistent_cache_file_path<P: AsRef<Path>>(
     }
 
     if add_extension {
-        path.set_extension("json.log");
+        path.set_extension(extension);
     }
 
     path
 }
 
+/// A record of the cache file.
 #[derive(Serialize, Deserialize)]
-struct Entry<K, V> {
-    key: K,
-    value: V,
+#[serde(untagged)]
+enum Record<K, V> {
+    Entry { key: K, value: V },
+    Removed { removed: K },
 }
 
-impl<K: Serialize, V: Serialize> core::fmt::Debug for Entry<K, V> {
+impl<K: Serialize, V: Serialize> core::fmt::Debug for Record<K, V> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let formatted = serde_json::to_string_pretty(self).unwrap();
         write!(f, "{formatted}")
     }
 }
 
+const RECORD_ENTRY: u8 = 0;
+const RECORD_REMOVED: u8 = 1;
+/// Tag, key length and value length of a binary record.
+const RECORD_HEADER_LEN: usize = 1 + 4 + 8;
+
+#[derive(Debug)]
+struct RecordEncoding {
+    format: CacheFormat,
+    separator: Vec<u8>,
+}
+
+impl RecordEncoding {
+    fn encode_entry<K: Serialize, V: Serialize>(&self, key: &K, value: &V) -> Vec<u8> {
+        match self.format {
+            CacheFormat::Json => self.encode_json(&Record::Entry { key, value }),
+            CacheFormat::Binary => {
+                let value = bincode::serde::encode_to_vec(value, bincode::config::standard())
+                    .expect("Can serialize data");
+                encode_binary(RECORD_ENTRY, key, &value)
+            }
+        }
+    }
+
+    fn encode_removed<K: Serialize>(&self, key: &K) -> Vec<u8> {
+        match self.format {
+            CacheFormat::Json => self.encode_json(&Record::<_, ()>::Removed { removed: key }),
+            CacheFormat::Binary => encode_binary(RECORD_REMOVED, key, &[]),
+        }
+    }
+
+    fn encode_json<K: Serialize, V: Serialize>(&self, record: &Record<K, V>) -> Vec<u8> {
+        let mut bytes = serde_json::to_vec(record).expect("Can serialize data");
+        bytes.extend_from_slice(&self.separator);
+        bytes
+    }
+
+    /// Decode all records, along with the range of bytes they occupy.
+    #[allow(clippy::type_complexity)]
+    fn decode<K: DeserializeOwned, V: DeserializeOwned>(
+        &self,
+        bytes: &[u8],
+    ) -> Vec<(Result<Record<K, V>, String>, Range<usize>)> {
+        match self.format {
+            CacheFormat::Json => self.decode_json(bytes),
+            CacheFormat::Binary => decode_binary(bytes),
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn decode_json<K: DeserializeOwned, V: DeserializeOwned>(
+        &self,
+        bytes: &[u8],
+    ) -> Vec<(Result<Record<K, V>, String>, Range<usize>)> {
+        let mut records = Vec::new();
+        let mut start = 0;
+
+        while let Some(pos) = bytes[start..]
+            .windows(self.separator.len())
+            .position(|w| w == self.separator)
+        {
+            let record =
+                serde_json::from_slice(&bytes[start..start + pos]).map_err(|err| format!("{err}"));
+            let end = start + pos + self.separator.len();
+            records.push((record, start..end));
+            start = end;
+        }
+
+        records
+    }
+}
+
+fn encode_binary<K: Serialize>(tag: u8, key: &K, value: &[u8]) -> Vec<u8> {
+    let key = serde_json::to_vec(key).expect("Can serialize data");
+
+    let mut bytes = Vec::with_capacity(RECORD_HEADER_LEN + key.len() + value.len());
+    bytes.push(tag);
+    bytes.extend_from_slice(&(key.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&key);
+    bytes.extend_from_slice(value);
+    bytes
+}
+
+#[allow(clippy::type_complexity)]
+fn decode_binary<K: DeserializeOwned, V: DeserializeOwned>(
+    bytes: &[u8],
+) -> Vec<(Result<Record<K, V>, String>, Range<usize>)> {
+    let mut records = Vec::new();
+    let mut start = 0;
+
+    while start < bytes.len() {
+        let Some(header) = bytes.get(start..start + RECORD_HEADER_LEN) else {
+            records.push((Err("Truncated record".to_string()), start..bytes.len()));
+            break;
+        };
+        let tag = header[0];
+        let key_len = u32::from_le_bytes(header[1..5].try_into().unwrap()) as usize;
+        let value_len = u64::from_le_bytes(header[5..13].try_into().unwrap());
+
+        // Corrupted lengths can overflow, which can only mean the record doesn't fit either.
+        let key_start = start + RECORD_HEADER_LEN;
+        let bounds = key_start.checked_add(key_len).and_then(|value_start| {
+            let end = value_start.checked_add(usize::try_from(value_len).ok()?)?;
+            (end <= bytes.len()).then_some((value_start, end))
+        });
+        let Some((value_start, end)) = bounds else {
+            records.push((Err("Truncated record".to_string()), start..bytes.len()));
+            break;
+        };
+
+        let key = serde_json::from_slice::<K>(&bytes[key_start..value_start])
+            .map_err(|err| format!("{err}"));
+        let record = match tag {
+            RECORD_ENTRY => key.and_then(|key| {
+                bincode::serde::decode_from_slice::<V, _>(
+                    &bytes[value_start..end],
+                    bincode::config::standard(),
+                )
+                .map(|(value, _)| Record::Entry { key, value })
+                .map_err(|err| format!("{err}"))
+            }),
+            RECORD_REMOVED => key.map(|removed| Record::Removed { removed }),
+            tag => {
+                // The length of unknown records can't be trusted, so the rest is unreadable.
+                records.push((Err(format!("Unknown record {tag}")), start..bytes.len()));
+                break;
+            }
+        };
+
+        records.push((record, start..end));
+        start = end;
+    }
+
+    records
+}
+
 pub(crate) fn sanitize_path_segment(segment: &str) -> String {
     sanitize_filename::sanitize_with_options(
         segment,
@@ -376,4 +853,128 @@ mod tests {
         let value2_actual = cache.get(&key2()).unwrap();
         assert_eq!(value2_actual, &value2());
     }
+
+    fn temp_option(root: &Path) -> CacheOption {
+        CacheOption::default().root(root).name("test").version("v2")
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_cache_replace_and_remove() {
+        let root = tempfile::tempdir().unwrap();
+
+        let mut cache = Cache::<String, u32>::new("entries", temp_option(root.path()));
+        cache.insert("a".to_string(), 1).unwrap();
+        cache.insert("b".to_string(), 2).unwrap();
+        assert_eq!(cache.replace("a".to_string(), 3), Some(1));
+        assert_eq!(cache.remove(&"b".to_string()), Some(2));
+        assert_eq!(cache.remove(&"b".to_string()), None);
+
+        let mut reloaded = Cache::<String, u32>::new("entries", temp_option(root.path()));
+        assert_eq!(reloaded.len(), 1);
+        assert_eq!(reloaded.get(&"a".to_string()), Some(&3));
+
+        // Changes from another instance are synced before modifying the cache.
+        reloaded.remove(&"a".to_string());
+        cache.insert("c".to_string(), 4).unwrap();
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_cache_compaction() {
+        let root = tempfile::tempdir().unwrap();
+
+        let mut cache = Cache::<u32, Vec<u8>>::new("compact", temp_option(root.path()));
+        for i in 0..64 {
+            cache.replace(i % 4, vec![i as u8; 4096]);
+        }
+        assert!(cache.file.size() < 2 * COMPACTION_MIN_SIZE);
+
+        let mut other = Cache::<u32, Vec<u8>>::new("compact", temp_option(root.path()));
+        cache.replace(0, vec![1; 8]);
+        cache.compact();
+        assert!(cache.file.size() < cache.live_size + 64);
+
+        // The other instance reloads the rewritten file.
+        other.for_each(|_, _| {});
+        assert_eq!(other.len(), 4);
+        assert_eq!(other.get(&0), Some(&vec![1; 8]));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_cache_eviction() {
+        let root = tempfile::tempdir().unwrap();
+        let option = || temp_option(root.path()).max_entries(4);
+
+        let mut cache = Cache::<u32, u32>::new("lru", option());
+        for i in 0..4 {
+            cache.insert(i, i).unwrap();
+        }
+        cache.get(&0);
+        cache.insert(4, 4).unwrap();
+
+        assert_eq!(cache.len(), 3);
+        assert!(cache.get(&0).is_some());
+        assert!(cache.get(&1).is_none());
+        assert!(cache.get(&2).is_none());
+
+        let reloaded = Cache::<u32, u32>::new("lru", option());
+        assert_eq!(reloaded.len(), 3);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_cache_binary() {
+        let root = tempfile::tempdir().unwrap();
+        let option = || temp_option(root.path()).format(CacheFormat::Binary);
+
+        let mut cache = Cache::<String, Vec<u8>>::new("binary", option());
+        cache.insert("a".to_string(), vec![0, 10, 255]).unwrap();
+        cache.insert("b\n".to_string(), vec![10; 1024]).unwrap();
+        cache.remove(&"a".to_string());
+
+        let reloaded = Cache::<String, Vec<u8>>::new("binary", option());
+        assert_eq!(reloaded.len(), 1);
+        assert_eq!(reloaded.get(&"b\n".to_string()), Some(&vec![10; 1024]));
+    }
+
+    #[test]
+    fn test_decode_binary_overflowing_length() {
+        let mut bytes = vec![RECORD_ENTRY];
+        bytes.extend(2u32.to_le_bytes());
+        bytes.extend(u64::MAX.to_le_bytes());
+        bytes.extend(b"\"\"");
+
+        let records = decode_binary::<String, u32>(&bytes);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].0.as_ref().err().unwrap(), "Truncated record");
+        assert_eq!(records[0].1, 0..bytes.len());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_cache_migration() {
+        let root = tempfile::tempdir().unwrap();
+        let option = |version| CacheOption::default().root(root.path()).version(version);
+
+        let mut cache = Cache::<String, u32>::new("migrate", option("v1"));
+        cache.insert("a".to_string(), 1).unwrap();
+        cache.insert("b".to_string(), 2).unwrap();
+
+        let cache = Cache::<String, u32>::new(
+            "migrate",
+            option("v2").format(CacheFormat::Binary).migrate_from("v1"),
+        );
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&"b".to_string()), Some(&2));
+
+        let mut cache = Cache::<String, u64>::new("migrate", option("v3"));
+        let migrated = cache.migrate::<String, u32, _>("v2", |key, value| {
+            (value > 1).then(|| (key, value as u64 * 10))
+        });
+        assert_eq!(migrated, 1);
+        assert_eq!(cache.get(&"b".to_string()), Some(&20));
+    }
 }
@@ -1,16 +1,34 @@
 use core::{fmt::Display, time::Duration};
 use std::{
     fs::{self, File},
-    io::{BufReader, Seek, SeekFrom, Write},
+    io::{Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
 };
 
+/// Marks the start of a file written by [`CacheFile::rewrite`], followed by the hexadecimal
+/// generation of the file and a new line.
+const HEADER_MAGIC: &[u8] = b"#cubecl-cache ";
+const HEADER_LEN: usize = HEADER_MAGIC.len() + 17;
+
 /// Multi-process safe append-only file .
+///
+/// The file can also be rewritten entirely, in which case a new generation is written in its
+/// header so that other processes know they have to load the content again.
 #[derive(Debug)]
 pub struct CacheFile {
     path: PathBuf,
     lock: FileLock,
     cursor: u64,
+    header: Option<Vec<u8>>,
+}
+
+/// The content read from a [`CacheFile`] when locking it.
+#[derive(Debug)]
+pub enum CacheFileContent {
+    /// The content appended since the last lock.
+    Appended(Vec<u8>),
+    /// The file was rewritten since the last lock, this is all of its content.
+    Rewritten(Vec<u8>),
 }
 
 impl Display for CacheFile {
@@ -38,23 +56,42 @@ impl CacheFile {
             lock: FileLock::new(&path, lock_max_duration),
             path,
             cursor: 0,
+            header: None,
         }
     }
 
     /// Locks the file and returns the content that wasn't synced since the last lock.
-    pub fn lock(&mut self) -> Option<BufReader<File>> {
+    pub fn lock(&mut self) -> Option<CacheFileContent> {
         self.lock.lock();
 
-        let mut file = File::open(&self.path).unwrap();
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&self.path)
+            .unwrap();
         let end = file.metadata().unwrap().len();
-        file.seek(SeekFrom::Start(self.cursor)).unwrap();
 
+        let header = read_header(&mut file);
+        let rewritten = self.header.as_ref() != Some(&header);
+        if rewritten {
+            self.cursor = header.len() as u64;
+            self.header = Some(header);
+        }
+
+        let mut content = Vec::new();
         if self.cursor < end {
-            let buf = BufReader::new(file);
-            self.cursor = end;
-            Some(buf)
+            file.seek(SeekFrom::Start(self.cursor)).unwrap();
+            file.read_to_end(&mut content).unwrap();
+            self.cursor += content.len() as u64;
+        } else if !rewritten {
+            return None;
+        }
+
+        if rewritten {
+            Some(CacheFileContent::Rewritten(content))
         } else {
-            None
+            Some(CacheFileContent::Appended(content))
         }
     }
 
@@ -63,6 +100,11 @@ impl CacheFile {
         self.lock.unlock();
     }
 
+    /// The size of the file, as of the last time it was locked.
+    pub fn size(&self) -> u64 {
+        self.cursor
+    }
+
     /// Write the content to the file.
     ///
     /// Panics if the file isn't locked or there is an internal error.
@@ -76,18 +118,67 @@ impl CacheFile {
             .open(&self.path)
             .unwrap();
 
-        self.cursor += file.write(content).unwrap() as u64;
+        file.write_all(content).unwrap();
+        self.cursor += content.len() as u64;
+    }
+
+    /// Replace the whole content of the file with a new generation.
+    ///
+    /// The new file is written next to the current one before being moved in place, so other
+    /// processes never observe a partially written file.
+    ///
+    /// Panics if the file isn't locked or there is an internal error.
+    pub fn rewrite(&mut self, content: &[u8]) {
+        if !self.lock.is_lock {
+            panic!("The cache file should be locked before rewriting it.")
+        }
+
+        let header = new_header();
+        let mut path_tmp = self.path.clone().into_os_string();
+        path_tmp.push(".tmp");
+
+        let mut file = File::create(&path_tmp).unwrap();
+        file.write_all(&header).unwrap();
+        file.write_all(content).unwrap();
+        file.sync_all().ok();
+        core::mem::drop(file);
+        fs::rename(&path_tmp, &self.path).unwrap();
+
+        self.cursor = (header.len() + content.len()) as u64;
+        self.header = Some(header);
+    }
+}
+
+/// Reads the header of the file, which is empty for files that were never rewritten.
+fn read_header(file: &mut File) -> Vec<u8> {
+    let mut header = vec![0; HEADER_LEN];
+    file.seek(SeekFrom::Start(0)).unwrap();
+
+    match file.read_exact(&mut header) {
+        Ok(()) if header.starts_with(HEADER_MAGIC) => header,
+        _ => Vec::new(),
     }
 }
 
+fn new_header() -> Vec<u8> {
+    let time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    let generation = time.as_nanos() as u64 ^ ((std::process::id() as u64) << 32);
+
+    let mut header = HEADER_MAGIC.to_vec();
+    header.extend_from_slice(format!("{generation:016x}\n").as_bytes());
+    header
+}
+
 #[derive(Debug)]
 /// A very simple file lock that only depends on std.
 ///
 /// The lock is only valid for a fixed duration; after that, there is no guarantee.
 /// This is to combat corrupted data, since killing a process might leave the lock file on disk.
 ///
-/// Since it is used with an append-only cache file, we could simply delete the entire cache file
-/// when the lock is outdated.
+/// Since it is used with an append-only cache file that is only rewritten atomically, we could
+/// simply delete the entire cache file when the lock is outdated.
 struct FileLock {
     is_lock: bool,
     path_lock: PathBuf,
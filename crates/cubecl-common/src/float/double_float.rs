@@ -0,0 +1,447 @@
+use core::{
+    cmp::Ordering,
+    fmt::{Debug, Display, Formatter},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign},
+};
+
+use bytemuck::{Pod, Zeroable};
+use num_traits::{Num, NumCast, One, ToPrimitive, Zero};
+
+/// An emulated double precision floating point type, stored as the unevaluated sum of two
+/// [`prim@f32`].
+///
+/// The high word holds the value rounded to [`prim@f32`], and the low word the rounding error, so
+/// the pair has about 48 bits of mantissa with the exponent range of [`prim@f32`]. Kernels compute
+/// on it with error-free transformations on devices without native [`prim@f64`] support.
+///
+/// Arithmetic on the host goes through [`prim@f64`], which is at least as precise as the
+/// emulation.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Default, Zeroable, Pod, PartialEq)]
+pub struct df64 {
+    hi: f32,
+    lo: f32,
+}
+
+impl df64 {
+    /// Number of significant binary digits, twice the mantissa of [`prim@f32`].
+    pub const MANTISSA_DIGITS: u32 = 2 * f32::MANTISSA_DIGITS;
+    /// Approximate number of significant decimal digits.
+    pub const DIGITS: u32 = 14;
+    /// Difference between `1.0` and the next larger representable number.
+    pub const EPSILON: Self = Self::from_f32(1.0 / (1u64 << 48) as f32);
+    /// Largest finite value.
+    pub const MAX: Self = Self::from_f32(f32::MAX);
+    /// Smallest finite value.
+    pub const MIN: Self = Self::from_f32(f32::MIN);
+    /// Smallest positive normal value.
+    pub const MIN_POSITIVE: Self = Self::from_f32(f32::MIN_POSITIVE);
+    /// Infinity.
+    pub const INFINITY: Self = Self::from_f32(f32::INFINITY);
+    /// Negative infinity.
+    pub const NEG_INFINITY: Self = Self::from_f32(f32::NEG_INFINITY);
+    /// Not a number.
+    pub const NAN: Self = Self::from_f32(f32::NAN);
+
+    /// Create a `df64` from its high and low words.
+    ///
+    /// The words should be normalized, with `lo` smaller than half an ulp of `hi`.
+    pub const fn from_parts(hi: f32, lo: f32) -> Self {
+        df64 { hi, lo }
+    }
+
+    /// The high and low words of the `df64`.
+    pub const fn to_parts(self) -> (f32, f32) {
+        (self.hi, self.lo)
+    }
+
+    /// Create a `df64` from [`prim@f32`], which is represented exactly.
+    pub const fn from_f32(val: f32) -> Self {
+        df64 { hi: val, lo: 0.0 }
+    }
+
+    /// Create a `df64` from [`prim@f64`], rounding the mantissa to 48 bits.
+    pub const fn from_f64(val: f64) -> Self {
+        let hi = val as f32;
+        if !hi.is_finite() {
+            return Self::from_f32(hi);
+        }
+        df64 {
+            hi,
+            lo: (val - hi as f64) as f32,
+        }
+    }
+
+    /// Turn a `df64` into [`prim@f32`]
+    pub const fn to_f32(self) -> f32 {
+        self.hi
+    }
+
+    /// Turn a `df64` into [`prim@f64`]
+    pub const fn to_f64(self) -> f64 {
+        self.hi as f64 + self.lo as f64
+    }
+
+    /// Compare two df64 numbers
+    pub fn total_cmp(&self, other: &df64) -> Ordering {
+        self.hi
+            .total_cmp(&other.hi)
+            .then_with(|| self.lo.total_cmp(&other.lo))
+    }
+
+    /// Whether this df64 represents `NaN`
+    pub fn is_nan(&self) -> bool {
+        self.hi.is_nan() || self.lo.is_nan()
+    }
+
+    fn map(self, func: impl FnOnce(f64) -> f64) -> Self {
+        Self::from_f64(func(self.to_f64()))
+    }
+
+    fn zip(self, other: Self, func: impl FnOnce(f64, f64) -> f64) -> Self {
+        Self::from_f64(func(self.to_f64(), other.to_f64()))
+    }
+}
+
+impl Debug for df64 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&df64::to_f64(*self), f)
+    }
+}
+
+impl Display for df64 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&df64::to_f64(*self), f)
+    }
+}
+
+impl PartialOrd for df64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        df64::to_f64(*self).partial_cmp(&df64::to_f64(*other))
+    }
+}
+
+impl Neg for df64 {
+    type Output = df64;
+
+    fn neg(self) -> Self::Output {
+        df64 {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+macro_rules! impl_binary_op {
+    ($trait:ident, $func:ident, $assign_trait:ident, $assign_func:ident, $op:tt) => {
+        impl $trait for df64 {
+            type Output = df64;
+
+            fn $func(self, rhs: Self) -> Self::Output {
+                self.zip(rhs, |a, b| a $op b)
+            }
+        }
+
+        impl $assign_trait for df64 {
+            fn $assign_func(&mut self, rhs: Self) {
+                *self = *self $op rhs;
+            }
+        }
+    };
+}
+
+impl_binary_op!(Add, add, AddAssign, add_assign, +);
+impl_binary_op!(Sub, sub, SubAssign, sub_assign, -);
+impl_binary_op!(Mul, mul, MulAssign, mul_assign, *);
+impl_binary_op!(Div, div, DivAssign, div_assign, /);
+impl_binary_op!(Rem, rem, RemAssign, rem_assign, %);
+
+impl From<f32> for df64 {
+    fn from(value: f32) -> Self {
+        Self::from_f32(value)
+    }
+}
+
+impl From<f64> for df64 {
+    fn from(value: f64) -> Self {
+        Self::from_f64(value)
+    }
+}
+
+impl From<df64> for f64 {
+    fn from(val: df64) -> Self {
+        val.to_f64()
+    }
+}
+
+impl ToPrimitive for df64 {
+    fn to_i64(&self) -> Option<i64> {
+        Some((*self).to_f64() as i64)
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        Some((*self).to_f64() as u64)
+    }
+
+    fn to_f32(&self) -> Option<f32> {
+        Some((*self).to_f32())
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some((*self).to_f64())
+    }
+}
+
+impl NumCast for df64 {
+    fn from<T: num_traits::ToPrimitive>(n: T) -> Option<Self> {
+        Some(df64::from_f64(n.to_f64()?))
+    }
+}
+
+impl num_traits::Float for df64 {
+    fn nan() -> Self {
+        Self::NAN
+    }
+
+    fn infinity() -> Self {
+        Self::INFINITY
+    }
+
+    fn neg_infinity() -> Self {
+        Self::NEG_INFINITY
+    }
+
+    fn neg_zero() -> Self {
+        Self::from_f32(-0.0)
+    }
+
+    fn min_value() -> Self {
+        Self::MIN
+    }
+
+    fn min_positive_value() -> Self {
+        Self::MIN_POSITIVE
+    }
+
+    fn max_value() -> Self {
+        Self::MAX
+    }
+
+    fn is_nan(self) -> bool {
+        df64::is_nan(&self)
+    }
+
+    fn is_infinite(self) -> bool {
+        self.hi.is_infinite()
+    }
+
+    fn is_finite(self) -> bool {
+        self.hi.is_finite()
+    }
+
+    fn is_normal(self) -> bool {
+        self.hi.is_normal()
+    }
+
+    fn classify(self) -> core::num::FpCategory {
+        self.hi.classify()
+    }
+
+    fn floor(self) -> Self {
+        self.map(f64::floor)
+    }
+
+    fn ceil(self) -> Self {
+        self.map(f64::ceil)
+    }
+
+    fn round(self) -> Self {
+        self.map(f64::round)
+    }
+
+    fn trunc(self) -> Self {
+        self.map(f64::trunc)
+    }
+
+    fn fract(self) -> Self {
+        self.map(f64::fract)
+    }
+
+    fn abs(self) -> Self {
+        match self.hi.is_sign_negative() {
+            true => -self,
+            false => self,
+        }
+    }
+
+    fn signum(self) -> Self {
+        Self::from_f32(self.hi.signum())
+    }
+
+    fn is_sign_positive(self) -> bool {
+        self.hi.is_sign_positive()
+    }
+
+    fn is_sign_negative(self) -> bool {
+        self.hi.is_sign_negative()
+    }
+
+    fn mul_add(self, a: Self, b: Self) -> Self {
+        Self::from_f64(self.to_f64().mul_add(a.to_f64(), b.to_f64()))
+    }
+
+    fn recip(self) -> Self {
+        self.map(f64::recip)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        self.map(|x| x.powi(n))
+    }
+
+    fn powf(self, n: Self) -> Self {
+        self.zip(n, f64::powf)
+    }
+
+    fn sqrt(self) -> Self {
+        self.map(f64::sqrt)
+    }
+
+    fn exp(self) -> Self {
+        self.map(f64::exp)
+    }
+
+    fn exp2(self) -> Self {
+        self.map(f64::exp2)
+    }
+
+    fn ln(self) -> Self {
+        self.map(f64::ln)
+    }
+
+    fn log(self, base: Self) -> Self {
+        self.zip(base, f64::log)
+    }
+
+    fn log2(self) -> Self {
+        self.map(f64::log2)
+    }
+
+    fn log10(self) -> Self {
+        self.map(f64::log10)
+    }
+
+    fn max(self, other: Self) -> Self {
+        self.zip(other, f64::max)
+    }
+
+    fn min(self, other: Self) -> Self {
+        self.zip(other, f64::min)
+    }
+
+    fn abs_sub(self, other: Self) -> Self {
+        self.zip(other, |a, b| (a - b).abs())
+    }
+
+    fn cbrt(self) -> Self {
+        self.map(f64::cbrt)
+    }
+
+    fn hypot(self, other: Self) -> Self {
+        self.zip(other, f64::hypot)
+    }
+
+    fn sin(self) -> Self {
+        self.map(f64::sin)
+    }
+
+    fn cos(self) -> Self {
+        self.map(f64::cos)
+    }
+
+    fn tan(self) -> Self {
+        self.map(f64::tan)
+    }
+
+    fn asin(self) -> Self {
+        self.map(f64::asin)
+    }
+
+    fn acos(self) -> Self {
+        self.map(f64::acos)
+    }
+
+    fn atan(self) -> Self {
+        self.map(f64::atan)
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        self.zip(other, f64::atan2)
+    }
+
+    fn sin_cos(self) -> (Self, Self) {
+        let (a, b) = self.to_f64().sin_cos();
+        (Self::from_f64(a), Self::from_f64(b))
+    }
+
+    fn exp_m1(self) -> Self {
+        self.map(f64::exp_m1)
+    }
+
+    fn ln_1p(self) -> Self {
+        self.map(f64::ln_1p)
+    }
+
+    fn sinh(self) -> Self {
+        self.map(f64::sinh)
+    }
+
+    fn cosh(self) -> Self {
+        self.map(f64::cosh)
+    }
+
+    fn tanh(self) -> Self {
+        self.map(f64::tanh)
+    }
+
+    fn asinh(self) -> Self {
+        self.map(f64::asinh)
+    }
+
+    fn acosh(self) -> Self {
+        self.map(f64::acosh)
+    }
+
+    fn atanh(self) -> Self {
+        self.map(f64::atanh)
+    }
+
+    fn integer_decode(self) -> (u64, i16, i8) {
+        num_traits::Float::integer_decode(self.to_f64())
+    }
+}
+
+impl Num for df64 {
+    type FromStrRadixErr = <f64 as Num>::FromStrRadixErr;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        Ok(df64::from_f64(f64::from_str_radix(str, radix)?))
+    }
+}
+
+impl One for df64 {
+    fn one() -> Self {
+        Self::from_f32(1.0)
+    }
+}
+
+impl Zero for df64 {
+    fn zero() -> Self {
+        Self::from_f32(0.0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.hi == 0.0
+    }
+}
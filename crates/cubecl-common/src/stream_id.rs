@@ -12,6 +12,15 @@ pub struct StreamId {
 #[cfg(multi_threading)]
 static STREAM_COUNT: AtomicU64 = AtomicU64::new(0);
 
+#[cfg(all(not(multi_threading), target_has_atomic = "ptr"))]
+use core::sync::atomic::AtomicUsize;
+#[cfg(all(not(multi_threading), not(target_has_atomic = "ptr")))]
+use portable_atomic::AtomicUsize;
+
+/// Without threads, every thread shares the stream `0`, so created streams start at `1`.
+#[cfg(not(multi_threading))]
+static CREATED_STREAM_COUNT: AtomicUsize = AtomicUsize::new(1);
+
 #[cfg(multi_threading)]
 std::thread_local! {
         static ID: std::cell::RefCell::<Option<u64>> = const { std::cell::RefCell::new(None) };
@@ -28,6 +37,19 @@ impl StreamId {
         }
     }
 
+    /// Create a new stream id that isn't bound to any thread.
+    ///
+    /// Tasks submitted on the new stream can overlap with the tasks submitted on the stream of the
+    /// current thread.
+    pub fn create() -> Self {
+        Self {
+            #[cfg(multi_threading)]
+            value: STREAM_COUNT.fetch_add(1, core::sync::atomic::Ordering::Acquire),
+            #[cfg(not(multi_threading))]
+            value: CREATED_STREAM_COUNT.fetch_add(1, core::sync::atomic::Ordering::Acquire) as u64,
+        }
+    }
+
     /// Swap the current stream id for the given one.
     ///
     /// # Safety
@@ -1,6 +1,9 @@
-use cubecl_ir::{Id, Scope, StorageType, Type};
+use cubecl_ir::{Id, Scope, StorageType, TextureDim, Type};
 use cubecl_runtime::{
-    kernel::{Binding, KernelDefinition, KernelOptions, Location, ScalarBinding, Visibility},
+    kernel::{
+        Binding, KernelDefinition, KernelOptions, Location, ScalarBinding, TextureBinding,
+        Visibility,
+    },
     server::CubeDim,
 };
 
@@ -14,6 +17,7 @@ pub struct KernelIntegrator {
     buffer_bindings: Vec<Binding>,
     scalar_bindings: Vec<ScalarBinding>,
     tensor_maps: Vec<Binding>,
+    textures: Vec<TextureBinding>,
 }
 
 /// The information necessary to compile a [kernel definition](KernelDefinition).
@@ -22,6 +26,8 @@ pub struct KernelExpansion {
     pub buffers: Vec<BufferInfo>,
     pub scalars: Vec<ScalarInfo>,
     pub tensor_maps: Vec<BufferInfo>,
+    pub textures: Vec<TextureInfo>,
+    pub dynamic_shared_memory: Option<usize>,
     pub scope: Scope,
 }
 
@@ -84,6 +90,14 @@ pub struct BufferInfo {
     pub has_extended_meta: bool,
 }
 
+/// Information related to a texture binding.
+#[derive(Clone, Debug)]
+pub struct TextureInfo {
+    pub id: Id,
+    pub item: Type,
+    pub dim: TextureDim,
+}
+
 /// Information related to a scalar input.
 #[derive(Clone, Debug)]
 pub struct ScalarInfo {
@@ -99,6 +113,7 @@ impl KernelIntegrator {
             buffer_bindings: Default::default(),
             scalar_bindings: Default::default(),
             tensor_maps: Default::default(),
+            textures: Default::default(),
         }
     }
 
@@ -108,16 +123,19 @@ impl KernelIntegrator {
         self.register_buffers();
         self.register_scalars();
         self.register_tensor_maps();
+        self.register_textures();
 
         self.scalar_bindings.sort_by_key(|binding| binding.ty);
 
         KernelDefinition {
             buffers: self.buffer_bindings,
             tensor_maps: self.tensor_maps,
+            textures: self.textures,
             scalars: self.scalar_bindings,
             cube_dim: settings.cube_dim,
             body: self.expansion.scope,
             options: settings.options,
+            dynamic_shared_memory: self.expansion.dynamic_shared_memory,
         }
     }
 
@@ -155,4 +173,14 @@ impl KernelIntegrator {
             });
         }
     }
+
+    fn register_textures(&mut self) {
+        for texture in self.expansion.textures.drain(..) {
+            self.textures.push(TextureBinding {
+                id: texture.id,
+                ty: texture.item,
+                dim: texture.dim,
+            });
+        }
+    }
 }
@@ -4,13 +4,14 @@ use std::{
 };
 
 use crate::{
-    BufferInfo, KernelExpansion, KernelIntegrator, KernelSettings, ScalarInfo,
+    BufferInfo, KernelExpansion, KernelIntegrator, KernelSettings, ScalarInfo, TextureInfo,
     ir::{Id, Type},
     prelude::KernelDefinition,
 };
 use alloc::collections::BTreeMap;
 use cubecl_ir::{
-    DeviceProperties, ExpandElement, Scope, StorageType, TargetProperties, Variable, VariableKind,
+    DeviceProperties, ExpandElement, Scope, StorageType, TargetProperties, TextureDim, Variable,
+    VariableKind,
 };
 use cubecl_runtime::{
     config::{GlobalConfig, compilation::CompilationLogLevel},
@@ -24,6 +25,9 @@ pub struct KernelBuilder {
     buffers: Vec<BufferInfo>,
     scalars: BTreeMap<StorageType, usize>,
     tensor_maps: Vec<BufferInfo>,
+    textures: Vec<TextureInfo>,
+    spec_constants: Id,
+    dynamic_shared_memory: Option<usize>,
 }
 
 static DEBUG: AtomicI8 = AtomicI8::new(-1);
@@ -77,6 +81,27 @@ impl KernelBuilder {
         ExpandElement::Plain(Variable::new(VariableKind::TensorMapOutput(id), item))
     }
 
+    /// Register a texture and return the [element](ExpandElement) to be used for kernel expansion.
+    ///
+    /// Textures are numbered separately from buffers, so they don't shift the metadata of the
+    /// other bindings.
+    pub fn texture(&mut self, item: Type, dim: TextureDim) -> ExpandElement {
+        let id = self.textures.len() as Id;
+        self.textures.push(TextureInfo { id, item, dim });
+        ExpandElement::Plain(Variable::new(VariableKind::Texture(id), item))
+    }
+
+    /// Register a specialization constant and return the [element](ExpandElement) to be used for
+    /// kernel expansion. Ids follow the registration order, like the values in the launcher.
+    pub fn spec_constant(&mut self, storage: StorageType) -> ExpandElement {
+        let id = self.spec_constants;
+        self.spec_constants += 1;
+        ExpandElement::Plain(Variable::new(
+            VariableKind::SpecConstant(id),
+            Type::new(storage),
+        ))
+    }
+
     /// Register an input array and return the [element](ExpandElement) to be used for kernel expansion.
     pub fn input_tensor(&mut self, item: Type) -> ExpandElement {
         let id = self.buffer_id();
@@ -124,6 +149,11 @@ impl KernelBuilder {
         self.scope.input(id, item)
     }
 
+    /// Specialize the dynamic shared memory of the kernel to `size` bytes.
+    pub fn dynamic_shared_memory(&mut self, size: usize) {
+        self.dynamic_shared_memory = Some(size);
+    }
+
     pub fn runtime_properties(&mut self, properties: TargetProperties) {
         self.scope.runtime_properties = Rc::new(properties);
     }
@@ -144,6 +174,8 @@ impl KernelBuilder {
             buffers: self.buffers,
             scalars,
             tensor_maps: self.tensor_maps,
+            textures: self.textures,
+            dynamic_shared_memory: self.dynamic_shared_memory,
         })
         .integrate(settings)
     }
@@ -167,6 +199,9 @@ impl KernelBuilder {
             buffers: Default::default(),
             scalars: Default::default(),
             tensor_maps: Default::default(),
+            textures: Default::default(),
+            spec_constants: 0,
+            dynamic_shared_memory: None,
         }
     }
 }
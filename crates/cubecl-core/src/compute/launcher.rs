@@ -1,10 +1,12 @@
 use std::{collections::BTreeMap, marker::PhantomData};
 
-use crate::prelude::{ArrayArg, TensorArg, TensorMapArg, TensorMapKind};
+use crate::prelude::{ArrayArg, TensorArg, TensorMapArg, TensorMapKind, TextureArg};
 use crate::{CubeScalar, KernelSettings};
 use crate::{MetadataBuilder, Runtime};
-use cubecl_ir::StorageType;
-use cubecl_runtime::server::{Binding, CubeCount, LaunchError, ScalarBinding, TensorMapBinding};
+use cubecl_ir::{ConstantValue, StorageType};
+use cubecl_runtime::server::{
+    Binding, CubeCount, LaunchError, ScalarBinding, TensorMapBinding, TextureBinding,
+};
 use cubecl_runtime::{
     client::ComputeClient,
     kernel::{CubeKernel, KernelTask},
@@ -15,6 +17,8 @@ use cubecl_runtime::{
 pub struct KernelLauncher<R: Runtime> {
     tensors: TensorState<R>,
     scalars: ScalarState,
+    dynamic_shared_memory: usize,
+    spec_constants: Vec<ConstantValue>,
     pub settings: KernelSettings,
     runtime: PhantomData<R>,
 }
@@ -30,6 +34,16 @@ impl<R: Runtime> KernelLauncher<R> {
         self.tensors.push_tensor_map(tensor);
     }
 
+    /// Register a texture to be launched. Textures the device can't sample natively are bound as
+    /// a regular tensor of texels.
+    pub fn register_texture(&mut self, texture: &TextureArg<'_, R>) {
+        if texture.native {
+            self.tensors.push_texture(texture);
+        } else {
+            self.tensors.push_tensor(&texture.as_tensor_arg());
+        }
+    }
+
     /// Register an input array to be launched.
     pub fn register_array(&mut self, array: &ArrayArg<'_, R>) {
         self.tensors.push_array(array);
@@ -45,6 +59,16 @@ impl<R: Runtime> KernelLauncher<R> {
         self.scalars.push_raw(bytes, dtype);
     }
 
+    /// Register the size in bytes of the dynamic shared memory allocated for each cube.
+    pub fn register_dynamic_shared_memory(&mut self, size: usize) {
+        self.dynamic_shared_memory = size;
+    }
+
+    /// Register the value of the next specialization constant, set when the pipeline is created.
+    pub fn register_spec_constant(&mut self, value: ConstantValue) {
+        self.spec_constants.push(value);
+    }
+
     /// Launch the kernel.
     #[track_caller]
     pub fn launch<K: CubeKernel>(
@@ -98,6 +122,8 @@ impl<R: Runtime> KernelLauncher<R> {
         self.scalars.register(&mut bindings);
 
         bindings
+            .with_dynamic_shared_memory(self.dynamic_shared_memory)
+            .with_spec_constants(self.spec_constants)
     }
 }
 
@@ -109,6 +135,7 @@ pub enum TensorState<R: Runtime> {
     Some {
         buffers: Vec<Binding>,
         tensor_maps: Vec<TensorMapBinding>,
+        textures: Vec<TextureBinding>,
         metadata: MetadataBuilder,
         runtime: PhantomData<R>,
     },
@@ -131,6 +158,7 @@ impl<R: Runtime> TensorState<R> {
             *self = TensorState::Some {
                 buffers: Vec::new(),
                 tensor_maps: Vec::new(),
+                textures: Vec::new(),
                 metadata: MetadataBuilder::new(*addr_type),
                 runtime: PhantomData,
             };
@@ -153,6 +181,14 @@ impl<R: Runtime> TensorState<R> {
         tensor_maps
     }
 
+    fn textures(&mut self) -> &mut Vec<TextureBinding> {
+        self.maybe_init();
+        let TensorState::Some { textures, .. } = self else {
+            panic!("Should be init");
+        };
+        textures
+    }
+
     fn metadata(&mut self) -> &mut MetadataBuilder {
         self.maybe_init();
         let TensorState::Some { metadata, .. } = self else {
@@ -225,10 +261,18 @@ impl<R: Runtime> TensorState<R> {
         self.tensor_maps().push(TensorMapBinding { binding, map });
     }
 
+    /// Push a new natively backed texture to the state.
+    pub fn push_texture(&mut self, texture: &TextureArg<'_, R>) {
+        let binding = texture.handle.clone().binding();
+        let meta = texture.meta.clone();
+        self.textures().push(TextureBinding { binding, meta });
+    }
+
     fn register(self, bindings_global: &mut Bindings) {
         if let Self::Some {
             buffers,
             tensor_maps,
+            textures,
             metadata,
             ..
         } = self
@@ -237,6 +281,7 @@ impl<R: Runtime> TensorState<R> {
 
             bindings_global.buffers = buffers;
             bindings_global.tensor_maps = tensor_maps;
+            bindings_global.textures = textures;
             bindings_global.metadata = metadata;
         }
     }
@@ -283,6 +328,8 @@ impl<R: Runtime> KernelLauncher<R> {
                 addr_type: settings.address_type.unsigned_type(),
             },
             scalars: Default::default(),
+            dynamic_shared_memory: 0,
+            spec_constants: Vec::new(),
             settings,
             runtime: PhantomData,
         }
@@ -1,3 +1,5 @@
+use core::cell::RefCell;
+
 use cubecl_ir::ExpandElement;
 use num_traits::NumCast;
 
@@ -306,6 +308,22 @@ pub fn for_expand<I: Numeric>(
     }
 }
 
+/// Expand a for loop containing a `continue`, which is never unrolled since the `continue` needs
+/// a runtime loop to jump to. Asking to unroll it is reported as a validation error of the kernel.
+pub fn for_continue_expand<I: Numeric>(
+    scope: &mut Scope,
+    range: impl Iterable<I>,
+    unroll: bool,
+    body: impl FnMut(&mut Scope, ExpandElementTyped<I>),
+) {
+    if unroll {
+        // The body expects the index of an unrolled loop to be comptime, so it isn't expanded.
+        scope.push_error("Can't unroll a loop containing `continue`");
+        return;
+    }
+    range.expand(scope, body);
+}
+
 pub fn if_expand(scope: &mut Scope, runtime_cond: ExpandElement, block: impl FnOnce(&mut Scope)) {
     let comptime_cond = runtime_cond.as_const().map(|it| it.as_bool());
     match comptime_cond {
@@ -562,6 +580,74 @@ pub fn return_expand(scope: &mut Scope) {
     scope.register(Branch::Return);
 }
 
+pub fn continue_expand(scope: &mut Scope) {
+    scope.register(Branch::Continue);
+}
+
+/// State of a function containing an early `return`.
+///
+/// The body of the function is expanded inside a loop that runs once, so a `return` can break out
+/// of it. Since a `break` only exits the innermost loop, every loop of the body that contains a
+/// `return` is followed by [ReturnExpand::propagate].
+pub struct ReturnExpand {
+    returned: ExpandElementTyped<bool>,
+    value: RefCell<Option<ExpandElement>>,
+}
+
+impl ReturnExpand {
+    pub fn new(scope: &mut Scope) -> Self {
+        let returned: ExpandElementTyped<bool> = scope
+            .create_local_mut(Type::new(bool::as_type(scope)))
+            .into();
+        let init = ExpandElementTyped::from_lit(scope, false);
+        assign::expand_no_check(scope, init, returned.clone());
+
+        Self {
+            returned,
+            value: RefCell::new(None),
+        }
+    }
+
+    /// Return from the function without a value.
+    pub fn ret(&self, scope: &mut Scope) {
+        let returned = ExpandElementTyped::from_lit(scope, true);
+        assign::expand_no_check(scope, returned, self.returned.clone());
+        break_expand(scope);
+    }
+
+    /// Return `value` from the function. The output variable is created by the first return,
+    /// since the line size of the value is only known at this point.
+    pub fn ret_value<C: CubePrimitive>(&self, scope: &mut Scope, value: ExpandElementTyped<C>) {
+        let out: ExpandElementTyped<C> = self
+            .value
+            .borrow_mut()
+            .get_or_insert_with(|| scope.create_local_mut(value.expand.ty))
+            .clone()
+            .into();
+        assign::expand_no_check::<C>(scope, value, out);
+        self.ret(scope);
+    }
+
+    /// Keep breaking out of the enclosing loops after a `return`.
+    pub fn propagate(&self, scope: &mut Scope) {
+        if_expand(scope, self.returned.expand.clone(), break_expand);
+    }
+
+    /// Register the loop containing the function `body`, and get the returned value.
+    pub fn finish<C: CubePrimitive>(self, scope: &mut Scope, body: Scope) -> ExpandElementTyped<C> {
+        scope.register(Branch::Loop(Box::new(Loop { scope: body })));
+        self.value
+            .into_inner()
+            .expect("A function returning a value should return on every path")
+            .into()
+    }
+
+    /// Register the loop containing the function `body`, for functions without return value.
+    pub fn finish_unit(self, scope: &mut Scope, body: Scope) {
+        scope.register(Branch::Loop(Box::new(Loop { scope: body })));
+    }
+}
+
 // Don't make this `FnOnce`, it must be executable multiple times
 pub fn loop_expand(scope: &mut Scope, mut block: impl FnMut(&mut Scope)) {
     let mut inside_loop = scope.child();
@@ -0,0 +1,164 @@
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use core::marker::PhantomData;
+
+use crate::ir::{ExpandElement, Scope, Type};
+use crate::{prelude::*, unexpanded};
+use serde::{Deserialize, Serialize};
+
+/// Shared memory sized at launch time rather than at compile time.
+///
+/// The size in bytes is passed with the launch through a [`DynamicSharedMemoryArg`], so changing
+/// it doesn't require a new compilation on runtimes that support it natively (see
+/// `Features::dynamic_shared_memory`). Other runtimes specialize the kernel on the size instead.
+///
+/// The memory is used through typed [views](DynamicSharedMemory::view), each carving a slice out
+/// of the region at a byte offset. All views reinterpret the same memory, so they alias each other
+/// whatever their type.
+#[derive(Clone, Copy)]
+pub struct DynamicSharedMemory {
+    _private: PhantomData<()>,
+}
+
+/// Runtime argument for [`DynamicSharedMemory`], holding the size in bytes of the memory
+/// allocated for each cube.
+pub struct DynamicSharedMemoryArg<R: Runtime> {
+    /// Size in bytes of the shared memory.
+    pub size: usize,
+    /// Whether the size is set at launch rather than specialized into the kernel.
+    pub native: bool,
+    _runtime: PhantomData<R>,
+}
+
+impl<R: Runtime> DynamicSharedMemoryArg<R> {
+    /// Allocate `size` bytes of shared memory for each cube.
+    pub fn new(client: &ComputeClient<R>, size: usize) -> Self {
+        Self {
+            size,
+            native: client.properties().features.dynamic_shared_memory,
+            _runtime: PhantomData,
+        }
+    }
+}
+
+impl<R: Runtime> ArgSettings<R> for DynamicSharedMemoryArg<R> {
+    fn register(&self, launcher: &mut KernelLauncher<R>) {
+        if self.native {
+            launcher.register_dynamic_shared_memory(self.size);
+        }
+    }
+}
+
+/// Compilation argument for [`DynamicSharedMemory`]. Holds the size in bytes when it's
+/// specialized into the kernel.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct DynamicSharedMemoryCompilationArg {
+    pub specialized: Option<usize>,
+}
+
+impl CompilationArg for DynamicSharedMemoryCompilationArg {}
+
+/// Expand type of [`DynamicSharedMemory`].
+///
+/// Views of the same type share a single variable, so they index the same memory.
+#[derive(Clone)]
+pub struct DynamicSharedMemoryExpand {
+    specialized: Option<usize>,
+    views: Rc<RefCell<Vec<(Type, ExpandElement)>>>,
+}
+
+impl CubeType for DynamicSharedMemory {
+    type ExpandType = DynamicSharedMemoryExpand;
+}
+
+impl IntoMut for DynamicSharedMemoryExpand {
+    fn into_mut(self, _scope: &mut Scope) -> Self {
+        self
+    }
+}
+
+impl CubeDebug for DynamicSharedMemoryExpand {}
+
+impl LaunchArg for DynamicSharedMemory {
+    type RuntimeArg<'a, R: Runtime> = DynamicSharedMemoryArg<R>;
+    type CompilationArg = DynamicSharedMemoryCompilationArg;
+
+    fn compilation_arg<R: Runtime>(runtime_arg: &Self::RuntimeArg<'_, R>) -> Self::CompilationArg {
+        DynamicSharedMemoryCompilationArg {
+            specialized: (!runtime_arg.native).then_some(runtime_arg.size),
+        }
+    }
+
+    fn expand(
+        arg: &Self::CompilationArg,
+        builder: &mut KernelBuilder,
+    ) -> DynamicSharedMemoryExpand {
+        if let Some(size) = arg.specialized {
+            builder.dynamic_shared_memory(size);
+        }
+        DynamicSharedMemoryExpand {
+            specialized: arg.specialized,
+            views: Default::default(),
+        }
+    }
+}
+
+impl DynamicSharedMemory {
+    /// Get a view of `len` elements of type `T`, starting `offset` bytes into the shared memory.
+    ///
+    /// The offset must be a multiple of the size of `T`. When the size is specialized into the
+    /// kernel, the size of `T` must also be a multiple of 4 bytes.
+    ///
+    /// WGSL and SPIR-V can't reinterpret shared memory, so views are lowered to an array of `u32`
+    /// words there. Views of atomics or of elements under 4 bytes, like `f16` or `u8`, are
+    /// rejected with a validation error. Views can only be indexed or copied from and to, other
+    /// uses like cooperative matrix loads or passing a view to a function are also rejected.
+    #[allow(unused_variables)]
+    pub fn view<T: CubePrimitive>(&self, offset: usize, len: usize) -> Slice<T, ReadWrite> {
+        unexpanded!()
+    }
+}
+
+impl DynamicSharedMemoryExpand {
+    pub fn __expand_view_method<T: CubePrimitive>(
+        &self,
+        scope: &mut Scope,
+        offset: ExpandElementTyped<usize>,
+        len: ExpandElementTyped<usize>,
+    ) -> SliceExpand<T, ReadWrite> {
+        let ty = Type::new(T::as_type(scope));
+        let array = self.array(scope, ty);
+        let start = crate::frontend::div::expand(scope, offset, ty.size().into());
+        let end = crate::frontend::add::expand(scope, start.clone(), len);
+
+        Slice::__expand_new(
+            scope,
+            SliceOriginExpand::SharedMemory(array.into()),
+            start,
+            end,
+        )
+    }
+
+    /// The view of type `ty` of the memory, declared on first use.
+    ///
+    /// When the size is specialized, the compiler either places the views at the same offset of
+    /// the shared memory, or lowers them to a single array of words with the
+    /// [`DynamicSharedMemoryProcessor`](crate::post_processing::dynamic_shared_memory::DynamicSharedMemoryProcessor).
+    fn array(&self, scope: &mut Scope, ty: Type) -> ExpandElement {
+        let mut views = self.views.borrow_mut();
+        if let Some((_, array)) = views.iter().find(|(view_ty, _)| *view_ty == ty) {
+            return array.clone();
+        }
+
+        if self.specialized.is_some() && (ty.is_atomic() || !ty.size().is_multiple_of(4)) {
+            scope.push_error(format!(
+                "Views of a specialized dynamic shared memory must be non-atomic and a multiple \
+                 of 4 bytes, got {ty}"
+            ));
+        }
+
+        let array = scope.create_dynamic_shared_array(ty);
+        views.push((ty, array.clone()));
+        array
+    }
+}
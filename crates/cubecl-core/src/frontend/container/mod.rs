@@ -1,6 +1,7 @@
 mod array;
 mod base;
 mod cell;
+mod dynamic_shared_memory;
 mod iter;
 mod line;
 mod registry;
@@ -8,11 +9,13 @@ mod sequence;
 mod shared_memory;
 mod slice;
 mod tensor;
+mod texture;
 
 pub(crate) use base::*;
 
 pub use array::*;
 pub use cell::*;
+pub use dynamic_shared_memory::*;
 pub use iter::*;
 pub use line::*;
 pub use registry::*;
@@ -20,3 +23,4 @@ pub use sequence::*;
 pub use shared_memory::*;
 pub use slice::*;
 pub use tensor::*;
+pub use texture::*;
@@ -0,0 +1,435 @@
+use core::marker::PhantomData;
+
+use crate as cubecl;
+use crate::ir::{ExpandElement, Instruction, Scope, TextureOps};
+use crate::{prelude::*, unexpanded};
+use cubecl_ir::{ElemType, FloatKind, LineSize, SemanticType, StorageType, Type, VariableKind};
+use cubecl_runtime::server::{Handle, TextureMeta};
+use serde::{Deserialize, Serialize};
+
+pub use cubecl_ir::{AddressMode, FilterMode, Sampler, TextureDim};
+
+/// A texture backed by a contiguous buffer of texels, with the channels of each texel
+/// interleaved.
+///
+/// Devices that support textures natively (see [`SemanticType::Texture`]) bind `f32` and `f16`
+/// texels as a texture resource. Other devices and texel types bind the texels as a regular
+/// tensor and filter them in the kernel.
+pub struct TextureArg<'a, R: Runtime> {
+    /// The handle holding the texels.
+    pub handle: &'a Handle,
+    /// The layout of the texels.
+    pub meta: TextureMeta,
+    /// Whether the texture is bound as a native texture.
+    pub native: bool,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+    _runtime: PhantomData<R>,
+}
+
+impl<'a, R: Runtime> TextureArg<'a, R> {
+    /// Create a 2D texture of `[width, height]` texels with `channels` channels of type `ty`.
+    pub fn new_2d(
+        client: &ComputeClient<R>,
+        handle: &'a Handle,
+        extent: [usize; 2],
+        channels: LineSize,
+        ty: StorageType,
+    ) -> Self {
+        let [width, height] = extent;
+        Self::new(
+            client,
+            handle,
+            TextureDim::D2,
+            [width, height, 1],
+            channels,
+            ty,
+        )
+    }
+
+    /// Create a 3D texture of `[width, height, depth]` texels with `channels` channels of type
+    /// `ty`.
+    pub fn new_3d(
+        client: &ComputeClient<R>,
+        handle: &'a Handle,
+        extent: [usize; 3],
+        channels: LineSize,
+        ty: StorageType,
+    ) -> Self {
+        Self::new(client, handle, TextureDim::D3, extent, channels, ty)
+    }
+
+    fn new(
+        client: &ComputeClient<R>,
+        handle: &'a Handle,
+        dim: TextureDim,
+        extent: [usize; 3],
+        channels: LineSize,
+        ty: StorageType,
+    ) -> Self {
+        assert!(
+            matches!(channels, 1 | 2 | 4),
+            "Textures must have 1, 2 or 4 channels, got {channels}"
+        );
+        let [width, height, depth] = extent;
+        let shape = match dim {
+            TextureDim::D2 => vec![height, width, channels],
+            TextureDim::D3 => vec![depth, height, width, channels],
+        };
+        let mut strides = vec![1; shape.len()];
+        for i in (0..shape.len() - 1).rev() {
+            strides[i] = strides[i + 1] * shape[i + 1];
+        }
+        assert!(
+            handle.size() as usize >= shape.iter().product::<usize>() * ty.size(),
+            "Texture handle is too small for its extent"
+        );
+
+        Self {
+            handle,
+            meta: TextureMeta {
+                dim,
+                extent,
+                channels,
+                storage_ty: ty,
+            },
+            native: client.properties().supports_type(SemanticType::Texture)
+                && matches!(
+                    ty.elem_type(),
+                    ElemType::Float(FloatKind::F32 | FloatKind::F16)
+                ),
+            shape,
+            strides,
+            _runtime: PhantomData,
+        }
+    }
+
+    /// The texels as a tensor of shape `[(depth,) height, width, channels]`, read with one line
+    /// per texel.
+    pub fn as_tensor_arg(&self) -> TensorArg<'_, R> {
+        unsafe {
+            TensorArg::from_raw_parts_and_size(
+                self.handle,
+                &self.strides,
+                &self.shape,
+                self.meta.channels,
+                self.meta.storage_ty.size(),
+            )
+        }
+    }
+}
+
+impl<R: Runtime> ArgSettings<R> for TextureArg<'_, R> {
+    fn register(&self, launcher: &mut KernelLauncher<R>) {
+        launcher.register_texture(self)
+    }
+}
+
+/// Compilation argument for a [texture](Texture2D).
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct TextureCompilationArg {
+    pub dim: TextureDim,
+    pub channels: LineSize,
+    pub native: bool,
+}
+
+impl CompilationArg for TextureCompilationArg {}
+
+fn compilation_arg<R: Runtime>(arg: &TextureArg<'_, R>) -> TextureCompilationArg {
+    TextureCompilationArg {
+        dim: arg.meta.dim,
+        channels: arg.meta.channels,
+        native: arg.native,
+    }
+}
+
+fn expand_texture<F: Float>(
+    arg: &TextureCompilationArg,
+    builder: &mut KernelBuilder,
+    dim: TextureDim,
+) -> ExpandElement {
+    assert_eq!(arg.dim, dim, "Texture dimensionality doesn't match");
+    let item = Type::new(F::as_type(&builder.scope)).line(arg.channels);
+    match arg.native {
+        true => builder.texture(item, dim),
+        false => builder.input_tensor(item),
+    }
+}
+
+fn register_op<F: Float>(
+    scope: &mut Scope,
+    texture: &ExpandElement,
+    op: TextureOps,
+) -> ExpandElementTyped<Line<F>> {
+    let out = scope.create_local(texture.ty);
+    scope.register(Instruction::new(op, *out));
+    out.into()
+}
+
+fn is_native(texture: &ExpandElement) -> bool {
+    matches!(texture.kind, VariableKind::Texture(_))
+}
+
+macro_rules! texture_type {
+    ($name:ident, $dim:ident, ($($coord:ident),*), $doc:literal) => {
+        #[doc = $doc]
+        ///
+        /// Texels are read as a [`Line`] with one element per channel.
+        #[derive(Clone, Copy)]
+        pub struct $name<F: Float> {
+            _ty: PhantomData<F>,
+        }
+
+        impl<F: Float> CubeType for $name<F> {
+            type ExpandType = ExpandElementTyped<$name<F>>;
+        }
+
+        impl<F: Float> ExpandElementIntoMut for $name<F> {
+            fn elem_into_mut(_scope: &mut Scope, elem: ExpandElement) -> ExpandElement {
+                elem
+            }
+        }
+
+        impl<F: Float> LaunchArg for $name<F> {
+            type RuntimeArg<'a, R: Runtime> = TextureArg<'a, R>;
+            type CompilationArg = TextureCompilationArg;
+
+            fn compilation_arg<R: Runtime>(
+                runtime_arg: &Self::RuntimeArg<'_, R>,
+            ) -> Self::CompilationArg {
+                compilation_arg(runtime_arg)
+            }
+
+            fn expand(
+                arg: &Self::CompilationArg,
+                builder: &mut KernelBuilder,
+            ) -> ExpandElementTyped<$name<F>> {
+                expand_texture::<F>(arg, builder, TextureDim::$dim).into()
+            }
+        }
+
+        impl<F: Float> $name<F> {
+            /// Sample the texture at normalized coordinates, where `0.0` and `1.0` are the outer
+            /// edges of the first and last texels.
+            #[allow(unused_variables)]
+            pub fn sample(&self, coords: ($(texture_type!(@ty f32, $coord)),*), sampler: Sampler) -> Line<F> {
+                unexpanded!()
+            }
+
+            /// Fetch the texel at integer coordinates, without filtering.
+            #[allow(unused_variables)]
+            pub fn load(&self, texel: ($(texture_type!(@ty u32, $coord)),*)) -> Line<F> {
+                unexpanded!()
+            }
+        }
+
+        impl<F: Float> ExpandElementTyped<$name<F>> {
+            pub fn __expand_sample_method(
+                &self,
+                scope: &mut Scope,
+                coords: ($(texture_type!(@ty ExpandElementTyped<f32>, $coord)),*),
+                sampler: Sampler,
+            ) -> ExpandElementTyped<Line<F>> {
+                let ($($coord),*) = coords;
+                if is_native(&self.expand) {
+                    let op = TextureOps::Sample {
+                        texture: *self.expand,
+                        sampler,
+                        coordinates: vec![$(*$coord.expand),*],
+                    };
+                    return register_op::<F>(scope, &self.expand, op);
+                }
+
+                let texels = self.expand.clone().into();
+                match sampler.filter {
+                    FilterMode::Nearest => {
+                        emulation::$name::sample_nearest::expand::<F, f32>(
+                            scope,
+                            texels,
+                            $($coord,)*
+                            sampler.address,
+                        )
+                    }
+                    FilterMode::Linear => emulation::$name::sample_linear::expand::<F, f32>(
+                        scope,
+                        texels,
+                        $($coord,)*
+                        sampler.address,
+                    ),
+                }
+            }
+
+            pub fn __expand_load_method(
+                &self,
+                scope: &mut Scope,
+                texel: ($(texture_type!(@ty ExpandElementTyped<u32>, $coord)),*),
+            ) -> ExpandElementTyped<Line<F>> {
+                let ($($coord),*) = texel;
+                if is_native(&self.expand) {
+                    let op = TextureOps::Load {
+                        texture: *self.expand,
+                        coordinates: vec![$(*$coord.expand),*],
+                    };
+                    return register_op::<F>(scope, &self.expand, op);
+                }
+
+                emulation::$name::load::expand::<F>(scope, self.expand.clone().into(), $($coord),*)
+            }
+        }
+    };
+    (@ty $ty:ty, $coord:ident) => { $ty };
+}
+
+texture_type!(
+    Texture2D,
+    D2,
+    (x, y),
+    "A 2D texture, sampled with `(u, v)` coordinates."
+);
+texture_type!(
+    Texture3D,
+    D3,
+    (x, y, z),
+    "A 3D texture, sampled with `(u, v, w)` coordinates."
+);
+
+/// Filtering of textures bound as a regular tensor of texels, for devices without native
+/// textures.
+#[allow(non_snake_case)]
+mod emulation {
+    use super::*;
+
+    /// Resolve a texel coordinate that may be out of bounds according to `mode`.
+    #[cube]
+    fn resolve(coord: i32, size: i32, #[comptime] mode: AddressMode) -> usize {
+        let mut resolved = select(coord < 0, 0, select(coord >= size, size - 1, coord));
+        if comptime![mode == AddressMode::Repeat] {
+            resolved = ((coord % size) + size) % size;
+        } else if comptime![mode == AddressMode::MirrorRepeat] {
+            let period = size * 2;
+            let wrapped = ((coord % period) + period) % period;
+            resolved = select(wrapped >= size, period - 1 - wrapped, wrapped);
+        }
+        usize::cast_from(resolved)
+    }
+
+    /// Index of the texel closest to the normalized coordinate `coord`.
+    #[cube]
+    fn nearest<C: Float>(coord: C, size: usize, #[comptime] mode: AddressMode) -> usize {
+        let texel = C::floor(coord * C::cast_from(size));
+        resolve(i32::cast_from(texel), i32::cast_from(size), mode)
+    }
+
+    /// The two texels around the normalized coordinate `coord`, and the weight of the second one.
+    #[cube]
+    fn linear<C: Float>(coord: C, size: usize, #[comptime] mode: AddressMode) -> (usize, usize, C) {
+        let pos = coord * C::cast_from(size) - C::new(0.5);
+        let first = C::floor(pos);
+        let texel = i32::cast_from(first);
+        let size = i32::cast_from(size);
+        (
+            resolve(texel, size, mode),
+            resolve(texel + 1, size, mode),
+            pos - first,
+        )
+    }
+
+    #[cube]
+    fn lerp<F: Float, C: Float>(a: Line<F>, b: Line<F>, weight: C) -> Line<F> {
+        a + (b - a) * Line::cast_from(weight)
+    }
+
+    pub mod Texture2D {
+        use super::*;
+
+        #[cube]
+        pub fn load<F: Float>(texels: &Tensor<Line<F>>, x: u32, y: u32) -> Line<F> {
+            texels[usize::cast_from(y) * texels.shape(1) + usize::cast_from(x)]
+        }
+
+        #[cube]
+        pub fn sample_nearest<F: Float, C: Float>(
+            texels: &Tensor<Line<F>>,
+            u: C,
+            v: C,
+            #[comptime] mode: AddressMode,
+        ) -> Line<F> {
+            let width = texels.shape(1);
+            let x = nearest::<C>(u, width, mode);
+            let y = nearest::<C>(v, texels.shape(0), mode);
+            texels[y * width + x]
+        }
+
+        #[cube]
+        pub fn sample_linear<F: Float, C: Float>(
+            texels: &Tensor<Line<F>>,
+            u: C,
+            v: C,
+            #[comptime] mode: AddressMode,
+        ) -> Line<F> {
+            let width = texels.shape(1);
+            let (x0, x1, wx) = linear::<C>(u, width, mode);
+            let (y0, y1, wy) = linear::<C>(v, texels.shape(0), mode);
+            let top = lerp::<F, C>(texels[y0 * width + x0], texels[y0 * width + x1], wx);
+            let bottom = lerp::<F, C>(texels[y1 * width + x0], texels[y1 * width + x1], wx);
+            lerp::<F, C>(top, bottom, wy)
+        }
+    }
+
+    pub mod Texture3D {
+        use super::*;
+
+        #[cube]
+        fn texel<F: Float>(texels: &Tensor<Line<F>>, x: usize, y: usize, z: usize) -> Line<F> {
+            texels[(z * texels.shape(1) + y) * texels.shape(2) + x]
+        }
+
+        #[cube]
+        pub fn load<F: Float>(texels: &Tensor<Line<F>>, x: u32, y: u32, z: u32) -> Line<F> {
+            texel(
+                texels,
+                usize::cast_from(x),
+                usize::cast_from(y),
+                usize::cast_from(z),
+            )
+        }
+
+        #[cube]
+        pub fn sample_nearest<F: Float, C: Float>(
+            texels: &Tensor<Line<F>>,
+            u: C,
+            v: C,
+            w: C,
+            #[comptime] mode: AddressMode,
+        ) -> Line<F> {
+            let x = nearest::<C>(u, texels.shape(2), mode);
+            let y = nearest::<C>(v, texels.shape(1), mode);
+            let z = nearest::<C>(w, texels.shape(0), mode);
+            texel(texels, x, y, z)
+        }
+
+        #[cube]
+        pub fn sample_linear<F: Float, C: Float>(
+            texels: &Tensor<Line<F>>,
+            u: C,
+            v: C,
+            w: C,
+            #[comptime] mode: AddressMode,
+        ) -> Line<F> {
+            let (x0, x1, wx) = linear::<C>(u, texels.shape(2), mode);
+            let (y0, y1, wy) = linear::<C>(v, texels.shape(1), mode);
+            let (z0, z1, wz) = linear::<C>(w, texels.shape(0), mode);
+
+            let front_top = lerp::<F, C>(texel(texels, x0, y0, z0), texel(texels, x1, y0, z0), wx);
+            let front_bottom =
+                lerp::<F, C>(texel(texels, x0, y1, z0), texel(texels, x1, y1, z0), wx);
+            let back_top = lerp::<F, C>(texel(texels, x0, y0, z1), texel(texels, x1, y0, z1), wx);
+            let back_bottom =
+                lerp::<F, C>(texel(texels, x0, y1, z1), texel(texels, x1, y1, z1), wx);
+
+            let front = lerp::<F, C>(front_top, front_bottom, wy);
+            let back = lerp::<F, C>(back_top, back_bottom, wy);
+            lerp::<F, C>(front, back, wz)
+        }
+    }
+}
@@ -3,7 +3,7 @@ use crate::{
     ir::{ConstantValue, Operation, Scope, Variable, VariableKind},
     prelude::{KernelBuilder, KernelLauncher, init_expand},
 };
-use cubecl_common::{e2m1, e2m1x2, e2m3, e3m2, e4m3, e5m2, flex32, tf32, ue8m0};
+use cubecl_common::{df64, e2m1, e2m1x2, e2m3, e3m2, e4m3, e5m2, flex32, tf32, ue8m0};
 use cubecl_ir::{ExpandElement, LineSize};
 use cubecl_runtime::runtime::Runtime;
 use half::{bf16, f16};
@@ -244,6 +244,7 @@ from_const!(f16);
 from_const!(bf16);
 from_const!(flex32);
 from_const!(tf32);
+from_const!(df64);
 from_const!(f32);
 from_const!(e2m1);
 from_const!(e2m1x2);
@@ -413,6 +414,7 @@ pub(crate) fn into_mut_expand_element<E: Into<ExpandElement>>(
 
     match elem.kind {
         VariableKind::GlobalScalar { .. } => init(elem),
+        VariableKind::SpecConstant(_) => init(elem),
         VariableKind::Constant { .. } => init(elem),
         VariableKind::LocalMut { .. } => init(elem),
         VariableKind::Versioned { .. } => init(elem),
@@ -420,6 +422,7 @@ pub(crate) fn into_mut_expand_element<E: Into<ExpandElement>>(
         VariableKind::Builtin(_) => init(elem),
         VariableKind::Shared { .. }
         | VariableKind::SharedArray { .. }
+        | VariableKind::DynamicSharedArray { .. }
         | VariableKind::GlobalInputArray { .. }
         | VariableKind::GlobalOutputArray { .. }
         | VariableKind::LocalArray { .. }
@@ -428,7 +431,8 @@ pub(crate) fn into_mut_expand_element<E: Into<ExpandElement>>(
         | VariableKind::BarrierToken { .. }
         | VariableKind::Pipeline { .. }
         | VariableKind::TensorMapOutput(_)
-        | VariableKind::TensorMapInput(_) => elem,
+        | VariableKind::TensorMapInput(_)
+        | VariableKind::Texture(_) => elem,
     }
 }
 
@@ -8,6 +8,7 @@ use crate::{
 
 use super::Numeric;
 
+mod double_float;
 mod fp4;
 mod fp6;
 mod fp8;
@@ -0,0 +1,81 @@
+use cubecl_common::df64;
+use cubecl_ir::{ConstantValue, ElemType, ExpandElement, FloatKind, Scope, StorageType};
+
+use crate::prelude::{Numeric, into_runtime_expand_element};
+
+use super::{
+    CubePrimitive, CubeType, ExpandElementIntoMut, ExpandElementTyped, Float, IntoRuntime,
+    into_mut_expand_element,
+};
+
+impl CubeType for df64 {
+    type ExpandType = ExpandElementTyped<df64>;
+}
+
+impl CubePrimitive for df64 {
+    /// Return the element type to use on GPU
+    fn as_type_native() -> Option<StorageType> {
+        Some(ElemType::Float(FloatKind::DF64).into())
+    }
+
+    fn from_const_value(value: ConstantValue) -> Self {
+        let ConstantValue::Float(value) = value else {
+            unreachable!()
+        };
+        df64::from_f64(value)
+    }
+}
+
+impl IntoRuntime for df64 {
+    fn __expand_runtime_method(self, scope: &mut Scope) -> ExpandElementTyped<Self> {
+        let elem: ExpandElementTyped<Self> = self.into();
+        into_runtime_expand_element(scope, elem).into()
+    }
+}
+
+impl Numeric for df64 {
+    fn min_value() -> Self {
+        df64::MIN
+    }
+    fn max_value() -> Self {
+        df64::MAX
+    }
+}
+
+impl ExpandElementIntoMut for df64 {
+    fn elem_into_mut(scope: &mut Scope, elem: ExpandElement) -> ExpandElement {
+        into_mut_expand_element(scope, elem)
+    }
+}
+
+impl Float for df64 {
+    const DIGITS: u32 = df64::DIGITS;
+
+    const EPSILON: Self = df64::EPSILON;
+
+    const INFINITY: Self = df64::INFINITY;
+
+    const MANTISSA_DIGITS: u32 = df64::MANTISSA_DIGITS;
+
+    /// Maximum possible [`df64`](crate::frontend::df64) power of 10 exponent, the same as `f32`
+    const MAX_10_EXP: i32 = f32::MAX_10_EXP;
+    /// Maximum possible [`df64`](crate::frontend::df64) power of 2 exponent, the same as `f32`
+    const MAX_EXP: i32 = f32::MAX_EXP;
+
+    /// Minimum possible normal [`df64`](crate::frontend::df64) power of 10 exponent
+    const MIN_10_EXP: i32 = f32::MIN_10_EXP;
+    /// One greater than the minimum possible normal [`df64`](crate::frontend::df64) power of 2 exponent
+    const MIN_EXP: i32 = f32::MIN_EXP;
+
+    const MIN_POSITIVE: Self = df64::MIN_POSITIVE;
+
+    const NAN: Self = df64::NAN;
+
+    const NEG_INFINITY: Self = df64::NEG_INFINITY;
+
+    const RADIX: u32 = 2;
+
+    fn new(val: f32) -> Self {
+        df64::from_f32(val)
+    }
+}
@@ -6,6 +6,7 @@ mod cube_elem;
 mod float;
 mod int;
 mod numeric;
+mod spec_const;
 mod uint;
 
 pub use atomic::*;
@@ -16,3 +17,4 @@ pub use cube_elem::*;
 pub use float::*;
 pub use int::*;
 pub use numeric::*;
+pub use spec_const::*;
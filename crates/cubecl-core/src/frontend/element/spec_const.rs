@@ -0,0 +1,89 @@
+use core::marker::PhantomData;
+
+use crate::ir::{ConstantValue, ExpandElement};
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A scalar kernel parameter whose value is set when the pipeline is created.
+///
+/// Unlike comptime values, changing a specialization constant doesn't compile the kernel again on
+/// runtimes that support them natively (see `Features::spec_constants`), which makes them a good
+/// fit for values that only feed into arithmetic, such as tile sizes swept by autotune. Other
+/// runtimes specialize the kernel on the value instead.
+///
+/// Kernel parameters are declared with the `#[spec_const]` attribute rather than with this type
+/// directly.
+pub struct SpecConst<T: CubePrimitive> {
+    _ty: PhantomData<T>,
+}
+
+/// Runtime argument for [`SpecConst`].
+pub struct SpecConstArg<T> {
+    /// Value of the constant.
+    pub value: T,
+    /// Whether the value is set when the pipeline is created rather than specialized into the
+    /// kernel.
+    pub native: bool,
+}
+
+impl<T> SpecConstArg<T> {
+    /// Set the constant to `value`.
+    ///
+    /// Specialization constants are limited to 32-bit types, wider values are specialized into
+    /// the kernel instead.
+    pub fn new<R: Runtime>(client: &ComputeClient<R>, value: T) -> Self {
+        Self {
+            value,
+            native: client.properties().features.spec_constants && size_of::<T>() <= 4,
+        }
+    }
+}
+
+impl<T: Into<ConstantValue> + Copy + Send + Sync, R: Runtime> ArgSettings<R> for SpecConstArg<T> {
+    fn register(&self, launcher: &mut KernelLauncher<R>) {
+        if self.native {
+            launcher.register_spec_constant(self.value.into());
+        }
+    }
+}
+
+/// Compilation argument for [`SpecConst`]. Holds the value when it's specialized into the kernel.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct SpecConstCompilationArg {
+    pub specialized: Option<ConstantValue>,
+}
+
+impl CompilationArg for SpecConstCompilationArg {}
+
+impl<T: CubePrimitive> CubeType for SpecConst<T> {
+    type ExpandType = ExpandElementTyped<T>;
+}
+
+impl<T: CubePrimitive + Into<ConstantValue> + Copy + Send + Sync + 'static> LaunchArg
+    for SpecConst<T>
+{
+    type RuntimeArg<'a, R: Runtime> = SpecConstArg<T>;
+    type CompilationArg = SpecConstCompilationArg;
+
+    fn compilation_arg<R: Runtime>(runtime_arg: &Self::RuntimeArg<'_, R>) -> Self::CompilationArg {
+        SpecConstCompilationArg {
+            specialized: (!runtime_arg.native).then(|| runtime_arg.value.into()),
+        }
+    }
+
+    fn expand(arg: &Self::CompilationArg, builder: &mut KernelBuilder) -> ExpandElementTyped<T> {
+        let storage = T::as_type(&builder.scope);
+
+        match arg.specialized {
+            Some(value) => ExpandElementTyped::new(ExpandElement::Plain(storage.constant(value))),
+            None => {
+                if storage.size() > 4 {
+                    builder.scope.push_error(format!(
+                        "Specialization constants are limited to 32-bit types, got {storage}"
+                    ));
+                }
+                builder.spec_constant(storage).into()
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use cubecl_ir::{
+    ExpandElement, Function, FunctionCall, Instruction, Operation, Scope, Variable, VariableKind,
+};
+
+use super::{CubePrimitive, ExpandElementTyped};
+
+/// Expands a `#[cube(noinline)]` function into a [`Function`] and a call to it.
+///
+/// The function is specialized for each set of generics, comptime arguments and argument types,
+/// and is only expanded once per kernel for each specialization.
+pub struct FunctionExpand {
+    name: &'static str,
+    hasher: DefaultHasher,
+    params: Vec<Variable>,
+    args: Vec<Variable>,
+}
+
+/// A value that can be passed to a non-inlined function.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be passed to a `noinline` function",
+    note = "Only primitives and lines can be passed to a `noinline` function"
+)]
+pub trait FunctionParam: Sized {
+    /// The variable passed by the caller.
+    fn arg(&self) -> Variable;
+    /// Create a new parameter of the same type as this argument.
+    fn param(&self, scope: &mut Scope) -> Self;
+}
+
+/// A value that can be returned from a non-inlined function.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be returned from a `noinline` function",
+    note = "Only primitives and lines can be returned from a `noinline` function"
+)]
+pub trait FunctionReturn: Sized {
+    /// Assign the returned value to a new variable at the end of the function body.
+    fn into_output(self, scope: &mut Scope) -> Option<Variable>;
+    /// Create the returned value from the output of the call.
+    fn from_output(out: Option<ExpandElement>) -> Self;
+}
+
+impl FunctionExpand {
+    /// Start the expansion of the function `name`, defined at `path` and `line`.
+    pub fn new(name: &'static str, path: &'static str, line: u32) -> Self {
+        let mut hasher = DefaultHasher::new();
+        path.hash(&mut hasher);
+        name.hash(&mut hasher);
+        line.hash(&mut hasher);
+        Self {
+            name,
+            hasher,
+            params: Vec::new(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Specialize the function for the generic type `T`.
+    pub fn generic<T: ?Sized>(&mut self) {
+        core::any::type_name::<T>().hash(&mut self.hasher);
+    }
+
+    /// Specialize the function for a comptime argument.
+    pub fn comptime_arg<T: Hash + ?Sized>(&mut self, value: &T) {
+        value.hash(&mut self.hasher);
+    }
+
+    /// Register a runtime argument, and return the parameter to use in its place inside the body.
+    pub fn param<P: FunctionParam>(&mut self, scope: &mut Scope, arg: P) -> P {
+        let param = arg.param(scope);
+        let arg = arg.arg();
+        arg.ty.hash(&mut self.hasher);
+        self.args.push(arg);
+        self.params.push(param.arg());
+        param
+    }
+
+    /// Expand the body into a function if it doesn't exist yet, and call it.
+    pub fn call<R: FunctionReturn>(
+        self,
+        scope: &mut Scope,
+        body: impl FnOnce(&mut Scope) -> R,
+    ) -> R {
+        let name = format!("{}_{:x}", self.name, self.hasher.finish());
+
+        let existing = scope
+            .functions
+            .borrow()
+            .iter()
+            .find(|function| function.name == name)
+            .map(|function| (function.captures.clone(), function.out));
+        let (captures, out) = match existing {
+            Some(existing) => existing,
+            None => {
+                let mut body_scope = scope.function_body();
+                let out = body(&mut body_scope).into_output(&mut body_scope);
+
+                let (captures, capture_params): (Vec<_>, Vec<_>) =
+                    body_scope.captures().into_iter().unzip();
+                let mut params = self.params;
+                params.extend(capture_params);
+                // Functions are only tracked by the kernel, avoid a reference cycle
+                body_scope.functions = Default::default();
+
+                scope.functions.borrow_mut().push(Function {
+                    name: name.clone(),
+                    params,
+                    captures: captures.clone(),
+                    out,
+                    body: body_scope,
+                });
+                (captures, out)
+            }
+        };
+
+        let mut args = self.args;
+        for captured in captures {
+            let VariableKind::Builtin(builtin) = captured.kind else {
+                unreachable!("Only builtins can be captured");
+            };
+            args.push(scope.builtin(builtin, captured.ty.storage_type()));
+        }
+
+        let call = Operation::Call(FunctionCall { name, args });
+        let out = match out {
+            Some(out) => {
+                let out = scope.create_local(out.ty);
+                scope.register(Instruction::new(call, *out));
+                Some(out)
+            }
+            None => {
+                scope.register(Instruction::no_out(call));
+                None
+            }
+        };
+
+        R::from_output(out)
+    }
+}
+
+impl<T: CubePrimitive> FunctionParam for ExpandElementTyped<T> {
+    fn arg(&self) -> Variable {
+        *self.expand
+    }
+
+    fn param(&self, scope: &mut Scope) -> Self {
+        scope.create_local(self.expand.ty).into()
+    }
+}
+
+impl FunctionReturn for () {
+    fn into_output(self, _scope: &mut Scope) -> Option<Variable> {
+        None
+    }
+
+    fn from_output(_out: Option<ExpandElement>) {}
+}
+
+impl<T: CubePrimitive> FunctionReturn for ExpandElementTyped<T> {
+    fn into_output(self, scope: &mut Scope) -> Option<Variable> {
+        let out = scope.create_local(self.expand.ty);
+        scope.register(Instruction::new(Operation::Copy(*self.expand), *out));
+        Some(*out)
+    }
+
+    fn from_output(out: Option<ExpandElement>) -> Self {
+        out.expect("Function should return a value").into()
+    }
+}
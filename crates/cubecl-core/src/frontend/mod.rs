@@ -12,6 +12,7 @@ mod const_expand;
 mod container;
 mod debug;
 mod element;
+mod function;
 mod indexation;
 mod list;
 mod operation;
@@ -22,12 +23,14 @@ mod scalar;
 mod topology;
 mod trigonometry;
 mod validation;
+mod work_distribution;
 
 pub use branch::{RangeExpand, SteppedRangeExpand, range, range_stepped};
 pub use const_expand::*;
 pub use container::*;
 pub use debug::*;
 pub use element::*;
+pub use function::*;
 pub use indexation::*;
 pub use list::*;
 pub use operation::*;
@@ -38,5 +41,6 @@ pub use scalar::*;
 pub use topology::*;
 pub use trigonometry::*;
 pub use validation::*;
+pub use work_distribution::*;
 
 pub use crate::{debug_print, debug_print_expand};
@@ -1,9 +1,9 @@
 use crate::ir::{Arithmetic, Bitwise, ExpandElement, Operator, Scope};
+use crate::{df64, frontend::CubeType, tf32};
 use crate::{
     flex32,
     frontend::{CubePrimitive, ExpandElementTyped},
 };
-use crate::{frontend::CubeType, tf32};
 use crate::{
     frontend::operation::base::{binary_expand, binary_expand_fixed_output},
     unexpanded,
@@ -249,6 +249,7 @@ impl_binary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -261,6 +262,7 @@ impl_binary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -273,6 +275,7 @@ impl_binary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -285,6 +288,7 @@ impl_binary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -300,6 +304,7 @@ impl_binary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64,
     i8,
@@ -325,6 +330,7 @@ impl_binary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64,
     i8,
@@ -350,6 +356,7 @@ impl_binary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64,
     i8,
@@ -403,6 +410,7 @@ impl_binary_func_fixed_output_vectorization!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64,
     i8,
@@ -426,6 +434,7 @@ impl_binary_func_mixed_types!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64,
     i8,
@@ -2,7 +2,7 @@ use cubecl_common::{e2m1, e4m3, e5m2, ue8m0};
 use half::{bf16, f16};
 
 use crate::{
-    flex32,
+    df64, flex32,
     ir::{Arithmetic, ClampOperator, ExpandElement, Scope},
     prelude::CubePrimitive,
     tf32, unexpanded,
@@ -45,6 +45,7 @@ impl Clamp for f16 {}
 impl Clamp for bf16 {}
 impl Clamp for flex32 {}
 impl Clamp for tf32 {}
+impl Clamp for df64 {}
 impl Clamp for f32 {}
 impl Clamp for f64 {}
 impl Clamp for i8 {}
@@ -3,7 +3,7 @@ use cubecl_ir::{Bitwise, Comparison, Operator, Type};
 use half::{bf16, f16};
 
 use crate::{
-    flex32,
+    df64, flex32,
     ir::{Arithmetic, ExpandElement, Scope},
     prelude::{CubePrimitive, ExpandElementTyped},
     tf32, unexpanded,
@@ -104,6 +104,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64,
     i8,
@@ -126,6 +127,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     // f32,
     f64
 );
@@ -138,6 +140,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -150,6 +153,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -162,6 +166,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -174,6 +179,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -186,6 +192,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -198,6 +205,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -210,6 +218,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -222,6 +231,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -234,6 +244,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -246,6 +257,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -258,6 +270,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -270,6 +283,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -282,6 +296,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -294,6 +309,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -306,6 +322,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -318,6 +335,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -330,6 +348,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -342,6 +361,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -354,6 +374,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -366,6 +387,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -378,6 +400,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -390,6 +413,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -402,6 +426,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -414,6 +439,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -427,6 +453,7 @@ impl_unary_func_fixed_out_vectorization!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -439,6 +466,7 @@ impl_unary_func!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -536,6 +564,7 @@ impl_unary_func_fixed_out_ty!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -549,6 +578,7 @@ impl_unary_func_fixed_out_ty!(
     bf16,
     flex32,
     tf32,
+    df64,
     f32,
     f64
 );
@@ -1,5 +1,5 @@
 use cubecl::prelude::*;
-use cubecl_common::{e4m3, e5m2, ue8m0};
+use cubecl_common::{df64, e4m3, e5m2, ue8m0};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -59,6 +59,7 @@ impl InputScalar {
                         write::<f32>(val, &mut out.data)
                     }
                     FloatKind::F64 => write::<f64>(val, &mut out.data),
+                    FloatKind::DF64 => write::<df64>(val, &mut out.data),
                     FloatKind::E2M1 | FloatKind::E2M3 | FloatKind::E3M2 => {
                         unimplemented!("fp6 CPU conversion not yet implemented")
                     }
@@ -19,10 +19,8 @@ macro_rules! constant {
 
             /// Expansion of the constant variable.
             pub fn expand(scope: &mut Scope) -> ExpandElementTyped<u32> {
-                ExpandElementTyped::new(ExpandElement::Plain(crate::ir::Variable::builtin(
-                    $var,
-                    u32::as_type(scope),
-                )))
+                let ty = u32::as_type(scope);
+                ExpandElementTyped::new(ExpandElement::Plain(scope.builtin($var, ty)))
             }
         }
     };
@@ -40,10 +38,8 @@ macro_rules! constant_usize {
 
             /// Expansion of the constant variable.
             pub fn expand(scope: &mut Scope) -> ExpandElementTyped<usize> {
-                ExpandElementTyped::new(ExpandElement::Plain(crate::ir::Variable::builtin(
-                    $var,
-                    usize::as_type(scope),
-                )))
+                let ty = usize::as_type(scope);
+                ExpandElementTyped::new(ExpandElement::Plain(scope.builtin($var, ty)))
             }
         }
     };
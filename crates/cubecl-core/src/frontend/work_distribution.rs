@@ -0,0 +1,338 @@
+//! Iterators distributing a range of work items over the units or cubes of a kernel launch.
+//!
+//! They decouple the number of work items from the launch dimensions, so a kernel can be launched
+//! with as many cubes as can be resident on the device (see [`PersistentGrid`]) and loop over its
+//! share of the work:
+//!
+//! ```ignore
+//! #[cube(launch)]
+//! fn kernel(input: &Array<f32>, output: &mut Array<f32>) {
+//!     for i in GridStride::units(input.len()) {
+//!         output[i] = input[i] * 2.0;
+//!     }
+//! }
+//! ```
+//!
+//! N-dimensional work ranges are flattened with [`work_len`] and recovered in the loop body with
+//! [`work_position`].
+
+use cubecl_ir::Scope;
+use cubecl_runtime::{kernel::KernelResourceUsage, server::CubeCountSelection, server::CubeDim};
+
+use super::branch::{Iterable, RangeExpand, break_expand, if_expand, loop_expand};
+use crate::prelude::*;
+use crate::{self as cubecl, unexpanded};
+
+/// Grid-stride distribution: worker `w` of `n` processes the items `w, w + n, w + 2n, ...`.
+///
+/// Consecutive workers process consecutive items, which keeps global memory accesses coalesced.
+#[derive(CubeType, Clone, Copy)]
+pub struct GridStride {
+    /// The first item of this worker.
+    pub start: usize,
+    /// The number of items.
+    pub end: usize,
+    /// The distance between two items of this worker.
+    pub step: usize,
+}
+
+#[cube]
+impl GridStride {
+    /// Distributes `num_items` over all the units of the launch.
+    pub fn units(num_items: usize) -> GridStride {
+        GridStride {
+            start: ABSOLUTE_POS,
+            end: num_items,
+            step: CUBE_COUNT * usize::cast_from(CUBE_DIM),
+        }
+    }
+
+    /// Distributes `num_items` over the cubes of the launch, every unit of a cube iterating over
+    /// the same items.
+    pub fn cubes(num_items: usize) -> GridStride {
+        GridStride {
+            start: CUBE_POS,
+            end: num_items,
+            step: CUBE_COUNT,
+        }
+    }
+}
+
+impl Iterator for GridStride {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unexpanded!()
+    }
+}
+
+impl Iterable<usize> for GridStrideExpand {
+    fn expand(self, scope: &mut Scope, body: impl FnMut(&mut Scope, ExpandElementTyped<usize>)) {
+        RangeExpand::new(self.start, self.end, false)
+            .__expand_step_by_method(self.step)
+            .expand(scope, body);
+    }
+
+    fn expand_unroll(
+        self,
+        _scope: &mut Scope,
+        _body: impl FnMut(&mut Scope, ExpandElementTyped<usize>),
+    ) {
+        unimplemented!("Can't unroll a grid-stride loop")
+    }
+}
+
+/// Block-cyclic distribution: the items are split into blocks of `block_size` consecutive items,
+/// and worker `w` of `n` processes the blocks `w, w + n, w + 2n, ...`.
+///
+/// Each worker processes contiguous runs of items, which suits kernels reusing data between
+/// neighbouring items. A `break` in the loop body only leaves the current block.
+#[derive(CubeType, Clone, Copy)]
+pub struct BlockCyclic {
+    /// The first item of the first block of this worker.
+    pub start: usize,
+    /// The number of items.
+    pub end: usize,
+    /// The distance between two blocks of this worker.
+    pub step: usize,
+    /// The number of items in a block.
+    pub block_size: usize,
+}
+
+#[cube]
+impl BlockCyclic {
+    /// Distributes `num_items` over all the units of the launch, in blocks of `block_size` items.
+    pub fn units(num_items: usize, block_size: usize) -> BlockCyclic {
+        BlockCyclic {
+            start: ABSOLUTE_POS * block_size,
+            end: num_items,
+            step: CUBE_COUNT * usize::cast_from(CUBE_DIM) * block_size,
+            block_size,
+        }
+    }
+
+    /// Distributes `num_items` over the cubes of the launch, in blocks of `block_size` items.
+    /// Every unit of a cube iterates over the same items.
+    pub fn cubes(num_items: usize, block_size: usize) -> BlockCyclic {
+        BlockCyclic {
+            start: CUBE_POS * block_size,
+            end: num_items,
+            step: CUBE_COUNT * block_size,
+            block_size,
+        }
+    }
+
+    /// Returns the end of the block starting at `block_start`, the last block being truncated to
+    /// the number of items.
+    pub fn block_end(&self, block_start: usize) -> usize {
+        Min::min(block_start + self.block_size, self.end)
+    }
+}
+
+impl Iterator for BlockCyclic {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unexpanded!()
+    }
+}
+
+impl Iterable<usize> for BlockCyclicExpand {
+    fn expand(
+        self,
+        scope: &mut Scope,
+        mut body: impl FnMut(&mut Scope, ExpandElementTyped<usize>),
+    ) {
+        let blocks = RangeExpand::new(self.start.clone(), self.end.clone(), false)
+            .__expand_step_by_method(self.step.clone());
+        blocks.expand(scope, |scope, block_start| {
+            let block_end = self
+                .clone()
+                .__expand_block_end_method(scope, block_start.clone());
+            RangeExpand::new(block_start, block_end, false).expand(scope, &mut body);
+        });
+    }
+
+    fn expand_unroll(
+        self,
+        _scope: &mut Scope,
+        _body: impl FnMut(&mut Scope, ExpandElementTyped<usize>),
+    ) {
+        unimplemented!("Can't unroll a block-cyclic loop")
+    }
+}
+
+/// Dynamic distribution: workers take the next unprocessed item from a global atomic counter
+/// until all items are taken.
+///
+/// This balances the load when the cost of the items varies, at the price of one atomic operation
+/// per item. The counter must be zero when the kernel starts, and is left at some value greater
+/// than or equal to the number of items, so it must be reset before being reused.
+#[derive(CubeType)]
+pub struct WorkQueue {
+    counter: Atomic<u32>,
+    /// The number of items.
+    pub num_items: usize,
+    broadcast: SharedMemory<u32>,
+    #[cube(comptime)]
+    per_cube: bool,
+}
+
+#[cube]
+impl WorkQueue {
+    /// Distributes `num_items` over the units of the launch, each unit taking items on its own.
+    pub fn units(counter: &Atomic<u32>, num_items: usize) -> WorkQueue {
+        WorkQueue {
+            counter: *counter,
+            num_items,
+            broadcast: SharedMemory::new(1usize),
+            per_cube: false,
+        }
+    }
+
+    /// Distributes `num_items` over the cubes of the launch. The first unit of the cube takes the
+    /// items, and every unit of the cube iterates over them, so all units must iterate together.
+    pub fn cubes(counter: &Atomic<u32>, num_items: usize) -> WorkQueue {
+        WorkQueue {
+            counter: *counter,
+            num_items,
+            broadcast: SharedMemory::new(1usize),
+            per_cube: true,
+        }
+    }
+
+    /// Takes the next item from the queue. Once the queue is empty, the returned item is greater
+    /// than or equal to the number of items.
+    pub fn pop(&mut self) -> usize {
+        if comptime!(self.per_cube) {
+            if UNIT_POS == 0 {
+                self.broadcast[0] = Atomic::add(&self.counter, 1u32);
+            }
+            sync_cube();
+            let item = self.broadcast[0];
+            sync_cube();
+            usize::cast_from(item)
+        } else {
+            usize::cast_from(Atomic::add(&self.counter, 1u32))
+        }
+    }
+
+    /// Whether an item returned by [`pop`](Self::pop) is past the end of the queue.
+    pub fn is_done(&self, item: usize) -> bool {
+        item >= self.num_items
+    }
+}
+
+impl Iterator for WorkQueue {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unexpanded!()
+    }
+}
+
+impl Iterable<usize> for WorkQueueExpand {
+    fn expand(
+        self,
+        scope: &mut Scope,
+        mut body: impl FnMut(&mut Scope, ExpandElementTyped<usize>),
+    ) {
+        loop_expand(scope, |scope| {
+            let item = self.clone().__expand_pop_method(scope);
+            let done = self.clone().__expand_is_done_method(scope, item.clone());
+            if_expand(scope, done.expand, break_expand);
+            body(scope, item);
+        });
+    }
+
+    fn expand_unroll(
+        self,
+        _scope: &mut Scope,
+        _body: impl FnMut(&mut Scope, ExpandElementTyped<usize>),
+    ) {
+        unimplemented!("Can't unroll a work queue loop")
+    }
+}
+
+/// Returns the number of items of an N-dimensional work range of the given `shape`.
+#[cube]
+pub fn work_len(shape: &Sequence<usize>) -> usize {
+    let mut len = 1;
+
+    #[unroll]
+    for i in 0..shape.len() {
+        len *= *shape.index(i);
+    }
+
+    len
+}
+
+/// Returns the position in an N-dimensional work range of the given `shape` of the item at
+/// `index`, with the last dimension being contiguous.
+#[cube]
+pub fn work_position(index: usize, shape: &Sequence<usize>) -> Sequence<usize> {
+    let rank = comptime![shape.len()];
+    let mut remainder = index;
+    let mut position = Sequence::new();
+
+    #[unroll]
+    for i in 0..rank {
+        let dim = comptime![rank - i - 1];
+        let size = *shape.index(dim);
+        position.push(remainder % size);
+        remainder /= size;
+    }
+
+    position.rev()
+}
+
+/// Launch dimensions for a persistent kernel, distributing its work with [`GridStride`],
+/// [`BlockCyclic`] or [`WorkQueue`] over as many cubes as can be resident on the device at once.
+///
+/// When the device doesn't report its occupancy limits, enough cubes are launched to give every
+/// worker a single item.
+#[derive(Debug, Clone)]
+pub struct PersistentGrid {
+    /// The cube dimension to launch with.
+    pub cube_dim: CubeDim,
+    /// The cube count to launch with.
+    pub cube_count: CubeCount,
+}
+
+impl PersistentGrid {
+    /// Sizes the grid for `num_items` distributed over units, picking the cube dimension with the
+    /// best occupancy for the kernel resource `usage`.
+    pub fn units<R: Runtime>(
+        client: &ComputeClient<R>,
+        usage: KernelResourceUsage,
+        num_items: usize,
+    ) -> Self {
+        let occupancy = client.occupancy(usage);
+        let cube_dim = occupancy.cube_dim(num_items);
+        let num_cubes = occupancy.persistent_num_cubes(cube_dim, num_items);
+        Self::new(client, cube_dim, num_cubes)
+    }
+
+    /// Sizes the grid for `num_items` distributed over cubes of `cube_dim`.
+    pub fn cubes<R: Runtime>(
+        client: &ComputeClient<R>,
+        usage: KernelResourceUsage,
+        cube_dim: CubeDim,
+        num_items: usize,
+    ) -> Self {
+        let working_units = num_items.saturating_mul(cube_dim.num_elems() as usize);
+        let num_cubes = client
+            .occupancy(usage)
+            .persistent_num_cubes(cube_dim, working_units);
+        Self::new(client, cube_dim, num_cubes)
+    }
+
+    fn new<R: Runtime>(client: &ComputeClient<R>, cube_dim: CubeDim, num_cubes: u32) -> Self {
+        let max_cube_count = &client.properties().hardware.max_cube_count;
+        let cube_count = CubeCountSelection::from_limits(max_cube_count, num_cubes).cube_count();
+        Self {
+            cube_dim,
+            cube_count,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use cubecl_common::{e2m1, e2m1x2, e4m3, e5m2, flex32, tf32, ue8m0};
+use cubecl_common::{df64, e2m1, e2m1x2, e4m3, e5m2, flex32, tf32, ue8m0};
 use cubecl_ir::StorageType;
 
 use crate::{
@@ -307,6 +307,27 @@ impl CubeElement for flex32 {
     }
 }
 
+impl CubeElement for df64 {
+    fn type_name() -> &'static str {
+        "df64"
+    }
+    fn as_bytes(slice: &[Self]) -> &[u8] {
+        bytemuck::cast_slice(slice)
+    }
+    fn from_bytes(bytes: &[u8]) -> &[Self] {
+        bytemuck::cast_slice(bytes)
+    }
+    fn cube_type() -> StorageType {
+        ElemType::Float(FloatKind::DF64).into()
+    }
+    fn maximum_value() -> Self {
+        df64::MAX
+    }
+    fn minimum_value() -> Self {
+        df64::MIN
+    }
+}
+
 impl CubeElement for tf32 {
     fn type_name() -> &'static str {
         "tf32"
@@ -0,0 +1,432 @@
+use alloc::{format, rc::Rc};
+use hashbrown::HashMap;
+
+use crate as cubecl;
+use cubecl_ir::{
+    Allocator, AtomicOp, BinaryOperator, CompareAndSwapOperator, DeviceProperties, ElemType,
+    ExpandElement, IndexOperator, Instruction, Operation, Operator, Processor, Scope,
+    ScopeProcessing, StorageType, Type, UIntKind, UnaryOperator, Variable, VariableKind,
+};
+
+use crate::prelude::*;
+
+/// Emulates atomic operations missing on a float atomic type with compare-and-swap loops on the
+/// unsigned integer of the same size, as selected by
+/// [`atomic_polyfill_type`](cubecl_ir::features::Features::atomic_polyfill_type).
+///
+/// All operations on a polyfilled type go through a reinterpreted integer view of the atomic, so
+/// targets that can't reinterpret atomic pointers can declare the type as the integer directly.
+///
+/// 16-bit types without 16-bit integer atomics are packed in pairs into `u32` words. Indexing a
+/// packed list yields a pointer to the aligned word and the shift of the element in it, and every
+/// operation updates its half of the word with shifts and masks. Pointers to packed elements must
+/// be indexed in the scope they're used in.
+#[derive(new, Debug)]
+pub struct AtomicPolyfillProcessor {
+    properties: Rc<DeviceProperties>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CasOp {
+    Add,
+    Sub,
+    Max,
+    Min,
+    Swap,
+}
+
+/// A pointer to a packed 16-bit atomic.
+#[derive(Clone, Copy, Debug)]
+struct PackedPointer {
+    /// The `u32` word containing the element.
+    word: Variable,
+    /// The bit offset of the element in the word.
+    shift: Variable,
+}
+
+impl Processor for AtomicPolyfillProcessor {
+    fn transform(&self, mut processing: ScopeProcessing, allocator: Allocator) -> ScopeProcessing {
+        let mut instructions = Vec::new();
+        core::mem::swap(&mut processing.instructions, &mut instructions);
+        let mut packed = HashMap::<Variable, PackedPointer>::new();
+
+        for instruction in instructions {
+            match &instruction.operation {
+                Operation::Operator(Operator::Index(op) | Operator::UncheckedIndex(op))
+                    if self.is_packed(instruction.out()) =>
+                {
+                    let unchecked = matches!(
+                        instruction.operation,
+                        Operation::Operator(Operator::UncheckedIndex(_))
+                    );
+                    let mut polyfill = Polyfill {
+                        processing: &mut processing,
+                        allocator: &allocator,
+                        elem: instruction.out().elem_type(),
+                        int: ElemType::UInt(UIntKind::U32),
+                    };
+                    let pointer = polyfill.index_packed(op, unchecked);
+                    packed.insert(instruction.out(), pointer);
+                    continue;
+                }
+                Operation::Copy(input) if packed.contains_key(input) => {
+                    packed.insert(instruction.out(), packed[input]);
+                    continue;
+                }
+                _ => {}
+            }
+
+            if let Operation::Atomic(op) = &instruction.operation {
+                let pointer = match op {
+                    AtomicOp::Load(op) => op.input,
+                    AtomicOp::Store(_) => instruction.out(),
+                    AtomicOp::CompareAndSwap(op) => op.input,
+                    AtomicOp::Swap(op)
+                    | AtomicOp::Add(op)
+                    | AtomicOp::Sub(op)
+                    | AtomicOp::Max(op)
+                    | AtomicOp::Min(op)
+                    | AtomicOp::And(op)
+                    | AtomicOp::Or(op)
+                    | AtomicOp::Xor(op) => op.lhs,
+                };
+
+                if let Some(int) = self.polyfill_type(pointer) {
+                    let mut polyfill = Polyfill {
+                        processing: &mut processing,
+                        allocator: &allocator,
+                        elem: pointer.elem_type(),
+                        int,
+                    };
+                    if !self.is_packed(pointer) {
+                        polyfill.apply(op, pointer, instruction.out);
+                    } else if let Some(pointer) = packed.get(&pointer) {
+                        polyfill.apply_packed(op, *pointer, instruction.out);
+                    } else if matches!(
+                        pointer.kind,
+                        VariableKind::LocalConst { .. }
+                            | VariableKind::LocalMut { .. }
+                            | VariableKind::Versioned { .. }
+                    ) {
+                        polyfill.processing.errors.push(format!(
+                            "Pointers to packed 16-bit atomics must be indexed in the scope they're used in, got `{instruction}`"
+                        ));
+                    } else {
+                        // Atomic variables that aren't lists hold a single element in the low
+                        // half of their word.
+                        let pointer = polyfill.packed_variable(pointer);
+                        polyfill.apply_packed(op, pointer, instruction.out);
+                    }
+                    continue;
+                }
+            }
+
+            processing.instructions.push(instruction);
+        }
+        processing
+    }
+}
+
+impl AtomicPolyfillProcessor {
+    fn polyfill_type(&self, pointer: Variable) -> Option<ElemType> {
+        match pointer.storage_type() {
+            StorageType::Atomic(elem) => self.properties.features.atomic_polyfill_type(elem),
+            _ => None,
+        }
+    }
+
+    /// Whether `pointer` is an atomic packed with its neighbour into a wider word.
+    fn is_packed(&self, pointer: Variable) -> bool {
+        self.polyfill_type(pointer)
+            .is_some_and(|int| int.size() > pointer.elem_type().size())
+    }
+}
+
+struct Polyfill<'a> {
+    processing: &'a mut ScopeProcessing,
+    allocator: &'a Allocator,
+    elem: ElemType,
+    int: ElemType,
+}
+
+impl Polyfill<'_> {
+    fn apply(&mut self, op: &AtomicOp, pointer: Variable, out: Option<Variable>) {
+        let view = self.reinterpret(pointer, StorageType::Atomic(self.int));
+
+        match op {
+            AtomicOp::Load(_) => {
+                let value = self.int_local();
+                self.push(AtomicOp::Load(UnaryOperator { input: view }), value);
+                self.push_reinterpret(value, out.unwrap());
+            }
+            AtomicOp::Store(op) => {
+                let value = self.reinterpret(op.input, self.int.into());
+                self.push(AtomicOp::Store(UnaryOperator { input: value }), view);
+            }
+            AtomicOp::Swap(op) => {
+                let value = self.reinterpret(op.rhs, self.int.into());
+                let old = self.int_local();
+                self.push(
+                    AtomicOp::Swap(BinaryOperator {
+                        lhs: view,
+                        rhs: value,
+                    }),
+                    old,
+                );
+                self.push_reinterpret(old, out.unwrap());
+            }
+            AtomicOp::CompareAndSwap(op) => {
+                let cmp = self.reinterpret(op.cmp, self.int.into());
+                let val = self.reinterpret(op.val, self.int.into());
+                let old = self.int_local();
+                self.push(
+                    AtomicOp::CompareAndSwap(CompareAndSwapOperator {
+                        input: view,
+                        cmp,
+                        val,
+                    }),
+                    old,
+                );
+                self.push_reinterpret(old, out.unwrap());
+            }
+            AtomicOp::And(op) => self.bitwise(view, op, out.unwrap(), AtomicOp::And),
+            AtomicOp::Or(op) => self.bitwise(view, op, out.unwrap(), AtomicOp::Or),
+            AtomicOp::Xor(op) => self.bitwise(view, op, out.unwrap(), AtomicOp::Xor),
+            AtomicOp::Add(op) => self.cas_loop(view, op.rhs, out.unwrap(), CasOp::Add),
+            AtomicOp::Sub(op) => self.cas_loop(view, op.rhs, out.unwrap(), CasOp::Sub),
+            AtomicOp::Max(op) => self.cas_loop(view, op.rhs, out.unwrap(), CasOp::Max),
+            AtomicOp::Min(op) => self.cas_loop(view, op.rhs, out.unwrap(), CasOp::Min),
+        }
+    }
+
+    fn bitwise(
+        &mut self,
+        view: Variable,
+        op: &BinaryOperator,
+        out: Variable,
+        atomic_op: fn(BinaryOperator) -> AtomicOp,
+    ) {
+        let rhs = self.reinterpret(op.rhs, self.int.into());
+        let old = self.int_local();
+        self.push(atomic_op(BinaryOperator { lhs: view, rhs }), old);
+        self.push_reinterpret(old, out);
+    }
+
+    fn cas_loop(&mut self, view: Variable, value: Variable, out: Variable, op: CasOp) {
+        let out_poly = self.expand(|scope| {
+            cas_loop::expand::<NumericExpand<0>, IntExpand<0>>(
+                scope,
+                ExpandElement::Plain(view).into(),
+                ExpandElement::Plain(value).into(),
+                op,
+            )
+            .expand
+        });
+        self.push(Operation::Copy(out_poly), out);
+    }
+
+    /// Replaces the index of a packed list by the index of the word containing the element.
+    fn index_packed(&mut self, op: &IndexOperator, unchecked: bool) -> PackedPointer {
+        let index_ty = op.index.storage_type();
+        let index = ExpandElement::Plain(op.index);
+        let word_index = self.expand(|scope| {
+            scope.register_type::<IntExpand<1>>(index_ty);
+            packed_word_index::expand::<IntExpand<1>>(scope, index.clone().into()).expand
+        });
+        let shift = self.expand(|scope| {
+            scope.register_type::<IntExpand<1>>(index_ty);
+            packed_shift::expand::<IntExpand<1>>(scope, index.into()).expand
+        });
+
+        let word = self.int_pointer();
+        let op = IndexOperator {
+            index: word_index,
+            ..op.clone()
+        };
+        self.push(
+            match unchecked {
+                true => Operator::UncheckedIndex(op),
+                false => Operator::Index(op),
+            },
+            word,
+        );
+        PackedPointer { word, shift }
+    }
+
+    /// A packed atomic that isn't a list, stored in the low half of its word.
+    fn packed_variable(&mut self, pointer: Variable) -> PackedPointer {
+        let word = self.reinterpret(pointer, StorageType::Atomic(self.int));
+        let shift = Variable::constant(0.into(), ElemType::UInt(UIntKind::U32));
+        PackedPointer { word, shift }
+    }
+
+    fn apply_packed(&mut self, op: &AtomicOp, pointer: PackedPointer, out: Option<Variable>) {
+        let word = ExpandElement::Plain(pointer.word);
+        let shift = ExpandElement::Plain(pointer.shift);
+
+        let value = match op {
+            AtomicOp::Load(_) => self.expand(|scope| {
+                packed_load::expand::<NumericExpand<0>>(scope, word.into(), shift.into()).expand
+            }),
+            AtomicOp::Store(op) => {
+                self.packed_cas_loop(word, shift, op.input, CasOp::Swap);
+                return;
+            }
+            AtomicOp::Swap(op) => self.packed_cas_loop(word, shift, op.rhs, CasOp::Swap),
+            AtomicOp::Add(op) => self.packed_cas_loop(word, shift, op.rhs, CasOp::Add),
+            AtomicOp::Sub(op) => self.packed_cas_loop(word, shift, op.rhs, CasOp::Sub),
+            AtomicOp::Max(op) => self.packed_cas_loop(word, shift, op.rhs, CasOp::Max),
+            AtomicOp::Min(op) => self.packed_cas_loop(word, shift, op.rhs, CasOp::Min),
+            AtomicOp::CompareAndSwap(_) | AtomicOp::And(_) | AtomicOp::Or(_) | AtomicOp::Xor(_) => {
+                unreachable!("Only defined on integers, which aren't polyfilled")
+            }
+        };
+        self.push(Operation::Copy(value), out.unwrap());
+    }
+
+    fn packed_cas_loop(
+        &mut self,
+        word: ExpandElement,
+        shift: ExpandElement,
+        value: Variable,
+        op: CasOp,
+    ) -> Variable {
+        self.expand(|scope| {
+            packed_cas_loop::expand::<NumericExpand<0>>(
+                scope,
+                word.into(),
+                shift.into(),
+                ExpandElement::Plain(value).into(),
+                op,
+            )
+            .expand
+        })
+    }
+
+    /// Expands `body` in a new scope with the polyfilled type registered, appending its
+    /// instructions to the processed ones. Returns the variable of the expanded value.
+    fn expand(&mut self, body: impl FnOnce(&mut Scope) -> ExpandElement) -> Variable {
+        let mut scope = Scope::root(false)
+            .with_allocator(self.allocator.clone())
+            .with_types(self.processing.typemap.clone());
+        scope.register_type::<NumericExpand<0>>(self.elem.into());
+        scope.register_type::<IntExpand<0>>(self.int.into());
+
+        let value = *body(&mut scope);
+        let tmp_processing = scope.process([]);
+
+        self.processing
+            .instructions
+            .extend(tmp_processing.instructions);
+        self.processing.variables.extend(tmp_processing.variables);
+        value
+    }
+
+    fn int_local(&mut self) -> Variable {
+        *self.allocator.create_local(Type::new(self.int.into()))
+    }
+
+    fn int_pointer(&mut self) -> Variable {
+        *self
+            .allocator
+            .create_local(Type::new(StorageType::Atomic(self.int)))
+    }
+
+    /// Reinterprets `value` as `ty`, returning the new variable.
+    fn reinterpret(&mut self, value: Variable, ty: StorageType) -> Variable {
+        let out = *self.allocator.create_local(Type::new(ty));
+        self.push_reinterpret(value, out);
+        out
+    }
+
+    fn push_reinterpret(&mut self, value: Variable, out: Variable) {
+        self.push(Operator::Reinterpret(UnaryOperator { input: value }), out);
+    }
+
+    fn push(&mut self, operation: impl Into<Operation>, out: Variable) {
+        self.processing
+            .instructions
+            .push(Instruction::new(operation, out));
+    }
+}
+
+/// Applies `op` to the value of `pointer`, retrying until no other unit wrote to it in between.
+/// Returns the old value, like the native atomics.
+#[cube]
+fn cas_loop<N: Numeric, U: Int>(pointer: &Atomic<U>, value: N, #[comptime] op: CasOp) -> N {
+    let mut old = Atomic::load(pointer);
+    loop {
+        let new = cas_apply::<N>(N::reinterpret(old), value, op);
+        let prev = Atomic::compare_and_swap(pointer, old, U::reinterpret(new));
+        if prev == old {
+            break;
+        }
+        old = prev;
+    }
+    N::reinterpret(old)
+}
+
+#[cube]
+fn cas_apply<N: Numeric>(current: N, value: N, #[comptime] op: CasOp) -> N {
+    if comptime![op == CasOp::Add] {
+        current + value
+    } else if comptime![op == CasOp::Sub] {
+        current - value
+    } else if comptime![op == CasOp::Max] {
+        Max::max(current, value)
+    } else if comptime![op == CasOp::Min] {
+        Min::min(current, value)
+    } else {
+        value
+    }
+}
+
+/// Like [`cas_loop`], on the half of the word selected by `shift`.
+#[cube]
+fn packed_cas_loop<N: Numeric>(
+    pointer: &Atomic<u32>,
+    shift: u32,
+    value: N,
+    #[comptime] op: CasOp,
+) -> N {
+    let mut old = Atomic::load(pointer);
+    loop {
+        let new = cas_apply::<N>(unpack_half::<N>(old, shift), value, op);
+        let prev = Atomic::compare_and_swap(pointer, old, pack_half::<N>(old, shift, new));
+        if prev == old {
+            break;
+        }
+        old = prev;
+    }
+    unpack_half::<N>(old, shift)
+}
+
+#[cube]
+fn packed_load<N: Numeric>(pointer: &Atomic<u32>, shift: u32) -> N {
+    unpack_half::<N>(Atomic::load(pointer), shift)
+}
+
+/// The index of the word containing the packed element at `index`.
+#[cube]
+fn packed_word_index<I: Int>(index: I) -> I {
+    index >> I::new(1)
+}
+
+/// The bit offset of the packed element at `index` in its word.
+#[cube]
+fn packed_shift<I: Int>(index: I) -> u32 {
+    u32::cast_from(index & I::new(1)) * 16
+}
+
+/// The half of `word` starting at bit `shift`.
+#[cube]
+fn unpack_half<N: Numeric>(word: u32, shift: u32) -> N {
+    Line::<N>::reinterpret(word >> shift)[0usize]
+}
+
+/// `word` with the half starting at bit `shift` replaced by the bits of `value`.
+#[cube]
+fn pack_half<N: Numeric>(word: u32, shift: u32, value: N) -> u32 {
+    let bits = u32::reinterpret(Line::<N>::empty(2usize).fill(value)) & 0xFFFF;
+    (word & (0xFFFFFFFFu32 ^ (0xFFFFu32 << shift))) | (bits << shift)
+}
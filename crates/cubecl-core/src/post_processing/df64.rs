@@ -0,0 +1,1100 @@
+use crate as cubecl;
+use cubecl_ir::{
+    Allocator, Arithmetic, BinaryOperator, Bitwise, ClampOperator, Comparison, ConstantValue,
+    ElemType, ExpandElement, FloatKind, IndexAssignOperator, IndexOperator, Instruction, IntKind,
+    LineInitOperator, Metadata, Operation, OperationReflect, Operator, Processor, Scope,
+    ScopeProcessing, Select, StorageType, Type, UIntKind, UnaryOperator, Variable,
+};
+
+use crate::prelude::*;
+
+/// Emulates the [`df64`] type by lowering it to pairs of `f32`, so kernels instantiated with it
+/// run on targets without double precision support.
+///
+/// A `Line<df64, N>` is lowered to a `Line<f32, 2 * N>` holding the high and low words of each
+/// element in turn, which matches the memory layout of [`df64`]. Buffers and scalars only need
+/// their type [lowered](lower_type) by the compiler.
+///
+/// Addition, subtraction, multiplication, division, square root, fused multiply-add, rounding,
+/// comparisons and conversions are computed with error-free transformations, for about 48 bits of
+/// precision. The transformations rely on IEEE `f32` arithmetic, so they must not be reassociated
+/// or contracted by the compiler. Other math functions are computed on the value rounded to `f32`,
+/// and atomic or plane operations on [`df64`] aren't supported.
+///
+/// Conversions to integers truncate towards zero and wrap when out of range.
+#[derive(new, Debug)]
+pub struct Df64EmulationProcessor;
+
+/// Whether `ty` is [`df64`], which is lowered by the [`Df64EmulationProcessor`].
+pub fn is_df64(ty: StorageType) -> bool {
+    matches!(ty, StorageType::Scalar(ElemType::Float(FloatKind::DF64)))
+}
+
+/// The type [`df64`] is lowered to by the [`Df64EmulationProcessor`]. Other types are returned
+/// unchanged.
+pub fn lower_type(ty: Type) -> Type {
+    if !is_df64(ty.storage_type()) {
+        return ty;
+    }
+    Type::scalar(ElemType::Float(FloatKind::F32)).line(ty.line_size() * 2)
+}
+
+/// The [`df64`] scalar lowered to `ty` by [`lower_type`], for variables that are never lines
+/// otherwise, like global scalars.
+pub fn lowered_scalar(ty: Type) -> Option<StorageType> {
+    match ty {
+        Type::Line(StorageType::Scalar(ElemType::Float(FloatKind::F32)), 2) => {
+            Some(ElemType::Float(FloatKind::DF64).into())
+        }
+        _ => None,
+    }
+}
+
+fn lower_var(mut var: Variable) -> Variable {
+    var.ty = lower_type(var.ty);
+    var
+}
+
+impl Processor for Df64EmulationProcessor {
+    fn transform(&self, mut processing: ScopeProcessing, allocator: Allocator) -> ScopeProcessing {
+        let mut instructions = Vec::new();
+        core::mem::swap(&mut processing.instructions, &mut instructions);
+
+        for instruction in instructions {
+            let mut lowering = Lowering {
+                processing: &mut processing,
+                allocator: &allocator,
+            };
+            if !lowering.lower(&instruction) {
+                processing.instructions.push(instruction);
+            }
+        }
+
+        for var in processing.variables.iter_mut() {
+            *var = lower_var(*var);
+        }
+
+        processing
+    }
+}
+
+/// The words of a [`df64`], whose sum is the represented value.
+#[derive(CubeType, Clone, Copy)]
+struct Df64 {
+    hi: f32,
+    lo: f32,
+}
+
+struct Lowering<'a> {
+    processing: &'a mut ScopeProcessing,
+    allocator: &'a Allocator,
+}
+
+impl Lowering<'_> {
+    /// Lowers `instruction` if it operates on [`df64`]. Returns whether it was lowered.
+    fn lower(&mut self, instruction: &Instruction) -> bool {
+        let Some(out) = instruction.out else {
+            return false;
+        };
+
+        match &instruction.operation {
+            Operation::Copy(input) if is_df64(out.storage_type()) => {
+                let value = self.materialize(*input);
+                self.push(Operation::Copy(value), lower_var(out));
+            }
+            Operation::Arithmetic(op) => {
+                let Some(args) = op.args() else {
+                    return false;
+                };
+                if !args.iter().any(|arg| self.is_lowered(*arg)) && !self.is_lowered(out) {
+                    return false;
+                }
+                self.arithmetic(op, out);
+            }
+            Operation::Comparison(op) => {
+                let cmp = match op {
+                    Comparison::Lower(op) => (op, CmpOp::Lower),
+                    Comparison::LowerEqual(op) => (op, CmpOp::LowerEqual),
+                    Comparison::Equal(op) => (op, CmpOp::Equal),
+                    Comparison::NotEqual(op) => (op, CmpOp::NotEqual),
+                    Comparison::GreaterEqual(op) => (op, CmpOp::GreaterEqual),
+                    Comparison::Greater(op) => (op, CmpOp::Greater),
+                    Comparison::IsNan(op) if self.is_lowered(op.input) => {
+                        self.predicate(op.input, out, is_nan::expand);
+                        return true;
+                    }
+                    Comparison::IsInf(op) if self.is_lowered(op.input) => {
+                        self.predicate(op.input, out, is_inf::expand);
+                        return true;
+                    }
+                    _ => return false,
+                };
+                let (op, cmp) = cmp;
+                if !self.is_lowered(op.lhs) {
+                    return false;
+                }
+                let n = out.line_size();
+                let (lhs, rhs) = (self.split(op.lhs, n), self.split(op.rhs, n));
+                let values = self.expand(|scope| {
+                    lhs.into_iter()
+                        .zip(rhs)
+                        .map(|(a, b)| *compare::expand(scope, a, b, cmp).expand)
+                        .collect()
+                });
+                self.merge_values(values, out);
+            }
+            Operation::Operator(op) => match op {
+                Operator::Cast(op) => return self.cast(op, out),
+                Operator::Reinterpret(op)
+                    if self.is_lowered(op.input) || is_df64(out.storage_type()) =>
+                {
+                    let input = self.materialize(op.input);
+                    self.push(
+                        Operator::Reinterpret(UnaryOperator { input }),
+                        lower_var(out),
+                    );
+                }
+                Operator::Index(op) | Operator::UncheckedIndex(op) if self.is_lowered(op.list) => {
+                    let unchecked = matches!(
+                        instruction.operation,
+                        Operation::Operator(Operator::UncheckedIndex(_))
+                    );
+                    self.index(op, out, unchecked);
+                }
+                Operator::IndexAssign(op) | Operator::UncheckedIndexAssign(op)
+                    if self.is_lowered(out) =>
+                {
+                    let unchecked = matches!(
+                        instruction.operation,
+                        Operation::Operator(Operator::UncheckedIndexAssign(_))
+                    );
+                    self.index_assign(op, out, unchecked);
+                }
+                Operator::InitLine(op) if is_df64(out.storage_type()) => {
+                    let values = op
+                        .inputs
+                        .iter()
+                        .flat_map(|input| self.split(*input, 1))
+                        .collect();
+                    self.merge(values, out);
+                }
+                Operator::Select(op) if is_df64(out.storage_type()) => self.select(op, out),
+                Operator::CopyMemory(op) if self.is_lowered(op.input) => {
+                    let mut op = op.clone();
+                    op.input = lower_var(op.input);
+                    self.push(Operator::CopyMemory(op), lower_var(out));
+                }
+                Operator::CopyMemoryBulk(op) if self.is_lowered(op.input) => {
+                    let mut op = op.clone();
+                    op.input = lower_var(op.input);
+                    self.push(Operator::CopyMemoryBulk(op), lower_var(out));
+                }
+                _ => return false,
+            },
+            Operation::Metadata(op) => {
+                let Some(args) = op.args() else {
+                    return false;
+                };
+                if !args.iter().any(|arg| self.is_lowered(*arg)) {
+                    return false;
+                }
+                let args = args.into_iter().map(lower_var).collect::<Vec<_>>();
+                let op = Metadata::from_code_and_args(op.op_code(), &args).unwrap();
+                self.push(op, out);
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn is_lowered(&self, var: Variable) -> bool {
+        is_df64(var.storage_type())
+    }
+
+    fn arithmetic(&mut self, op: &Arithmetic, out: Variable) {
+        match op {
+            Arithmetic::Add(op) => self.binary(op, out, add::expand),
+            Arithmetic::Sub(op) => self.binary(op, out, sub::expand),
+            Arithmetic::Mul(op) => self.binary(op, out, mul::expand),
+            Arithmetic::Div(op) => self.binary(op, out, div::expand),
+            Arithmetic::Max(op) => self.binary(op, out, max::expand),
+            Arithmetic::Min(op) => self.binary(op, out, min::expand),
+            Arithmetic::Modulo(op) => {
+                self.binary(op, out, |scope, a, b| rem::expand(scope, a, b, false))
+            }
+            Arithmetic::Remainder(op) => {
+                self.binary(op, out, |scope, a, b| rem::expand(scope, a, b, true))
+            }
+            Arithmetic::Fma(op) => {
+                let n = out.line_size();
+                let a = self.split(op.a, n);
+                let b = self.split(op.b, n);
+                let c = self.split(op.c, n);
+                let values = self.expand(|scope| {
+                    (0..n)
+                        .map(|i| fma::expand(scope, a[i].clone(), b[i].clone(), c[i].clone()))
+                        .collect()
+                });
+                self.merge(values, out);
+            }
+            Arithmetic::Clamp(op) => self.clamp(op, out),
+            Arithmetic::Neg(op) => self.unary(op.input, out, neg::expand),
+            Arithmetic::Abs(op) => self.unary(op.input, out, abs::expand),
+            Arithmetic::Sqrt(op) => self.unary(op.input, out, sqrt::expand),
+            Arithmetic::InverseSqrt(op) => self.unary(op.input, out, |scope, a| {
+                let root = sqrt::expand(scope, a);
+                recip::expand(scope, root)
+            }),
+            Arithmetic::Recip(op) => self.unary(op.input, out, recip::expand),
+            Arithmetic::Floor(op) => self.unary(op.input, out, floor::expand),
+            Arithmetic::Ceil(op) => self.unary(op.input, out, ceil::expand),
+            Arithmetic::Trunc(op) => self.unary(op.input, out, trunc::expand),
+            Arithmetic::Round(op) => self.unary(op.input, out, round::expand),
+            Arithmetic::Dot(op) => {
+                let n = op.lhs.line_size();
+                let (lhs, rhs) = (self.split(op.lhs, n), self.split(op.rhs, n));
+                let value = self.expand(|scope| dot(scope, lhs, rhs));
+                self.merge(vec![value], out);
+            }
+            Arithmetic::Magnitude(op) => {
+                let n = op.input.line_size();
+                let input = self.split(op.input, n);
+                let value = self.expand(|scope| {
+                    let squared = dot(scope, input.clone(), input);
+                    sqrt::expand(scope, squared)
+                });
+                self.merge(vec![value], out);
+            }
+            _ => self.rounded(op, out),
+        }
+    }
+
+    /// Computes `op` on the operands rounded to `f32`, for the functions that aren't emulated.
+    fn rounded(&mut self, op: &Arithmetic, out: Variable) {
+        let args = op
+            .args()
+            .unwrap()
+            .into_iter()
+            .map(|arg| match self.is_lowered(arg) {
+                true => self.narrow(arg),
+                false => arg,
+            })
+            .collect::<Vec<_>>();
+        let op = Arithmetic::from_code_and_args(op.op_code(), &args).unwrap();
+
+        if !self.is_lowered(out) {
+            self.push(op, out);
+            return;
+        }
+
+        let f32_ty = Type::scalar(ElemType::Float(FloatKind::F32)).line(out.line_size());
+        let value = *self.allocator.create_local(f32_ty);
+        self.push(op, value);
+        let lanes = (0..out.line_size())
+            .map(|i| self.lane(value, i))
+            .collect::<Vec<_>>();
+        let values = self.expand(|scope| {
+            lanes
+                .into_iter()
+                .map(|lane| from_f32::expand(scope, typed(lane)))
+                .collect()
+        });
+        self.merge(values, out);
+    }
+
+    /// The value of the [`df64`] `var` rounded to `f32`.
+    fn narrow(&mut self, var: Variable) -> Variable {
+        let n = var.line_size();
+        let values = self.split(var, n);
+        let values = self.expand(|scope| {
+            values
+                .into_iter()
+                .map(|value| *to_f32::expand(scope, value).expand)
+                .collect::<Vec<_>>()
+        });
+        let out = *self
+            .allocator
+            .create_local(Type::scalar(ElemType::Float(FloatKind::F32)).line(n));
+        self.merge_values(values, out);
+        out
+    }
+
+    fn binary(
+        &mut self,
+        op: &BinaryOperator,
+        out: Variable,
+        func: impl Fn(&mut Scope, Df64Expand, Df64Expand) -> Df64Expand,
+    ) {
+        let n = out.line_size();
+        let (lhs, rhs) = (self.split(op.lhs, n), self.split(op.rhs, n));
+        let values = self.expand(|scope| {
+            lhs.into_iter()
+                .zip(rhs)
+                .map(|(a, b)| func(scope, a, b))
+                .collect()
+        });
+        self.merge(values, out);
+    }
+
+    fn unary(
+        &mut self,
+        input: Variable,
+        out: Variable,
+        func: impl Fn(&mut Scope, Df64Expand) -> Df64Expand,
+    ) {
+        let input = self.split(input, out.line_size());
+        let values = self.expand(|scope| input.into_iter().map(|a| func(scope, a)).collect());
+        self.merge(values, out);
+    }
+
+    fn predicate(
+        &mut self,
+        input: Variable,
+        out: Variable,
+        func: impl Fn(&mut Scope, Df64Expand) -> ExpandElementTyped<bool>,
+    ) {
+        let input = self.split(input, out.line_size());
+        let values =
+            self.expand(|scope| input.into_iter().map(|a| *func(scope, a).expand).collect());
+        self.merge_values(values, out);
+    }
+
+    fn clamp(&mut self, op: &ClampOperator, out: Variable) {
+        let n = out.line_size();
+        let input = self.split(op.input, n);
+        let min_value = self.split(op.min_value, n);
+        let max_value = self.split(op.max_value, n);
+        let values = self.expand(|scope| {
+            (0..n)
+                .map(|i| {
+                    let value = max::expand(scope, input[i].clone(), min_value[i].clone());
+                    min::expand(scope, value, max_value[i].clone())
+                })
+                .collect()
+        });
+        self.merge(values, out);
+    }
+
+    /// Returns whether the cast involves [`df64`] and was lowered.
+    fn cast(&mut self, op: &UnaryOperator, out: Variable) -> bool {
+        let (from, to) = (op.input.storage_type(), out.storage_type());
+        let n = out.line_size();
+
+        match (is_df64(from), is_df64(to)) {
+            (false, false) => return false,
+            (true, true) => {
+                let input = self.materialize(op.input);
+                self.push(Operation::Copy(input), lower_var(out));
+            }
+            (true, false) => {
+                let values = self.split(op.input, n);
+                let values = match out.elem_type() {
+                    ElemType::Float(FloatKind::F64) => values
+                        .into_iter()
+                        .map(|value| self.f64_value(value))
+                        .collect(),
+                    ElemType::Float(kind) => {
+                        let values = self.expand(|scope| {
+                            values
+                                .into_iter()
+                                .map(|value| *to_f32::expand(scope, value).expand)
+                                .collect::<Vec<_>>()
+                        });
+                        values
+                            .into_iter()
+                            .map(|value| self.cast_scalar(value, ElemType::Float(kind)))
+                            .collect()
+                    }
+                    ElemType::Bool => self.expand(|scope| {
+                        values
+                            .into_iter()
+                            .map(|value| *is_non_zero::expand(scope, value).expand)
+                            .collect()
+                    }),
+                    elem => {
+                        let words = self.expand(|scope| {
+                            values
+                                .into_iter()
+                                .map(|value| to_words::expand(scope, value))
+                                .collect::<Vec<_>>()
+                        });
+                        words
+                            .into_iter()
+                            .map(|words| self.int_from_words(words, elem))
+                            .collect()
+                    }
+                };
+                self.merge_values(values, out);
+            }
+            (false, true) => {
+                let lanes = (0..n).map(|i| self.lane(op.input, i)).collect::<Vec<_>>();
+                let values = lanes.into_iter().map(|lane| self.widen(lane)).collect();
+                self.merge(values, out);
+            }
+        }
+
+        true
+    }
+
+    /// Converts a scalar that isn't a [`df64`].
+    fn widen(&mut self, value: Variable) -> Df64Expand {
+        let f32_elem = ElemType::Float(FloatKind::F32);
+        match value.elem_type() {
+            ElemType::Float(FloatKind::F64) => {
+                let hi = self.cast_scalar(value, f32_elem);
+                let hi_f64 = self.cast_scalar(hi, value.elem_type());
+                let rest = *self.allocator.create_local(value.ty);
+                self.push(
+                    Arithmetic::Sub(BinaryOperator {
+                        lhs: value,
+                        rhs: hi_f64,
+                    }),
+                    rest,
+                );
+                let lo = self.cast_scalar(rest, f32_elem);
+                Df64Expand {
+                    hi: typed(hi),
+                    lo: typed(lo),
+                }
+            }
+            ElemType::Float(_) => {
+                let value = self.cast_scalar(value, f32_elem);
+                self.expand(|scope| from_f32::expand(scope, typed(value)))
+            }
+            ElemType::Bool => {
+                let value = self.cast_scalar(value, f32_elem);
+                self.expand(|scope| from_f32::expand(scope, typed(value)))
+            }
+            elem if elem.size() == 8 => {
+                let signed = elem.is_signed_int();
+                let (lo, hi) = self.int_words(value);
+                self.expand(|scope| from_words::expand(scope, typed(lo), typed(hi), signed))
+            }
+            elem if elem.is_signed_int() => {
+                let value = self.cast_scalar(value, ElemType::Int(IntKind::I32));
+                self.expand(|scope| from_i32::expand(scope, typed(value)))
+            }
+            _ => {
+                let value = self.cast_scalar(value, ElemType::UInt(UIntKind::U32));
+                self.expand(|scope| from_u32::expand(scope, typed(value)))
+            }
+        }
+    }
+
+    fn f64_value(&mut self, value: Df64Expand) -> Variable {
+        let f64_elem = ElemType::Float(FloatKind::F64);
+        let hi = self.cast_scalar(*value.hi.expand, f64_elem);
+        let lo = self.cast_scalar(*value.lo.expand, f64_elem);
+        let out = *self.allocator.create_local(Type::scalar(f64_elem));
+        self.push(Arithmetic::Add(BinaryOperator { lhs: hi, rhs: lo }), out);
+        out
+    }
+
+    /// The low and high `u32` words of the 64-bit integer `value`.
+    fn int_words(&mut self, value: Variable) -> (Variable, Variable) {
+        let u32_elem = ElemType::UInt(UIntKind::U32);
+        let lo = self.cast_scalar(value, u32_elem);
+        let shifted = *self.allocator.create_local(value.ty);
+        self.push(
+            Bitwise::ShiftRight(BinaryOperator {
+                lhs: value,
+                rhs: 32u32.into(),
+            }),
+            shifted,
+        );
+        let hi = self.cast_scalar(shifted, u32_elem);
+        (lo, hi)
+    }
+
+    /// The integer of type `elem` with the two's complement `words`.
+    fn int_from_words(
+        &mut self,
+        (lo, hi): (ExpandElementTyped<u32>, ExpandElementTyped<u32>),
+        elem: ElemType,
+    ) -> Variable {
+        let (lo, hi) = (*lo.expand, *hi.expand);
+        if elem.size() < 8 {
+            return match elem {
+                ElemType::Int(IntKind::I32) => self.reinterpret(lo, elem),
+                _ => self.cast_scalar(lo, elem),
+            };
+        }
+
+        let hi = match elem.is_signed_int() {
+            true => self.reinterpret(hi, ElemType::Int(IntKind::I32)),
+            false => hi,
+        };
+        let hi = self.cast_scalar(hi, elem);
+        let lo = self.cast_scalar(lo, elem);
+        let shifted = *self.allocator.create_local(Type::scalar(elem));
+        self.push(
+            Bitwise::ShiftLeft(BinaryOperator {
+                lhs: hi,
+                rhs: 32u32.into(),
+            }),
+            shifted,
+        );
+        let out = *self.allocator.create_local(Type::scalar(elem));
+        self.push(
+            Bitwise::BitwiseOr(BinaryOperator {
+                lhs: shifted,
+                rhs: lo,
+            }),
+            out,
+        );
+        out
+    }
+
+    fn index(&mut self, op: &IndexOperator, out: Variable, unchecked: bool) {
+        if op.list.is_array() {
+            let op = IndexOperator {
+                list: lower_var(op.list),
+                line_size: op.line_size * 2,
+                ..op.clone()
+            };
+            let out = lower_var(out);
+            self.push(
+                match unchecked {
+                    true => Operator::UncheckedIndex(op),
+                    false => Operator::Index(op),
+                },
+                out,
+            );
+            return;
+        }
+
+        // Element of a line
+        let list = lower_var(op.list);
+        let (hi, lo) = self.word_indices(op.index);
+        let value = Df64Expand {
+            hi: typed(self.word(list, hi)),
+            lo: typed(self.word(list, lo)),
+        };
+        self.merge(vec![value], out);
+    }
+
+    fn index_assign(&mut self, op: &IndexAssignOperator, out: Variable, unchecked: bool) {
+        if out.is_array() {
+            let op = IndexAssignOperator {
+                value: self.materialize(op.value),
+                line_size: op.line_size * 2,
+                ..op.clone()
+            };
+            let out = lower_var(out);
+            self.push(
+                match unchecked {
+                    true => Operator::UncheckedIndexAssign(op),
+                    false => Operator::IndexAssign(op),
+                },
+                out,
+            );
+            return;
+        }
+
+        // Element of a line
+        let out = lower_var(out);
+        let value = self.split(op.value, 1).remove(0);
+        let (hi, lo) = self.word_indices(op.index);
+        self.assign_word(out, hi, *value.hi.expand);
+        self.assign_word(out, lo, *value.lo.expand);
+    }
+
+    fn select(&mut self, op: &Select, out: Variable) {
+        let n = out.line_size();
+        let conds = (0..n).map(|i| self.lane(op.cond, i)).collect::<Vec<_>>();
+        let then = self.split(op.then, n);
+        let or_else = self.split(op.or_else, n);
+        let values = self.expand(|scope| {
+            conds
+                .into_iter()
+                .zip(then.into_iter().zip(or_else))
+                .map(|(cond, (a, b))| select_df64::expand(scope, typed(cond), a, b))
+                .collect()
+        });
+        self.merge(values, out);
+    }
+
+    /// Runs `expand` in a new scope, and appends the instructions it generated.
+    fn expand<T>(&mut self, expand: impl FnOnce(&mut Scope) -> T) -> T {
+        let mut scope = Scope::root(false)
+            .with_allocator(self.allocator.clone())
+            .with_types(self.processing.typemap.clone());
+        let out = expand(&mut scope);
+        let processing = scope.process([]);
+
+        self.processing.instructions.extend(processing.instructions);
+        self.processing.variables.extend(processing.variables);
+        out
+    }
+
+    /// The words of the first `lanes` elements of the [`df64`] `var`. Scalars are broadcast.
+    fn split(&mut self, var: Variable, lanes: usize) -> Vec<Df64Expand> {
+        if let Some(value) = var.as_const() {
+            let value = match value {
+                ConstantValue::Int(value) => value as f64,
+                ConstantValue::UInt(value) => value as f64,
+                ConstantValue::Float(value) => value,
+                ConstantValue::Bool(value) => value as u32 as f64,
+            };
+            let (hi, lo) = df64::from_f64(value).to_parts();
+            let value = Df64Expand {
+                hi: ExpandElement::Plain(hi.into()).into(),
+                lo: ExpandElement::Plain(lo.into()).into(),
+            };
+            return vec![value; lanes];
+        }
+
+        let list = lower_var(var);
+        let broadcast = var.line_size() == 1;
+        (0..lanes)
+            .map(|i| {
+                let i = if broadcast { 0 } else { i };
+                Df64Expand {
+                    hi: typed(self.word(list, (2 * i).into())),
+                    lo: typed(self.word(list, (2 * i + 1).into())),
+                }
+            })
+            .collect()
+    }
+
+    /// Writes the words of each element to the [`df64`] `out`.
+    fn merge(&mut self, values: Vec<Df64Expand>, out: Variable) {
+        let out = lower_var(out);
+        let merged = *self.allocator.create_local_restricted(out.ty);
+        self.processing.variables.push(merged);
+
+        for (i, value) in values.into_iter().enumerate() {
+            self.assign_word(merged, (2 * i).into(), *value.hi.expand);
+            self.assign_word(merged, (2 * i + 1).into(), *value.lo.expand);
+        }
+
+        self.push(Operation::Copy(merged), out);
+    }
+
+    /// Writes the value of each element to `out`, which isn't a [`df64`].
+    fn merge_values(&mut self, values: Vec<Variable>, out: Variable) {
+        match values.len() {
+            1 => self.push(Operation::Copy(values[0]), out),
+            _ => self.push(Operator::InitLine(LineInitOperator { inputs: values }), out),
+        }
+    }
+
+    /// A [`df64`] constant is lowered to a variable holding its words.
+    fn materialize(&mut self, var: Variable) -> Variable {
+        if var.as_const().is_none() || !self.is_lowered(var) {
+            return lower_var(var);
+        }
+        let local = *self.allocator.create_local(var.ty);
+        let values = self.split(var, var.line_size());
+        self.merge(values, local);
+        lower_var(local)
+    }
+
+    /// The word at `index` of the lowered `list`.
+    fn word(&mut self, list: Variable, index: Variable) -> Variable {
+        let word = *self.allocator.create_local(Type::scalar(list.elem_type()));
+        self.push(
+            Operator::Index(IndexOperator {
+                list,
+                index,
+                line_size: 0,
+                unroll_factor: 1,
+            }),
+            word,
+        );
+        word
+    }
+
+    fn assign_word(&mut self, list: Variable, index: Variable, word: Variable) {
+        self.push(
+            Operator::IndexAssign(IndexAssignOperator {
+                index,
+                value: word,
+                line_size: 0,
+                unroll_factor: 1,
+            }),
+            list,
+        );
+    }
+
+    /// Indices of the high and low words of the element at `index`.
+    fn word_indices(&mut self, index: Variable) -> (Variable, Variable) {
+        if let Some(index) = index.as_const() {
+            let index = index.as_usize();
+            return ((2 * index).into(), (2 * index + 1).into());
+        }
+
+        let hi = *self.allocator.create_local(index.ty);
+        let lo = *self.allocator.create_local(index.ty);
+        let two = Variable::constant(2.into(), index.ty);
+        let one = Variable::constant(1.into(), index.ty);
+        self.push(
+            Arithmetic::Mul(BinaryOperator {
+                lhs: index,
+                rhs: two,
+            }),
+            hi,
+        );
+        self.push(Arithmetic::Add(BinaryOperator { lhs: hi, rhs: one }), lo);
+        (hi, lo)
+    }
+
+    /// The element at `index` of `var`, which isn't a [`df64`]. Scalars are broadcast.
+    fn lane(&mut self, var: Variable, index: usize) -> Variable {
+        if var.line_size() == 1 {
+            return var;
+        }
+        let lane = *self.allocator.create_local(Type::new(var.storage_type()));
+        self.push(
+            Operator::Index(IndexOperator {
+                list: var,
+                index: index.into(),
+                line_size: 0,
+                unroll_factor: 1,
+            }),
+            lane,
+        );
+        lane
+    }
+
+    fn cast_scalar(&mut self, value: Variable, elem: ElemType) -> Variable {
+        if value.elem_type() == elem {
+            return value;
+        }
+        let out = *self.allocator.create_local(Type::scalar(elem));
+        self.push(Operator::Cast(UnaryOperator { input: value }), out);
+        out
+    }
+
+    fn reinterpret(&mut self, value: Variable, elem: ElemType) -> Variable {
+        let out = *self.allocator.create_local(Type::scalar(elem));
+        self.push(Operator::Reinterpret(UnaryOperator { input: value }), out);
+        out
+    }
+
+    fn push(&mut self, operation: impl Into<Operation>, out: Variable) {
+        self.processing
+            .instructions
+            .push(Instruction::new(operation, out));
+    }
+}
+
+fn typed<T: CubePrimitive>(var: Variable) -> ExpandElementTyped<T> {
+    ExpandElement::Plain(var).into()
+}
+
+fn dot(scope: &mut Scope, lhs: Vec<Df64Expand>, rhs: Vec<Df64Expand>) -> Df64Expand {
+    let mut terms = lhs.into_iter().zip(rhs);
+    let (a, b) = terms.next().unwrap();
+    let mut sum = mul::expand(scope, a, b);
+    for (a, b) in terms {
+        let product = mul::expand(scope, a, b);
+        sum = add::expand(scope, sum, product);
+    }
+    sum
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CmpOp {
+    Lower,
+    LowerEqual,
+    Equal,
+    NotEqual,
+    GreaterEqual,
+    Greater,
+}
+
+#[cube]
+fn select_df64(cond: bool, then: Df64, or_else: Df64) -> Df64 {
+    Df64 {
+        hi: select(cond, then.hi, or_else.hi),
+        lo: select(cond, then.lo, or_else.lo),
+    }
+}
+
+#[cube]
+fn from_f32(value: f32) -> Df64 {
+    Df64 {
+        hi: value,
+        lo: 0.0f32,
+    }
+}
+
+#[cube]
+fn to_f32(a: Df64) -> f32 {
+    a.hi + a.lo
+}
+
+/// Multiplies by a power of two, which is exact.
+#[cube]
+fn scale(a: Df64, factor: f32) -> Df64 {
+    Df64 {
+        hi: a.hi * factor,
+        lo: a.lo * factor,
+    }
+}
+
+/// Special values are only represented by the high word, which is the result of the operation
+/// on the high words.
+#[cube]
+fn finite(a: Df64, approx: f32) -> Df64 {
+    let special = f32::is_nan(approx) || f32::is_inf(approx);
+    select_df64(special, from_f32(approx), a)
+}
+
+/// The rounded sum and its exact error, for any `a` and `b`.
+#[cube]
+fn two_sum(a: f32, b: f32) -> Df64 {
+    let sum = a + b;
+    let b_virtual = sum - a;
+    let a_virtual = sum - b_virtual;
+    Df64 {
+        hi: sum,
+        lo: (a - a_virtual) + (b - b_virtual),
+    }
+}
+
+/// The rounded sum and its exact error, when `|a| >= |b|`.
+#[cube]
+fn quick_two_sum(a: f32, b: f32) -> Df64 {
+    let sum = a + b;
+    Df64 {
+        hi: sum,
+        lo: b - (sum - a),
+    }
+}
+
+/// Splits the mantissa of `a` in two halves of 12 bits, whose products are exact. Big values are
+/// scaled down first so the split can't overflow.
+#[cube]
+fn split(a: f32) -> Df64 {
+    let big = f32::abs(a) > 1.0e30f32;
+    let scaled = select(big, a * 3.7252903e-9f32, a);
+    let t = scaled * 4097.0f32;
+    let hi = t - (t - scaled);
+    let parts = Df64 {
+        hi,
+        lo: scaled - hi,
+    };
+    select_df64(big, scale(parts, 268435456.0f32), parts)
+}
+
+/// The rounded product and its exact error.
+#[cube]
+fn two_prod(a: f32, b: f32) -> Df64 {
+    let product = a * b;
+    let a_parts = split(a);
+    let b_parts = split(b);
+    let err =
+        ((a_parts.hi * b_parts.hi - product) + a_parts.hi * b_parts.lo + a_parts.lo * b_parts.hi)
+            + a_parts.lo * b_parts.lo;
+    Df64 {
+        hi: product,
+        lo: err,
+    }
+}
+
+#[cube]
+fn add(a: Df64, b: Df64) -> Df64 {
+    let s = two_sum(a.hi, b.hi);
+    let t = two_sum(a.lo, b.lo);
+    let s = quick_two_sum(s.hi, s.lo + t.hi);
+    let s = quick_two_sum(s.hi, s.lo + t.lo);
+    finite(s, a.hi + b.hi)
+}
+
+#[cube]
+fn neg(a: Df64) -> Df64 {
+    Df64 {
+        hi: -a.hi,
+        lo: -a.lo,
+    }
+}
+
+#[cube]
+fn sub(a: Df64, b: Df64) -> Df64 {
+    add(a, neg(b))
+}
+
+#[cube]
+fn mul(a: Df64, b: Df64) -> Df64 {
+    let p = two_prod(a.hi, b.hi);
+    let lo = p.lo + (a.hi * b.lo + a.lo * b.hi);
+    finite(quick_two_sum(p.hi, lo), a.hi * b.hi)
+}
+
+#[cube]
+fn mul_f32(a: Df64, b: f32) -> Df64 {
+    let p = two_prod(a.hi, b);
+    quick_two_sum(p.hi, p.lo + a.lo * b)
+}
+
+/// Long division, refining the quotient of the high words with the remainders.
+#[cube]
+fn div(a: Df64, b: Df64) -> Df64 {
+    let q1 = a.hi / b.hi;
+    let r = sub(a, mul_f32(b, q1));
+    let q2 = r.hi / b.hi;
+    let r = sub(r, mul_f32(b, q2));
+    let q3 = r.hi / b.hi;
+    let q = add(quick_two_sum(q1, q2), from_f32(q3));
+    finite(q, q1)
+}
+
+#[cube]
+fn recip(a: Df64) -> Df64 {
+    div(from_f32(1.0f32), a)
+}
+
+/// One Newton step from the `f32` square root, which doubles its precision.
+#[cube]
+fn sqrt(a: Df64) -> Df64 {
+    let root = f32::sqrt(a.hi);
+    let square = two_prod(root, root);
+    let residual = ((a.hi - square.hi) - square.lo) + a.lo;
+    let refined = quick_two_sum(root, residual / (2.0f32 * root));
+    let regular = a.hi > 0.0f32 && !f32::is_inf(a.hi);
+    select_df64(regular, refined, from_f32(root))
+}
+
+#[cube]
+fn fma(a: Df64, b: Df64, c: Df64) -> Df64 {
+    add(mul(a, b), c)
+}
+
+#[cube]
+fn abs(a: Df64) -> Df64 {
+    select_df64(a.hi < 0.0f32, neg(a), a)
+}
+
+#[cube]
+fn compare(a: Df64, b: Df64, #[comptime] op: CmpOp) -> bool {
+    let hi_equal = a.hi == b.hi;
+
+    if comptime![op == CmpOp::Lower] {
+        a.hi < b.hi || (hi_equal && a.lo < b.lo)
+    } else if comptime![op == CmpOp::LowerEqual] {
+        a.hi < b.hi || (hi_equal && a.lo <= b.lo)
+    } else if comptime![op == CmpOp::Equal] {
+        hi_equal && a.lo == b.lo
+    } else if comptime![op == CmpOp::NotEqual] {
+        !hi_equal || a.lo != b.lo
+    } else if comptime![op == CmpOp::GreaterEqual] {
+        a.hi > b.hi || (hi_equal && a.lo >= b.lo)
+    } else {
+        a.hi > b.hi || (hi_equal && a.lo > b.lo)
+    }
+}
+
+#[cube]
+fn max(a: Df64, b: Df64) -> Df64 {
+    select_df64(compare(a, b, CmpOp::Lower), b, a)
+}
+
+#[cube]
+fn min(a: Df64, b: Df64) -> Df64 {
+    select_df64(compare(a, b, CmpOp::Lower), a, b)
+}
+
+#[cube]
+fn is_nan(a: Df64) -> bool {
+    f32::is_nan(a.hi) || f32::is_nan(a.lo)
+}
+
+#[cube]
+fn is_inf(a: Df64) -> bool {
+    f32::is_inf(a.hi)
+}
+
+#[cube]
+fn is_non_zero(a: Df64) -> bool {
+    a.hi != 0.0f32
+}
+
+/// The low word only matters when the high word is already an integer.
+#[cube]
+fn floor(a: Df64) -> Df64 {
+    let hi = f32::floor(a.hi);
+    let integer = quick_two_sum(hi, f32::floor(a.lo));
+    select_df64(hi == a.hi, integer, from_f32(hi))
+}
+
+#[cube]
+fn ceil(a: Df64) -> Df64 {
+    neg(floor(neg(a)))
+}
+
+#[cube]
+fn trunc(a: Df64) -> Df64 {
+    select_df64(a.hi < 0.0f32, ceil(a), floor(a))
+}
+
+/// Rounds half to even, like the native `round`.
+#[cube]
+fn round(a: Df64) -> Df64 {
+    let half = from_f32(0.5f32);
+    let rounded = floor(add(a, half));
+    let tie = compare(sub(rounded, a), half, CmpOp::Equal);
+    let odd = compare(
+        scale(floor(scale(rounded, 0.5f32)), 2.0f32),
+        rounded,
+        CmpOp::NotEqual,
+    );
+    select_df64(tie && odd, sub(rounded, from_f32(1.0f32)), rounded)
+}
+
+/// Remainder with the sign of the dividend, or of the divisor when `floored`.
+#[cube]
+fn rem(a: Df64, b: Df64, #[comptime] floored: bool) -> Df64 {
+    let quotient = div(a, b);
+    let quotient = if comptime![floored] {
+        floor(quotient)
+    } else {
+        trunc(quotient)
+    };
+    sub(a, mul(quotient, b))
+}
+
+/// Converts in two halves of 16 bits, which are exact in `f32`.
+#[cube]
+fn from_u32(value: u32) -> Df64 {
+    let hi = f32::cast_from(value >> 16u32) * 65536.0f32;
+    two_sum(hi, f32::cast_from(value & 0xFFFFu32))
+}
+
+#[cube]
+fn from_i32(value: i32) -> Df64 {
+    let hi = f32::cast_from(value >> 16i32) * 65536.0f32;
+    two_sum(hi, f32::cast_from(u32::reinterpret(value) & 0xFFFFu32))
+}
+
+#[cube]
+fn from_words(lo: u32, hi: u32, #[comptime] signed: bool) -> Df64 {
+    let high = if comptime![signed] {
+        from_i32(i32::reinterpret(hi))
+    } else {
+        from_u32(hi)
+    };
+    add(scale(high, 4294967296.0f32), from_u32(lo))
+}
+
+/// The 32 low bits of the integer `a` in `[0, 2^32)`, computed in halves of 16 bits.
+#[cube]
+fn integer_to_u32(a: Df64) -> u32 {
+    let hi = floor(scale(a, 1.0f32 / 65536.0f32));
+    let lo = sub(a, scale(hi, 65536.0f32));
+    u32::cast_from(hi.hi) * 65536u32 + u32::cast_from(lo.hi)
+}
+
+/// The low and high two's complement words of `a` truncated to an integer, wrapping at 64 bits.
+#[cube]
+fn to_words(a: Df64) -> (u32, u32) {
+    let integer = trunc(a);
+    let hi = floor(scale(integer, 1.0f32 / 4294967296.0f32));
+    let lo = sub(integer, scale(hi, 4294967296.0f32));
+    let hi_high = floor(scale(hi, 1.0f32 / 65536.0f32));
+    let hi_low = sub(hi, scale(hi_high, 65536.0f32));
+    let hi = i32::cast_from(hi_high.hi) * 65536i32 + i32::cast_from(hi_low.hi);
+    (integer_to_u32(lo), u32::reinterpret(hi))
+}
@@ -0,0 +1,275 @@
+use cubecl_ir::{
+    Allocator, Arithmetic, BinaryOperator, CoopMma, ElemType, IndexAssignOperator, IndexOperator,
+    Instruction, LineInitOperator, Operation, OperationReflect, Operator, Processor, Scope,
+    ScopeProcessing, Type, UIntKind, UnaryOperator, Variable, VariableKind,
+};
+
+/// Alignment of the memory backing the views, enough for any vectorized type.
+const ALIGN: usize = 16;
+
+/// Lowers the views of a [`DynamicSharedMemory`](crate::prelude::DynamicSharedMemory) specialized
+/// on its size to a single shared array of `u32` words, for targets that can't reinterpret shared
+/// memory.
+///
+/// Each access to a view reads or writes the words covered by the accessed item, reinterpreted to
+/// the type of the view, so views of different types alias each other like when the size is set at
+/// launch. Only indexing and copies are lowered, and the items of the views must be a multiple of
+/// 4 bytes. Any other use of a view, like a cooperative matrix load or passing it to a function, is
+/// reported as a validation error of the kernel.
+#[derive(Debug, Clone)]
+pub struct DynamicSharedMemoryProcessor {
+    words: Variable,
+}
+
+impl DynamicSharedMemoryProcessor {
+    /// Declares the array backing the `size` bytes of dynamic shared memory of the kernel with the
+    /// body `scope`. The same processor must be used for every scope of the kernel.
+    pub fn new(scope: &Scope, size: usize) -> Self {
+        let words = Variable::new(
+            VariableKind::SharedArray {
+                id: scope.new_local_index(),
+                length: Ord::max(size.div_ceil(4), 1),
+                unroll_factor: 1,
+                alignment: Some(ALIGN),
+            },
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        );
+        Self { words }
+    }
+}
+
+fn is_view(var: Variable) -> bool {
+    matches!(var.kind, VariableKind::DynamicSharedArray { .. })
+}
+
+/// Whether `instruction` reads or writes a view, including the operations without reflected
+/// arguments.
+fn uses_view(instruction: &Instruction) -> bool {
+    let operation = &instruction.operation;
+    let args = match operation {
+        Operation::Call(call) => call.args.clone(),
+        Operation::CoopMma(CoopMma::Load { value, .. }) => vec![*value],
+        Operation::CoopMma(CoopMma::LoadMatrix { buffer, .. }) => vec![*buffer],
+        _ => operation.args().unwrap_or_default(),
+    };
+    instruction.out.is_some_and(is_view) || args.into_iter().any(is_view)
+}
+
+impl Processor for DynamicSharedMemoryProcessor {
+    fn transform(&self, mut processing: ScopeProcessing, allocator: Allocator) -> ScopeProcessing {
+        let mut instructions = Vec::new();
+        core::mem::swap(&mut processing.instructions, &mut instructions);
+
+        for instruction in instructions {
+            let mut lowering = Lowering {
+                processing: &mut processing,
+                allocator: &allocator,
+                words: self.words,
+            };
+
+            match &instruction.operation {
+                Operation::Operator(Operator::Index(op)) if is_view(op.list) => {
+                    lowering.read(op.index, instruction.out(), false);
+                }
+                Operation::Operator(Operator::UncheckedIndex(op)) if is_view(op.list) => {
+                    lowering.read(op.index, instruction.out(), true);
+                }
+                Operation::Operator(Operator::IndexAssign(op)) if is_view(instruction.out()) => {
+                    lowering.write(op.index, op.value, false);
+                }
+                Operation::Operator(Operator::UncheckedIndexAssign(op))
+                    if is_view(instruction.out()) =>
+                {
+                    lowering.write(op.index, op.value, true);
+                }
+                Operation::Operator(Operator::CopyMemory(op))
+                    if is_view(op.input) || is_view(instruction.out()) =>
+                {
+                    lowering.copy(op.input, op.in_index, instruction.out(), op.out_index);
+                }
+                Operation::Operator(Operator::CopyMemoryBulk(op))
+                    if is_view(op.input) || is_view(instruction.out()) =>
+                {
+                    for i in 0..op.len {
+                        let in_index = lowering.offset(op.in_index, i);
+                        let out_index = lowering.offset(op.out_index, i);
+                        lowering.copy(op.input, in_index, instruction.out(), out_index);
+                    }
+                }
+                // Dropped so the compiler can report the error instead of failing on the view.
+                _ if uses_view(&instruction) => processing.errors.push(format!(
+                    "Views of a specialized dynamic shared memory can only be indexed or copied \
+                     on this runtime, got `{instruction}`"
+                )),
+                _ => processing.instructions.push(instruction),
+            }
+        }
+
+        processing
+    }
+}
+
+struct Lowering<'a> {
+    processing: &'a mut ScopeProcessing,
+    allocator: &'a Allocator,
+    words: Variable,
+}
+
+impl Lowering<'_> {
+    /// Reads the item at `index` of a view into `out`.
+    fn read(&mut self, index: Variable, out: Variable, unchecked: bool) {
+        let count = out.ty.size() / 4;
+        let first = self.first_word(index, count);
+
+        let values: Vec<_> = (0..count)
+            .map(|i| {
+                let index = self.offset(first, i);
+                let word = *self.allocator.create_local(self.words.ty);
+                let op = IndexOperator {
+                    list: self.words,
+                    index,
+                    line_size: 0,
+                    unroll_factor: 1,
+                };
+                self.push(
+                    match unchecked {
+                        true => Operator::UncheckedIndex(op),
+                        false => Operator::Index(op),
+                    },
+                    word,
+                );
+                word
+            })
+            .collect();
+
+        let bits = match values.len() {
+            1 => values[0],
+            _ => {
+                let line = *self.allocator.create_local(self.words.ty.line(count));
+                self.push(
+                    Operator::InitLine(LineInitOperator { inputs: values }),
+                    line,
+                );
+                line
+            }
+        };
+        self.push(Operator::Reinterpret(UnaryOperator { input: bits }), out);
+    }
+
+    /// Writes `value` to the item at `index` of a view.
+    fn write(&mut self, index: Variable, value: Variable, unchecked: bool) {
+        let count = value.ty.size() / 4;
+        let first = self.first_word(index, count);
+
+        let bits = *self.allocator.create_local(self.words.ty.line(count));
+        self.push(Operator::Reinterpret(UnaryOperator { input: value }), bits);
+
+        for i in 0..count {
+            let word = match count {
+                1 => bits,
+                _ => {
+                    let word = *self.allocator.create_local(self.words.ty);
+                    self.push(
+                        Operator::Index(IndexOperator {
+                            list: bits,
+                            index: i.into(),
+                            line_size: 0,
+                            unroll_factor: 1,
+                        }),
+                        word,
+                    );
+                    word
+                }
+            };
+            let op = IndexAssignOperator {
+                index: self.offset(first, i),
+                value: word,
+                line_size: 0,
+                unroll_factor: 1,
+            };
+            self.push(
+                match unchecked {
+                    true => Operator::UncheckedIndexAssign(op),
+                    false => Operator::IndexAssign(op),
+                },
+                self.words,
+            );
+        }
+    }
+
+    /// Copies the item at `in_index` of `input` to the item at `out_index` of `out`, either of
+    /// them being a view.
+    fn copy(&mut self, input: Variable, in_index: Variable, out: Variable, out_index: Variable) {
+        let value = *self.allocator.create_local(input.ty);
+        match is_view(input) {
+            true => self.read(in_index, value, false),
+            false => self.push(
+                Operator::Index(IndexOperator {
+                    list: input,
+                    index: in_index,
+                    line_size: 0,
+                    unroll_factor: 1,
+                }),
+                value,
+            ),
+        }
+        match is_view(out) {
+            true => self.write(out_index, value, false),
+            false => self.push(
+                Operator::IndexAssign(IndexAssignOperator {
+                    index: out_index,
+                    value,
+                    line_size: 0,
+                    unroll_factor: 1,
+                }),
+                out,
+            ),
+        }
+    }
+
+    /// Index of the first word of the item at `index`, for items of `count` words.
+    fn first_word(&mut self, index: Variable, count: usize) -> Variable {
+        if count == 1 {
+            return index;
+        }
+        if let Some(value) = index.as_const() {
+            return Variable::constant((value.as_usize() * count).into(), index.ty);
+        }
+
+        let first = *self.allocator.create_local(index.ty);
+        self.push(
+            Arithmetic::Mul(BinaryOperator {
+                lhs: index,
+                rhs: Variable::constant(count.into(), index.ty),
+            }),
+            first,
+        );
+        first
+    }
+
+    /// Index of the word `offset` words after `first`.
+    fn offset(&mut self, first: Variable, offset: usize) -> Variable {
+        if offset == 0 {
+            return first;
+        }
+        if let Some(value) = first.as_const() {
+            return Variable::constant((value.as_usize() + offset).into(), first.ty);
+        }
+
+        let index = *self.allocator.create_local(first.ty);
+        self.push(
+            Arithmetic::Add(BinaryOperator {
+                lhs: first,
+                rhs: Variable::constant(offset.into(), first.ty),
+            }),
+            index,
+        );
+        index
+    }
+
+    fn push(&mut self, operation: impl Into<Operation>, out: Variable) {
+        self.processing
+            .instructions
+            .push(Instruction::new(operation, out));
+    }
+}
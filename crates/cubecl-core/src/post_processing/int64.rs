@@ -0,0 +1,1052 @@
+use crate as cubecl;
+use cubecl_ir::{
+    Allocator, Arithmetic, Bitwise, ClampOperator, Comparison, ConstantValue, ElemType,
+    ExpandElement, IndexAssignOperator, IndexOperator, Instruction, IntKind, Metadata, Operation,
+    OperationReflect, Operator, Processor, Scope, ScopeProcessing, Select, StorageType, Type,
+    UIntKind, UnaryOperator, Variable,
+};
+
+use crate::prelude::*;
+
+/// Emulates 64-bit integers on targets that don't support them, by lowering them to pairs of
+/// 32-bit words.
+///
+/// A `Line<u64, N>` is lowered to a `Line<u32, 2 * N>` holding the low and high words of each
+/// element in turn, and `i64` to `i32` words. This matches the memory layout of the 64-bit type,
+/// so buffers and scalars only need their type [lowered](lower_type) by the compiler.
+///
+/// Arithmetic, comparison, bitwise operations, shifts and casts are computed on the words of each
+/// element. Other operations on 64-bit integers, like atomics and plane operations, are left as is
+/// and still require native support.
+#[derive(new, Debug)]
+pub struct Int64EmulationProcessor;
+
+/// Whether `ty` is a 64-bit integer, which is lowered by the [`Int64EmulationProcessor`].
+pub fn is_int64(ty: StorageType) -> bool {
+    matches!(
+        ty,
+        StorageType::Scalar(ElemType::Int(IntKind::I64) | ElemType::UInt(UIntKind::U64))
+    )
+}
+
+/// The type 64-bit integers are lowered to by the [`Int64EmulationProcessor`]. Other types are
+/// returned unchanged.
+pub fn lower_type(ty: Type) -> Type {
+    if !is_int64(ty.storage_type()) {
+        return ty;
+    }
+    let word = match ty.elem_type() {
+        ElemType::Int(_) => ElemType::Int(IntKind::I32),
+        _ => ElemType::UInt(UIntKind::U32),
+    };
+    Type::scalar(word).line(ty.line_size() * 2)
+}
+
+/// The 64-bit integer scalar lowered to `ty` by [`lower_type`], for variables that are never
+/// lines otherwise, like global scalars.
+pub fn lowered_scalar(ty: Type) -> Option<StorageType> {
+    match ty {
+        Type::Line(StorageType::Scalar(ElemType::UInt(UIntKind::U32)), 2) => {
+            Some(ElemType::UInt(UIntKind::U64).into())
+        }
+        Type::Line(StorageType::Scalar(ElemType::Int(IntKind::I32)), 2) => {
+            Some(ElemType::Int(IntKind::I64).into())
+        }
+        _ => None,
+    }
+}
+
+fn lower_var(mut var: Variable) -> Variable {
+    var.ty = lower_type(var.ty);
+    var
+}
+
+impl Processor for Int64EmulationProcessor {
+    fn transform(&self, mut processing: ScopeProcessing, allocator: Allocator) -> ScopeProcessing {
+        let mut instructions = Vec::new();
+        core::mem::swap(&mut processing.instructions, &mut instructions);
+
+        for instruction in instructions {
+            let mut lowering = Lowering {
+                processing: &mut processing,
+                allocator: &allocator,
+            };
+            if !lowering.lower(&instruction) {
+                processing.instructions.push(instruction);
+            }
+        }
+
+        for var in processing.variables.iter_mut() {
+            *var = lower_var(*var);
+        }
+
+        processing
+    }
+}
+
+/// The words of a 64-bit integer.
+#[derive(CubeType, Clone, Copy)]
+struct Word64 {
+    lo: u32,
+    hi: u32,
+}
+
+struct Lowering<'a> {
+    processing: &'a mut ScopeProcessing,
+    allocator: &'a Allocator,
+}
+
+impl Lowering<'_> {
+    /// Lowers `instruction` if it operates on 64-bit integers. Returns whether it was lowered.
+    fn lower(&mut self, instruction: &Instruction) -> bool {
+        let Some(out) = instruction.out else {
+            return false;
+        };
+        let signed = |var: &Variable| var.elem_type().is_signed_int();
+
+        match &instruction.operation {
+            Operation::Copy(input) if is_int64(out.storage_type()) => {
+                let value = self.materialize(*input);
+                self.push(Operation::Copy(value), lower_var(out));
+            }
+            Operation::Arithmetic(op) => match op {
+                Arithmetic::Add(op) if self.is_lowered(op.lhs) => {
+                    self.binary(op.lhs, op.rhs, out, add::expand)
+                }
+                Arithmetic::Sub(op) if self.is_lowered(op.lhs) => {
+                    self.binary(op.lhs, op.rhs, out, sub::expand)
+                }
+                Arithmetic::Mul(op) if self.is_lowered(op.lhs) => {
+                    self.binary(op.lhs, op.rhs, out, mul::expand)
+                }
+                Arithmetic::Div(op) if self.is_lowered(op.lhs) => {
+                    let signed = signed(&op.lhs);
+                    self.binary(op.lhs, op.rhs, out, |scope, a, b| {
+                        div::expand(scope, a, b, signed)
+                    })
+                }
+                Arithmetic::Modulo(op) if self.is_lowered(op.lhs) => {
+                    let signed = signed(&op.lhs);
+                    self.binary(op.lhs, op.rhs, out, |scope, a, b| {
+                        rem::expand(scope, a, b, signed, false)
+                    })
+                }
+                Arithmetic::Remainder(op) if self.is_lowered(op.lhs) => {
+                    let signed = signed(&op.lhs);
+                    self.binary(op.lhs, op.rhs, out, |scope, a, b| {
+                        rem::expand(scope, a, b, signed, true)
+                    })
+                }
+                Arithmetic::Max(op) if self.is_lowered(op.lhs) => {
+                    let signed = signed(&op.lhs);
+                    self.binary(op.lhs, op.rhs, out, |scope, a, b| {
+                        max::expand(scope, a, b, signed)
+                    })
+                }
+                Arithmetic::Min(op) if self.is_lowered(op.lhs) => {
+                    let signed = signed(&op.lhs);
+                    self.binary(op.lhs, op.rhs, out, |scope, a, b| {
+                        min::expand(scope, a, b, signed)
+                    })
+                }
+                Arithmetic::Neg(op) if self.is_lowered(op.input) => {
+                    self.unary(op.input, out, neg::expand)
+                }
+                Arithmetic::Abs(op) if self.is_lowered(op.input) => {
+                    let signed = signed(&op.input);
+                    self.unary(op.input, out, |scope, a| abs::expand(scope, a, signed))
+                }
+                Arithmetic::Clamp(op) if self.is_lowered(op.input) => self.clamp(op, out),
+                _ => return false,
+            },
+            Operation::Comparison(op) => {
+                let (op, cmp) = match op {
+                    Comparison::Lower(op) => (op, CmpOp::Lower),
+                    Comparison::LowerEqual(op) => (op, CmpOp::LowerEqual),
+                    Comparison::Equal(op) => (op, CmpOp::Equal),
+                    Comparison::NotEqual(op) => (op, CmpOp::NotEqual),
+                    Comparison::GreaterEqual(op) => (op, CmpOp::GreaterEqual),
+                    Comparison::Greater(op) => (op, CmpOp::Greater),
+                    _ => return false,
+                };
+                if !self.is_lowered(op.lhs) {
+                    return false;
+                }
+                let signed = signed(&op.lhs);
+                let n = out.line_size();
+                let (lhs, rhs) = (self.split(op.lhs, n), self.split(op.rhs, n));
+                let values = self.expand(|scope| {
+                    lhs.into_iter()
+                        .zip(rhs)
+                        .map(|(a, b)| *compare::expand(scope, a, b, cmp, signed).expand)
+                        .collect()
+                });
+                self.merge_values(values, out);
+            }
+            Operation::Bitwise(op) => match op {
+                Bitwise::BitwiseAnd(op) if self.is_lowered(op.lhs) => {
+                    self.binary(op.lhs, op.rhs, out, |scope, a, b| {
+                        bitwise::expand(scope, a, b, BitOp::And)
+                    })
+                }
+                Bitwise::BitwiseOr(op) if self.is_lowered(op.lhs) => {
+                    self.binary(op.lhs, op.rhs, out, |scope, a, b| {
+                        bitwise::expand(scope, a, b, BitOp::Or)
+                    })
+                }
+                Bitwise::BitwiseXor(op) if self.is_lowered(op.lhs) => {
+                    self.binary(op.lhs, op.rhs, out, |scope, a, b| {
+                        bitwise::expand(scope, a, b, BitOp::Xor)
+                    })
+                }
+                Bitwise::BitwiseNot(op) if self.is_lowered(op.input) => {
+                    self.unary(op.input, out, not::expand)
+                }
+                Bitwise::ReverseBits(op) if self.is_lowered(op.input) => {
+                    self.unary(op.input, out, reverse_bits::expand)
+                }
+                Bitwise::ShiftLeft(op) if self.is_lowered(op.lhs) => {
+                    self.shift(op.lhs, op.rhs, out, shl::expand)
+                }
+                Bitwise::ShiftRight(op) if self.is_lowered(op.lhs) => {
+                    let signed = signed(&op.lhs);
+                    self.shift(op.lhs, op.rhs, out, |scope, a, s| {
+                        shr::expand(scope, a, s, signed)
+                    })
+                }
+                Bitwise::CountOnes(op) if self.is_lowered(op.input) => {
+                    self.count(op.input, out, CountOp::Ones)
+                }
+                Bitwise::LeadingZeros(op) if self.is_lowered(op.input) => {
+                    self.count(op.input, out, CountOp::LeadingZeros)
+                }
+                Bitwise::FindFirstSet(op) if self.is_lowered(op.input) => {
+                    self.count(op.input, out, CountOp::FirstSet)
+                }
+                _ => return false,
+            },
+            Operation::Operator(op) => match op {
+                Operator::Cast(op) => return self.cast(op, out),
+                Operator::Reinterpret(op)
+                    if self.is_lowered(op.input) || is_int64(out.storage_type()) =>
+                {
+                    let input = self.materialize(op.input);
+                    self.push(
+                        Operator::Reinterpret(UnaryOperator { input }),
+                        lower_var(out),
+                    );
+                }
+                Operator::Index(op) | Operator::UncheckedIndex(op) if self.is_lowered(op.list) => {
+                    let unchecked = matches!(
+                        instruction.operation,
+                        Operation::Operator(Operator::UncheckedIndex(_))
+                    );
+                    self.index(op, out, unchecked);
+                }
+                Operator::IndexAssign(op) | Operator::UncheckedIndexAssign(op)
+                    if self.is_lowered(out) =>
+                {
+                    let unchecked = matches!(
+                        instruction.operation,
+                        Operation::Operator(Operator::UncheckedIndexAssign(_))
+                    );
+                    self.index_assign(op, out, unchecked);
+                }
+                Operator::InitLine(op) if is_int64(out.storage_type()) => {
+                    let words = op
+                        .inputs
+                        .iter()
+                        .flat_map(|input| self.split(*input, 1))
+                        .collect();
+                    self.merge(words, out);
+                }
+                Operator::Select(op) if is_int64(out.storage_type()) => self.select(op, out),
+                Operator::CopyMemory(op) if self.is_lowered(op.input) => {
+                    let mut op = op.clone();
+                    op.input = lower_var(op.input);
+                    self.push(Operator::CopyMemory(op), lower_var(out));
+                }
+                Operator::CopyMemoryBulk(op) if self.is_lowered(op.input) => {
+                    let mut op = op.clone();
+                    op.input = lower_var(op.input);
+                    self.push(Operator::CopyMemoryBulk(op), lower_var(out));
+                }
+                _ => return false,
+            },
+            Operation::Metadata(op) => {
+                let Some(args) = op.args() else {
+                    return false;
+                };
+                if !args.iter().any(|arg| self.is_lowered(*arg)) {
+                    return false;
+                }
+                let args = args.into_iter().map(lower_var).collect::<Vec<_>>();
+                let op = Metadata::from_code_and_args(op.op_code(), &args).unwrap();
+                self.push(op, out);
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn is_lowered(&self, var: Variable) -> bool {
+        is_int64(var.storage_type())
+    }
+
+    fn binary(
+        &mut self,
+        lhs: Variable,
+        rhs: Variable,
+        out: Variable,
+        op: impl Fn(&mut Scope, Word64Expand, Word64Expand) -> Word64Expand,
+    ) {
+        let n = out.line_size();
+        let (lhs, rhs) = (self.split(lhs, n), self.split(rhs, n));
+        let words = self.expand(|scope| {
+            lhs.into_iter()
+                .zip(rhs)
+                .map(|(a, b)| op(scope, a, b))
+                .collect()
+        });
+        self.merge(words, out);
+    }
+
+    fn unary(
+        &mut self,
+        input: Variable,
+        out: Variable,
+        op: impl Fn(&mut Scope, Word64Expand) -> Word64Expand,
+    ) {
+        let input = self.split(input, out.line_size());
+        let words = self.expand(|scope| input.into_iter().map(|a| op(scope, a)).collect());
+        self.merge(words, out);
+    }
+
+    fn clamp(&mut self, op: &ClampOperator, out: Variable) {
+        let n = out.line_size();
+        let input = self.split(op.input, n);
+        let min_value = self.split(op.min_value, n);
+        let max_value = self.split(op.max_value, n);
+        let signed = op.input.elem_type().is_signed_int();
+        let words = self.expand(|scope| {
+            (0..n)
+                .map(|i| {
+                    let value = max::expand(scope, input[i].clone(), min_value[i].clone(), signed);
+                    min::expand(scope, value, max_value[i].clone(), signed)
+                })
+                .collect()
+        });
+        self.merge(words, out);
+    }
+
+    fn shift(
+        &mut self,
+        lhs: Variable,
+        rhs: Variable,
+        out: Variable,
+        op: impl Fn(&mut Scope, Word64Expand, ExpandElementTyped<u32>) -> Word64Expand,
+    ) {
+        let n = out.line_size();
+        let lhs = self.split(lhs, n);
+        // Only the low word of a 64-bit shift amount matters.
+        let amounts = match self.is_lowered(rhs) {
+            true => self.split(rhs, n).into_iter().map(|word| word.lo).collect(),
+            false => (0..n)
+                .map(|i| {
+                    let lane = self.lane(rhs, i);
+                    typed(self.cast_scalar(lane, ElemType::UInt(UIntKind::U32)))
+                })
+                .collect::<Vec<_>>(),
+        };
+        let words = self.expand(|scope| {
+            lhs.into_iter()
+                .zip(amounts)
+                .map(|(a, s)| op(scope, a, s))
+                .collect()
+        });
+        self.merge(words, out);
+    }
+
+    fn count(&mut self, input: Variable, out: Variable, op: CountOp) {
+        let input = self.split(input, out.line_size());
+        let values = self.expand(|scope| {
+            input
+                .into_iter()
+                .map(|a| *count::expand(scope, a, op).expand)
+                .collect::<Vec<_>>()
+        });
+        let out_elem = out.elem_type();
+        let values = values
+            .into_iter()
+            .map(|value| self.cast_scalar(value, out_elem))
+            .collect();
+        self.merge_values(values, out);
+    }
+
+    /// Returns whether the cast involves 64-bit integers and was lowered.
+    fn cast(&mut self, op: &UnaryOperator, out: Variable) -> bool {
+        let (from, to) = (op.input.storage_type(), out.storage_type());
+        let n = out.line_size();
+
+        match (is_int64(from), is_int64(to)) {
+            (false, false) => return false,
+            (true, true) => {
+                let input = self.materialize(op.input);
+                let operation: Operation = match input.elem_type() == lower_var(out).elem_type() {
+                    true => Operation::Copy(input),
+                    false => Operator::Reinterpret(UnaryOperator { input }).into(),
+                };
+                self.push(operation, lower_var(out));
+            }
+            (true, false) => {
+                let signed = op.input.elem_type().is_signed_int();
+                let words = self.split(op.input, n);
+                let out_elem = out.elem_type();
+                let values = match out_elem {
+                    ElemType::Float(_) => self.expand(|scope| {
+                        scope.register_type::<FloatExpand<0>>(out_elem.into());
+                        words
+                            .into_iter()
+                            .map(|word| {
+                                *to_float::expand::<FloatExpand<0>>(scope, word, signed).expand
+                            })
+                            .collect()
+                    }),
+                    ElemType::Bool => self.expand(|scope| {
+                        words
+                            .into_iter()
+                            .map(|word| *is_non_zero::expand(scope, word).expand)
+                            .collect()
+                    }),
+                    _ => words
+                        .into_iter()
+                        .map(|word| self.cast_scalar(*word.lo.expand, out_elem))
+                        .collect(),
+                };
+                self.merge_values(values, out);
+            }
+            (false, true) => {
+                let in_elem = op.input.elem_type();
+                let lanes = (0..n).map(|i| self.lane(op.input, i)).collect::<Vec<_>>();
+                let words = match in_elem {
+                    ElemType::Float(_) => {
+                        let signed = out.elem_type().is_signed_int();
+                        self.expand(|scope| {
+                            scope.register_type::<FloatExpand<0>>(in_elem.into());
+                            lanes
+                                .into_iter()
+                                .map(|lane| {
+                                    let lane = ExpandElement::Plain(lane).into();
+                                    from_float::expand::<FloatExpand<0>>(scope, lane, signed)
+                                })
+                                .collect()
+                        })
+                    }
+                    ElemType::Int(_) => {
+                        let lanes = lanes
+                            .into_iter()
+                            .map(|lane| self.cast_scalar(lane, ElemType::Int(IntKind::I32)))
+                            .collect::<Vec<_>>();
+                        self.expand(|scope| {
+                            lanes
+                                .into_iter()
+                                .map(|lane| {
+                                    from_i32::expand(scope, ExpandElement::Plain(lane).into())
+                                })
+                                .collect()
+                        })
+                    }
+                    _ => lanes
+                        .into_iter()
+                        .map(|lane| Word64Expand {
+                            lo: typed(self.cast_scalar(lane, ElemType::UInt(UIntKind::U32))),
+                            hi: ExpandElement::Plain(0u32.into()).into(),
+                        })
+                        .collect(),
+                };
+                self.merge(words, out);
+            }
+        }
+
+        true
+    }
+
+    fn index(&mut self, op: &IndexOperator, out: Variable, unchecked: bool) {
+        if op.list.is_array() {
+            let op = IndexOperator {
+                list: lower_var(op.list),
+                line_size: op.line_size * 2,
+                ..op.clone()
+            };
+            let out = lower_var(out);
+            self.push(
+                match unchecked {
+                    true => Operator::UncheckedIndex(op),
+                    false => Operator::Index(op),
+                },
+                out,
+            );
+            return;
+        }
+
+        // Element of a line
+        let list = lower_var(op.list);
+        let (lo, hi) = self.word_indices(op.index);
+        let words = Word64Expand {
+            lo: typed(self.word(list, lo)),
+            hi: typed(self.word(list, hi)),
+        };
+        self.merge(vec![words], out);
+    }
+
+    fn index_assign(&mut self, op: &IndexAssignOperator, out: Variable, unchecked: bool) {
+        if out.is_array() {
+            let op = IndexAssignOperator {
+                value: self.materialize(op.value),
+                line_size: op.line_size * 2,
+                ..op.clone()
+            };
+            let out = lower_var(out);
+            self.push(
+                match unchecked {
+                    true => Operator::UncheckedIndexAssign(op),
+                    false => Operator::IndexAssign(op),
+                },
+                out,
+            );
+            return;
+        }
+
+        // Element of a line
+        let out = lower_var(out);
+        let word = self.split(op.value, 1).remove(0);
+        let (lo, hi) = self.word_indices(op.index);
+        self.assign_word(out, lo, *word.lo.expand);
+        self.assign_word(out, hi, *word.hi.expand);
+    }
+
+    fn select(&mut self, op: &Select, out: Variable) {
+        let n = out.line_size();
+        let conds = (0..n).map(|i| self.lane(op.cond, i)).collect::<Vec<_>>();
+        let then = self.split(op.then, n);
+        let or_else = self.split(op.or_else, n);
+        let words = self.expand(|scope| {
+            conds
+                .into_iter()
+                .zip(then.into_iter().zip(or_else))
+                .map(|(cond, (a, b))| {
+                    select_word::expand(scope, ExpandElement::Plain(cond).into(), a, b)
+                })
+                .collect()
+        });
+        self.merge(words, out);
+    }
+
+    /// Runs `expand` in a new scope, and appends the instructions it generated.
+    fn expand<T>(&mut self, expand: impl FnOnce(&mut Scope) -> T) -> T {
+        let mut scope = Scope::root(false)
+            .with_allocator(self.allocator.clone())
+            .with_types(self.processing.typemap.clone());
+        let out = expand(&mut scope);
+        let processing = scope.process([]);
+
+        self.processing.instructions.extend(processing.instructions);
+        self.processing.variables.extend(processing.variables);
+        out
+    }
+
+    /// The words of the first `lanes` elements of the 64-bit integer `var`. Scalars are broadcast.
+    fn split(&mut self, var: Variable, lanes: usize) -> Vec<Word64Expand> {
+        if let Some(value) = var.as_const() {
+            let bits = match value {
+                ConstantValue::Int(value) => value as u64,
+                ConstantValue::UInt(value) => value,
+                ConstantValue::Float(value) => value as i64 as u64,
+                ConstantValue::Bool(value) => value as u64,
+            };
+            let word = Word64Expand {
+                lo: ExpandElement::Plain((bits as u32).into()).into(),
+                hi: ExpandElement::Plain(((bits >> 32) as u32).into()).into(),
+            };
+            return vec![word; lanes];
+        }
+
+        let list = lower_var(var);
+        let broadcast = var.line_size() == 1;
+        (0..lanes)
+            .map(|i| {
+                let i = if broadcast { 0 } else { i };
+                Word64Expand {
+                    lo: typed(self.word(list, (2 * i).into())),
+                    hi: typed(self.word(list, (2 * i + 1).into())),
+                }
+            })
+            .collect()
+    }
+
+    /// Writes the words of each element to the 64-bit integer `out`.
+    fn merge(&mut self, words: Vec<Word64Expand>, out: Variable) {
+        let out = lower_var(out);
+        let merged = *self.allocator.create_local_restricted(out.ty);
+        self.processing.variables.push(merged);
+
+        for (i, word) in words.into_iter().enumerate() {
+            self.assign_word(merged, (2 * i).into(), *word.lo.expand);
+            self.assign_word(merged, (2 * i + 1).into(), *word.hi.expand);
+        }
+
+        self.push(Operation::Copy(merged), out);
+    }
+
+    /// Writes the value of each element to `out`, which isn't a 64-bit integer.
+    fn merge_values(&mut self, values: Vec<Variable>, out: Variable) {
+        match values.len() {
+            1 => self.push(Operation::Copy(values[0]), out),
+            _ => self.push(
+                Operator::InitLine(cubecl_ir::LineInitOperator { inputs: values }),
+                out,
+            ),
+        }
+    }
+
+    /// A 64-bit integer constant is lowered to a variable holding its words.
+    fn materialize(&mut self, var: Variable) -> Variable {
+        if var.as_const().is_none() || !self.is_lowered(var) {
+            return lower_var(var);
+        }
+        let local = *self.allocator.create_local(var.ty);
+        let words = self.split(var, var.line_size());
+        self.merge(words, local);
+        lower_var(local)
+    }
+
+    /// The word at `index` of the lowered `list`, as an `u32`.
+    fn word(&mut self, list: Variable, index: Variable) -> Variable {
+        let elem = list.elem_type();
+        let word = *self.allocator.create_local(Type::scalar(elem));
+        self.push(
+            Operator::Index(IndexOperator {
+                list,
+                index,
+                line_size: 0,
+                unroll_factor: 1,
+            }),
+            word,
+        );
+        match elem.is_signed_int() {
+            true => self.reinterpret(word, ElemType::UInt(UIntKind::U32)),
+            false => word,
+        }
+    }
+
+    fn assign_word(&mut self, list: Variable, index: Variable, word: Variable) {
+        let value = match list.elem_type().is_signed_int() {
+            true => self.reinterpret(word, ElemType::Int(IntKind::I32)),
+            false => word,
+        };
+        self.push(
+            Operator::IndexAssign(IndexAssignOperator {
+                index,
+                value,
+                line_size: 0,
+                unroll_factor: 1,
+            }),
+            list,
+        );
+    }
+
+    /// Indices of the low and high words of the element at `index`.
+    fn word_indices(&mut self, index: Variable) -> (Variable, Variable) {
+        if let Some(index) = index.as_const() {
+            let index = index.as_usize();
+            return ((2 * index).into(), (2 * index + 1).into());
+        }
+
+        let lo = *self.allocator.create_local(index.ty);
+        let hi = *self.allocator.create_local(index.ty);
+        let two = Variable::constant(2.into(), index.ty);
+        let one = Variable::constant(1.into(), index.ty);
+        self.push(
+            Arithmetic::Mul(cubecl_ir::BinaryOperator {
+                lhs: index,
+                rhs: two,
+            }),
+            lo,
+        );
+        self.push(
+            Arithmetic::Add(cubecl_ir::BinaryOperator { lhs: lo, rhs: one }),
+            hi,
+        );
+        (lo, hi)
+    }
+
+    /// The element at `index` of `var`, which isn't a 64-bit integer. Scalars are broadcast.
+    fn lane(&mut self, var: Variable, index: usize) -> Variable {
+        if var.line_size() == 1 {
+            return var;
+        }
+        let lane = *self.allocator.create_local(Type::new(var.storage_type()));
+        self.push(
+            Operator::Index(IndexOperator {
+                list: var,
+                index: index.into(),
+                line_size: 0,
+                unroll_factor: 1,
+            }),
+            lane,
+        );
+        lane
+    }
+
+    fn cast_scalar(&mut self, value: Variable, elem: ElemType) -> Variable {
+        if value.elem_type() == elem {
+            return value;
+        }
+        let out = *self.allocator.create_local(Type::scalar(elem));
+        self.push(Operator::Cast(UnaryOperator { input: value }), out);
+        out
+    }
+
+    fn reinterpret(&mut self, value: Variable, elem: ElemType) -> Variable {
+        let out = *self.allocator.create_local(Type::scalar(elem));
+        self.push(Operator::Reinterpret(UnaryOperator { input: value }), out);
+        out
+    }
+
+    fn push(&mut self, operation: impl Into<Operation>, out: Variable) {
+        self.processing
+            .instructions
+            .push(Instruction::new(operation, out));
+    }
+}
+
+fn typed<T: CubePrimitive>(var: Variable) -> ExpandElementTyped<T> {
+    ExpandElement::Plain(var).into()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CmpOp {
+    Lower,
+    LowerEqual,
+    Equal,
+    NotEqual,
+    GreaterEqual,
+    Greater,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BitOp {
+    And,
+    Or,
+    Xor,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CountOp {
+    Ones,
+    LeadingZeros,
+    FirstSet,
+}
+
+#[cube]
+fn select_word(cond: bool, then: Word64, or_else: Word64) -> Word64 {
+    Word64 {
+        lo: select(cond, then.lo, or_else.lo),
+        hi: select(cond, then.hi, or_else.hi),
+    }
+}
+
+#[cube]
+fn is_negative(a: Word64) -> bool {
+    (a.hi >> 31u32) == 1u32
+}
+
+#[cube]
+fn is_non_zero(a: Word64) -> bool {
+    (a.lo | a.hi) != 0u32
+}
+
+#[cube]
+fn add(a: Word64, b: Word64) -> Word64 {
+    let lo = a.lo + b.lo;
+    let carry = u32::cast_from(lo < a.lo);
+    Word64 {
+        lo,
+        hi: a.hi + b.hi + carry,
+    }
+}
+
+#[cube]
+fn sub(a: Word64, b: Word64) -> Word64 {
+    let borrow = u32::cast_from(a.lo < b.lo);
+    Word64 {
+        lo: a.lo - b.lo,
+        hi: a.hi - b.hi - borrow,
+    }
+}
+
+#[cube]
+fn neg(a: Word64) -> Word64 {
+    let zero = Word64 { lo: 0u32, hi: 0u32 };
+    sub(zero, a)
+}
+
+#[cube]
+fn abs(a: Word64, #[comptime] signed: bool) -> Word64 {
+    if comptime![signed] {
+        select_word(is_negative(a), neg(a), a)
+    } else {
+        a
+    }
+}
+
+/// The full product of two `u32`, computed on 16-bit halves so it can't overflow.
+#[cube]
+fn mul_wide(a: u32, b: u32) -> Word64 {
+    let (a_lo, a_hi) = (a & 0xFFFFu32, a >> 16u32);
+    let (b_lo, b_hi) = (b & 0xFFFFu32, b >> 16u32);
+    let lo_lo = a_lo * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_lo = a_hi * b_lo;
+    let hi_hi = a_hi * b_hi;
+    let mid = (lo_lo >> 16u32) + (lo_hi & 0xFFFFu32) + (hi_lo & 0xFFFFu32);
+    Word64 {
+        lo: (lo_lo & 0xFFFFu32) | (mid << 16u32),
+        hi: hi_hi + (lo_hi >> 16u32) + (hi_lo >> 16u32) + (mid >> 16u32),
+    }
+}
+
+/// The product wraps, so it's the same for signed and unsigned integers.
+#[cube]
+fn mul(a: Word64, b: Word64) -> Word64 {
+    let low = mul_wide(a.lo, b.lo);
+    Word64 {
+        lo: low.lo,
+        hi: low.hi + a.lo * b.hi + a.hi * b.lo,
+    }
+}
+
+/// Long division of unsigned integers, returning the quotient or the remainder.
+#[cube]
+fn div_rem_unsigned(n: Word64, d: Word64, #[comptime] remainder: bool) -> Word64 {
+    let mut q_lo = 0u32;
+    let mut q_hi = 0u32;
+    let mut r_lo = 0u32;
+    let mut r_hi = 0u32;
+
+    for i in 0..64u32 {
+        let bit = 63u32 - i;
+        let n_word = select(bit >= 32u32, n.hi, n.lo);
+        r_hi = (r_hi << 1u32) | (r_lo >> 31u32);
+        r_lo = (r_lo << 1u32) | ((n_word >> (bit & 31u32)) & 1u32);
+
+        let r = Word64 { lo: r_lo, hi: r_hi };
+        if !compare(r, d, CmpOp::Lower, false) {
+            let diff = sub(r, d);
+            r_lo = diff.lo;
+            r_hi = diff.hi;
+            let q_bit = 1u32 << (bit & 31u32);
+            if bit >= 32u32 {
+                q_hi |= q_bit;
+            } else {
+                q_lo |= q_bit;
+            }
+        }
+    }
+
+    if comptime![remainder] {
+        Word64 { lo: r_lo, hi: r_hi }
+    } else {
+        Word64 { lo: q_lo, hi: q_hi }
+    }
+}
+
+/// Division truncating towards zero.
+#[cube]
+fn div(a: Word64, b: Word64, #[comptime] signed: bool) -> Word64 {
+    if comptime![signed] {
+        let q = div_rem_unsigned(abs(a, true), abs(b, true), false);
+        select_word(is_negative(a) != is_negative(b), neg(q), q)
+    } else {
+        div_rem_unsigned(a, b, false)
+    }
+}
+
+/// Remainder with the sign of the dividend, or of the divisor when `floored`.
+#[cube]
+fn rem(a: Word64, b: Word64, #[comptime] signed: bool, #[comptime] floored: bool) -> Word64 {
+    if comptime![signed] {
+        let r = div_rem_unsigned(abs(a, true), abs(b, true), true);
+        let r = select_word(is_negative(a), neg(r), r);
+        if comptime![floored] {
+            let adjust = is_non_zero(r) && is_negative(r) != is_negative(b);
+            select_word(adjust, add(r, b), r)
+        } else {
+            r
+        }
+    } else {
+        div_rem_unsigned(a, b, true)
+    }
+}
+
+#[cube]
+fn compare(a: Word64, b: Word64, #[comptime] op: CmpOp, #[comptime] signed: bool) -> bool {
+    let hi_lower = if comptime![signed] {
+        i32::reinterpret(a.hi) < i32::reinterpret(b.hi)
+    } else {
+        a.hi < b.hi
+    };
+    let hi_greater = if comptime![signed] {
+        i32::reinterpret(a.hi) > i32::reinterpret(b.hi)
+    } else {
+        a.hi > b.hi
+    };
+    let hi_equal = a.hi == b.hi;
+
+    if comptime![op == CmpOp::Lower] {
+        hi_lower || (hi_equal && a.lo < b.lo)
+    } else if comptime![op == CmpOp::LowerEqual] {
+        hi_lower || (hi_equal && a.lo <= b.lo)
+    } else if comptime![op == CmpOp::Equal] {
+        hi_equal && a.lo == b.lo
+    } else if comptime![op == CmpOp::NotEqual] {
+        !hi_equal || a.lo != b.lo
+    } else if comptime![op == CmpOp::GreaterEqual] {
+        hi_greater || (hi_equal && a.lo >= b.lo)
+    } else {
+        hi_greater || (hi_equal && a.lo > b.lo)
+    }
+}
+
+#[cube]
+fn max(a: Word64, b: Word64, #[comptime] signed: bool) -> Word64 {
+    select_word(compare(a, b, CmpOp::Lower, signed), b, a)
+}
+
+#[cube]
+fn min(a: Word64, b: Word64, #[comptime] signed: bool) -> Word64 {
+    select_word(compare(a, b, CmpOp::Lower, signed), a, b)
+}
+
+#[cube]
+fn bitwise(a: Word64, b: Word64, #[comptime] op: BitOp) -> Word64 {
+    if comptime![op == BitOp::And] {
+        Word64 {
+            lo: a.lo & b.lo,
+            hi: a.hi & b.hi,
+        }
+    } else if comptime![op == BitOp::Or] {
+        Word64 {
+            lo: a.lo | b.lo,
+            hi: a.hi | b.hi,
+        }
+    } else {
+        Word64 {
+            lo: a.lo ^ b.lo,
+            hi: a.hi ^ b.hi,
+        }
+    }
+}
+
+#[cube]
+fn not(a: Word64) -> Word64 {
+    Word64 {
+        lo: u32::bitwise_not(a.lo),
+        hi: u32::bitwise_not(a.hi),
+    }
+}
+
+#[cube]
+fn reverse_bits(a: Word64) -> Word64 {
+    Word64 {
+        lo: u32::reverse_bits(a.hi),
+        hi: u32::reverse_bits(a.lo),
+    }
+}
+
+/// Shifting by 32 or more isn't allowed on `u32`, so the bits carried to the other word are
+/// shifted in two steps.
+#[cube]
+fn shl(a: Word64, shift: u32) -> Word64 {
+    let s = shift & 31u32;
+    let carry = (a.lo >> 1u32) >> (31u32 - s);
+    let small = Word64 {
+        lo: a.lo << s,
+        hi: (a.hi << s) | carry,
+    };
+    let large = Word64 {
+        lo: 0u32,
+        hi: a.lo << s,
+    };
+    select_word((shift & 63u32) >= 32u32, large, small)
+}
+
+#[cube]
+fn shr(a: Word64, shift: u32, #[comptime] signed: bool) -> Word64 {
+    let s = shift & 31u32;
+    let carry = (a.hi << 1u32) << (31u32 - s);
+    let (hi, fill) = if comptime![signed] {
+        let hi = i32::reinterpret(a.hi);
+        (
+            u32::reinterpret(hi >> i32::cast_from(s)),
+            u32::reinterpret(hi >> 31i32),
+        )
+    } else {
+        (a.hi >> s, 0u32)
+    };
+    let small = Word64 {
+        lo: (a.lo >> s) | carry,
+        hi,
+    };
+    let large = Word64 { lo: hi, hi: fill };
+    select_word((shift & 63u32) >= 32u32, large, small)
+}
+
+#[cube]
+fn count(a: Word64, #[comptime] op: CountOp) -> u32 {
+    if comptime![op == CountOp::Ones] {
+        u32::count_ones(a.lo) + u32::count_ones(a.hi)
+    } else if comptime![op == CountOp::LeadingZeros] {
+        select(
+            a.hi == 0u32,
+            32u32 + u32::leading_zeros(a.lo),
+            u32::leading_zeros(a.hi),
+        )
+    } else {
+        let hi = select(a.hi == 0u32, 0u32, 32u32 + u32::find_first_set(a.hi));
+        select(a.lo == 0u32, hi, u32::find_first_set(a.lo))
+    }
+}
+
+#[cube]
+fn from_i32(value: i32) -> Word64 {
+    Word64 {
+        lo: u32::reinterpret(value),
+        hi: u32::reinterpret(value >> 31i32),
+    }
+}
+
+#[cube]
+fn to_float<F: Float>(a: Word64, #[comptime] signed: bool) -> F {
+    let negative = comptime![signed] && is_negative(a);
+    let magnitude = select_word(negative, neg(a), a);
+    let value = F::cast_from(magnitude.hi) * F::new(4294967296.0) + F::cast_from(magnitude.lo);
+    select(negative, -value, value)
+}
+
+/// Converts a float, truncating towards zero. Negative values saturate to zero when unsigned.
+#[cube]
+fn from_float<F: Float>(value: F, #[comptime] signed: bool) -> Word64 {
+    let negative = value < F::new(0.0);
+    let magnitude = F::trunc(F::abs(value));
+    let hi = F::floor(magnitude / F::new(4294967296.0));
+    let lo = magnitude - hi * F::new(4294967296.0);
+    let word = Word64 {
+        lo: u32::cast_from(lo),
+        hi: u32::cast_from(hi),
+    };
+    let zero = Word64 { lo: 0u32, hi: 0u32 };
+    let negated = if comptime![signed] { neg(word) } else { zero };
+    select_word(negative, negated, word)
+}
@@ -1,4 +1,8 @@
+pub mod atomic;
 pub mod checked_io;
+pub mod df64;
+pub mod dynamic_shared_memory;
+pub mod int64;
 pub mod predicate;
 pub mod saturating;
 pub mod unroll;
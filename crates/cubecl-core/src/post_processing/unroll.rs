@@ -44,7 +44,8 @@ impl UnrollProcessor {
         inst: &Instruction,
         mappings: &mut Mappings,
     ) -> TransformAction {
-        if matches!(inst.operation, Operation::Marker(_)) {
+        // Function parameters aren't unrolled, so calls keep their arguments as is
+        if matches!(inst.operation, Operation::Marker(_) | Operation::Call(_)) {
             return TransformAction::Ignore;
         }
 
@@ -602,6 +603,7 @@ impl Processor for UnrollProcessor {
             variables: processing.variables,
             instructions,
             typemap: processing.typemap.clone(),
+            errors: processing.errors,
         }
     }
 }
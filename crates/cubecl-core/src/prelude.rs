@@ -12,7 +12,7 @@ pub use crate::{
     pod::CubeElement,
     terminate,
 };
-pub use cubecl_common::{flex32, tf32};
+pub use cubecl_common::{df64, flex32, tf32};
 pub use cubecl_ir::{AddressType, FastMath, LineSize, Scope, StorageType};
 pub use cubecl_runtime::{
     client::ComputeClient,
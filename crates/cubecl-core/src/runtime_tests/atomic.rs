@@ -1,7 +1,7 @@
 use crate::{self as cubecl};
 
 use cubecl::prelude::*;
-use cubecl_ir::{StorageType, features::TypeUsage};
+use cubecl_ir::features::TypeUsage;
 
 #[cube(launch)]
 pub fn kernel_atomic_add<I: Numeric>(output: &mut Array<Atomic<I>>) {
@@ -11,8 +11,12 @@ pub fn kernel_atomic_add<I: Numeric>(output: &mut Array<Atomic<I>>) {
 }
 
 fn supports_feature<R: Runtime, F: Numeric>(client: &ComputeClient<R>, feat: TypeUsage) -> bool {
-    let ty = StorageType::Atomic(F::as_type_native_unchecked().elem_type());
-    client.properties().type_usage(ty).contains(feat)
+    let elem = F::as_type_native_unchecked().elem_type();
+    client
+        .properties()
+        .features
+        .atomic_usage(elem)
+        .contains(feat)
 }
 
 pub fn test_kernel_atomic_add<R: Runtime, F: Numeric + CubeElement>(client: ComputeClient<R>) {
@@ -101,6 +105,70 @@ pub fn test_kernel_atomic_max<R: Runtime, F: Numeric + CubeElement>(client: Comp
     assert_eq!(actual[0], F::from_int(12));
 }
 
+#[cube(launch)]
+pub fn kernel_atomic_contended<I: Numeric>(
+    histogram: &mut Array<Atomic<I>>,
+    maximum: &mut Array<Atomic<I>>,
+) {
+    let bucket = UNIT_POS % histogram.len() as u32;
+    Atomic::add(&histogram[bucket as usize], I::from_int(1));
+    Atomic::max(&maximum[bucket as usize], I::cast_from(UNIT_POS));
+}
+
+/// Many units updating the same few values, so emulated atomics have to retry.
+pub fn test_kernel_atomic_contended<R: Runtime, F: Numeric + CubeElement>(
+    client: ComputeClient<R>,
+) {
+    if !supports_feature::<R, F>(&client, TypeUsage::AtomicAdd)
+        || !supports_feature::<R, F>(&client, TypeUsage::AtomicMinMax)
+    {
+        println!(
+            "{} Add or Max not supported - skipped",
+            Atomic::<F>::as_type_native_unchecked()
+        );
+        return;
+    }
+    let num_units = 64;
+    let num_buckets = 4;
+    let zeros = vec![F::from_int(0); num_buckets];
+    let histogram = client.create_from_slice(F::as_bytes(&zeros));
+    let maximum = client.create_from_slice(F::as_bytes(&zeros));
+
+    kernel_atomic_contended::launch::<F, R>(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(num_units as u32),
+        unsafe { ArrayArg::from_raw_parts::<F>(&histogram, num_buckets, 1) },
+        unsafe { ArrayArg::from_raw_parts::<F>(&maximum, num_buckets, 1) },
+    )
+    .unwrap();
+
+    let actual = client.read_one(histogram);
+    let actual = F::from_bytes(&actual);
+    let expected = vec![F::from_int((num_units / num_buckets) as i64); num_buckets];
+    assert_eq!(actual, &expected);
+
+    let actual = client.read_one(maximum);
+    let actual = F::from_bytes(&actual);
+    let expected = (0..num_buckets)
+        .map(|i| F::from_int((num_units - num_buckets + i) as i64))
+        .collect::<Vec<_>>();
+    assert_eq!(actual, &expected);
+}
+
+/// Neighbouring `f16` atomics share a word when 16-bit atomics are polyfilled, so this also
+/// checks that concurrent updates of both halves don't overwrite each other.
+pub fn test_kernel_atomic_contended_f16<R: Runtime>(client: ComputeClient<R>) {
+    if !client
+        .properties()
+        .supports_type(half::f16::as_type_native_unchecked())
+    {
+        println!("f16 not supported - skipped");
+        return;
+    }
+    test_kernel_atomic_contended::<R, half::f16>(client);
+}
+
 #[allow(missing_docs)]
 #[macro_export]
 macro_rules! testgen_atomic_int {
@@ -130,6 +198,14 @@ macro_rules! testgen_atomic_int {
                 client,
             );
         }
+
+        #[test]
+        fn test_atomic_contended_int() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::atomic::test_kernel_atomic_contended::<TestRuntime, IntType>(
+                client,
+            );
+        }
     };
 }
 
@@ -166,5 +242,28 @@ macro_rules! testgen_atomic_float {
                 client,
             );
         }
+
+        #[test]
+        fn test_atomic_contended_float() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::atomic::test_kernel_atomic_contended::<
+                TestRuntime,
+                FloatType,
+            >(client);
+        }
+    };
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_atomic_untyped {
+    () => {
+        #[test]
+        fn test_atomic_contended_f16() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::atomic::test_kernel_atomic_contended_f16::<TestRuntime>(
+                client,
+            );
+        }
     };
 }
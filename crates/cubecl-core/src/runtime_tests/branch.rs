@@ -63,6 +63,75 @@ pub fn kernel_select<F: Float>(output: &mut Array<F>, cond: u32) {
     }
 }
 
+#[cube(launch)]
+pub fn kernel_continue_for<F: Float>(input: &Array<F>, output: &mut Array<F>) {
+    if UNIT_POS == 0 {
+        let mut sum = F::new(0.0);
+        for i in 0..input.len() {
+            if input[i] < F::new(0.0) {
+                continue;
+            }
+            sum += input[i];
+        }
+        output[0] = sum;
+    }
+}
+
+#[cube(launch)]
+pub fn kernel_continue_for_unroll<F: Float>(output: &mut Array<F>) {
+    let mut sum = F::new(0.0);
+    #[unroll]
+    for i in 0..4u32 {
+        if i == 2 {
+            continue;
+        }
+        sum += F::new(1.0);
+    }
+    output[0] = sum;
+}
+
+#[cube(launch)]
+pub fn kernel_continue_while<F: Float>(output: &mut Array<F>) {
+    if UNIT_POS == 0 {
+        let mut sum = F::new(0.0);
+        let mut i: u32 = 0;
+        while i < 10 {
+            i += 1;
+            if i > 5 {
+                continue;
+            }
+            sum += F::new(1.0);
+        }
+        output[0] = sum;
+    }
+}
+
+#[cube(launch)]
+pub fn kernel_early_return<F: Float>(output: &mut Array<F>) {
+    if ABSOLUTE_POS >= output.len() {
+        return;
+    }
+    output[ABSOLUTE_POS] = F::new(2.0);
+}
+
+#[cube]
+fn first_above<F: Float>(input: &Array<F>, threshold: F) -> u32 {
+    for i in 0..input.len() {
+        if input[i] > threshold {
+            return i as u32;
+        }
+    }
+    input.len() as u32
+}
+
+#[cube(launch)]
+pub fn kernel_return_value<F: Float>(input: &Array<F>, output: &mut Array<u32>) {
+    if UNIT_POS == 0 {
+        output[0] = first_above::<F>(input, F::new(2.0));
+        output[1] = first_above::<F>(input, F::new(10.0));
+    }
+}
+
 pub fn test_switch_const<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
     let handle = client.create_from_slice(as_bytes![F: 0.0, 1.0]);
 
@@ -191,6 +260,91 @@ pub fn test_select<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>,
     }
 }
 
+pub fn test_continue_for<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let input = client.create_from_slice(as_bytes![F: 1.0, -2.0, 3.0, -4.0, 5.0]);
+    let output = client.empty(core::mem::size_of::<F>());
+
+    kernel_continue_for::launch::<F, R>(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(1),
+        unsafe { ArrayArg::from_raw_parts::<F>(&input, 5, 1) },
+        unsafe { ArrayArg::from_raw_parts::<F>(&output, 1, 1) },
+    )
+    .unwrap();
+
+    let actual = client.read_one(output);
+    let actual = F::from_bytes(&actual);
+
+    assert_eq!(actual[0], F::new(9.0));
+}
+
+pub fn test_continue_for_unroll<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let output = client.empty(core::mem::size_of::<F>());
+
+    let result = kernel_continue_for_unroll::launch::<F, R>(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(1),
+        unsafe { ArrayArg::from_raw_parts::<F>(&output, 1, 1) },
+    );
+
+    assert!(result.is_err());
+}
+
+pub fn test_continue_while<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let output = client.create_from_slice(as_bytes![F: 0.0]);
+
+    kernel_continue_while::launch::<F, R>(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(1),
+        unsafe { ArrayArg::from_raw_parts::<F>(&output, 1, 1) },
+    )
+    .unwrap();
+
+    let actual = client.read_one(output);
+    let actual = F::from_bytes(&actual);
+
+    assert_eq!(actual[0], F::new(5.0));
+}
+
+pub fn test_early_return<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let output = client.create_from_slice(as_bytes![F: 0.0, 0.0, 0.0]);
+
+    kernel_early_return::launch::<F, R>(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(4),
+        unsafe { ArrayArg::from_raw_parts::<F>(&output, 3, 1) },
+    )
+    .unwrap();
+
+    let actual = client.read_one(output);
+    let actual = F::from_bytes(&actual);
+
+    assert_eq!(actual, &[F::new(2.0), F::new(2.0), F::new(2.0)]);
+}
+
+pub fn test_return_value<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let input = client.create_from_slice(as_bytes![F: 1.0, 2.0, 3.0, 4.0]);
+    let output = client.create_from_slice(u32::as_bytes(&[0, 0]));
+
+    kernel_return_value::launch::<F, R>(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(1),
+        unsafe { ArrayArg::from_raw_parts::<F>(&input, 4, 1) },
+        unsafe { ArrayArg::from_raw_parts::<u32>(&output, 2, 1) },
+    )
+    .unwrap();
+
+    let actual = client.read_one(output);
+    let actual = u32::from_bytes(&actual);
+
+    assert_eq!(actual, &[2, 4]);
+}
+
 #[allow(missing_docs)]
 #[macro_export]
 macro_rules! testgen_branch {
@@ -248,5 +402,39 @@ macro_rules! testgen_branch {
             let client = TestRuntime::client(&Default::default());
             cubecl_core::runtime_tests::branch::test_switch_const::<TestRuntime, FloatType>(client);
         }
+
+        #[test]
+        fn test_continue_for() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::branch::test_continue_for::<TestRuntime, FloatType>(client);
+        }
+
+        #[test]
+        fn test_continue_for_unroll() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::branch::test_continue_for_unroll::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+
+        #[test]
+        fn test_continue_while() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::branch::test_continue_while::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+
+        #[test]
+        fn test_early_return() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::branch::test_early_return::<TestRuntime, FloatType>(client);
+        }
+
+        #[test]
+        fn test_return_value() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::branch::test_return_value::<TestRuntime, FloatType>(client);
+        }
     };
 }
@@ -0,0 +1,139 @@
+use crate::{self as cubecl};
+use cubecl::{CubeScalar, prelude::*};
+use cubecl_ir::features::TypeUsage;
+
+const NUM_OPS: usize = 12;
+
+#[cube(launch)]
+pub fn kernel_double_float<F: Float + CubeScalar>(
+    lhs: &Array<Line<F>>,
+    rhs: &Array<Line<F>>,
+    scalar: F,
+    output: &mut Array<Line<F>>,
+) {
+    let pos = ABSOLUTE_POS;
+    let len = lhs.len();
+    if pos < len {
+        let a = lhs[pos];
+        let b = rhs[pos];
+        output[pos] = a + b;
+        output[len + pos] = a - b;
+        output[2 * len + pos] = a * b;
+        output[3 * len + pos] = a / b;
+        output[4 * len + pos] = Line::sqrt(Line::abs(a));
+        output[5 * len + pos] = fma(a, b, Line::<F>::new(scalar));
+        output[6 * len + pos] = Line::floor(a);
+        output[7 * len + pos] = Max::max(a, b);
+        output[8 * len + pos] = Line::cast_from(Line::<i64>::cast_from(a));
+        output[9 * len + pos] = Line::cast_from(Line::<f32>::cast_from(a));
+        output[10 * len + pos] = Line::cast_from(a < b);
+        output[11 * len + pos] = a + Line::new(F::new(0.1));
+    }
+}
+
+/// Checks the operations of [`kernel_double_float`] against `f64`, with values that need more
+/// precision than `f32`.
+pub fn test_double_float<R: Runtime>(client: ComputeClient<R>) {
+    if !df64::supported_uses(&client).is_superset(TypeUsage::Arithmetic | TypeUsage::Buffer) {
+        println!("Unsupported, skipping");
+        return;
+    }
+
+    let lhs = [
+        1.0 + 1e-10,
+        core::f64::consts::PI,
+        -123_456.789_012_345,
+        2.5,
+        1e15 / 3.0,
+        -0.001_234_567_890_123,
+        3.5,
+        -7.25,
+    ];
+    let rhs = [
+        1.0 - 1e-10,
+        core::f64::consts::E,
+        0.000_987_654_321,
+        -2.5,
+        7.0,
+        1e-8 / 3.0,
+        3.5 + 1e-12,
+        1.0 / 3.0,
+    ];
+    let scalar = 1e-9 / 7.0;
+
+    let num_elems = lhs.len();
+    let mut expected = vec![0.0; num_elems * NUM_OPS];
+    for i in 0..num_elems {
+        // Operands are rounded to `df64` first, like the kernel inputs
+        let (a, b) = (
+            df64::from_f64(lhs[i]).to_f64(),
+            df64::from_f64(rhs[i]).to_f64(),
+        );
+        let c = df64::from_f64(scalar).to_f64();
+        let values = [
+            a + b,
+            a - b,
+            a * b,
+            a / b,
+            a.abs().sqrt(),
+            a.mul_add(b, c),
+            a.floor(),
+            a.max(b),
+            a.trunc(),
+            a as f32 as f64,
+            (a < b) as u32 as f64,
+            a + 0.1f32 as f64,
+        ];
+        for (op, value) in values.into_iter().enumerate() {
+            expected[op * num_elems + i] = value;
+        }
+    }
+
+    let lhs = lhs.map(df64::from_f64);
+    let rhs = rhs.map(df64::from_f64);
+
+    for line_size in [1, 2, 4] {
+        let lhs_handle = client.create_from_slice(df64::as_bytes(&lhs));
+        let rhs_handle = client.create_from_slice(df64::as_bytes(&rhs));
+        let output = client.empty(num_elems * NUM_OPS * size_of::<df64>());
+
+        kernel_double_float::launch::<df64, R>(
+            &client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new_1d((num_elems / line_size) as u32),
+            unsafe { ArrayArg::from_raw_parts::<df64>(&lhs_handle, num_elems, line_size) },
+            unsafe { ArrayArg::from_raw_parts::<df64>(&rhs_handle, num_elems, line_size) },
+            ScalarArg::new(df64::from_f64(scalar)),
+            unsafe { ArrayArg::from_raw_parts::<df64>(&output, num_elems * NUM_OPS, line_size) },
+        )
+        .unwrap();
+
+        let actual = client.read_one(output);
+        let actual = df64::from_bytes(&actual);
+        for (i, (actual, expected)) in actual.iter().zip(&expected).enumerate() {
+            let actual = actual.to_f64();
+            // About 44 bits of precision, leaving a few bits for the rounding of each step
+            let tolerance = expected.abs() * 2f64.powi(-44) + f64::MIN_POSITIVE;
+            assert!(
+                (actual - expected).abs() <= tolerance,
+                "op {}, element {}, line size {line_size}: {actual} != {expected}",
+                i / num_elems,
+                i % num_elems,
+            );
+        }
+    }
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_df64 {
+    () => {
+        use super::*;
+
+        #[test]
+        fn test_double_float() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::df64::test_double_float::<TestRuntime>(client);
+        }
+    };
+}
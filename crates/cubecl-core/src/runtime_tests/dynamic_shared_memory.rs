@@ -0,0 +1,65 @@
+use crate::{self as cubecl};
+use cubecl::prelude::*;
+
+#[cube(launch)]
+fn dynamic_shared_memory_views(
+    smem: &DynamicSharedMemory,
+    output: &mut Array<f32>,
+    #[comptime] units: usize,
+) {
+    let mut values = smem.view::<f32>(0, units);
+    let mut counts = smem.view::<u32>(units * 4, units);
+    let pos = UNIT_POS as usize;
+
+    values[pos] = f32::cast_from(UNIT_POS) * 2.0;
+    counts[pos] = UNIT_POS + 1;
+    sync_cube();
+
+    // Read what the next unit wrote, to check the views are shared across the cube.
+    let next = (pos + 1) % units;
+    output[pos] = values[next] + f32::cast_from(counts[next]);
+}
+
+fn launch<R: Runtime>(client: &ComputeClient<R>, units: usize) {
+    let output = client.empty(units * core::mem::size_of::<f32>());
+
+    dynamic_shared_memory_views::launch::<R>(
+        client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(units as u32),
+        DynamicSharedMemoryArg::new(client, units * 8),
+        unsafe { ArrayArg::from_raw_parts::<f32>(&output, units, 1) },
+        units,
+    )
+    .unwrap();
+
+    let actual = client.read_one(output);
+    let expected = (0..units)
+        .map(|pos| {
+            let next = (pos + 1) % units;
+            next as f32 * 2.0 + (next + 1) as f32
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(f32::from_bytes(&actual), expected);
+}
+
+pub fn test_dynamic_shared_memory<R: Runtime>(client: ComputeClient<R>) {
+    launch(&client, 32);
+    launch(&client, 64);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_dynamic_shared_memory {
+    () => {
+        use super::*;
+
+        #[test]
+        fn test_dynamic_shared_memory() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::dynamic_shared_memory::test_dynamic_shared_memory::<
+                TestRuntime,
+            >(client);
+        }
+    };
+}
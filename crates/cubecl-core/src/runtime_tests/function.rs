@@ -0,0 +1,178 @@
+use crate::{self as cubecl, as_bytes, as_type};
+use cubecl::prelude::*;
+
+#[cube(noinline)]
+fn mul_add<F: Float>(a: F, b: F, c: F) -> F {
+    a * b + c
+}
+
+#[cube(noinline)]
+fn scaled_unit_pos<F: Float>(scale: F) -> F {
+    F::cast_from(UNIT_POS) * scale
+}
+
+#[cube(noinline)]
+fn clamp_to<F: Float>(value: F, #[comptime] limit: u32) -> F {
+    let limit = F::cast_from(limit);
+    if value > limit {
+        return limit;
+    }
+    value
+}
+
+#[cube(noinline)]
+fn mul_add_twice<F: Float>(a: F, b: F) -> F {
+    let x = mul_add::<F>(a, b, F::new(1.0));
+    mul_add::<F>(x, b, F::new(1.0))
+}
+
+#[cube(launch)]
+pub fn kernel_function_call<F: Float>(input: &Array<F>, output: &mut Array<F>) {
+    if ABSOLUTE_POS < output.len() {
+        let value = input[ABSOLUTE_POS];
+        output[ABSOLUTE_POS] =
+            mul_add::<F>(value, F::new(2.0), F::new(1.0)) + mul_add::<F>(value, value, value);
+    }
+}
+
+#[cube(launch)]
+pub fn kernel_function_builtin<F: Float>(output: &mut Array<F>) {
+    output[ABSOLUTE_POS] = scaled_unit_pos::<F>(F::new(3.0));
+}
+
+#[cube(launch)]
+pub fn kernel_function_return<F: Float>(input: &Array<F>, output: &mut Array<F>) {
+    if ABSOLUTE_POS < output.len() {
+        let value = input[ABSOLUTE_POS];
+        output[ABSOLUTE_POS] = clamp_to::<F>(value, 2u32) + clamp_to::<F>(value, 4u32);
+    }
+}
+
+#[cube(launch)]
+pub fn kernel_function_nested<F: Float>(input: &Array<F>, output: &mut Array<F>) {
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = mul_add_twice::<F>(input[ABSOLUTE_POS], F::new(2.0));
+    }
+}
+
+pub fn test_function_call<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let input = client.create_from_slice(as_bytes!(F: 1.0, 2.0, 3.0));
+    let output = client.empty(3 * size_of::<F>());
+
+    unsafe {
+        kernel_function_call::launch::<F, R>(
+            &client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new_1d(4),
+            ArrayArg::from_raw_parts::<F>(&input, 3, 1),
+            ArrayArg::from_raw_parts::<F>(&output, 3, 1),
+        )
+        .unwrap();
+    }
+
+    let actual = client.read_one(output);
+    let actual = F::from_bytes(&actual);
+
+    assert_eq!(actual, as_type!(F: 5.0, 11.0, 19.0));
+}
+
+pub fn test_function_builtin<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let output = client.empty(4 * size_of::<F>());
+
+    unsafe {
+        kernel_function_builtin::launch::<F, R>(
+            &client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new_1d(4),
+            ArrayArg::from_raw_parts::<F>(&output, 4, 1),
+        )
+        .unwrap();
+    }
+
+    let actual = client.read_one(output);
+    let actual = F::from_bytes(&actual);
+
+    assert_eq!(actual, as_type!(F: 0.0, 3.0, 6.0, 9.0));
+}
+
+pub fn test_function_return<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let input = client.create_from_slice(as_bytes!(F: 1.0, 3.0, 5.0));
+    let output = client.empty(3 * size_of::<F>());
+
+    unsafe {
+        kernel_function_return::launch::<F, R>(
+            &client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new_1d(4),
+            ArrayArg::from_raw_parts::<F>(&input, 3, 1),
+            ArrayArg::from_raw_parts::<F>(&output, 3, 1),
+        )
+        .unwrap();
+    }
+
+    let actual = client.read_one(output);
+    let actual = F::from_bytes(&actual);
+
+    assert_eq!(actual, as_type!(F: 2.0, 5.0, 6.0));
+}
+
+pub fn test_function_nested<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let input = client.create_from_slice(as_bytes!(F: 1.0, 2.0));
+    let output = client.empty(2 * size_of::<F>());
+
+    unsafe {
+        kernel_function_nested::launch::<F, R>(
+            &client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new_1d(2),
+            ArrayArg::from_raw_parts::<F>(&input, 2, 1),
+            ArrayArg::from_raw_parts::<F>(&output, 2, 1),
+        )
+        .unwrap();
+    }
+
+    let actual = client.read_one(output);
+    let actual = F::from_bytes(&actual);
+
+    assert_eq!(actual, as_type!(F: 7.0, 11.0));
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_function {
+    () => {
+        use super::*;
+
+        #[test]
+        fn test_function_call() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::function::test_function_call::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+
+        #[test]
+        fn test_function_builtin() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::function::test_function_builtin::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+
+        #[test]
+        fn test_function_return() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::function::test_function_return::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+
+        #[test]
+        fn test_function_nested() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::function::test_function_nested::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+    };
+}
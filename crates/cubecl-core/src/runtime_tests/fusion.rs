@@ -0,0 +1,324 @@
+use crate::{self as cubecl};
+use cubecl::prelude::*;
+use cubecl_runtime::server::Handle;
+
+#[cube(launch, fusable)]
+pub fn fusion_scale<F: Float>(input: &Array<F>, output: &mut Array<F>, factor: u32) {
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = input[ABSOLUTE_POS] * F::cast_from(factor);
+    }
+}
+
+#[cube(launch, fusable)]
+pub fn fusion_add<F: Float>(lhs: &Array<F>, rhs: &Array<F>, output: &mut Array<F>) {
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = lhs[ABSOLUTE_POS] + rhs[ABSOLUTE_POS];
+    }
+}
+
+#[cube(launch, fusable)]
+pub fn fusion_repeat_add<F: Float>(input: &Array<F>, output: &mut Array<F>, times: usize) {
+    if ABSOLUTE_POS < output.len() {
+        for _ in 0..times {
+            output[ABSOLUTE_POS] += input[ABSOLUTE_POS];
+        }
+    }
+}
+
+#[cube(launch, fusable)]
+pub fn fusion_swap_pairs<F: Float>(input: &Array<F>, output: &mut Array<F>) {
+    let pos = ABSOLUTE_POS * 2;
+    if pos + 1 < output.len() {
+        output[pos] = input[pos + 1];
+        output[pos + 1] = input[pos];
+    }
+}
+
+#[cube(launch, fusable)]
+pub fn fusion_invalid<F: Float>(output: &mut Array<F>) {
+    if ABSOLUTE_POS < output.len() {
+        // Loops containing `continue` can't be unrolled, so the kernel fails to compile.
+        #[unroll]
+        for i in 0..2u32 {
+            if i == 0 {
+                continue;
+            }
+            output[ABSOLUTE_POS] = F::new(1.0);
+        }
+    }
+}
+
+fn launch_scale<R: Runtime, F: Float + CubeElement>(
+    client: &ComputeClient<R>,
+    input: &Handle,
+    output: &Handle,
+    len: usize,
+    factor: u32,
+) {
+    unsafe {
+        fusion_scale::launch::<F, R>(
+            client,
+            CubeCount::Static(len as u32 / 32, 1, 1),
+            CubeDim::new_1d(32),
+            ArrayArg::from_raw_parts::<F>(input, len, 1),
+            ArrayArg::from_raw_parts::<F>(output, len, 1),
+            ScalarArg::new(factor),
+        )
+        .unwrap();
+    }
+}
+
+fn launch_add<R: Runtime, F: Float + CubeElement>(
+    client: &ComputeClient<R>,
+    lhs: &Handle,
+    rhs: &Handle,
+    output: &Handle,
+    len: usize,
+) {
+    unsafe {
+        fusion_add::launch::<F, R>(
+            client,
+            CubeCount::Static(len as u32 / 32, 1, 1),
+            CubeDim::new_1d(32),
+            ArrayArg::from_raw_parts::<F>(lhs, len, 1),
+            ArrayArg::from_raw_parts::<F>(rhs, len, 1),
+            ArrayArg::from_raw_parts::<F>(output, len, 1),
+        )
+        .unwrap();
+    }
+}
+
+fn launch_repeat_add<R: Runtime, F: Float + CubeElement>(
+    client: &ComputeClient<R>,
+    input: &Handle,
+    output: &Handle,
+    len: usize,
+    times: usize,
+) {
+    unsafe {
+        fusion_repeat_add::launch::<F, R>(
+            client,
+            CubeCount::Static(len as u32 / 32, 1, 1),
+            CubeDim::new_1d(32),
+            ArrayArg::from_raw_parts::<F>(input, len, 1),
+            ArrayArg::from_raw_parts::<F>(output, len, 1),
+            ScalarArg::new(times),
+        )
+        .unwrap();
+    }
+}
+
+fn launch_swap_pairs<R: Runtime, F: Float + CubeElement>(
+    client: &ComputeClient<R>,
+    input: &Handle,
+    output: &Handle,
+    len: usize,
+) {
+    unsafe {
+        fusion_swap_pairs::launch::<F, R>(
+            client,
+            CubeCount::Static(len as u32 / 32, 1, 1),
+            CubeDim::new_1d(32),
+            ArrayArg::from_raw_parts::<F>(input, len, 1),
+            ArrayArg::from_raw_parts::<F>(output, len, 1),
+        )
+        .unwrap();
+    }
+}
+
+pub fn test_fusion_intermediate<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let len = 64;
+    let values: Vec<F> = (0..len).map(|i| F::new(i as f32)).collect();
+    let input = client.create_from_slice(F::as_bytes(&values));
+    let output = client.empty(len * size_of::<F>());
+
+    client.enable_fusion();
+    let intermediate = client.empty(len * size_of::<F>());
+    launch_scale::<R, F>(&client, &input, &intermediate, len, 2);
+    launch_scale::<R, F>(&client, &intermediate, &output, len, 3);
+    core::mem::drop(intermediate);
+    launch_add::<R, F>(&client, &input, &output, &output, len);
+    client.flush_fusion().unwrap();
+    client.disable_fusion().unwrap();
+
+    let actual = client.read_one(output);
+    let expected: Vec<F> = (0..len).map(|i| F::new(i as f32 * 7.0)).collect();
+    assert_eq!(F::from_bytes(&actual), expected.as_slice());
+}
+
+pub fn test_fusion_kept_buffers<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let len = 64;
+    let values: Vec<F> = (0..len).map(|i| F::new(i as f32)).collect();
+    let input = client.create_from_slice(F::as_bytes(&values));
+    let intermediate = client.empty(len * size_of::<F>());
+    let output = client.empty(len * size_of::<F>());
+
+    client.enable_fusion();
+    launch_scale::<R, F>(&client, &input, &intermediate, len, 2);
+    launch_add::<R, F>(&client, &intermediate, &input, &output, len);
+    client.disable_fusion().unwrap();
+
+    let actual = client.read_one(intermediate);
+    let expected: Vec<F> = (0..len).map(|i| F::new(i as f32 * 2.0)).collect();
+    assert_eq!(F::from_bytes(&actual), expected.as_slice());
+
+    let actual = client.read_one(output);
+    let expected: Vec<F> = (0..len).map(|i| F::new(i as f32 * 3.0)).collect();
+    assert_eq!(F::from_bytes(&actual), expected.as_slice());
+}
+
+pub fn test_fusion_read_flushes<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let len = 32;
+    let values: Vec<F> = (0..len).map(|i| F::new(i as f32)).collect();
+    let input = client.create_from_slice(F::as_bytes(&values));
+    let output = client.empty(len * size_of::<F>());
+
+    client.enable_fusion();
+    launch_scale::<R, F>(&client, &input, &output, len, 5);
+
+    let actual = client.read_one(output);
+    let expected: Vec<F> = (0..len).map(|i| F::new(i as f32 * 5.0)).collect();
+    assert_eq!(F::from_bytes(&actual), expected.as_slice());
+    client.disable_fusion().unwrap();
+}
+
+pub fn test_fusion_looped<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let len = 64;
+    let values: Vec<F> = (0..len).map(|i| F::new(i as f32)).collect();
+    let zeros: Vec<F> = (0..len).map(|_| F::new(0.0)).collect();
+    let input = client.create_from_slice(F::as_bytes(&values));
+    let output = client.empty(len * size_of::<F>());
+
+    client.enable_fusion();
+    let intermediate = client.create_from_slice(F::as_bytes(&zeros));
+    launch_repeat_add::<R, F>(&client, &input, &intermediate, len, 3);
+    launch_scale::<R, F>(&client, &intermediate, &output, len, 2);
+    core::mem::drop(intermediate);
+    client.disable_fusion().unwrap();
+
+    let actual = client.read_one(output);
+    let expected: Vec<F> = (0..len).map(|i| F::new(i as f32 * 6.0)).collect();
+    assert_eq!(F::from_bytes(&actual), expected.as_slice());
+}
+
+pub fn test_fusion_many_elements_per_unit<R: Runtime, F: Float + CubeElement>(
+    client: ComputeClient<R>,
+) {
+    let len = 64;
+    let values: Vec<F> = (0..len).map(|i| F::new(i as f32)).collect();
+    let input = client.create_from_slice(F::as_bytes(&values));
+    let output = client.empty(len * size_of::<F>());
+
+    client.enable_fusion();
+    let intermediate = client.empty(len * size_of::<F>());
+    launch_scale::<R, F>(&client, &input, &intermediate, len, 2);
+    launch_swap_pairs::<R, F>(&client, &intermediate, &output, len);
+    core::mem::drop(intermediate);
+    client.disable_fusion().unwrap();
+
+    let actual = client.read_one(output);
+    let expected: Vec<F> = (0..len).map(|i| F::new((i ^ 1) as f32 * 2.0)).collect();
+    assert_eq!(F::from_bytes(&actual), expected.as_slice());
+}
+
+pub fn test_fusion_other_stream_read<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let other = client.stream();
+    let len = 32;
+    let values: Vec<F> = (0..len).map(|i| F::new(i as f32)).collect();
+    let input = client.create_from_slice(F::as_bytes(&values));
+    let output = client.empty(len * size_of::<F>());
+
+    client.enable_fusion();
+    launch_scale::<R, F>(&client, &input, &output, len, 5);
+
+    let actual = other.read_one(output);
+    let expected: Vec<F> = (0..len).map(|i| F::new(i as f32 * 5.0)).collect();
+    assert_eq!(F::from_bytes(&actual), expected.as_slice());
+    client.disable_fusion().unwrap();
+}
+
+pub fn test_fusion_deferred_error<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let len = 32;
+    let output = client.empty(len * size_of::<F>());
+
+    client.enable_fusion();
+    // The launch is deferred, so its error is only known once it's flushed.
+    fusion_invalid::launch::<F, R>(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(len as u32),
+        unsafe { ArrayArg::from_raw_parts::<F>(&output, len, 1) },
+    )
+    .unwrap();
+
+    client.flush();
+    assert!(client.try_flush().is_err());
+    assert!(client.try_flush().is_ok());
+    client.disable_fusion().unwrap();
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_fusion {
+    () => {
+        use super::*;
+
+        #[test]
+        fn test_fusion_intermediate() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::fusion::test_fusion_intermediate::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+
+        #[test]
+        fn test_fusion_kept_buffers() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::fusion::test_fusion_kept_buffers::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+
+        #[test]
+        fn test_fusion_read_flushes() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::fusion::test_fusion_read_flushes::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+
+        #[test]
+        fn test_fusion_looped() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::fusion::test_fusion_looped::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+
+        #[test]
+        fn test_fusion_many_elements_per_unit() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::fusion::test_fusion_many_elements_per_unit::<
+                TestRuntime,
+                FloatType,
+            >(client);
+        }
+
+        #[test]
+        fn test_fusion_other_stream_read() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::fusion::test_fusion_other_stream_read::<
+                TestRuntime,
+                FloatType,
+            >(client);
+        }
+
+        #[test]
+        fn test_fusion_deferred_error() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::fusion::test_fusion_deferred_error::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+    };
+}
@@ -0,0 +1,138 @@
+use crate::{self as cubecl};
+use cubecl::prelude::*;
+use cubecl_runtime::server::Handle;
+
+#[cube(launch)]
+pub fn graph_scale<F: Float>(input: &Array<F>, output: &mut Array<F>, factor: u32) {
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = input[ABSOLUTE_POS] * F::cast_from(factor);
+    }
+}
+
+fn launch_scale<R: Runtime, F: Float + CubeElement>(
+    client: &ComputeClient<R>,
+    input: &Handle,
+    output: &Handle,
+    len: usize,
+    factor: u32,
+) {
+    unsafe {
+        graph_scale::launch::<F, R>(
+            client,
+            CubeCount::Static(len as u32 / 32, 1, 1),
+            CubeDim::new_1d(32),
+            ArrayArg::from_raw_parts::<F>(input, len, 1),
+            ArrayArg::from_raw_parts::<F>(output, len, 1),
+            ScalarArg::new(factor),
+        )
+        .unwrap();
+    }
+}
+
+pub fn test_launch_graph_replay<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let len = 64;
+    let values: Vec<F> = (0..len).map(|i| F::new(i as f32)).collect();
+    let input = client.create_from_slice(F::as_bytes(&values));
+    let intermediate = client.empty(len * size_of::<F>());
+    let output = client.empty(len * size_of::<F>());
+
+    let ((), graph) = client
+        .capture(|| {
+            launch_scale::<R, F>(&client, &input, &intermediate, len, 2);
+            launch_scale::<R, F>(&client, &intermediate, &output, len, 3);
+        })
+        .unwrap();
+    assert_eq!(graph.len(), 2);
+
+    let actual = client.read_one(output.clone());
+    let expected: Vec<F> = (0..len).map(|i| F::new(i as f32 * 6.0)).collect();
+    assert_eq!(F::from_bytes(&actual), expected.as_slice());
+
+    let output_replay = client.empty(len * size_of::<F>());
+    client.replay(&graph).unwrap();
+
+    let mut graph = graph;
+    assert_eq!(graph.replace_buffer(&output, &output_replay), 1);
+    graph.launches_mut()[1].set_scalar(u32::as_type_native_unchecked(), 0, 5u32);
+    client.replay(&graph).unwrap();
+
+    let actual = client.read_one(output);
+    assert_eq!(F::from_bytes(&actual), expected.as_slice());
+
+    let actual = client.read_one(output_replay);
+    let expected: Vec<F> = (0..len).map(|i| F::new(i as f32 * 10.0)).collect();
+    assert_eq!(F::from_bytes(&actual), expected.as_slice());
+}
+
+pub fn test_launch_graph_stream<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let other = client.stream();
+    let len = 32;
+    let values: Vec<F> = (0..len).map(|i| F::new(i as f32)).collect();
+    let input = client.create_from_slice(F::as_bytes(&values));
+    let output = client.empty(len * size_of::<F>());
+
+    let ((), graph) = client
+        .capture(|| {
+            launch_scale::<R, F>(&other, &input, &output, len, 2);
+            launch_scale::<R, F>(&client, &input, &output, len, 4);
+        })
+        .unwrap();
+
+    assert_eq!(graph.len(), 1);
+}
+
+pub fn test_launch_graph_capture_panic<R: Runtime, F: Float + CubeElement>(
+    client: ComputeClient<R>,
+) {
+    let len = 32;
+    let values: Vec<F> = (0..len).map(|i| F::new(i as f32)).collect();
+    let input = client.create_from_slice(F::as_bytes(&values));
+    let output = client.empty(len * size_of::<F>());
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        client.capture(|| {
+            launch_scale::<R, F>(&client, &input, &output, len, 2);
+            panic!("Failed while capturing");
+        })
+    }));
+    assert!(result.is_err());
+
+    // The stream isn't left being captured by the failed capture.
+    let ((), graph) = client
+        .capture(|| launch_scale::<R, F>(&client, &input, &output, len, 3))
+        .unwrap();
+    assert_eq!(graph.len(), 1);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_graph {
+    () => {
+        use super::*;
+
+        #[test]
+        fn test_launch_graph_replay() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::graph::test_launch_graph_replay::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+
+        #[test]
+        fn test_launch_graph_stream() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::graph::test_launch_graph_stream::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+
+        #[test]
+        fn test_launch_graph_capture_panic() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::graph::test_launch_graph_capture_panic::<
+                TestRuntime,
+                FloatType,
+            >(client);
+        }
+    };
+}
@@ -0,0 +1,192 @@
+use crate::{self as cubecl};
+use core::fmt::Debug;
+use cubecl::{CubeScalar, prelude::*};
+use cubecl_ir::features::TypeUsage;
+
+const NUM_OPS: usize = 16;
+
+#[cube(launch)]
+pub fn kernel_int64<I: Int + CubeScalar>(
+    lhs: &Array<Line<I>>,
+    rhs: &Array<Line<I>>,
+    scalar: I,
+    output: &mut Array<Line<I>>,
+) {
+    let pos = ABSOLUTE_POS;
+    let len = lhs.len();
+    if pos < len {
+        let a = lhs[pos];
+        let b = rhs[pos];
+        output[pos] = a + b;
+        output[len + pos] = a - b;
+        output[2 * len + pos] = a * b;
+        output[3 * len + pos] = a / b;
+        output[4 * len + pos] = Remainder::rem(a, b);
+        output[5 * len + pos] = Max::max(a, b);
+        output[6 * len + pos] = Min::min(a, b);
+        output[7 * len + pos] = (a << Line::new(I::new(13))) ^ b;
+        output[8 * len + pos] = a >> Line::new(I::new(37));
+        output[9 * len + pos] = Line::cast_from(a < b);
+        output[10 * len + pos] = Line::cast_from(Line::count_ones(a));
+        output[11 * len + pos] = a + Line::new(scalar);
+        output[12 * len + pos] = Line::cast_from(Line::<f32>::cast_from(b));
+        output[13 * len + pos] = Line::cast_from(Line::<u32>::cast_from(a));
+        output[14 * len + pos] = Line::cast_from(Line::<i32>::cast_from(a));
+        output[15 * len + pos] = Line::cast_from(a == b);
+    }
+}
+
+fn supported<R: Runtime, I: Int>(client: &ComputeClient<R>) -> bool {
+    I::supported_uses(client).is_superset(TypeUsage::Arithmetic | TypeUsage::Buffer)
+}
+
+/// Checks the operations of [`kernel_int64`] on values using both words of 64-bit integers,
+/// which exercises the emulation on targets without native support.
+pub fn test_int64<R: Runtime, I: Int + CubeScalar + PartialEq + Debug>(
+    client: ComputeClient<R>,
+    lhs: &[I],
+    rhs: &[I],
+    scalar: I,
+    reference: impl Fn(I, I, I) -> [I; NUM_OPS],
+) {
+    if !supported::<R, I>(&client) {
+        println!("Unsupported, skipping");
+        return;
+    }
+
+    let num_elems = lhs.len();
+    let mut expected = vec![lhs[0]; num_elems * NUM_OPS];
+    for i in 0..num_elems {
+        for (op, value) in reference(lhs[i], rhs[i], scalar).into_iter().enumerate() {
+            expected[op * num_elems + i] = value;
+        }
+    }
+
+    for line_size in [1, 2, 4] {
+        let lhs_handle = client.create_from_slice(I::as_bytes(lhs));
+        let rhs_handle = client.create_from_slice(I::as_bytes(rhs));
+        let output = client.empty(num_elems * NUM_OPS * size_of::<I>());
+
+        kernel_int64::launch::<I, R>(
+            &client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new_1d((num_elems / line_size) as u32),
+            unsafe { ArrayArg::from_raw_parts::<I>(&lhs_handle, num_elems, line_size) },
+            unsafe { ArrayArg::from_raw_parts::<I>(&rhs_handle, num_elems, line_size) },
+            ScalarArg::new(scalar),
+            unsafe { ArrayArg::from_raw_parts::<I>(&output, num_elems * NUM_OPS, line_size) },
+        )
+        .unwrap();
+
+        let actual = client.read_one(output);
+        let actual = I::from_bytes(&actual);
+        for op in 0..NUM_OPS {
+            let range = op * num_elems..(op + 1) * num_elems;
+            assert_eq!(
+                &actual[range.clone()],
+                &expected[range],
+                "op {op}, line size {line_size}"
+            );
+        }
+    }
+}
+
+pub fn test_int64_u64<R: Runtime>(client: ComputeClient<R>) {
+    let lhs = [
+        0x1_2345_6789,
+        0xFFFF_FFFF,
+        0xFFFF_FFFF_FFFF_FFFF,
+        7,
+        0x8000_0000_0000_0001,
+        0x0000_0001_0000_0000,
+        123_456_789_012_345,
+        42,
+    ];
+    let rhs = [
+        3,
+        1,
+        0x1_0000_0003,
+        0xFFFF_0000_0000,
+        0xFFFF,
+        2,
+        987_654_321,
+        42,
+    ];
+    test_int64::<R, u64>(client, &lhs, &rhs, 0xFFFF_FFFF, |a, b, scalar| {
+        [
+            a.wrapping_add(b),
+            a.wrapping_sub(b),
+            a.wrapping_mul(b),
+            a / b,
+            a % b,
+            Ord::max(a, b),
+            Ord::min(a, b),
+            (a << 13) ^ b,
+            a >> 37,
+            (a < b) as u64,
+            a.count_ones() as u64,
+            a.wrapping_add(scalar),
+            b as f32 as u64,
+            a as u32 as u64,
+            a as i32 as u64,
+            (a == b) as u64,
+        ]
+    });
+}
+
+pub fn test_int64_i64<R: Runtime>(client: ComputeClient<R>) {
+    let lhs = [
+        0x1_2345_6789,
+        -0xFFFF_FFFF,
+        i64::MAX,
+        -7,
+        i64::MIN + 1,
+        -0x0000_0001_0000_0000,
+        123_456_789_012_345,
+        -42,
+    ];
+    let rhs = [3, -1, 0x1_0000_0003, 2, -0xFFFF, 5, -987_654, -42];
+    test_int64::<R, i64>(client, &lhs, &rhs, -0xFFFF_FFFF, |a, b, scalar| {
+        [
+            a.wrapping_add(b),
+            a.wrapping_sub(b),
+            a.wrapping_mul(b),
+            a / b,
+            match a % b {
+                r if r != 0 && (r < 0) != (b < 0) => r + b,
+                r => r,
+            },
+            Ord::max(a, b),
+            Ord::min(a, b),
+            (a << 13) ^ b,
+            a >> 37,
+            (a < b) as i64,
+            a.count_ones() as i64,
+            a.wrapping_add(scalar),
+            b as f32 as i64,
+            a as u32 as i64,
+            a as i32 as i64,
+            (a == b) as i64,
+        ]
+    });
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_int64 {
+    () => {
+        use super::*;
+
+        #[test]
+        fn test_int64_u64() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::int64::test_int64_u64::<TestRuntime>(client);
+        }
+
+        #[test]
+        fn test_int64_i64() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::int64::test_int64_i64::<TestRuntime>(client);
+        }
+    };
+}
@@ -9,29 +9,39 @@ pub mod comparison;
 pub mod const_match;
 pub mod constants;
 pub mod debug;
+pub mod df64;
 pub mod different_rank;
+pub mod dynamic_shared_memory;
 pub mod enums;
 pub mod file;
+pub mod function;
+pub mod fusion;
+pub mod graph;
 pub mod index;
+pub mod int64;
 pub mod launch;
 pub mod line;
 pub mod metadata;
 pub mod minifloat;
+pub mod narrow_storage;
 pub mod numeric;
 pub mod plane;
 pub mod properties;
 pub mod saturating;
 pub mod sequence;
 pub mod slice;
+pub mod spec_const;
 pub mod stream;
 pub mod synchronization;
 pub mod tensor;
 pub mod tensormap;
+pub mod texture;
 pub mod to_client;
 pub mod topology;
 pub mod traits;
 pub mod unary;
 pub mod unroll;
+pub mod work_distribution;
 
 #[allow(missing_docs)]
 #[macro_export]
@@ -93,6 +103,9 @@ macro_rules! testgen_float {
         cubecl_core::testgen_binary!();
         cubecl_core::testgen_branch!();
         cubecl_core::testgen_different_rank!();
+        cubecl_core::testgen_function!();
+        cubecl_core::testgen_graph!();
+        cubecl_core::testgen_fusion!();
         cubecl_core::testgen_index!();
         cubecl_core::testgen_launch!();
         cubecl_core::testgen_line!();
@@ -144,6 +157,14 @@ macro_rules! testgen_untyped {
         cubecl_core::testgen_debug!();
         cubecl_core::testgen_binary_untyped!();
         cubecl_core::testgen_cluster!();
+        cubecl_core::testgen_texture!();
+        cubecl_core::testgen_dynamic_shared_memory!();
+        cubecl_core::testgen_spec_const!();
+        cubecl_core::testgen_narrow_storage!();
+        cubecl_core::testgen_int64!();
+        cubecl_core::testgen_df64!();
+        cubecl_core::testgen_work_distribution!();
+        cubecl_core::testgen_atomic_untyped!();
 
         cubecl_core::testgen_enums!();
         cubecl_core::testgen_comparison!();
@@ -0,0 +1,175 @@
+use crate::{self as cubecl};
+use core::fmt::Debug;
+use cubecl::prelude::*;
+use cubecl_ir::features::TypeUsage;
+
+#[cube(launch_unchecked)]
+pub fn kernel_narrow_store<N: Numeric>(input: &Array<Line<f32>>, output: &mut Array<Line<N>>) {
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = Line::cast_from(input[ABSOLUTE_POS]);
+    }
+}
+
+#[cube(launch_unchecked)]
+pub fn kernel_narrow_load<N: Numeric>(input: &Array<Line<N>>, output: &mut Array<Line<f32>>) {
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = Line::cast_from(input[ABSOLUTE_POS]);
+    }
+}
+
+fn supported<R: Runtime, N: Numeric>(client: &ComputeClient<R>) -> bool {
+    N::supported_uses(client).is_superset(TypeUsage::Buffer | TypeUsage::Conversion)
+}
+
+/// Round trips values through a buffer of `N`, with neighbouring elements written by different
+/// units so they share words when the storage is packed.
+pub fn test_narrow_storage<R: Runtime, N: Numeric + CubeElement + PartialEq + Debug>(
+    client: ComputeClient<R>,
+    signed: bool,
+) {
+    if !supported::<R, N>(&client) {
+        println!("Unsupported, skipping");
+        return;
+    }
+
+    for line_size in [1, 2, 4] {
+        round_trip::<R, N>(&client, line_size, signed);
+    }
+}
+
+fn round_trip<R: Runtime, N: Numeric + CubeElement + PartialEq + Debug>(
+    client: &ComputeClient<R>,
+    line_size: LineSize,
+    signed: bool,
+) {
+    let num_elems = 64;
+    let offset = if signed { 50 } else { 0 };
+    let values: Vec<i64> = (0..num_elems as i64)
+        .map(|i| (i * 3) % 100 - offset)
+        .collect();
+    let data: Vec<f32> = values.iter().map(|v| *v as f32).collect();
+
+    let input = client.create_from_slice(f32::as_bytes(&data));
+    let narrow = client.empty(num_elems * size_of::<N>());
+    let output = client.empty(num_elems * size_of::<f32>());
+    let num_units = (num_elems / line_size) as u32;
+
+    unsafe {
+        kernel_narrow_store::launch_unchecked::<N, R>(
+            client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new_1d(num_units),
+            ArrayArg::from_raw_parts::<f32>(&input, num_elems, line_size),
+            ArrayArg::from_raw_parts::<N>(&narrow, num_elems, line_size),
+        )
+        .unwrap();
+        kernel_narrow_load::launch_unchecked::<N, R>(
+            client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new_1d(num_units),
+            ArrayArg::from_raw_parts::<N>(&narrow, num_elems, line_size),
+            ArrayArg::from_raw_parts::<f32>(&output, num_elems, line_size),
+        )
+        .unwrap();
+    }
+
+    let actual = client.read_one(narrow);
+    let actual = N::from_bytes(&actual);
+    let expected: Vec<N> = values.iter().map(|v| N::from_int(*v)).collect();
+    assert_eq!(&actual[..num_elems], &expected, "line size {line_size}");
+
+    let actual = client.read_one(output);
+    let actual = f32::from_bytes(&actual);
+    assert_eq!(actual, &data, "line size {line_size}");
+}
+
+/// Casting to `bf16` rounds to the nearest value, ties to even.
+pub fn test_bf16_rounding<R: Runtime>(client: ComputeClient<R>) {
+    if !supported::<R, half::bf16>(&client) {
+        println!("Unsupported, skipping");
+        return;
+    }
+
+    let data = [
+        1.0039062f32,
+        1.0117188,
+        1.0078125,
+        -2.0078125,
+        core::f32::consts::PI,
+        65535.0,
+    ];
+    let expected = data.map(half::bf16::from_f32);
+
+    let input = client.create_from_slice(f32::as_bytes(&data));
+    let narrow = client.empty(data.len() * size_of::<half::bf16>());
+
+    unsafe {
+        kernel_narrow_store::launch_unchecked::<half::bf16, R>(
+            &client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new_1d(data.len() as u32),
+            ArrayArg::from_raw_parts::<f32>(&input, data.len(), 1),
+            ArrayArg::from_raw_parts::<half::bf16>(&narrow, data.len(), 1),
+        )
+        .unwrap();
+    }
+
+    let actual = client.read_one(narrow);
+    let actual = half::bf16::from_bytes(&actual);
+    assert_eq!(&actual[..data.len()], &expected);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_narrow_storage {
+    () => {
+        use super::*;
+
+        #[test]
+        fn test_narrow_storage_u8() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::narrow_storage::test_narrow_storage::<TestRuntime, u8>(
+                client, false,
+            );
+        }
+
+        #[test]
+        fn test_narrow_storage_i8() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::narrow_storage::test_narrow_storage::<TestRuntime, i8>(
+                client, true,
+            );
+        }
+
+        #[test]
+        fn test_narrow_storage_u16() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::narrow_storage::test_narrow_storage::<TestRuntime, u16>(
+                client, false,
+            );
+        }
+
+        #[test]
+        fn test_narrow_storage_i16() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::narrow_storage::test_narrow_storage::<TestRuntime, i16>(
+                client, true,
+            );
+        }
+
+        #[test]
+        fn test_narrow_storage_bf16() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::narrow_storage::test_narrow_storage::<
+                TestRuntime,
+                half::bf16,
+            >(client, true);
+        }
+
+        #[test]
+        fn test_bf16_rounding() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::narrow_storage::test_bf16_rounding::<TestRuntime>(client);
+        }
+    };
+}
@@ -0,0 +1,47 @@
+use crate::{self as cubecl};
+use cubecl::prelude::*;
+use cubecl_runtime::server::Handle;
+
+#[cube(launch)]
+fn spec_const_scale(input: &Array<f32>, output: &mut Array<f32>, #[spec_const] factor: u32) {
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = input[ABSOLUTE_POS] * f32::cast_from(factor);
+    }
+}
+
+fn launch<R: Runtime>(client: &ComputeClient<R>, input: &Handle, factor: u32) -> Vec<f32> {
+    let output = client.empty(4 * core::mem::size_of::<f32>());
+
+    spec_const_scale::launch::<R>(
+        client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(4),
+        unsafe { ArrayArg::from_raw_parts::<f32>(input, 4, 1) },
+        unsafe { ArrayArg::from_raw_parts::<f32>(&output, 4, 1) },
+        SpecConstArg::new(client, factor),
+    )
+    .unwrap();
+
+    f32::from_bytes(&client.read_one(output)).to_vec()
+}
+
+pub fn test_spec_const<R: Runtime>(client: ComputeClient<R>) {
+    let input = client.create_from_slice(f32::as_bytes(&[1.0, 2.0, 3.0, 4.0]));
+
+    assert_eq!(launch(&client, &input, 2), [2.0, 4.0, 6.0, 8.0]);
+    assert_eq!(launch(&client, &input, 3), [3.0, 6.0, 9.0, 12.0]);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_spec_const {
+    () => {
+        use super::*;
+
+        #[test]
+        fn test_spec_const() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::spec_const::test_spec_const::<TestRuntime>(client);
+        }
+    };
+}
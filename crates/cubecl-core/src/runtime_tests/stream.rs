@@ -1,6 +1,7 @@
 use crate::{self as cubecl};
 use cubecl::prelude::*;
 use cubecl_common::stream_id::StreamId;
+use cubecl_runtime::config::GlobalConfig;
 
 #[cube(launch)]
 pub fn big_task<F: Float>(input: &Array<u32>, output: &mut Array<F>, num_loop: usize) {
@@ -54,6 +55,107 @@ pub fn test_stream<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>)
     assert_eq!(actual[0], F::new(1318936000.0));
 }
 
+#[cube(launch)]
+pub fn scale_task<F: Float>(input: &Array<F>, output: &mut Array<F>, factor: u32) {
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = input[ABSOLUTE_POS] * F::cast_from(factor);
+    }
+}
+
+pub fn test_stream_event<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let producer = client.stream();
+    let consumer = client.stream();
+    assert_ne!(producer.stream_id(), consumer.stream_id());
+
+    let len = 256;
+    let input: Vec<F> = (0..len).map(|i| F::new(i as f32)).collect();
+    let input = producer.create_from_slice(F::as_bytes(&input));
+    let intermediate = producer.empty(len * core::mem::size_of::<F>());
+    let output = consumer.empty(len * core::mem::size_of::<F>());
+
+    unsafe {
+        scale_task::launch::<F, R>(
+            &producer,
+            CubeCount::Static(len as u32 / 32, 1, 1),
+            CubeDim::new_1d(32),
+            ArrayArg::from_raw_parts::<F>(&input, len, 1),
+            ArrayArg::from_raw_parts::<F>(&intermediate, len, 1),
+            ScalarArg::new(2),
+        )
+        .unwrap();
+    };
+    let event = producer.record_event().unwrap();
+    assert_eq!(event.stream_id(), producer.stream_id());
+
+    consumer.wait_event(&event).unwrap();
+    unsafe {
+        scale_task::launch::<F, R>(
+            &consumer,
+            CubeCount::Static(len as u32 / 32, 1, 1),
+            CubeDim::new_1d(32),
+            ArrayArg::from_raw_parts::<F>(&intermediate, len, 1),
+            ArrayArg::from_raw_parts::<F>(&output, len, 1),
+            ScalarArg::new(3),
+        )
+        .unwrap();
+    };
+    let done = consumer.record_event().unwrap();
+
+    event.wait().unwrap();
+    assert!(event.query().unwrap());
+    done.wait().unwrap();
+    assert!(done.query().unwrap());
+
+    let actual = consumer.read_one(output);
+    let actual = F::from_bytes(&actual);
+    let expected: Vec<F> = (0..len).map(|i| F::new(i as f32 * 6.0)).collect();
+
+    assert_eq!(actual, expected.as_slice());
+}
+
+#[cube(launch)]
+pub fn increment_task<F: Float>(input: &Array<F>, output: &mut Array<F>) {
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = input[ABSOLUTE_POS] + F::new(1.0);
+    }
+}
+
+pub fn test_stream_aliasing<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    // Past the maximum number of streams, some of the new streams share the same queue.
+    let num_streams = GlobalConfig::get().streaming.max_streams as usize + 2;
+    let streams: Vec<_> = (0..num_streams).map(|_| client.stream()).collect();
+
+    let len = 256;
+    let input: Vec<F> = (0..len).map(|i| F::new(i as f32)).collect();
+    let mut current = streams[0].create_from_slice(F::as_bytes(&input));
+    let mut event = streams[0].record_event().unwrap();
+
+    // Chain the streams, so each one waits on a stream it may be sharing its queue with.
+    for stream in streams.iter() {
+        stream.wait_event(&event).unwrap();
+        let output = stream.empty(len * core::mem::size_of::<F>());
+        unsafe {
+            increment_task::launch::<F, R>(
+                stream,
+                CubeCount::Static(len as u32 / 32, 1, 1),
+                CubeDim::new_1d(32),
+                ArrayArg::from_raw_parts::<F>(&current, len, 1),
+                ArrayArg::from_raw_parts::<F>(&output, len, 1),
+            )
+            .unwrap();
+        };
+        event = stream.record_event().unwrap();
+        current = output;
+    }
+
+    event.wait().unwrap();
+    let actual = client.read_one(current);
+    let actual = F::from_bytes(&actual);
+    let expected: Vec<F> = (0..len).map(|i| F::new((i + num_streams) as f32)).collect();
+
+    assert_eq!(actual, expected.as_slice());
+}
+
 #[allow(missing_docs)]
 #[macro_export]
 macro_rules! testgen_stream {
@@ -66,5 +168,19 @@ macro_rules! testgen_stream {
             let client = TestRuntime::client(&Default::default());
             cubecl_core::runtime_tests::stream::test_stream::<TestRuntime, FloatType>(client);
         }
+
+        #[test]
+        fn test_stream_event() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::stream::test_stream_event::<TestRuntime, FloatType>(client);
+        }
+
+        #[test]
+        fn test_stream_aliasing() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::stream::test_stream_aliasing::<TestRuntime, FloatType>(
+                client,
+            );
+        }
     };
 }
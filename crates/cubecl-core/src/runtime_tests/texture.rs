@@ -0,0 +1,136 @@
+use crate::{self as cubecl};
+use cubecl::prelude::*;
+
+const WIDTH: usize = 4;
+const HEIGHT: usize = 4;
+
+#[cube(launch)]
+fn texture_load(texture: &Texture2D<f32>, output: &mut Array<Line<f32>>) {
+    let pos = UNIT_POS_Y * CUBE_DIM_X + UNIT_POS_X;
+    output[pos as usize] = texture.load((UNIT_POS_X, UNIT_POS_Y));
+}
+
+#[cube(launch)]
+fn texture_sample(
+    texture: &Texture2D<f32>,
+    output: &mut Array<Line<f32>>,
+    offset: f32,
+    #[comptime] sampler: Sampler,
+) {
+    let u = (f32::cast_from(UNIT_POS_X) + offset) / f32::cast_from(CUBE_DIM_X);
+    let v = (f32::cast_from(UNIT_POS_Y) + offset) / f32::cast_from(CUBE_DIM_Y);
+    let pos = UNIT_POS_Y * CUBE_DIM_X + UNIT_POS_X;
+    output[pos as usize] = texture.sample((u, v), sampler);
+}
+
+fn texels(channels: usize) -> Vec<f32> {
+    (0..WIDTH * HEIGHT * channels).map(|i| i as f32).collect()
+}
+
+fn launch_sample<R: Runtime>(client: &ComputeClient<R>, offset: f32, sampler: Sampler) -> Vec<f32> {
+    let texels = texels(1);
+    let handle = client.create_from_slice(f32::as_bytes(&texels));
+    let output = client.empty(texels.len() * core::mem::size_of::<f32>());
+
+    texture_sample::launch::<R>(
+        client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_2d(WIDTH as u32, HEIGHT as u32),
+        TextureArg::new_2d(
+            client,
+            &handle,
+            [WIDTH, HEIGHT],
+            1,
+            f32::as_type_native_unchecked(),
+        ),
+        unsafe { ArrayArg::from_raw_parts::<f32>(&output, texels.len(), 1) },
+        ScalarArg::new(offset),
+        sampler,
+    )
+    .unwrap();
+
+    f32::from_bytes(&client.read_one(output)).to_vec()
+}
+
+pub fn test_texture_load<R: Runtime>(client: ComputeClient<R>) {
+    let texels = texels(4);
+    let handle = client.create_from_slice(f32::as_bytes(&texels));
+    let output = client.empty(texels.len() * core::mem::size_of::<f32>());
+
+    texture_load::launch::<R>(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_2d(WIDTH as u32, HEIGHT as u32),
+        TextureArg::new_2d(
+            &client,
+            &handle,
+            [WIDTH, HEIGHT],
+            4,
+            f32::as_type_native_unchecked(),
+        ),
+        unsafe { ArrayArg::from_raw_parts::<f32>(&output, WIDTH * HEIGHT, 4) },
+    )
+    .unwrap();
+
+    let actual = client.read_one(output);
+    assert_eq!(f32::from_bytes(&actual), texels);
+}
+
+pub fn test_texture_sample_nearest<R: Runtime>(client: ComputeClient<R>) {
+    let actual = launch_sample(&client, 0.5, Sampler::nearest());
+    assert_eq!(actual, texels(1));
+}
+
+pub fn test_texture_sample_repeat<R: Runtime>(client: ComputeClient<R>) {
+    let sampler = Sampler::nearest().with_address(AddressMode::Repeat);
+    let actual = launch_sample(&client, WIDTH as f32 + 0.5, sampler);
+    assert_eq!(actual, texels(1));
+}
+
+pub fn test_texture_sample_linear<R: Runtime>(client: ComputeClient<R>) {
+    let actual = launch_sample(&client, 0.5, Sampler::linear());
+    assert_eq!(actual, texels(1));
+
+    // Sampling on the corner between four texels averages them, with the last row and column
+    // clamped to the edge.
+    let actual = launch_sample(&client, 1.0, Sampler::linear());
+    let texel =
+        |x: usize, y: usize| (Ord::min(y, HEIGHT - 1) * WIDTH + Ord::min(x, WIDTH - 1)) as f32;
+    let expected = (0..HEIGHT)
+        .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+        .map(|(x, y)| (texel(x, y) + texel(x + 1, y) + texel(x, y + 1) + texel(x + 1, y + 1)) / 4.0)
+        .collect::<Vec<_>>();
+    assert_eq!(actual, expected);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_texture {
+    () => {
+        use super::*;
+
+        #[test]
+        fn test_texture_load() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::texture::test_texture_load::<TestRuntime>(client);
+        }
+
+        #[test]
+        fn test_texture_sample_nearest() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::texture::test_texture_sample_nearest::<TestRuntime>(client);
+        }
+
+        #[test]
+        fn test_texture_sample_repeat() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::texture::test_texture_sample_repeat::<TestRuntime>(client);
+        }
+
+        #[test]
+        fn test_texture_sample_linear() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::texture::test_texture_sample_linear::<TestRuntime>(client);
+        }
+    };
+}
@@ -0,0 +1,217 @@
+use crate::{self as cubecl};
+
+use cubecl::prelude::*;
+use cubecl_ir::features::TypeUsage;
+use cubecl_runtime::kernel::KernelResourceUsage;
+
+const NUM_ITEMS: usize = 1000;
+const NUM_CUBES: u32 = 3;
+const CUBE_SIZE: u32 = 32;
+const BLOCK_SIZE: usize = 7;
+
+#[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
+pub enum Distribution {
+    GridStride,
+    BlockCyclic,
+    WorkQueue,
+}
+
+#[cube]
+fn visit(output: &Array<Atomic<u32>>, item: usize) {
+    Atomic::add(&output[item], 1u32);
+}
+
+#[cube(launch)]
+pub fn kernel_work_units(
+    output: &mut Array<Atomic<u32>>,
+    counter: &mut Array<Atomic<u32>>,
+    #[comptime] distribution: Distribution,
+) {
+    let num_items = output.len();
+    match distribution {
+        Distribution::GridStride => {
+            for item in GridStride::units(num_items) {
+                visit(output, item);
+            }
+        }
+        Distribution::BlockCyclic => {
+            for item in BlockCyclic::units(num_items, BLOCK_SIZE) {
+                visit(output, item);
+            }
+        }
+        Distribution::WorkQueue => {
+            for item in WorkQueue::units(&counter[0], num_items) {
+                visit(output, item);
+            }
+        }
+    }
+}
+
+#[cube(launch)]
+pub fn kernel_work_cubes(
+    output: &mut Array<Atomic<u32>>,
+    counter: &mut Array<Atomic<u32>>,
+    #[comptime] distribution: Distribution,
+) {
+    let num_items = output.len();
+    match distribution {
+        Distribution::GridStride => {
+            for item in GridStride::cubes(num_items) {
+                visit(output, item);
+            }
+        }
+        Distribution::BlockCyclic => {
+            for item in BlockCyclic::cubes(num_items, BLOCK_SIZE) {
+                visit(output, item);
+            }
+        }
+        Distribution::WorkQueue => {
+            for item in WorkQueue::cubes(&counter[0], num_items) {
+                visit(output, item);
+            }
+        }
+    }
+}
+
+#[cube(launch)]
+pub fn kernel_work_position(output: &mut Array<u32>, dim_0: usize, dim_1: usize, dim_2: usize) {
+    let mut shape = Sequence::new();
+    shape.push(dim_0);
+    shape.push(dim_1);
+    shape.push(dim_2);
+
+    for item in GridStride::units(work_len(&shape)) {
+        let position = work_position(item, &shape);
+        output[item] = u32::cast_from(
+            *position.index(0usize) * 100usize
+                + *position.index(1usize) * 10usize
+                + *position.index(2usize),
+        );
+    }
+}
+
+fn supports_atomic_add<R: Runtime>(client: &ComputeClient<R>) -> bool {
+    let elem = u32::as_type_native_unchecked().elem_type();
+    client
+        .properties()
+        .features
+        .atomic_usage(elem)
+        .contains(TypeUsage::AtomicAdd)
+}
+
+/// Checks that every item is visited once per unit of the worker it's assigned to, with fewer
+/// workers than items.
+pub fn test_work_distribution<R: Runtime>(client: ComputeClient<R>, per_cube: bool) {
+    if !supports_atomic_add(&client) {
+        println!("Atomic add not supported - skipped");
+        return;
+    }
+
+    for distribution in [
+        Distribution::GridStride,
+        Distribution::BlockCyclic,
+        Distribution::WorkQueue,
+    ] {
+        let output = client.create_from_slice(u32::as_bytes(&[0; NUM_ITEMS]));
+        let counter = client.create_from_slice(u32::as_bytes(&[0]));
+        let output_arg = unsafe { ArrayArg::from_raw_parts::<u32>(&output, NUM_ITEMS, 1) };
+        let counter_arg = unsafe { ArrayArg::from_raw_parts::<u32>(&counter, 1, 1) };
+        let cube_count = CubeCount::Static(NUM_CUBES, 1, 1);
+        let cube_dim = CubeDim::new_1d(CUBE_SIZE);
+
+        if per_cube {
+            kernel_work_cubes::launch::<R>(
+                &client,
+                cube_count,
+                cube_dim,
+                output_arg,
+                counter_arg,
+                distribution,
+            )
+            .unwrap();
+        } else {
+            kernel_work_units::launch::<R>(
+                &client,
+                cube_count,
+                cube_dim,
+                output_arg,
+                counter_arg,
+                distribution,
+            )
+            .unwrap();
+        }
+
+        let actual = client.read_one(output);
+        let actual = u32::from_bytes(&actual);
+        let expected = if per_cube { CUBE_SIZE } else { 1 };
+        for (item, visits) in actual.iter().enumerate() {
+            assert_eq!(
+                *visits, expected,
+                "{distribution:?}, item {item}: visited {visits} times"
+            );
+        }
+    }
+}
+
+pub fn test_work_position<R: Runtime>(client: ComputeClient<R>) {
+    let shape = [3, 4, 5];
+    let num_items = shape.iter().product::<usize>();
+    let output = client.empty(num_items * size_of::<u32>());
+    let grid = PersistentGrid::units(&client, KernelResourceUsage::default(), num_items);
+
+    kernel_work_position::launch::<R>(
+        &client,
+        grid.cube_count,
+        grid.cube_dim,
+        unsafe { ArrayArg::from_raw_parts::<u32>(&output, num_items, 1) },
+        ScalarArg::new(shape[0]),
+        ScalarArg::new(shape[1]),
+        ScalarArg::new(shape[2]),
+    )
+    .unwrap();
+
+    let actual = client.read_one(output);
+    let actual = u32::from_bytes(&actual);
+    let mut expected = Vec::with_capacity(num_items);
+    for i in 0..shape[0] {
+        for j in 0..shape[1] {
+            for k in 0..shape[2] {
+                expected.push((i * 100 + j * 10 + k) as u32);
+            }
+        }
+    }
+
+    assert_eq!(actual, expected);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_work_distribution {
+    () => {
+        use super::*;
+
+        #[test]
+        fn test_work_distribution_units() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::work_distribution::test_work_distribution::<TestRuntime>(
+                client, false,
+            );
+        }
+
+        #[test]
+        fn test_work_distribution_cubes() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::work_distribution::test_work_distribution::<TestRuntime>(
+                client, true,
+            );
+        }
+
+        #[test]
+        fn test_work_position() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::work_distribution::test_work_position::<TestRuntime>(
+                client,
+            );
+        }
+    };
+}
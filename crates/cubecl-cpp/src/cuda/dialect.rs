@@ -8,6 +8,7 @@ use cubecl_core::{
 use crate::{
     Dialect,
     cuda::{
+        TEXTURE,
         extension::{Fragment, LdMatrix, MmaExecute, MmaExecuteScaled, MmaExtension, StMatrix},
         processors::CudaMmaProcessor,
         ptx::*,
@@ -16,7 +17,7 @@ use crate::{
         self, Binding, Component, DialectBindings, DialectCubeBuiltins, DialectIncludes,
         DialectInstructions, DialectProcessors, DialectTypes, DialectWarpReduceCompiler,
         DialectWmmaCompiler, Elem, FP4Kind, FP6Kind, FP8Kind, Flags, Instruction, Item, ManualMma,
-        Variable, WarpInstruction, unary,
+        TextureBinding, Variable, WarpInstruction, unary,
     },
 };
 
@@ -249,6 +250,9 @@ impl<M: DialectWmmaCompiler<Self>> DialectTypes<Self> for CudaDialect<M> {
         if flags.inst_async_copy {
             writeln!(f, "{COPY_ASYNC}")?;
         }
+        if flags.inst_texture {
+            writeln!(f, "{TEXTURE}")?;
+        }
         Ok(())
     }
 
@@ -333,6 +337,7 @@ impl<M: DialectWmmaCompiler<Self>> DialectBindings<Self> for CudaDialect<M> {
         f: &mut std::fmt::Formatter<'_>,
         kernel_name: &str,
         tensor_maps: &[Binding<Self>],
+        textures: &[TextureBinding],
         buffers: &[Binding<Self>],
         scalars: &[(Elem<Self>, usize)],
         flags: &Flags<Self>,
@@ -354,7 +359,7 @@ extern \"C\" __global__ void __launch_bounds__({})",
         writeln!(f, "{kernel_name} (")?;
         let has_scalars =
             !scalars.is_empty() || (flags.use_grid_constants && flags.static_meta_length > 0);
-        shared::compile_bindings(f, tensor_maps, buffers, has_scalars, flags)?;
+        shared::compile_bindings(f, tensor_maps, textures, buffers, has_scalars, flags)?;
         if flags.use_grid_constants {
             shared::compile_scalars_static(f, scalars, flags)?;
         } else {
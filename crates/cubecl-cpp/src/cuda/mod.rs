@@ -8,3 +8,5 @@ pub mod ptx;
 
 pub use dialect::*;
 use extension::*;
+
+pub const TEXTURE: &str = include_str!("texture.cuh");
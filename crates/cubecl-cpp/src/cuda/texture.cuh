@@ -0,0 +1,6 @@
+// Textures are bound with a texture object per sampler state, indexed like `Sampler::index`, and
+// one reading the texels with unnormalized coordinates for loads.
+struct texture_ref {
+  cudaTextureObject_t texels;
+  cudaTextureObject_t samplers[6];
+};
@@ -9,7 +9,7 @@ use crate::{
     Dialect,
     shared::{
         self, Binding, DialectBindings, DialectCubeBuiltins, DialectIncludes, DialectTypes,
-        DialectWmmaCompiler, Flags, Item, ManualMma,
+        DialectWmmaCompiler, Flags, Item, ManualMma, TextureBinding,
     },
 };
 use crate::{
@@ -20,10 +20,10 @@ use crate::{
     },
 };
 
-use super::Extension;
 use super::arch::AMDArchitecture;
 use super::extension::{WmmaExtension, format_f162bf16, format_max, format_min};
 use super::mma::{WmmaCast, WmmaExecute, WmmaFill, WmmaIntrinsicCompiler, WmmaLoad, WmmaStore};
+use super::{Extension, TEXTURE};
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct HipDialect<M> {
@@ -308,6 +308,13 @@ impl<M: DialectWmmaCompiler<Self>> DialectTypes<Self> for HipDialect<M> {
     fn compile_local_memory_qualifier(_f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         Ok(())
     }
+
+    fn compile_polyfills(f: &mut std::fmt::Formatter<'_>, flags: &Flags<Self>) -> std::fmt::Result {
+        if flags.inst_texture {
+            writeln!(f, "{TEXTURE}")?;
+        }
+        Ok(())
+    }
 }
 
 // Kernel argument bindings
@@ -317,6 +324,7 @@ impl<M: DialectWmmaCompiler<Self>> DialectBindings<Self> for HipDialect<M> {
         f: &mut std::fmt::Formatter<'_>,
         kernel_name: &str,
         tensor_maps: &[Binding<Self>],
+        textures: &[TextureBinding],
         buffers: &[Binding<Self>],
         scalars: &[(Elem<Self>, usize)],
         flags: &Flags<Self>,
@@ -329,7 +337,14 @@ extern \"C\" __global__ void __launch_bounds__({}) {kernel_name}(
 ",
             flags.cube_dim.num_elems()
         )?;
-        shared::compile_bindings::<Self>(f, tensor_maps, buffers, !scalars.is_empty(), flags)?;
+        shared::compile_bindings::<Self>(
+            f,
+            tensor_maps,
+            textures,
+            buffers,
+            !scalars.is_empty(),
+            flags,
+        )?;
         shared::compile_scalars_dynamic::<Self>(f, scalars)?;
         f.write_str("\n)")?;
 
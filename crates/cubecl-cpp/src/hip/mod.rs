@@ -6,3 +6,5 @@ pub mod processors;
 pub use dialect::*;
 use extension::*;
 pub mod mma;
+
+pub const TEXTURE: &str = include_str!("texture.hpp");
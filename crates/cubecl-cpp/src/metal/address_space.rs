@@ -98,7 +98,9 @@ impl<D: Dialect> From<&Variable<D>> for AddressSpace {
                     AddressSpace::Device
                 }
             }
-            Variable::SharedArray(..) => AddressSpace::ThreadGroup,
+            Variable::SharedArray(..) | Variable::DynamicSharedArray(..) => {
+                AddressSpace::ThreadGroup
+            }
             _ => AddressSpace::Thread,
         }
     }
@@ -1,4 +1,4 @@
-use cubecl_core::prelude::Visibility;
+use cubecl_core::{ir::TextureDim, prelude::Visibility};
 
 use crate::{
     Dialect,
@@ -6,7 +6,7 @@ use crate::{
     shared::{Binding, Component, MslComputeKernel, Variable},
 };
 
-pub fn bindings(repr: &MslComputeKernel) -> (Vec<Visibility>, Vec<Visibility>) {
+pub fn bindings(repr: &MslComputeKernel) -> (Vec<Visibility>, Vec<Visibility>, Vec<TextureDim>) {
     let mut bindings: Vec<Visibility> = vec![];
     // must be in the same order as the compilation order: inputs, outputs and named
     for b in repr.buffers.iter() {
@@ -19,7 +19,8 @@ pub fn bindings(repr: &MslComputeKernel) -> (Vec<Visibility>, Vec<Visibility>) {
     for _ in repr.scalars.iter() {
         meta.push(Visibility::Read);
     }
-    (bindings, meta)
+    let textures = repr.textures.iter().map(|texture| texture.dim).collect();
+    (bindings, meta, textures)
 }
 
 pub fn format_global_binding_arg<D: Dialect>(
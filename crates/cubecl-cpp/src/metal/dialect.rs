@@ -11,12 +11,13 @@ use crate::{
         DialectIncludes, DialectInstructions, DialectProcessors, DialectTypes,
         DialectWarpReduceCompiler, DialectWmmaCompiler, Elem, Flags, FmtLeft, Fragment,
         FragmentIdent, FragmentLayout, Instruction, Item, ManualMma, SharedMemory,
-        SupportedMmaCombinations, Variable, WarpInstruction, WmmaInstruction, wmma_api_base,
+        SupportedMmaCombinations, TextureBinding, Variable, WarpInstruction, WmmaInstruction,
+        wmma_api_base,
     },
 };
 use core::panic;
 use cubecl_core::{
-    ir::{self as gpu, features::MmaConfig},
+    ir::{self as gpu, AddressMode, FilterMode, Sampler, features::MmaConfig},
     prelude::{Location, Visibility},
 };
 use std::fmt::Display;
@@ -325,6 +326,30 @@ struct alignas({alignment}) {item} {{"
         write!(f, "thread")
     }
 
+    fn compile_polyfills(f: &mut std::fmt::Formatter<'_>, flags: &Flags<Self>) -> std::fmt::Result {
+        if flags.inst_texture {
+            // Samplers are known when compiling, so the samplers bound with the textures are
+            // left unused.
+            for sampler in Sampler::all() {
+                let filter = match sampler.filter {
+                    FilterMode::Nearest => "nearest",
+                    FilterMode::Linear => "linear",
+                };
+                let address = match sampler.address {
+                    AddressMode::ClampToEdge => "clamp_to_edge",
+                    AddressMode::Repeat => "repeat",
+                    AddressMode::MirrorRepeat => "mirrored_repeat",
+                };
+                writeln!(
+                    f,
+                    "constexpr sampler __sampler_{}(coord::normalized, filter::{filter}, address::{address});",
+                    sampler.index()
+                )?;
+            }
+        }
+        Ok(())
+    }
+
     fn compile_shared_memory_declaration(
         f: &mut std::fmt::Formatter<'_>,
         shared: &SharedMemory<Self>,
@@ -357,6 +382,22 @@ struct alignas({alignment}) {item} {{"
                     "threadgroup {item}& shared_memory_{index} = reinterpret_cast<threadgroup {item}&>(dynamic_shared_mem[{offset}]);"
                 )
             }
+            SharedMemory::Dynamic {
+                index,
+                item,
+                offset,
+                specialized,
+                ..
+            } => {
+                match specialized {
+                    Some(size) => writeln!(f, "// Dynamic shared array, {size} bytes")?,
+                    None => writeln!(f, "// Dynamic shared array, sized at launch")?,
+                }
+                writeln!(
+                    f,
+                    "threadgroup {item}* shared_memory_{index} = reinterpret_cast<threadgroup {item}*>(&dynamic_shared_mem[{offset}]);"
+                )
+            }
         }
     }
 }
@@ -368,6 +409,7 @@ impl DialectBindings<Self> for MslDialect {
         f: &mut std::fmt::Formatter<'_>,
         kernel_name: &str,
         tensor_maps: &[Binding<Self>],
+        textures: &[TextureBinding],
         buffers: &[Binding<Self>],
         scalars: &[(Elem<Self>, usize)],
         flags: &Flags<Self>,
@@ -409,6 +451,29 @@ void {kernel_name}("
             let name = format!("scalars_{elem}");
             format_global_binding_arg(&name, &binding, None, &mut buffer_idx, f)?;
         }
+        // Shared memory sized at launch is a threadgroup buffer, whose length the launcher sets
+        // with `setThreadgroupMemoryLength:atIndex:` to the static size plus the launch size.
+        if flags.op_dynamic_shared_memory {
+            let comma = if buffer_idx > 0 { "," } else { "" };
+            write!(
+                f,
+                "{comma}\n    threadgroup uchar* dynamic_shared_mem [[threadgroup(0)]]"
+            )?;
+        }
+        let mut has_args = buffer_idx > 0 || flags.op_dynamic_shared_memory;
+        for (i, texture) in textures.iter().enumerate() {
+            let comma = if has_args { "," } else { "" };
+            let ty = match texture.dim {
+                gpu::TextureDim::D2 => "texture2d",
+                gpu::TextureDim::D3 => "texture3d",
+            };
+            write!(
+                f,
+                "{comma}\n    {ty}<float> texture_{} [[texture({i})]]",
+                texture.id
+            )?;
+            has_args = true;
+        }
 
         // Global metal builtins args
         let builtins = vec![
@@ -437,11 +502,10 @@ void {kernel_name}("
             (flags.indexes.plane_dim, Variable::<Self>::PlaneDim),
             (flags.indexes.plane_index, Variable::<Self>::PlanePos),
         ];
-        let comma = !buffers.is_empty() || flags.static_meta_length > 0 || !scalars.is_empty();
         builtins
             .iter()
             .filter(|(cond, _)| *cond)
-            .try_for_each(|(_, var)| format_metal_builtin_binding_arg(f, var, comma))?;
+            .try_for_each(|(_, var)| format_metal_builtin_binding_arg(f, var, has_args))?;
         f.write_str("\n)")
     }
 
@@ -449,7 +513,16 @@ void {kernel_name}("
         f: &mut std::fmt::Formatter<'_>,
         body: &shared::Body<Self>,
     ) -> std::fmt::Result {
-        if !body.shared_memories.is_empty() {
+        let launch_sized = body.shared_memories.iter().any(|smem| {
+            matches!(
+                smem,
+                SharedMemory::Dynamic {
+                    specialized: None,
+                    ..
+                }
+            )
+        });
+        if !body.shared_memories.is_empty() && !launch_sized {
             let size = body
                 .shared_memories
                 .iter()
@@ -461,6 +534,23 @@ void {kernel_name}("
         }
         Ok(())
     }
+
+    fn compile_device_function_signature(
+        f: &mut std::fmt::Formatter<'_>,
+        name: &str,
+        params: &[Variable<Self>],
+        out: Option<&Variable<Self>>,
+    ) -> std::fmt::Result {
+        let ret = out.map(|out| out.item().to_string());
+        write!(f, "{} {name}(", ret.as_deref().unwrap_or("void"))?;
+        for (i, param) in params.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} {param}", param.item())?;
+        }
+        f.write_str(")")
+    }
 }
 
 // Cube builtins dialect
@@ -812,6 +902,39 @@ impl DialectInstructions<Self> for MslDialect {
         write!(f, "subsat({lhs}, {rhs})")
     }
 
+    // textures
+    fn compile_texture_sample(
+        f: &mut std::fmt::Formatter<'_>,
+        texture: &Variable<Self>,
+        sampler: Sampler,
+        coordinates: &[Variable<Self>],
+        fetch: &str,
+    ) -> std::fmt::Result {
+        let index = sampler.index();
+        let coords = join_coordinates(coordinates);
+        let swizzle = texel_swizzle(fetch);
+        write!(
+            f,
+            "{texture}.sample(__sampler_{index}, float{}({coords})){swizzle}",
+            coordinates.len()
+        )
+    }
+
+    fn compile_texture_load(
+        f: &mut std::fmt::Formatter<'_>,
+        texture: &Variable<Self>,
+        coordinates: &[Variable<Self>],
+        fetch: &str,
+    ) -> std::fmt::Result {
+        let coords = join_coordinates(coordinates);
+        let swizzle = texel_swizzle(fetch);
+        write!(
+            f,
+            "{texture}.read(uint{}({coords})){swizzle}",
+            coordinates.len()
+        )
+    }
+
     // debug
     fn compile_instruction_printf(
         f: &mut std::fmt::Formatter<'_>,
@@ -1273,3 +1396,20 @@ impl DialectProcessors<Self> for MslDialect {
         Vec::new()
     }
 }
+
+fn join_coordinates(coordinates: &[Variable<MslDialect>]) -> String {
+    coordinates
+        .iter()
+        .map(|coord| coord.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Texels are read as a `float4`, only the channels of the texture are kept.
+fn texel_swizzle(fetch: &str) -> &'static str {
+    match fetch {
+        "float" => ".x",
+        "float2" => ".xy",
+        _ => "",
+    }
+}
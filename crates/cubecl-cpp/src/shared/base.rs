@@ -1,24 +1,28 @@
 use super::{
-    BinaryInstruction, Binding, Body, Component, ComputeKernel, ConstArray, Dialect, Elem, FP4Kind,
-    FP6Kind, FP8Kind, Fragment, FragmentIdent, FragmentLayout, IndexAssignInstruction,
-    IndexInstruction, Instruction, Item, LocalArray, SharedMemory, UnaryInstruction, Variable,
-    WarpInstruction, WmmaInstruction, barrier::BarrierOps, pipeline::PipelineOps,
+    BinaryInstruction, Binding, Body, Component, ComputeKernel, ConstArray,
+    DYNAMIC_SHARED_MEMORY_ALIGN, DeviceFunction, Dialect, Elem, FP4Kind, FP6Kind, FP8Kind,
+    Fragment, FragmentIdent, FragmentLayout, IndexAssignInstruction, IndexInstruction, Instruction,
+    Item, LocalArray, SharedMemory, TextureBinding, UnaryInstruction, Variable, WarpInstruction,
+    WmmaInstruction, barrier::BarrierOps, pipeline::PipelineOps, texture::TextureOps,
 };
 use crate::shared::MmaShape;
 use cubecl_common::backtrace::BackTrace;
 use cubecl_core::{
     CubeDim,
     ir::{
-        self as gpu, DeviceProperties, ElemType, FloatKind, InstructionModes, OpaqueType,
+        self as gpu, DeviceProperties, ElemType, FloatKind, Id, InstructionModes, OpaqueType,
         Operation, Processor, SourceLoc, StorageType,
         features::{EnumSet, TypeUsage},
     },
-    post_processing::checked_io::CheckedIoProcessor,
+    post_processing::{atomic::AtomicPolyfillProcessor, checked_io::CheckedIoProcessor},
     prelude::{FastMath, KernelDefinition},
     server::ExecutionMode,
 };
 use cubecl_opt::{Optimizer, SharedLiveness};
-use cubecl_runtime::compiler::{CompilationError, Compiler};
+use cubecl_runtime::{
+    compiler::{CompilationError, Compiler},
+    kernel::KernelResourceUsage,
+};
 use std::{collections::HashSet, fmt::Debug};
 
 pub(super) static COUNTER_TMP_VAR: std::sync::atomic::AtomicU32 =
@@ -78,6 +82,7 @@ pub struct Flags<D: Dialect> {
     pub elem_bf16: bool,
     pub elem_f16: bool,
     pub elem_tf32: bool,
+    pub elem_df64: bool,
     pub indexes: CubeIndexFlags,
     pub op_barrier: bool,
     pub op_pipeline: bool,
@@ -86,6 +91,9 @@ pub struct Flags<D: Dialect> {
     pub inst_wmma: bool,
     pub inst_ptx_wrappers: bool,
     pub inst_async_copy: bool,
+    pub inst_texture: bool,
+    /// Whether the kernel uses shared memory sized at launch.
+    pub op_dynamic_shared_memory: bool,
     pub use_grid_constants: bool,
     pub static_meta_length: usize,
     pub has_dynamic_meta: bool,
@@ -100,6 +108,7 @@ pub struct CppCompiler<D: Dialect> {
     barriers: Vec<BarrierOps<D>>,
     compilation_options: CompilationOptions,
     const_arrays: Vec<ConstArray<D>>,
+    dynamic_shared_arrays: Vec<(Id, Item<D>)>,
     ext_meta_positions: Vec<u32>,
     cluster_dim: CubeDim,
     extensions: Vec<D::Extension>,
@@ -111,6 +120,8 @@ pub struct CppCompiler<D: Dialect> {
     source_loc: Option<SourceLoc>,
     strategy: ExecutionMode,
     addr_type: Item<D>,
+    /// The features of the device, used to declare polyfilled atomics as their integer type.
+    features: Option<gpu::features::Features>,
 }
 
 impl<D: Dialect> Default for Flags<D> {
@@ -122,6 +133,7 @@ impl<D: Dialect> Default for Flags<D> {
             elem_bf16: Default::default(),
             elem_f16: Default::default(),
             elem_tf32: Default::default(),
+            elem_df64: Default::default(),
             indexes: Default::default(),
             op_barrier: Default::default(),
             op_pipeline: Default::default(),
@@ -130,6 +142,8 @@ impl<D: Dialect> Default for Flags<D> {
             inst_wmma: Default::default(),
             inst_ptx_wrappers: Default::default(),
             inst_async_copy: Default::default(),
+            inst_texture: Default::default(),
+            op_dynamic_shared_memory: Default::default(),
             use_grid_constants: Default::default(),
             static_meta_length: Default::default(),
             has_dynamic_meta: Default::default(),
@@ -146,6 +160,7 @@ impl<D: Dialect> Default for CppCompiler<D> {
             barriers: Default::default(),
             compilation_options: Default::default(),
             const_arrays: Default::default(),
+            dynamic_shared_arrays: Default::default(),
             ext_meta_positions: Default::default(),
             cluster_dim: CubeDim::new_single(),
             extensions: Default::default(),
@@ -157,6 +172,7 @@ impl<D: Dialect> Default for CppCompiler<D> {
             source_loc: Default::default(),
             strategy: Default::default(),
             addr_type: Item::scalar(Elem::U32, true),
+            features: None,
         }
     }
 }
@@ -189,6 +205,11 @@ impl<D: Dialect> Compiler for CppCompiler<D> {
         self.addr_type = self.compile_type(addr_type.into());
         self.compilation_options = compilation_options.clone();
         self.strategy = strategy;
+        self.features = kernel
+            .body
+            .properties
+            .as_ref()
+            .map(|properties| properties.features.clone());
 
         if !self.compilation_options.supports_features.clusters {
             kernel.options.cluster_dim = None;
@@ -197,9 +218,25 @@ impl<D: Dialect> Compiler for CppCompiler<D> {
 
         let ir = self.clone().compile_ir(kernel, addr_type);
         COUNTER_TMP_VAR.store(0, std::sync::atomic::Ordering::Relaxed);
+
+        if ir.flags.elem_df64 {
+            return Err(CompilationError::UnsupportedInstruction {
+                reason: "df64 is only emulated on WGSL and SPIR-V".into(),
+                backtrace: BackTrace::capture(),
+            });
+        }
+
         Ok(ir)
     }
 
+    fn resource_usage(&self, kernel: &Self::Representation) -> KernelResourceUsage {
+        KernelResourceUsage {
+            shared_memory: kernel.shared_memory_size(),
+            local_arrays: kernel.local_array_sizes(),
+            ..Default::default()
+        }
+    }
+
     fn elem_size(&self, elem: gpu::ElemType) -> usize {
         elem.size()
     }
@@ -218,11 +255,26 @@ impl<D: Dialect> CppCompiler<D> {
         self.build_metadata(&value);
 
         let instructions = self.compile_scope(&mut value.body.clone());
+        let functions = value
+            .body
+            .functions
+            .take()
+            .into_iter()
+            .map(|function| self.compile_function(function))
+            .collect();
         let tensor_maps = value
             .tensor_maps
             .into_iter()
             .map(|b| self.compile_binding(b))
             .collect();
+        let textures = value
+            .textures
+            .iter()
+            .map(|texture| TextureBinding {
+                id: texture.id,
+                dim: texture.dim,
+            })
+            .collect();
         let buffers = value
             .buffers
             .into_iter()
@@ -246,9 +298,13 @@ impl<D: Dialect> CppCompiler<D> {
             elem_bf16: self.flags.elem_bf16,
             elem_f16: self.flags.elem_f16,
             elem_tf32: self.flags.elem_tf32,
+            elem_df64: self.flags.elem_df64,
             inst_tma: self.flags.inst_tma,
             inst_tma_im2col: self.flags.inst_tma_im2col,
             inst_async_copy: self.flags.inst_async_copy,
+            inst_texture: self.flags.inst_texture,
+            op_dynamic_shared_memory: !self.dynamic_shared_arrays.is_empty()
+                && value.dynamic_shared_memory.is_none(),
             inst_ptx_wrappers: self.flags.inst_ptx_wrappers,
             use_grid_constants: self.compilation_options.supports_features.grid_constants,
             // TODO: At some point we should only pass dynamic meta if tensors are present,
@@ -262,7 +318,7 @@ impl<D: Dialect> CppCompiler<D> {
 
         let mut opt = Optimizer::shared_only(value.body, value.cube_dim);
         let shared_allocs = opt.analysis::<SharedLiveness>();
-        let shared_memories = shared_allocs
+        let mut shared_memories: Vec<_> = shared_allocs
             .allocations
             .values()
             .map(|alloc| match alloc.smem {
@@ -287,6 +343,29 @@ impl<D: Dialect> CppCompiler<D> {
             })
             .collect();
 
+        // Views of the dynamic shared memory all start after the static allocations
+        if !self.dynamic_shared_arrays.is_empty() {
+            let align = shared_memories
+                .iter()
+                .map(|smem| smem.align())
+                .fold(DYNAMIC_SHARED_MEMORY_ALIGN, usize::max);
+            let offset = shared_memories
+                .iter()
+                .map(|smem| smem.offset() + smem.size())
+                .max()
+                .unwrap_or_default()
+                .next_multiple_of(align);
+            shared_memories.extend(self.dynamic_shared_arrays.iter().map(|(id, item)| {
+                SharedMemory::Dynamic {
+                    index: *id,
+                    item: *item,
+                    align,
+                    offset,
+                    specialized: value.dynamic_shared_memory,
+                }
+            }));
+        }
+
         let body = Body {
             instructions,
             shared_memories,
@@ -303,10 +382,12 @@ impl<D: Dialect> CppCompiler<D> {
 
         ComputeKernel {
             tensor_maps,
+            textures,
             buffers,
             scalars,
             meta_static_len: self.metadata.static_len() as usize,
             cube_dim: value.cube_dim,
+            functions,
             body,
             extensions: self.extensions,
             flags,
@@ -367,6 +448,10 @@ impl<D: Dialect> CppCompiler<D> {
         let dialect_processors = D::processors();
         let mut processors: Vec<&dyn Processor> = vec![&*checked_io];
         processors.extend(dialect_processors.iter().map(|it| &**it));
+        let atomic = scope.properties.clone().map(AtomicPolyfillProcessor::new);
+        if let Some(atomic) = &atomic {
+            processors.push(atomic);
+        }
 
         let processing = scope.process(processors);
 
@@ -384,6 +469,38 @@ impl<D: Dialect> CppCompiler<D> {
         instructions
     }
 
+    /// Compile a function body on its own, since arrays and derived indexes are declared in the
+    /// body they're used in.
+    fn compile_function(&mut self, function: gpu::Function) -> DeviceFunction<D> {
+        let const_arrays = core::mem::take(&mut self.const_arrays);
+        let local_arrays = core::mem::take(&mut self.local_arrays);
+        let indexes = core::mem::take(&mut self.flags.indexes);
+
+        let mut scope = function.body;
+        let instructions = self.compile_scope(&mut scope);
+        let body = Body {
+            instructions,
+            shared_memories: Vec::new(),
+            pipelines: Vec::new(),
+            barriers: Vec::new(),
+            const_arrays: core::mem::replace(&mut self.const_arrays, const_arrays),
+            local_arrays: core::mem::replace(&mut self.local_arrays, local_arrays),
+        };
+        let function_indexes = core::mem::replace(&mut self.flags.indexes, indexes);
+
+        DeviceFunction {
+            name: function.name,
+            params: function
+                .params
+                .into_iter()
+                .map(|param| self.compile_variable(param))
+                .collect(),
+            out: function.out.map(|out| self.compile_variable(out)),
+            body,
+            indexes: D::builtin_rules(&function_indexes),
+        }
+    }
+
     fn compile_instruction(
         &mut self,
         instructions: &mut Vec<Instruction<D>>,
@@ -562,6 +679,37 @@ impl<D: Dialect> CppCompiler<D> {
                 // Don't need to handle scopes
                 _ => {}
             },
+            gpu::Operation::Texture(texture_ops) => {
+                self.flags.inst_texture = true;
+                let out = self.compile_variable(out.unwrap());
+                let texture_ops = match texture_ops {
+                    gpu::TextureOps::Sample {
+                        texture,
+                        sampler,
+                        coordinates,
+                    } => TextureOps::Sample {
+                        texture: self.compile_variable(texture),
+                        sampler,
+                        coordinates: coordinates
+                            .into_iter()
+                            .map(|coord| self.compile_variable(coord))
+                            .collect(),
+                        out,
+                    },
+                    gpu::TextureOps::Load {
+                        texture,
+                        coordinates,
+                    } => TextureOps::Load {
+                        texture: self.compile_variable(texture),
+                        coordinates: coordinates
+                            .into_iter()
+                            .map(|coord| self.compile_variable(coord))
+                            .collect(),
+                        out,
+                    },
+                };
+                instructions.push(Instruction::Texture(texture_ops));
+            }
             gpu::Operation::Barrier(barrier_ops) => match barrier_ops {
                 gpu::BarrierOps::Declare { barrier } => {
                     let StorageType::Opaque(OpaqueType::Barrier(level)) = barrier.ty.storage_type()
@@ -816,6 +964,15 @@ impl<D: Dialect> CppCompiler<D> {
                 }
             }
             gpu::Operation::Marker(_) => {}
+            gpu::Operation::Call(call) => instructions.push(Instruction::Call {
+                name: call.name,
+                args: call
+                    .args
+                    .into_iter()
+                    .map(|arg| self.compile_variable(arg))
+                    .collect(),
+                out: out.map(|out| self.compile_variable(out)),
+            }),
         }
     }
 
@@ -1065,6 +1222,7 @@ impl<D: Dialect> CppCompiler<D> {
             }),
             gpu::Branch::Return => instructions.push(Instruction::Return),
             gpu::Branch::Break => instructions.push(Instruction::Break),
+            gpu::Branch::Continue => instructions.push(Instruction::Continue),
             gpu::Branch::RangeLoop(mut range_loop) => instructions.push(Instruction::RangeLoop {
                 i: self.compile_variable(range_loop.i),
                 start: self.compile_variable(range_loop.start),
@@ -1680,6 +1838,13 @@ impl<D: Dialect> CppCompiler<D> {
                 elem: self.compile_storage_type(item.storage_type()),
                 in_struct: self.compilation_options.supports_features.grid_constants,
             },
+            gpu::VariableKind::SpecConstant(_) => {
+                panic!("Specialization constants should be specialized on this runtime")
+            }
+            gpu::VariableKind::Texture(id) => {
+                self.flags.inst_texture = true;
+                Variable::Texture(id, self.compile_type(item))
+            }
             gpu::VariableKind::TensorMapInput(id) => {
                 self.flags.inst_tma = true;
                 Variable::TensorMap(id)
@@ -1710,6 +1875,17 @@ impl<D: Dialect> CppCompiler<D> {
                 let item = self.compile_type(item);
                 Variable::SharedArray(id, item, length)
             }
+            gpu::VariableKind::DynamicSharedArray { id } => {
+                let item = self.compile_type(item);
+                if !self
+                    .dynamic_shared_arrays
+                    .iter()
+                    .any(|(other, _)| *other == id)
+                {
+                    self.dynamic_shared_arrays.push((id, item));
+                }
+                Variable::DynamicSharedArray(id, item)
+            }
             gpu::VariableKind::Shared { id } => {
                 let item = self.compile_type(item);
                 Variable::Shared(id, item)
@@ -1947,7 +2123,15 @@ impl<D: Dialect> CppCompiler<D> {
     fn compile_storage_type(&mut self, value: gpu::StorageType) -> Elem<D> {
         match value {
             gpu::StorageType::Scalar(ty) => self.compile_elem(ty),
-            gpu::StorageType::Atomic(ty) => Elem::Atomic(ty.into()),
+            // Polyfilled atomics are only accessed through an integer view, see
+            // `AtomicPolyfillProcessor`.
+            gpu::StorageType::Atomic(ty) => {
+                let polyfill = self
+                    .features
+                    .as_ref()
+                    .and_then(|features| features.atomic_polyfill_type(ty));
+                Elem::Atomic(polyfill.unwrap_or(ty).into())
+            }
             gpu::StorageType::Packed(gpu::ElemType::Float(kind), 2) => match kind {
                 FloatKind::E2M1 => {
                     self.flags.elem_fp4 = true;
@@ -2034,6 +2218,11 @@ impl<D: Dialect> CppCompiler<D> {
                 gpu::FloatKind::Flex32 => Elem::F32,
                 gpu::FloatKind::F32 => Elem::F32,
                 gpu::FloatKind::F64 => Elem::F64,
+                gpu::FloatKind::DF64 => {
+                    // Rejected once the kernel is compiled, see `Compiler::compile`.
+                    self.flags.elem_df64 = true;
+                    Elem::F64
+                }
             },
             gpu::ElemType::Int(kind) => match kind {
                 gpu::IntKind::I8 => Elem::I8,
@@ -1,7 +1,7 @@
 use std::{collections::HashSet, fmt::Debug};
 use std::{fmt::Display, hash::Hash};
 
-use cubecl_core::ir::Processor;
+use cubecl_core::ir::{Processor, Sampler};
 
 use crate::shared::{
     FmtLeft, IndexedVariable, MmaShape, SupportedMmaCombinations, SupportedScaledMmaCombinations,
@@ -10,8 +10,8 @@ use crate::shared::{
 
 use super::{
     Architecture, AtomicKind, Binding, Body, Component, CubeIndexFlags, Elem, Flags, Fragment,
-    FragmentIdent, FragmentLayout, Instruction, Item, SharedMemory, Variable, WarpInstruction,
-    WmmaInstruction,
+    FragmentIdent, FragmentLayout, Instruction, Item, SharedMemory, TextureBinding, Variable,
+    WarpInstruction, WmmaInstruction,
 };
 
 // Base dialect
@@ -131,6 +131,22 @@ pub trait DialectTypes<D: Dialect> {
                     "{item} &shared_memory_{index} = reinterpret_cast<{item}&>(dynamic_shared_mem[{offset}]);"
                 )
             }
+            SharedMemory::Dynamic {
+                index,
+                item,
+                offset,
+                specialized,
+                ..
+            } => {
+                match specialized {
+                    Some(size) => writeln!(f, "// Dynamic shared array, {size} bytes")?,
+                    None => writeln!(f, "// Dynamic shared array, sized at launch")?,
+                }
+                writeln!(
+                    f,
+                    "{item} *shared_memory_{index} = reinterpret_cast<{item}*>(&dynamic_shared_mem[{offset}]);"
+                )
+            }
         }
     }
     fn compile_polyfills(_f: &mut std::fmt::Formatter<'_>, _flags: &Flags<D>) -> std::fmt::Result {
@@ -149,6 +165,7 @@ pub trait DialectBindings<D: Dialect> {
         f: &mut std::fmt::Formatter<'_>,
         kernel_name: &str,
         tensor_maps: &[Binding<D>],
+        textures: &[TextureBinding],
         buffers: &[Binding<D>],
         scalars: &[(Elem<D>, usize)],
         flags: &Flags<D>,
@@ -159,6 +176,26 @@ pub trait DialectBindings<D: Dialect> {
     ) -> std::fmt::Result {
         Ok(())
     }
+    fn compile_device_function_signature(
+        f: &mut std::fmt::Formatter<'_>,
+        name: &str,
+        params: &[Variable<D>],
+        out: Option<&Variable<D>>,
+    ) -> std::fmt::Result {
+        let ret = out.map(|out| out.item().to_string());
+        write!(
+            f,
+            "__device__ __noinline__ {} {name}(",
+            ret.as_deref().unwrap_or("void")
+        )?;
+        for (i, param) in params.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} {param}", param.item())?;
+        }
+        f.write_str(")")
+    }
 }
 
 // Cube builtins dialect
@@ -533,6 +570,39 @@ pub trait DialectInstructions<D: Dialect> {
         item: Item<D>,
     ) -> std::fmt::Result;
 
+    // textures
+    /// Fetch the texel filtered by `sampler` at normalized coordinates, as the `fetch` type.
+    fn compile_texture_sample(
+        f: &mut std::fmt::Formatter<'_>,
+        texture: &Variable<D>,
+        sampler: Sampler,
+        coordinates: &[Variable<D>],
+        fetch: &str,
+    ) -> std::fmt::Result {
+        let rank = coordinates.len();
+        let index = sampler.index();
+        write!(f, "tex{rank}D<{fetch}>({texture}.samplers[{index}]")?;
+        for coord in coordinates {
+            write!(f, ", {coord}")?;
+        }
+        f.write_str(")")
+    }
+    /// Fetch the texel at integer coordinates, as the `fetch` type.
+    fn compile_texture_load(
+        f: &mut std::fmt::Formatter<'_>,
+        texture: &Variable<D>,
+        coordinates: &[Variable<D>],
+        fetch: &str,
+    ) -> std::fmt::Result {
+        // `texels` uses unnormalized coordinates, so the center of the texel is sampled.
+        let rank = coordinates.len();
+        write!(f, "tex{rank}D<{fetch}>({texture}.texels")?;
+        for coord in coordinates {
+            write!(f, ", float({coord}) + 0.5f")?;
+        }
+        f.write_str(")")
+    }
+
     // debug
     fn compile_instruction_printf(
         f: &mut std::fmt::Formatter<'_>,
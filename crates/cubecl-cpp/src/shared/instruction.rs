@@ -2,7 +2,7 @@ use crate::shared::FmtLeft;
 
 use super::{
     Component, Dialect, Elem, Item, Variable, WarpInstruction, WmmaInstruction,
-    barrier::BarrierOps, binary::*, pipeline::PipelineOps, unary::*,
+    barrier::BarrierOps, binary::*, pipeline::PipelineOps, texture::TextureOps, unary::*,
 };
 use std::{
     borrow::Cow,
@@ -143,6 +143,12 @@ pub enum Instruction<D: Dialect> {
     },
     Return,
     Break,
+    Continue,
+    Call {
+        name: String,
+        args: Vec<Variable<D>>,
+        out: Option<Variable<D>>,
+    },
     Equal(BinaryInstruction<D>),
     NotEqual(BinaryInstruction<D>),
     Lower(BinaryInstruction<D>),
@@ -274,6 +280,7 @@ pub enum Instruction<D: Dialect> {
     },
     Pipeline(PipelineOps<D>),
     Barrier(BarrierOps<D>),
+    Texture(TextureOps<D>),
     MemCopyAsyncTensorSharedToGlobal {
         smem_buffer: Variable<D>,
         smem_offset: Variable<D>,
@@ -291,6 +298,18 @@ impl<D: Dialect> Display for Instruction<D> {
         match self {
             Instruction::Return => f.write_str("return;"),
             Instruction::Break => f.write_str("break;"),
+            Instruction::Continue => f.write_str("continue;"),
+            Instruction::Call { name, args, out } => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                match out {
+                    Some(out) => writeln!(f, "{} = {name}({args});", out.fmt_left()),
+                    None => writeln!(f, "{name}({args});"),
+                }
+            }
             Instruction::DeclareVariable { var } => match var {
                 Variable::WmmaFragment { .. } => D::compile_wmma_fragment_declaration(f, var),
                 _ => {
@@ -604,6 +623,12 @@ for ({i_ty} {i} = {start}; {i} {cmp} {end}; {increment}) {{
             Instruction::Warp(it) => write!(f, "{it}"),
             Instruction::Fma { a, b, c, out } => Fma::format(f, a, b, c, out),
             Instruction::Wmma(it) => write!(f, "{it}"),
+            // Atomics are pointers, and only reinterpreted to the type they're declared as.
+            Instruction::Bitcast(UnaryInstruction { input, out })
+                if matches!(out.elem(), Elem::Atomic(_)) =>
+            {
+                writeln!(f, "auto {out} = {input};")
+            }
             Instruction::Bitcast(UnaryInstruction { input, out }) => {
                 let qualifier = out.const_qualifier();
                 let input_item = input.item();
@@ -696,6 +721,7 @@ for ({i_ty} {i} = {start}; {i} {cmp} {end}; {increment}) {{
             }
             Instruction::Pipeline(pipeline_ops) => write!(f, "{pipeline_ops}"),
             Instruction::Barrier(barrier_ops) => write!(f, "{barrier_ops}"),
+            Instruction::Texture(texture_ops) => write!(f, "{texture_ops}"),
             Instruction::Line { file, line } => writeln!(f, "#line {line} \"{file}\""),
             Instruction::ProxyAsyncToSharedFence => {
                 writeln!(
@@ -1,9 +1,9 @@
 use crate::shared::STATIC_INFO_NAME;
 
-use super::{Body, Component, Dialect, Elem, Flags, INFO_NAME, Item, Variable};
+use super::{Body, Component, CubeIndexFlags, Dialect, Elem, Flags, INFO_NAME, Item, Variable};
 use cubecl_core::{
     CubeDim,
-    ir::Id,
+    ir::{Id, TextureDim},
     prelude::{Location, Visibility},
 };
 
@@ -18,6 +18,12 @@ pub struct Binding<D: Dialect> {
     pub vis: Visibility,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TextureBinding {
+    pub id: Id,
+    pub dim: TextureDim,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum SharedMemory<D: Dialect> {
     Array {
@@ -33,13 +39,27 @@ pub enum SharedMemory<D: Dialect> {
         align: usize,
         offset: usize,
     },
+    /// A view of the dynamic shared memory, placed after every static allocation. All views share
+    /// the same offset.
+    Dynamic {
+        index: Id,
+        item: Item<D>,
+        align: usize,
+        offset: usize,
+        /// Size in bytes when specialized into the kernel, `None` when set at launch.
+        specialized: Option<usize>,
+    },
 }
 
+/// Minimum alignment of the dynamic shared memory, enough for any vectorized type.
+pub const DYNAMIC_SHARED_MEMORY_ALIGN: usize = 16;
+
 impl<D: Dialect> SharedMemory<D> {
     pub fn size(&self) -> usize {
         match self {
             SharedMemory::Array { item, length, .. } => *length * item.size(),
             SharedMemory::Value { item, .. } => item.size(),
+            SharedMemory::Dynamic { specialized, .. } => specialized.unwrap_or_default(),
         }
     }
 
@@ -47,6 +67,7 @@ impl<D: Dialect> SharedMemory<D> {
         match self {
             SharedMemory::Array { align, .. } => *align,
             SharedMemory::Value { align, .. } => *align,
+            SharedMemory::Dynamic { align, .. } => *align,
         }
     }
 
@@ -54,6 +75,7 @@ impl<D: Dialect> SharedMemory<D> {
         match self {
             SharedMemory::Array { offset, .. } => *offset,
             SharedMemory::Value { offset, .. } => *offset,
+            SharedMemory::Dynamic { offset, .. } => *offset,
         }
     }
 }
@@ -100,12 +122,42 @@ impl<D: Dialect> SharedMemory<D> {
     }
 }
 
+/// A non-inlined function called from the kernel. Builtins are passed in by the caller, so the
+/// body only declares the derived indexes it uses itself.
+#[derive(Debug, Clone)]
+pub struct DeviceFunction<D: Dialect> {
+    pub name: String,
+    pub params: Vec<Variable<D>>,
+    pub out: Option<Variable<D>>,
+    pub body: Body<D>,
+    pub indexes: CubeIndexFlags,
+}
+
+impl<D: Dialect> DeviceFunction<D> {
+    fn format(&self, f: &mut std::fmt::Formatter<'_>, flags: &Flags<D>) -> std::fmt::Result {
+        D::compile_device_function_signature(f, &self.name, &self.params, self.out.as_ref())?;
+        f.write_str(" {\n")?;
+        let flags = Flags {
+            indexes: self.indexes.clone(),
+            ..flags.clone()
+        };
+        compile_cube_builtin_bindings_decl::<D>(f, &flags)?;
+        write!(f, "{}", self.body)?;
+        if let Some(out) = &self.out {
+            writeln!(f, "return {out};")?;
+        }
+        f.write_str("}\n\n")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ComputeKernel<D: Dialect> {
     pub tensor_maps: Vec<Binding<D>>,
+    pub textures: Vec<TextureBinding>,
     pub buffers: Vec<Binding<D>>,
     pub scalars: Vec<(Elem<D>, usize)>,
     pub meta_static_len: usize,
+    pub functions: Vec<DeviceFunction<D>>,
     pub body: Body<D>,
     pub cube_dim: CubeDim,
     pub cluster_dim: Option<CubeDim>,
@@ -121,6 +173,13 @@ impl<D: Dialect> ComputeKernel<D> {
         let ends = smems.map(|it| it.offset() + it.size());
         ends.max().unwrap_or_default()
     }
+
+    /// Size in bytes of every local array of the kernel and its device functions.
+    pub fn local_array_sizes(&self) -> Vec<usize> {
+        let bodies = core::iter::once(&self.body).chain(self.functions.iter().map(|f| &f.body));
+        let arrays = bodies.flat_map(|body| body.local_arrays.iter());
+        arrays.map(|it| it.item.size() * it.size).collect()
+    }
 }
 
 impl<D: Dialect> Display for ComputeKernel<D> {
@@ -129,6 +188,9 @@ impl<D: Dialect> Display for ComputeKernel<D> {
         if !self.tensor_maps.is_empty() {
             flags.inst_tma = true;
         }
+        if !self.textures.is_empty() {
+            flags.inst_texture = true;
+        }
 
         // Program Scope -----------------------------------------------------
         D::compile_includes(f, &flags)?;
@@ -136,11 +198,17 @@ impl<D: Dialect> Display for ComputeKernel<D> {
         D::compile_polyfills(f, &flags)?;
         D::compile_extensions(f, &self.extensions)?;
 
+        // Device functions --------------------------------------------------
+        for function in self.functions.iter() {
+            function.format(f, &flags)?;
+        }
+
         // Kernel signature --------------------------------------------------
         D::compile_kernel_signature(
             f,
             &self.kernel_name,
             &self.tensor_maps,
+            &self.textures,
             &self.buffers,
             &self.scalars,
             &self.flags,
@@ -237,6 +305,7 @@ struct metadata_st {{
 pub fn compile_bindings<D: Dialect>(
     f: &mut core::fmt::Formatter<'_>,
     tensor_maps: &[Binding<D>],
+    textures: &[TextureBinding],
     buffers: &[Binding<D>],
     trailing_comma: bool,
     flags: &Flags<D>,
@@ -251,6 +320,11 @@ pub fn compile_bindings<D: Dialect>(
             binding.id
         )
     }));
+    args.extend(
+        textures
+            .iter()
+            .map(|texture| format!("const texture_ref texture_{}", texture.id)),
+    );
     args.extend(
         tensor_maps
             .iter()
@@ -11,6 +11,7 @@ mod item;
 mod kernel;
 mod mma;
 mod pipeline;
+mod texture;
 mod variable;
 mod warp;
 
@@ -0,0 +1,76 @@
+use std::fmt::Display;
+
+use cubecl_core::ir::Sampler;
+
+use super::{Component, Dialect, FmtLeft, Variable};
+
+#[derive(Debug, Clone)]
+pub enum TextureOps<D: Dialect> {
+    Sample {
+        texture: Variable<D>,
+        sampler: Sampler,
+        coordinates: Vec<Variable<D>>,
+        out: Variable<D>,
+    },
+    Load {
+        texture: Variable<D>,
+        coordinates: Vec<Variable<D>>,
+        out: Variable<D>,
+    },
+}
+
+impl<D: Dialect> TextureOps<D> {
+    fn out(&self) -> &Variable<D> {
+        match self {
+            TextureOps::Sample { out, .. } | TextureOps::Load { out, .. } => out,
+        }
+    }
+}
+
+/// Texels are always fetched as floats, then converted to the element type of the output.
+fn fetch_type(channels: usize) -> &'static str {
+    match channels {
+        1 => "float",
+        2 => "float2",
+        4 => "float4",
+        _ => unreachable!("Textures have 1, 2 or 4 channels"),
+    }
+}
+
+impl<D: Dialect> Display for TextureOps<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let out = self.out();
+        let item = out.item();
+        let fetch = fetch_type(item.vectorization);
+        let texel = Variable::<D>::tmp(item);
+
+        write!(f, "const {fetch} {texel} = ")?;
+        match self {
+            TextureOps::Sample {
+                texture,
+                sampler,
+                coordinates,
+                ..
+            } => D::compile_texture_sample(f, texture, *sampler, coordinates, fetch)?,
+            TextureOps::Load {
+                texture,
+                coordinates,
+                ..
+            } => D::compile_texture_load(f, texture, coordinates, fetch)?,
+        }
+        f.write_str(";\n")?;
+
+        let elem = item.elem;
+        let out = out.fmt_left();
+        if item.vectorization == 1 {
+            return writeln!(f, "{out} = {elem}({texel});");
+        }
+
+        let components = ["x", "y", "z", "w"][..item.vectorization]
+            .iter()
+            .map(|c| format!("{elem}({texel}.{c})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(f, "{out} = {item}{{{components}}};")
+    }
+}
@@ -67,6 +67,7 @@ pub enum Variable<D: Dialect> {
     ConstantArray(Id, Item<D>, usize),
     Constant(ConstantValue, Item<D>),
     TensorMap(Id),
+    Texture(Id, Item<D>),
     LocalMut {
         id: Id,
         item: Item<D>,
@@ -84,6 +85,7 @@ pub enum Variable<D: Dialect> {
         item: Item<D>,
     },
     SharedArray(Id, Item<D>, usize),
+    DynamicSharedArray(Id, Item<D>),
     Shared(Id, Item<D>),
     LocalArray(Id, Item<D>, usize),
     WmmaFragment {
@@ -174,6 +176,7 @@ impl<D: Dialect> Component<D> for Variable<D> {
             Variable::GlobalOutputArray(_, e) => *e,
             Variable::LocalArray(_, e, _) => *e,
             Variable::SharedArray(_, e, _) => *e,
+            Variable::DynamicSharedArray(_, e) => *e,
             Variable::Shared(_, e) => *e,
             Variable::ConstantArray(_, e, _) => *e,
             Variable::LocalMut { item, .. } => *item,
@@ -188,6 +191,7 @@ impl<D: Dialect> Component<D> for Variable<D> {
             | Variable::Barrier { .. }
             | Variable::BarrierToken { .. } => Item::new(Elem::Bool, 1, false),
             Variable::TensorMap(_) => unreachable!(),
+            Variable::Texture(_, item) => *item,
         }
     }
 
@@ -209,6 +213,7 @@ impl<D: Dialect> Display for Variable<D> {
             Variable::GlobalInputArray(id, _) => f.write_fmt(format_args!("buffer_{id}")),
             Variable::GlobalOutputArray(id, _) => write!(f, "buffer_{id}"),
             Variable::TensorMap(id) => write!(f, "tensor_map_{id}"),
+            Variable::Texture(id, _) => write!(f, "texture_{id}"),
             Variable::LocalMut { id, .. } => f.write_fmt(format_args!("l_mut_{id}")),
             Variable::LocalConst { id, .. } => f.write_fmt(format_args!("l_{id}")),
             Variable::Named { name, .. } => f.write_fmt(format_args!("{name}")),
@@ -232,7 +237,9 @@ impl<D: Dialect> Display for Variable<D> {
                     .collect::<Vec<_>>();
                 write!(f, "{item} {{ {} }}", values.join(","))
             }
-            Variable::SharedArray(number, _, _) | Variable::Shared(number, _) => {
+            Variable::SharedArray(number, _, _)
+            | Variable::DynamicSharedArray(number, _)
+            | Variable::Shared(number, _) => {
                 write!(f, "shared_memory_{number}")
             }
 
@@ -439,6 +446,9 @@ impl<D: Dialect> Variable<D> {
 
                 Variable::SharedArray(*id, item, size / scaling)
             }
+            Variable::DynamicSharedArray(id, item) => {
+                Variable::DynamicSharedArray(*id, item.optimized())
+            }
             Variable::LocalArray(id, item, size) => {
                 let before = item.vectorization;
                 let item = item.optimized();
@@ -500,11 +510,13 @@ impl<D: Dialect> Variable<D> {
             Variable::Named { .. } => false,
             Variable::Pipeline { .. } => false,
             Variable::SharedArray(_, _, _) => false,
+            Variable::DynamicSharedArray(_, _) => false,
             Variable::Shared(_, _) => false,
             Variable::Slice { .. } => false,
             Variable::Tmp { .. } => false,
             Variable::WmmaFragment { .. } => false,
             Variable::TensorMap { .. } => false,
+            Variable::Texture { .. } => false,
         }
     }
 
@@ -531,6 +543,7 @@ impl<D: Dialect> Variable<D> {
             Variable::Slice { id, .. } => Some(*id),
             Variable::Shared(id, ..) => Some(*id),
             Variable::SharedArray(id, ..) => Some(*id),
+            Variable::DynamicSharedArray(id, ..) => Some(*id),
             Variable::LocalArray(id, ..) => Some(*id),
             Variable::WmmaFragment { id, .. } => Some(*id),
             Variable::Pipeline { id, .. } => Some(*id),
@@ -546,6 +559,7 @@ impl<D: Dialect> Variable<D> {
         match self {
             Variable::Slice { .. }
             | Variable::SharedArray(_, _, _)
+            | Variable::DynamicSharedArray(_, _)
             | Variable::GlobalInputArray(_, _)
             | Variable::GlobalOutputArray(_, _) => format!("{self}"),
             _ => format!("&{self}"),
@@ -16,8 +16,8 @@ use cubecl_core::{
     Compiler,
     ir::{self, StorageType},
     post_processing::{
-        checked_io::CheckedIoProcessor, predicate::PredicateProcessor,
-        saturating::SaturatingArithmeticProcessor,
+        checked_io::CheckedIoProcessor, dynamic_shared_memory::DynamicSharedMemoryProcessor,
+        predicate::PredicateProcessor, saturating::SaturatingArithmeticProcessor,
     },
     prelude::KernelDefinition,
     server::ExecutionMode,
@@ -48,30 +48,30 @@ impl Compiler for MlirCompiler {
         mode: ExecutionMode, // TODO support this by adding array bound checking
         addr_type: StorageType,
     ) -> Result<Self::Representation, CompilationError> {
-        let errors = kernel.body.pop_errors();
-        if !errors.is_empty() {
-            let mut reason = "Can't compile mlir kernel".to_string();
-            for error in errors {
-                reason += error.as_str();
-                reason += "\n";
-            }
-
-            return Err(CompilationError::Validation {
-                reason,
+        validate(&mut kernel.body)?;
+        if !kernel.textures.is_empty() {
+            return Err(CompilationError::UnsupportedInstruction {
+                reason: "Textures aren't supported on CPU".into(),
                 backtrace: BackTrace::capture(),
             });
         }
 
         #[cfg(feature = "mlir-dump")]
         dump_scope(&kernel.body, &kernel.options.kernel_name);
-        let opt = OptimizerBuilder::default()
+        let mut builder = OptimizerBuilder::default()
             .with_transformer(ErfTransform)
             .with_transformer(HypotTransform)
-            .with_transformer(RhypotTransform)
+            .with_transformer(RhypotTransform);
+        if let Some(size) = kernel.dynamic_shared_memory {
+            builder = builder.with_processor(DynamicSharedMemoryProcessor::new(&kernel.body, size));
+        }
+        let opt = builder
             .with_processor(CheckedIoProcessor::new(mode))
             .with_processor(SaturatingArithmeticProcessor::new(true))
             .with_processor(PredicateProcessor)
             .optimize(kernel.body.clone(), kernel.cube_dim);
+        // Processors can reject instructions they can't lower.
+        validate(&mut kernel.body)?;
 
         let mut shared_memories = SharedMemories::default();
         shared_memories.visit(&opt);
@@ -95,6 +95,25 @@ impl Compiler for MlirCompiler {
     }
 }
 
+/// Fails with the validation errors pushed to the kernel, if any.
+fn validate(body: &mut ir::Scope) -> Result<(), CompilationError> {
+    let errors = body.pop_errors();
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    let mut reason = "Can't compile mlir kernel".to_string();
+    for error in errors {
+        reason += error.as_str();
+        reason += "\n";
+    }
+
+    Err(CompilationError::Validation {
+        reason,
+        backtrace: BackTrace::capture(),
+    })
+}
+
 #[cfg(feature = "mlir-dump")]
 fn dump_scope(scope: &cubecl_core::prelude::Scope, name: &str) {
     use std::fs;
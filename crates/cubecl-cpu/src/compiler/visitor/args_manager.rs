@@ -127,6 +127,21 @@ impl<'a, 'b> ArgsManagerBuilder<'a, 'b> {
         FunctionType::new(context, &self.function_types, &[])
     }
 
+    /// Arguments of a device function, which only has access to its own parameters.
+    pub fn function_args(&self) -> ArgsManager<'a> {
+        ArgsManager {
+            buffers: Vec::new(),
+            scalars_memref: HashMap::new(),
+            metadata_memref: None,
+            builtin: [None; NB_BUILTIN],
+            metadata: self.metadata.clone(),
+            shared_memory_values: HashMap::new(),
+            ext_meta_positions: self.ext_meta_positions.clone(),
+            addr_type: self.addr_type,
+            addr_size: self.addr_size,
+        }
+    }
+
     pub fn create_top_block(self, region: &Region<'a>) -> ArgsManager<'a> {
         let mut args = ArgsManager {
             buffers: Vec::with_capacity(self.buffers_len),
@@ -122,7 +122,7 @@ impl<'a> Visitor<'a> {
             } => {
                 let body_block = self.visit_basic_block(*body, opt);
                 let destination_operands = self.get_block_args(block_id, *body);
-                self.visit_basic_block(*continue_target, opt);
+                self.visit_continue_target(*continue_target, opt);
                 self.visit_basic_block(*merge, opt);
                 this_block.append_operation(cf::br(
                     body_block.deref(),
@@ -139,7 +139,7 @@ impl<'a> Visitor<'a> {
                 let condition = self.get_variable(*break_cond);
                 let condition = self.cast_to_bool(condition, break_cond.ty);
                 let body_block = self.visit_basic_block(*body, opt);
-                self.visit_basic_block(*continue_target, opt);
+                self.visit_continue_target(*continue_target, opt);
                 let next_block = self.visit_basic_block(*merge, opt);
                 let body_argument = self.get_block_args(block_id, *body);
                 let next_argument = self.get_block_args(block_id, *continue_target);
@@ -153,8 +153,12 @@ impl<'a> Visitor<'a> {
                     self.location,
                 ));
             }
-            ControlFlow::Return => {
-                this_block.append_operation(cf::br(&self.last_block, &[], self.location));
+            ControlFlow::Return { value } => {
+                let returned: Vec<_> = value
+                    .iter()
+                    .map(|value| self.get_variable(*value))
+                    .collect();
+                this_block.append_operation(cf::br(&self.last_block, &returned, self.location));
             }
             ControlFlow::None => {
                 let destination = opt.successors(block_id)[0];
@@ -165,4 +169,11 @@ impl<'a> Visitor<'a> {
         };
         this_block
     }
+
+    /// The continue target of a loop that never loops back has no edges, and is never branched to.
+    fn visit_continue_target(&mut self, continue_target: NodeIndex, opt: &Optimizer) {
+        if !opt.successors(continue_target).is_empty() {
+            self.visit_basic_block(continue_target, opt);
+        }
+    }
 }
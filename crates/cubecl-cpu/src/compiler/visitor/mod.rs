@@ -11,7 +11,7 @@ use std::collections::HashMap;
 
 use args_manager::{ArgsManager, ArgsManagerBuilder};
 use cubecl_core::{
-    ir::{Builtin, StorageType},
+    ir::{Builtin, Function, StorageType},
     prelude::KernelDefinition,
 };
 use cubecl_opt::{NodeIndex, Optimizer};
@@ -30,7 +30,7 @@ use tracel_llvm::mlir_rs::{
     ir::{
         Attribute, Block, BlockRef, Identifier, Location, Module, Operation, Region, RegionRef,
         attribute::{DenseElementsAttribute, StringAttribute, TypeAttribute},
-        r#type::{IntegerType, MemRefType, RankedTensorType},
+        r#type::{FunctionType, IntegerType, MemRefType, RankedTensorType},
     },
 };
 
@@ -195,6 +195,18 @@ impl<'a> Visitor<'a> {
             ));
         }
         add_external_function_to_module(context, module);
+        for function in kernel.body.functions.borrow().iter() {
+            let function_opt = opt.function(function.clone());
+            Self::insert_function(
+                function,
+                module,
+                &function_opt,
+                context,
+                location,
+                args.function_args(),
+            )
+            .unwrap();
+        }
         module.body().append_operation(func::func(
             context,
             name,
@@ -215,6 +227,87 @@ impl<'a> Visitor<'a> {
         ));
     }
 
+    /// Declare a device function in the module. The body is wrapped in an `execute_region`, like
+    /// the kernel body, which yields the returned value.
+    pub(self) fn insert_function(
+        function: &Function,
+        module: &tracel_llvm::mlir_rs::ir::Module<'a>,
+        opt: &Optimizer,
+        context: &'a Context,
+        location: Location<'a>,
+        args: ArgsManager<'a>,
+    ) -> Result<(), Error> {
+        let param_types: Vec<_> = function
+            .params
+            .iter()
+            .map(|param| param.ty.to_type(context))
+            .collect();
+        let result_types: Vec<_> = function
+            .out
+            .iter()
+            .map(|out| out.ty.to_type(context))
+            .collect();
+        let func_type =
+            TypeAttribute::new(FunctionType::new(context, &param_types, &result_types).into());
+
+        let region = Region::new();
+        let inputs: Vec<_> = param_types.iter().map(|ty| (*ty, location)).collect();
+        let block = Block::new(&inputs);
+        let params = (0..function.params.len())
+            .map(|i| block.argument(i).map(Into::into))
+            .collect::<Result<Vec<Value<'a, 'a>>, _>>()?;
+        region.append_block(block);
+
+        let current_block = region.first_block().unwrap();
+        let ops = current_block.append_operation(scf::execute_region(
+            &result_types,
+            Region::new(),
+            location,
+        ));
+        let current_region = ops.region(0)?;
+
+        let outputs: Vec<_> = result_types.iter().map(|ty| (*ty, location)).collect();
+        let last_block = Block::new(&outputs);
+        let returned = (0..result_types.len())
+            .map(|i| last_block.argument(i).map(Into::into))
+            .collect::<Result<Vec<Value<'a, 'a>>, _>>()?;
+        last_block.append_operation(scf::r#yield(&returned, location));
+        let last_block = current_region.append_block(last_block);
+
+        let mut visitor = Visitor::new(
+            current_block,
+            last_block,
+            module,
+            current_region,
+            context,
+            location,
+            args,
+            opt,
+        );
+        for (param, value) in function.params.iter().zip(params) {
+            visitor.insert_variable(*param, value);
+        }
+        visitor.visit_basic_block(opt.entry(), opt);
+
+        let results = (0..result_types.len())
+            .map(|i| ops.result(i).map(Into::into))
+            .collect::<Result<Vec<Value<'a, 'a>>, _>>()?;
+        current_block.append_operation(func::r#return(&results, location));
+
+        module.body().append_operation(func::func(
+            context,
+            StringAttribute::new(context, &function.name),
+            func_type,
+            region,
+            &[(
+                Identifier::new(context, "sym_visibility"),
+                StringAttribute::new(context, "private").into(),
+            )],
+            location,
+        ));
+        Ok(())
+    }
+
     pub(self) fn insert_builtin_loop(
         block: BlockRef<'a, 'a>,
         module: &tracel_llvm::mlir_rs::ir::Module<'a>,
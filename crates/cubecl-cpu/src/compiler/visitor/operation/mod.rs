@@ -5,9 +5,9 @@ pub(super) mod metadata;
 pub(super) mod operator;
 pub(super) mod synchronization;
 
-use cubecl_core::ir::{NonSemantic, Operation};
+use cubecl_core::ir::{FunctionCall, NonSemantic, Operation};
 use tracel_llvm::mlir_rs::{
-    dialect::{llvm, ods::llvm as llvm_ods},
+    dialect::{func, llvm, ods::llvm as llvm_ods},
     ir::{
         attribute::{FlatSymbolRefAttribute, TypeAttribute},
         r#type::IntegerType,
@@ -57,6 +57,9 @@ impl<'a> Visitor<'a> {
                 self.visit_synchronization(synchronization);
             }
             Operation::Marker(_) => {}
+            Operation::Call(call) => {
+                self.visit_call(call, &[]);
+            }
             operation => {
                 todo!(
                     "This operation ({}) is not implemented without an out",
@@ -66,6 +69,26 @@ impl<'a> Visitor<'a> {
         }
     }
 
+    fn visit_call(
+        &mut self,
+        call: &FunctionCall,
+        result_types: &[Type<'a>],
+    ) -> Option<Value<'a, 'a>> {
+        let args: Vec<_> = call
+            .args
+            .iter()
+            .map(|arg| self.get_variable(*arg))
+            .collect();
+        let operation = self.block.append_operation(func::call(
+            self.context,
+            FlatSymbolRefAttribute::new(self.context, &call.name),
+            &args,
+            result_types,
+            self.location,
+        ));
+        operation.result(0).ok().map(Into::into)
+    }
+
     pub fn visit_operation_with_out(&mut self, operation: &Operation, out: Variable) {
         match operation {
             Operation::Atomic(_atomic) => {
@@ -99,9 +122,16 @@ impl<'a> Visitor<'a> {
             Operation::Operator(operator) => {
                 self.visit_operator_with_out(operator, out);
             }
+            Operation::Call(call) => {
+                let value = self.visit_call(call, &[out.ty.to_type(self.context)]);
+                self.insert_variable(out, value.unwrap());
+            }
             Operation::CoopMma(_) | Operation::Plane(_) | Operation::Tma(_) => {
                 panic!("{operation} is not supported on CPU.");
             }
+            Operation::Texture(_) => {
+                unreachable!("Kernels binding textures are rejected before being compiled")
+            }
             Operation::Branch(_) => {
                 unreachable!("Branch operation are removed in SSA form");
             }
@@ -14,9 +14,9 @@ use cubecl_core::{
     future::DynFut,
     ir::MemoryDeviceProperties,
     server::{
-        Allocation, AllocationDescriptor, Binding, Bindings, ComputeServer, CopyDescriptor,
-        ExecutionError, IoError, LaunchError, ProfileError, ProfilingToken, ServerCommunication,
-        ServerUtilities,
+        Allocation, AllocationDescriptor, AllocationKind, Binding, Bindings, ComputeServer,
+        CopyDescriptor, ExecutionError, IoError, LaunchError, ProfileError, ProfilingToken,
+        ServerCommunication, ServerUtilities,
     },
 };
 use cubecl_runtime::{
@@ -332,7 +332,41 @@ impl ComputeServer for CpuServer {
 }
 
 impl ServerCommunication for CpuServer {
-    const SERVER_COMM_ENABLED: bool = false;
+    const SERVER_COMM_ENABLED: bool = true;
+
+    fn copy(
+        server_src: &mut Self,
+        server_dst: &mut Self,
+        src: CopyDescriptor<'_>,
+        stream_id_src: StreamId,
+        stream_id_dst: StreamId,
+    ) -> Result<Allocation, IoError> {
+        let shape = src.shape.to_vec();
+        let elem_size = src.elem_size;
+
+        // Reads are zero-copy, so the bytes are copied before the source is modified by later
+        // tasks. Both servers live in host memory, so no staging buffer is needed.
+        let read = server_src.read(vec![src], stream_id_src);
+        let data = cubecl_common::future::block_on(read)?.remove(0);
+        let data = Bytes::from_bytes_vec(data.to_vec());
+
+        let allocation = server_dst
+            .create(
+                vec![AllocationDescriptor::new(
+                    AllocationKind::Contiguous,
+                    &shape,
+                    elem_size,
+                )],
+                stream_id_dst,
+            )?
+            .remove(0);
+        let descriptor = allocation
+            .handle
+            .copy_descriptor(&shape, &allocation.strides, elem_size);
+        server_dst.write(vec![(descriptor, data)], stream_id_dst)?;
+
+        Ok(allocation)
+    }
 }
 
 pub(crate) fn contiguous_strides(shape: &[usize]) -> Vec<usize> {
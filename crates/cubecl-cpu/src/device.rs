@@ -1,17 +1,27 @@
 use cubecl_common::device::{Device, DeviceId};
 
+/// A CPU device.
+///
+/// Each index is a virtual device with its own server and memory, all running on the same
+/// processor, which allows testing multi-device workflows like collectives without accelerators.
+/// Only the first device is reported by [Device::device_count].
 #[derive(new, Clone, PartialEq, Eq, Default, Hash, Debug)]
-pub struct CpuDevice;
+pub struct CpuDevice {
+    /// The index of the virtual device.
+    pub index: u32,
+}
 
 impl Device for CpuDevice {
-    fn from_id(_device_id: DeviceId) -> Self {
-        Self
+    fn from_id(device_id: DeviceId) -> Self {
+        Self {
+            index: device_id.index_id,
+        }
     }
 
     fn to_id(&self) -> DeviceId {
         DeviceId {
             type_id: 0,
-            index_id: 0,
+            index_id: self.index,
         }
     }
 
@@ -11,7 +11,15 @@ mod tests {
     cubecl_core::testgen_all!(f32: [f16, f32, f64], i32: [i8, i16, i32, i64], u32: [u8, u16, u32, u64]);
     cubecl_std::testgen!();
     cubecl_std::testgen_tensor_identity!([f16, f32, u32]);
+    cubecl_std::testgen_tensor_indexing!([f32, u32]);
+    cubecl_std::testgen_tensor_histogram!([f32]);
+    cubecl_std::testgen_tensor_topk!([f32]);
+    cubecl_std::testgen_tensor_concat!([f32]);
+    cubecl_std::testgen_tensor_pad!([f32]);
+    cubecl_std::testgen_tensor_io!();
+    cubecl_std::testgen_tensor_collective!([f32]);
     cubecl_std::testgen_quantized_view!(f32);
+    cubecl_std::testgen_quantize!(f32);
 }
 
 pub mod compiler;
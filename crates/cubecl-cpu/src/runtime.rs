@@ -56,6 +56,10 @@ impl DeviceState for CpuServer {
             max_units_per_cube: u32::MAX,
             max_cube_dim,
             num_streaming_multiprocessors: None,
+            max_cubes_per_multiprocessor: None,
+            max_units_per_multiprocessor: None,
+            max_shared_memory_per_multiprocessor: None,
+            registers_per_multiprocessor: None,
             num_tensor_cores: None,
             min_tensor_cores_dim: None,
         };
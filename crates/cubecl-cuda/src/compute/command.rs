@@ -2,7 +2,8 @@ use crate::{
     CudaCompiler,
     compute::{
         MB, context::CudaContext, io::controller::PinnedMemoryManagedAllocController,
-        storage::gpu::GpuResource, stream::CudaStreamBackend, sync::Fence, valid_strides,
+        storage::gpu::GpuResource, stream::CudaStreamBackend, sync::Fence, texture::TextureRef,
+        valid_strides,
     },
 };
 use cubecl_common::{
@@ -14,7 +15,8 @@ use cubecl_core::{
     MemoryUsage,
     future::DynFut,
     server::{
-        Binding, CopyDescriptor, ExecutionError, ExecutionMode, Handle, IoError, ProfileError,
+        Binding, CopyDescriptor, ExecutionError, ExecutionMode, Handle, IoError, LaunchError,
+        ProfileError, TextureMeta,
     },
 };
 use cubecl_runtime::{
@@ -59,6 +61,27 @@ impl<'a> Command<'a> {
             })
     }
 
+    /// Retrieves the texture object for a texture binding, creating it if needed.
+    ///
+    /// # Parameters
+    ///
+    /// * `binding` - The binding of the memory holding the texels.
+    /// * `meta` - The layout of the texels.
+    pub fn texture(
+        &mut self,
+        binding: Binding,
+        meta: &TextureMeta,
+    ) -> Result<TextureRef, LaunchError> {
+        let memory = binding.memory.downgrade();
+        let resource = self.resource(binding)?;
+        // Texels are copied on the stream recording a graph, so the copy is replayed with it.
+        let stream = match self.ctx.graphs.recording_stream() {
+            Some(stream) => stream,
+            None => self.streams.current().sys,
+        };
+        self.ctx.textures.get(&resource, memory, meta, stream)
+    }
+
     /// Retrieves the gpu memory usage of the current stream.
     ///
     /// # Returns
@@ -411,6 +434,7 @@ impl<'a> Command<'a> {
     /// * `mode` - The execution mode for the current kernel.
     /// * `dispatch_count` - The number of thread blocks in the x, y, and z dimensions.
     /// * `tensor_maps` - Tensor maps for structured memory access.
+    /// * `textures` - Textures sampled by the kernel.
     /// * `resources` - GPU resources (e.g., buffers) used by the kernel.
     /// * `scalars` - Scalar arguments passed to the kernel.
     /// * `logger` - The logger to use to write compilation & runtime info.
@@ -425,7 +449,9 @@ impl<'a> Command<'a> {
         kernel: Box<dyn CubeTask<CudaCompiler>>,
         mode: ExecutionMode,
         dispatch_count: (u32, u32, u32),
+        dynamic_shared_memory: usize,
         tensor_maps: &[CUtensorMap],
+        textures: &[TextureRef],
         resources: &[GpuResource],
         scalars: &[*mut c_void],
         logger: Arc<ServerLogger>,
@@ -440,7 +466,9 @@ impl<'a> Command<'a> {
             stream,
             kernel_id,
             dispatch_count,
+            dynamic_shared_memory,
             tensor_maps,
+            textures,
             resources,
             scalars,
         );
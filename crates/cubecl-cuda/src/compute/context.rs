@@ -2,8 +2,11 @@ use cubecl_common::backtrace::BackTrace;
 use cubecl_cpp::formatter::format_cpp;
 use cubecl_cpp::{cuda::arch::CudaArchitecture, shared::CompilationOptions};
 use cubecl_runtime::compiler::CompilationError;
+use cubecl_runtime::kernel::KernelResourceUsage;
 
+use super::graph::GraphCache;
 use super::storage::gpu::GpuResource;
+use super::texture::{TextureCache, TextureRef};
 use crate::install::{cccl_include_path, include_path};
 use crate::{CudaCompiler, compute::stream::Stream};
 use cubecl_core::prelude::*;
@@ -18,7 +21,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::{ffi::CStr, os::raw::c_void};
 
-use cubecl_common::cache::{Cache, CacheOption};
+use cubecl_common::cache::{Cache, CacheFormat, CacheOption};
 
 #[derive(Debug)]
 pub(crate) struct CudaContext {
@@ -28,12 +31,14 @@ pub(crate) struct CudaContext {
     pub timestamps: TimestampProfiler,
     pub arch: CudaArchitecture,
     pub compilation_options: CompilationOptions,
+    pub textures: TextureCache,
+    pub graphs: GraphCache,
 }
 
 #[derive(Debug)]
 pub struct CompiledKernel {
     cube_dim: CubeDim,
-    shared_mem_bytes: usize,
+    pub resource_usage: KernelResourceUsage,
     func: *mut CUfunc_st,
 }
 
@@ -41,7 +46,7 @@ pub struct CompiledKernel {
 pub struct PtxCacheEntry {
     entrypoint_name: String,
     cube_dim: (u32, u32, u32),
-    shared_mem_bytes: usize,
+    resource_usage: KernelResourceUsage,
     cluster_dim: Option<(u32, u32, u32)>,
     ptx: Vec<std::ffi::c_char>,
 }
@@ -61,7 +66,10 @@ impl CudaContext {
                     let root = cache.root();
                     Some(Cache::new(
                         "ptx",
-                        CacheOption::default().name("cuda").root(root),
+                        CacheOption::default()
+                            .name("cuda")
+                            .root(root)
+                            .format(CacheFormat::Binary),
                     ))
                 } else {
                     None
@@ -70,6 +78,8 @@ impl CudaContext {
             arch,
             timestamps: TimestampProfiler::default(),
             compilation_options,
+            textures: TextureCache::default(),
+            graphs: GraphCache::default(),
         }
     }
 
@@ -86,17 +96,19 @@ impl CudaContext {
             if let Some(entry) = cache.get(&name) {
                 log::trace!("Using PTX cache");
 
-                return self.load_ptx(
-                    entry.ptx.clone(),
-                    kernel_id.clone(),
-                    entry.entrypoint_name.clone(),
-                    CubeDim {
-                        x: entry.cube_dim.0,
-                        y: entry.cube_dim.1,
-                        z: entry.cube_dim.2,
-                    },
-                    entry.shared_mem_bytes,
-                );
+                return self
+                    .load_ptx(
+                        entry.ptx.clone(),
+                        kernel_id.clone(),
+                        entry.entrypoint_name.clone(),
+                        CubeDim {
+                            x: entry.cube_dim.0,
+                            y: entry.cube_dim.1,
+                            z: entry.cube_dim.2,
+                        },
+                        entry.resource_usage.clone(),
+                    )
+                    .map(|_| ());
             }
             Some(name)
         } else {
@@ -139,8 +151,6 @@ impl CudaContext {
 
         let cluster_dim = compute_kernel.cluster_dim;
 
-        logger.log_compilation(&kernel_compiled);
-
         let ptx = unsafe {
             // I'd like to set the name to the kernel name, but keep getting UTF-8 errors so let's
             // leave it `None` for now
@@ -187,15 +197,13 @@ impl CudaContext {
             })?
         };
 
-        let repr = kernel_compiled.repr.unwrap();
-
         if let Some(cache) = &mut self.ptx_cache {
             let result = cache.insert(
                 name.unwrap(),
                 PtxCacheEntry {
                     entrypoint_name: kernel_compiled.entrypoint_name.clone(),
                     cube_dim: (cube_dim.x, cube_dim.y, cube_dim.z),
-                    shared_mem_bytes: repr.shared_memory_size(),
+                    resource_usage: kernel_compiled.resource_usage.clone(),
                     cluster_dim: cluster_dim.map(|cluster| (cluster.x, cluster.y, cluster.z)),
                     ptx: ptx.clone(),
                 },
@@ -205,13 +213,16 @@ impl CudaContext {
             }
         }
 
-        self.load_ptx(
+        kernel_compiled.resource_usage = self.load_ptx(
             ptx,
             kernel_id.clone(),
-            kernel_compiled.entrypoint_name,
+            kernel_compiled.entrypoint_name.clone(),
             cube_dim,
-            repr.shared_memory_size(),
-        )
+            kernel_compiled.resource_usage.clone(),
+        )?;
+        logger.log_compilation(&kernel_compiled);
+
+        Ok(())
     }
 
     fn load_ptx(
@@ -220,8 +231,8 @@ impl CudaContext {
         kernel_id: KernelId,
         entrypoint_name: String,
         cube_dim: CubeDim,
-        shared_mem_bytes: usize,
-    ) -> Result<(), CompilationError> {
+        resource_usage: KernelResourceUsage,
+    ) -> Result<KernelResourceUsage, CompilationError> {
         let func_name = CString::new(entrypoint_name).unwrap();
         let func = unsafe {
             let module = cudarc::driver::result::module::load_data(ptx.as_ptr() as *const _)
@@ -238,24 +249,28 @@ impl CudaContext {
             })?
         };
 
+        let resource_usage = driver_resource_usage(func, cube_dim, resource_usage);
         self.module_names.insert(
             kernel_id.clone(),
             CompiledKernel {
                 cube_dim,
-                shared_mem_bytes,
+                resource_usage: resource_usage.clone(),
                 func,
             },
         );
 
-        Ok(())
+        Ok(resource_usage)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn execute_task(
         &mut self,
         stream: &mut Stream,
         kernel_id: KernelId,
         dispatch_count: (u32, u32, u32),
+        dynamic_shared_memory: usize,
         tensor_maps: &[CUtensorMap],
+        textures: &[TextureRef],
         resources: &[GpuResource],
         scalars: &[*mut c_void],
     ) -> Result<(), String> {
@@ -263,16 +278,23 @@ impl CudaContext {
             .iter()
             .map(|map| map as *const _ as *mut c_void)
             .collect::<Vec<_>>();
+        bindings.extend(
+            textures
+                .iter()
+                .map(|texture| texture as *const _ as *mut c_void),
+        );
         bindings.extend(resources.iter().map(|memory| memory.binding));
         bindings.extend(scalars);
 
         let kernel = self.module_names.get(&kernel_id).unwrap();
         let cube_dim = kernel.cube_dim;
+        // Dynamic shared memory is placed after the static allocations.
+        let shared_mem_bytes = kernel.resource_usage.shared_memory + dynamic_shared_memory;
         unsafe {
             cudarc::driver::result::function::set_function_attribute(
                 kernel.func,
                 CUfunction_attribute::CU_FUNC_ATTRIBUTE_MAX_DYNAMIC_SHARED_SIZE_BYTES,
-                kernel.shared_mem_bytes as i32,
+                shared_mem_bytes as i32,
             )
             .map_err(|err| format!("{err:?}"))?;
             cudarc::driver::result::launch_kernel(
@@ -281,8 +303,9 @@ impl CudaContext {
                 (cube_dim.x, cube_dim.y, cube_dim.z),
                 // Shared memory is collected into a single buffer, with each shared memory being
                 // an offset pointer
-                kernel.shared_mem_bytes as u32,
-                stream.sys,
+                shared_mem_bytes as u32,
+                // Kernels of a launch graph are recorded instead of being executed.
+                self.graphs.recording_stream().unwrap_or(stream.sys),
                 &mut bindings,
             )
             .map_err(|err| format!("{err:?}"))?;
@@ -291,3 +314,35 @@ impl CudaContext {
         Ok(())
     }
 }
+
+/// Fill in the resource usage reported by the driver for a loaded function. Attributes the driver
+/// fails to report are left unset.
+fn driver_resource_usage(
+    func: *mut CUfunc_st,
+    cube_dim: CubeDim,
+    usage: KernelResourceUsage,
+) -> KernelResourceUsage {
+    let attribute = |attribute| unsafe {
+        cudarc::driver::result::function::get_function_attribute(func, attribute).ok()
+    };
+    let max_active_cubes = unsafe {
+        cudarc::driver::result::occupancy::max_active_block_per_multiprocessor(
+            func,
+            cube_dim.num_elems() as i32,
+            0,
+        )
+        .ok()
+    };
+
+    KernelResourceUsage {
+        registers: attribute(CUfunction_attribute::CU_FUNC_ATTRIBUTE_NUM_REGS).map(|it| it as u32),
+        local_memory: attribute(CUfunction_attribute::CU_FUNC_ATTRIBUTE_LOCAL_SIZE_BYTES)
+            .map(|it| it as usize),
+        max_units_per_cube: attribute(
+            CUfunction_attribute::CU_FUNC_ATTRIBUTE_MAX_THREADS_PER_BLOCK,
+        )
+        .map(|it| it as u32),
+        max_active_cubes: max_active_cubes.map(|it| it as u32),
+        ..usage
+    }
+}
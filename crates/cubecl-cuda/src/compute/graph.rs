@@ -0,0 +1,139 @@
+use std::{collections::HashMap, ptr};
+
+use cubecl_common::backtrace::BackTrace;
+use cubecl_core::server::LaunchError;
+use cubecl_runtime::id::KernelId;
+use cudarc::driver::sys::{
+    CUgraph, CUgraphExec, CUgraphExecUpdateResultInfo, CUresult, CUstream, CUstreamCaptureMode,
+    cuGraphDestroy, cuGraphExecDestroy, cuGraphExecUpdate_v2, cuGraphInstantiateWithFlags,
+    cuGraphLaunch, cuStreamBeginCapture_v2, cuStreamEndCapture,
+};
+
+/// Native graphs replaying the [launch graphs](cubecl_runtime::graph::LaunchGraph) of a context.
+///
+/// The kernels of a launch graph are recorded on a dedicated capture stream, so the uploads of
+/// their metadata and scalars still happen on the stream of the launch before the graph runs.
+/// Each replay records a new graph, which updates the executable graph instantiated for the same
+/// kernels when possible, since updating the parameters of the kernels is much cheaper than
+/// instantiating a graph.
+#[derive(Debug, Default)]
+pub(crate) struct GraphCache {
+    capture: Option<CUstream>,
+    recording: bool,
+    executables: HashMap<Vec<KernelId>, CUgraphExec>,
+}
+
+impl GraphCache {
+    /// The stream recording the kernels of a graph, if a graph is being recorded.
+    pub fn recording_stream(&self) -> Option<CUstream> {
+        self.capture.filter(|_| self.recording)
+    }
+
+    /// Start recording the kernels launched on the context.
+    pub fn begin(&mut self) -> Result<(), LaunchError> {
+        let stream = match self.capture {
+            Some(stream) => stream,
+            None => {
+                let stream = cudarc::driver::result::stream::create(
+                    cudarc::driver::result::stream::StreamKind::NonBlocking,
+                )
+                .map_err(|err| launch_error("create the capture stream", err))?;
+                *self.capture.insert(stream)
+            }
+        };
+
+        // The kernels can be compiled and the memory pools can allocate while recording.
+        unsafe {
+            cuStreamBeginCapture_v2(stream, CUstreamCaptureMode::CU_STREAM_CAPTURE_MODE_RELAXED)
+                .result()
+                .map_err(|err| launch_error("begin the capture", err))?;
+        }
+        self.recording = true;
+
+        Ok(())
+    }
+
+    /// Stop recording, and launch the recorded kernels of the given launch graph on `stream`.
+    pub fn launch(&mut self, kernels: Vec<KernelId>, stream: CUstream) -> Result<(), LaunchError> {
+        let graph = self.end()?;
+        let result = unsafe { self.launch_graph(graph, kernels, stream) };
+        unsafe { cuGraphDestroy(graph) };
+
+        result
+    }
+
+    /// Stop recording and discard the recorded kernels.
+    pub fn abort(&mut self) {
+        if let Ok(graph) = self.end() {
+            unsafe { cuGraphDestroy(graph) };
+        }
+    }
+
+    fn end(&mut self) -> Result<CUgraph, LaunchError> {
+        let stream = self
+            .recording_stream()
+            .ok_or_else(|| LaunchError::Unknown {
+                reason: "No launch graph is being recorded".into(),
+                backtrace: BackTrace::capture(),
+            })?;
+        self.recording = false;
+
+        let mut graph = ptr::null_mut();
+        unsafe {
+            cuStreamEndCapture(stream, &mut graph)
+                .result()
+                .map_err(|err| launch_error("end the capture", err))?;
+        }
+
+        Ok(graph)
+    }
+
+    unsafe fn launch_graph(
+        &mut self,
+        graph: CUgraph,
+        kernels: Vec<KernelId>,
+        stream: CUstream,
+    ) -> Result<(), LaunchError> {
+        let updated = match self.executables.get(&kernels) {
+            Some(exec) => {
+                let mut info: CUgraphExecUpdateResultInfo = unsafe { std::mem::zeroed() };
+                match unsafe { cuGraphExecUpdate_v2(*exec, graph, &mut info) } {
+                    CUresult::CUDA_SUCCESS => Some(*exec),
+                    _ => {
+                        unsafe { cuGraphExecDestroy(*exec) };
+                        self.executables.remove(&kernels);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let exec = match updated {
+            Some(exec) => exec,
+            None => {
+                let mut exec = ptr::null_mut();
+                unsafe {
+                    cuGraphInstantiateWithFlags(&mut exec, graph, 0)
+                        .result()
+                        .map_err(|err| launch_error("instantiate the graph", err))?;
+                }
+                self.executables.insert(kernels, exec);
+                exec
+            }
+        };
+
+        unsafe {
+            cuGraphLaunch(exec, stream)
+                .result()
+                .map_err(|err| launch_error("launch the graph", err))
+        }
+    }
+}
+
+fn launch_error(action: &str, err: impl core::fmt::Debug) -> LaunchError {
+    LaunchError::Unknown {
+        reason: format!("Failed to {action}: {err:?}"),
+        backtrace: BackTrace::capture(),
+    }
+}
@@ -1,9 +1,11 @@
 pub(crate) mod command;
 pub(crate) mod context;
+pub(crate) mod graph;
 pub(crate) mod io;
 pub(crate) mod storage;
 pub(crate) mod stream;
 pub(crate) mod sync;
+pub(crate) mod texture;
 
 mod server;
 
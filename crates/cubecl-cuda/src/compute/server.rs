@@ -18,18 +18,20 @@ use cubecl_core::{
     prelude::*,
     server::{
         Allocation, AllocationDescriptor, AllocationKind, Binding, Bindings, CopyDescriptor,
-        ExecutionError, IoError, LaunchError, ProfileError, ProfilingToken, ServerCommunication,
-        ServerUtilities, TensorMapBinding, TensorMapMeta,
+        ExecutionError, Handle, IoError, LaunchError, ProfileError, ProfilingToken,
+        ServerCommunication, ServerUtilities, TensorMapBinding, TensorMapMeta,
     },
 };
 use cubecl_runtime::{
     compiler::CubeTask,
     config::GlobalConfig,
+    graph::GraphLaunch,
+    kernel::KernelResourceUsage,
     logging::ServerLogger,
     memory_management::{MemoryAllocationMode, MemoryUsage, offset_handles},
     server::{self, ComputeServer},
     storage::BindingResource,
-    stream::MultiStream,
+    stream::{Event, MultiStream, MultiStreamEvent},
 };
 use cudarc::driver::sys::{
     CUcontext, CUresult, CUtensorMapDataType, CUtensorMapFloatOOBfill, CUtensorMapInterleave,
@@ -155,6 +157,196 @@ impl ComputeServer for CudaServer {
         mode: ExecutionMode,
         stream_id: StreamId,
     ) -> Result<(), LaunchError> {
+        // The uploaded metadata and scalars can be released once the kernel is enqueued.
+        unsafe { self.launch_kernel(kernel, count, bindings, mode, stream_id) }?;
+
+        Ok(())
+    }
+
+    unsafe fn launch_graph(
+        &mut self,
+        launches: Vec<GraphLaunch<Self::Kernel>>,
+        stream_id: StreamId,
+    ) -> Result<(), LaunchError> {
+        // Dynamic cube counts are read before launching the kernel, so they can't be written by
+        // a previous kernel of the same graph.
+        if launches
+            .iter()
+            .any(|launch| matches!(launch.count, CubeCount::Dynamic(_)))
+        {
+            for launch in launches {
+                unsafe {
+                    self.launch(
+                        launch.kernel,
+                        launch.count,
+                        launch.bindings,
+                        launch.mode,
+                        stream_id,
+                    )?
+                };
+            }
+            return Ok(());
+        }
+
+        unsafe {
+            cudarc::driver::result::ctx::set_current(self.ctx.context).unwrap();
+        };
+        self.ctx.graphs.begin()?;
+
+        let mut kernels = Vec::with_capacity(launches.len());
+        let mut uploads = Vec::new();
+        for launch in launches {
+            let mut kernel_id = launch.kernel.id();
+            kernel_id.mode(launch.mode);
+            kernels.push(kernel_id);
+
+            let launched = unsafe {
+                self.launch_kernel(
+                    launch.kernel,
+                    launch.count,
+                    launch.bindings,
+                    launch.mode,
+                    stream_id,
+                )
+            };
+            match launched {
+                Ok(handles) => uploads.extend(handles),
+                Err(err) => {
+                    self.ctx.graphs.abort();
+                    return Err(err);
+                }
+            }
+        }
+
+        // The uploads of every kernel must stay alive until the graph is enqueued after them.
+        let stream = self.command_no_inputs(stream_id).streams.current().sys;
+        self.ctx.graphs.launch(kernels, stream)?;
+        core::mem::drop(uploads);
+
+        Ok(())
+    }
+
+    fn flush(&mut self, _stream_id: StreamId) {}
+
+    fn sync(&mut self, stream_id: StreamId) -> DynFut<Result<(), ExecutionError>> {
+        let mut command = self.command_no_inputs(stream_id);
+        command.sync()
+    }
+
+    fn record_event(&mut self, stream_id: StreamId) -> Result<Event, ExecutionError> {
+        unsafe {
+            cudarc::driver::result::ctx::set_current(self.ctx.context).unwrap();
+        };
+        let event = self.streams.record_event(stream_id);
+
+        Ok(Event::new(stream_id, event))
+    }
+
+    fn wait_event(&mut self, event: &Event, stream_id: StreamId) -> Result<(), ExecutionError> {
+        let Some(inner) = event.server_event::<MultiStreamEvent<CudaStreamBackend>>() else {
+            // Recorded by another kind of server, the device can't wait on it.
+            return event.wait();
+        };
+        unsafe {
+            cudarc::driver::result::ctx::set_current(self.ctx.context).unwrap();
+        };
+        self.streams.wait_event(stream_id, inner);
+
+        Ok(())
+    }
+
+    fn start_profile(&mut self, stream_id: StreamId) -> ProfilingToken {
+        if let Err(err) = cubecl_common::future::block_on(self.sync(stream_id)) {
+            log::warn!("{err}");
+        }
+
+        self.ctx.timestamps.start()
+    }
+
+    fn end_profile(
+        &mut self,
+        stream_id: StreamId,
+        token: ProfilingToken,
+    ) -> Result<ProfileDuration, ProfileError> {
+        if let Err(err) = cubecl_common::future::block_on(self.sync(stream_id)) {
+            self.ctx.timestamps.error(err.into());
+        }
+        self.ctx.timestamps.stop(token)
+    }
+
+    fn get_resource(
+        &mut self,
+        binding: server::Binding,
+        stream_id: StreamId,
+    ) -> BindingResource<GpuResource> {
+        let mut command = self.command(stream_id, [&binding].into_iter());
+
+        BindingResource::new(
+            binding.clone(),
+            command.resource(binding).expect("Failed to find resource"),
+        )
+    }
+
+    fn memory_usage(&mut self, stream_id: StreamId) -> MemoryUsage {
+        let mut command = self.command_no_inputs(stream_id);
+        command.memory_usage()
+    }
+
+    fn memory_cleanup(&mut self, stream_id: StreamId) {
+        let mut command = self.command_no_inputs(stream_id);
+        command.memory_cleanup()
+    }
+
+    fn allocation_mode(&mut self, mode: MemoryAllocationMode, stream_id: StreamId) {
+        let mut command = self.command_no_inputs(stream_id);
+        command.allocation_mode(mode)
+    }
+
+    fn resource_usage(&mut self, kernel_id: &KernelId) -> Option<KernelResourceUsage> {
+        let kernel = self.ctx.module_names.get(kernel_id)?;
+        Some(kernel.resource_usage.clone())
+    }
+}
+
+impl ServerCommunication for CudaServer {
+    const SERVER_COMM_ENABLED: bool = true;
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(server_src, server_dst, src))
+    )]
+    fn copy(
+        server_src: &mut Self,
+        server_dst: &mut Self,
+        src: CopyDescriptor<'_>,
+        stream_id_src: StreamId,
+        stream_id_dst: StreamId,
+    ) -> Result<Allocation, IoError> {
+        if server_src.peer_activated {
+            Self::change_server_peer(server_src, server_dst, src, stream_id_src, stream_id_dst)
+        } else {
+            Self::change_server_serialized(
+                server_src,
+                server_dst,
+                src,
+                stream_id_src,
+                stream_id_dst,
+            )
+        }
+    }
+}
+
+impl CudaServer {
+    /// Launch the kernel, and return the handles of the uploaded metadata and scalars, which must
+    /// stay alive until the kernel is enqueued on the stream.
+    unsafe fn launch_kernel(
+        &mut self,
+        kernel: <Self as ComputeServer>::Kernel,
+        count: CubeCount,
+        bindings: Bindings,
+        mode: ExecutionMode,
+        stream_id: StreamId,
+    ) -> Result<Vec<Handle>, LaunchError> {
         let mut kernel_id = kernel.id();
         let logger = self.streams.logger.clone();
         kernel_id.mode(mode);
@@ -365,110 +557,34 @@ impl ComputeServer for CudaServer {
             tensor_maps.push(binding);
         }
 
-        resources.extend(
-            scalar_bindings
-                .into_iter()
-                .map(|s| command.resource(s.binding()).expect("Resource to exist")),
-        );
+        let textures = bindings
+            .textures
+            .into_iter()
+            .map(|texture| command.texture(texture.binding, &texture.meta))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        resources.extend(scalar_bindings.iter().map(|s| {
+            command
+                .resource(s.clone().binding())
+                .expect("Resource to exist")
+        }));
 
         command.kernel(
             kernel_id,
             kernel,
             mode,
             count,
+            bindings.dynamic_shared_memory,
             &tensor_maps,
+            &textures,
             &resources,
             &scalars,
             logger,
         )?;
 
-        Ok(())
-    }
-
-    fn flush(&mut self, _stream_id: StreamId) {}
-
-    fn sync(&mut self, stream_id: StreamId) -> DynFut<Result<(), ExecutionError>> {
-        let mut command = self.command_no_inputs(stream_id);
-        command.sync()
-    }
-
-    fn start_profile(&mut self, stream_id: StreamId) -> ProfilingToken {
-        if let Err(err) = cubecl_common::future::block_on(self.sync(stream_id)) {
-            log::warn!("{err}");
-        }
-
-        self.ctx.timestamps.start()
-    }
-
-    fn end_profile(
-        &mut self,
-        stream_id: StreamId,
-        token: ProfilingToken,
-    ) -> Result<ProfileDuration, ProfileError> {
-        if let Err(err) = cubecl_common::future::block_on(self.sync(stream_id)) {
-            self.ctx.timestamps.error(err.into());
-        }
-        self.ctx.timestamps.stop(token)
-    }
-
-    fn get_resource(
-        &mut self,
-        binding: server::Binding,
-        stream_id: StreamId,
-    ) -> BindingResource<GpuResource> {
-        let mut command = self.command(stream_id, [&binding].into_iter());
-
-        BindingResource::new(
-            binding.clone(),
-            command.resource(binding).expect("Failed to find resource"),
-        )
-    }
-
-    fn memory_usage(&mut self, stream_id: StreamId) -> MemoryUsage {
-        let mut command = self.command_no_inputs(stream_id);
-        command.memory_usage()
-    }
-
-    fn memory_cleanup(&mut self, stream_id: StreamId) {
-        let mut command = self.command_no_inputs(stream_id);
-        command.memory_cleanup()
-    }
-
-    fn allocation_mode(&mut self, mode: MemoryAllocationMode, stream_id: StreamId) {
-        let mut command = self.command_no_inputs(stream_id);
-        command.allocation_mode(mode)
-    }
-}
-
-impl ServerCommunication for CudaServer {
-    const SERVER_COMM_ENABLED: bool = true;
-
-    #[cfg_attr(
-        feature = "tracing",
-        tracing::instrument(level = "trace", skip(server_src, server_dst, src))
-    )]
-    fn copy(
-        server_src: &mut Self,
-        server_dst: &mut Self,
-        src: CopyDescriptor<'_>,
-        stream_id_src: StreamId,
-        stream_id_dst: StreamId,
-    ) -> Result<Allocation, IoError> {
-        if server_src.peer_activated {
-            Self::change_server_peer(server_src, server_dst, src, stream_id_src, stream_id_dst)
-        } else {
-            Self::change_server_serialized(
-                server_src,
-                server_dst,
-                src,
-                stream_id_src,
-                stream_id_dst,
-            )
-        }
+        Ok(scalar_bindings)
     }
-}
 
-impl CudaServer {
     /// Create a new cuda server.
     pub(crate) fn new(
         ctx: CudaContext,
@@ -680,6 +796,8 @@ fn elem_to_tensor_map_type(ty: StorageType) -> CUtensorMapDataType {
             FloatKind::Flex32 | FloatKind::F32 => CU_TENSOR_MAP_DATA_TYPE_FLOAT32,
             FloatKind::TF32 => CU_TENSOR_MAP_DATA_TYPE_TFLOAT32,
             FloatKind::F64 => CU_TENSOR_MAP_DATA_TYPE_FLOAT64,
+            // A pair of `f32`, only loaded as raw bits. Zero bits are `0.0` for both halves.
+            FloatKind::DF64 => CU_TENSOR_MAP_DATA_TYPE_UINT64,
         },
         StorageType::Scalar(ElemType::Int(kind)) => match kind {
             // UInt is fine because zero bits and size is the same between both
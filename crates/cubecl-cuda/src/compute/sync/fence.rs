@@ -1,6 +1,9 @@
 use cubecl_common::backtrace::BackTrace;
 use cubecl_core::server::ExecutionError;
-use cudarc::driver::sys::{CUevent_flags, CUevent_st, CUevent_wait_flags, CUstream_st};
+use cudarc::driver::{
+    DriverError,
+    sys::{CUevent_flags, CUevent_st, CUevent_wait_flags, CUresult, CUstream_st},
+};
 
 /// A fence is simply an [event](CUevent_st) created on a [stream](CUevent_st) that you can wait
 /// until completion.
@@ -76,4 +79,42 @@ impl Fence {
             cudarc::driver::result::event::destroy(self.event).unwrap();
         }
     }
+
+    /// Same as [wait_sync](Self::wait_sync), but keeps the [Fence] alive so it can be waited on
+    /// again.
+    pub fn wait_sync_ref(&self) -> Result<(), ExecutionError> {
+        unsafe {
+            cudarc::driver::result::event::synchronize(self.event).map_err(|err| {
+                ExecutionError::Generic {
+                    reason: format!("{err:?}"),
+                    backtrace: BackTrace::capture(),
+                }
+            })
+        }
+    }
+
+    /// Same as [wait_async](Self::wait_async), but keeps the [Fence] alive so it can be waited
+    /// on again.
+    pub fn wait_async_ref(&self, stream: *mut CUstream_st) {
+        unsafe {
+            cudarc::driver::result::stream::wait_event(
+                stream,
+                self.event,
+                CUevent_wait_flags::CU_EVENT_WAIT_DEFAULT,
+            )
+            .unwrap();
+        }
+    }
+
+    /// Returns whether the [Fence] is reached, without blocking.
+    pub fn is_reached(&self) -> Result<bool, ExecutionError> {
+        match unsafe { cudarc::driver::result::event::query(self.event) } {
+            Ok(()) => Ok(true),
+            Err(DriverError(CUresult::CUDA_ERROR_NOT_READY)) => Ok(false),
+            Err(err) => Err(ExecutionError::Generic {
+                reason: format!("{err:?}"),
+                backtrace: BackTrace::capture(),
+            }),
+        }
+    }
 }
@@ -0,0 +1,254 @@
+use std::collections::{HashMap, hash_map::Entry};
+
+use cubecl_common::backtrace::BackTrace;
+use cubecl_core::{
+    ir::{AddressMode, ElemType, FilterMode, FloatKind, Sampler, StorageType, TextureDim},
+    server::{LaunchError, TextureMeta},
+};
+use cubecl_runtime::id::WeakBindingRef;
+use cudarc::driver::sys::{
+    CU_TRSF_NORMALIZED_COORDINATES, CUDA_ARRAY3D_DESCRIPTOR, CUDA_MEMCPY3D, CUDA_RESOURCE_DESC,
+    CUDA_TEXTURE_DESC, CUaddress_mode, CUarray, CUarray_format, CUfilter_mode, CUmemorytype,
+    CUresourcetype, CUstream, CUtexObject, cuArray3DCreate_v2, cuArrayDestroy, cuMemcpy3DAsync_v2,
+    cuTexObjectCreate, cuTexObjectDestroy,
+};
+
+use super::{storage::gpu::GpuResource, sync::Fence};
+
+/// Kernel parameter for a texture, matching `texture_ref` in the generated source.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TextureRef {
+    texels: CUtexObject,
+    samplers: [CUtexObject; Sampler::COUNT],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct TextureKey {
+    ptr: u64,
+    dim: TextureDim,
+    extent: [usize; 3],
+    format: CUarray_format,
+    channels: u32,
+}
+
+/// A CUDA array holding a copy of the texels, with the texture objects reading it.
+#[derive(Debug)]
+struct Texture {
+    array: CUarray,
+    texture: TextureRef,
+    memory: WeakBindingRef,
+    stream: CUstream,
+}
+
+/// Textures created for launches so far.
+///
+/// Hardware filtering reads CUDA arrays, so each texture owns an array the bound buffer is copied
+/// into on every launch, and a texture object per sampler state. Textures are reused while the
+/// buffer backing them is alive, and destroyed once it is freed.
+#[derive(Debug, Default)]
+pub(crate) struct TextureCache {
+    textures: HashMap<TextureKey, Texture>,
+}
+
+impl TextureCache {
+    /// Get the kernel parameter for the texture described by `meta` over `resource`, copying the
+    /// texels on `stream`.
+    pub fn get(
+        &mut self,
+        resource: &GpuResource,
+        memory: WeakBindingRef,
+        meta: &TextureMeta,
+        stream: CUstream,
+    ) -> Result<TextureRef, LaunchError> {
+        self.evict_freed()?;
+
+        let format =
+            storage_to_array_format(meta.storage_ty).ok_or_else(|| LaunchError::Unknown {
+                reason: format!("Unsupported texture format {}", meta.storage_ty),
+                backtrace: BackTrace::capture(),
+            })?;
+        let key = TextureKey {
+            ptr: resource.ptr,
+            dim: meta.dim,
+            extent: meta.extent,
+            format,
+            channels: meta.channels as u32,
+        };
+
+        let texture = match self.textures.entry(key) {
+            Entry::Occupied(entry) => {
+                let texture = entry.into_mut();
+                texture.memory = memory;
+                texture.stream = stream;
+                texture
+            }
+            Entry::Vacant(entry) => entry.insert(create_texture(&key, memory, stream)?),
+        };
+        copy_texels(&key, meta.storage_ty.size(), texture.array, stream)?;
+
+        Ok(texture.texture)
+    }
+
+    /// Destroy the textures whose buffer was freed, once the kernels using them are done.
+    fn evict_freed(&mut self) -> Result<(), LaunchError> {
+        let freed = self
+            .textures
+            .iter()
+            .filter(|(_, texture)| texture.memory.is_free())
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>();
+
+        for key in freed {
+            let texture = self.textures.remove(&key).unwrap();
+            Fence::new(texture.stream)
+                .wait_sync()
+                .map_err(|err| LaunchError::Unknown {
+                    reason: format!("{err:?}"),
+                    backtrace: BackTrace::capture(),
+                })?;
+            unsafe { destroy_texture(&texture) };
+        }
+
+        Ok(())
+    }
+}
+
+fn create_texture(
+    key: &TextureKey,
+    memory: WeakBindingRef,
+    stream: CUstream,
+) -> Result<Texture, LaunchError> {
+    let [width, height, depth] = key.extent;
+    let descriptor = CUDA_ARRAY3D_DESCRIPTOR {
+        Width: width,
+        Height: height,
+        // A depth of zero allocates a 2D array.
+        Depth: match key.dim {
+            TextureDim::D2 => 0,
+            TextureDim::D3 => depth,
+        },
+        Format: key.format,
+        NumChannels: key.channels,
+        Flags: 0,
+    };
+
+    unsafe {
+        let mut array = std::ptr::null_mut();
+        cuArray3DCreate_v2(&mut array, &descriptor)
+            .result()
+            .map_err(launch_error)?;
+
+        let mut res_desc: CUDA_RESOURCE_DESC = std::mem::zeroed();
+        res_desc.resType = CUresourcetype::CU_RESOURCE_TYPE_ARRAY;
+        res_desc.res.array.hArray = array;
+
+        let mut texture = TextureRef {
+            texels: 0,
+            samplers: [0; Sampler::COUNT],
+        };
+        // Loads use unnormalized coordinates, which can only be clamped.
+        let mut result =
+            create_texture_object(&res_desc, Sampler::nearest(), false, &mut texture.texels);
+        for (sampler, object) in Sampler::all().zip(texture.samplers.iter_mut()) {
+            result = result.and_then(|_| create_texture_object(&res_desc, sampler, true, object));
+        }
+
+        let texture = Texture {
+            array,
+            texture,
+            memory,
+            stream,
+        };
+        if let Err(err) = result {
+            destroy_texture(&texture);
+            return Err(err);
+        }
+        Ok(texture)
+    }
+}
+
+unsafe fn create_texture_object(
+    res_desc: &CUDA_RESOURCE_DESC,
+    sampler: Sampler,
+    normalized: bool,
+    object: &mut CUtexObject,
+) -> Result<(), LaunchError> {
+    unsafe {
+        let mut tex_desc: CUDA_TEXTURE_DESC = std::mem::zeroed();
+        tex_desc.addressMode = [address_mode(sampler.address); 3];
+        tex_desc.filterMode = match sampler.filter {
+            FilterMode::Nearest => CUfilter_mode::CU_TR_FILTER_MODE_POINT,
+            FilterMode::Linear => CUfilter_mode::CU_TR_FILTER_MODE_LINEAR,
+        };
+        if normalized {
+            tex_desc.flags = CU_TRSF_NORMALIZED_COORDINATES;
+        }
+
+        cuTexObjectCreate(object, res_desc, &tex_desc, std::ptr::null())
+            .result()
+            .map_err(launch_error)
+    }
+}
+
+/// Copy the contiguous texels of the buffer into the array of the texture.
+fn copy_texels(
+    key: &TextureKey,
+    elem_size: usize,
+    array: CUarray,
+    stream: CUstream,
+) -> Result<(), LaunchError> {
+    let [width, height, depth] = key.extent;
+    let row_size = width * key.channels as usize * elem_size;
+
+    unsafe {
+        let mut copy: CUDA_MEMCPY3D = std::mem::zeroed();
+        copy.srcMemoryType = CUmemorytype::CU_MEMORYTYPE_DEVICE;
+        copy.srcDevice = key.ptr;
+        copy.srcPitch = row_size;
+        copy.srcHeight = height;
+        copy.dstMemoryType = CUmemorytype::CU_MEMORYTYPE_ARRAY;
+        copy.dstArray = array;
+        copy.WidthInBytes = row_size;
+        copy.Height = height;
+        copy.Depth = depth;
+
+        cuMemcpy3DAsync_v2(&copy, stream)
+            .result()
+            .map_err(launch_error)
+    }
+}
+
+unsafe fn destroy_texture(texture: &Texture) {
+    unsafe {
+        for object in core::iter::once(texture.texture.texels).chain(texture.texture.samplers) {
+            if object != 0 {
+                cuTexObjectDestroy(object);
+            }
+        }
+        cuArrayDestroy(texture.array);
+    }
+}
+
+fn address_mode(mode: AddressMode) -> CUaddress_mode {
+    match mode {
+        AddressMode::ClampToEdge => CUaddress_mode::CU_TR_ADDRESS_MODE_CLAMP,
+        AddressMode::Repeat => CUaddress_mode::CU_TR_ADDRESS_MODE_WRAP,
+        AddressMode::MirrorRepeat => CUaddress_mode::CU_TR_ADDRESS_MODE_MIRROR,
+    }
+}
+
+fn launch_error(err: cudarc::driver::DriverError) -> LaunchError {
+    LaunchError::Unknown {
+        reason: format!("{err:?}"),
+        backtrace: BackTrace::capture(),
+    }
+}
+
+fn storage_to_array_format(ty: StorageType) -> Option<CUarray_format> {
+    match ty.elem_type() {
+        ElemType::Float(FloatKind::F32) => Some(CUarray_format::CU_AD_FORMAT_FLOAT),
+        ElemType::Float(FloatKind::F16) => Some(CUarray_format::CU_AD_FORMAT_HALF),
+        _ => None,
+    }
+}
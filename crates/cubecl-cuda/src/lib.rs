@@ -76,5 +76,12 @@ mod tests {
     cubecl_core::testgen_all!(f32: [f16, bf16, f32, f64], i32: [i8, i16, i32, i64], u32: [u8, u16, u32, u64]);
     cubecl_std::testgen!();
     cubecl_std::testgen_tensor_identity!([f16, bf16, f32, u32]);
+    cubecl_std::testgen_tensor_indexing!([f16, f32, u32]);
+    cubecl_std::testgen_tensor_histogram!([f16, f32]);
+    cubecl_std::testgen_tensor_topk!([f16, f32]);
+    cubecl_std::testgen_tensor_concat!([f16, f32]);
+    cubecl_std::testgen_tensor_pad!([f16, f32]);
+    cubecl_std::testgen_tensor_io!();
     cubecl_std::testgen_quantized_view!(f16);
+    cubecl_std::testgen_quantize!(f16);
 }
@@ -130,6 +130,30 @@ impl DeviceState for CudaServer {
                 get_attribute(device_ptr, CU_DEVICE_ATTRIBUTE_MULTIPROCESSOR_COUNT).unwrap() as u32,
             );
             let num_tensor_cores = tensor_cores_per_sm(arch_version);
+            let max_cubes_per_multiprocessor = get_attribute(
+                device_ptr,
+                CU_DEVICE_ATTRIBUTE_MAX_BLOCKS_PER_MULTIPROCESSOR,
+            )
+            .ok()
+            .map(|it| it as u32);
+            let max_units_per_multiprocessor = get_attribute(
+                device_ptr,
+                CU_DEVICE_ATTRIBUTE_MAX_THREADS_PER_MULTIPROCESSOR,
+            )
+            .ok()
+            .map(|it| it as u32);
+            let max_shared_memory_per_multiprocessor = get_attribute(
+                device_ptr,
+                CU_DEVICE_ATTRIBUTE_MAX_SHARED_MEMORY_PER_MULTIPROCESSOR,
+            )
+            .ok()
+            .map(|it| it as usize);
+            let registers_per_multiprocessor = get_attribute(
+                device_ptr,
+                CU_DEVICE_ATTRIBUTE_MAX_REGISTERS_PER_MULTIPROCESSOR,
+            )
+            .ok()
+            .map(|it| it as u32);
 
             comp_opts.warp_size = warp_size;
 
@@ -143,6 +167,10 @@ impl DeviceState for CudaServer {
                 max_units_per_cube: max_threads,
                 max_cube_dim,
                 num_streaming_multiprocessors,
+                max_cubes_per_multiprocessor,
+                max_units_per_multiprocessor,
+                max_shared_memory_per_multiprocessor,
+                registers_per_multiprocessor,
                 num_tensor_cores,
                 min_tensor_cores_dim: if supported_wmma_combinations.is_empty() {
                     None
@@ -161,6 +189,7 @@ impl DeviceState for CudaServer {
         );
         register_supported_types(&mut device_props);
         device_props.register_type_usage(ElemType::Float(FloatKind::TF32), TypeUsage::Conversion);
+        device_props.register_semantic_type(SemanticType::Texture);
         if arch_version >= 60 {
             device_props.register_type_usage(
                 StorageType::Atomic(ElemType::Float(FloatKind::F64)),
@@ -265,6 +294,7 @@ impl DeviceState for CudaServer {
         }
 
         device_props.features.dynamic_line_size = true;
+        device_props.features.dynamic_shared_memory = true;
         device_props.features.alignment = true;
         device_props.features.plane.insert(Plane::Ops);
         device_props
@@ -3,7 +3,8 @@ use cubecl_core::{
     MemoryUsage,
     future::DynFut,
     server::{
-        Binding, CopyDescriptor, ExecutionError, ExecutionMode, Handle, IoError, ProfileError,
+        Binding, CopyDescriptor, ExecutionError, ExecutionMode, Handle, IoError, LaunchError,
+        ProfileError, TextureMeta,
     },
 };
 use cubecl_hip_sys::{
@@ -23,7 +24,7 @@ use crate::{
     compute::{
         MB, context::HipContext, fence::Fence, gpu::GpuResource,
         io::controller::PinnedMemoryManagedAllocController, stream::HipStreamBackend,
-        valid_strides,
+        texture::TextureRef, valid_strides,
     },
     runtime::HipCompiler,
 };
@@ -58,6 +59,27 @@ impl<'a> Command<'a> {
             })
     }
 
+    /// Retrieves the texture object for a texture binding, creating it if needed.
+    ///
+    /// # Parameters
+    ///
+    /// * `binding` - The binding of the memory holding the texels.
+    /// * `meta` - The layout of the texels.
+    pub fn texture(
+        &mut self,
+        binding: Binding,
+        meta: &TextureMeta,
+    ) -> Result<TextureRef, LaunchError> {
+        let memory = binding.memory.downgrade();
+        let resource = self.resource(binding)?;
+        // Texels are copied on the stream recording a graph, so the copy is replayed with it.
+        let stream = match self.ctx.graphs.recording_stream() {
+            Some(stream) => stream,
+            None => self.streams.current().sys,
+        };
+        self.ctx.textures.get(&resource, memory, meta, stream)
+    }
+
     /// Retrieves the gpu memory usage of the current stream.
     ///
     /// # Returns
@@ -401,12 +423,15 @@ impl<'a> Command<'a> {
     /// # Panics
     ///
     /// * If the execution fails, with an error message or profiling error.
+    #[allow(clippy::too_many_arguments)]
     pub fn kernel(
         &mut self,
         kernel_id: KernelId,
         kernel: Box<dyn CubeTask<HipCompiler>>,
         mode: ExecutionMode,
         dispatch_count: (u32, u32, u32),
+        dynamic_shared_memory: usize,
+        textures: &[TextureRef],
         resources: &[GpuResource],
         logger: Arc<ServerLogger>,
     ) -> Result<(), CompilationError> {
@@ -416,9 +441,14 @@ impl<'a> Command<'a> {
 
         let stream = self.streams.current();
 
-        let result = self
-            .ctx
-            .execute_task(stream, kernel_id, dispatch_count, resources);
+        let result = self.ctx.execute_task(
+            stream,
+            kernel_id,
+            dispatch_count,
+            dynamic_shared_memory,
+            textures,
+            resources,
+        );
 
         if let Err(err) = result {
             match self.ctx.timestamps.is_empty() {
@@ -1,14 +1,18 @@
+use super::graph::GraphCache;
 use super::storage::gpu::GpuResource;
+use super::texture::{TextureCache, TextureRef};
 use crate::compute::stream::Stream;
 use crate::runtime::HipCompiler;
 use cubecl_common::backtrace::BackTrace;
 use cubecl_common::cache::Cache;
+use cubecl_common::cache::CacheFormat;
 use cubecl_common::cache::CacheOption;
 use cubecl_core::prelude::*;
 use cubecl_cpp::formatter::format_cpp;
 use cubecl_cpp::shared::CompilationOptions;
 use cubecl_hip_sys::{HIP_SUCCESS, get_hip_include_path, hiprtcResult_HIPRTC_SUCCESS};
 use cubecl_runtime::compiler::CompilationError;
+use cubecl_runtime::kernel::KernelResourceUsage;
 use cubecl_runtime::timestamp_profiler::TimestampProfiler;
 use cubecl_runtime::{compiler::CubeTask, logging::ServerLogger};
 use serde::Deserialize;
@@ -16,6 +20,7 @@ use serde::Serialize;
 use std::collections::HashMap;
 use std::ffi::CStr;
 use std::ffi::CString;
+use std::ffi::c_void;
 use std::sync::Arc;
 
 #[derive(Debug)]
@@ -24,6 +29,8 @@ pub(crate) struct HipContext {
     pub timestamps: TimestampProfiler,
     pub compilation_options: CompilationOptions,
     pub compilation_cache: Option<Cache<String, CompilationCacheEntry>>,
+    pub textures: TextureCache,
+    pub graphs: GraphCache,
 }
 
 #[derive(Debug)]
@@ -31,14 +38,14 @@ pub struct HipCompiledKernel {
     _module: cubecl_hip_sys::hipModule_t,
     func: cubecl_hip_sys::hipFunction_t,
     cube_dim: CubeDim,
-    shared_mem_bytes: usize,
+    pub resource_usage: KernelResourceUsage,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct CompilationCacheEntry {
     entrypoint_name: String,
     cube_dim: (u32, u32, u32),
-    shared_mem_bytes: usize,
+    resource_usage: KernelResourceUsage,
     binary: Vec<i8>,
 }
 
@@ -54,12 +61,17 @@ impl HipContext {
                     let root = cache.root();
                     Some(Cache::new(
                         "hip-kernel",
-                        CacheOption::default().name("hip").root(root),
+                        CacheOption::default()
+                            .name("hip")
+                            .root(root)
+                            .format(CacheFormat::Binary),
                     ))
                 } else {
                     None
                 }
             },
+            textures: TextureCache::default(),
+            graphs: GraphCache::default(),
         }
     }
 
@@ -84,7 +96,7 @@ impl HipContext {
                         y: entry.cube_dim.1,
                         z: entry.cube_dim.2,
                     },
-                    entry.shared_mem_bytes,
+                    entry.resource_usage.clone(),
                 )?;
                 return Ok(());
             }
@@ -109,7 +121,6 @@ impl HipContext {
                 jitc_kernel.source = formatted;
             }
         }
-        logger.log_compilation(&jitc_kernel);
 
         // Create HIP Program
         let program = unsafe {
@@ -222,8 +233,6 @@ impl HipContext {
             }
         }
 
-        let repr = jitc_kernel.repr.unwrap();
-
         if let Some(cache) = self.compilation_cache.as_mut() {
             cache
                 .insert(
@@ -235,20 +244,23 @@ impl HipContext {
                             jitc_kernel.cube_dim.y,
                             jitc_kernel.cube_dim.z,
                         ),
-                        shared_mem_bytes: repr.shared_memory_size(),
+                        resource_usage: jitc_kernel.resource_usage.clone(),
                         binary: code.clone(),
                     },
                 )
                 .unwrap();
         }
 
-        self.load_compiled_binary(
+        jitc_kernel.resource_usage = self.load_compiled_binary(
             code,
             kernel_id.clone(),
-            jitc_kernel.entrypoint_name,
+            jitc_kernel.entrypoint_name.clone(),
             jitc_kernel.cube_dim,
-            repr.shared_memory_size(),
-        )
+            jitc_kernel.resource_usage.clone(),
+        )?;
+        logger.log_compilation(&jitc_kernel);
+
+        Ok(())
     }
 
     fn load_compiled_binary(
@@ -257,8 +269,8 @@ impl HipContext {
         kernel_id: KernelId,
         entrypoint_name: String,
         cube_dim: CubeDim,
-        shared_mem_bytes: usize,
-    ) -> Result<(), CompilationError> {
+        resource_usage: KernelResourceUsage,
+    ) -> Result<KernelResourceUsage, CompilationError> {
         let func_name = CString::new(entrypoint_name.clone()).unwrap();
 
         // Create the HIP module
@@ -288,6 +300,8 @@ impl HipContext {
             }
         }
 
+        let resource_usage = driver_resource_usage(func, cube_dim, resource_usage);
+
         // register module
         self.module_names.insert(
             kernel_id.clone(),
@@ -295,11 +309,11 @@ impl HipContext {
                 _module: module,
                 func,
                 cube_dim,
-                shared_mem_bytes,
+                resource_usage: resource_usage.clone(),
             },
         );
 
-        Ok(())
+        Ok(resource_usage)
     }
 
     /// Executes a task on the given stream.
@@ -308,12 +322,15 @@ impl HipContext {
         stream: &mut Stream,
         kernel_id: KernelId,
         dispatch_count: (u32, u32, u32),
+        dynamic_shared_memory: usize,
+        textures: &[TextureRef],
         resources: &[GpuResource],
     ) -> Result<(), LaunchError> {
-        let mut bindings = resources
+        let mut bindings = textures
             .iter()
-            .map(|memory| memory.binding)
+            .map(|texture| texture as *const _ as *mut c_void)
             .collect::<Vec<_>>();
+        bindings.extend(resources.iter().map(|memory| memory.binding));
 
         let kernel = self.module_names.get(&kernel_id).unwrap();
         let cube_dim = kernel.cube_dim;
@@ -328,9 +345,10 @@ impl HipContext {
                 cube_dim.y,
                 cube_dim.z,
                 // Shared memory is collected into a single buffer, with each shared memory being
-                // an offset pointer
-                kernel.shared_mem_bytes as u32,
-                stream.sys,
+                // an offset pointer. Dynamic shared memory is placed after the static allocations.
+                (kernel.resource_usage.shared_memory + dynamic_shared_memory) as u32,
+                // Kernels of a launch graph are recorded instead of being executed.
+                self.graphs.recording_stream().unwrap_or(stream.sys),
                 bindings.as_mut_ptr(),
                 std::ptr::null_mut(),
             );
@@ -350,3 +368,44 @@ impl HipContext {
         }
     }
 }
+
+/// Fill in the resource usage reported by the driver for a loaded function. Attributes the driver
+/// fails to report are left unset.
+fn driver_resource_usage(
+    func: cubecl_hip_sys::hipFunction_t,
+    cube_dim: CubeDim,
+    usage: KernelResourceUsage,
+) -> KernelResourceUsage {
+    let attribute = |attribute| {
+        let mut value = 0;
+        let status = unsafe { cubecl_hip_sys::hipFuncGetAttribute(&mut value, attribute, func) };
+        (status == HIP_SUCCESS).then_some(value)
+    };
+    let max_active_cubes = {
+        let mut value = 0;
+        let status = unsafe {
+            cubecl_hip_sys::hipModuleOccupancyMaxActiveBlocksPerMultiprocessor(
+                &mut value,
+                func,
+                cube_dim.num_elems() as i32,
+                0,
+            )
+        };
+        (status == HIP_SUCCESS).then_some(value)
+    };
+
+    KernelResourceUsage {
+        registers: attribute(cubecl_hip_sys::hipFunction_attribute_HIP_FUNC_ATTRIBUTE_NUM_REGS)
+            .map(|it| it as u32),
+        local_memory: attribute(
+            cubecl_hip_sys::hipFunction_attribute_HIP_FUNC_ATTRIBUTE_LOCAL_SIZE_BYTES,
+        )
+        .map(|it| it as usize),
+        max_units_per_cube: attribute(
+            cubecl_hip_sys::hipFunction_attribute_HIP_FUNC_ATTRIBUTE_MAX_THREADS_PER_BLOCK,
+        )
+        .map(|it| it as u32),
+        max_active_cubes: max_active_cubes.map(|it| it as u32),
+        ..usage
+    }
+}
@@ -85,4 +85,45 @@ impl Fence {
 
         Ok(())
     }
+
+    /// Same as [wait_sync](Self::wait_sync), but keeps the [Fence] alive so it can be waited on
+    /// again.
+    pub fn wait_sync_ref(&self) -> Result<(), ExecutionError> {
+        let status = unsafe { cubecl_hip_sys::hipEventSynchronize(self.event) };
+
+        if status != HIP_SUCCESS {
+            return Err(ExecutionError::Generic {
+                reason: format!("Should successfully wait for stream event: {status}"),
+                backtrace: BackTrace::capture(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Same as [wait_async](Self::wait_async), but keeps the [Fence] alive so it can be waited
+    /// on again.
+    pub fn wait_async_ref(&self, stream: cubecl_hip_sys::hipStream_t) {
+        unsafe {
+            let status = cubecl_hip_sys::hipStreamWaitEvent(stream, self.event, 0);
+            assert_eq!(
+                status, HIP_SUCCESS,
+                "Should successfully wait for stream event"
+            );
+        }
+    }
+
+    /// Returns whether the [Fence] is reached, without blocking.
+    pub fn is_reached(&self) -> Result<bool, ExecutionError> {
+        let status = unsafe { cubecl_hip_sys::hipEventQuery(self.event) };
+
+        match status {
+            HIP_SUCCESS => Ok(true),
+            cubecl_hip_sys::hipError_t_hipErrorNotReady => Ok(false),
+            _ => Err(ExecutionError::Generic {
+                reason: format!("Should successfully query the stream event: {status}"),
+                backtrace: BackTrace::capture(),
+            }),
+        }
+    }
 }
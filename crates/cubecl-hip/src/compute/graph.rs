@@ -0,0 +1,148 @@
+use std::{collections::HashMap, ptr};
+
+use cubecl_common::backtrace::BackTrace;
+use cubecl_core::server::LaunchError;
+use cubecl_hip_sys::{HIP_SUCCESS, hipGraph_t, hipGraphExec_t, hipStream_t};
+use cubecl_runtime::id::KernelId;
+
+/// Native graphs replaying the [launch graphs](cubecl_runtime::graph::LaunchGraph) of a context.
+///
+/// The kernels of a launch graph are recorded on a dedicated capture stream, so the uploads of
+/// their metadata and scalars still happen on the stream of the launch before the graph runs.
+/// Each replay records a new graph, which updates the executable graph instantiated for the same
+/// kernels when possible.
+#[derive(Debug, Default)]
+pub(crate) struct GraphCache {
+    capture: Option<hipStream_t>,
+    recording: bool,
+    executables: HashMap<Vec<KernelId>, hipGraphExec_t>,
+}
+
+impl GraphCache {
+    /// The stream recording the kernels of a graph, if a graph is being recorded.
+    pub fn recording_stream(&self) -> Option<hipStream_t> {
+        self.capture.filter(|_| self.recording)
+    }
+
+    /// Start recording the kernels launched on the context.
+    pub fn begin(&mut self) -> Result<(), LaunchError> {
+        let stream = match self.capture {
+            Some(stream) => stream,
+            None => unsafe {
+                let mut stream: hipStream_t = ptr::null_mut();
+                let status = cubecl_hip_sys::hipStreamCreateWithFlags(
+                    &mut stream,
+                    cubecl_hip_sys::hipStreamNonBlocking,
+                );
+                check(status, "create the capture stream")?;
+                *self.capture.insert(stream)
+            },
+        };
+
+        // The kernels can be compiled and the memory pools can allocate while recording.
+        unsafe {
+            let status = cubecl_hip_sys::hipStreamBeginCapture(
+                stream,
+                cubecl_hip_sys::hipStreamCaptureMode_hipStreamCaptureModeRelaxed,
+            );
+            check(status, "begin the capture")?;
+        }
+        self.recording = true;
+
+        Ok(())
+    }
+
+    /// Stop recording, and launch the recorded kernels of the given launch graph on `stream`.
+    pub fn launch(
+        &mut self,
+        kernels: Vec<KernelId>,
+        stream: hipStream_t,
+    ) -> Result<(), LaunchError> {
+        let graph = self.end()?;
+        let result = unsafe { self.launch_graph(graph, kernels, stream) };
+        unsafe { cubecl_hip_sys::hipGraphDestroy(graph) };
+
+        result
+    }
+
+    /// Stop recording and discard the recorded kernels.
+    pub fn abort(&mut self) {
+        if let Ok(graph) = self.end() {
+            unsafe { cubecl_hip_sys::hipGraphDestroy(graph) };
+        }
+    }
+
+    fn end(&mut self) -> Result<hipGraph_t, LaunchError> {
+        let stream = self
+            .recording_stream()
+            .ok_or_else(|| LaunchError::Unknown {
+                reason: "No launch graph is being recorded".into(),
+                backtrace: BackTrace::capture(),
+            })?;
+        self.recording = false;
+
+        let mut graph: hipGraph_t = ptr::null_mut();
+        unsafe {
+            let status = cubecl_hip_sys::hipStreamEndCapture(stream, &mut graph);
+            check(status, "end the capture")?;
+        }
+
+        Ok(graph)
+    }
+
+    unsafe fn launch_graph(
+        &mut self,
+        graph: hipGraph_t,
+        kernels: Vec<KernelId>,
+        stream: hipStream_t,
+    ) -> Result<(), LaunchError> {
+        let updated = match self.executables.get(&kernels) {
+            Some(exec) => {
+                let mut error_node = ptr::null_mut();
+                let mut result = 0;
+                let status = unsafe {
+                    cubecl_hip_sys::hipGraphExecUpdate(*exec, graph, &mut error_node, &mut result)
+                };
+                match status == HIP_SUCCESS
+                    && result == cubecl_hip_sys::hipGraphExecUpdateResult_hipGraphExecUpdateSuccess
+                {
+                    true => Some(*exec),
+                    false => {
+                        unsafe { cubecl_hip_sys::hipGraphExecDestroy(*exec) };
+                        self.executables.remove(&kernels);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let exec = match updated {
+            Some(exec) => exec,
+            None => {
+                let mut exec: hipGraphExec_t = ptr::null_mut();
+                unsafe {
+                    let status = cubecl_hip_sys::hipGraphInstantiateWithFlags(&mut exec, graph, 0);
+                    check(status, "instantiate the graph")?;
+                }
+                self.executables.insert(kernels, exec);
+                exec
+            }
+        };
+
+        unsafe {
+            let status = cubecl_hip_sys::hipGraphLaunch(exec, stream);
+            check(status, "launch the graph")
+        }
+    }
+}
+
+fn check(status: cubecl_hip_sys::hipError_t, action: &str) -> Result<(), LaunchError> {
+    match status == HIP_SUCCESS {
+        true => Ok(()),
+        false => Err(LaunchError::Unknown {
+            reason: format!("Failed to {action} with status {status:?}"),
+            backtrace: BackTrace::capture(),
+        }),
+    }
+}
@@ -3,9 +3,11 @@ mod server;
 pub(crate) mod command;
 pub(crate) mod context;
 pub(crate) mod fence;
+pub(crate) mod graph;
 pub(crate) mod io;
 pub(crate) mod storage;
 pub(crate) mod stream;
+pub(crate) mod texture;
 
 pub use server::*;
 pub use storage::*;
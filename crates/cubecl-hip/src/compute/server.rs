@@ -14,18 +14,20 @@ use cubecl_core::{
     ir::MemoryDeviceProperties,
     prelude::*,
     server::{
-        Allocation, AllocationKind, Binding, Bindings, CopyDescriptor, ExecutionError, IoError,
-        LaunchError, ProfileError, ProfilingToken, ServerCommunication, ServerUtilities,
+        Allocation, AllocationKind, Binding, Bindings, CopyDescriptor, ExecutionError, Handle,
+        IoError, LaunchError, ProfileError, ProfilingToken, ServerCommunication, ServerUtilities,
     },
 };
 use cubecl_runtime::{
     compiler::CubeTask,
     config::GlobalConfig,
+    graph::GraphLaunch,
+    kernel::KernelResourceUsage,
     logging::ServerLogger,
     memory_management::{MemoryAllocationMode, MemoryUsage, offset_handles},
     server::{self, ComputeServer},
     storage::BindingResource,
-    stream::MultiStream,
+    stream::{Event, MultiStream, MultiStreamEvent},
 };
 use std::sync::Arc;
 
@@ -159,66 +161,68 @@ impl ComputeServer for HipServer {
         mode: ExecutionMode,
         stream_id: StreamId,
     ) -> Result<(), LaunchError> {
-        let mut kernel_id = kernel.id();
-        let logger = self.streams.logger.clone();
-        kernel_id.mode(mode);
-        let mut command = self.command(stream_id, bindings.buffers.iter());
-
-        let count = match count {
-            CubeCount::Static(x, y, z) => (x, y, z),
-            // TODO: HIP doesn't have an exact equivalen of dynamic dispatch. Instead, kernels are free to launch other kernels.
-            // One option is to create a dummy kernel with 1 thread that launches the real kernel with the dynamic dispatch settings.
-            // For now, just read the dispatch settings from the buffer.
-            CubeCount::Dynamic(binding) => {
-                let data = future::block_on(command.read_async(vec![CopyDescriptor::new(
-                    binding,
-                    &[3],
-                    &[1],
-                    4,
-                )]))
-                .unwrap();
-                let data = bytemuck::cast_slice(&data[0]);
-                assert!(
-                    data.len() == 3,
-                    "Dynamic cube count should contain 3 values"
-                );
-                (data[0], data[1], data[2])
-            }
-        };
-
-        let Bindings {
-            buffers,
-            metadata,
-            scalars,
-            tensor_maps,
-        } = bindings;
+        // The uploaded metadata and scalars can be released once the kernel is enqueued.
+        unsafe { self.launch_kernel(kernel, count, bindings, mode, stream_id) }?;
 
-        debug_assert!(tensor_maps.is_empty(), "Can't use tensor maps on HIP");
+        Ok(())
+    }
 
-        let info = command
-            .create_with_data(bytemuck::cast_slice(&metadata.data))
-            .unwrap();
-        let scalars: Vec<_> = scalars
-            .values()
-            .map(|s| command.create_with_data(s.data()).unwrap())
-            .collect();
+    unsafe fn launch_graph(
+        &mut self,
+        launches: Vec<GraphLaunch<Self::Kernel>>,
+        stream_id: StreamId,
+    ) -> Result<(), LaunchError> {
+        // Dynamic cube counts are read before launching the kernel, so they can't be written by
+        // a previous kernel of the same graph.
+        if launches
+            .iter()
+            .any(|launch| matches!(launch.count, CubeCount::Dynamic(_)))
+        {
+            for launch in launches {
+                unsafe {
+                    self.launch(
+                        launch.kernel,
+                        launch.count,
+                        launch.bindings,
+                        launch.mode,
+                        stream_id,
+                    )?
+                };
+            }
+            return Ok(());
+        }
 
-        let mut resources: Vec<_> = buffers
-            .into_iter()
-            .map(|b| command.resource(b).expect("Resource to exist."))
-            .collect();
-        resources.push(
-            command
-                .resource(info.clone().binding())
-                .expect("Resource to exist."),
-        );
-        resources.extend(
-            scalars
-                .into_iter()
-                .map(|s| command.resource(s.binding()).expect("Resource to exist.")),
-        );
+        self.ctx.graphs.begin()?;
+
+        let mut kernels = Vec::with_capacity(launches.len());
+        let mut uploads = Vec::new();
+        for launch in launches {
+            let mut kernel_id = launch.kernel.id();
+            kernel_id.mode(launch.mode);
+            kernels.push(kernel_id);
+
+            let launched = unsafe {
+                self.launch_kernel(
+                    launch.kernel,
+                    launch.count,
+                    launch.bindings,
+                    launch.mode,
+                    stream_id,
+                )
+            };
+            match launched {
+                Ok(handles) => uploads.extend(handles),
+                Err(err) => {
+                    self.ctx.graphs.abort();
+                    return Err(err);
+                }
+            }
+        }
 
-        command.kernel(kernel_id, kernel, mode, count, &resources, logger)?;
+        // The uploads of every kernel must stay alive until the graph is enqueued after them.
+        let stream = self.command_no_inputs(stream_id).streams.current().sys;
+        self.ctx.graphs.launch(kernels, stream)?;
+        core::mem::drop(uploads);
 
         Ok(())
     }
@@ -230,6 +234,22 @@ impl ComputeServer for HipServer {
         command.sync()
     }
 
+    fn record_event(&mut self, stream_id: StreamId) -> Result<Event, ExecutionError> {
+        let event = self.streams.record_event(stream_id);
+
+        Ok(Event::new(stream_id, event))
+    }
+
+    fn wait_event(&mut self, event: &Event, stream_id: StreamId) -> Result<(), ExecutionError> {
+        let Some(inner) = event.server_event::<MultiStreamEvent<HipStreamBackend>>() else {
+            // Recorded by another kind of server, the device can't wait on it.
+            return event.wait();
+        };
+        self.streams.wait_event(stream_id, inner);
+
+        Ok(())
+    }
+
     fn start_profile(&mut self, stream_id: StreamId) -> ProfilingToken {
         if let Err(err) = cubecl_common::future::block_on(self.sync(stream_id)) {
             self.ctx.timestamps.error(err.into())
@@ -266,6 +286,11 @@ impl ComputeServer for HipServer {
         let mut command = self.command_no_inputs(stream_id);
         command.allocation_mode(mode)
     }
+
+    fn resource_usage(&mut self, kernel_id: &KernelId) -> Option<KernelResourceUsage> {
+        let kernel = self.ctx.module_names.get(kernel_id)?;
+        Some(kernel.resource_usage.clone())
+    }
 }
 
 impl ServerCommunication for HipServer {
@@ -287,6 +312,97 @@ impl ServerCommunication for HipServer {
 }
 
 impl HipServer {
+    /// Launch the kernel, and return the handles of the uploaded metadata and scalars, which must
+    /// stay alive until the kernel is enqueued on the stream.
+    unsafe fn launch_kernel(
+        &mut self,
+        kernel: <Self as ComputeServer>::Kernel,
+        count: CubeCount,
+        bindings: Bindings,
+        mode: ExecutionMode,
+        stream_id: StreamId,
+    ) -> Result<Vec<Handle>, LaunchError> {
+        let mut kernel_id = kernel.id();
+        let logger = self.streams.logger.clone();
+        kernel_id.mode(mode);
+        let mut command = self.command(stream_id, bindings.buffers.iter());
+
+        let count = match count {
+            CubeCount::Static(x, y, z) => (x, y, z),
+            // TODO: HIP doesn't have an exact equivalen of dynamic dispatch. Instead, kernels are free to launch other kernels.
+            // One option is to create a dummy kernel with 1 thread that launches the real kernel with the dynamic dispatch settings.
+            // For now, just read the dispatch settings from the buffer.
+            CubeCount::Dynamic(binding) => {
+                let data = future::block_on(command.read_async(vec![CopyDescriptor::new(
+                    binding,
+                    &[3],
+                    &[1],
+                    4,
+                )]))
+                .unwrap();
+                let data = bytemuck::cast_slice(&data[0]);
+                assert!(
+                    data.len() == 3,
+                    "Dynamic cube count should contain 3 values"
+                );
+                (data[0], data[1], data[2])
+            }
+        };
+
+        let Bindings {
+            buffers,
+            metadata,
+            scalars,
+            tensor_maps,
+            textures,
+            dynamic_shared_memory,
+            spec_constants,
+        } = bindings;
+
+        debug_assert!(tensor_maps.is_empty(), "Can't use tensor maps on HIP");
+        debug_assert!(
+            spec_constants.is_empty(),
+            "Can't use specialization constants on HIP"
+        );
+
+        let info = command
+            .create_with_data(bytemuck::cast_slice(&metadata.data))
+            .unwrap();
+        let scalars: Vec<_> = scalars
+            .values()
+            .map(|s| command.create_with_data(s.data()).unwrap())
+            .collect();
+
+        let textures = textures
+            .into_iter()
+            .map(|texture| command.texture(texture.binding, &texture.meta))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut resources: Vec<_> = buffers
+            .into_iter()
+            .map(|b| command.resource(b).expect("Resource to exist."))
+            .collect();
+        let uploads: Vec<_> = [info].into_iter().chain(scalars).collect();
+        resources.extend(uploads.iter().map(|handle| {
+            command
+                .resource(handle.clone().binding())
+                .expect("Resource to exist.")
+        }));
+
+        command.kernel(
+            kernel_id,
+            kernel,
+            mode,
+            count,
+            dynamic_shared_memory,
+            &textures,
+            &resources,
+            logger,
+        )?;
+
+        Ok(uploads)
+    }
+
     /// Create a new hip server.
     pub(crate) fn new(
         ctx: HipContext,
@@ -78,4 +78,16 @@ impl EventStreamBackend for HipStreamBackend {
     fn wait_event_sync(event: Self::Event) -> Result<(), ExecutionError> {
         event.wait_sync()
     }
+
+    fn wait_event_ref(stream: &mut Self::Stream, event: &Self::Event) {
+        event.wait_async_ref(stream.sys);
+    }
+
+    fn wait_event_ref_sync(event: &Self::Event) -> Result<(), ExecutionError> {
+        event.wait_sync_ref()
+    }
+
+    fn is_event_reached(event: &Self::Event) -> Result<bool, ExecutionError> {
+        event.is_reached()
+    }
 }
@@ -0,0 +1,255 @@
+use std::collections::{HashMap, hash_map::Entry};
+
+use cubecl_common::backtrace::BackTrace;
+use cubecl_core::{
+    ir::{AddressMode, ElemType, FilterMode, FloatKind, Sampler, StorageType, TextureDim},
+    server::{LaunchError, TextureMeta},
+};
+use cubecl_hip_sys::{
+    HIP_ARRAY3D_DESCRIPTOR, HIP_MEMCPY3D, HIP_SUCCESS, hipArray_Format, hipArray_t, hipError_t,
+    hipResourceDesc, hipStream_t, hipTextureAddressMode, hipTextureDesc, hipTextureObject_t,
+};
+use cubecl_runtime::id::WeakBindingRef;
+
+use super::{fence::Fence, storage::gpu::GpuResource};
+
+/// Kernel parameter for a texture, matching `texture_ref` in the generated source.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TextureRef {
+    texels: hipTextureObject_t,
+    samplers: [hipTextureObject_t; Sampler::COUNT],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct TextureKey {
+    ptr: usize,
+    dim: TextureDim,
+    extent: [usize; 3],
+    format: hipArray_Format,
+    channels: u32,
+}
+
+/// A HIP array holding a copy of the texels, with the texture objects reading it.
+#[derive(Debug)]
+struct Texture {
+    array: hipArray_t,
+    texture: TextureRef,
+    memory: WeakBindingRef,
+    stream: hipStream_t,
+}
+
+/// Textures created for launches so far.
+///
+/// Hardware filtering reads HIP arrays, so each texture owns an array the bound buffer is copied
+/// into on every launch, and a texture object per sampler state. Textures are reused while the
+/// buffer backing them is alive, and destroyed once it is freed.
+#[derive(Debug, Default)]
+pub(crate) struct TextureCache {
+    textures: HashMap<TextureKey, Texture>,
+}
+
+impl TextureCache {
+    /// Get the kernel parameter for the texture described by `meta` over `resource`, copying the
+    /// texels on `stream`.
+    pub fn get(
+        &mut self,
+        resource: &GpuResource,
+        memory: WeakBindingRef,
+        meta: &TextureMeta,
+        stream: hipStream_t,
+    ) -> Result<TextureRef, LaunchError> {
+        self.evict_freed()?;
+
+        let format =
+            storage_to_array_format(meta.storage_ty).ok_or_else(|| LaunchError::Unknown {
+                reason: format!("Unsupported texture format {}", meta.storage_ty),
+                backtrace: BackTrace::capture(),
+            })?;
+        let key = TextureKey {
+            ptr: resource.ptr as usize,
+            dim: meta.dim,
+            extent: meta.extent,
+            format,
+            channels: meta.channels as u32,
+        };
+
+        let texture = match self.textures.entry(key) {
+            Entry::Occupied(entry) => {
+                let texture = entry.into_mut();
+                texture.memory = memory;
+                texture.stream = stream;
+                texture
+            }
+            Entry::Vacant(entry) => entry.insert(create_texture(&key, memory, stream)?),
+        };
+        copy_texels(&key, meta.storage_ty.size(), texture.array, stream)?;
+
+        Ok(texture.texture)
+    }
+
+    /// Destroy the textures whose buffer was freed, once the kernels using them are done.
+    fn evict_freed(&mut self) -> Result<(), LaunchError> {
+        let freed = self
+            .textures
+            .iter()
+            .filter(|(_, texture)| texture.memory.is_free())
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>();
+
+        for key in freed {
+            let texture = self.textures.remove(&key).unwrap();
+            Fence::new(texture.stream)
+                .wait_sync()
+                .map_err(|err| LaunchError::Unknown {
+                    reason: format!("{err:?}"),
+                    backtrace: BackTrace::capture(),
+                })?;
+            unsafe { destroy_texture(&texture) };
+        }
+
+        Ok(())
+    }
+}
+
+fn create_texture(
+    key: &TextureKey,
+    memory: WeakBindingRef,
+    stream: hipStream_t,
+) -> Result<Texture, LaunchError> {
+    let [width, height, depth] = key.extent;
+    let descriptor = HIP_ARRAY3D_DESCRIPTOR {
+        Width: width,
+        Height: height,
+        // A depth of zero allocates a 2D array.
+        Depth: match key.dim {
+            TextureDim::D2 => 0,
+            TextureDim::D3 => depth,
+        },
+        Format: key.format,
+        NumChannels: key.channels,
+        Flags: 0,
+    };
+
+    unsafe {
+        let mut array = std::ptr::null_mut();
+        check(cubecl_hip_sys::hipArray3DCreate(&mut array, &descriptor))?;
+
+        let mut res_desc: hipResourceDesc = std::mem::zeroed();
+        res_desc.resType = cubecl_hip_sys::hipResourceType_hipResourceTypeArray;
+        res_desc.res.array.array = array;
+
+        let mut texture = TextureRef {
+            texels: std::ptr::null_mut(),
+            samplers: [std::ptr::null_mut(); Sampler::COUNT],
+        };
+        // Loads use unnormalized coordinates, which can only be clamped.
+        let mut result =
+            create_texture_object(&res_desc, Sampler::nearest(), false, &mut texture.texels);
+        for (sampler, object) in Sampler::all().zip(texture.samplers.iter_mut()) {
+            result = result.and_then(|_| create_texture_object(&res_desc, sampler, true, object));
+        }
+
+        let texture = Texture {
+            array,
+            texture,
+            memory,
+            stream,
+        };
+        if let Err(err) = result {
+            destroy_texture(&texture);
+            return Err(err);
+        }
+        Ok(texture)
+    }
+}
+
+unsafe fn create_texture_object(
+    res_desc: &hipResourceDesc,
+    sampler: Sampler,
+    normalized: bool,
+    object: &mut hipTextureObject_t,
+) -> Result<(), LaunchError> {
+    unsafe {
+        let mut tex_desc: hipTextureDesc = std::mem::zeroed();
+        tex_desc.addressMode = [address_mode(sampler.address); 3];
+        tex_desc.filterMode = match sampler.filter {
+            FilterMode::Nearest => cubecl_hip_sys::hipTextureFilterMode_hipFilterModePoint,
+            FilterMode::Linear => cubecl_hip_sys::hipTextureFilterMode_hipFilterModeLinear,
+        };
+        tex_desc.readMode = cubecl_hip_sys::hipTextureReadMode_hipReadModeElementType;
+        tex_desc.normalizedCoords = normalized as i32;
+
+        check(cubecl_hip_sys::hipCreateTextureObject(
+            object,
+            res_desc,
+            &tex_desc,
+            std::ptr::null(),
+        ))
+    }
+}
+
+/// Copy the contiguous texels of the buffer into the array of the texture.
+fn copy_texels(
+    key: &TextureKey,
+    elem_size: usize,
+    array: hipArray_t,
+    stream: hipStream_t,
+) -> Result<(), LaunchError> {
+    let [width, height, depth] = key.extent;
+    let row_size = width * key.channels as usize * elem_size;
+
+    unsafe {
+        let mut copy: HIP_MEMCPY3D = std::mem::zeroed();
+        copy.srcMemoryType = cubecl_hip_sys::hipMemoryType_hipMemoryTypeDevice;
+        copy.srcDevice = key.ptr as _;
+        copy.srcPitch = row_size;
+        copy.srcHeight = height;
+        copy.dstMemoryType = cubecl_hip_sys::hipMemoryType_hipMemoryTypeArray;
+        copy.dstArray = array;
+        copy.WidthInBytes = row_size;
+        copy.Height = height;
+        copy.Depth = depth;
+
+        check(cubecl_hip_sys::hipDrvMemcpy3DAsync(&copy, stream))
+    }
+}
+
+unsafe fn destroy_texture(texture: &Texture) {
+    unsafe {
+        for object in core::iter::once(texture.texture.texels).chain(texture.texture.samplers) {
+            if !object.is_null() {
+                cubecl_hip_sys::hipDestroyTextureObject(object);
+            }
+        }
+        cubecl_hip_sys::hipArrayDestroy(texture.array);
+    }
+}
+
+fn address_mode(mode: AddressMode) -> hipTextureAddressMode {
+    match mode {
+        AddressMode::ClampToEdge => cubecl_hip_sys::hipTextureAddressMode_hipAddressModeClamp,
+        AddressMode::Repeat => cubecl_hip_sys::hipTextureAddressMode_hipAddressModeWrap,
+        AddressMode::MirrorRepeat => cubecl_hip_sys::hipTextureAddressMode_hipAddressModeMirror,
+    }
+}
+
+fn check(status: hipError_t) -> Result<(), LaunchError> {
+    match status {
+        HIP_SUCCESS => Ok(()),
+        status => Err(LaunchError::Unknown {
+            reason: format!("Unable to prepare the texture, status {status:?}"),
+            backtrace: BackTrace::capture(),
+        }),
+    }
+}
+
+fn storage_to_array_format(ty: StorageType) -> Option<hipArray_Format> {
+    match ty.elem_type() {
+        ElemType::Float(FloatKind::F32) => {
+            Some(cubecl_hip_sys::hipArray_Format_HIP_AD_FORMAT_FLOAT)
+        }
+        ElemType::Float(FloatKind::F16) => Some(cubecl_hip_sys::hipArray_Format_HIP_AD_FORMAT_HALF),
+        _ => None,
+    }
+}
@@ -11,7 +11,7 @@ use cubecl_core::{
     MemoryConfiguration, Runtime,
     ir::{
         ContiguousElements, DeviceProperties, HardwareProperties, LineSize, MatrixLayout,
-        MemoryDeviceProperties, MmaProperties, TargetProperties, features::Plane,
+        MemoryDeviceProperties, MmaProperties, SemanticType, TargetProperties, features::Plane,
     },
     server::ServerUtilities,
 };
@@ -55,6 +55,14 @@ impl DeviceState for HipServer {
         let mut prop_max_threads = 0;
         let mut max_cube_dim = (1, 1, 1);
         let mut mem_alignment = 32;
+        #[allow(unused_assignments)]
+        let mut max_cubes_per_multiprocessor = None;
+        #[allow(unused_assignments)]
+        let mut max_units_per_multiprocessor = None;
+        #[allow(unused_assignments)]
+        let mut max_shared_memory_per_multiprocessor = None;
+        #[allow(unused_assignments)]
+        let mut registers_per_multiprocessor = None;
         unsafe {
             let mut ll_device_props = MaybeUninit::uninit();
             let status = cubecl_hip_sys::hipGetDevicePropertiesR0600(
@@ -77,6 +85,14 @@ impl DeviceState for HipServer {
             max_cube_dim.0 = ll_device_props.maxThreadsDim[0] as u32;
             max_cube_dim.1 = ll_device_props.maxThreadsDim[1] as u32;
             max_cube_dim.2 = ll_device_props.maxThreadsDim[2] as u32;
+            // Some HIP versions report 0 for the limits they don't know.
+            let known = |value: i32| (value > 0).then_some(value as u32);
+            max_cubes_per_multiprocessor = known(ll_device_props.maxBlocksPerMultiProcessor);
+            max_units_per_multiprocessor = known(ll_device_props.maxThreadsPerMultiProcessor);
+            max_shared_memory_per_multiprocessor =
+                (ll_device_props.maxSharedMemoryPerMultiProcessor > 0)
+                    .then_some(ll_device_props.maxSharedMemoryPerMultiProcessor);
+            registers_per_multiprocessor = known(ll_device_props.regsPerMultiprocessor);
 
             // Just to be sure we check both.
             mem_alignment = usize::max(mem_alignment, ll_device_props.textureAlignment);
@@ -129,6 +145,10 @@ impl DeviceState for HipServer {
             max_units_per_cube: prop_max_threads,
             max_cube_dim,
             num_streaming_multiprocessors: None,
+            max_cubes_per_multiprocessor,
+            max_units_per_multiprocessor,
+            max_shared_memory_per_multiprocessor,
+            registers_per_multiprocessor,
             num_tensor_cores: None,
             min_tensor_cores_dim: if supported_wmma_combinations.is_empty() {
                 None
@@ -150,7 +170,11 @@ impl DeviceState for HipServer {
         // device_props.register_feature(Feature::Type(Elem::AtomicFloat(FloatKind::F16)));
         // device_props.register_feature(Feature::Type(Elem::AtomicFloat(FloatKind::BF16)));
 
+        // Float atomics can only be added natively.
+        device_props.features.atomic_polyfills = true;
         device_props.features.dynamic_line_size = true;
+        device_props.features.dynamic_shared_memory = true;
+        device_props.register_semantic_type(SemanticType::Texture);
         device_props.features.alignment = true;
         device_props.features.plane.insert(Plane::Ops);
         device_props
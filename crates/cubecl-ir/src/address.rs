@@ -4,6 +4,7 @@ use crate::{IntKind, Scope, StorageType, UIntKind};
 /// This is the type `usize` maps to when used in a kernel, with `isize` being mapped to the signed
 /// equivalent.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Default, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AddressType {
     // Discriminants are explicit to ensure correct ordering
     #[default]
@@ -129,6 +129,15 @@ impl Allocator {
         self.next_id.fetch_add(1, Ordering::Release)
     }
 
+    /// Create an allocator with its own pool of mutable variables, that still shares ids with this
+    /// one. Used for function bodies, which must declare their own locals.
+    pub fn fork(&self) -> Self {
+        Self {
+            local_mut_pool: Default::default(),
+            next_id: self.next_id.clone(),
+        }
+    }
+
     pub fn take_variables(&self) -> Vec<Variable> {
         self.local_mut_pool
             .borrow_mut()
@@ -143,7 +152,7 @@ use cubecl_macros_internal::TypeHash;
 pub use expand_element::*;
 
 mod expand_element {
-    use cubecl_common::{e2m1, e2m1x2, e2m3, e3m2, e4m3, e5m2, flex32, tf32, ue8m0};
+    use cubecl_common::{df64, e2m1, e2m1x2, e2m3, e3m2, e4m3, e5m2, flex32, tf32, ue8m0};
     use half::{bf16, f16};
 
     use super::*;
@@ -226,6 +235,7 @@ mod expand_element {
     impl_into_expand_element!(f16);
     impl_into_expand_element!(bf16);
     impl_into_expand_element!(tf32);
+    impl_into_expand_element!(df64);
     impl_into_expand_element!(f32);
     impl_into_expand_element!(i8);
     impl_into_expand_element!(i16);
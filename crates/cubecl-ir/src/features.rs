@@ -1,4 +1,4 @@
-use crate::{AddressType, SemanticType, StorageType, Type};
+use crate::{AddressType, ElemType, SemanticType, StorageType, Type, UIntKind};
 use alloc::collections::{BTreeMap, BTreeSet};
 
 use enumset::EnumSetType;
@@ -7,6 +7,8 @@ pub use enumset::EnumSet;
 
 /// Features supported by a runtime
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Features {
     /// Plane features supported by this runtime.
     pub plane: EnumSet<Plane>,
@@ -20,10 +22,27 @@ pub struct Features {
     pub address_types: BTreeSet<AddressType>,
 
     /// Types supported by this runtime, and which usages they support.
+    #[cfg_attr(feature = "serde", serde(with = "storage_types"))]
     pub storage_types: BTreeMap<StorageType, EnumSet<TypeUsage>>,
     /// Semantic constructs supported by this runtime.
     pub semantic_types: BTreeSet<SemanticType>,
 
+    /// Whether shared memory can be sized at launch time. When it isn't, dynamic shared memory
+    /// is specialized into the kernel instead.
+    pub dynamic_shared_memory: bool,
+
+    /// Whether kernels can declare specialization constants, whose value is set when the pipeline
+    /// is created. When they can't, the value is specialized into the kernel instead.
+    pub spec_constants: bool,
+
+    /// Whether atomic operations missing from the usages of an atomic type are emulated with
+    /// compare-and-swap loops on the unsigned integer of the same size.
+    pub atomic_polyfills: bool,
+
+    /// Whether 64-bit integers are emulated with pairs of 32-bit words, because the target
+    /// doesn't support them natively.
+    pub int64_emulation: bool,
+
     /// Whether `copy_async` is supported
     pub copy_async: bool,
     /// Tensor Memory Accelerator supported features
@@ -47,6 +66,8 @@ pub struct Features {
 
 /// Operations allowed for this type. CMMA is defined separately.
 #[derive(Debug, Hash, PartialOrd, Ord, EnumSetType)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", enumset(serialize_repr = "list"))]
 pub enum TypeUsage {
     /// Conversion to/from the type. All types should support this.
     Conversion,
@@ -66,6 +87,8 @@ pub enum TypeUsage {
 
 /// Supported plane features
 #[derive(Debug, Hash, PartialOrd, Ord, EnumSetType)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", enumset(serialize_repr = "list"))]
 pub enum Plane {
     /// Basic plane-wide operations
     Ops,
@@ -119,6 +142,8 @@ pub struct ScaledMmaConfig {
 
 /// Atomic features that may be supported by a [cube runtime](Runtime).
 #[derive(Debug, PartialOrd, Ord, EnumSetType)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", enumset(serialize_repr = "list"))]
 pub enum Tma {
     /// Base feature set for tensor memory accelerator features. Includes tiling and im2col
     Base,
@@ -151,6 +176,41 @@ impl Features {
     pub fn supports_address(&self, ty: impl Into<AddressType>) -> bool {
         self.address_types.contains(&ty.into())
     }
+
+    /// The unsigned integer type whose compare-and-swap emulates the atomic operations on `elem`,
+    /// when some aren't supported natively and [atomics are polyfilled](Self::atomic_polyfills).
+    ///
+    /// Only float types are polyfilled, integer atomics keep their native operations. 16-bit types
+    /// without 16-bit integer atomics are packed in pairs into `u32` words, so the returned type
+    /// can be wider than `elem`.
+    pub fn atomic_polyfill_type(&self, elem: ElemType) -> Option<ElemType> {
+        let native = self.type_usage(StorageType::Atomic(elem));
+        if !self.atomic_polyfills
+            || !matches!(elem, ElemType::Float(_))
+            || native.is_superset(TypeUsage::all_atomic())
+        {
+            return None;
+        }
+
+        let candidates: &[UIntKind] = match elem.size() {
+            2 => &[UIntKind::U16, UIntKind::U32],
+            4 => &[UIntKind::U32],
+            8 => &[UIntKind::U64],
+            _ => return None,
+        };
+        candidates.iter().map(|int| ElemType::UInt(*int)).find(|int| {
+            self.type_usage(StorageType::Atomic(*int))
+                .contains(TypeUsage::AtomicLoadStore)
+        })
+    }
+
+    /// Get the usages for an atomic type, including the ones emulated by polyfills.
+    pub fn atomic_usage(&self, elem: ElemType) -> EnumSet<TypeUsage> {
+        match self.atomic_polyfill_type(elem) {
+            Some(_) => TypeUsage::all_atomic(),
+            None => self.type_usage(StorageType::Atomic(elem)),
+        }
+    }
 }
 
 impl TypeUsage {
@@ -164,3 +224,38 @@ impl TypeUsage {
         TypeUsage::AtomicAdd | TypeUsage::AtomicLoadStore | TypeUsage::AtomicMinMax
     }
 }
+
+/// Serializes the storage types as a list of entries, since formats like JSON only allow string
+/// keys.
+#[cfg(feature = "serde")]
+mod storage_types {
+    use super::{EnumSet, StorageType, TypeUsage};
+    use alloc::{collections::BTreeMap, vec::Vec};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Entry {
+        ty: StorageType,
+        usage: EnumSet<TypeUsage>,
+    }
+
+    pub fn serialize<S: Serializer>(
+        types: &BTreeMap<StorageType, EnumSet<TypeUsage>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(types.iter().map(|(ty, usage)| Entry {
+            ty: *ty,
+            usage: *usage,
+        }))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<StorageType, EnumSet<TypeUsage>>, D::Error> {
+        let entries = Vec::<Entry>::deserialize(deserializer)?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| (entry.ty, entry.usage))
+            .collect())
+    }
+}
@@ -0,0 +1,69 @@
+use core::fmt::Display;
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{OperationArgs, Scope, TypeHash};
+
+use super::Variable;
+
+/// A device function that isn't inlined into its callers, and is instead emitted as a separate
+/// function in the compiled source. Functions can only take and return plain values.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, TypeHash, PartialEq, Eq)]
+pub struct Function {
+    /// The mangled name of the function, unique for each set of generics and comptime arguments.
+    pub name: String,
+    /// The parameters of the function, in call order. Builtins read by the body are captured as
+    /// trailing parameters.
+    pub params: Vec<Variable>,
+    /// The builtins read by the body, passed by the caller as the trailing arguments.
+    pub captures: Vec<Variable>,
+    /// The value returned by the function, if any. It is assigned exactly once, at the end of the
+    /// body.
+    pub out: Option<Variable>,
+    pub body: Scope,
+}
+
+/// Call a [`Function`] by name. The output of the instruction receives the returned value.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, TypeHash, PartialEq, Eq, Hash)]
+pub struct FunctionCall {
+    pub name: String,
+    pub args: Vec<Variable>,
+}
+
+impl OperationArgs for FunctionCall {
+    fn as_args(&self) -> Option<Vec<Variable>> {
+        Some(self.args.clone())
+    }
+}
+
+impl Display for Function {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "fn {}(", self.name)?;
+        for (i, param) in self.params.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{param}: {}", param.ty)?;
+        }
+        write!(f, ")")?;
+        if let Some(out) = &self.out {
+            write!(f, " -> {out}: {}", out.ty)?;
+        }
+        write!(f, " {}", self.body)
+    }
+}
+
+impl Display for FunctionCall {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "call {}(", self.name)?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{arg}")?;
+        }
+        write!(f, ")")
+    }
+}
@@ -13,6 +13,7 @@ mod bitwise;
 mod branch;
 mod cmma;
 mod comparison;
+mod function;
 mod marker;
 mod metadata;
 mod non_semantic;
@@ -25,6 +26,7 @@ mod reflect;
 mod runtime_properties;
 mod scope;
 mod synchronization;
+mod texture;
 mod tma;
 mod r#type;
 mod type_hash;
@@ -39,6 +41,7 @@ pub use bitwise::*;
 pub use branch::*;
 pub use cmma::*;
 pub use comparison::*;
+pub use function::*;
 pub use marker::*;
 pub use metadata::*;
 pub use non_semantic::*;
@@ -51,6 +54,7 @@ pub use reflect::*;
 pub use runtime_properties::*;
 pub use scope::*;
 pub use synchronization::*;
+pub use texture::*;
 pub use tma::*;
 pub use r#type::*;
 pub use variable::*;
@@ -3,9 +3,9 @@ use core::fmt::Display;
 use super::{Branch, CoopMma, NonSemantic, Plane, Synchronization, Type, Variable};
 use crate::{
     Arithmetic, AtomicOp, Bitwise, InstructionModes, LineSize, Metadata, OperationArgs,
-    OperationReflect, Operator, TmaOps, comparison::Comparison, marker::Marker,
+    OperationReflect, Operator, TextureOps, TmaOps, comparison::Comparison, marker::Marker,
 };
-use crate::{BarrierOps, SourceLoc, TypeHash};
+use crate::{BarrierOps, FunctionCall, SourceLoc, TypeHash};
 use alloc::{
     format,
     string::{String, ToString},
@@ -52,12 +52,16 @@ pub enum Operation {
     Barrier(BarrierOps),
     #[operation(nested)]
     Tma(TmaOps),
+    #[operation(nested)]
+    Texture(TextureOps),
     /// Non-semantic instructions (i.e. comments, debug info)
     #[operation(nested)]
     NonSemantic(NonSemantic),
     // Markers used by compilers to update state or modes, but don't emit instructions
     #[operation(nested)]
     Marker(Marker),
+    /// Call to a non-inlined device function
+    Call(FunctionCall),
 }
 
 /// An instruction that contains a right hand side [`Operation`] and an optional out variable.
@@ -195,7 +199,9 @@ impl Display for Operation {
             Operation::NonSemantic(non_semantic) => write!(f, "{non_semantic}"),
             Operation::Barrier(barrier_ops) => write!(f, "{barrier_ops}"),
             Operation::Tma(tma_ops) => write!(f, "{tma_ops}"),
+            Operation::Texture(texture_ops) => write!(f, "{texture_ops}"),
             Operation::Marker(marker) => write!(f, "{marker}"),
+            Operation::Call(call) => write!(f, "{call}"),
         }
     }
 }
@@ -3,7 +3,7 @@ use core::fmt::Display;
 use alloc::string::ToString;
 use alloc::vec::Vec;
 
-use crate::{Allocator, TypeMap};
+use crate::{Allocator, TypeMap, ValidationErrors};
 
 use super::{Instruction, Variable};
 
@@ -19,6 +19,8 @@ pub struct ScopeProcessing {
     pub instructions: Vec<Instruction>,
     /// The type map
     pub typemap: TypeMap,
+    /// The validation errors of the kernel, for instructions a processor can't handle.
+    pub errors: ValidationErrors,
 }
 
 impl Display for ScopeProcessing {
@@ -20,6 +20,7 @@ use enumset::EnumSet;
 /// query this at compile time is currently available. As a result, the minimum value should usually
 /// be assumed.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HardwareProperties {
     /// The maximum size of a single load instruction, in bits. Used for optimized line sizes.
     pub load_width: u32,
@@ -39,6 +40,19 @@ pub struct HardwareProperties {
     pub max_cube_dim: (u32, u32, u32),
     /// Number of streaming multiprocessors (SM), if available
     pub num_streaming_multiprocessors: Option<u32>,
+    /// Maximum number of cubes resident at once on a single SM, if available
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_cubes_per_multiprocessor: Option<u32>,
+    /// Maximum number of units resident at once on a single SM, if available
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_units_per_multiprocessor: Option<u32>,
+    /// Amount of shared memory on a single SM, shared by all resident cubes, in bytes, if
+    /// available
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_shared_memory_per_multiprocessor: Option<usize>,
+    /// Number of 32-bit registers on a single SM, shared by all resident units, if available
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub registers_per_multiprocessor: Option<u32>,
     /// Number of available parallel cpu units, if the runtime is CPU.
     pub num_cpu_cores: Option<u32>,
     /// Number of tensor cores per SM, if any
@@ -52,6 +66,7 @@ pub struct HardwareProperties {
 
 /// Properties of the device related to allocation.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MemoryDeviceProperties {
     /// The maximum nr. of bytes that can be allocated in one go.
     pub max_page_size: u64,
@@ -62,6 +77,7 @@ pub struct MemoryDeviceProperties {
 /// Properties of what the device can do, like what `Feature` are
 /// supported by it and what its memory properties are.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeviceProperties {
     /// The features supported by the runtime.
     pub features: Features,
@@ -4,8 +4,8 @@ use enumset::EnumSet;
 use hashbrown::{HashMap, HashSet};
 
 use crate::{
-    BarrierLevel, CubeFnSource, DeviceProperties, ExpandElement, FastMath, Matrix, Processor,
-    SemanticType, SourceLoc, StorageType, TargetProperties, TypeHash,
+    BarrierLevel, Builtin, CubeFnSource, DeviceProperties, ExpandElement, FastMath, Function,
+    Matrix, Processor, SemanticType, SourceLoc, StorageType, TargetProperties, TypeHash,
 };
 
 use super::{
@@ -44,14 +44,29 @@ pub struct Scope {
     pub modes: Rc<RefCell<InstructionModes>>,
     #[cfg_attr(feature = "serde", serde(skip))]
     pub properties: Option<Rc<DeviceProperties>>,
+    /// Non-inlined functions called from this kernel, shared with all child scopes.
+    pub functions: Rc<RefCell<Vec<Function>>>,
+    /// Builtins captured by the function body this scope belongs to, if any.
+    #[type_hash(skip)]
+    captures: Option<Captures>,
 }
 
+/// Builtins read by a function body, with the parameter that replaces each of them.
+pub type Captures = Rc<RefCell<Vec<(Variable, Variable)>>>;
+
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, TypeHash)]
 pub struct ValidationErrors {
     errors: Rc<RefCell<Vec<String>>>,
 }
 
+impl ValidationErrors {
+    /// Adds a validation error.
+    pub fn push(&self, msg: impl Into<String>) {
+        self.errors.borrow_mut().push(msg.into());
+    }
+}
+
 /// Debug related fields, most of these are global
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, TypeHash)]
@@ -130,6 +145,8 @@ impl Scope {
             runtime_properties: Rc::new(Default::default()),
             modes: Default::default(),
             properties: None,
+            functions: Default::default(),
+            captures: None,
         }
     }
 
@@ -245,9 +262,48 @@ impl Scope {
             runtime_properties: self.runtime_properties.clone(),
             modes: self.modes.clone(),
             properties: self.properties.clone(),
+            functions: self.functions.clone(),
+            captures: self.captures.clone(),
+        }
+    }
+
+    /// Create an empty scope for the body of a [`Function`]. The body shares functions and
+    /// variable ids with this scope, but declares its own locals and captures the builtins it
+    /// reads.
+    pub fn function_body(&mut self) -> Self {
+        let mut scope = self.child();
+        scope.depth = 0;
+        scope.allocator = self.allocator.fork();
+        scope.captures = Some(Default::default());
+        scope
+    }
+
+    /// Read a builtin. Inside a function body, the builtin is replaced by a parameter that the
+    /// caller fills in.
+    pub fn builtin(&mut self, builtin: Builtin, ty: StorageType) -> Variable {
+        let var = Variable::builtin(builtin, ty);
+        let Some(captures) = &self.captures else {
+            return var;
+        };
+        let mut captures = captures.borrow_mut();
+        match captures.iter().find(|(captured, _)| *captured == var) {
+            Some((_, param)) => *param,
+            None => {
+                let param = *self.allocator.create_local(var.ty);
+                captures.push((var, param));
+                param
+            }
         }
     }
 
+    /// The builtins captured by this function body, with their parameters.
+    pub fn captures(&self) -> Vec<(Variable, Variable)> {
+        self.captures
+            .as_ref()
+            .map(|captures| captures.borrow().clone())
+            .unwrap_or_default()
+    }
+
     // Adds a validation error.
     pub fn push_error(&mut self, msg: impl Into<String>) {
         self.validation_errors.errors.borrow_mut().push(msg.into());
@@ -286,6 +342,7 @@ impl Scope {
             variables,
             instructions,
             typemap: self.typemap.clone(),
+            errors: self.validation_errors.clone(),
         };
 
         for p in processors {
@@ -324,6 +381,16 @@ impl Scope {
         ExpandElement::Plain(shared_array)
     }
 
+    /// Create a view of the dynamic shared memory with the given [item type](Item).
+    pub fn create_dynamic_shared_array<I: Into<Type>>(&mut self, item: I) -> ExpandElement {
+        let item = item.into();
+        let index = self.new_local_index();
+        ExpandElement::Plain(Variable::new(
+            VariableKind::DynamicSharedArray { id: index },
+            item,
+        ))
+    }
+
     /// Create a shared variable of the given [item type](Item).
     pub fn create_shared<I: Into<Type>>(&mut self, item: I) -> ExpandElement {
         let item = item.into();
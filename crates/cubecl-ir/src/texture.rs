@@ -0,0 +1,189 @@
+use crate::TypeHash;
+use alloc::{string::String, vec::Vec};
+use core::fmt::{Display, Write};
+
+use crate::{FromArgList, OperationReflect};
+
+use super::Variable;
+
+/// Dimensionality of a texture.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, TypeHash, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TextureDim {
+    D2,
+    D3,
+}
+
+impl TextureDim {
+    /// Number of coordinates needed to address a texel.
+    pub fn rank(&self) -> usize {
+        match self {
+            TextureDim::D2 => 2,
+            TextureDim::D3 => 3,
+        }
+    }
+}
+
+/// How texels are combined when sampling between texel centers.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, TypeHash, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FilterMode {
+    /// Take the closest texel.
+    Nearest,
+    /// Linearly interpolate between the neighbouring texels.
+    Linear,
+}
+
+/// How coordinates outside of `[0, 1]` are resolved.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, TypeHash, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AddressMode {
+    /// Clamp to the texels on the edge.
+    ClampToEdge,
+    /// Wrap around.
+    Repeat,
+    /// Wrap around, mirroring the texture on every repetition.
+    MirrorRepeat,
+}
+
+/// Comptime sampler state used when sampling a texture with normalized coordinates.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, TypeHash, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Sampler {
+    pub filter: FilterMode,
+    pub address: AddressMode,
+}
+
+impl Sampler {
+    /// Number of distinct sampler states.
+    pub const COUNT: usize = 6;
+
+    pub const fn new(filter: FilterMode, address: AddressMode) -> Self {
+        Self { filter, address }
+    }
+
+    /// Nearest filtering, clamped to the edges.
+    pub const fn nearest() -> Self {
+        Self::new(FilterMode::Nearest, AddressMode::ClampToEdge)
+    }
+
+    /// Linear filtering, clamped to the edges.
+    pub const fn linear() -> Self {
+        Self::new(FilterMode::Linear, AddressMode::ClampToEdge)
+    }
+
+    pub fn with_address(mut self, address: AddressMode) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Index of the sampler state, below [`Sampler::COUNT`]. Backends with native textures bind
+    /// one sampler per state, in this order, so launches don't depend on the samplers a kernel
+    /// uses.
+    pub fn index(self) -> usize {
+        self.to_bits() as usize
+    }
+
+    /// Every sampler state, ordered by [index](Sampler::index).
+    pub fn all() -> impl Iterator<Item = Sampler> {
+        (0..Self::COUNT as u32).map(Self::from_bits)
+    }
+
+    fn to_bits(self) -> u32 {
+        let filter = match self.filter {
+            FilterMode::Nearest => 0,
+            FilterMode::Linear => 1,
+        };
+        let address = match self.address {
+            AddressMode::ClampToEdge => 0,
+            AddressMode::Repeat => 1,
+            AddressMode::MirrorRepeat => 2,
+        };
+        filter | (address << 1)
+    }
+
+    fn from_bits(bits: u32) -> Self {
+        let filter = match bits & 1 {
+            0 => FilterMode::Nearest,
+            _ => FilterMode::Linear,
+        };
+        let address = match bits >> 1 {
+            0 => AddressMode::ClampToEdge,
+            1 => AddressMode::Repeat,
+            _ => AddressMode::MirrorRepeat,
+        };
+        Self::new(filter, address)
+    }
+}
+
+impl FromArgList for Sampler {
+    fn from_arg_list(args: &mut alloc::collections::VecDeque<Variable>) -> Self {
+        Sampler::from_bits(u32::from_arg_list(args))
+    }
+
+    fn as_arg_list(&self) -> impl IntoIterator<Item = Variable> {
+        [self.to_bits().into()]
+    }
+}
+
+impl Display for Sampler {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let filter = match self.filter {
+            FilterMode::Nearest => "nearest",
+            FilterMode::Linear => "linear",
+        };
+        let address = match self.address {
+            AddressMode::ClampToEdge => "clamp_to_edge",
+            AddressMode::Repeat => "repeat",
+            AddressMode::MirrorRepeat => "mirror_repeat",
+        };
+        write!(f, "sampler({filter}, {address})")
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, TypeHash, PartialEq, Eq, Hash, OperationReflect)]
+#[operation(opcode_name = TextureOpCode)]
+/// Operations available on a texture
+pub enum TextureOps {
+    /// Sample the texture at normalized coordinates, filtered by `sampler`.
+    Sample {
+        texture: Variable,
+        sampler: Sampler,
+        coordinates: Vec<Variable>,
+    },
+    /// Fetch a single texel at integer coordinates, without filtering.
+    Load {
+        texture: Variable,
+        coordinates: Vec<Variable>,
+    },
+}
+
+fn format_coordinates(coordinates: &[Variable]) -> String {
+    coordinates.iter().fold(String::new(), |mut s, coord| {
+        let _ = write!(s, ", {coord}");
+        s
+    })
+}
+
+impl Display for TextureOps {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TextureOps::Sample {
+                texture,
+                sampler,
+                coordinates,
+            } => {
+                let coords = format_coordinates(coordinates);
+                write!(f, "texture_sample({texture}, {sampler}{coords})")
+            }
+            TextureOps::Load {
+                texture,
+                coordinates,
+            } => {
+                let coords = format_coordinates(coordinates);
+                write!(f, "texture_load({texture}{coords})")
+            }
+        }
+    }
+}
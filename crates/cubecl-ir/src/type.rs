@@ -2,7 +2,7 @@ use super::{ConstantValue, Variable, VariableKind};
 use crate::{BarrierLevel, TypeHash};
 use core::fmt::Display;
 use cubecl_common::{
-    e2m1, e2m1x2, e2m3, e3m2, e4m3, e5m2, flex32,
+    df64, e2m1, e2m1x2, e2m3, e3m2, e4m3, e5m2, flex32,
     quant::scheme::{QuantParam, QuantValue},
     tf32, ue8m0,
 };
@@ -33,6 +33,9 @@ pub enum FloatKind {
     F32,
     TF32,
     F64,
+    /// Emulated double precision, stored as a pair of `f32`. Lowered by the compilers that
+    /// support it.
+    DF64,
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -78,6 +81,7 @@ pub enum SemanticType {
     BarrierToken,
     Pipeline,
     TensorMap,
+    Texture,
 }
 
 /// Physical type containing one or more elements
@@ -139,6 +143,7 @@ impl ElemType {
                 FloatKind::BF16 => core::mem::size_of::<half::bf16>(),
                 FloatKind::F32 => core::mem::size_of::<f32>(),
                 FloatKind::F64 => core::mem::size_of::<f64>(),
+                FloatKind::DF64 => core::mem::size_of::<df64>(),
                 FloatKind::Flex32 => core::mem::size_of::<f32>(),
                 FloatKind::TF32 => core::mem::size_of::<f32>(),
             },
@@ -171,6 +176,7 @@ impl ElemType {
                 | FloatKind::BF16
                 | FloatKind::F32
                 | FloatKind::F64
+                | FloatKind::DF64
                 | FloatKind::Flex32
                 | FloatKind::TF32 => self.size() * 8,
                 FloatKind::E2M1 => 4,
@@ -220,7 +226,9 @@ impl ElemType {
                 FloatKind::UE8M0 => ue8m0::MAX,
                 FloatKind::F16 => half::f16::MAX.to_f64(),
                 FloatKind::BF16 => half::bf16::MAX.to_f64(),
-                FloatKind::Flex32 | FloatKind::TF32 | FloatKind::F32 => f32::MAX as f64,
+                FloatKind::Flex32 | FloatKind::TF32 | FloatKind::F32 | FloatKind::DF64 => {
+                    f32::MAX as f64
+                }
                 FloatKind::F64 => f64::MAX,
             }
             .into(),
@@ -255,7 +263,9 @@ impl ElemType {
                 FloatKind::UE8M0 => ue8m0::MIN,
                 FloatKind::F16 => half::f16::MIN.to_f64(),
                 FloatKind::BF16 => half::bf16::MIN.to_f64(),
-                FloatKind::Flex32 | FloatKind::TF32 | FloatKind::F32 => f32::MIN as f64,
+                FloatKind::Flex32 | FloatKind::TF32 | FloatKind::F32 | FloatKind::DF64 => {
+                    f32::MIN as f64
+                }
                 FloatKind::F64 => f64::MIN,
             }
             .into(),
@@ -292,6 +302,7 @@ impl ElemType {
                 FloatKind::BF16 => 0.0078125, // bf16 epsilon ≈ 2^-7
                 FloatKind::Flex32 | FloatKind::F32 | FloatKind::TF32 => f32::EPSILON.into(),
                 FloatKind::F64 => f64::EPSILON,
+                FloatKind::DF64 => df64::EPSILON.to_f64(),
             },
             ElemType::Int(_) | ElemType::UInt(_) => 1.0, // step of 1
             ElemType::Bool => 1.0,
@@ -548,6 +559,7 @@ impl Display for ElemType {
                 FloatKind::TF32 => f.write_str("tf32"),
                 FloatKind::F32 => f.write_str("f32"),
                 FloatKind::F64 => f.write_str("f64"),
+                FloatKind::DF64 => f.write_str("df64"),
             },
             Self::Int(kind) => match kind {
                 IntKind::I8 => f.write_str("i8"),
@@ -572,6 +584,7 @@ impl Display for SemanticType {
             SemanticType::BarrierToken => f.write_str("barrier_token"),
             SemanticType::Pipeline => f.write_str("pipeline"),
             SemanticType::TensorMap => f.write_str("tensor_map"),
+            SemanticType::Texture => f.write_str("texture"),
         }
     }
 }
@@ -698,6 +711,12 @@ impl From<tf32> for ConstantValue {
     }
 }
 
+impl From<df64> for ConstantValue {
+    fn from(value: df64) -> Self {
+        ConstantValue::Float(value.to_f64())
+    }
+}
+
 impl From<f32> for ConstantValue {
     fn from(value: f32) -> Self {
         ConstantValue::Float(value as f64)
@@ -739,6 +758,7 @@ impl_into_variable!(
     flex32 => FloatKind::Flex32,
     tf32 => FloatKind::TF32,
     f64 => FloatKind::F64,
+    df64 => FloatKind::DF64,
 
     usize => UIntKind::U32,
     isize => IntKind::I32,
@@ -3,7 +3,7 @@ use core::{fmt::Display, hash::Hash};
 use crate::{BarrierLevel, FloatKind, IntKind, StorageType, TypeHash};
 
 use super::{ElemType, Matrix, Type, UIntKind};
-use cubecl_common::{e2m1, e4m3, e5m2, ue8m0};
+use cubecl_common::{df64, e2m1, e4m3, e5m2, ue8m0};
 use derive_more::From;
 use float_ord::FloatOrd;
 
@@ -47,8 +47,12 @@ pub enum VariableKind {
     GlobalInputArray(Id),
     GlobalOutputArray(Id),
     GlobalScalar(Id),
+    /// A scalar whose value is set when the pipeline is created, rather than when the kernel is
+    /// compiled.
+    SpecConstant(Id),
     TensorMapInput(Id),
     TensorMapOutput(Id),
+    Texture(Id),
     LocalArray {
         id: Id,
         length: usize,
@@ -76,6 +80,11 @@ pub enum VariableKind {
         unroll_factor: usize,
         alignment: Option<usize>,
     },
+    /// A typed view of the shared memory region whose size is set at launch. Every view of the
+    /// same kernel starts at the beginning of the region.
+    DynamicSharedArray {
+        id: Id,
+    },
     Shared {
         id: Id,
     },
@@ -138,8 +147,11 @@ impl Variable {
             VariableKind::GlobalOutputArray { .. } => false,
             VariableKind::TensorMapInput(_) => true,
             VariableKind::TensorMapOutput(_) => false,
+            VariableKind::Texture(_) => true,
+            VariableKind::SpecConstant(_) => true,
             VariableKind::LocalMut { .. } => false,
             VariableKind::SharedArray { .. } => false,
+            VariableKind::DynamicSharedArray { .. } => false,
             VariableKind::Shared { .. } => false,
             VariableKind::Matrix { .. } => false,
             VariableKind::LocalArray { .. } => false,
@@ -164,6 +176,7 @@ impl Variable {
                 | VariableKind::GlobalOutputArray { .. }
                 | VariableKind::ConstantArray { .. }
                 | VariableKind::SharedArray { .. }
+                | VariableKind::DynamicSharedArray { .. }
                 | VariableKind::LocalArray { .. }
                 | VariableKind::Matrix { .. }
         )
@@ -423,6 +436,7 @@ impl ConstantValue {
                         self.as_f64() as f32 as f64
                     }
                     FloatKind::F64 => self.as_f64(),
+                    FloatKind::DF64 => df64::from_f64(self.as_f64()).to_f64(),
                 }
                 .into(),
                 ElemType::Int(kind) => match kind {
@@ -473,12 +487,15 @@ impl Variable {
             | VariableKind::GlobalOutputArray(id)
             | VariableKind::TensorMapInput(id)
             | VariableKind::TensorMapOutput(id)
+            | VariableKind::Texture(id)
+            | VariableKind::SpecConstant(id)
             | VariableKind::GlobalScalar(id)
             | VariableKind::LocalMut { id, .. }
             | VariableKind::Versioned { id, .. }
             | VariableKind::LocalConst { id, .. }
             | VariableKind::ConstantArray { id, .. }
             | VariableKind::SharedArray { id, .. }
+            | VariableKind::DynamicSharedArray { id }
             | VariableKind::Shared { id, .. }
             | VariableKind::LocalArray { id, .. }
             | VariableKind::Matrix { id, .. } => Some(id),
@@ -502,6 +519,8 @@ impl Display for Variable {
             VariableKind::GlobalScalar(id) => write!(f, "scalar({id})"),
             VariableKind::TensorMapInput(id) => write!(f, "tensor_map({id})"),
             VariableKind::TensorMapOutput(id) => write!(f, "tensor_map({id})"),
+            VariableKind::Texture(id) => write!(f, "texture({id})"),
+            VariableKind::SpecConstant(id) => write!(f, "spec_constant({id})"),
             VariableKind::Constant(constant) => write!(f, "{}({constant})", self.ty),
             VariableKind::LocalMut { id } => write!(f, "local({id})"),
             VariableKind::Versioned { id, version } => {
@@ -510,6 +529,7 @@ impl Display for Variable {
             VariableKind::LocalConst { id } => write!(f, "binding({id})"),
             VariableKind::ConstantArray { id, .. } => write!(f, "const_array({id})"),
             VariableKind::SharedArray { id, .. } => write!(f, "shared_array({id})"),
+            VariableKind::DynamicSharedArray { id } => write!(f, "dynamic_shared_array({id})"),
             VariableKind::Shared { id } => write!(f, "shared({id})"),
             VariableKind::LocalArray { id, .. } => write!(f, "array({id})"),
             VariableKind::Matrix { id, .. } => write!(f, "matrix({id})"),
@@ -84,8 +84,11 @@ pub enum Expression {
         ident: Ident,
         args: Vec<Expression>,
     },
-    Continue(Span),
-    Return(Span),
+    Continue,
+    Return {
+        expr: Option<Box<Expression>>,
+        span: Span,
+    },
     ForLoop {
         range: Box<Expression>,
         unroll: Option<Box<Expression>>,
@@ -195,7 +198,7 @@ impl Expression {
             Expression::FunctionCall { .. } => None,
             Expression::Break => None,
             Expression::Cast { to, .. } => Some(to.clone()),
-            Expression::Continue { .. } => None,
+            Expression::Continue => None,
             Expression::Return { .. } => None,
             Expression::ForLoop { .. } => None,
             Expression::FieldAccess { .. } => None,
@@ -1,12 +1,15 @@
+use std::mem::replace;
+
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned};
-use syn::{GenericArgument, Ident, Member, Pat, Path, PathArguments, spanned::Spanned};
+use syn::{GenericArgument, Ident, Member, Pat, Path, PathArguments, Type, spanned::Spanned};
 
 use crate::{
     expression::{Block, Expression, MatchArm},
     operator::Operator,
     paths::{frontend_path, frontend_type, prelude_type},
     scope::Context,
+    statement::Statement,
 };
 
 macro_rules! error {
@@ -268,11 +271,32 @@ impl Expression {
                 let path = frontend_path();
                 quote![#path::branch::break_expand(scope);]
             }
-            Expression::Continue(span) => error!(*span, "Continue not supported yet"),
-            Expression::Return(span) => error!(
-                *span,
-                "Return not supported yet. Consider using the terminate!() macro instead."
-            ),
+            Expression::Continue => {
+                context.has_continue = true;
+                let path = frontend_path();
+                quote![#path::branch::continue_expand(scope);]
+            }
+            Expression::Return { span, .. } if context.in_closure => {
+                error!(*span, "Return is not supported in closures")
+            }
+            Expression::Return { expr, .. } => {
+                context.has_return = true;
+                match expr {
+                    Some(expr) => {
+                        let ret_ty = context.return_type.clone();
+                        let expr = expr
+                            .as_const(context)
+                            .unwrap_or_else(|| expr.to_tokens(context));
+                        quote! {
+                            {
+                                let _ret = #expr;
+                                __return.ret_value::<#ret_ty>(scope, _ret.into());
+                            }
+                        }
+                    }
+                    None => quote![__return.ret(scope);],
+                }
+            }
             Expression::Cast { from, to } => {
                 let cast = prelude_type("Cast");
                 let from = from.to_tokens(context);
@@ -297,22 +321,34 @@ impl Expression {
                     .as_ref()
                     .and_then(|it| it.as_const(context))
                     .unwrap_or(quote![false]);
-                let block = context.in_fn_mut(scope, |ctx| block.to_tokens(ctx));
+                let (block, has_return, has_continue) =
+                    context.in_loop(|ctx| ctx.in_fn_mut(scope, |ctx| block.to_tokens(ctx)));
                 let var_ty = var_ty.as_ref().map(|it| quote![: #it]);
+                let for_expand = match has_continue {
+                    true => quote![for_continue_expand],
+                    false => quote![for_expand],
+                };
+                let propagate = has_return.then(|| quote![__return.propagate(scope);]);
 
                 quote! {
                     {
                         let _range = #range;
                         let _unroll = #unroll;
-                        #for_ty::for_expand(scope, _range, _unroll, |scope, #var_name #var_ty| #block);
+                        #for_ty::#for_expand(scope, _range, _unroll, |scope, #var_name #var_ty| #block);
+                        #propagate
                     }
                 }
             }
             Expression::Loop { block, scope } => {
                 let loop_ty = frontend_type("branch");
-                let block = context.in_fn_mut(scope, |ctx| block.to_tokens(ctx));
+                let (block, has_return, _) =
+                    context.in_loop(|ctx| ctx.in_fn_mut(scope, |ctx| block.to_tokens(ctx)));
+                let propagate = has_return.then(|| quote![__return.propagate(scope);]);
 
-                quote![#loop_ty::loop_expand(scope, |scope| #block);]
+                quote! {
+                    #loop_ty::loop_expand(scope, |scope| #block);
+                    #propagate
+                }
             }
             Expression::If {
                 condition,
@@ -529,7 +565,9 @@ impl Expression {
                 scope,
             } => {
                 // Without knowing the closure type, we need to assume it's `FnMut`
+                let in_closure = replace(&mut context.in_closure, true);
                 let body = context.in_fn_mut(scope, |ctx| body.to_tokens(ctx));
+                context.in_closure = in_closure;
                 quote![|scope, #(#params),*| #body]
             }
             Expression::Verbatim { tokens, .. } => tokens.clone(),
@@ -688,6 +726,67 @@ impl Block {
             }
         }
     }
+
+    /// Expand the body of a function. If it contains an early `return`, the body is wrapped in a
+    /// loop that the returns break out of.
+    pub fn to_fn_tokens(&self, context: &mut Context) -> TokenStream {
+        let inner: Vec<_> = self.inner.iter().map(|it| it.to_tokens(context)).collect();
+        let ret = self.ret.as_ref().map(|ret| {
+            ret.as_const(context)
+                .unwrap_or_else(|| ret.to_tokens(context))
+        });
+
+        if !context.has_return {
+            let ret = ret.unwrap_or_else(|| quote![()]);
+            return quote! {
+                {
+                    #(#inner)*
+                    #ret
+                }
+            };
+        }
+
+        let branch = frontend_type("branch");
+        let ret_ty = &context.return_type;
+        let is_unit = matches!(ret_ty, Type::Tuple(tuple) if tuple.elems.is_empty());
+        let ret = match ret {
+            Some(ret) if !is_unit => quote! {
+                let _ret = #ret;
+                __return.ret_value::<#ret_ty>(scope, _ret.into());
+            },
+            Some(ret) => quote! {
+                #ret;
+                __return.ret(scope);
+            },
+            None if ends_with_return(&self.inner) => quote![],
+            None => quote![__return.ret(scope);],
+        };
+        let finish = match is_unit {
+            true => quote![__return.finish_unit(scope, __return_body)],
+            false => quote![__return.finish::<#ret_ty>(scope, __return_body)],
+        };
+
+        quote! {
+            {
+                let __return = #branch::ReturnExpand::new(scope);
+                let mut __return_body = scope.child();
+                {
+                    let scope = &mut __return_body;
+                    #(#inner)*
+                    #ret
+                }
+                #finish
+            }
+        }
+    }
+}
+
+fn ends_with_return(statements: &[Statement]) -> bool {
+    matches!(
+        statements.last(),
+        Some(Statement::Expression { expression, .. })
+            if matches!(**expression, Expression::Return { .. })
+    )
 }
 
 fn split_generics(path: &Expression, context: &mut Context) -> (PathArguments, TokenStream) {
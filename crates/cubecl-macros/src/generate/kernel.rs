@@ -20,7 +20,7 @@ impl KernelFn {
         let vis = &self.vis;
         let sig = &self.sig;
         let body = match &self.body {
-            KernelBody::Block(block) => &block.to_tokens(&mut self.context),
+            KernelBody::Block(block) => &block.to_fn_tokens(&mut self.context),
             KernelBody::Verbatim(tokens) => tokens,
         };
         let name = &self.full_name;
@@ -65,6 +65,11 @@ impl KernelFn {
                 quote![#fast_math(scope, #value, |scope| {#body})]
             })
             .unwrap_or_else(|| quote![#body]);
+        let body = if self.args.noinline.is_present() {
+            self.noinline_body(body)
+        } else {
+            body
+        };
 
         let out = quote! {
             #vis #sig {
@@ -80,6 +85,41 @@ impl KernelFn {
     }
 }
 
+impl KernelFn {
+    /// Wrap the body in a call to a separate function, specialized on the generics and comptime
+    /// arguments.
+    fn noinline_body(&self, body: TokenStream) -> TokenStream {
+        let function = frontend_type("FunctionExpand");
+        let name = &self.full_name;
+
+        let generics = self.sig.generics.type_params().map(|param| {
+            let ident = &param.ident;
+            quote![__function.generic::<#ident>();]
+        });
+        let comptime = self
+            .sig
+            .parameters
+            .iter()
+            .filter(|it| it.is_const)
+            .map(|param| {
+                let name = &param.name;
+                quote![__function.comptime_arg(&#name);]
+            });
+        let params = self.sig.runtime_params().map(|param| {
+            let name = &param.name;
+            quote![let #name = __function.param(scope, #name);]
+        });
+
+        quote! {
+            let mut __function = #function::new(#name, module_path!(), line!());
+            #(#generics)*
+            #(#comptime)*
+            #(#params)*
+            __function.call(scope, |scope| {#body})
+        }
+    }
+}
+
 impl ToTokens for KernelSignature {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let scope = prelude_type("Scope");
@@ -357,6 +397,13 @@ impl Launch {
     pub fn kernel_definition(&self) -> TokenStream {
         if self.args.is_launch() {
             let kernel_metadata = prelude_type("KernelMetadata");
+            let fusable = self.args.fusable.is_present().then(|| {
+                quote! {
+                    fn fusable(&self) -> bool {
+                        true
+                    }
+                }
+            });
             let cube_kernel = prelude_type("CubeKernel");
             let kernel_settings = prelude_type("KernelSettings");
             let compute_client = prelude_type("ComputeClient");
@@ -429,6 +476,8 @@ impl Launch {
                     fn address_type(&self) -> #storage_ty {
                         self.settings.address_type.unsigned_type()
                     }
+
+                    #fusable
                 }
 
                 impl #generics #cube_kernel for #kernel_name #generic_names #where_clause {
@@ -33,6 +33,10 @@ mod statement;
 /// * `debug` - panics after generation to print the output to console
 /// * `create_dummy_kernel` - Generates a function to create a kernel without launching it. Used for
 ///   testing.
+/// * `noinline` - emits the function as a separate device function and calls it, instead of
+///   inlining it into the caller. Only primitives and lines can be passed and returned.
+/// * `fusable` - allows launches of the kernel to be fused with other element-wise launches when
+///   fusion is enabled on the client. Every unit must only access the elements at its own position.
 ///
 /// # Trait arguments
 /// * `expand_base_traits` - base traits for the expanded "second half" of a trait with methods.
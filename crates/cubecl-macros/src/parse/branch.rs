@@ -1,3 +1,4 @@
+use proc_macro2::Span;
 use quote::quote;
 use syn::{
     Expr, ExprForLoop, ExprIf, ExprLoop, ExprMatch, Ident, Pat, parse_quote, spanned::Spanned,
@@ -38,7 +39,7 @@ pub fn expand_for_loop(
         .map_err(|_| syn::Error::new(span, "Unsupported for loop expression"))?;
 
     if right.is_const() && !matches!(right, Expression::Range { .. }) {
-        return expand_for_in_loop(var.ident, right, for_loop.body, context);
+        return expand_for_in_loop(var.ident, right, for_loop.body, span, context);
     }
 
     let (block, scope) = context.in_scope(|context| {
@@ -66,6 +67,7 @@ fn expand_for_in_loop(
     var_name: Ident,
     right: Expression,
     block: syn::Block,
+    span: Span,
     context: &mut Context,
 ) -> syn::Result<Expression> {
     let statements = block
@@ -75,7 +77,19 @@ fn expand_for_in_loop(
         .collect::<Result<Vec<_>, _>>()?;
 
     let right = right.to_tokens(context);
-    let statements = statements.into_iter().map(|it| it.to_tokens(context));
+    // The loop is unrolled at expansion time, so there's no runtime loop to continue.
+    let (statements, _, has_continue) = context.in_loop(|ctx| {
+        statements
+            .into_iter()
+            .map(|it| it.to_tokens(ctx))
+            .collect::<Vec<_>>()
+    });
+    if has_continue {
+        return Err(syn::Error::new(
+            span,
+            "Continue is not supported in loops over comptime values",
+        ));
+    }
     let for_loop = Expression::VerbatimTerminated {
         tokens: quote! {
             for #var_name in #right {
@@ -20,7 +20,7 @@ use super::{
 // All supported primitives. Primitives don't start with an uppercase letter
 pub(crate) const PRIMITIVES: &[&str] = &[
     "bool", "i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64", "f16", "bf16", "f32", "f64",
-    "flex32", "e2m1", "e2m1x2", "e2m3", "e3m2", "e4m3", "e5m2", "ue8m0", "usize", "isize",
+    "flex32", "df64", "e2m1", "e2m1x2", "e2m3", "e3m2", "e4m3", "e5m2", "ue8m0", "usize", "isize",
 ];
 
 impl Expression {
@@ -186,8 +186,24 @@ impl Expression {
             Expr::Const(block) => Expression::Verbatim {
                 tokens: quote![#block],
             },
-            Expr::Continue(cont) => Expression::Continue(cont.span()),
-            Expr::Return(ret) => Expression::Return(ret.span()),
+            Expr::Continue(cont) => {
+                if let Some(label) = cont.label {
+                    return Err(syn::Error::new_spanned(
+                        label,
+                        "Labeled continue is not supported",
+                    ));
+                }
+                Expression::Continue
+            }
+            Expr::Return(ret) => {
+                let span = ret.span();
+                let expr = ret
+                    .expr
+                    .map(|expr| Expression::from_expr(*expr, context))
+                    .transpose()?
+                    .map(Box::new);
+                Expression::Return { expr, span }
+            }
             Expr::ForLoop(for_loop) => expand_for_loop(for_loop, context)?,
             Expr::Loop(loop_expr) => expand_loop(loop_expr, context)?,
             Expr::If(if_expr) => expand_if(if_expr, context)?,
@@ -73,12 +73,12 @@ pub struct RemoveHelpers;
 impl VisitMut for RemoveHelpers {
     fn visit_fn_arg_mut(&mut self, i: &mut syn::FnArg) {
         match i {
-            syn::FnArg::Receiver(recv) => recv
-                .attrs
-                .retain(|it| !is_comptime_attr(it) && !is_define_attribute(it)),
-            syn::FnArg::Typed(typed) => typed
-                .attrs
-                .retain(|it| !is_comptime_attr(it) && !is_define_attribute(it)),
+            syn::FnArg::Receiver(recv) => recv.attrs.retain(|it| {
+                !is_comptime_attr(it) && !is_define_attribute(it) && !is_spec_const_attr(it)
+            }),
+            syn::FnArg::Typed(typed) => typed.attrs.retain(|it| {
+                !is_comptime_attr(it) && !is_define_attribute(it) && !is_spec_const_attr(it)
+            }),
         }
         visit_mut::visit_fn_arg_mut(self, i);
     }
@@ -210,6 +210,10 @@ pub fn is_comptime_attr(attr: &Attribute) -> bool {
     attr.path().is_ident("comptime")
 }
 
+pub fn is_spec_const_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("spec_const")
+}
+
 pub fn is_unroll_attr(attr: &Attribute) -> bool {
     attr.path().is_ident("unroll")
 }
@@ -227,4 +231,5 @@ pub fn is_helper(attr: &Attribute) -> bool {
         || is_unroll_attr(attr)
         || is_expr_attribute(attr)
         || is_define_attribute(attr)
+        || is_spec_const_attr(attr)
 }
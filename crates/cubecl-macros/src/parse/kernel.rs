@@ -14,7 +14,11 @@ use syn::{
     visit_mut::VisitMut,
 };
 
-use super::{desugar::Desugar, helpers::is_comptime_attr, statement::parse_pat};
+use super::{
+    desugar::Desugar,
+    helpers::{is_comptime_attr, is_spec_const_attr},
+    statement::parse_pat,
+};
 
 #[derive(Default, FromMeta, Clone)]
 pub(crate) struct KernelArgs {
@@ -24,8 +28,12 @@ pub(crate) struct KernelArgs {
     // Force override because macro hygiene can cause weird issues
     pub no_debug_symbols: Flag,
     pub fast_math: Option<Expr>,
+    /// Emit a real function and call it instead of inlining the body
+    pub noinline: Flag,
     pub debug: Flag,
     pub create_dummy_kernel: Flag,
+    /// Allow launches of the kernel to be fused with other element-wise launches
+    pub fusable: Flag,
     pub cluster_dim: Option<Expr>,
     pub src_file: Option<LitStr>,
     /// Base traits for a split expand trait
@@ -358,12 +366,16 @@ impl KernelParam {
             ..
         } = parse_pat(*param.pat.clone())?;
         let mut is_const = false;
+        let mut is_spec_const = false;
         let mut defines = Vec::new();
 
         for attr in param.attrs.iter() {
             if is_comptime_attr(attr) {
                 is_const = true;
             }
+            if is_spec_const_attr(attr) {
+                is_spec_const = true;
+            }
             if attr.path().is_ident("define") {
                 match attr.parse_args::<Ident>() {
                     Ok(ident) => {
@@ -383,8 +395,12 @@ impl KernelParam {
             }
         }
 
-        let ty = *param.ty.clone();
-        let normalized_ty = normalize_kernel_ty(*param.ty, is_const, &mut is_ref, &mut is_mut);
+        let mut ty = *param.ty;
+        if is_spec_const {
+            let spec_const = frontend_type("SpecConst");
+            ty = parse_quote!(#spec_const<#ty>);
+        }
+        let normalized_ty = normalize_kernel_ty(ty.clone(), is_const, &mut is_ref, &mut is_mut);
 
         Ok(Self {
             name: ident,
@@ -492,6 +508,14 @@ impl KernelFn {
         let debug_symbols = cfg_debug || args.debug_symbols.is_present();
 
         let span = Span::call_site();
+        if args.noinline.is_present()
+            && let Some(receiver) = sig.receiver()
+        {
+            return Err(syn::Error::new_spanned(
+                receiver,
+                "`noinline` functions can't take `self`",
+            ));
+        }
         let sig = KernelSignature::from_signature(sig, args)?;
         let mut context = Context::new(sig.returns.ty(), debug_symbols);
         context.extend(sig.parameters.clone());
@@ -567,6 +591,13 @@ impl Launch {
             &args,
         )?;
 
+        if args.is_launch() && args.noinline.is_present() {
+            return Err(syn::Error::new_spanned(
+                &func.sig.name,
+                "Launch kernels can't be `noinline`",
+            ));
+        }
+
         // Bail early if the user tries to have a return type in a launch kernel.
         if args.is_launch()
             && let ReturnType::Type(arrow, ty) = &ret
@@ -52,6 +52,12 @@ pub struct Context {
     level: usize,
     mut_scope_idx: usize,
     pub debug_symbols: bool,
+    /// Whether a `return` was expanded in the function so far.
+    pub has_return: bool,
+    /// Whether a `continue` was expanded in the innermost loop so far.
+    pub has_continue: bool,
+    /// Whether a closure body is being expanded.
+    pub in_closure: bool,
 }
 
 impl Context {
@@ -77,6 +83,9 @@ impl Context {
             level: 0,
             mut_scope_idx: 0,
             debug_symbols,
+            has_return: false,
+            has_continue: false,
+            in_closure: false,
         }
     }
 
@@ -136,6 +145,19 @@ impl Context {
         res
     }
 
+    /// Expand the body of a loop, returning whether it contains a `return` and whether it
+    /// contains a `continue` targeting this loop.
+    pub fn in_loop<T>(&mut self, with: impl FnOnce(&mut Self) -> T) -> (T, bool, bool) {
+        let has_return = replace(&mut self.has_return, false);
+        let has_continue = replace(&mut self.has_continue, false);
+        let res = with(self);
+        let loop_return = self.has_return;
+        let loop_continue = self.has_continue;
+        self.has_return |= has_return;
+        self.has_continue = has_continue;
+        (res, loop_return, loop_continue)
+    }
+
     pub fn variable(&self, name: &Ident) -> Option<ManagedVar> {
         // Walk through each scope backwards until we find the variable.
         let scopes = self.scopes.iter().rev();
@@ -64,10 +64,18 @@ impl DomFrontiers {
         let nodes = opt.node_ids().into_iter().map(|it| (it, HashSet::new()));
         let mut dom_frontiers: HashMap<NodeIndex, HashSet<NodeIndex>> = nodes.collect();
 
-        for node in opt.node_ids() {
+        let entry = opt.entry();
+        let is_reachable = |node| node == entry || doms.immediate_dominator(node).is_some();
+
+        for node in opt
+            .node_ids()
+            .into_iter()
+            .filter(|node| is_reachable(*node))
+        {
             let predecessors = opt.predecessors(node);
             if predecessors.len() >= 2 {
-                for predecessor in predecessors {
+                // Unreachable blocks (i.e. code after a `break`) don't contribute to the frontiers
+                for predecessor in predecessors.into_iter().filter(|pred| is_reachable(*pred)) {
                     let mut runner = predecessor;
                     while runner != doms.immediate_dominator(node).unwrap() {
                         dom_frontiers.get_mut(&runner).unwrap().insert(node);
@@ -3,7 +3,7 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use cubecl_ir::Id;
 use petgraph::graph::NodeIndex;
 
-use crate::{Optimizer, analyses::post_order::PostOrder};
+use crate::{ControlFlow, Optimizer, analyses::post_order::PostOrder};
 
 use super::Analysis;
 
@@ -87,6 +87,14 @@ fn calculate_block_sets(opt: &mut Optimizer, block: NodeIndex) -> BlockSets {
 
     let ops = opt.program[block].ops.clone();
 
+    // The returned value is read after all operations in the block
+    let control_flow = opt.program[block].control_flow.clone();
+    if let ControlFlow::Return { value: Some(value) } = &*control_flow.borrow()
+        && let Some(id) = opt.local_variable_id(value)
+    {
+        generated.insert(id);
+    }
+
     for op in ops.borrow_mut().values_mut().rev() {
         // Reads must be tracked after writes
         opt.visit_out(&mut op.out, |opt, var| {
@@ -26,7 +26,8 @@ impl Analysis for Uniformity {
 impl Uniformity {
     fn run(&mut self, opt: &Optimizer) {
         let root = opt.entry();
-        self.block_uniformity.insert(root, true);
+        // Functions can be called from divergent control flow, so their body is never uniform
+        self.block_uniformity.insert(root, !opt.in_function);
         while self.analyze_block(opt, root).is_none() {}
     }
 
@@ -159,7 +160,7 @@ impl Uniformity {
                 self.block_uniformity
                     .insert(*merge, is_uniform && block_uniform);
             }
-            ControlFlow::Return => {}
+            ControlFlow::Return { .. } => {}
             ControlFlow::None => {
                 let successor = opt.successors(block_id)[0];
                 self.block_uniformity
@@ -208,10 +209,12 @@ impl Uniformity {
         match var.kind {
             VariableKind::ConstantArray { .. }
             | VariableKind::SharedArray { .. }
+            | VariableKind::DynamicSharedArray { .. }
             | VariableKind::Shared { .. }
             | VariableKind::GlobalInputArray(_)
             | VariableKind::GlobalOutputArray(_)
             | VariableKind::GlobalScalar(_)
+            | VariableKind::SpecConstant(_)
             | VariableKind::Constant(_) => true,
             VariableKind::Builtin(builtin) => match builtin {
                 Builtin::UnitPosPlane
@@ -256,6 +259,7 @@ impl Uniformity {
             }
             VariableKind::TensorMapInput(_) => true,
             VariableKind::TensorMapOutput(_) => true,
+            VariableKind::Texture(_) => true,
         }
     }
 
@@ -49,6 +49,7 @@ impl Optimizer {
                 ControlFlow::IfElse { cond, .. } => visit_read(self, cond),
                 ControlFlow::LoopBreak { break_cond, .. } => visit_read(self, break_cond),
                 ControlFlow::Switch { value, .. } => visit_read(self, value),
+                ControlFlow::Return { value: Some(value) } => visit_read(self, value),
                 _ => {}
             };
         }
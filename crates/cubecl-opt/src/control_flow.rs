@@ -7,7 +7,7 @@ use cubecl_ir::{
     Arithmetic, BinaryOperator, Branch, Comparison, ConstantValue, ElemType, If, IfElse,
     Instruction, Loop, Marker, Operation, RangeLoop, Switch, Type, Variable, VariableKind,
 };
-use petgraph::{Direction, graph::EdgeIndex, visit::EdgeRef};
+use petgraph::{Direction, algo::has_path_connecting, graph::EdgeIndex, visit::EdgeRef};
 use stable_vec::StableVec;
 
 /// Control flow that terminates a block
@@ -44,8 +44,9 @@ pub enum ControlFlow {
         merge: NodeIndex,
     },
     /// A return statement. This should only occur once in the program and all other returns should
-    /// instead branch to this single return block.
-    Return,
+    /// instead branch to this single return block. `value` is the value returned from a function
+    /// body, and is always `None` for kernels.
+    Return { value: Option<Variable> },
     /// No special control flow. The block must have exactly one edge that should be followed.
     #[default]
     None,
@@ -71,6 +72,14 @@ impl Optimizer {
                 let loop_break = self.loop_break.back().expect("Can't break outside loop");
                 self.program.add_edge(current_block, *loop_break, 0);
             }
+            Branch::Continue => {
+                let current_block = self.current_block.take().unwrap();
+                let loop_continue = self
+                    .loop_continue
+                    .back()
+                    .expect("Can't continue outside loop");
+                self.program.add_edge(current_block, *loop_continue, 0);
+            }
         }
     }
 
@@ -222,24 +231,31 @@ impl Optimizer {
         let body = self.program.add_node(BasicBlock::default());
         let next = self.program.add_node(BasicBlock::default());
 
+        let continue_target = self.program.add_node(BasicBlock::default());
+
         self.program.add_edge(header, body, 0);
 
         self.loop_break.push_back(next);
+        self.loop_continue.push_back(continue_target);
 
         self.current_block = Some(body);
         self.parse_scope(loop_.scope);
-        let continue_target = self.program.add_node(BasicBlock::default());
         self.program[continue_target]
             .block_use
             .push(BlockUse::ContinueTarget);
 
         self.loop_break.pop_back();
+        self.loop_continue.pop_back();
 
         if let Some(current_block) = self.current_block {
             self.program.add_edge(current_block, continue_target, 0);
         }
 
-        self.program.add_edge(continue_target, header, 0);
+        // A loop that always breaks has no back edge, the continue target is then kept
+        // unreachable for structured targets.
+        if self.is_reachable(continue_target) {
+            self.program.add_edge(continue_target, header, 0);
+        }
 
         *self.program[header].control_flow.borrow_mut() = ControlFlow::Loop {
             body,
@@ -273,27 +289,45 @@ impl Optimizer {
         self.program.add_edge(header, body, 0);
         self.program.add_edge(header, next, 0);
 
+        // Only used when the body contains a `continue`, since the increment must then be
+        // shared by every path back to the header, or when the body never loops back.
+        let continue_block = self.program.add_node(BasicBlock::default());
+
         self.loop_break.push_back(next);
+        self.loop_continue.push_back(continue_block);
 
         self.current_block = Some(body);
         self.parse_scope(range_loop.scope);
 
         self.loop_break.pop_back();
-
-        let current_block = self.current_block.expect("For loop has no loopback path");
-
-        let continue_target = if self.program[current_block]
-            .block_use
-            .contains(&BlockUse::Merge)
-        {
-            let target = self.program.add_node(BasicBlock::default());
-            self.program.add_edge(current_block, target, 0);
-            target
-        } else {
-            current_block
+        self.loop_continue.pop_back();
+
+        let continue_target = match self.current_block {
+            Some(current_block) if !self.has_predecessors(continue_block) => {
+                self.program.remove_node(continue_block);
+                if self.program[current_block]
+                    .block_use
+                    .contains(&BlockUse::Merge)
+                {
+                    let target = self.program.add_node(BasicBlock::default());
+                    self.program.add_edge(current_block, target, 0);
+                    target
+                } else {
+                    current_block
+                }
+            }
+            Some(current_block) => {
+                self.program.add_edge(current_block, continue_block, 0);
+                continue_block
+            }
+            None => continue_block,
         };
 
-        self.program.add_edge(continue_target, header, 0);
+        // See `parse_loop`, the continue target of a loop that never loops back is unreachable.
+        let loops_back = self.is_reachable(continue_target);
+        if loops_back {
+            self.program.add_edge(continue_target, header, 0);
+        }
 
         self.program[continue_target]
             .block_use
@@ -324,13 +358,26 @@ impl Optimizer {
                 merge: next,
             };
         }
-        self.program[current_block]
-            .ops
-            .borrow_mut()
-            .push(Instruction::new(
-                Arithmetic::Add(BinaryOperator { lhs: i, rhs: step }),
-                i,
-            ));
+        if loops_back {
+            self.program[continue_target]
+                .ops
+                .borrow_mut()
+                .push(Instruction::new(
+                    Arithmetic::Add(BinaryOperator { lhs: i, rhs: step }),
+                    i,
+                ));
+        }
+    }
+
+    fn has_predecessors(&self, block: NodeIndex) -> bool {
+        self.program
+            .edges_directed(block, Direction::Incoming)
+            .next()
+            .is_some()
+    }
+
+    fn is_reachable(&self, block: NodeIndex) -> bool {
+        has_path_connecting(&self.program.graph, self.program.root, block, None)
     }
 
     pub(crate) fn split_critical_edges(&mut self) {
@@ -340,8 +387,15 @@ impl Optimizer {
             let successors: Vec<_> = successors.collect();
 
             if successors.len() > 1 {
+                // The exit edge of a loop header must stay pointed at the structured merge, since
+                // `break`s inside the body branch to it as well
+                let loop_merge = match &*self.block(block).control_flow.borrow() {
+                    ControlFlow::LoopBreak { merge, .. } => Some(*merge),
+                    _ => None,
+                };
                 let crit = successors
                     .iter()
+                    .filter(|(_, b)| Some(*b) != loop_merge)
                     .filter(|(_, b)| self.predecessors(*b).len() > 1)
                     .collect::<Vec<_>>();
                 for (edge, successor) in crit {
@@ -174,7 +174,10 @@ impl Display for Optimizer {
                         merge.index()
                     )?;
                 }
-                super::ControlFlow::Return => writeln!(f, "    return;")?,
+                super::ControlFlow::Return { value: None } => writeln!(f, "    return;")?,
+                super::ControlFlow::Return { value: Some(value) } => {
+                    writeln!(f, "    return {value};")?
+                }
                 super::ControlFlow::None => {
                     let edge = self.program.edges(node).next();
                     let target = edge.map(|it| it.target().index()).unwrap_or(255);
@@ -383,7 +386,10 @@ impl Display for BasicBlock {
                     merge.index()
                 )?;
             }
-            super::ControlFlow::Return => writeln!(f, "    return;")?,
+            super::ControlFlow::Return { value: None } => writeln!(f, "    return;")?,
+            super::ControlFlow::Return { value: Some(value) } => {
+                writeln!(f, "    return {value};")?
+            }
             super::ControlFlow::None => {
                 writeln!(f, "    branch;")?;
             }
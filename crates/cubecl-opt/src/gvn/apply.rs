@@ -149,7 +149,11 @@ impl GvnState {
     pub fn eliminate(&mut self, opt: &mut Optimizer, changes: &AtomicCounter) {
         let changes_pre = changes.get();
         for block in opt.node_ids() {
-            let leaders = &self.block_sets[&block].leaders;
+            // Unreachable continue targets aren't part of the dominator tree
+            let Some(sets) = self.block_sets.get(&block) else {
+                continue;
+            };
+            let leaders = &sets.leaders;
             for op in opt.program[block].ops.borrow_mut().values_mut() {
                 if let Some(leader) = self.values.lookup_op(op).and_then(|val| leaders.get(&val)) {
                     let var = leader.as_var();
@@ -84,6 +84,8 @@ pub fn value_of_var(var: &Variable) -> Option<Value> {
         } => Value::ConstArray(id, item, length, unroll_factor),
         VariableKind::LocalMut { .. }
         | VariableKind::SharedArray { .. }
+        | VariableKind::DynamicSharedArray { .. }
+        | VariableKind::SpecConstant(_)
         | VariableKind::Shared { .. }
         | VariableKind::LocalArray { .. }
         | VariableKind::Matrix { .. } => None?,
@@ -94,6 +96,7 @@ pub fn value_of_var(var: &Variable) -> Option<Value> {
         }
         VariableKind::TensorMapInput(_) => panic!("Tensor map is not supported"),
         VariableKind::TensorMapOutput(_) => panic!("Tensor map is not supported"),
+        VariableKind::Texture(_) => panic!("Texture is not supported"),
     };
     Some(val)
 }
@@ -121,7 +121,9 @@ impl ValueTable {
             Operation::Bitwise(bitwise) => self.create_expr_simple_op(bitwise, inst.out()),
             Operation::Operator(operator) => self.create_expr_operator(operator, inst.out()),
             Operation::Metadata(metadata) => self.create_expr_meta(metadata, inst.out()),
-            Operation::Plane(_) | Operation::Atomic(_) => Err(value_of_var(&inst.out())),
+            Operation::Plane(_) | Operation::Atomic(_) | Operation::Texture(_) => {
+                Err(value_of_var(&inst.out()))
+            }
             Operation::Branch(_)
             | Operation::Synchronization(_)
             | Operation::CoopMma(_)
@@ -129,6 +131,8 @@ impl ValueTable {
             | Operation::Barrier(_)
             | Operation::Tma(_)
             | Operation::Marker(_) => Err(None),
+            // The body may depend on state that isn't passed in, i.e. plane operations
+            Operation::Call(_) => Err(inst.out.as_ref().and_then(value_of_var)),
         }
     }
 
@@ -1,6 +1,6 @@
 use cubecl_ir::{
     Arithmetic, AtomicOp, BarrierOps, BinaryOperator, Bitwise, Comparison, CoopMma, Instruction,
-    Metadata, NonSemantic, Operation, Operator, Plane, TmaOps, UnaryOperator, Variable,
+    Metadata, NonSemantic, Operation, Operator, Plane, TextureOps, TmaOps, UnaryOperator, Variable,
 };
 
 use super::Optimizer;
@@ -51,10 +51,16 @@ impl Optimizer {
             Operation::Branch(_) => unreachable!(),
             Operation::Barrier(barrier_ops) => self.visit_barrier(barrier_ops, visit_read),
             Operation::Tma(tma_ops) => self.visit_tma(tma_ops, visit_read),
+            Operation::Texture(texture_ops) => self.visit_texture(texture_ops, visit_read),
             Operation::NonSemantic(non_semantic) => {
                 self.visit_nonsemantic(non_semantic, visit_read)
             }
             Operation::Marker(_) => {}
+            Operation::Call(call) => {
+                for arg in call.args.iter_mut() {
+                    visit_read(self, arg);
+                }
+            }
         }
     }
 
@@ -537,6 +543,29 @@ impl Optimizer {
         }
     }
 
+    fn visit_texture(
+        &mut self,
+        texture_ops: &mut TextureOps,
+        mut visit_read: impl FnMut(&mut Self, &mut Variable),
+    ) {
+        match texture_ops {
+            TextureOps::Sample {
+                texture,
+                coordinates,
+                ..
+            }
+            | TextureOps::Load {
+                texture,
+                coordinates,
+            } => {
+                visit_read(self, texture);
+                for coord in coordinates {
+                    visit_read(self, coord)
+                }
+            }
+        }
+    }
+
     fn visit_nonsemantic(
         &mut self,
         non_semantic: &mut NonSemantic,
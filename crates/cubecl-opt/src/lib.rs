@@ -35,8 +35,8 @@ use std::{
 use analyses::{AnalysisCache, dominance::DomFrontiers, liveness::Liveness, writes::Writes};
 use cubecl_core::CubeDim;
 use cubecl_ir::{
-    self as core, Allocator, Branch, Id, Operation, Operator, Processor, Scope, Type, Variable,
-    VariableKind,
+    self as core, Allocator, Branch, Function, Id, Operation, Operator, Processor, Scope, Type,
+    Variable, VariableKind,
 };
 use gvn::GvnPass;
 use passes::{
@@ -142,8 +142,14 @@ pub struct Optimizer {
     current_block: Option<NodeIndex>,
     /// The current loop's break target
     loop_break: VecDeque<NodeIndex>,
+    /// The current loop's continue target
+    loop_continue: VecDeque<NodeIndex>,
     /// The single return block
     pub ret: NodeIndex,
+    /// The value returned by the return block, if this is a function body
+    return_value: Option<Variable>,
+    /// Whether this is the body of a function, which may be called from non-uniform control flow
+    in_function: bool,
     /// Root scope to allocate variables on
     pub root_scope: Scope,
     /// The `CubeDim` used for range analysis
@@ -159,7 +165,10 @@ impl Default for Optimizer {
             allocator: Default::default(),
             current_block: Default::default(),
             loop_break: Default::default(),
+            loop_continue: Default::default(),
             ret: Default::default(),
+            return_value: None,
+            in_function: false,
             root_scope: Scope::root(false),
             cube_dim: CubeDim::new_1d(1),
             analysis_cache: Default::default(),
@@ -191,6 +200,24 @@ impl Optimizer {
         opt
     }
 
+    /// Create a new optimizer for a function called by this kernel, using the same settings.
+    /// The returned value of the function is read by the return block.
+    pub fn function(&self, function: Function) -> Self {
+        let mut opt = Self {
+            root_scope: function.body.clone(),
+            cube_dim: self.cube_dim,
+            allocator: function.body.allocator.clone(),
+            transformers: self.transformers.clone(),
+            processors: self.processors.clone(),
+            return_value: function.out,
+            in_function: true,
+            ..Default::default()
+        };
+        opt.run_opt();
+
+        opt
+    }
+
     /// Create a new optimizer with the scope, `CubeDim` and execution mode passed into the compiler.
     /// Parses the scope and runs several optimization and analysis loops.
     pub fn shared_only(expand: Scope, cube_dim: CubeDim) -> Self {
@@ -259,7 +286,9 @@ impl Optimizer {
         self.program.root = entry;
         self.current_block = Some(entry);
         self.ret = self.program.add_node(BasicBlock::default());
-        *self.program[self.ret].control_flow.borrow_mut() = ControlFlow::Return;
+        *self.program[self.ret].control_flow.borrow_mut() = ControlFlow::Return {
+            value: self.return_value,
+        };
         self.parse_scope(scope);
         if let Some(current_block) = self.current_block {
             self.program.add_edge(current_block, self.ret, 0);
@@ -401,9 +430,14 @@ impl Optimizer {
             });
         }
 
-        let is_break = processed.instructions.contains(&Branch::Break.into());
+        let is_break = processed.instructions.contains(&Branch::Break.into())
+            || processed.instructions.contains(&Branch::Continue.into());
 
         for mut instruction in processed.instructions {
+            // Anything after a `break`, `continue` or `return` is unreachable
+            if self.current_block.is_none() {
+                break;
+            }
             let mut removed = false;
             for transform in self.transformers.iter() {
                 match transform.maybe_transform(&mut scope, &instruction) {
@@ -144,8 +144,22 @@ pub struct EliminateDeadBlocks;
 impl OptimizerPass for EliminateDeadBlocks {
     fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
         let post_order = opt.analysis::<PostOrder>().forward();
+        // The continue target of a loop that never loops back is unreachable, but still needed
+        // by structured targets.
+        let continue_targets: Vec<_> = post_order
+            .iter()
+            .filter_map(|node| match &*opt.program[*node].control_flow.borrow() {
+                ControlFlow::Loop {
+                    continue_target, ..
+                }
+                | ControlFlow::LoopBreak {
+                    continue_target, ..
+                } => Some(*continue_target),
+                _ => None,
+            })
+            .collect();
         for node in opt.node_ids() {
-            if !post_order.contains(&node) {
+            if !post_order.contains(&node) && !continue_targets.contains(&node) {
                 opt.program.remove_node(node);
                 changes.inc();
             }
@@ -342,7 +356,7 @@ pub fn update_references(opt: &mut Optimizer, from: NodeIndex, to: NodeIndex) {
                 update(continue_target);
                 update(merge);
             }
-            ControlFlow::Return | ControlFlow::None => {}
+            ControlFlow::Return { .. } | ControlFlow::None => {}
         }
     }
 }
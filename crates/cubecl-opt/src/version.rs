@@ -163,6 +163,7 @@ impl Optimizer {
                 self.version_read(break_cond, state)
             }
             ControlFlow::Switch { value, .. } => self.version_read(value, state),
+            ControlFlow::Return { value: Some(value) } => self.version_read(value, state),
             _ => {}
         }
     }
@@ -1,8 +1,12 @@
 use crate::{
     config::{TypeNameFormatLevel, type_name_format},
-    kernel::KernelMetadata,
+    fusion::DeferredLaunch,
+    graph::LaunchGraph,
+    id::KernelId,
+    kernel::{KernelMetadata, KernelResourceUsage},
     logging::ProfileLevel,
     memory_management::{MemoryAllocationMode, MemoryUsage},
+    occupancy::OccupancyCalculator,
     runtime::Runtime,
     server::{
         Allocation, AllocationDescriptor, AllocationKind, Binding, Bindings, ComputeServer,
@@ -10,7 +14,9 @@ use crate::{
         ProfileError, ServerCommunication, ServerUtilities,
     },
     storage::{BindingResource, ComputeStorage},
+    stream::Event,
 };
+use alloc::boxed::Box;
 use alloc::format;
 use alloc::sync::Arc;
 use alloc::vec;
@@ -78,13 +84,51 @@ impl<R: Runtime> ComputeClient<R> {
         }
     }
 
-    fn stream_id(&self) -> StreamId {
+    /// The stream on which the current client enqueues its tasks.
+    ///
+    /// Unless set explicitly, this is the stream of the current thread.
+    pub fn stream_id(&self) -> StreamId {
         match self.stream_id {
             Some(val) => val,
             None => StreamId::current(),
         }
     }
 
+    /// Create a new client on the same server that enqueues its tasks on a new stream.
+    ///
+    /// Tasks enqueued on different streams can overlap, for example to transfer data while
+    /// computing. Tasks that share bindings across streams are still synchronized automatically,
+    /// and explicit dependencies can be expressed with [events](Self::record_event).
+    ///
+    /// Servers only have a fixed number of streams, set by the `max_streams` option of the
+    /// [streaming config](crate::config::streaming::StreamingConfig). Past that number, new
+    /// streams share the queue of an existing one, so they stay correct but don't overlap with it.
+    pub fn stream(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+            utilities: self.utilities.clone(),
+            stream_id: Some(StreamId::create()),
+        }
+    }
+
+    /// Record an event on the stream of the current client, marking the completion of every task
+    /// enqueued on it so far.
+    pub fn record_event(&self) -> Result<Event, ExecutionError> {
+        self.flush_deferred([])?;
+        let stream_id = self.stream_id();
+        self.context.lock().record_event(stream_id)
+    }
+
+    /// Make the stream of the current client wait for the event before executing any task
+    /// enqueued after this call.
+    ///
+    /// This doesn't block the current thread, unless the server doesn't support native events.
+    pub fn wait_event(&self, event: &Event) -> Result<(), ExecutionError> {
+        self.flush_deferred([])?;
+        let stream_id = self.stream_id();
+        self.context.lock().wait_event(event, stream_id)
+    }
+
     /// Set the stream in which the current client is operating on.
     ///
     /// # Safety
@@ -95,6 +139,10 @@ impl<R: Runtime> ComputeClient<R> {
     }
 
     fn do_read(&self, descriptors: Vec<CopyDescriptor<'_>>) -> DynFut<Result<Vec<Bytes>, IoError>> {
+        if let Err(err) = self.flush_deferred(descriptors.iter().map(|desc| &desc.binding)) {
+            let err = IoError::Execution(err.into());
+            return Box::pin(async move { Err(err) });
+        }
         let stream_id = self.stream_id();
         let mut state = self.context.lock();
         let fut = state.read(descriptors, stream_id);
@@ -193,6 +241,7 @@ impl<R: Runtime> ComputeClient<R> {
         &self,
         binding: Binding,
     ) -> BindingResource<<<R::Server as ComputeServer>::Storage as ComputeStorage>::Resource> {
+        self.flush_deferred_or_report([&binding]);
         let stream_id = self.stream_id();
         self.context.lock().get_resource(binding, stream_id)
     }
@@ -488,6 +537,7 @@ impl<R: Runtime> ComputeClient<R> {
         src_descriptor: CopyDescriptor<'_>,
         dst_server: &Self,
     ) -> Allocation {
+        self.flush_deferred_or_report([&src_descriptor.binding]);
         if R::Server::SERVER_COMM_ENABLED {
             let guard = self.context.lock_device_kind();
             let mut server_src = self.context.lock();
@@ -531,6 +581,70 @@ impl<R: Runtime> ComputeClient<R> {
         mode: ExecutionMode,
         stream_id: StreamId,
     ) -> Result<(), LaunchError> {
+        let kernel = self
+            .utilities
+            .captures
+            .record(stream_id, kernel, &count, &bindings, mode);
+        let launch = DeferredLaunch {
+            kernel,
+            count,
+            bindings,
+            mode,
+        };
+        // The launches deferred on other streams must be visible to this launch.
+        for (other, launches) in self
+            .utilities
+            .fusion
+            .take_using(stream_id, &launch.bindings.buffers)
+        {
+            // SAFETY: Every launch was submitted with its own execution mode.
+            unsafe { self.launch_deferred(launches, other)? };
+        }
+
+        let max_bindings = self.utilities.properties.hardware.max_bindings;
+        let (flushed, launch) = self.utilities.fusion.defer(stream_id, launch, max_bindings);
+
+        // SAFETY: The deferred launches were submitted with their own execution mode.
+        unsafe { self.launch_deferred(flushed, stream_id)? };
+
+        match launch {
+            // SAFETY: Forwarding the execution mode of the caller.
+            Some(launch) => unsafe { self.launch_now(launch, stream_id) },
+            None => Ok(()),
+        }
+    }
+
+    /// Launch the deferred launches, fused into a single kernel when possible.
+    unsafe fn launch_deferred(
+        &self,
+        launches: Vec<DeferredLaunch<<R::Server as ComputeServer>::Kernel>>,
+        stream_id: StreamId,
+    ) -> Result<(), LaunchError> {
+        match self.utilities.fusion.fuse(launches) {
+            // SAFETY: Every fused launch was submitted with the same execution mode.
+            Ok(launch) => unsafe { self.launch_now(launch, stream_id) },
+            Err(launches) => {
+                for launch in launches {
+                    // SAFETY: Every launch was submitted with its own execution mode.
+                    unsafe { self.launch_now(launch, stream_id)? };
+                }
+                Ok(())
+            }
+        }
+    }
+
+    #[track_caller]
+    unsafe fn launch_now(
+        &self,
+        launch: DeferredLaunch<<R::Server as ComputeServer>::Kernel>,
+        stream_id: StreamId,
+    ) -> Result<(), LaunchError> {
+        let DeferredLaunch {
+            kernel,
+            count,
+            bindings,
+            mode,
+        } = launch;
         let level = self.utilities.logger.profile_level();
 
         match level {
@@ -616,14 +730,147 @@ impl<R: Runtime> ComputeClient<R> {
         }
     }
 
+    /// Capture the kernels launched on the stream of the current client by `func` into a
+    /// [LaunchGraph], which can then be [replayed](Self::replay).
+    ///
+    /// The kernels are still launched normally while capturing, on every server, so the graph is
+    /// only recorded once every launch has been validated by the server. The capture therefore
+    /// executes the kernels one more time than the replays: kernels accumulating into their
+    /// buffers are applied by the capture as well. Launches from every client on the same stream
+    /// are captured, but nested captures on the same stream aren't supported.
+    ///
+    /// Servers without native graphs, like the CPU server, replay a graph by launching each of
+    /// its kernels again, which only saves the argument packing of the launches.
+    pub fn capture<O>(&self, func: impl FnOnce() -> O) -> Result<(O, LaunchGraph<R>), LaunchError> {
+        let stream_id = self.stream_id();
+
+        self.flush_deferred([])?;
+        let capture = self.utilities.captures.begin(stream_id);
+        let out = func();
+        let flushed = self.flush_deferred([]);
+        let launches = capture.end();
+        flushed?;
+
+        Ok((out, LaunchGraph::from(launches)))
+    }
+
+    /// Replay every launch of the [graph](LaunchGraph) on the stream of the current client, with
+    /// the current scalars and buffers of the graph.
+    pub fn replay(&self, graph: &LaunchGraph<R>) -> Result<(), LaunchError> {
+        self.flush_fusion()?;
+        let stream_id = self.stream_id();
+        let launches = graph.submissions();
+
+        // SAFETY: Every launch was already submitted with the same execution mode while capturing,
+        // and buffers can only be replaced by buffers of the same size.
+        unsafe { self.context.lock().launch_graph(launches, stream_id) }
+    }
+
+    /// Defer the [fusable](KernelMetadata::fusable) launches on the stream of the current client,
+    /// so that consecutive element-wise launches over the same shapes are fused into a single
+    /// kernel.
+    ///
+    /// The deferred launches are launched when calling [flush_fusion](Self::flush_fusion), when
+    /// a launch can't be fused with them, and before any other operation on the stream, like
+    /// reading a buffer. Buffers whose handles are all dropped before the launches are fused, and
+    /// that are written before being read, are replaced by local variables, so their content is
+    /// undefined after the fused launch.
+    ///
+    /// Only launches writing their outputs at `ABSOLUTE_POS` are fused, and launches can't be fused
+    /// when a buffer written by one of them is read at another position.
+    pub fn enable_fusion(&self) {
+        self.utilities.fusion.enable(self.stream_id());
+    }
+
+    /// Launch the deferred launches and stop deferring the launches on the stream of the current
+    /// client.
+    pub fn disable_fusion(&self) -> Result<(), LaunchError> {
+        let stream_id = self.stream_id();
+        let launches = self.utilities.fusion.disable(stream_id);
+
+        // SAFETY: Every launch was submitted with its own execution mode.
+        unsafe { self.launch_deferred(launches, stream_id) }
+    }
+
+    /// Launch the launches deferred on the stream of the current client.
+    pub fn flush_fusion(&self) -> Result<(), LaunchError> {
+        let stream_id = self.stream_id();
+        let launches = self.utilities.fusion.take(stream_id);
+
+        // SAFETY: Every launch was submitted with its own execution mode.
+        unsafe { self.launch_deferred(launches, stream_id) }
+    }
+
+    /// Launch the deferred launches before an operation that depends on them: the launches of
+    /// the stream of the current client, and the launches of other streams using the bindings.
+    ///
+    /// Also returns the errors kept by [flush_deferred_or_report](Self::flush_deferred_or_report).
+    fn flush_deferred<'a>(
+        &self,
+        bindings: impl IntoIterator<Item = &'a Binding>,
+    ) -> Result<(), LaunchError> {
+        let flushed = self.launch_deferred_before(bindings);
+
+        match self.utilities.fusion.take_error(self.stream_id()) {
+            Some(err) => {
+                if let Err(flushed) = flushed {
+                    self.utilities.fusion.report(self.stream_id(), flushed);
+                }
+                Err(err)
+            }
+            None => flushed,
+        }
+    }
+
+    /// Launch the deferred launches before an operation that can't return their errors, which are
+    /// kept to be returned by the next operation that can, like [try_flush](Self::try_flush).
+    fn flush_deferred_or_report<'a>(&self, bindings: impl IntoIterator<Item = &'a Binding>) {
+        if let Err(err) = self.launch_deferred_before(bindings) {
+            self.utilities.fusion.report(self.stream_id(), err);
+        }
+    }
+
+    fn launch_deferred_before<'a>(
+        &self,
+        bindings: impl IntoIterator<Item = &'a Binding>,
+    ) -> Result<(), LaunchError> {
+        self.flush_fusion()?;
+
+        for (stream_id, launches) in self.utilities.fusion.take_using(self.stream_id(), bindings) {
+            // SAFETY: Every launch was submitted with its own execution mode.
+            unsafe { self.launch_deferred(launches, stream_id)? };
+        }
+
+        Ok(())
+    }
+
     /// Flush all outstanding commands.
+    ///
+    /// The errors of the [deferred launches](Self::enable_fusion) are returned by the next
+    /// operation returning a result, use [try_flush](Self::try_flush) to get them.
     pub fn flush(&self) {
+        self.flush_deferred_or_report([]);
         let stream_id = self.stream_id();
         self.context.lock().flush(stream_id)
     }
 
+    /// Flush all outstanding commands, and return the errors of the
+    /// [deferred launches](Self::enable_fusion) flushed by this call or by a previous operation
+    /// that couldn't return them.
+    pub fn try_flush(&self) -> Result<(), LaunchError> {
+        let flushed = self.flush_deferred([]);
+        let stream_id = self.stream_id();
+        self.context.lock().flush(stream_id);
+
+        flushed
+    }
+
     /// Wait for the completion of every task in the server.
     pub fn sync(&self) -> DynFut<Result<(), ExecutionError>> {
+        if let Err(err) = self.flush_deferred([]) {
+            let err = ExecutionError::from(err);
+            return Box::pin(async move { Err(err) });
+        }
         let stream_id = self.stream_id();
         let mut state = self.context.lock();
         let fut = state.sync(stream_id);
@@ -645,11 +892,31 @@ impl<R: Runtime> ComputeClient<R> {
         Arc::get_mut(&mut self.utilities).map(|state| &mut state.properties)
     }
 
+    /// Create an [occupancy calculator](OccupancyCalculator) for a kernel with the given resource
+    /// usage on this device.
+    pub fn occupancy(&self, usage: KernelResourceUsage) -> OccupancyCalculator {
+        OccupancyCalculator::new(&self.properties().hardware, usage)
+    }
+
     /// Get the current memory usage of this client.
     pub fn memory_usage(&self) -> MemoryUsage {
         self.context.lock().memory_usage(self.stream_id())
     }
 
+    /// Get the resources used by a kernel compiled for the given execution mode.
+    ///
+    /// Returns `None` if the kernel wasn't launched with that mode yet, or if the runtime doesn't
+    /// report resource usage.
+    pub fn resource_usage(
+        &self,
+        kernel_id: &KernelId,
+        mode: ExecutionMode,
+    ) -> Option<KernelResourceUsage> {
+        let mut kernel_id = kernel_id.clone();
+        kernel_id.mode(mode);
+        self.context.lock().resource_usage(&kernel_id)
+    }
+
     /// Change the memory allocation mode.
     ///
     /// # Safety
@@ -702,6 +969,8 @@ impl<R: Runtime> ComputeClient<R> {
         func: impl FnOnce() -> O,
         #[allow(unused)] func_name: &str,
     ) -> Result<(O, ProfileDuration), ProfileError> {
+        self.flush_deferred([])?;
+
         // Get the outer caller. For execute() this points straight to the
         // cube kernel. For general profiling it points to whoever calls profile.
         #[cfg(feature = "profile-tracy")]
@@ -1,5 +1,5 @@
 use crate::{
-    kernel::{CompiledKernel, KernelDefinition, KernelMetadata},
+    kernel::{CompiledKernel, KernelDefinition, KernelMetadata, KernelResourceUsage},
     server::ExecutionMode,
 };
 use alloc::string::String;
@@ -18,6 +18,12 @@ pub trait CubeTask<C: Compiler>: KernelMetadata + Send + Sync {
         mode: ExecutionMode,
         address_type: StorageType,
     ) -> Result<CompiledKernel<C>, CompilationError>;
+
+    /// The [kernel definition](KernelDefinition) of the task, if it can be expanded outside of
+    /// compilation.
+    fn define(&self) -> Option<KernelDefinition> {
+        None
+    }
 }
 
 /// JIT compilation error.
@@ -82,6 +88,12 @@ pub trait Compiler: Sync + Send + 'static + Clone + core::fmt::Debug {
         addr_type: StorageType,
     ) -> Result<Self::Representation, CompilationError>;
 
+    /// The resources statically used by the compiled `kernel`, such as shared memory and local
+    /// arrays. Bindings and the fields reported by drivers are filled in separately.
+    fn resource_usage(&self, _kernel: &Self::Representation) -> KernelResourceUsage {
+        KernelResourceUsage::default()
+    }
+
     /// The size of the given element in bytes.
     fn elem_size(&self, elem: ElemType) -> usize;
 
@@ -0,0 +1,138 @@
+use alloc::string::{String, ToString};
+use cubecl_ir::DeviceProperties;
+use std::path::Path;
+use thiserror::Error;
+
+/// The properties of a device, detached from the device itself.
+///
+/// Profiles can be saved to and loaded from TOML or JSON, so code that depends on device
+/// properties (CMMA configurations, plane sizes, TMA support) can be exercised on machines that
+/// don't have the device, see [`CompileOnly`](super::CompileOnly). A few profiles of common GPUs
+/// are [bundled](DeviceProfile::bundled) with the crate.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DeviceProfile {
+    /// Name of the device the profile was captured from.
+    pub name: String,
+    /// Properties of the device.
+    pub properties: DeviceProperties,
+}
+
+/// Error when loading or saving a [device profile](DeviceProfile).
+#[derive(Error, Debug)]
+pub enum DeviceProfileError {
+    /// The profile file couldn't be read or written.
+    #[error("Can't access the device profile file: {0}")]
+    Io(#[from] std::io::Error),
+    /// The TOML profile is invalid.
+    #[error("Invalid TOML device profile: {0}")]
+    Toml(#[from] toml::de::Error),
+    /// The JSON profile is invalid.
+    #[error("Invalid JSON device profile: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The file extension is neither `toml` nor `json`.
+    #[error("Unknown device profile format {extension:?}, expected `toml` or `json`")]
+    UnknownFormat {
+        /// The extension of the file, if any.
+        extension: Option<String>,
+    },
+    /// No bundled profile has this name.
+    #[error("No bundled device profile named {0:?}")]
+    UnknownProfile(String),
+}
+
+/// Name and TOML source of the bundled profiles.
+const BUNDLED: &[(&str, &str)] = &[
+    (
+        "nvidia-a100",
+        include_str!("../../profiles/nvidia-a100.toml"),
+    ),
+    (
+        "nvidia-rtx-4090",
+        include_str!("../../profiles/nvidia-rtx-4090.toml"),
+    ),
+    (
+        "nvidia-h100",
+        include_str!("../../profiles/nvidia-h100.toml"),
+    ),
+    ("amd-mi300x", include_str!("../../profiles/amd-mi300x.toml")),
+];
+
+impl DeviceProfile {
+    /// Create a profile named `name` from the properties of a device, usually obtained with
+    /// `client.properties()`.
+    pub fn new(name: impl Into<String>, properties: DeviceProperties) -> Self {
+        Self {
+            name: name.into(),
+            properties,
+        }
+    }
+
+    /// Names of the bundled profiles, to be loaded with [bundled](Self::bundled).
+    pub fn bundled_names() -> impl Iterator<Item = &'static str> {
+        BUNDLED.iter().map(|(name, _)| *name)
+    }
+
+    /// Load the bundled profile named `name`.
+    pub fn bundled(name: &str) -> Result<Self, DeviceProfileError> {
+        let (_, source) = BUNDLED
+            .iter()
+            .find(|(bundled, _)| *bundled == name)
+            .ok_or_else(|| DeviceProfileError::UnknownProfile(name.to_string()))?;
+        Self::from_toml(source)
+    }
+
+    /// Parse a profile from TOML.
+    pub fn from_toml(source: &str) -> Result<Self, DeviceProfileError> {
+        Ok(toml::from_str(source)?)
+    }
+
+    /// Parse a profile from JSON.
+    pub fn from_json(source: &str) -> Result<Self, DeviceProfileError> {
+        Ok(serde_json::from_str(source)?)
+    }
+
+    /// Serialize the profile to TOML.
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("Device profile should be serializable")
+    }
+
+    /// Serialize the profile to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Device profile should be serializable")
+    }
+
+    /// Load a profile from a `.toml` or `.json` file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, DeviceProfileError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+
+        match extension(path)?.as_str() {
+            "toml" => Self::from_toml(&source),
+            _ => Self::from_json(&source),
+        }
+    }
+
+    /// Save the profile to a `.toml` or `.json` file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), DeviceProfileError> {
+        let path = path.as_ref();
+        let content = match extension(path)?.as_str() {
+            "toml" => self.to_toml(),
+            _ => self.to_json(),
+        };
+        std::fs::write(path, content)?;
+
+        Ok(())
+    }
+}
+
+fn extension(path: &Path) -> Result<String, DeviceProfileError> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+
+    match extension.as_deref() {
+        Some("toml") | Some("json") => Ok(extension.unwrap()),
+        _ => Err(DeviceProfileError::UnknownFormat { extension }),
+    }
+}
@@ -0,0 +1,5 @@
+mod base;
+mod server;
+
+pub use base::*;
+pub use server::*;
@@ -0,0 +1,349 @@
+use super::DeviceProfile;
+use crate::{
+    client::ComputeClient,
+    compiler::{Compiler, CubeTask},
+    id::KernelId,
+    logging::ServerLogger,
+    memory_management::{
+        MemoryAllocationMode, MemoryConfiguration, MemoryManagement, MemoryManagementOptions,
+        MemoryUsage,
+    },
+    runtime::Runtime,
+    server::{
+        Allocation, AllocationDescriptor, Binding, Bindings, ComputeServer, CopyDescriptor,
+        CubeCount, ExecutionError, ExecutionMode, Handle, IoError, LaunchError, ProfileError,
+        ProfilingToken, ServerCommunication, ServerUtilities,
+    },
+    storage::{BindingResource, BytesResource, BytesStorage, ComputeStorage},
+    timestamp_profiler::TimestampProfiler,
+};
+use alloc::sync::Arc;
+use core::marker::PhantomData;
+use cubecl_common::{
+    backtrace::BackTrace,
+    bytes::Bytes,
+    device::{Device, DeviceId, DeviceState},
+    future::DynFut,
+    profile::ProfileDuration,
+    stream_id::StreamId,
+};
+use cubecl_ir::{LineSize, TargetProperties};
+use std::sync::Mutex;
+
+/// Runtime emulating a device of the runtime `R` from a [profile](DeviceProfile).
+///
+/// Kernels launched on a compile-only client are expanded and compiled with the compiler of `R`
+/// against the properties of the profile, but never executed. This allows testing kernel
+/// generation for any target on a machine without the device. The compiled kernels are recorded
+/// in the [info](ComputeClient::info) of the client.
+///
+/// Memory is allocated on the host so launch arguments can be created as usual, but its content
+/// is never written by kernels.
+#[derive(Debug)]
+pub struct CompileOnly<R: Runtime> {
+    _runtime: PhantomData<R>,
+}
+
+impl<R: Runtime> CompileOnly<R>
+where
+    R::Compiler: Default,
+{
+    /// Create a compile-only client on `device`, emulating the device described by `profile`.
+    ///
+    /// The compilation options should match what the runtime `R` would select for the emulated
+    /// device, since they can't be inferred from the profile.
+    ///
+    /// # Panics
+    ///
+    /// If a client was already created on `device`.
+    pub fn client_from_profile(
+        device: &ProfileDevice,
+        profile: &DeviceProfile,
+        options: <R::Compiler as Compiler>::CompilationOptions,
+    ) -> ComputeClient<Self> {
+        let server = CompileOnlyServer::new(profile, R::Compiler::default(), options);
+        ComputeClient::init(device, server)
+    }
+}
+
+impl<R: Runtime> Runtime for CompileOnly<R> {
+    type Compiler = R::Compiler;
+    type Server = CompileOnlyServer<R::Compiler>;
+    type Device = ProfileDevice;
+
+    fn client(device: &Self::Device) -> ComputeClient<Self> {
+        ComputeClient::load(device)
+    }
+
+    fn name(_client: &ComputeClient<Self>) -> &'static str {
+        "compile-only"
+    }
+
+    fn require_array_lengths() -> bool {
+        R::require_array_lengths()
+    }
+
+    fn supported_line_sizes() -> &'static [LineSize] {
+        R::supported_line_sizes()
+    }
+
+    fn max_global_line_size() -> LineSize {
+        R::max_global_line_size()
+    }
+
+    fn max_cube_count() -> (u32, u32, u32) {
+        R::max_cube_count()
+    }
+
+    fn can_read_tensor(shape: &[usize], strides: &[usize]) -> bool {
+        R::can_read_tensor(shape, strides)
+    }
+
+    fn target_properties() -> TargetProperties {
+        R::target_properties()
+    }
+}
+
+/// Device of a [compile-only](CompileOnly) client. Each index holds a separate client, so
+/// multiple profiles can be emulated at once.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Default)]
+pub struct ProfileDevice {
+    /// Index of the device.
+    pub index: u32,
+}
+
+impl Device for ProfileDevice {
+    fn from_id(device_id: DeviceId) -> Self {
+        Self {
+            index: device_id.index_id,
+        }
+    }
+
+    fn to_id(&self) -> DeviceId {
+        DeviceId {
+            type_id: 0,
+            index_id: self.index,
+        }
+    }
+
+    fn device_count(_type_id: u16) -> usize {
+        usize::MAX
+    }
+}
+
+/// A kernel compiled by a [compile-only](CompileOnly) client.
+#[derive(Debug, Clone)]
+pub struct CompiledSource {
+    /// Id of the kernel.
+    pub id: KernelId,
+    /// Name of the kernel entrypoint.
+    pub entrypoint_name: String,
+    /// Source of the compiled kernel.
+    pub source: String,
+}
+
+/// Information of a [compile-only](CompileOnly) client.
+#[derive(Debug, Default)]
+pub struct CompileOnlyInfo {
+    compiled: Mutex<Vec<CompiledSource>>,
+}
+
+impl CompileOnlyInfo {
+    /// The kernels compiled so far, in launch order.
+    pub fn compiled(&self) -> Vec<CompiledSource> {
+        self.compiled.lock().unwrap().clone()
+    }
+}
+
+/// Server of a [compile-only](CompileOnly) client.
+#[derive(Debug)]
+pub struct CompileOnlyServer<C: Compiler> {
+    compiler: C,
+    options: C::CompilationOptions,
+    memory_management: MemoryManagement<BytesStorage>,
+    timestamps: TimestampProfiler,
+    utilities: Arc<ServerUtilities<Self>>,
+}
+
+impl<C: Compiler> CompileOnlyServer<C> {
+    /// Create a server emulating the device described by `profile`.
+    pub fn new(profile: &DeviceProfile, compiler: C, options: C::CompilationOptions) -> Self {
+        let properties = profile.properties.clone();
+        let logger = Arc::new(ServerLogger::default());
+        let memory_management = MemoryManagement::from_configuration(
+            BytesStorage::default(),
+            &properties.memory,
+            MemoryConfiguration::ExclusivePages,
+            logger.clone(),
+            MemoryManagementOptions::new(format!("Emulated {}", profile.name)),
+        );
+        let utilities = ServerUtilities::new(properties, logger, CompileOnlyInfo::default());
+
+        Self {
+            compiler,
+            options,
+            memory_management,
+            timestamps: TimestampProfiler::default(),
+            utilities: Arc::new(utilities),
+        }
+    }
+
+    fn resource(&mut self, binding: Binding) -> Result<BytesResource, IoError> {
+        self.memory_management
+            .get_resource(binding.memory, binding.offset_start, binding.offset_end)
+            .ok_or_else(|| IoError::InvalidHandle {
+                backtrace: BackTrace::capture(),
+            })
+    }
+}
+
+impl<C: Compiler> DeviceState for CompileOnlyServer<C> {
+    fn init(_device_id: DeviceId) -> Self {
+        panic!("Compile-only clients must be created from a profile with `client_from_profile`")
+    }
+}
+
+impl<C: Compiler> ServerCommunication for CompileOnlyServer<C> {
+    const SERVER_COMM_ENABLED: bool = false;
+}
+
+impl<C: Compiler> ComputeServer for CompileOnlyServer<C> {
+    type Kernel = Box<dyn CubeTask<C>>;
+    type Info = CompileOnlyInfo;
+    type Storage = BytesStorage;
+
+    fn logger(&self) -> Arc<ServerLogger> {
+        self.utilities.logger.clone()
+    }
+
+    fn utilities(&self) -> Arc<ServerUtilities<Self>> {
+        self.utilities.clone()
+    }
+
+    fn create(
+        &mut self,
+        descriptors: Vec<AllocationDescriptor<'_>>,
+        stream_id: StreamId,
+    ) -> Result<Vec<Allocation>, IoError> {
+        descriptors
+            .into_iter()
+            .map(|descriptor| {
+                let rank = descriptor.shape.len();
+                let mut strides = vec![1; rank];
+                for i in (0..rank.saturating_sub(1)).rev() {
+                    strides[i] = strides[i + 1] * descriptor.shape[i + 1];
+                }
+                let size =
+                    (descriptor.shape.iter().product::<usize>() * descriptor.elem_size) as u64;
+                let handle = Handle::new(
+                    self.memory_management.reserve(size)?,
+                    None,
+                    None,
+                    stream_id,
+                    0,
+                    size,
+                );
+                Ok(Allocation::new(handle, strides))
+            })
+            .collect()
+    }
+
+    fn read(
+        &mut self,
+        descriptors: Vec<CopyDescriptor<'_>>,
+        _stream_id: StreamId,
+    ) -> DynFut<Result<Vec<Bytes>, IoError>> {
+        let bytes = descriptors
+            .into_iter()
+            .map(|descriptor| {
+                let len = descriptor.shape.iter().product::<usize>() * descriptor.elem_size;
+                let resource = self.resource(descriptor.binding)?;
+                Ok(Bytes::from_bytes_vec(resource.read()[..len].to_vec()))
+            })
+            .collect();
+
+        Box::pin(async move { bytes })
+    }
+
+    fn write(
+        &mut self,
+        descriptors: Vec<(CopyDescriptor<'_>, Bytes)>,
+        _stream_id: StreamId,
+    ) -> Result<(), IoError> {
+        for (descriptor, data) in descriptors {
+            let mut resource = self.resource(descriptor.binding)?;
+            resource.write()[..data.len()].copy_from_slice(&data);
+        }
+
+        Ok(())
+    }
+
+    fn sync(&mut self, _stream_id: StreamId) -> DynFut<Result<(), ExecutionError>> {
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn get_resource(
+        &mut self,
+        binding: Binding,
+        _stream_id: StreamId,
+    ) -> BindingResource<<Self::Storage as ComputeStorage>::Resource> {
+        let resource = self.resource(binding.clone()).unwrap();
+        BindingResource::new(binding, resource)
+    }
+
+    unsafe fn launch(
+        &mut self,
+        kernel: Self::Kernel,
+        _count: CubeCount,
+        _bindings: Bindings,
+        mode: ExecutionMode,
+        _stream_id: StreamId,
+    ) -> Result<(), LaunchError> {
+        let compiled = kernel.compile(
+            &mut self.compiler,
+            &self.options,
+            mode,
+            kernel.address_type(),
+        )?;
+        self.utilities.logger.log_compilation(&compiled);
+
+        self.utilities
+            .info
+            .compiled
+            .lock()
+            .unwrap()
+            .push(CompiledSource {
+                id: kernel.id(),
+                entrypoint_name: compiled.entrypoint_name,
+                source: compiled.source,
+            });
+
+        Ok(())
+    }
+
+    fn flush(&mut self, _stream_id: StreamId) {}
+
+    fn memory_usage(&mut self, _stream_id: StreamId) -> MemoryUsage {
+        self.memory_management.memory_usage()
+    }
+
+    fn memory_cleanup(&mut self, _stream_id: StreamId) {
+        self.memory_management.cleanup(true);
+    }
+
+    fn start_profile(&mut self, _stream_id: StreamId) -> ProfilingToken {
+        self.timestamps.start()
+    }
+
+    fn end_profile(
+        &mut self,
+        _stream_id: StreamId,
+        token: ProfilingToken,
+    ) -> Result<ProfileDuration, ProfileError> {
+        self.timestamps.stop(token)
+    }
+
+    fn allocation_mode(&mut self, mode: MemoryAllocationMode, _stream_id: StreamId) {
+        self.memory_management.mode(mode)
+    }
+}
@@ -0,0 +1,255 @@
+use super::{
+    plan::{FusionKey, FusionPlan},
+    summary::KernelSummary,
+};
+use crate::{
+    compiler::{Compiler, CubeTask},
+    id::KernelId,
+    kernel::KernelMetadata,
+    server::{Binding, Bindings, CubeCount, ExecutionMode, LaunchError},
+};
+use alloc::{boxed::Box, collections::BTreeSet, sync::Arc, vec::Vec};
+use cubecl_common::{stream_id::StreamId, stub::Mutex};
+use cubecl_ir::StorageType;
+use hashbrown::HashMap;
+
+/// A launch deferred on a stream with fusion enabled.
+pub(crate) struct DeferredLaunch<K> {
+    pub(crate) kernel: K,
+    pub(crate) count: CubeCount,
+    pub(crate) bindings: Bindings,
+    pub(crate) mode: ExecutionMode,
+}
+
+/// Lazy fusion of consecutive [fusable](KernelMetadata::fusable) launches.
+///
+/// The launches deferred on a stream are merged into a single kernel when they are flushed. The
+/// scopes of the kernels are appended one after the other, their bindings are renamed to the
+/// bindings of the fused kernel, and buffers that are no longer owned by any handle are replaced
+/// by local variables when they're written before being read.
+///
+/// The fusion plans are cached by the sequence of [kernel ids](KernelId) and the aliasing of their
+/// bindings, which also identifies the fused kernel in the compilation cache of the server.
+pub(crate) struct LaunchFusion<K> {
+    streams: Mutex<HashMap<StreamId, Vec<DeferredLaunch<K>>>>,
+    kernels: Mutex<HashMap<KernelId, Option<Arc<KernelSummary>>>>,
+    plans: Mutex<HashMap<FusionKey, Option<Arc<FusionPlan>>>>,
+    /// The errors of deferred launches flushed by operations that can't return them.
+    errors: Mutex<HashMap<StreamId, LaunchError>>,
+}
+
+impl<K> Default for LaunchFusion<K> {
+    fn default() -> Self {
+        Self {
+            streams: Mutex::new(HashMap::new()),
+            kernels: Mutex::new(HashMap::new()),
+            plans: Mutex::new(HashMap::new()),
+            errors: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K> LaunchFusion<K> {
+    /// Start deferring the fusable launches of the given stream.
+    pub(crate) fn enable(&self, stream_id: StreamId) {
+        self.streams.lock().unwrap().entry(stream_id).or_default();
+    }
+
+    /// Stop deferring the launches of the given stream, and return the deferred launches.
+    pub(crate) fn disable(&self, stream_id: StreamId) -> Vec<DeferredLaunch<K>> {
+        self.streams
+            .lock()
+            .unwrap()
+            .remove(&stream_id)
+            .unwrap_or_default()
+    }
+
+    /// Take the launches deferred on the given stream.
+    pub(crate) fn take(&self, stream_id: StreamId) -> Vec<DeferredLaunch<K>> {
+        self.streams
+            .lock()
+            .unwrap()
+            .get_mut(&stream_id)
+            .map(core::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Keep the error of a deferred launch until it can be returned on the given stream.
+    ///
+    /// Only the first error is kept, since the following ones are often caused by it.
+    pub(crate) fn report(&self, stream_id: StreamId, error: LaunchError) {
+        self.errors
+            .lock()
+            .unwrap()
+            .entry(stream_id)
+            .or_insert(error);
+    }
+
+    /// Take the error kept for the given stream.
+    pub(crate) fn take_error(&self, stream_id: StreamId) -> Option<LaunchError> {
+        self.errors.lock().unwrap().remove(&stream_id)
+    }
+
+    /// Take the launches deferred on every stream other than `stream_id` that use one of the
+    /// bindings, since their writes must be visible to the operation on the given stream.
+    pub(crate) fn take_using<'a>(
+        &self,
+        stream_id: StreamId,
+        bindings: impl IntoIterator<Item = &'a Binding>,
+    ) -> Vec<(StreamId, Vec<DeferredLaunch<K>>)> {
+        let bindings: Vec<&Binding> = bindings.into_iter().collect();
+        let mut streams = self.streams.lock().unwrap();
+
+        streams
+            .iter_mut()
+            .filter(|(other, deferred)| {
+                **other != stream_id
+                    && deferred.iter().any(|launch| {
+                        launch.bindings.buffers.iter().any(|binding| {
+                            bindings
+                                .iter()
+                                .any(|other| other.memory.id() == binding.memory.id())
+                        })
+                    })
+            })
+            .map(|(other, deferred)| (*other, core::mem::take(deferred)))
+            .collect()
+    }
+}
+
+impl<C: Compiler> LaunchFusion<Box<dyn CubeTask<C>>> {
+    /// Defer the launch if its stream has fusion enabled and its kernel is fusable.
+    ///
+    /// Returns the deferred launches that must be launched first, followed by the launch itself
+    /// when it wasn't deferred.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn defer(
+        &self,
+        stream_id: StreamId,
+        launch: DeferredLaunch<Box<dyn CubeTask<C>>>,
+        max_bindings: u32,
+    ) -> (
+        Vec<DeferredLaunch<Box<dyn CubeTask<C>>>>,
+        Option<DeferredLaunch<Box<dyn CubeTask<C>>>>,
+    ) {
+        let mut streams = self.streams.lock().unwrap();
+        let Some(deferred) = streams.get_mut(&stream_id) else {
+            return (Vec::new(), Some(launch));
+        };
+
+        if !launch.kernel.fusable()
+            || !matches!(launch.count, CubeCount::Static(..))
+            || !launch.bindings.tensor_maps.is_empty()
+            || !launch.bindings.textures.is_empty()
+            || launch.bindings.dynamic_shared_memory > 0
+            || !launch.bindings.spec_constants.is_empty()
+        {
+            return (core::mem::take(deferred), Some(launch));
+        }
+
+        let flushed = match deferred.is_empty() || accepts(deferred, &launch, max_bindings) {
+            true => Vec::new(),
+            false => core::mem::take(deferred),
+        };
+        deferred.push(launch);
+
+        (flushed, None)
+    }
+
+    /// Fuse the launches into a single launch, or return them when they can't be fused.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn fuse(
+        &self,
+        launches: Vec<DeferredLaunch<Box<dyn CubeTask<C>>>>,
+    ) -> Result<DeferredLaunch<Box<dyn CubeTask<C>>>, Vec<DeferredLaunch<Box<dyn CubeTask<C>>>>>
+    {
+        if launches.len() < 2 {
+            return Err(launches);
+        }
+
+        let Some(summaries) = self.summaries(&launches) else {
+            return Err(launches);
+        };
+        let Some((key, metadata)) = FusionKey::new(&launches, &summaries) else {
+            return Err(launches);
+        };
+
+        let plan = {
+            let mut plans = self.plans.lock().unwrap();
+            match plans.get(&key) {
+                Some(plan) => plan.clone(),
+                None => {
+                    let plan = FusionPlan::new(key.clone(), &summaries).map(Arc::new);
+                    plans.insert(key, plan.clone());
+                    plan
+                }
+            }
+        };
+
+        match plan {
+            Some(plan) => Ok(plan.launch(launches, &metadata)),
+            None => Err(launches),
+        }
+    }
+
+    fn summaries(
+        &self,
+        launches: &[DeferredLaunch<Box<dyn CubeTask<C>>>],
+    ) -> Option<Vec<Arc<KernelSummary>>> {
+        let mut kernels = self.kernels.lock().unwrap();
+
+        launches
+            .iter()
+            .map(|launch| {
+                kernels
+                    .entry(launch.kernel.id())
+                    .or_insert_with(|| {
+                        let mut definition = launch.kernel.define()?;
+                        KernelSummary::new(&mut definition).map(Arc::new)
+                    })
+                    .clone()
+            })
+            .collect()
+    }
+}
+
+/// Whether the launch can be fused with the deferred launches.
+fn accepts<K: KernelMetadata>(
+    deferred: &[DeferredLaunch<K>],
+    launch: &DeferredLaunch<K>,
+    max_bindings: u32,
+) -> bool {
+    let first = &deferred[0];
+    let same_count = match (&first.count, &launch.count) {
+        (CubeCount::Static(x, y, z), CubeCount::Static(x_new, y_new, z_new)) => {
+            (x, y, z) == (x_new, y_new, z_new)
+        }
+        _ => false,
+    };
+    if !same_count
+        || first.mode != launch.mode
+        || first.kernel.address_type() != launch.kernel.address_type()
+    {
+        return false;
+    }
+
+    let mut buffers: Vec<&Binding> = Vec::new();
+    let mut scalars = BTreeSet::<StorageType>::new();
+    for launch in deferred.iter().chain([launch]) {
+        for binding in launch.bindings.buffers.iter() {
+            if !buffers.iter().any(|other| same_buffer(other, binding)) {
+                buffers.push(binding);
+            }
+        }
+        scalars.extend(launch.bindings.scalars.keys());
+    }
+
+    // The metadata also takes a binding.
+    buffers.len() + scalars.len() < max_bindings as usize
+}
+
+pub(super) fn same_buffer(lhs: &Binding, rhs: &Binding) -> bool {
+    lhs.memory.id() == rhs.memory.id()
+        && lhs.offset_start == rhs.offset_start
+        && lhs.offset_end == rhs.offset_end
+}
@@ -0,0 +1,113 @@
+use super::summary::KernelSummary;
+use crate::server::MetadataBinding;
+use alloc::{vec, vec::Vec};
+
+/// The metadata of a buffer binding, as packed by the kernel launcher.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct MetadataEntry {
+    buffer_len: u64,
+    len: u64,
+    /// The shape and strides of tensors.
+    extended: Option<(Vec<u64>, Vec<u64>)>,
+}
+
+/// Unpack the metadata of each buffer binding, laid out as described in the `metadata` module of
+/// `cubecl-core`.
+pub(super) fn decode_metadata(
+    metadata: &MetadataBinding,
+    summary: &KernelSummary,
+    address_size: usize,
+) -> Option<Vec<MetadataEntry>> {
+    let bytes: &[u8] = bytemuck::cast_slice(&metadata.data);
+    let read = |index: usize| -> Option<u64> {
+        let bytes = bytes.get(index * address_size..(index + 1) * address_size)?;
+        match address_size {
+            4 => Some(u32::from_ne_bytes(bytes.try_into().ok()?) as u64),
+            _ => Some(u64::from_ne_bytes(bytes.try_into().ok()?)),
+        }
+    };
+
+    let num_buffers = summary.buffers.len();
+    let num_extended = summary.buffers.iter().filter(|it| it.extended).count();
+    let extended_start = 2 * num_buffers;
+    let mut position = 0;
+
+    let mut entries = Vec::with_capacity(num_buffers);
+    for (index, buffer) in summary.buffers.iter().enumerate() {
+        let extended = match buffer.extended {
+            true => {
+                let rank = read(extended_start + position)? as usize;
+                let shape = read(extended_start + num_extended + position)? as usize;
+                let strides = read(extended_start + 2 * num_extended + position)? as usize;
+                position += 1;
+
+                Some((
+                    (0..rank)
+                        .map(|dim| read(shape + dim))
+                        .collect::<Option<_>>()?,
+                    (0..rank)
+                        .map(|dim| read(strides + dim))
+                        .collect::<Option<_>>()?,
+                ))
+            }
+            false => None,
+        };
+        entries.push(MetadataEntry {
+            buffer_len: read(index)?,
+            len: read(num_buffers + index)?,
+            extended,
+        });
+    }
+
+    Some(entries)
+}
+
+/// Pack the metadata of each buffer binding, like the kernel launcher.
+pub(super) fn encode_metadata(
+    entries: Vec<&MetadataEntry>,
+    address_size: usize,
+) -> MetadataBinding {
+    let extended: Vec<_> = entries
+        .iter()
+        .filter_map(|entry| entry.extended.as_ref())
+        .collect();
+
+    let mut values: Vec<u64> = entries.iter().map(|entry| entry.buffer_len).collect();
+    values.extend(entries.iter().map(|entry| entry.len));
+    values.extend(extended.iter().map(|(shape, _)| shape.len() as u64));
+
+    let mut offset = values.len() + 2 * extended.len();
+    for (shape, _) in extended.iter() {
+        values.push(offset as u64);
+        offset += shape.len();
+    }
+    for (_, strides) in extended.iter() {
+        values.push(offset as u64);
+        offset += strides.len();
+    }
+
+    let static_len = values.len();
+    for (shape, _) in extended.iter() {
+        values.extend(shape.iter());
+    }
+    for (_, strides) in extended.iter() {
+        values.extend(strides.iter());
+    }
+
+    let mut bytes = Vec::with_capacity(values.len() * address_size);
+    for value in values {
+        match address_size {
+            4 => bytes.extend_from_slice(&(value as u32).to_ne_bytes()),
+            _ => bytes.extend_from_slice(&value.to_ne_bytes()),
+        }
+    }
+
+    MetadataBinding::new(pad(&bytes), static_len)
+}
+
+/// Pad the bytes to `u64`s, to prevent misalignment.
+pub(super) fn pad(bytes: &[u8]) -> Vec<u64> {
+    let mut data = vec![0u64; bytes.len().div_ceil(size_of::<u64>())];
+    bytemuck::cast_slice_mut::<u64, u8>(&mut data)[..bytes.len()].copy_from_slice(bytes);
+    data
+}
@@ -0,0 +1,8 @@
+mod base;
+mod metadata;
+mod plan;
+mod rename;
+mod summary;
+mod task;
+
+pub(crate) use base::*;
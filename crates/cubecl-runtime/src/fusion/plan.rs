@@ -0,0 +1,401 @@
+use super::{
+    base::{DeferredLaunch, same_buffer},
+    metadata::{MetadataEntry, decode_metadata, encode_metadata, pad},
+    rename::Renamer,
+    summary::{Access, KernelSummary},
+    task::FusedTask,
+};
+use crate::{
+    compiler::{Compiler, CubeTask},
+    id::KernelId,
+    kernel::{self, KernelDefinition, KernelMetadata, KernelOptions, Visibility},
+    server::{Binding, Bindings, CubeDim, ScalarBinding},
+};
+use alloc::{boxed::Box, collections::BTreeMap, string::ToString, sync::Arc, vec, vec::Vec};
+use cubecl_ir::{Id, StorageType, Type, Variable, VariableKind};
+use hashbrown::HashMap;
+
+/// The kernels of a fused launch, with the buffer and the metadata of each of their bindings.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) struct FusionKey {
+    kernels: Vec<KernelId>,
+    /// The distinct buffer and metadata of each buffer binding of each launch.
+    buffers: Vec<Vec<(usize, usize)>>,
+    /// Whether each distinct buffer is only referenced by the fused launches.
+    intermediates: Vec<bool>,
+}
+
+/// How the bindings of the fused launches map to the bindings of the fused kernel.
+pub(super) struct FusionPlan {
+    pub(super) id: KernelId,
+    cube_dim: CubeDim,
+    buffers: Vec<FusedBuffer>,
+    /// The types of the local variables replacing the eliminated buffers.
+    locals: Vec<Type>,
+    /// The target of each buffer binding of each launch.
+    targets: Vec<Vec<BufferTarget>>,
+}
+
+pub(super) struct FusedBuffer {
+    /// The launch and binding index of the first binding of the buffer.
+    source: (usize, usize),
+    metadata: usize,
+    ty: Type,
+    visibility: Visibility,
+    extended: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum BufferTarget {
+    Buffer(usize),
+    /// The buffer is replaced by a local variable, and its metadata is read from another buffer
+    /// with the same metadata.
+    Local {
+        local: usize,
+        metadata: usize,
+    },
+}
+
+impl FusionKey {
+    pub(super) fn new<K: KernelMetadata>(
+        launches: &[DeferredLaunch<K>],
+        summaries: &[Arc<KernelSummary>],
+    ) -> Option<(Self, Vec<MetadataEntry>)> {
+        let address_size = launches[0].kernel.address_type().size();
+        let mut buffers = Vec::with_capacity(launches.len());
+        let mut distinct: Vec<&Binding> = Vec::new();
+        let mut metadata: Vec<MetadataEntry> = Vec::new();
+
+        for (launch, summary) in launches.iter().zip(summaries) {
+            if launch.bindings.buffers.len() != summary.buffers.len() {
+                return None;
+            }
+
+            let entries = decode_metadata(&launch.bindings.metadata, summary, address_size)?;
+            let mut slots = Vec::with_capacity(entries.len());
+
+            for (binding, entry) in launch.bindings.buffers.iter().zip(entries) {
+                let buffer = match distinct
+                    .iter()
+                    .position(|other| same_buffer(other, binding))
+                {
+                    Some(index) => index,
+                    None => {
+                        distinct.push(binding);
+                        distinct.len() - 1
+                    }
+                };
+
+                let entry = match metadata.iter().position(|other| *other == entry) {
+                    Some(index) => index,
+                    None => {
+                        metadata.push(entry);
+                        metadata.len() - 1
+                    }
+                };
+                slots.push((buffer, entry));
+            }
+            buffers.push(slots);
+        }
+
+        // A buffer is an intermediate when no handle owns it anymore, since its content can then
+        // only be read by the fused launches.
+        let intermediates = distinct
+            .iter()
+            .map(|binding| !binding.memory.is_owned())
+            .collect();
+        let kernels = launches.iter().map(|launch| launch.kernel.id()).collect();
+
+        Some((
+            Self {
+                kernels,
+                buffers,
+                intermediates,
+            },
+            metadata,
+        ))
+    }
+}
+
+impl FusionPlan {
+    pub(super) fn new(key: FusionKey, summaries: &[Arc<KernelSummary>]) -> Option<Self> {
+        let cube_dim = summaries[0].cube_dim;
+        if summaries.iter().any(|summary| summary.cube_dim != cube_dim) {
+            return None;
+        }
+
+        // Merge the bindings of each distinct buffer.
+        let mut distinct: Vec<Option<FusedBuffer>> =
+            key.intermediates.iter().map(|_| None).collect();
+        let mut accesses = vec![Access::Unused; distinct.len()];
+        let mut written = vec![false; distinct.len()];
+        let mut aligned = vec![true; distinct.len()];
+        for (index, (slots, summary)) in key.buffers.iter().zip(summaries).enumerate() {
+            for (slot, (&(buffer, metadata), binding)) in
+                slots.iter().zip(summary.buffers.iter()).enumerate()
+            {
+                accesses[buffer] = accesses[buffer].then(binding.access);
+                written[buffer] |= binding.written;
+                aligned[buffer] &= binding.aligned;
+                match &mut distinct[buffer] {
+                    Some(fused) => {
+                        if fused.ty != binding.ty || fused.metadata != metadata {
+                            return None;
+                        }
+                        if binding.visibility == Visibility::ReadWrite {
+                            fused.visibility = Visibility::ReadWrite;
+                        }
+                    }
+                    None => {
+                        distinct[buffer] = Some(FusedBuffer {
+                            source: (index, slot),
+                            metadata,
+                            ty: binding.ty,
+                            visibility: binding.visibility,
+                            extended: binding.extended,
+                        });
+                    }
+                }
+            }
+        }
+        let distinct: Vec<FusedBuffer> = distinct.into_iter().collect::<Option<_>>()?;
+
+        // The elements written by a launch can only be read by the same unit in the fused kernel.
+        if written
+            .iter()
+            .zip(aligned.iter())
+            .any(|(written, aligned)| *written && !aligned)
+        {
+            return None;
+        }
+
+        // Intermediates written before being read are eliminated when another buffer can provide
+        // their metadata.
+        let mut kept: Vec<bool> = key
+            .intermediates
+            .iter()
+            .zip(accesses.iter())
+            .map(|(intermediate, access)| !intermediate || *access != Access::Write)
+            .collect();
+        for buffer in 0..distinct.len() {
+            let metadata = distinct[buffer].metadata;
+            let has_alias = (0..distinct.len())
+                .any(|other| kept[other] && distinct[other].metadata == metadata);
+            if !has_alias {
+                kept[buffer] = true;
+            }
+        }
+
+        let mut buffers = Vec::new();
+        let mut locals = Vec::new();
+        let mut indices = vec![0; distinct.len()];
+        let mut eliminated = Vec::new();
+        for (buffer, fused) in distinct.into_iter().enumerate() {
+            match kept[buffer] {
+                true => {
+                    indices[buffer] = buffers.len();
+                    buffers.push(fused);
+                }
+                false => eliminated.push((buffer, fused)),
+            }
+        }
+
+        let mut targets_of = vec![BufferTarget::Buffer(0); kept.len()];
+        for (buffer, index) in indices.iter().enumerate() {
+            targets_of[buffer] = BufferTarget::Buffer(*index);
+        }
+        for (buffer, fused) in eliminated {
+            let metadata = buffers
+                .iter()
+                .position(|other| other.metadata == fused.metadata)?;
+            targets_of[buffer] = BufferTarget::Local {
+                local: locals.len(),
+                metadata,
+            };
+            locals.push(fused.ty);
+        }
+
+        let targets = key
+            .buffers
+            .iter()
+            .map(|slots| {
+                slots
+                    .iter()
+                    .map(|(buffer, _)| targets_of[*buffer])
+                    .collect()
+            })
+            .collect();
+
+        Some(Self {
+            id: KernelId::new::<FusionPlan>().info(key),
+            cube_dim,
+            buffers,
+            locals,
+            targets,
+        })
+    }
+
+    /// Create the fused launch.
+    pub(super) fn launch<C: Compiler>(
+        self: &Arc<Self>,
+        launches: Vec<DeferredLaunch<Box<dyn CubeTask<C>>>>,
+        metadata: &[MetadataEntry],
+    ) -> DeferredLaunch<Box<dyn CubeTask<C>>> {
+        let address_size = launches[0].kernel.address_type().size();
+        let count = launches[0].count.clone();
+        let mode = launches[0].mode;
+
+        let buffers = self
+            .buffers
+            .iter()
+            .map(|buffer| {
+                let (launch, slot) = buffer.source;
+                launches[launch].bindings.buffers[slot].clone()
+            })
+            .collect();
+        let metadata = encode_metadata(
+            self.buffers
+                .iter()
+                .map(|buffer| &metadata[buffer.metadata])
+                .collect(),
+            address_size,
+        );
+
+        // The scalars of each type are concatenated in launch order.
+        let mut scalars = BTreeMap::<StorageType, (usize, Vec<u8>)>::new();
+        for launch in launches.iter() {
+            for (ty, binding) in launch.bindings.scalars.iter() {
+                let (length, data) = scalars.entry(*ty).or_default();
+                *length += binding.length;
+                data.extend_from_slice(&binding.data()[..binding.length * ty.size()]);
+            }
+        }
+        let scalars = scalars
+            .into_iter()
+            .map(|(ty, (length, data))| (ty, ScalarBinding::new(ty, length, pad(&data))))
+            .collect();
+
+        let kernels = launches.into_iter().map(|launch| launch.kernel).collect();
+
+        DeferredLaunch {
+            kernel: Box::new(FusedTask {
+                kernels,
+                plan: self.clone(),
+            }),
+            count,
+            bindings: Bindings {
+                buffers,
+                metadata,
+                scalars,
+                tensor_maps: Vec::new(),
+                textures: Vec::new(),
+                dynamic_shared_memory: 0,
+                spec_constants: Vec::new(),
+            },
+            mode,
+        }
+    }
+
+    /// Merge the definitions of the fused kernels into a single definition.
+    pub(super) fn merge(&self, definitions: Vec<KernelDefinition>) -> Option<KernelDefinition> {
+        let mut definitions = definitions.into_iter().enumerate();
+        let (_, mut fused) = definitions.next()?;
+
+        let buffers: Vec<Variable> = self
+            .buffers
+            .iter()
+            .enumerate()
+            .map(|(index, buffer)| {
+                let kind = match buffer.visibility {
+                    Visibility::Read => VariableKind::GlobalInputArray(index as Id),
+                    Visibility::ReadWrite => VariableKind::GlobalOutputArray(index as Id),
+                };
+                Variable::new(kind, buffer.ty)
+            })
+            .collect();
+        let locals: Vec<Variable> = self
+            .locals
+            .iter()
+            .map(|ty| {
+                let local = *fused.body.create_local_restricted(*ty);
+                fused.body.add_local_mut(local);
+                local
+            })
+            .collect();
+
+        let mut scalar_offsets = BTreeMap::new();
+        Renamer {
+            targets: &self.targets[0],
+            buffers: &buffers,
+            locals: &locals,
+            scalar_offsets: &scalar_offsets,
+            ids: None,
+        }
+        .scope(&mut fused.body)?;
+        add_scalars(&mut scalar_offsets, &fused.scalars);
+        let mut debug_symbols = fused.options.debug_symbols;
+
+        for (index, mut definition) in definitions {
+            let variables = definition.body.allocator.take_variables();
+            let mut renamer = Renamer {
+                targets: &self.targets[index],
+                buffers: &buffers,
+                locals: &locals,
+                scalar_offsets: &scalar_offsets,
+                ids: Some((fused.body.allocator.clone(), HashMap::new())),
+            };
+            renamer.scope(&mut definition.body)?;
+            for var in variables {
+                fused.body.add_local_mut(renamer.variable(var)?);
+            }
+
+            for var in definition.body.locals {
+                fused.body.add_local_mut(var);
+            }
+            fused.body.const_arrays.extend(definition.body.const_arrays);
+            fused.body.instructions.extend(definition.body.instructions);
+
+            add_scalars(&mut scalar_offsets, &definition.scalars);
+            debug_symbols |= definition.options.debug_symbols;
+        }
+
+        let buffers = self
+            .buffers
+            .iter()
+            .enumerate()
+            .map(|(index, buffer)| kernel::Binding {
+                id: index as Id,
+                location: kernel::Location::Storage,
+                visibility: buffer.visibility,
+                ty: buffer.ty,
+                size: None,
+                has_extended_meta: buffer.extended,
+            })
+            .collect();
+        let scalars = scalar_offsets
+            .into_iter()
+            .map(|(ty, count)| kernel::ScalarBinding { ty, count })
+            .collect();
+
+        Some(KernelDefinition {
+            buffers,
+            tensor_maps: Vec::new(),
+            textures: Vec::new(),
+            scalars,
+            cube_dim: self.cube_dim,
+            body: fused.body,
+            options: KernelOptions {
+                kernel_name: "fused_elementwise".to_string(),
+                debug_symbols,
+                cluster_dim: None,
+            },
+            dynamic_shared_memory: None,
+        })
+    }
+}
+
+fn add_scalars(offsets: &mut BTreeMap<StorageType, usize>, scalars: &[kernel::ScalarBinding]) {
+    for scalar in scalars {
+        *offsets.entry(scalar.ty).or_default() += scalar.count;
+    }
+}
@@ -0,0 +1,163 @@
+use super::{plan::BufferTarget, summary::branch_parts};
+use alloc::{collections::BTreeMap, vec::Vec};
+use cubecl_ir::{
+    Allocator, Id, Instruction, Metadata, Operation, OperationReflect, Operator, Scope,
+    StorageType, Variable, VariableKind,
+};
+use hashbrown::HashMap;
+
+/// Renames the variables of a fused kernel scope.
+pub(super) struct Renamer<'a> {
+    pub(super) targets: &'a [BufferTarget],
+    pub(super) buffers: &'a [Variable],
+    pub(super) locals: &'a [Variable],
+    pub(super) scalar_offsets: &'a BTreeMap<StorageType, usize>,
+    /// The allocator of the fused kernel with the new id of every local variable, for kernels
+    /// that don't provide the root scope.
+    pub(super) ids: Option<(Allocator, HashMap<Id, Id>)>,
+}
+
+impl Renamer<'_> {
+    pub(super) fn scope(&mut self, scope: &mut Scope) -> Option<()> {
+        if let Some((allocator, _)) = &self.ids {
+            scope.allocator = allocator.clone();
+        }
+        for var in scope.locals.iter_mut() {
+            *var = self.variable(*var)?;
+        }
+        for (var, values) in scope.const_arrays.iter_mut() {
+            *var = self.variable(*var)?;
+            for value in values.iter_mut() {
+                *value = self.variable(*value)?;
+            }
+        }
+        for inst in scope.instructions.iter_mut() {
+            self.instruction(inst)?;
+        }
+        Some(())
+    }
+
+    fn instruction(&mut self, inst: &mut Instruction) -> Option<()> {
+        // Reads and writes of eliminated buffers become copies from and to their local.
+        match &inst.operation {
+            Operation::Operator(Operator::Index(op) | Operator::UncheckedIndex(op)) => {
+                if let Some(local) = self.local(op.list) {
+                    let out = self.variable(inst.out?)?;
+                    inst.operation = Operation::Copy(local);
+                    inst.out = Some(out);
+                    return Some(());
+                }
+            }
+            Operation::Operator(Operator::IndexAssign(op) | Operator::UncheckedIndexAssign(op)) => {
+                if let Some(local) = self.local(inst.out?) {
+                    let value = self.variable(op.value)?;
+                    inst.operation = Operation::Copy(value);
+                    inst.out = Some(local);
+                    return Some(());
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(out) = inst.out {
+            inst.out = Some(self.variable(out)?);
+        }
+
+        match &mut inst.operation {
+            Operation::Metadata(Metadata::Stride { dim, var } | Metadata::Shape { dim, var }) => {
+                *dim = self.variable(*dim)?;
+                *var = self.metadata(*var)?;
+            }
+            Operation::Metadata(
+                Metadata::Rank { var } | Metadata::Length { var } | Metadata::BufferLength { var },
+            ) => {
+                *var = self.metadata(*var)?;
+            }
+            Operation::Branch(branch) => {
+                let (variables, scopes) = branch_parts(branch)?;
+                for var in variables {
+                    *var = self.variable(*var)?;
+                }
+                for scope in scopes {
+                    self.scope(scope)?;
+                }
+            }
+            Operation::Call(_) => return None,
+            operation => {
+                let args = operation
+                    .args()?
+                    .into_iter()
+                    .map(|var| self.variable(var))
+                    .collect::<Option<Vec<_>>>()?;
+                *operation = Operation::from_code_and_args(operation.op_code(), &args)?;
+            }
+        }
+
+        Some(())
+    }
+
+    /// The local replacing the buffer, if it was eliminated.
+    fn local(&self, var: Variable) -> Option<Variable> {
+        match self.target(var)? {
+            BufferTarget::Local { local, .. } => Some(self.locals[local]),
+            BufferTarget::Buffer(_) => None,
+        }
+    }
+
+    /// The buffer providing the metadata of `var`.
+    fn metadata(&mut self, var: Variable) -> Option<Variable> {
+        match self.target(var) {
+            Some(BufferTarget::Local { metadata, .. }) => Some(self.buffers[metadata]),
+            _ => self.variable(var),
+        }
+    }
+
+    fn target(&self, var: Variable) -> Option<BufferTarget> {
+        match var.kind {
+            VariableKind::GlobalInputArray(id) | VariableKind::GlobalOutputArray(id) => {
+                self.targets.get(id as usize).copied()
+            }
+            _ => None,
+        }
+    }
+
+    pub(super) fn variable(&mut self, var: Variable) -> Option<Variable> {
+        let kind = match var.kind {
+            VariableKind::GlobalInputArray(_) | VariableKind::GlobalOutputArray(_) => {
+                match self.target(var)? {
+                    BufferTarget::Buffer(index) => return Some(self.buffers[index]),
+                    BufferTarget::Local { .. } => return None,
+                }
+            }
+            VariableKind::GlobalScalar(id) => {
+                let offset = self.scalar_offsets.get(&var.storage_type()).copied();
+                VariableKind::GlobalScalar(id + offset.unwrap_or(0) as Id)
+            }
+            VariableKind::LocalMut { id } => VariableKind::LocalMut { id: self.id(id) },
+            VariableKind::LocalConst { id } => VariableKind::LocalConst { id: self.id(id) },
+            VariableKind::Versioned { id, version } => VariableKind::Versioned {
+                id: self.id(id),
+                version,
+            },
+            VariableKind::ConstantArray {
+                id,
+                length,
+                unroll_factor,
+            } => VariableKind::ConstantArray {
+                id: self.id(id),
+                length,
+                unroll_factor,
+            },
+            VariableKind::Constant(_) | VariableKind::Builtin(_) => var.kind,
+            _ => return None,
+        };
+        Some(Variable::new(kind, var.ty))
+    }
+
+    fn id(&mut self, id: Id) -> Id {
+        match &mut self.ids {
+            Some((allocator, ids)) => *ids.entry(id).or_insert_with(|| allocator.new_local_index()),
+            None => id,
+        }
+    }
+}
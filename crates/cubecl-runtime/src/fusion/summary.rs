@@ -0,0 +1,252 @@
+use crate::{
+    kernel::{KernelDefinition, Visibility},
+    server::CubeDim,
+};
+use alloc::{vec, vec::Vec};
+use cubecl_ir::{
+    Branch, Builtin, If, IfElse, Instruction, Loop, Metadata, Operation, OperationReflect,
+    Operator, RangeLoop, Scope, Switch, Type, Variable, VariableKind,
+};
+
+/// How the bindings of a kernel are used, to decide whether it can be fused.
+pub(super) struct KernelSummary {
+    pub(super) cube_dim: CubeDim,
+    pub(super) buffers: Vec<BufferSummary>,
+}
+
+pub(super) struct BufferSummary {
+    pub(super) ty: Type,
+    pub(super) visibility: Visibility,
+    pub(super) extended: bool,
+    pub(super) access: Access,
+    /// Whether the elements of the buffer are written.
+    pub(super) written: bool,
+    /// Whether every element read from the buffer is the one of the unit, so that it can't be
+    /// written by another unit of a fused launch.
+    pub(super) aligned: bool,
+}
+
+/// The first access to the elements of a buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Access {
+    Unused,
+    Read,
+    Write,
+    /// The buffer is used in a way that prevents replacing it with a local variable.
+    Other,
+}
+
+impl Access {
+    /// The first access of a buffer accessed with `self`, then with `next`.
+    pub(super) fn then(self, next: Self) -> Self {
+        match (self, next) {
+            (Access::Other, _) | (_, Access::Other) => Access::Other,
+            (Access::Unused, next) => next,
+            (access, _) => access,
+        }
+    }
+}
+
+impl KernelSummary {
+    pub(super) fn new(definition: &mut KernelDefinition) -> Option<Self> {
+        let unsupported = !definition.tensor_maps.is_empty()
+            || !definition.textures.is_empty()
+            || definition.options.cluster_dim.is_some()
+            || definition.dynamic_shared_memory.is_some()
+            || !definition.body.functions.borrow().is_empty()
+            || definition
+                .buffers
+                .iter()
+                .enumerate()
+                .any(|(index, buffer)| buffer.id as usize != index);
+        if unsupported {
+            return None;
+        }
+
+        let mut summary = Self {
+            cube_dim: definition.cube_dim,
+            buffers: definition
+                .buffers
+                .iter()
+                .map(|buffer| BufferSummary {
+                    ty: buffer.ty,
+                    visibility: buffer.visibility,
+                    extended: buffer.has_extended_meta,
+                    access: Access::Unused,
+                    written: false,
+                    aligned: true,
+                })
+                .collect(),
+        };
+        summary.scope(&mut definition.body, false)?;
+
+        Some(summary)
+    }
+
+    fn scope(&mut self, scope: &mut Scope, looped: bool) -> Option<()> {
+        for var in scope.locals.iter() {
+            self.variable(*var, Access::Other)?;
+        }
+        for (var, _) in scope.const_arrays.iter() {
+            self.variable(*var, Access::Other)?;
+        }
+        for inst in scope.instructions.iter_mut() {
+            self.instruction(inst, looped)?;
+        }
+        Some(())
+    }
+
+    /// Summarize the instruction, with `looped` when it's nested in a loop.
+    fn instruction(&mut self, inst: &mut Instruction, looped: bool) -> Option<()> {
+        match &mut inst.operation {
+            Operation::Operator(Operator::Index(op) | Operator::UncheckedIndex(op)) => {
+                let out = inst.out?;
+                let access = match out.ty == op.list.ty {
+                    true => Access::Read,
+                    false => Access::Other,
+                };
+                if (looped || !is_unit_position(op.index))
+                    && let Some(buffer) = self.buffer(op.list)
+                {
+                    buffer.aligned = false;
+                }
+                self.variable(op.index, Access::Other)?;
+                self.variable(out, Access::Other)?;
+                self.variable(op.list, access)
+            }
+            Operation::Operator(Operator::IndexAssign(op) | Operator::UncheckedIndexAssign(op)) => {
+                let out = inst.out?;
+                let access = match out.ty == op.value.ty {
+                    true => Access::Write,
+                    false => Access::Other,
+                };
+                if let Some(buffer) = self.buffer(out) {
+                    // Units writing other elements, or many elements, could overwrite the
+                    // elements read by other units of the fused launches.
+                    if looped || !is_unit_position(op.index) {
+                        return None;
+                    }
+                    buffer.written = true;
+                }
+                self.variable(op.index, Access::Other)?;
+                self.variable(op.value, Access::Other)?;
+                self.variable(out, access)
+            }
+            Operation::Metadata(Metadata::Stride { dim, var } | Metadata::Shape { dim, var }) => {
+                self.variable(*dim, Access::Other)?;
+                self.variable(*var, Access::Unused)
+            }
+            Operation::Metadata(
+                Metadata::Rank { var } | Metadata::Length { var } | Metadata::BufferLength { var },
+            ) => self.variable(*var, Access::Unused),
+            Operation::Branch(branch) => {
+                let looped = looped || matches!(branch, Branch::RangeLoop(_) | Branch::Loop(_));
+                let (variables, scopes) = branch_parts(branch)?;
+                for var in variables {
+                    self.variable(*var, Access::Other)?;
+                }
+                for scope in scopes {
+                    self.scope(scope, looped)?;
+                }
+                Some(())
+            }
+            Operation::Call(_) => None,
+            operation => {
+                for var in operation.args()?.into_iter().chain(inst.out) {
+                    self.variable(var, Access::Other)?;
+                }
+                Some(())
+            }
+        }
+    }
+
+    fn buffer(&mut self, var: Variable) -> Option<&mut BufferSummary> {
+        match var.kind {
+            VariableKind::GlobalInputArray(id) | VariableKind::GlobalOutputArray(id) => {
+                self.buffers.get_mut(id as usize)
+            }
+            _ => None,
+        }
+    }
+
+    fn variable(&mut self, var: Variable, access: Access) -> Option<()> {
+        match var.kind {
+            VariableKind::GlobalInputArray(_) | VariableKind::GlobalOutputArray(_) => {
+                let buffer = self.buffer(var)?;
+                buffer.access = buffer.access.then(access);
+                Some(())
+            }
+            VariableKind::GlobalScalar(_)
+            | VariableKind::LocalMut { .. }
+            | VariableKind::LocalConst { .. }
+            | VariableKind::Versioned { .. }
+            | VariableKind::ConstantArray { .. }
+            | VariableKind::Constant(_)
+            | VariableKind::Builtin(_) => Some(()),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the index is the absolute position of the unit.
+///
+/// Only the [AbsolutePos](Builtin::AbsolutePos) builtin itself is recognized, which covers
+/// `ABSOLUTE_POS` and immutable bindings of it. Indices computed from it, or copied into a mutable
+/// local, are conservatively treated as any other position: reading a buffer with them prevents
+/// the fusion with a launch writing the buffer, and writing with them prevents any fusion.
+fn is_unit_position(index: Variable) -> bool {
+    matches!(index.kind, VariableKind::Builtin(Builtin::AbsolutePos))
+}
+
+/// The variables and the nested scopes of a branch, or `None` for an early return, which would
+/// skip the kernels fused after the current one.
+pub(super) fn branch_parts(branch: &mut Branch) -> Option<(Vec<&mut Variable>, Vec<&mut Scope>)> {
+    let parts = match branch {
+        Branch::If(if_) => {
+            let If { cond, scope } = if_.as_mut();
+            (vec![cond], vec![scope])
+        }
+        Branch::IfElse(if_else) => {
+            let IfElse {
+                cond,
+                scope_if,
+                scope_else,
+            } = if_else.as_mut();
+            (vec![cond], vec![scope_if, scope_else])
+        }
+        Branch::Switch(switch) => {
+            let Switch {
+                value,
+                scope_default,
+                cases,
+            } = switch.as_mut();
+            let mut variables = vec![value];
+            let mut scopes = vec![scope_default];
+            for (value, scope) in cases.iter_mut() {
+                variables.push(value);
+                scopes.push(scope);
+            }
+            (variables, scopes)
+        }
+        Branch::RangeLoop(range_loop) => {
+            let RangeLoop {
+                i,
+                start,
+                end,
+                step,
+                scope,
+                ..
+            } = range_loop.as_mut();
+            let mut variables = vec![i, start, end];
+            variables.extend(step.as_mut());
+            (variables, vec![scope])
+        }
+        Branch::Loop(loop_) => {
+            let Loop { scope } = loop_.as_mut();
+            (Vec::new(), vec![scope])
+        }
+        Branch::Return => return None,
+        Branch::Break | Branch::Continue => (Vec::new(), Vec::new()),
+    };
+    Some(parts)
+}
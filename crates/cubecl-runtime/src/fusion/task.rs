@@ -0,0 +1,81 @@
+use super::plan::FusionPlan;
+use crate::{
+    compiler::{CompilationError, Compiler, CubeTask},
+    id::KernelId,
+    kernel::{CompiledKernel, KernelDefinition, KernelMetadata, KernelResourceUsage},
+    server::ExecutionMode,
+};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use cubecl_common::backtrace::BackTrace;
+use cubecl_ir::StorageType;
+
+/// A kernel merging the kernels of consecutive launches.
+pub(super) struct FusedTask<C: Compiler> {
+    pub(super) kernels: Vec<Box<dyn CubeTask<C>>>,
+    pub(super) plan: Arc<FusionPlan>,
+}
+
+impl<C: Compiler> KernelMetadata for FusedTask<C> {
+    fn id(&self) -> KernelId {
+        self.plan.id.clone()
+    }
+
+    fn address_type(&self) -> StorageType {
+        self.kernels[0].address_type()
+    }
+}
+
+impl<C: Compiler> CubeTask<C> for FusedTask<C> {
+    fn compile(
+        &self,
+        compiler: &mut C,
+        compilation_options: &C::CompilationOptions,
+        mode: ExecutionMode,
+        address_type: StorageType,
+    ) -> Result<CompiledKernel<C>, CompilationError> {
+        let definition = self.define().ok_or_else(|| {
+            let names: Vec<String> = self
+                .kernels
+                .iter()
+                .map(|kernel| kernel.name().to_string())
+                .collect();
+            CompilationError::Generic {
+                reason: format!("Can't fuse the kernels {names:?}"),
+                backtrace: BackTrace::capture(),
+            }
+        })?;
+        let entrypoint_name = definition.options.kernel_name.clone();
+        let cube_dim = definition.cube_dim;
+        let bindings = definition.num_bindings();
+        let repr = compiler.compile(definition, compilation_options, mode, address_type)?;
+        let resource_usage = KernelResourceUsage {
+            bindings,
+            ..compiler.resource_usage(&repr)
+        };
+
+        Ok(CompiledKernel {
+            entrypoint_name,
+            debug_name: Some(core::any::type_name::<Self>()),
+            source: repr.to_string(),
+            repr: Some(repr),
+            cube_dim,
+            debug_info: None,
+            resource_usage,
+        })
+    }
+
+    fn define(&self) -> Option<KernelDefinition> {
+        let definitions = self
+            .kernels
+            .iter()
+            .map(|kernel| kernel.define())
+            .collect::<Option<Vec<_>>>()?;
+        self.plan.merge(definitions)
+    }
+}
@@ -0,0 +1,311 @@
+use crate::{
+    compiler::{CompilationError, Compiler, CubeTask},
+    id::KernelId,
+    kernel::{CompiledKernel, KernelDefinition, KernelMetadata},
+    runtime::Runtime,
+    server::{Binding, Bindings, CubeCount, ExecutionMode, Handle},
+};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use cubecl_common::{stream_id::StreamId, stub::Mutex};
+use cubecl_ir::StorageType;
+use hashbrown::HashMap;
+
+/// A sequence of kernel launches recorded with
+/// [ComputeClient::capture](crate::client::ComputeClient::capture), which can be replayed with
+/// [ComputeClient::replay](crate::client::ComputeClient::replay).
+///
+/// Replaying a graph skips the argument packing and the per-launch locking of the server, and
+/// servers can map it to native graphs or pre-recorded command lists. The scalars and buffers of
+/// each launch can be updated between replays, but the kernels, cube counts and tensor metadata
+/// are fixed at capture time. The captured kernels are executed during the capture, like any
+/// other launch.
+///
+/// The graph keeps every buffer it references alive until it is dropped.
+pub struct LaunchGraph<R: Runtime> {
+    launches: Vec<CapturedLaunch<Box<dyn CubeTask<R::Compiler>>>>,
+}
+
+/// A kernel launch recorded in a [LaunchGraph].
+pub struct CapturedLaunch<K> {
+    kernel: Arc<K>,
+    /// The number of cubes dispatched by the launch.
+    pub count: CubeCount,
+    /// The bindings of the launch.
+    pub bindings: Bindings,
+    /// The execution mode of the launch.
+    pub mode: ExecutionMode,
+}
+
+/// A kernel launch of a [LaunchGraph] submitted to
+/// [ComputeServer::launch_graph](crate::server::ComputeServer::launch_graph).
+pub struct GraphLaunch<K> {
+    /// The kernel to launch.
+    pub kernel: K,
+    /// The number of cubes to dispatch.
+    pub count: CubeCount,
+    /// The bindings of the kernel.
+    pub bindings: Bindings,
+    /// The execution mode.
+    pub mode: ExecutionMode,
+}
+
+/// The launches recorded on each stream that is currently being captured.
+pub(crate) struct LaunchCaptures<K> {
+    streams: Mutex<HashMap<StreamId, Vec<CapturedLaunch<K>>>>,
+}
+
+/// Stops capturing the launches of a stream when dropped, so the stream isn't left being captured
+/// when the captured closure panics.
+pub(crate) struct CaptureGuard<'a, K> {
+    captures: &'a LaunchCaptures<K>,
+    stream_id: StreamId,
+    ended: bool,
+}
+
+/// A kernel shared between the launches of a graph, so it can be submitted on every replay.
+struct SharedTask<C: Compiler> {
+    task: Arc<Box<dyn CubeTask<C>>>,
+}
+
+impl<R: Runtime> LaunchGraph<R> {
+    /// The number of launches in the graph.
+    pub fn len(&self) -> usize {
+        self.launches.len()
+    }
+
+    /// Whether the graph doesn't contain any launch.
+    pub fn is_empty(&self) -> bool {
+        self.launches.is_empty()
+    }
+
+    /// The launches of the graph, in submission order.
+    pub fn launches(&self) -> &[CapturedLaunch<Box<dyn CubeTask<R::Compiler>>>] {
+        &self.launches
+    }
+
+    /// The launches of the graph, in submission order, to update their bindings.
+    pub fn launches_mut(&mut self) -> &mut [CapturedLaunch<Box<dyn CubeTask<R::Compiler>>>] {
+        &mut self.launches
+    }
+
+    /// Replace every binding of `old` in the graph with `new`, and return the number of bindings
+    /// that were replaced.
+    ///
+    /// # Panics
+    ///
+    /// If `new` doesn't have the same size as `old`, since the tensor metadata of the launches
+    /// can't be updated.
+    pub fn replace_buffer(&mut self, old: &Handle, new: &Handle) -> usize {
+        assert_eq!(
+            old.size(),
+            new.size(),
+            "A buffer of a launch graph can only be replaced by a buffer of the same size"
+        );
+
+        let mut replaced = 0;
+        for launch in self.launches.iter_mut() {
+            replaced += launch.replace_buffer(old, new);
+        }
+        replaced
+    }
+
+    pub(crate) fn submissions(&self) -> Vec<GraphLaunch<Box<dyn CubeTask<R::Compiler>>>> {
+        self.launches
+            .iter()
+            .map(|launch| GraphLaunch {
+                kernel: Box::new(SharedTask {
+                    task: launch.kernel.clone(),
+                }) as Box<dyn CubeTask<R::Compiler>>,
+                count: launch.count.clone(),
+                bindings: launch.bindings.clone(),
+                mode: launch.mode,
+            })
+            .collect()
+    }
+}
+
+impl<K: KernelMetadata> CapturedLaunch<K> {
+    /// The kernel of the launch.
+    pub fn kernel(&self) -> &K {
+        &self.kernel
+    }
+
+    /// Set the scalar at `index` among the scalars of type `ty` of the launch.
+    ///
+    /// # Panics
+    ///
+    /// If the launch doesn't have a scalar of type `ty` at `index`, or if the size of `value`
+    /// doesn't match the size of `ty`.
+    pub fn set_scalar<T: bytemuck::NoUninit>(&mut self, ty: StorageType, index: usize, value: T) {
+        let size = ty.size();
+        assert_eq!(
+            size,
+            size_of::<T>(),
+            "The scalar value should have the same size as {ty}"
+        );
+
+        let binding = self
+            .bindings
+            .scalars
+            .get_mut(&ty)
+            .unwrap_or_else(|| panic!("The launch doesn't have any scalar of type {ty}"));
+        assert!(
+            index < binding.length,
+            "Scalar index {index} is out of bounds for the {} scalars of type {ty}",
+            binding.length
+        );
+
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut binding.data);
+        data[index * size..(index + 1) * size].copy_from_slice(bytemuck::bytes_of(&value));
+    }
+
+    fn replace_buffer(&mut self, old: &Handle, new: &Handle) -> usize {
+        let matches = |binding: &Binding| {
+            binding.memory.id() == old.memory.id()
+                && binding.offset_start == old.offset_start
+                && binding.offset_end == old.offset_end
+        };
+
+        let mut replaced = 0;
+        let bindings = self.bindings.buffers.iter_mut().chain(
+            self.bindings
+                .tensor_maps
+                .iter_mut()
+                .map(|it| &mut it.binding),
+        );
+        let count = match &mut self.count {
+            CubeCount::Dynamic(binding) => Some(binding),
+            CubeCount::Static(..) => None,
+        };
+        for binding in bindings.chain(count) {
+            if matches(binding) {
+                *binding = new.clone().binding();
+                replaced += 1;
+            }
+        }
+        replaced
+    }
+}
+
+impl<K> Default for LaunchCaptures<K> {
+    fn default() -> Self {
+        Self {
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K> LaunchCaptures<K> {
+    /// Start capturing the launches of the given stream.
+    ///
+    /// # Panics
+    ///
+    /// If the stream is already being captured.
+    pub(crate) fn begin(&self, stream_id: StreamId) -> CaptureGuard<'_, K> {
+        let mut streams = self.streams.lock().unwrap();
+        assert!(
+            !streams.contains_key(&stream_id),
+            "A launch graph is already being captured on stream {stream_id}"
+        );
+        streams.insert(stream_id, Vec::new());
+
+        CaptureGuard {
+            captures: self,
+            stream_id,
+            ended: false,
+        }
+    }
+
+    /// Stop capturing the launches of the given stream, and return the recorded launches.
+    pub(crate) fn end(&self, stream_id: StreamId) -> Vec<CapturedLaunch<K>> {
+        self.streams
+            .lock()
+            .unwrap()
+            .remove(&stream_id)
+            .unwrap_or_default()
+    }
+}
+
+impl<K> CaptureGuard<'_, K> {
+    /// Stop capturing the launches of the stream, and return the recorded launches.
+    pub(crate) fn end(mut self) -> Vec<CapturedLaunch<K>> {
+        self.ended = true;
+        self.captures.end(self.stream_id)
+    }
+}
+
+impl<K> Drop for CaptureGuard<'_, K> {
+    fn drop(&mut self) {
+        if !self.ended {
+            self.captures.end(self.stream_id);
+        }
+    }
+}
+
+impl<C: Compiler> LaunchCaptures<Box<dyn CubeTask<C>>> {
+    /// Record the launch if its stream is being captured, and return the kernel to launch.
+    pub(crate) fn record(
+        &self,
+        stream_id: StreamId,
+        kernel: Box<dyn CubeTask<C>>,
+        count: &CubeCount,
+        bindings: &Bindings,
+        mode: ExecutionMode,
+    ) -> Box<dyn CubeTask<C>> {
+        let mut streams = self.streams.lock().unwrap();
+        let Some(launches) = streams.get_mut(&stream_id) else {
+            return kernel;
+        };
+
+        let task = Arc::new(kernel);
+        launches.push(CapturedLaunch {
+            kernel: task.clone(),
+            count: count.clone(),
+            bindings: bindings.clone(),
+            mode,
+        });
+
+        Box::new(SharedTask { task })
+    }
+}
+
+impl<R: Runtime> From<Vec<CapturedLaunch<Box<dyn CubeTask<R::Compiler>>>>> for LaunchGraph<R> {
+    fn from(launches: Vec<CapturedLaunch<Box<dyn CubeTask<R::Compiler>>>>) -> Self {
+        Self { launches }
+    }
+}
+
+impl<C: Compiler> KernelMetadata for SharedTask<C> {
+    fn id(&self) -> KernelId {
+        self.task.id()
+    }
+
+    fn name(&self) -> &'static str {
+        self.task.name()
+    }
+
+    fn address_type(&self) -> StorageType {
+        self.task.address_type()
+    }
+
+    fn fusable(&self) -> bool {
+        self.task.fusable()
+    }
+}
+
+impl<C: Compiler> CubeTask<C> for SharedTask<C> {
+    fn compile(
+        &self,
+        compiler: &mut C,
+        compilation_options: &C::CompilationOptions,
+        mode: ExecutionMode,
+        address_type: StorageType,
+    ) -> Result<CompiledKernel<C>, CompilationError> {
+        self.task
+            .compile(compiler, compilation_options, mode, address_type)
+    }
+
+    fn define(&self) -> Option<KernelDefinition> {
+        self.task.define()
+    }
+}
@@ -1,6 +1,6 @@
 use alloc::format;
 use alloc::string::String;
-use alloc::sync::Arc;
+use alloc::sync::{Arc, Weak};
 use core::{
     any::{Any, TypeId},
     fmt::Display,
@@ -54,7 +54,22 @@ pub struct HandleRef<Id> {
 #[derive(Clone, Debug)]
 pub struct BindingRef<Id> {
     id: Id,
-    _all: Arc<()>,
+    all: Arc<()>,
+    handle: Weak<Id>,
+}
+
+/// Reference to a buffer that doesn't keep it alive, to find out when the buffer is freed.
+#[derive(Clone, Debug)]
+pub struct WeakBindingRef {
+    all: Weak<()>,
+}
+
+impl WeakBindingRef {
+    /// If the buffer was freed, meaning it can be reused for another allocation.
+    pub fn is_free(&self) -> bool {
+        // Only the memory management holds a reference once the buffer is freed.
+        self.all.strong_count() <= 1
+    }
 }
 
 impl<Id> BindingRef<Id>
@@ -65,6 +80,21 @@ where
     pub(crate) fn id(&self) -> &Id {
         &self.id
     }
+
+    /// Whether a handle other than the one kept by the memory management still owns the buffer.
+    ///
+    /// Bindings don't own the buffer, so when this returns `false` the content of the buffer can
+    /// only be observed through the existing bindings.
+    pub(crate) fn is_owned(&self) -> bool {
+        self.handle.strong_count() > 1
+    }
+
+    /// Get a reference to the buffer that doesn't keep it alive.
+    pub fn downgrade(&self) -> WeakBindingRef {
+        WeakBindingRef {
+            all: Arc::downgrade(&self.all),
+        }
+    }
 }
 
 impl<Id> HandleRef<Id>
@@ -88,7 +118,8 @@ where
     pub(crate) fn binding(self) -> BindingRef<Id> {
         BindingRef {
             id: self.id.as_ref().clone(),
-            _all: self.all,
+            all: self.all,
+            handle: Arc::downgrade(&self.id),
         }
     }
 
@@ -11,7 +11,7 @@ use core::{
 };
 
 use cubecl_common::format::format_str;
-use cubecl_ir::{Id, Scope, StorageType, Type};
+use cubecl_ir::{Id, Scope, StorageType, TextureDim, Type};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -33,6 +33,14 @@ pub trait KernelMetadata: Send + Sync + 'static {
 
     /// Type of addresses in this kernel
     fn address_type(&self) -> StorageType;
+
+    /// Whether launches of this kernel can be fused with other element-wise launches.
+    ///
+    /// Every unit of a fusable kernel must only read and write the elements at its own position
+    /// in the global buffers.
+    fn fusable(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -40,10 +48,21 @@ pub trait KernelMetadata: Send + Sync + 'static {
 pub struct KernelDefinition {
     pub buffers: Vec<Binding>,
     pub tensor_maps: Vec<Binding>,
+    pub textures: Vec<TextureBinding>,
     pub scalars: Vec<ScalarBinding>,
     pub cube_dim: CubeDim,
     pub body: Scope,
     pub options: KernelOptions,
+    /// Size in bytes of the dynamic shared memory, when it's specialized into the kernel rather
+    /// than set at launch.
+    pub dynamic_shared_memory: Option<usize>,
+}
+
+impl KernelDefinition {
+    /// Number of buffers, tensor maps, textures and scalar groups bound to the kernel.
+    pub fn num_bindings(&self) -> usize {
+        self.buffers.len() + self.tensor_maps.len() + self.textures.len() + self.scalars.len()
+    }
 }
 
 #[derive(Default, Clone, Debug, Hash, PartialEq, Eq)]
@@ -68,6 +87,14 @@ pub struct Binding {
     pub has_extended_meta: bool,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct TextureBinding {
+    pub id: Id,
+    pub ty: Type,
+    pub dim: TextureDim,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct ScalarBinding {
@@ -129,6 +156,57 @@ pub struct CompiledKernel<C: Compiler> {
     pub cube_dim: CubeDim,
     /// Extra debugging information about the compiled kernel.
     pub debug_info: Option<DebugInformation>,
+    /// The resources used by the compiled kernel.
+    ///
+    /// Only the fields known by the compiler are set, runtimes fill in the fields reported by the
+    /// driver once the kernel is loaded.
+    pub resource_usage: KernelResourceUsage,
+}
+
+/// The resources used by a compiled kernel, which limit how many cubes can run at once.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KernelResourceUsage {
+    /// Bytes of shared memory statically allocated per cube. Shared memories that are never live
+    /// at the same time can share the same bytes.
+    pub shared_memory: usize,
+    /// Bytes of each local array, per unit.
+    pub local_arrays: Vec<usize>,
+    /// Number of buffers, tensor maps, textures and scalar groups bound to the kernel.
+    pub bindings: usize,
+    /// Number of registers used per unit, when reported by the driver.
+    pub registers: Option<u32>,
+    /// Bytes of local memory used per unit, including register spills, when reported by the
+    /// driver.
+    pub local_memory: Option<usize>,
+    /// Maximum number of units per cube the kernel can be launched with, when reported by the
+    /// driver.
+    pub max_units_per_cube: Option<u32>,
+    /// Maximum number of cubes that can be active at once on a single multiprocessor with the
+    /// compiled cube dim and no dynamic shared memory, when reported by the driver.
+    pub max_active_cubes: Option<u32>,
+}
+
+impl Display for KernelResourceUsage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "shared_memory: {} B, local_arrays: {:?} B, bindings: {}",
+            self.shared_memory, self.local_arrays, self.bindings
+        )?;
+        if let Some(registers) = self.registers {
+            write!(f, ", registers: {registers}")?;
+        }
+        if let Some(local_memory) = self.local_memory {
+            write!(f, ", local_memory: {local_memory} B")?;
+        }
+        if let Some(max_units) = self.max_units_per_cube {
+            write!(f, ", max_units_per_cube: {max_units}")?;
+        }
+        if let Some(max_cubes) = self.max_active_cubes {
+            write!(f, ", max_active_cubes: {max_cubes}")?;
+        }
+        Ok(())
+    }
 }
 
 /// Extra debugging information about the compiled kernel.
@@ -179,7 +257,12 @@ impl<C: Compiler, K: CubeKernel> CubeTask<C> for KernelTask<C, K> {
         let gpu_ir = self.kernel_definition.define();
         let entrypoint_name = gpu_ir.options.kernel_name.clone();
         let cube_dim = gpu_ir.cube_dim;
+        let bindings = gpu_ir.num_bindings();
         let lower_level_ir = compiler.compile(gpu_ir, compilation_options, mode, addr_type)?;
+        let resource_usage = KernelResourceUsage {
+            bindings,
+            ..compiler.resource_usage(&lower_level_ir)
+        };
 
         Ok(CompiledKernel {
             entrypoint_name,
@@ -188,8 +271,13 @@ impl<C: Compiler, K: CubeKernel> CubeTask<C> for KernelTask<C, K> {
             repr: Some(lower_level_ir),
             cube_dim,
             debug_info: None,
+            resource_usage,
         })
     }
+
+    fn define(&self) -> Option<KernelDefinition> {
+        Some(self.kernel_definition.define())
+    }
 }
 
 impl<C: Compiler, K: CubeKernel> KernelMetadata for KernelTask<C, K> {
@@ -206,6 +294,10 @@ impl<C: Compiler, K: CubeKernel> KernelMetadata for KernelTask<C, K> {
     fn address_type(&self) -> StorageType {
         self.kernel_definition.address_type()
     }
+
+    fn fusable(&self) -> bool {
+        self.kernel_definition.fusable()
+    }
 }
 
 impl<C: Compiler> KernelMetadata for Box<dyn CubeTask<C>> {
@@ -222,6 +314,10 @@ impl<C: Compiler> KernelMetadata for Box<dyn CubeTask<C>> {
     fn address_type(&self) -> StorageType {
         self.as_ref().address_type()
     }
+
+    fn fusable(&self) -> bool {
+        self.as_ref().fusable()
+    }
 }
 
 static COMPILATION_LEVEL: AtomicI8 = AtomicI8::new(-1);
@@ -283,6 +379,8 @@ cube_dim: ({}, {}, {})",
             self.cube_dim.x, self.cube_dim.y, self.cube_dim.z,
         ))?;
 
+        f.write_fmt(format_args!("\nresources: {}", self.resource_usage))?;
+
         if let Some(info) = &self.debug_info {
             f.write_fmt(format_args!(
                 "\ninfo: {}",
@@ -20,9 +20,18 @@ pub mod stream;
 /// Compute client module.
 pub mod client;
 
+/// Launch graph capture and replay.
+pub mod graph;
+
+/// Lazy fusion of element-wise launches.
+pub(crate) mod fusion;
+
 /// Autotune module
 pub mod tune;
 
+/// Occupancy calculator to pick launch dimensions.
+pub mod occupancy;
+
 /// Memory management module.
 pub mod memory_management;
 /// Compute server module.
@@ -47,3 +56,7 @@ pub mod compiler;
 pub mod runtime;
 /// Simple system profiling using timestamps.
 pub mod timestamp_profiler;
+
+/// Serializable device properties and compile-only clients emulating a device.
+#[cfg(all(std_io, feature = "storage-bytes"))]
+pub mod device_profile;
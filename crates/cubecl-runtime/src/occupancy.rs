@@ -0,0 +1,377 @@
+use alloc::vec::Vec;
+use cubecl_ir::HardwareProperties;
+
+use crate::{
+    kernel::KernelResourceUsage,
+    server::{CubeCountSelection, CubeDim},
+};
+
+/// The resource limiting how many cubes can be active at once on a multiprocessor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OccupancyLimit {
+    /// The maximum number of cubes per multiprocessor.
+    Cubes,
+    /// The maximum number of units per multiprocessor, or per cube when the cube can't be
+    /// launched at all.
+    Units,
+    /// The shared memory used by each cube.
+    SharedMemory,
+    /// The registers used by each unit.
+    Registers,
+}
+
+/// The occupancy of a kernel launched with a given [CubeDim].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Occupancy {
+    /// The cube dim the occupancy is computed for.
+    pub cube_dim: CubeDim,
+    /// Number of cubes active at once on a single multiprocessor. `0` if the kernel can't be
+    /// launched with this cube dim.
+    pub active_cubes: u32,
+    /// Ratio of the units of a multiprocessor that are active at once, between `0` and `1`.
+    pub ratio: f32,
+    /// The resource limiting the number of active cubes.
+    pub limit: OccupancyLimit,
+}
+
+/// Computes the occupancy of a kernel from its [resource usage](KernelResourceUsage) and the
+/// limits of the device, and suggests the [CubeDim] and cube count that maximize it.
+///
+/// The resource usage can be the one reported by
+/// [ComputeClient::resource_usage](crate::client::ComputeClient::resource_usage) once the kernel
+/// is compiled, or an estimate of the shared memory and registers the kernel needs. Limits that
+/// aren't reported by the device are ignored, and missing per multiprocessor limits fall back to
+/// the per cube limits.
+#[derive(Debug, Clone)]
+pub struct OccupancyCalculator {
+    hardware: HardwareProperties,
+    usage: KernelResourceUsage,
+    dynamic_shared_memory: usize,
+}
+
+impl OccupancyCalculator {
+    /// Create a calculator for a kernel with the given resource usage.
+    pub fn new(hardware: &HardwareProperties, usage: KernelResourceUsage) -> Self {
+        Self {
+            hardware: hardware.clone(),
+            usage,
+            dynamic_shared_memory: 0,
+        }
+    }
+
+    /// The dynamic shared memory allocated for each cube on top of the static shared memory, in
+    /// bytes.
+    pub fn dynamic_shared_memory(mut self, size: usize) -> Self {
+        self.dynamic_shared_memory = size;
+        self
+    }
+
+    /// The occupancy of the kernel when launched with `cube_dim`.
+    pub fn occupancy(&self, cube_dim: CubeDim) -> Occupancy {
+        let hardware = &self.hardware;
+        let units = cube_dim.num_elems();
+        let shared_memory = self.usage.shared_memory + self.dynamic_shared_memory;
+        let max_units = self.max_units_per_cube();
+
+        let unlaunchable = |limit| Occupancy {
+            cube_dim,
+            active_cubes: 0,
+            ratio: 0.0,
+            limit,
+        };
+        if units == 0 || units > max_units || !self.fits(cube_dim) {
+            return unlaunchable(OccupancyLimit::Units);
+        }
+        if shared_memory > hardware.max_shared_memory_size {
+            return unlaunchable(OccupancyLimit::SharedMemory);
+        }
+
+        // Units are scheduled by whole planes, so partial planes still take a full plane.
+        let plane_size = Ord::max(hardware.plane_size_max, 1);
+        let units_allocated = units.div_ceil(plane_size) * plane_size;
+        let units_per_sm = self.units_per_multiprocessor();
+
+        let mut limits = Vec::with_capacity(4);
+        limits.push((units_per_sm / units_allocated, OccupancyLimit::Units));
+        if let Some(max_cubes) = hardware.max_cubes_per_multiprocessor {
+            limits.push((max_cubes, OccupancyLimit::Cubes));
+        }
+        let smem_per_sm = hardware
+            .max_shared_memory_per_multiprocessor
+            .unwrap_or(hardware.max_shared_memory_size);
+        if let Some(cubes) = smem_per_sm.checked_div(shared_memory) {
+            let cubes = u32::try_from(cubes).unwrap_or(u32::MAX);
+            limits.push((cubes, OccupancyLimit::SharedMemory));
+        }
+        if let (Some(registers), Some(registers_per_sm)) =
+            (self.usage.registers, hardware.registers_per_multiprocessor)
+            && registers > 0
+        {
+            let cubes = registers_per_sm as u64 / (registers as u64 * units_allocated as u64);
+            limits.push((cubes as u32, OccupancyLimit::Registers));
+        }
+
+        // The first limit wins ties, so unit limits are reported over the others.
+        let (active_cubes, limit) = limits
+            .into_iter()
+            .reduce(|best, next| if next.0 < best.0 { next } else { best })
+            .unwrap();
+        let ratio = (active_cubes * units_allocated) as f32 / units_per_sm as f32;
+
+        Occupancy {
+            cube_dim,
+            active_cubes,
+            ratio: ratio.min(1.0),
+            limit,
+        }
+    }
+
+    /// The cube dims worth launching the kernel with to process `working_units` units, from the
+    /// highest to the lowest occupancy. Cube dims with the same occupancy are sorted from the
+    /// biggest to the smallest.
+    ///
+    /// Candidates have a whole number of planes on the `x` axis and a power of two number of
+    /// planes on the `y` axis, and are never bigger than needed for `working_units`.
+    pub fn cube_dim_candidates(&self, working_units: usize) -> Vec<CubeDim> {
+        let plane_size = Ord::max(self.hardware.plane_size_max, 1);
+        let max_units = self.max_units_per_cube();
+        let needed_units = Ord::max(working_units, 1).min(max_units as usize) as u64;
+        let max_units = Ord::min(
+            max_units as u64,
+            needed_units.next_multiple_of(plane_size as u64),
+        );
+
+        let mut candidates = (0..u32::BITS)
+            .map(|log2| 1u64 << log2)
+            .take_while(|num_planes| num_planes * plane_size as u64 <= max_units)
+            .map(|num_planes| self.occupancy(CubeDim::new_2d(plane_size, num_planes as u32)))
+            .filter(|occupancy| occupancy.active_cubes > 0)
+            .collect::<Vec<_>>();
+
+        candidates.sort_by(|a, b| {
+            b.ratio
+                .total_cmp(&a.ratio)
+                .then(b.cube_dim.num_elems().cmp(&a.cube_dim.num_elems()))
+        });
+        candidates.into_iter().map(|it| it.cube_dim).collect()
+    }
+
+    /// The cube dim with the highest occupancy to process `working_units` units.
+    ///
+    /// Falls back to a single plane when no cube dim can be launched with the resource usage.
+    pub fn cube_dim(&self, working_units: usize) -> CubeDim {
+        self.cube_dim_candidates(working_units)
+            .first()
+            .copied()
+            .unwrap_or(CubeDim::new_1d(Ord::max(self.hardware.plane_size_max, 1)))
+    }
+
+    /// The cube count to process `working_units` units with one unit per work item.
+    pub fn cube_count(&self, cube_dim: CubeDim, working_units: usize) -> CubeCountSelection {
+        CubeCountSelection::from_limits(
+            &self.hardware.max_cube_count,
+            self.num_cubes(cube_dim, working_units),
+        )
+    }
+
+    /// The number of cubes active at once on the whole device, if the number of multiprocessors
+    /// is known.
+    ///
+    /// Launching this many cubes and looping over the remaining work keeps the device fully
+    /// occupied without the scheduling overhead of extra cubes.
+    pub fn resident_cubes(&self, cube_dim: CubeDim) -> Option<u32> {
+        let num_sms = self.hardware.num_streaming_multiprocessors?;
+        Some(self.occupancy(cube_dim).active_cubes * num_sms)
+    }
+
+    /// The number of cubes to process `working_units` units with a persistent kernel, capped to
+    /// the [resident cubes](Self::resident_cubes) when known.
+    pub fn persistent_num_cubes(&self, cube_dim: CubeDim, working_units: usize) -> u32 {
+        let num_cubes = self.num_cubes(cube_dim, working_units);
+        match self.resident_cubes(cube_dim) {
+            Some(resident) if resident > 0 => Ord::min(num_cubes, resident),
+            _ => num_cubes,
+        }
+    }
+
+    fn num_cubes(&self, cube_dim: CubeDim, working_units: usize) -> u32 {
+        let units = Ord::max(cube_dim.num_elems(), 1) as usize;
+        let num_cubes = Ord::max(working_units.div_ceil(units), 1);
+        num_cubes.min(u32::MAX as usize) as u32
+    }
+
+    fn max_units_per_cube(&self) -> u32 {
+        match self.usage.max_units_per_cube {
+            Some(max_units) => Ord::min(max_units, self.hardware.max_units_per_cube),
+            None => self.hardware.max_units_per_cube,
+        }
+    }
+
+    fn units_per_multiprocessor(&self) -> u32 {
+        self.hardware
+            .max_units_per_multiprocessor
+            .unwrap_or(self.hardware.max_units_per_cube)
+    }
+
+    fn fits(&self, cube_dim: CubeDim) -> bool {
+        let (x, y, z) = self.hardware.max_cube_dim;
+        CubeDim::new_3d(x, y, z).can_contain(cube_dim)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hardware() -> HardwareProperties {
+        HardwareProperties {
+            load_width: 128,
+            plane_size_min: 32,
+            plane_size_max: 32,
+            max_bindings: 32,
+            max_shared_memory_size: 48 * 1024,
+            max_cube_count: (u16::MAX as u32, u16::MAX as u32, u16::MAX as u32),
+            max_units_per_cube: 1024,
+            max_cube_dim: (1024, 1024, 64),
+            num_streaming_multiprocessors: Some(10),
+            max_cubes_per_multiprocessor: Some(16),
+            max_units_per_multiprocessor: Some(2048),
+            max_shared_memory_per_multiprocessor: Some(100 * 1024),
+            registers_per_multiprocessor: Some(65536),
+            num_cpu_cores: None,
+            num_tensor_cores: None,
+            min_tensor_cores_dim: None,
+        }
+    }
+
+    #[test]
+    fn small_cubes_are_limited_by_the_cube_count() {
+        let calculator = OccupancyCalculator::new(&hardware(), Default::default());
+        let occupancy = calculator.occupancy(CubeDim::new_1d(64));
+
+        assert_eq!(occupancy.active_cubes, 16);
+        assert_eq!(occupancy.limit, OccupancyLimit::Cubes);
+        assert_eq!(occupancy.ratio, 0.5);
+    }
+
+    #[test]
+    fn partial_planes_take_a_full_plane() {
+        let calculator = OccupancyCalculator::new(&hardware(), Default::default());
+        let occupancy = calculator.occupancy(CubeDim::new_1d(520));
+
+        assert_eq!(occupancy.active_cubes, 3);
+        assert_eq!(occupancy.limit, OccupancyLimit::Units);
+    }
+
+    #[test]
+    fn shared_memory_limits_the_active_cubes() {
+        let usage = KernelResourceUsage {
+            shared_memory: 16 * 1024,
+            ..Default::default()
+        };
+        let calculator =
+            OccupancyCalculator::new(&hardware(), usage).dynamic_shared_memory(16 * 1024);
+        let occupancy = calculator.occupancy(CubeDim::new_1d(128));
+
+        assert_eq!(occupancy.active_cubes, 3);
+        assert_eq!(occupancy.limit, OccupancyLimit::SharedMemory);
+    }
+
+    #[test]
+    fn registers_limit_the_active_cubes() {
+        let usage = KernelResourceUsage {
+            registers: Some(128),
+            ..Default::default()
+        };
+        let calculator = OccupancyCalculator::new(&hardware(), usage);
+        let occupancy = calculator.occupancy(CubeDim::new_1d(256));
+
+        assert_eq!(occupancy.active_cubes, 2);
+        assert_eq!(occupancy.limit, OccupancyLimit::Registers);
+        assert_eq!(occupancy.ratio, 0.25);
+    }
+
+    #[test]
+    fn too_much_shared_memory_cant_launch() {
+        let usage = KernelResourceUsage {
+            shared_memory: 64 * 1024,
+            ..Default::default()
+        };
+        let calculator = OccupancyCalculator::new(&hardware(), usage);
+
+        assert_eq!(calculator.occupancy(CubeDim::new_1d(32)).active_cubes, 0);
+        assert!(calculator.cube_dim_candidates(4096).is_empty());
+        assert_eq!(calculator.cube_dim(4096), CubeDim::new_1d(32));
+    }
+
+    #[test]
+    fn candidates_prefer_occupancy_then_size() {
+        let calculator = OccupancyCalculator::new(&hardware(), Default::default());
+        let candidates = calculator.cube_dim_candidates(1 << 20);
+
+        // 128 units and more fill the multiprocessor, 64 units are limited to 16 cubes.
+        assert_eq!(
+            candidates,
+            [
+                CubeDim::new_2d(32, 32),
+                CubeDim::new_2d(32, 16),
+                CubeDim::new_2d(32, 8),
+                CubeDim::new_2d(32, 4),
+                CubeDim::new_2d(32, 2),
+                CubeDim::new_2d(32, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn candidates_are_capped_by_the_work_and_the_kernel() {
+        let usage = KernelResourceUsage {
+            max_units_per_cube: Some(512),
+            ..Default::default()
+        };
+        let calculator = OccupancyCalculator::new(&hardware(), usage);
+
+        assert_eq!(calculator.cube_dim(100), CubeDim::new_2d(32, 4));
+        assert_eq!(calculator.cube_dim(1 << 20), CubeDim::new_2d(32, 16));
+    }
+
+    #[test]
+    fn persistent_cubes_are_capped_by_the_resident_cubes() {
+        let calculator = OccupancyCalculator::new(&hardware(), Default::default());
+        let cube_dim = CubeDim::new_2d(32, 8);
+
+        assert_eq!(calculator.resident_cubes(cube_dim), Some(80));
+        assert_eq!(calculator.persistent_num_cubes(cube_dim, 1 << 20), 80);
+        assert_eq!(calculator.persistent_num_cubes(cube_dim, 1000), 4);
+    }
+
+    #[cfg(all(std_io, feature = "storage-bytes"))]
+    #[test]
+    fn bundled_profiles_report_their_occupancy() {
+        let profile = crate::device_profile::DeviceProfile::bundled("nvidia-a100").unwrap();
+        let hardware = &profile.properties.hardware;
+        let cube_dim = CubeDim::new_2d(32, 8);
+
+        let usage = KernelResourceUsage {
+            shared_memory: 48 * 1024,
+            registers: Some(64),
+            ..Default::default()
+        };
+        let calculator = OccupancyCalculator::new(hardware, usage);
+        let occupancy = calculator.occupancy(cube_dim);
+        assert_eq!(occupancy.active_cubes, 3);
+        assert_eq!(occupancy.limit, OccupancyLimit::SharedMemory);
+        assert_eq!(occupancy.ratio, 0.375);
+        assert_eq!(calculator.resident_cubes(cube_dim), Some(324));
+
+        let usage = KernelResourceUsage {
+            registers: Some(32),
+            ..Default::default()
+        };
+        let calculator = OccupancyCalculator::new(hardware, usage);
+        let occupancy = calculator.occupancy(cube_dim);
+        assert_eq!(occupancy.active_cubes, 8);
+        assert_eq!(occupancy.ratio, 1.0);
+        assert_eq!(calculator.persistent_num_cubes(cube_dim, 1 << 24), 864);
+    }
+}
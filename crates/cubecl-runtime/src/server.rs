@@ -1,7 +1,10 @@
 use crate::{
     client::ComputeClient,
     compiler::CompilationError,
-    kernel::KernelMetadata,
+    fusion::LaunchFusion,
+    graph::{GraphLaunch, LaunchCaptures},
+    id::KernelId,
+    kernel::{KernelMetadata, KernelResourceUsage},
     logging::ServerLogger,
     memory_management::{
         MemoryAllocationMode, MemoryHandle, MemoryUsage,
@@ -9,8 +12,10 @@ use crate::{
     },
     runtime::Runtime,
     storage::{BindingResource, ComputeStorage},
+    stream::{Event, SyncEvent},
     tma::{OobFill, TensorMapFormat, TensorMapInterleave, TensorMapPrefetch, TensorMapSwizzle},
 };
+use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::sync::Arc;
@@ -21,7 +26,7 @@ use cubecl_common::{
     backtrace::BackTrace, bytes::Bytes, device, future::DynFut, profile::ProfileDuration,
     stream_id::StreamId,
 };
-use cubecl_ir::{DeviceProperties, StorageType};
+use cubecl_ir::{ConstantValue, DeviceProperties, StorageType, TextureDim};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -75,6 +80,10 @@ pub struct ServerUtilities<Server: ComputeServer> {
     pub info: Server::Info,
     /// The logger based on global cubecl configs.
     pub logger: Arc<ServerLogger>,
+    /// The launches recorded on streams that are being captured into a launch graph.
+    pub(crate) captures: LaunchCaptures<Server::Kernel>,
+    /// The launches deferred on streams with fusion enabled.
+    pub(crate) fusion: LaunchFusion<Server::Kernel>,
 }
 
 impl<Server: core::fmt::Debug> core::fmt::Debug for ServerUtilities<Server>
@@ -116,6 +125,8 @@ impl<S: ComputeServer> ServerUtilities<S> {
             #[cfg(feature = "profile-tracy")]
             epoch_time: web_time::Instant::now(),
             info,
+            captures: LaunchCaptures::default(),
+            fusion: LaunchFusion::default(),
         }
     }
 }
@@ -181,6 +192,16 @@ pub enum ExecutionError {
         #[cfg_attr(std_io, serde(skip))]
         backtrace: BackTrace,
     },
+
+    /// The launches deferred by fusion couldn't be launched before the operation.
+    #[error("A deferred launch failed\nCaused by:\n  {0}")]
+    Launch(Box<LaunchError>),
+}
+
+impl From<LaunchError> for ExecutionError {
+    fn from(error: LaunchError) -> Self {
+        Self::Launch(Box::new(error))
+    }
 }
 
 /// The compute server is responsible for handling resources and computations over resources.
@@ -290,6 +311,24 @@ where
     /// Wait for the completion of every task in the server.
     fn sync(&mut self, stream_id: StreamId) -> DynFut<Result<(), ExecutionError>>;
 
+    /// Record an event on the stream, marking the completion of every task enqueued on it so far.
+    ///
+    /// Servers without native events fall back to an event that completes when the stream is
+    /// synchronized.
+    fn record_event(&mut self, stream_id: StreamId) -> Result<Event, ExecutionError> {
+        Ok(Event::new(stream_id, SyncEvent::new(self.sync(stream_id))))
+    }
+
+    /// Make the stream wait for the event before executing the tasks enqueued after this call.
+    ///
+    /// Servers without native events block the host until the event is completed instead.
+    fn wait_event(&mut self, event: &Event, stream_id: StreamId) -> Result<(), ExecutionError> {
+        if event.stream_id() == stream_id {
+            return Ok(());
+        }
+        event.wait()
+    }
+
     /// Given a resource handle, returns the storage resource.
     fn get_resource(
         &mut self,
@@ -314,6 +353,33 @@ where
         stream_id: StreamId,
     ) -> Result<(), LaunchError>;
 
+    /// Launch every kernel of a [launch graph](crate::graph::LaunchGraph) in order.
+    ///
+    /// Servers that support native graphs or pre-recorded command lists can override this to
+    /// reduce the per-launch overhead, by default each kernel is launched individually.
+    ///
+    /// # Safety
+    ///
+    /// When executing with mode [ExecutionMode::Unchecked], out-of-bound reads and writes can happen.
+    unsafe fn launch_graph(
+        &mut self,
+        launches: Vec<GraphLaunch<Self::Kernel>>,
+        stream_id: StreamId,
+    ) -> Result<(), LaunchError> {
+        for launch in launches {
+            unsafe {
+                self.launch(
+                    launch.kernel,
+                    launch.count,
+                    launch.bindings,
+                    launch.mode,
+                    stream_id,
+                )?
+            };
+        }
+        Ok(())
+    }
+
     /// Flush all outstanding tasks in the server.
     fn flush(&mut self, stream_id: StreamId);
 
@@ -335,6 +401,13 @@ where
 
     /// Update the memory mode of allocation in the server.
     fn allocation_mode(&mut self, mode: MemoryAllocationMode, stream_id: StreamId);
+
+    /// The resources used by a kernel already compiled by the server, with the driver reported
+    /// fields filled in when available. Returns `None` if the kernel wasn't compiled yet or the
+    /// server doesn't track compiled kernels.
+    fn resource_usage(&mut self, _kernel_id: &KernelId) -> Option<KernelResourceUsage> {
+        None
+    }
 }
 
 /// Defines functions for optimized data transfer between servers, supporting custom communication
@@ -534,7 +607,7 @@ impl Handle {
 }
 
 /// Bindings to execute a kernel.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Bindings {
     /// Buffer bindings
     pub buffers: Vec<Binding>,
@@ -545,6 +618,12 @@ pub struct Bindings {
     pub scalars: BTreeMap<StorageType, ScalarBinding>,
     /// Tensor map bindings
     pub tensor_maps: Vec<TensorMapBinding>,
+    /// Texture bindings
+    pub textures: Vec<TextureBinding>,
+    /// Size in bytes of the dynamic shared memory allocated for each cube at launch.
+    pub dynamic_shared_memory: usize,
+    /// Values of the specialization constants, indexed by id.
+    pub spec_constants: Vec<ConstantValue>,
 }
 
 impl Bindings {
@@ -590,10 +669,28 @@ impl Bindings {
         self.tensor_maps.extend(bindings);
         self
     }
+
+    /// Extend the textures with `bindings`
+    pub fn with_textures(mut self, bindings: Vec<TextureBinding>) -> Self {
+        self.textures.extend(bindings);
+        self
+    }
+
+    /// Set the size in bytes of the dynamic shared memory allocated for each cube
+    pub fn with_dynamic_shared_memory(mut self, size: usize) -> Self {
+        self.dynamic_shared_memory = size;
+        self
+    }
+
+    /// Set the values of the specialization constants, indexed by id
+    pub fn with_spec_constants(mut self, values: Vec<ConstantValue>) -> Self {
+        self.spec_constants = values;
+        self
+    }
 }
 
 /// Binding of a set of scalars of the same type to execute a kernel.
-#[derive(new, Debug, Default)]
+#[derive(new, Debug, Default, Clone)]
 pub struct MetadataBinding {
     /// Metadata values
     pub data: Vec<u64>,
@@ -691,6 +788,29 @@ pub struct TensorMapMeta {
     pub storage_ty: StorageType,
 }
 
+/// A texture sampled or loaded from by a kernel
+#[derive(new, Debug, Clone)]
+pub struct TextureBinding {
+    /// The binding for the backing texels
+    pub binding: Binding,
+    /// The texture metadata
+    pub meta: TextureMeta,
+}
+
+/// Layout of the texels backing a texture. Texels are contiguous, row-major, with interleaved
+/// channels.
+#[derive(Debug, Clone)]
+pub struct TextureMeta {
+    /// Dimensionality of the texture
+    pub dim: TextureDim,
+    /// Width, height and depth in texels. Depth is `1` for 2D textures.
+    pub extent: [usize; 3],
+    /// Number of channels per texel (1, 2 or 4)
+    pub channels: usize,
+    /// Storage type of each channel
+    pub storage_ty: StorageType,
+}
+
 impl Handle {
     /// If the tensor handle can be reused inplace.
     pub fn can_mut(&self) -> bool {
@@ -777,7 +897,12 @@ pub enum CubeCountSelection {
 impl CubeCountSelection {
     /// Creates a [CubeCount] while respecting the hardware limits.
     pub fn new<R: Runtime>(client: &ComputeClient<R>, num_cubes: u32) -> Self {
-        let cube_count = cube_count_spread(&client.properties().hardware.max_cube_count, num_cubes);
+        Self::from_limits(&client.properties().hardware.max_cube_count, num_cubes)
+    }
+
+    /// Creates a [CubeCount] while respecting the given maximum cube count.
+    pub fn from_limits(max_cube_count: &(u32, u32, u32), num_cubes: u32) -> Self {
+        let cube_count = cube_count_spread(max_cube_count, num_cubes);
 
         let num_cubes_actual = cube_count[0] * cube_count[1] * cube_count[2];
         let cube_count = CubeCount::Static(cube_count[0], cube_count[1], cube_count[2]);
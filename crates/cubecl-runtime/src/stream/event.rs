@@ -3,12 +3,15 @@ use crate::{
     logging::ServerLogger,
     memory_management::SliceId,
     server::{Binding, ExecutionError},
-    stream::{StreamFactory, StreamPool},
+    stream::{ServerEvent, StreamFactory, StreamPool},
+};
+use core::{
+    any::Any,
+    sync::atomic::{AtomicU64, Ordering},
 };
-use core::any::Any;
 use cubecl_common::stream_id::StreamId;
 use hashbrown::HashMap;
-use std::sync::{Arc, mpsc::SyncSender};
+use std::sync::{Arc, Mutex, mpsc::SyncSender};
 
 /// Trait defining the backend operations for managing streams and events.
 ///
@@ -29,6 +32,13 @@ pub trait EventStreamBackend: 'static {
     fn wait_event(stream: &mut Self::Stream, event: Self::Event);
     /// Wait for the given event synching the CPU.
     fn wait_event_sync(event: Self::Event) -> Result<(), ExecutionError>;
+    /// Makes the stream wait for the specified event, without consuming it so it can be waited on
+    /// again.
+    fn wait_event_ref(stream: &mut Self::Stream, event: &Self::Event);
+    /// Wait for the given event synching the CPU, without consuming it.
+    fn wait_event_ref_sync(event: &Self::Event) -> Result<(), ExecutionError>;
+    /// Returns whether the given event is reached, without synching the CPU.
+    fn is_event_reached(event: &Self::Event) -> Result<bool, ExecutionError>;
 }
 
 /// Manages multiple streams with synchronization logic based on shared bindings.
@@ -39,6 +49,8 @@ pub trait EventStreamBackend: 'static {
 pub struct MultiStream<B: EventStreamBackend> {
     /// The map of stream IDs to their corresponding stream wrappers.
     streams: StreamPool<EventStreamBackendWrapper<B>>,
+    /// Unique id used to recognize the events recorded by this multi-stream.
+    id: u64,
     /// The logger used by the server.
     pub logger: Arc<ServerLogger>,
     max_streams: usize,
@@ -89,6 +101,55 @@ impl<B: EventStreamBackend> GcTask<B> {
     }
 }
 
+/// An event recorded on a stream of a [MultiStream], that can be exposed to users.
+///
+/// Waiting on the event from another stream of the same [MultiStream] also marks the origin
+/// stream as synced, so shared bindings don't trigger a redundant synchronization.
+pub struct MultiStreamEvent<B: EventStreamBackend> {
+    /// Always `Some` until the event is dropped.
+    event: Mutex<Option<B::Event>>,
+    owner: u64,
+    origin: usize,
+    cursor: u64,
+    gc: SyncSender<GcTask<B>>,
+}
+
+static MULTI_STREAM_COUNT: AtomicU64 = AtomicU64::new(0);
+
+impl<B: EventStreamBackend> ServerEvent for MultiStreamEvent<B> {
+    fn query(&self) -> Result<bool, ExecutionError> {
+        let event = self.event.lock().unwrap();
+        B::is_event_reached(event.as_ref().unwrap())
+    }
+
+    fn wait(&self) -> Result<(), ExecutionError> {
+        let event = self.event.lock().unwrap();
+        B::wait_event_ref_sync(event.as_ref().unwrap())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl<B: EventStreamBackend> Drop for MultiStreamEvent<B> {
+    fn drop(&mut self) {
+        // The backend event is released by the gc once it's reached.
+        if let Some(event) = self.event.get_mut().unwrap().take() {
+            self.gc.send(GcTask::new((), event)).ok();
+        }
+    }
+}
+
+impl<B: EventStreamBackend> core::fmt::Debug for MultiStreamEvent<B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MultiStreamEvent")
+            .field("origin", &self.origin)
+            .field("cursor", &self.cursor)
+            .finish()
+    }
+}
+
 #[derive(Debug)]
 struct EventStreamBackendWrapper<B: EventStreamBackend> {
     backend: B,
@@ -181,6 +242,7 @@ impl<B: EventStreamBackend> MultiStream<B> {
         let wrapper = EventStreamBackendWrapper { backend };
         Self {
             streams: StreamPool::new(wrapper, max_streams, 1),
+            id: MULTI_STREAM_COUNT.fetch_add(1, Ordering::Relaxed),
             logger,
             max_streams: max_streams as usize,
             gc: GcThread::new(),
@@ -192,6 +254,46 @@ impl<B: EventStreamBackend> MultiStream<B> {
         self.gc.sender.send(gc).unwrap();
     }
 
+    /// Records an event on the given stream, marking the completion of every task enqueued on it
+    /// so far.
+    pub fn record_event(&mut self, stream_id: StreamId) -> MultiStreamEvent<B> {
+        let stream = self.streams.get_mut(&stream_id);
+        let event = B::flush(&mut stream.stream);
+
+        MultiStreamEvent {
+            event: Mutex::new(Some(event)),
+            owner: self.id,
+            origin: stream_index(&stream_id, self.max_streams),
+            cursor: stream.cursor,
+            gc: self.gc.sender.clone(),
+        }
+    }
+
+    /// Makes the given stream wait for an event, without synching the CPU.
+    pub fn wait_event(&mut self, stream_id: StreamId, event: &MultiStreamEvent<B>) {
+        let index = stream_index(&stream_id, self.max_streams);
+        let owned = event.owner == self.id;
+        let stream = self.streams.get_mut(&stream_id);
+
+        if owned {
+            if index == event.origin {
+                return;
+            }
+            if let Some(last_synced) = stream.last_synced.get(&event.origin)
+                && *last_synced >= event.cursor
+            {
+                return;
+            }
+        }
+
+        let inner = event.event.lock().unwrap();
+        B::wait_event_ref(&mut stream.stream, inner.as_ref().unwrap());
+
+        if owned {
+            stream.last_synced.insert(event.origin, event.cursor);
+        }
+    }
+
     /// Resolves and returns a mutable reference to the stream for the given ID, performing any necessary
     /// alignment based on the provided bindings.
     ///
@@ -439,6 +541,44 @@ mod tests {
         assert_eq!(stream2.cursor, 1);
     }
 
+    #[test]
+    fn test_wait_event_marks_synced() {
+        let logger = Arc::new(ServerLogger::default());
+        let stream_1 = StreamId { value: 1 };
+        let stream_2 = StreamId { value: 2 };
+
+        let mut ms = MultiStream::new(logger, TestBackend, MAX_STREAMS);
+        ms.resolve(stream_1, [].into_iter());
+        ms.resolve(stream_2, [].into_iter());
+
+        let event = ms.record_event(stream_2);
+        ms.wait_event(stream_1, &event);
+
+        let binding_2 = binding(stream_2);
+        let analysis = ms.update_shared_bindings(stream_1, [&binding_2].into_iter());
+
+        assert_eq!(analysis, SharedBindingAnalysis::default());
+        assert!(event.query().unwrap());
+    }
+
+    #[test]
+    fn test_wait_event_other_multi_stream() {
+        let logger = Arc::new(ServerLogger::default());
+        let stream_1 = StreamId { value: 1 };
+        let stream_2 = StreamId { value: 2 };
+
+        let mut ms = MultiStream::new(logger.clone(), TestBackend, MAX_STREAMS);
+        let mut other = MultiStream::new(logger, TestBackend, MAX_STREAMS);
+        ms.resolve(stream_1, [].into_iter());
+        other.resolve(stream_2, [].into_iter());
+
+        let event = other.record_event(stream_2);
+        ms.wait_event(stream_1, &event);
+
+        let stream1 = ms.streams.get_mut(&stream_1);
+        assert!(stream1.last_synced.is_empty());
+    }
+
     fn binding(stream: StreamId) -> Binding {
         Handle::new(SliceHandle::new(), None, None, stream, 0, 10).binding()
     }
@@ -468,5 +608,15 @@ mod tests {
         fn wait_event_sync(_event: Self::Event) -> Result<(), ExecutionError> {
             Ok(())
         }
+
+        fn wait_event_ref(_stream: &mut Self::Stream, _event: &Self::Event) {}
+
+        fn wait_event_ref_sync(_event: &Self::Event) -> Result<(), ExecutionError> {
+            Ok(())
+        }
+
+        fn is_event_reached(_event: &Self::Event) -> Result<bool, ExecutionError> {
+            Ok(true)
+        }
     }
 }
@@ -0,0 +1,120 @@
+use crate::server::ExecutionError;
+use alloc::sync::Arc;
+use core::{
+    any::Any,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use cubecl_common::{future::DynFut, stream_id::StreamId, stub::Mutex};
+
+/// A backend event recorded on a stream, which marks the completion of every task enqueued on
+/// that stream before it was recorded.
+pub trait ServerEvent: Any + Send + Sync + core::fmt::Debug {
+    /// Returns whether every task captured by the event is completed, without blocking.
+    fn query(&self) -> Result<bool, ExecutionError>;
+    /// Blocks the current thread until every task captured by the event is completed.
+    fn wait(&self) -> Result<(), ExecutionError>;
+    /// Get the event as [Any], so the server that recorded it can retrieve its concrete type.
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// An event recorded on a stream with
+/// [ComputeClient::record_event](crate::client::ComputeClient::record_event).
+///
+/// Other streams can wait on the event without blocking the host with
+/// [ComputeClient::wait_event](crate::client::ComputeClient::wait_event), and the host can query
+/// or wait for its completion directly. Events can be cloned and waited on any number of times.
+#[derive(Clone, Debug)]
+pub struct Event {
+    stream_id: StreamId,
+    inner: Arc<dyn ServerEvent>,
+}
+
+impl Event {
+    /// Create a new event recorded on the given stream.
+    pub fn new(stream_id: StreamId, event: impl ServerEvent) -> Self {
+        Self {
+            stream_id,
+            inner: Arc::new(event),
+        }
+    }
+
+    /// The stream the event was recorded on.
+    pub fn stream_id(&self) -> StreamId {
+        self.stream_id
+    }
+
+    /// Returns whether every task captured by the event is completed, without blocking.
+    pub fn query(&self) -> Result<bool, ExecutionError> {
+        self.inner.query()
+    }
+
+    /// Blocks the current thread until every task captured by the event is completed.
+    pub fn wait(&self) -> Result<(), ExecutionError> {
+        self.inner.wait()
+    }
+
+    /// Get the backend event, if it is of type `E`.
+    pub fn server_event<E: ServerEvent>(&self) -> Option<&E> {
+        self.inner.as_any().downcast_ref()
+    }
+}
+
+/// An event backed by the future returned when synchronizing a stream, for servers that don't
+/// support native events.
+pub struct SyncEvent {
+    state: Mutex<SyncEventState>,
+}
+
+enum SyncEventState {
+    Pending(DynFut<Result<(), ExecutionError>>),
+    Done(Result<(), ExecutionError>),
+}
+
+impl SyncEvent {
+    /// Create a new event that completes when the given synchronization future resolves.
+    pub fn new(sync: DynFut<Result<(), ExecutionError>>) -> Self {
+        Self {
+            state: Mutex::new(SyncEventState::Pending(sync)),
+        }
+    }
+}
+
+impl core::fmt::Debug for SyncEvent {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SyncEvent").finish()
+    }
+}
+
+impl ServerEvent for SyncEvent {
+    fn query(&self) -> Result<bool, ExecutionError> {
+        let mut state = self.state.lock().unwrap();
+        if let SyncEventState::Pending(fut) = &mut *state {
+            let mut cx = Context::from_waker(Waker::noop());
+            match Pin::new(fut).poll(&mut cx) {
+                Poll::Ready(result) => *state = SyncEventState::Done(result),
+                Poll::Pending => return Ok(false),
+            }
+        }
+        match &*state {
+            SyncEventState::Done(result) => result.clone().map(|_| true),
+            SyncEventState::Pending(_) => unreachable!(),
+        }
+    }
+
+    fn wait(&self) -> Result<(), ExecutionError> {
+        let mut state = self.state.lock().unwrap();
+        if let SyncEventState::Pending(fut) = &mut *state {
+            let result = cubecl_common::reader::read_sync(fut);
+            *state = SyncEventState::Done(result);
+        }
+        match &*state {
+            SyncEventState::Done(result) => result.clone(),
+            SyncEventState::Pending(_) => unreachable!(),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -2,11 +2,13 @@
 pub mod scheduler;
 
 mod base;
+mod handle;
 
 #[cfg(multi_threading)]
 mod event;
 
 pub use base::*;
+pub use handle::*;
 
 #[cfg(multi_threading)]
 pub use event::*;
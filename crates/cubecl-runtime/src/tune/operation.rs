@@ -1,4 +1,5 @@
 use alloc::boxed::Box;
+use alloc::format;
 use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
@@ -11,6 +12,7 @@ use super::{
     key_generator::{IntoKeyGenerator, KeyGenerator},
 };
 use super::{Tunable, TunePlan};
+use crate::server::CubeDim;
 
 /// Default checksum for an operation set
 #[cfg(std_io)]
@@ -66,6 +68,25 @@ impl<K: AutotuneKey, Inputs: Clone + Send + 'static, Output: 'static>
         self
     }
 
+    /// Register one tunable per candidate [cube dim](CubeDim), created with `tunable`.
+    ///
+    /// The candidates are usually the best ones suggested by an
+    /// [occupancy calculator](crate::occupancy::OccupancyCalculator::cube_dim_candidates), and
+    /// are tuned in order. Each tunable is named after `name` and its cube dim.
+    pub fn with_cube_dim_candidates<Marker, F: IntoTuneFn<Inputs, Output, Marker>>(
+        mut self,
+        name: &str,
+        candidates: impl IntoIterator<Item = CubeDim>,
+        tunable: impl Fn(CubeDim) -> F,
+    ) -> Self {
+        for cube_dim in candidates {
+            let CubeDim { x, y, z } = cube_dim;
+            let name = format!("{name}<{x}x{y}x{z}>");
+            self.tunables.push(Tunable::new(name, tunable(cube_dim)));
+        }
+        self
+    }
+
     /// Override the checksum algorithm
     pub fn with_custom_checksum(
         mut self,
@@ -113,7 +113,10 @@ impl<K: AutotuneKey> TuneCache<K> {
                 in_memory_cache: HashMap::new(),
                 persistent_cache: Cache::new(
                     format!("{device_id}/{name}"),
-                    options.root(root).name("autotune"),
+                    options
+                        .root(root)
+                        .name("autotune")
+                        .format(cubecl_common::cache::CacheFormat::Binary),
                 ),
             };
             cache.load();
@@ -64,7 +64,7 @@ pub fn test_client(device: &DummyDevice) -> DummyClient {
     ComputeClient::load(device)
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct DummyCompiler;
 
 impl Compiler for DummyCompiler {
@@ -70,6 +70,7 @@ impl CubeTask<DummyCompiler> for KernelTask {
             repr: Some(self.clone()),
             cube_dim: CubeDim::new_single(),
             debug_info: None,
+            resource_usage: Default::default(),
         })
     }
 }
@@ -271,6 +272,10 @@ impl DummyServer {
             max_units_per_cube: 1024,
             max_cube_dim: (1024, 1024, 64),
             num_streaming_multiprocessors: None,
+            max_cubes_per_multiprocessor: None,
+            max_units_per_multiprocessor: None,
+            max_shared_memory_per_multiprocessor: None,
+            registers_per_multiprocessor: None,
             num_tensor_cores: None,
             min_tensor_cores_dim: None,
             num_cpu_cores: None,
@@ -98,3 +98,53 @@ fn autotune_basic_multiplication_execution() {
     // If slow kernel was selected it would output [0, 1, 2]
     assert_eq!(obtained_resource, Vec::from([0, 4, 8]));
 }
+
+#[test]
+#[cfg(feature = "std")]
+fn bundled_device_profiles_round_trip() {
+    use cubecl_runtime::device_profile::DeviceProfile;
+
+    for name in DeviceProfile::bundled_names() {
+        let profile = DeviceProfile::bundled(name).unwrap();
+
+        assert_eq!(
+            DeviceProfile::from_toml(&profile.to_toml()).unwrap(),
+            profile
+        );
+        assert_eq!(
+            DeviceProfile::from_json(&profile.to_json()).unwrap(),
+            profile
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn compile_only_client_compiles_without_executing() {
+    use cubecl_runtime::device_profile::{CompileOnly, DeviceProfile, ProfileDevice};
+
+    let profile = DeviceProfile::bundled("nvidia-h100").unwrap();
+    let client =
+        CompileOnly::<DummyRuntime>::client_from_profile(&ProfileDevice { index: 0 }, &profile, ());
+    assert_eq!(client.properties(), &profile.properties);
+
+    let lhs = client.create_from_slice(&[0, 1, 2]);
+    let rhs = client.create_from_slice(&[4, 4, 4]);
+    let out = client.create_from_slice(&[0, 0, 0]);
+
+    client
+        .launch(
+            Box::new(KernelTask::new(DummyElementwiseAddition)),
+            CubeCount::Static(1, 1, 1),
+            Bindings::new().with_buffers(vec![lhs.binding(), rhs.binding(), out.clone().binding()]),
+        )
+        .unwrap();
+
+    let compiled = client.info().compiled();
+    assert_eq!(compiled.len(), 1);
+    assert_eq!(
+        compiled[0].entrypoint_name,
+        core::any::type_name::<DummyElementwiseAddition>()
+    );
+    assert_eq!(client.read_one(out).to_vec(), Vec::from([0, 0, 0]));
+}
@@ -156,10 +156,16 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
                 continue_target,
                 merge,
             } => self.compile_loop_break(break_cond, body, continue_target, merge),
-            ControlFlow::Return => {
+            ControlFlow::Return { value: None } => {
                 self.ret().unwrap();
                 self.current_block = None;
             }
+            ControlFlow::Return { value: Some(value) } => {
+                let value = self.compile_variable(value);
+                let value = self.read(&value);
+                self.ret_value(value).unwrap();
+                self.current_block = None;
+            }
             ControlFlow::None => {
                 let opt = self.opt.clone();
                 let children = opt.successors(self.current_block.unwrap());
@@ -237,6 +243,7 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
     }
 
     fn compile_loop(&mut self, body: NodeIndex, continue_target: NodeIndex, merge: NodeIndex) {
+        let header = self.current_block.unwrap();
         let body_label = self.label(body);
         let continue_label = self.label(continue_target);
         let merge_label = self.label(merge);
@@ -245,7 +252,7 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
             .unwrap();
         self.branch(body_label).unwrap();
         self.compile_block(body);
-        self.compile_block(continue_target);
+        self.compile_continue_target(continue_target, header);
         self.compile_block(merge);
     }
 
@@ -256,6 +263,7 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
         continue_target: NodeIndex,
         merge: NodeIndex,
     ) {
+        let header = self.current_block.unwrap();
         let break_cond = self.compile_variable(break_cond);
         let cond_id = self.read(&break_cond);
         let body_label = self.label(body);
@@ -267,7 +275,23 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
         self.branch_conditional(cond_id, body_label, merge_label, [])
             .unwrap();
         self.compile_block(body);
-        self.compile_block(continue_target);
+        self.compile_continue_target(continue_target, header);
         self.compile_block(merge);
     }
+
+    /// The continue target of a loop that never loops back is unreachable and has no edges, but
+    /// structured control flow still requires it to branch back to the header.
+    fn compile_continue_target(&mut self, continue_target: NodeIndex, header: NodeIndex) {
+        if !self.opt.successors(continue_target).is_empty() {
+            self.compile_block(continue_target);
+            return;
+        }
+
+        if self.visited.insert(continue_target) {
+            let continue_label = self.label(continue_target);
+            let header_label = self.label(header);
+            self.begin_block(Some(continue_label)).unwrap();
+            self.branch(header_label).unwrap();
+        }
+    }
 }
@@ -11,8 +11,9 @@ use cubecl_core::{
     Compiler, CubeDim, Metadata, WgpuCompilationOptions,
     ir::{self as core, ElemType, InstructionModes, StorageType, UIntKind, features::EnumSet},
     post_processing::{
-        checked_io::CheckedIoProcessor, saturating::SaturatingArithmeticProcessor,
-        unroll::UnrollProcessor,
+        atomic::AtomicPolyfillProcessor, checked_io::CheckedIoProcessor, df64::Df64EmulationProcessor,
+        dynamic_shared_memory::DynamicSharedMemoryProcessor, int64::Int64EmulationProcessor,
+        saturating::SaturatingArithmeticProcessor, unroll::UnrollProcessor,
     },
     prelude::{FastMath, KernelDefinition},
     server::ExecutionMode,
@@ -21,10 +22,13 @@ use cubecl_opt::{BasicBlock, NodeIndex, Optimizer, OptimizerBuilder, SharedLiven
 use cubecl_runtime::{
     compiler::CompilationError,
     config::{GlobalConfig, compilation::CompilationLogLevel},
+    kernel::KernelResourceUsage,
 };
 use rspirv::{
     dr::{Builder, InsertPoint, Instruction, Module, Operand},
-    spirv::{BuiltIn, Capability, Decoration, FPFastMathMode, Op, StorageClass, Word},
+    spirv::{
+        BuiltIn, Capability, Decoration, FPFastMathMode, FunctionControl, Op, StorageClass, Word,
+    },
 };
 use std::{
     collections::HashSet,
@@ -59,6 +63,10 @@ pub struct SpirvCompiler<Target: SpirvTarget = GLCompute> {
     pub metadata: Metadata,
     pub debug_info: Option<DebugInfo>,
     pub compilation_options: WgpuCompilationOptions,
+    /// Whether 64-bit integers are lowered to their 32-bit words, see `Int64EmulationProcessor`.
+    pub int64_emulation: bool,
+    /// The features of the device, used to declare polyfilled atomics as their integer type.
+    pub features: Option<core::features::Features>,
 }
 
 unsafe impl<T: SpirvTarget> Send for SpirvCompiler<T> {}
@@ -87,10 +95,31 @@ impl<T: SpirvTarget> Clone for SpirvCompiler<T> {
             debug_info: self.debug_info.clone(),
             ext_meta_pos: self.ext_meta_pos.clone(),
             compilation_options: self.compilation_options.clone(),
+            int64_emulation: self.int64_emulation,
+            features: self.features.clone(),
         }
     }
 }
 
+/// Fails with the validation errors pushed to the kernel, if any.
+fn validate(body: &mut core::Scope) -> Result<(), CompilationError> {
+    let errors = body.pop_errors();
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    let mut reason = "Can't compile spirv kernel".to_string();
+    for error in errors {
+        reason += error.as_str();
+        reason += "\n";
+    }
+
+    Err(CompilationError::Validation {
+        reason,
+        backtrace: BackTrace::capture(),
+    })
+}
+
 fn debug_symbols_activated() -> bool {
     matches!(
         GlobalConfig::get().compilation.logger.level,
@@ -121,6 +150,8 @@ impl<T: SpirvTarget> Default for SpirvCompiler<T> {
             debug_info: Default::default(),
             ext_meta_pos: Default::default(),
             compilation_options: Default::default(),
+            int64_emulation: false,
+            features: None,
         }
     }
 }
@@ -150,21 +181,21 @@ impl<T: SpirvTarget> Compiler for SpirvCompiler<T> {
         mode: ExecutionMode,
         addr_type: StorageType,
     ) -> Result<Self::Representation, CompilationError> {
-        let errors = value.body.pop_errors();
-        if !errors.is_empty() {
-            let mut reason = "Can't compile spirv kernel".to_string();
-            for error in errors {
-                reason += error.as_str();
-                reason += "\n";
-            }
-
-            return Err(CompilationError::Validation {
-                reason,
-                backtrace: BackTrace::capture(),
-            });
-        }
+        validate(&mut value.body)?;
+
+        self.int64_emulation = value
+            .body
+            .properties
+            .as_ref()
+            .is_some_and(|properties| properties.features.int64_emulation);
+        self.features = value
+            .body
+            .properties
+            .as_ref()
+            .map(|properties| properties.features.clone());
 
         let bindings = value.buffers.clone();
+        let textures = value.textures.iter().map(|texture| texture.dim).collect();
         let scalars = value
             .scalars
             .iter()
@@ -197,16 +228,34 @@ impl<T: SpirvTarget> Compiler for SpirvCompiler<T> {
         self.compilation_options = compilation_options.clone();
         self.ext_meta_pos = ext_meta_pos;
 
-        let (module, optimizer) = self.compile_kernel(value);
+        let (module, optimizer) = self.compile_kernel(value)?;
+        let allocations = self.shared_liveness.allocations.values();
+        let shared_memory_size = allocations
+            .map(|alloc| alloc.offset + alloc.smem.size())
+            .max()
+            .unwrap_or_default();
+
         Ok(SpirvKernel {
             module,
             optimizer,
             bindings,
             scalars,
+            textures,
+            spec_constants: self.state.spec_constants.clone(),
             has_metadata: self.metadata.static_len() > 0,
+            shared_memory_size,
+            local_array_sizes: take(&mut self.state.local_array_sizes),
         })
     }
 
+    fn resource_usage(&self, kernel: &Self::Representation) -> KernelResourceUsage {
+        KernelResourceUsage {
+            shared_memory: kernel.shared_memory_size,
+            local_arrays: kernel.local_array_sizes.clone(),
+            ..Default::default()
+        }
+    }
+
     fn elem_size(&self, elem: core::ElemType) -> usize {
         elem.size()
     }
@@ -223,7 +272,10 @@ impl<Target: SpirvTarget> Debug for SpirvCompiler<Target> {
 }
 
 impl<Target: SpirvTarget> SpirvCompiler<Target> {
-    pub fn compile_kernel(&mut self, kernel: KernelDefinition) -> (Module, Optimizer) {
+    pub fn compile_kernel(
+        &mut self,
+        mut kernel: KernelDefinition,
+    ) -> Result<(Module, Optimizer), CompilationError> {
         let options = kernel.options.clone();
 
         self.debug_symbols = debug_symbols_activated() || options.debug_symbols;
@@ -232,15 +284,31 @@ impl<Target: SpirvTarget> SpirvCompiler<Target> {
 
         let mut target = self.target.clone();
 
-        let mut opt = OptimizerBuilder::default()
+        let mut builder = OptimizerBuilder::default()
             .with_transformer(ErfTransform)
             .with_transformer(BitwiseTransform)
             .with_transformer(HypotTransform)
             .with_transformer(RhypotTransform)
-            .with_processor(CheckedIoProcessor::new(self.mode))
             .with_processor(UnrollProcessor::new(MAX_VECTORIZATION))
-            .with_processor(SaturatingArithmeticProcessor::new(true))
-            .optimize(kernel.body.clone(), kernel.cube_dim);
+            .with_processor(SaturatingArithmeticProcessor::new(true));
+        if let Some(size) = kernel.dynamic_shared_memory {
+            builder = builder.with_processor(DynamicSharedMemoryProcessor::new(&kernel.body, size));
+        }
+        // Lowered 64-bit lines can be twice as wide, so they're unrolled again before bounds
+        // checks move the accesses to nested scopes. `df64` is never native in SPIR-V.
+        builder = builder.with_processor(Df64EmulationProcessor::new());
+        if self.int64_emulation {
+            builder = builder.with_processor(Int64EmulationProcessor::new());
+        }
+        builder = builder
+            .with_processor(UnrollProcessor::new(MAX_VECTORIZATION))
+            .with_processor(CheckedIoProcessor::new(self.mode));
+        if let Some(properties) = kernel.body.properties.clone() {
+            builder = builder.with_processor(AtomicPolyfillProcessor::new(properties));
+        }
+        let mut opt = builder.optimize(kernel.body.clone(), kernel.cube_dim);
+        // Processors can reject instructions they can't lower.
+        validate(&mut kernel.body)?;
 
         self.uniformity = opt.analysis::<Uniformity>();
         self.shared_liveness = opt.analysis::<SharedLiveness>();
@@ -253,8 +321,43 @@ impl<Target: SpirvTarget> SpirvCompiler<Target> {
 
         target.set_kernel_name(options.kernel_name.clone());
 
+        let functions = kernel.body.functions.borrow().clone();
+        for function in functions.iter() {
+            let id = self.id();
+            self.debug_name(id, function.name.as_str());
+            self.state.functions.insert(function.name.clone(), id);
+        }
+
         let (main, debug_setup) = self.declare_main(&options.kernel_name);
+        self.compile_body(debug_setup);
+
+        for function in functions {
+            self.compile_function(function);
+        }
+
+        self.declare_shared_memories();
 
+        let builtins = self
+            .state
+            .used_builtins
+            .clone()
+            .into_iter()
+            .map(|(builtin, (id, item))| {
+                let ty = Item::Pointer(StorageClass::Input, Box::new(item)).id(self);
+                self.variable(ty, Some(id), StorageClass::Input, None);
+                self.decorate(id, Decoration::BuiltIn, vec![builtin.into()]);
+                id
+            })
+            .collect::<Vec<_>>();
+
+        target.set_modes(self, main, builtins, cube_dims);
+
+        let module = take(&mut self.builder).module();
+        Ok((module, self.opt.as_ref().clone()))
+    }
+
+    /// Compile the blocks of the current optimizer into the function that was just declared.
+    fn compile_body(&mut self, debug_setup: impl Fn(&mut Self)) {
         let setup = self.id();
         self.debug_name(setup, "setup");
 
@@ -276,26 +379,62 @@ impl<Target: SpirvTarget> SpirvCompiler<Target> {
         self.branch(body).unwrap();
 
         self.end_function().unwrap();
+    }
 
-        self.declare_shared_memories();
+    /// Compile a non-inlined device function. Block, variable and builtin lookups are local to
+    /// each function, so they're reset before compiling the body.
+    fn compile_function(&mut self, mut function: core::Function) {
+        // Debug info is only tracked for the entry point
+        function.body.debug.entry_loc = None;
+        if let Some(debug_info) = &mut self.debug_info {
+            debug_info.previous_loc = None;
+        }
+        let id = self.state.functions[&function.name];
+        let params = function.params.clone();
+        let out = function.out;
 
-        let builtins = self
-            .state
-            .used_builtins
-            .clone()
-            .into_iter()
-            .map(|(builtin, (id, item))| {
-                let ty = Item::Pointer(StorageClass::Input, Box::new(item)).id(self);
-                self.variable(ty, Some(id), StorageClass::Input, None);
-                self.decorate(id, Decoration::BuiltIn, vec![builtin.into()]);
-                id
-            })
+        let mut opt = self.opt.function(function);
+        self.uniformity = opt.analysis::<Uniformity>();
+        self.opt = Rc::new(opt);
+        self.visited.clear();
+        self.current_block = None;
+
+        let state = &mut self.state;
+        state.bindings.clear();
+        state.variables.clear();
+        state.versioned.clear();
+        state.labels.clear();
+        state.end_labels.clear();
+        state.loops.clear();
+        state.slices.clear();
+        state.local_arrays.clear();
+        state.matrices.clear();
+        state.const_arrays.clear();
+        state.globals.clear();
+        state.loaded_builtins.clear();
+
+        let ret_ty = match out {
+            Some(out) => self.compile_type(out.ty).id(self),
+            None => self.type_void(),
+        };
+        let param_tys = params
+            .iter()
+            .map(|param| self.compile_type(param.ty).id(self))
             .collect::<Vec<_>>();
+        let fn_ty = self.type_function(ret_ty, param_tys.clone());
+        self.begin_function(ret_ty, Some(id), FunctionControl::DONT_INLINE, fn_ty)
+            .unwrap();
+
+        for (param, ty) in params.into_iter().zip(param_tys) {
+            let word = self.function_parameter(ty).unwrap();
+            let core::VariableKind::LocalConst { id } = param.kind else {
+                unreachable!("Function parameters are always local constants");
+            };
+            self.debug_var_name(word, param);
+            self.merge_binding(id, word);
+        }
 
-        target.set_modes(self, main, builtins, cube_dims);
-
-        let module = take(&mut self.builder).module();
-        (module, self.opt.as_ref().clone())
+        self.compile_body(|_| {});
     }
 
     fn setup(&mut self, label: Word, debug_setup: impl Fn(&mut Self)) -> usize {
@@ -49,7 +49,7 @@ pub struct DebugInfo {
 
     stack: Vec<FunctionCall>,
     definitions: Definitions,
-    previous_loc: Option<SourceLoc>,
+    pub(crate) previous_loc: Option<SourceLoc>,
 }
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -1,6 +1,6 @@
 use cubecl_core::ir::{
-    self as core, BinaryOperator, Comparison, Instruction, InstructionModes, Operation, Operator,
-    UnaryOperator,
+    self as core, BinaryOperator, Comparison, FunctionCall, Instruction, InstructionModes,
+    Operation, Operator, UnaryOperator,
 };
 use rspirv::spirv::{Capability, Decoration, Word};
 
@@ -47,7 +47,35 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
             Operation::NonSemantic(debug) => self.compile_debug(debug),
             Operation::Barrier(_) => panic!("Barrier not supported in SPIR-V"),
             Operation::Tma(_) => panic!("TMA not supported in SPIR-V"),
+            Operation::Texture(texture) => self.compile_texture(texture, inst.out),
             Operation::Marker(_) => {}
+            Operation::Call(call) => self.compile_call(call, inst.out),
+        }
+    }
+
+    fn compile_call(&mut self, call: FunctionCall, out: Option<core::Variable>) {
+        let function = self.state.functions[&call.name];
+        let args = call
+            .args
+            .into_iter()
+            .map(|arg| {
+                let arg = self.compile_variable(arg);
+                self.read(&arg)
+            })
+            .collect::<Vec<_>>();
+        match out {
+            Some(out) => {
+                let out = self.compile_variable(out);
+                let ty = out.item().id(self);
+                let out_id = self.write_id(&out);
+                self.function_call(ty, Some(out_id), function, args)
+                    .unwrap();
+                self.write(&out, out_id);
+            }
+            None => {
+                let void = self.type_void();
+                self.function_call(void, None, function, args).unwrap();
+            }
         }
     }
 
@@ -252,6 +280,14 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
                     b.logical_not(ty, Some(out), input).unwrap();
                 });
             }
+            // Polyfilled atomics are declared as the integer they're viewed as, so the pointer is
+            // reused as is.
+            Operator::Reinterpret(op) if out.ty.is_atomic() => {
+                let input = self.compile_variable(op.input);
+                let out = self.compile_variable(out);
+                let ptr = input.id(self);
+                self.merge_binding(out.as_binding().unwrap(), ptr);
+            }
             Operator::Reinterpret(op) => {
                 self.compile_unary_op(op, out, uniform, |b, _, ty, input, out| {
                     b.bitcast(ty, Some(out), input).unwrap();
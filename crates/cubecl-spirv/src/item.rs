@@ -1,4 +1,5 @@
 use cubecl_core::ir::{self as core, FloatKind, IntKind, UIntKind};
+use cubecl_core::post_processing::{df64, int64};
 use rspirv::spirv::{Capability, CooperativeMatrixUse, FPEncoding, Scope, StorageClass, Word};
 
 use crate::{compiler::SpirvCompiler, target::SpirvTarget, variable::ConstVal};
@@ -307,6 +308,11 @@ impl Elem {
 
 impl<T: SpirvTarget> SpirvCompiler<T> {
     pub fn compile_type(&mut self, item: core::Type) -> Item {
+        let item = match self.int64_emulation {
+            true => int64::lower_type(item),
+            false => item,
+        };
+        let item = df64::lower_type(item);
         match item {
             core::Type::Scalar(storage) => Item::Scalar(self.compile_storage_type(storage)),
             core::Type::Line(storage, size) => {
@@ -318,7 +324,16 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
 
     pub fn compile_storage_type(&mut self, ty: core::StorageType) -> Elem {
         match ty {
-            core::StorageType::Scalar(ty) | core::StorageType::Atomic(ty) => self.compile_elem(ty),
+            core::StorageType::Scalar(ty) => self.compile_elem(ty),
+            // Polyfilled atomics are only accessed through an integer view, see
+            // `AtomicPolyfillProcessor`.
+            core::StorageType::Atomic(ty) => {
+                let polyfill = self
+                    .features
+                    .as_ref()
+                    .and_then(|features| features.atomic_polyfill_type(ty));
+                self.compile_elem(polyfill.unwrap_or(ty))
+            }
             core::StorageType::Opaque(ty) => match ty {
                 core::OpaqueType::Barrier(_) => {
                     unimplemented!("Barrier type not supported in SPIR-V")
@@ -357,6 +372,8 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
             core::ElemType::Float(FloatKind::TF32) => panic!("TF32 not supported in SPIR-V"),
             core::ElemType::Float(FloatKind::Flex32) => Elem::Relaxed,
             core::ElemType::Float(FloatKind::F32) => Elem::Float(32, None),
+            // Lowered to pairs of `f32`, see `Df64EmulationProcessor`.
+            core::ElemType::Float(FloatKind::DF64) => Elem::Float(32, None),
             core::ElemType::Float(FloatKind::F64) => {
                 self.capabilities.insert(Capability::Float64);
                 Elem::Float(64, None)
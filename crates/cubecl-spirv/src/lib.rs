@@ -2,13 +2,20 @@
 
 use std::fmt::{Debug, Display};
 
-use cubecl_core::prelude::Binding;
+use cubecl_core::{
+    ir::{ConstantValue, TextureDim},
+    prelude::Binding,
+};
 use cubecl_opt::Optimizer;
 use item::Elem;
+use lookups::SpecConstant;
 use rspirv::{
     binary::{Assemble, Disassemble},
-    dr::Module,
+    dr::{Module, Operand},
+    grammar::CoreInstructionTable,
+    spirv::Op,
 };
+use variable::ConstVal;
 
 mod arithmetic;
 mod atomic;
@@ -26,6 +33,7 @@ mod metadata;
 mod subgroup;
 mod sync;
 mod target;
+mod texture;
 mod transformers;
 mod variable;
 
@@ -38,7 +46,15 @@ pub struct SpirvKernel {
     pub optimizer: Optimizer,
     pub bindings: Vec<Binding>,
     pub scalars: Vec<(Elem, usize)>,
+    /// Dimensionality of the textures, bound after the scalars and followed by one sampler per
+    /// sampler state.
+    pub textures: Vec<TextureDim>,
+    pub spec_constants: Vec<SpecConstant>,
     pub has_metadata: bool,
+    /// Size in bytes of the shared memory, with the allocations from [`cubecl_opt::SharedLiveness`].
+    pub shared_memory_size: usize,
+    /// Size in bytes of every local array of the kernel and its functions.
+    pub local_array_sizes: Vec<usize>,
 }
 
 impl Display for SpirvKernel {
@@ -51,4 +67,42 @@ impl SpirvKernel {
     pub fn assemble(&self) -> Vec<u32> {
         self.module.assemble()
     }
+
+    /// Assemble the module with the specialization constants set to `values`, indexed by id.
+    ///
+    /// Vulkan passthrough shaders can't take pipeline constants, so the defaults of the
+    /// `OpSpecConstant` declarations are patched instead.
+    pub fn specialize(&self, values: &[ConstantValue]) -> Vec<u32> {
+        if self.spec_constants.is_empty() {
+            return self.assemble();
+        }
+
+        let mut module = self.module.clone();
+        for constant in self.spec_constants.iter() {
+            let Some(value) = values.get(constant.id as usize) else {
+                continue;
+            };
+            let inst = module
+                .types_global_values
+                .iter_mut()
+                .find(|inst| inst.result_id == Some(constant.word))
+                .expect("Specialization constant should be declared");
+
+            match constant.item.elem() {
+                Elem::Bool => {
+                    inst.class = match value.as_bool() {
+                        true => CoreInstructionTable::get(Op::SpecConstantTrue),
+                        false => CoreInstructionTable::get(Op::SpecConstantFalse),
+                    };
+                }
+                _ => {
+                    inst.operands = vec![match ConstVal::from((*value, constant.item.clone())) {
+                        ConstVal::Bit32(val) => Operand::LiteralBit32(val),
+                        ConstVal::Bit64(val) => Operand::LiteralBit64(val),
+                    }];
+                }
+            }
+        }
+        module.assemble()
+    }
 }
@@ -2,6 +2,7 @@ use std::collections::VecDeque;
 
 use cubecl_core::{
     ir::{self, Builtin, Id, Type, VariableKind},
+    post_processing::{df64, int64},
     prelude::{Binding, KernelDefinition, Location, Visibility},
 };
 use cubecl_opt::{ConstArray, NodeIndex, SharedMemory};
@@ -24,6 +25,9 @@ pub struct LookupTables {
     pub buffers: Vec<Word>,
     pub scalar_bindings: HashMap<ir::StorageType, Word>,
     pub info: Word,
+    pub textures: Vec<Texture>,
+    /// One sampler per sampler state, ordered by [index](ir::Sampler::index).
+    pub samplers: Vec<Word>,
     pub cube_dims: Vec<Word>,
     pub cube_size: Word,
 
@@ -31,6 +35,8 @@ pub struct LookupTables {
     pub shared_arrays: HashMap<Id, SharedArray>,
     pub shared: HashMap<Id, SharedVar>,
     pub local_arrays: HashMap<Id, Array>,
+    /// Sizes of the local arrays of all functions, in bytes.
+    pub local_array_sizes: Vec<usize>,
     pub matrices: HashMap<Id, Matrix>,
     pub globals: HashMap<Builtin, Word>,
     pub loaded_builtins: HashMap<BuiltIn, Word>,
@@ -38,6 +44,7 @@ pub struct LookupTables {
     pub used_builtins: HashMap<BuiltIn, (Word, Item)>,
 
     pub scalars: HashMap<(Id, ir::StorageType), Word>,
+    pub spec_constants: Vec<SpecConstant>,
     pub array_types: HashSet<Word>,
     pub constants: HashMap<(ConstVal, Item), Word>,
     pub bindings: HashMap<Id, Word>,
@@ -48,6 +55,9 @@ pub struct LookupTables {
 
     pub atomic_scopes: HashMap<Word, Scope>,
 
+    // Device functions called by the kernel, by name
+    pub functions: HashMap<String, Word>,
+
     pub slices: HashMap<Id, Slice>,
 
     // For break, continue
@@ -58,6 +68,13 @@ pub struct LookupTables {
     pub debug_types: HashSet<Word>,
 }
 
+#[derive(Clone, Debug)]
+pub struct SpecConstant {
+    pub id: Id,
+    pub word: Word,
+    pub item: Item,
+}
+
 #[derive(Clone, Debug)]
 pub struct Slice {
     pub ptr: Variable,
@@ -79,6 +96,12 @@ impl From<&Slice> for Variable {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct Texture {
+    pub id: Word,
+    pub image_ty: Word,
+}
+
 #[derive(Clone, Debug)]
 pub struct Array {
     pub id: Word,
@@ -138,6 +161,10 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
             .buffers
             .into_iter()
             .map(|mut binding| {
+                if self.int64_emulation {
+                    binding.ty = int64::lower_type(binding.ty);
+                }
+                binding.ty = df64::lower_type(binding.ty);
                 // This is safe when combined with the unroll transform that adjusts all indices.
                 // Must not be used alone
                 if binding.ty.line_size() > MAX_VECTORIZATION {
@@ -182,6 +209,21 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
             })
             .collect();
 
+        // Textures follow the scalars, with one sampler per sampler state after them.
+        offset += self.state.scalar_bindings.len() as u32;
+        self.state.textures = kernel
+            .textures
+            .iter()
+            .enumerate()
+            .map(|(i, texture)| self.declare_texture(texture.id, texture.dim, i as u32 + offset))
+            .collect();
+        if !self.state.textures.is_empty() {
+            offset += self.state.textures.len() as u32;
+            self.state.samplers = (0..ir::Sampler::COUNT as u32)
+                .map(|i| self.declare_sampler(i + offset))
+                .collect();
+        }
+
         let cube_dims = [kernel.cube_dim.x, kernel.cube_dim.y, kernel.cube_dim.z];
         self.state.cube_dims = cube_dims.iter().map(|dim| self.const_u32(*dim)).collect();
         self.state.cube_size = self.const_u32(cube_dims.iter().product());
@@ -373,7 +415,7 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
     pub fn global_scalar(&mut self, id: Id, ty: ir::StorageType) -> Variable {
         if let Some(existing) = self.state.scalars.get(&(id, ty)).copied() {
             let item = self.compile_type(ir::Type::new(ty));
-            Variable::GlobalScalar(existing, item.elem())
+            global_scalar_var(existing, item)
         } else {
             let ir_var = ir::Variable::new(VariableKind::GlobalScalar(id), Type::new(ty));
             let current_block = self.selected_block();
@@ -385,7 +427,7 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
             let const_id = self.const_u32(id);
             let index = Variable::Constant(const_id, id.into(), Item::Scalar(Elem::Int(32, false)));
             let read_id = self.id();
-            let var = Variable::GlobalScalar(read_id, item.elem());
+            let var = global_scalar_var(read_id, item.clone());
             self.debug_var_name(read_id, ir_var);
             self.read_indexed_unchecked(&var, &arr, &index);
             self.select_block(current_block).unwrap();
@@ -394,6 +436,38 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
         }
     }
 
+    /// Declare the specialization constant `id` on first use. The declared default is zero, the
+    /// actual value is patched in by [`SpirvKernel::specialize`](crate::SpirvKernel::specialize).
+    pub fn spec_constant(&mut self, id: Id, ty: ir::StorageType) -> Variable {
+        let item = self.compile_type(ir::Type::new(ty));
+        if let Some(existing) = self.state.spec_constants.iter().find(|it| it.id == id) {
+            return Variable::Raw(existing.word, existing.item.clone());
+        }
+
+        let ty_id = item.id(self);
+        let (op, operands) = match item.elem() {
+            Elem::Bool => (spirv::Op::SpecConstantFalse, vec![]),
+            elem if elem.size() == 8 => {
+                (spirv::Op::SpecConstant, vec![dr::Operand::LiteralBit64(0)])
+            }
+            _ => (spirv::Op::SpecConstant, vec![dr::Operand::LiteralBit32(0)]),
+        };
+        let word = self.id();
+        let inst = dr::Instruction::new(op, Some(ty_id), Some(word), operands);
+        self.module_mut().types_global_values.push(inst);
+        self.decorate(
+            word,
+            spirv::Decoration::SpecId,
+            [dr::Operand::LiteralBit32(id)],
+        );
+        self.state.spec_constants.push(SpecConstant {
+            id,
+            word,
+            item: item.clone(),
+        });
+        Variable::Raw(word, item)
+    }
+
     pub fn register_const_array(&mut self, arr: ConstArray) {
         let var = ir::Variable::new(
             VariableKind::ConstantArray {
@@ -430,3 +504,11 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
         );
     }
 }
+
+/// Emulated 64-bit integer scalars are read as a vector of their words.
+fn global_scalar_var(id: Word, item: Item) -> Variable {
+    match item {
+        Item::Scalar(elem) => Variable::GlobalScalar(id, elem),
+        item => Variable::Raw(id, item),
+    }
+}
@@ -0,0 +1,148 @@
+use cubecl_core::ir::{self as core, Id, TextureDim, TextureOps, VariableKind};
+use rspirv::{
+    dr::Operand,
+    spirv::{Decoration, Dim, ImageFormat, ImageOperands, StorageClass, Word},
+};
+
+use crate::{
+    SpirvCompiler, SpirvTarget,
+    item::{Elem, Item},
+    lookups::Texture,
+    variable::ConstVal,
+};
+
+impl<T: SpirvTarget> SpirvCompiler<T> {
+    /// Declare the texture `id` bound at `binding`, with `f32` texels.
+    pub fn declare_texture(&mut self, id: Id, dim: TextureDim, binding: u32) -> Texture {
+        let sampled_ty = Elem::Float(32, None).id(self);
+        let dim = match dim {
+            TextureDim::D2 => Dim::Dim2D,
+            TextureDim::D3 => Dim::Dim3D,
+        };
+        let image_ty = self.type_image(sampled_ty, dim, 0, 0, 0, 1, ImageFormat::Unknown, None);
+        let var = self.declare_uniform_constant(image_ty, binding);
+        self.debug_name(var, format!("texture({id})"));
+
+        Texture { id: var, image_ty }
+    }
+
+    /// Declare the sampler bound at `binding`.
+    pub fn declare_sampler(&mut self, binding: u32) -> Word {
+        let sampler_ty = self.type_sampler();
+        let var = self.declare_uniform_constant(sampler_ty, binding);
+        self.debug_name(var, format!("sampler({binding})"));
+        var
+    }
+
+    fn declare_uniform_constant(&mut self, ty: Word, binding: u32) -> Word {
+        let ptr_ty = self.type_pointer(None, StorageClass::UniformConstant, ty);
+        let var = self.variable(ptr_ty, None, StorageClass::UniformConstant, None);
+        self.decorate(var, Decoration::DescriptorSet, vec![0u32.into()]);
+        self.decorate(var, Decoration::Binding, vec![binding.into()]);
+        var
+    }
+
+    pub fn compile_texture(&mut self, op: TextureOps, out: Option<core::Variable>) {
+        let out = self.compile_variable(out.unwrap());
+        let float = Elem::Float(32, None);
+        let texel_ty = Item::Vector(float, 4).id(self);
+
+        let texel = match op {
+            TextureOps::Sample {
+                texture,
+                sampler,
+                coordinates,
+            } => {
+                let image = self.load_texture(texture);
+                let sampler_ty = self.type_sampler();
+                let sampler = self.state.samplers[sampler.index()];
+                let sampler = self.load(sampler_ty, None, sampler, None, vec![]).unwrap();
+                let sampled_image_ty = self.type_sampled_image(image.image_ty);
+                let sampled_image = self
+                    .sampled_image(sampled_image_ty, None, image.id, sampler)
+                    .unwrap();
+                let coordinates = self.texture_coordinates(coordinates, float);
+                let lod = Item::Scalar(float).constant(self, ConstVal::Bit32(0));
+                self.image_sample_explicit_lod(
+                    texel_ty,
+                    None,
+                    sampled_image,
+                    coordinates,
+                    ImageOperands::LOD,
+                    [Operand::IdRef(lod)],
+                )
+                .unwrap()
+            }
+            TextureOps::Load {
+                texture,
+                coordinates,
+            } => {
+                let image = self.load_texture(texture);
+                let int = Elem::Int(32, true);
+                let coordinates = self.texture_coordinates(coordinates, int);
+                let lod = Item::Scalar(int).const_u32(self, 0);
+                self.image_fetch(
+                    texel_ty,
+                    None,
+                    image.id,
+                    coordinates,
+                    Some(ImageOperands::LOD),
+                    [Operand::IdRef(lod)],
+                )
+                .unwrap()
+            }
+        };
+
+        // Texels always have 4 channels, keep the ones of the output before converting them.
+        let out_item = out.item();
+        let (channels_item, channels) = match out_item {
+            Item::Vector(_, channels) => (Item::Vector(float, channels), channels),
+            _ => (Item::Scalar(float), 1),
+        };
+        let channels_ty = channels_item.id(self);
+        let value = match channels {
+            4 => texel,
+            1 => self
+                .composite_extract(channels_ty, None, texel, [0])
+                .unwrap(),
+            _ => self
+                .vector_shuffle(channels_ty, None, texel, texel, 0..channels)
+                .unwrap(),
+        };
+
+        let out_id = self.write_id(&out);
+        channels_item.cast_to(self, Some(out_id), value, &out_item);
+        self.write(&out, out_id);
+    }
+
+    /// Load the image of a texture, returned with its type.
+    fn load_texture(&mut self, texture: core::Variable) -> Texture {
+        let VariableKind::Texture(id) = texture.kind else {
+            unreachable!("Textures are always bound by the kernel")
+        };
+        let texture = self.state.textures[id as usize].clone();
+        let image = self
+            .load(texture.image_ty, None, texture.id, None, vec![])
+            .unwrap();
+
+        Texture {
+            id: image,
+            image_ty: texture.image_ty,
+        }
+    }
+
+    /// Build the coordinates vector expected by the image instructions, converting each
+    /// coordinate to `elem`.
+    fn texture_coordinates(&mut self, coordinates: Vec<core::Variable>, elem: Elem) -> Word {
+        let components = coordinates
+            .into_iter()
+            .map(|coord| {
+                let coord = self.compile_variable(coord);
+                let value = self.read(&coord);
+                coord.item().cast_to(self, None, value, &Item::Scalar(elem))
+            })
+            .collect::<Vec<_>>();
+        let ty = Item::Vector(elem, components.len() as u32).id(self);
+        self.composite_construct(ty, None, components).unwrap()
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
     lookups::Array,
 };
 use cubecl_core::ir::{self, ConstantValue, Id};
+use cubecl_core::post_processing::{df64, int64};
 use rspirv::{
     dr::Builder,
     spirv::{self, FPEncoding, StorageClass, Word},
@@ -337,7 +338,13 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
                 let id = self.state.buffers[pos as usize];
                 Variable::GlobalOutputArray(id, self.compile_type(item), pos)
             }
-            ir::VariableKind::GlobalScalar(id) => self.global_scalar(id, item.storage_type()),
+            ir::VariableKind::GlobalScalar(id) => {
+                match int64::lowered_scalar(item).or_else(|| df64::lowered_scalar(item)) {
+                    Some(ty) => self.global_scalar(id, ty),
+                    None => self.global_scalar(id, item.storage_type()),
+                }
+            }
+            ir::VariableKind::SpecConstant(id) => self.spec_constant(id, item.storage_type()),
             ir::VariableKind::LocalMut { id } => {
                 let item = self.compile_type(item);
                 let var = self.get_local(id, &item, variable);
@@ -381,6 +388,8 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
                     arr.id
                 } else {
                     let arr_ty = Item::Array(Box::new(item.clone()), length as u32);
+                    let arr_size = item.size() as usize * length;
+                    self.state.local_array_sizes.push(arr_size);
                     let ptr_ty = Item::Pointer(StorageClass::Function, Box::new(arr_ty)).id(self);
                     let arr_id = self.declare_function_variable(ptr_ty);
                     self.debug_var_name(arr_id, variable);
@@ -412,6 +421,12 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
             }
             ir::VariableKind::TensorMapInput(_) => panic!("Tensor map not supported."),
             ir::VariableKind::TensorMapOutput(_) => panic!("Tensor map not supported."),
+            ir::VariableKind::Texture(_) => {
+                panic!("Textures can only be sampled or loaded on this runtime.")
+            }
+            ir::VariableKind::DynamicSharedArray { .. } => {
+                panic!("Views of the dynamic shared memory can only be indexed on this runtime.")
+            }
         }
     }
 
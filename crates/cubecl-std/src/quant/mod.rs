@@ -1,6 +1,8 @@
 mod base;
 mod dequantize;
+mod quantize;
 pub mod view;
 
 pub use base::*;
 pub use dequantize::*;
+pub use quantize::*;
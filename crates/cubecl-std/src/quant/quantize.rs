@@ -0,0 +1,514 @@
+use cubecl::prelude::*;
+use cubecl_common::quant::scheme::*;
+use cubecl_common::{e2m1x2, e4m3, e5m2};
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise, server::CubeCountSelection};
+
+use super::{RunWithQuantType, view::run_with_quant_type};
+use crate::tensor::TensorHandle;
+
+/// Number of bisection steps used to find the percentile of a group. Enough to reach the
+/// precision of an `f32` relative to the absolute maximum.
+const PERCENTILE_STEPS: u32 = 24;
+
+/// Maximum number of units cooperating on the scale of a single group.
+const MAX_GROUP_UNITS: usize = 256;
+
+/// How the scale of each quantization group is calibrated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Calibration {
+    /// Map the absolute maximum of the group to the largest quantized value.
+    AbsMax,
+    /// Map the given percentile of the absolute values of the group, in `(0, 100]`, to the
+    /// largest quantized value. Values above it are clamped.
+    Percentile(f32),
+}
+
+/// Quantize a line of values with a single scale, packing them into the storage type of the
+/// scheme. The line size must be a multiple of `scheme.num_quants()`.
+#[cube]
+pub fn quantize_aligned<F: Float, S: CubePrimitive, Q: CubePrimitive>(
+    value: Line<F>,
+    scale: S,
+    #[comptime] scheme: QuantScheme,
+) -> Line<Q> {
+    let (min, max) = comptime![scheme.value.range()];
+    let scaled = match scheme.mode {
+        QuantMode::Symmetric => value / Line::<F>::cast_from(scale),
+    };
+    let clamped = Line::clamp(scaled, Line::new(F::new(min)), Line::new(F::new(max)));
+
+    match scheme.store {
+        QuantStore::Native => match scheme.value {
+            QuantValue::E5M2 | QuantValue::E4M3 | QuantValue::E2M1 => Line::<Q>::cast_from(clamped),
+            QuantValue::Q8F
+            | QuantValue::Q4F
+            | QuantValue::Q2F
+            | QuantValue::Q8S
+            | QuantValue::Q4S
+            | QuantValue::Q2S => Line::<Q>::cast_from(Line::round(clamped)),
+        },
+        QuantStore::U32 => Line::<Q>::cast_from(pack_cast_u32::<F>(clamped, scheme)),
+    }
+}
+
+/// Convert a set of values to the quantized format and pack them into `u32`s. Values must
+/// already be scaled and clamped to the range of the quantized format.
+#[cube]
+pub fn pack_cast_u32<F: Float>(value: Line<F>, #[comptime] scheme: QuantScheme) -> Line<u32> {
+    let num_quants = scheme.num_quants();
+    let native_packing = scheme.native_packing();
+    let out_line_size = value.line_size().comptime() / num_quants;
+    let size_bits = scheme.size_bits_value();
+
+    let mut out = Line::<u32>::empty(out_line_size);
+
+    #[unroll]
+    for line_idx in 0..out_line_size {
+        let in_offset = line_idx * num_quants;
+        let mut packed = 0u32;
+        #[unroll]
+        for packed_idx in range_stepped(0, num_quants, native_packing) {
+            let mut native = Line::<F>::empty(native_packing);
+            #[unroll]
+            for native_idx in 0..native_packing {
+                native[native_idx] = value[in_offset + packed_idx + native_idx];
+            }
+
+            let shift = packed_idx * size_bits;
+            packed |= cast_bits::<F>(native, scheme) << shift as u32;
+        }
+        out[line_idx] = packed;
+    }
+
+    out
+}
+
+/// Cast a scaled and clamped value to the quantized format, returning its bits in the low
+/// `n` bits of a `u32`. Inverse of the conversion applied when dequantizing.
+/// For `e2m1`, the input contains the two values of the packed `e2m1x2` representation.
+#[cube]
+fn cast_bits<F: Float>(value: Line<F>, #[comptime] scheme: QuantScheme) -> u32 {
+    match scheme.value {
+        QuantValue::E5M2 => u32::cast_from(u8::reinterpret(e5m2::cast_from(value[0]))),
+        QuantValue::E4M3 => u32::cast_from(u8::reinterpret(e4m3::cast_from(value[0]))),
+        QuantValue::E2M1 => u32::cast_from(u8::reinterpret(e2m1x2::cast_from(value))),
+        QuantValue::Q8F
+        | QuantValue::Q4F
+        | QuantValue::Q2F
+        | QuantValue::Q8S
+        | QuantValue::Q4S
+        | QuantValue::Q2S => {
+            let mask = comptime![(1u32 << scheme.size_bits_value()) - 1];
+            // Truncating the two's complement representation gives the packed signed value
+            let signed_value = i32::cast_from(F::round(value[0]));
+            u32::reinterpret(signed_value) & mask
+        }
+    }
+}
+
+/// Compute the scale of each group, with one cube per group.
+#[cube(launch_unchecked)]
+fn quantize_scales_kernel<F: Float, S: Numeric>(
+    input: &Tensor<F>,
+    scales: &mut Tensor<S>,
+    quantile: f32,
+    #[comptime] scheme: QuantScheme,
+    #[comptime] percentile: bool,
+    #[comptime] cube_size: usize,
+    #[define(F, S)] _dtypes: [StorageType; 2],
+) {
+    let group = CUBE_POS;
+    if group >= scales.len() {
+        terminate!();
+    }
+
+    let group_size = input.len() / scales.len();
+
+    let mut local_max = 0f32;
+    let mut elem = UNIT_POS as usize;
+    while elem < group_size {
+        let value = f32::cast_from(input[group_offset(input, scales, group, elem)]);
+        local_max = Max::max(local_max, Abs::abs(value));
+        elem += CUBE_DIM as usize;
+    }
+    let mut clip = cube_max(local_max, cube_size);
+
+    if comptime![percentile] {
+        // Bisect the smallest threshold where at least `quantile` of the values are smaller.
+        let needed = u32::cast_from(f32::ceil(quantile * group_size as f32));
+        let mut low = 0f32;
+        let mut high = clip;
+
+        for _ in 0..PERCENTILE_STEPS {
+            let mid = (low + high) / 2.0;
+
+            let mut local_count = 0u32;
+            let mut elem = UNIT_POS as usize;
+            while elem < group_size {
+                let value = f32::cast_from(input[group_offset(input, scales, group, elem)]);
+                local_count += u32::cast_from(Abs::abs(value) <= mid);
+                elem += CUBE_DIM as usize;
+            }
+
+            if cube_sum(local_count, cube_size) >= needed {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        clip = high;
+    }
+
+    if UNIT_POS == 0 {
+        let scale = group_scale(clip, scheme);
+        let offset = scale_offset(scales, group);
+        scales[offset] = S::cast_from(scale);
+    }
+}
+
+/// Quantize the values, with each unit writing a single stored element.
+#[cube(launch_unchecked)]
+fn quantize_kernel<F: Float, S: Numeric, Q: Numeric>(
+    input: &Tensor<F>,
+    scales: &Tensor<S>,
+    output: &mut Tensor<Q>,
+    #[comptime] scheme: QuantScheme,
+    #[define(F, S, Q)] _dtypes: [StorageType; 3],
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    let num_quants = comptime![scheme.num_quants()];
+    let rank = input.rank();
+
+    // Position of the first quantized value along each axis
+    let mut remaining = ABSOLUTE_POS;
+    let mut input_offset = 0;
+    let mut scales_offset = 0;
+    for i in 0..rank {
+        let dim = rank - i - 1;
+        let is_last = dim == rank - 1;
+        let shape = select(is_last, input.shape(dim) / num_quants, input.shape(dim));
+        let mut coord = remaining % shape;
+        remaining /= shape;
+        if is_last {
+            coord *= num_quants;
+        }
+
+        let block = input.shape(dim) / scales.shape(dim);
+        input_offset += coord * input.stride(dim);
+        scales_offset += (coord / block) * scales.stride(dim);
+    }
+
+    // Quantize in full precision, since the values are divided by the scale
+    let last_stride = input.stride(rank - 1);
+    let mut values = Line::<f32>::empty(num_quants);
+    #[unroll]
+    for i in 0..num_quants {
+        values[i] = f32::cast_from(input[input_offset + i * last_stride]);
+    }
+
+    let quantized = quantize_aligned::<f32, S, Q>(values, scales[scales_offset], scheme);
+    let out_offset = output_offset(output, ABSOLUTE_POS);
+    output[out_offset] = quantized[0];
+}
+
+/// Compute the scale from the clipping value of a group.
+#[cube]
+fn group_scale(clip: f32, #[comptime] scheme: QuantScheme) -> f32 {
+    let q_max = comptime![scheme.value.range().1];
+    let mut scale = clip / q_max;
+    // All values are zero, so any scale works.
+    if scale == 0.0 {
+        scale = 1.0;
+    }
+
+    match scheme.param {
+        QuantParam::UE8M0 => {
+            // Round up to the next power of two, so no value is clamped.
+            let bits = u32::reinterpret(scale);
+            f32::reinterpret((bits + 0x007F_FFFFu32) & 0xFF80_0000u32)
+        }
+        QuantParam::F32 | QuantParam::F16 | QuantParam::BF16 | QuantParam::UE4M3 => scale,
+    }
+}
+
+/// Offset in the input of the `elem`-th value of `group`, where groups are blocks that each
+/// map to a single scale.
+#[cube]
+fn group_offset<F: Float, S: Numeric>(
+    input: &Tensor<F>,
+    scales: &Tensor<S>,
+    group: usize,
+    elem: usize,
+) -> usize {
+    let rank = input.rank();
+    let mut group_remaining = group;
+    let mut elem_remaining = elem;
+    let mut offset = 0;
+
+    for i in 0..rank {
+        let dim = rank - i - 1;
+        let num_groups = scales.shape(dim);
+        let block = input.shape(dim) / num_groups;
+
+        let group_coord = group_remaining % num_groups;
+        let elem_coord = elem_remaining % block;
+        group_remaining /= num_groups;
+        elem_remaining /= block;
+
+        offset += (group_coord * block + elem_coord) * input.stride(dim);
+    }
+
+    offset
+}
+
+/// Offset of the scale at the contiguous position `group`.
+#[cube]
+fn scale_offset<S: Numeric>(scales: &Tensor<S>, group: usize) -> usize {
+    output_offset(scales, group)
+}
+
+/// Offset in a possibly strided tensor of the element at the contiguous position `pos`.
+#[cube]
+fn output_offset<E: Numeric>(tensor: &Tensor<E>, pos: usize) -> usize {
+    let rank = tensor.rank();
+    let mut remaining = pos;
+    let mut offset = 0;
+
+    for i in 0..rank {
+        let dim = rank - i - 1;
+        offset += (remaining % tensor.shape(dim)) * tensor.stride(dim);
+        remaining /= tensor.shape(dim);
+    }
+
+    offset
+}
+
+/// Maximum of `value` across all units of the cube. `cube_size` must be a power of two.
+#[cube]
+fn cube_max(value: f32, #[comptime] cube_size: usize) -> f32 {
+    let mut shared = SharedMemory::<f32>::new(cube_size);
+    shared[UNIT_POS as usize] = value;
+    sync_cube();
+
+    let mut stride = comptime![cube_size / 2].runtime();
+    while stride > 0 {
+        if (UNIT_POS as usize) < stride {
+            let unit = UNIT_POS as usize;
+            shared[unit] = Max::max(shared[unit], shared[unit + stride]);
+        }
+        sync_cube();
+        stride /= 2;
+    }
+
+    let result = shared[0];
+    sync_cube();
+    result
+}
+
+/// Sum of `value` across all units of the cube. `cube_size` must be a power of two.
+#[cube]
+fn cube_sum(value: u32, #[comptime] cube_size: usize) -> u32 {
+    let mut shared = SharedMemory::<u32>::new(cube_size);
+    shared[UNIT_POS as usize] = value;
+    sync_cube();
+
+    let mut stride = comptime![cube_size / 2].runtime();
+    while stride > 0 {
+        if (UNIT_POS as usize) < stride {
+            let unit = UNIT_POS as usize;
+            shared[unit] += shared[unit + stride];
+        }
+        sync_cube();
+        stride /= 2;
+    }
+
+    let result = shared[0];
+    sync_cube();
+    result
+}
+
+struct QuantTypes;
+
+impl RunWithQuantType for QuantTypes {
+    type Output = (StorageType, StorageType);
+
+    fn execute<Q: CubePrimitive, S: CubePrimitive>(self) -> Self::Output {
+        (Q::as_type_native_unchecked(), S::as_type_native_unchecked())
+    }
+}
+
+/// Returns the storage type of the quantized values and the type of the scales of a scheme.
+pub fn quant_types(scheme: &QuantScheme) -> (StorageType, StorageType) {
+    run_with_quant_type(QuantTypes, *scheme)
+}
+
+/// Returns the shape of the quantized values of a tensor with the provided shape.
+pub fn quantized_shape(shape: &[usize], scheme: &QuantScheme) -> Vec<usize> {
+    let mut shape = shape.to_vec();
+    let last = shape.len() - 1;
+    assert!(
+        shape[last].is_multiple_of(scheme.num_quants()),
+        "Last dimension should be a multiple of the number of packed values"
+    );
+    shape[last] /= scheme.num_quants();
+    shape
+}
+
+/// Returns the shape of the scales of a tensor with the provided shape.
+pub fn scales_shape(shape: &[usize], scheme: &QuantScheme) -> Vec<usize> {
+    match scheme.level {
+        QuantLevel::Tensor => vec![1; shape.len()],
+        QuantLevel::Block(block_size) => {
+            let block_size = block_size.to_dim_vec(shape.len());
+            assert!(
+                (block_size[shape.len() - 1] as usize).is_multiple_of(scheme.num_quants()),
+                "Block size should be a multiple of the number of packed values"
+            );
+            shape
+                .iter()
+                .zip(block_size)
+                .map(|(dim, block)| {
+                    assert!(
+                        dim.is_multiple_of(block as usize),
+                        "Shape should be a multiple of the block size"
+                    );
+                    dim / block as usize
+                })
+                .collect()
+        }
+    }
+}
+
+/// Quantize `input` according to `scheme`, calibrating the scales from the values.
+///
+/// Returns the quantized values with the shape from [`quantized_shape`], and the scales with the
+/// shape from [`scales_shape`].
+pub fn quantize<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    scheme: &QuantScheme,
+    calibration: Calibration,
+    dtype: StorageType,
+) -> Result<(TensorHandle<R>, TensorHandle<R>), LaunchError> {
+    let (value_dtype, _) = quant_types(scheme);
+
+    let scales = compute_scales(client, input, scheme, calibration, dtype)?;
+    let output = TensorHandle::empty(client, quantized_shape(input.shape, scheme), value_dtype);
+    quantize_ref(
+        client,
+        input,
+        &scales.as_ref(),
+        &output.as_ref(),
+        scheme,
+        dtype,
+    )?;
+
+    Ok((output, scales))
+}
+
+/// Compute the scales used to quantize `input` according to `scheme`.
+pub fn compute_scales<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    scheme: &QuantScheme,
+    calibration: Calibration,
+    dtype: StorageType,
+) -> Result<TensorHandle<R>, LaunchError> {
+    let (_, scale_dtype) = quant_types(scheme);
+    let scales = TensorHandle::empty(client, scales_shape(input.shape, scheme), scale_dtype);
+    compute_scales_ref(client, input, &scales.as_ref(), scheme, calibration, dtype)?;
+    Ok(scales)
+}
+
+/// Compute the scales used to quantize `input` according to `scheme` into `scales`, which must
+/// have the shape from [`scales_shape`].
+pub fn compute_scales_ref<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    scales: &TensorHandleRef<'_, R>,
+    scheme: &QuantScheme,
+    calibration: Calibration,
+    dtype: StorageType,
+) -> Result<(), LaunchError> {
+    assert_eq!(
+        scales.shape,
+        scales_shape(input.shape, scheme),
+        "Scales should have one element per block"
+    );
+    let (_, scale_dtype) = quant_types(scheme);
+
+    let num_groups: usize = scales.shape.iter().product();
+    let group_size = input.shape.iter().product::<usize>() / num_groups;
+    let cube_size = group_size.next_power_of_two().clamp(32, MAX_GROUP_UNITS);
+
+    let (percentile, quantile) = match calibration {
+        Calibration::AbsMax => (false, 1.0),
+        Calibration::Percentile(percentile) => {
+            assert!(
+                percentile > 0.0 && percentile <= 100.0,
+                "Percentile should be in (0, 100]"
+            );
+            (true, percentile / 100.0)
+        }
+    };
+
+    let cube_count = CubeCountSelection::new(client, num_groups as u32).cube_count();
+
+    unsafe {
+        quantize_scales_kernel::launch_unchecked(
+            client,
+            cube_count,
+            CubeDim::new_1d(cube_size as u32),
+            input.as_tensor_arg(1),
+            scales.as_tensor_arg(1),
+            ScalarArg::new(quantile),
+            *scheme,
+            percentile,
+            cube_size,
+            [dtype, scale_dtype],
+        )
+    }
+}
+
+/// Quantize `input` with precomputed `scales` into `output`, which must have the shape from
+/// [`quantized_shape`].
+pub fn quantize_ref<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    scales: &TensorHandleRef<'_, R>,
+    output: &TensorHandleRef<'_, R>,
+    scheme: &QuantScheme,
+    dtype: StorageType,
+) -> Result<(), LaunchError> {
+    assert_eq!(
+        scales.shape,
+        scales_shape(input.shape, scheme),
+        "Scales should have one element per block"
+    );
+    assert_eq!(
+        output.shape,
+        quantized_shape(input.shape, scheme),
+        "Output should have one element per packed value"
+    );
+    let (value_dtype, scale_dtype) = quant_types(scheme);
+
+    let num_elems: usize = output.shape.iter().product();
+    let cube_dim = CubeDim::new(client, num_elems);
+    let cube_count = calculate_cube_count_elemwise(client, num_elems, cube_dim);
+
+    unsafe {
+        quantize_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            scales.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            *scheme,
+            [dtype, scale_dtype, value_dtype],
+        )
+    }
+}
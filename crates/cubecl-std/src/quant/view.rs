@@ -298,6 +298,7 @@ pub(crate) fn expand_dynamic<E: CubePrimitive, C: Coordinates + 'static, IO: Sli
                 FloatKind::F32 => t(expand_dynamic_f::<f32, C>(values, scales, scheme, builder)),
                 FloatKind::TF32 => t(expand_dynamic_f::<tf32, C>(values, scales, scheme, builder)),
                 FloatKind::F64 => t(expand_dynamic_f::<f64, C>(values, scales, scheme, builder)),
+                FloatKind::DF64 => t(expand_dynamic_f::<df64, C>(values, scales, scheme, builder)),
                 FloatKind::E2M1
                 | FloatKind::E2M3
                 | FloatKind::E3M2
@@ -0,0 +1,554 @@
+use cubecl::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise, ir::LineSize, server::Handle};
+
+use crate::tensor::{TensorHandle, empty_contiguous, is_contiguous};
+
+/// How the tensors of a group are combined by a reduction collective.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ReduceOp {
+    /// The sum of the tensors.
+    Sum,
+    /// The mean of the tensors. Integers are rounded like a division.
+    Mean,
+    /// The element-wise maximum of the tensors.
+    Max,
+}
+
+/// The start and end of a chunk of a tensor, in elements.
+type Chunk = (usize, usize);
+
+#[cube(launch_unchecked)]
+fn copy_chunk_kernel<N: Numeric>(
+    input: &Array<Line<N>>,
+    output: &mut Array<Line<N>>,
+    offset: usize,
+    #[define(N)] _dtype: StorageType,
+) {
+    if ABSOLUTE_POS >= input.len() {
+        terminate!();
+    }
+
+    output[offset + ABSOLUTE_POS] = input[ABSOLUTE_POS];
+}
+
+#[cube(launch_unchecked)]
+fn reduce_chunk_kernel<N: Numeric>(
+    input: &Array<Line<N>>,
+    output: &mut Array<Line<N>>,
+    offset: usize,
+    #[comptime] op: ReduceOp,
+    #[define(N)] _dtype: StorageType,
+) {
+    if ABSOLUTE_POS >= input.len() {
+        terminate!();
+    }
+
+    let pos = offset + ABSOLUTE_POS;
+    output[pos] = match op {
+        ReduceOp::Sum | ReduceOp::Mean => output[pos] + input[ABSOLUTE_POS],
+        ReduceOp::Max => Max::max(output[pos], input[ABSOLUTE_POS]),
+    };
+}
+
+#[cube(launch_unchecked)]
+fn divide_chunk_kernel<N: Numeric>(
+    output: &mut Array<Line<N>>,
+    offset: usize,
+    len: usize,
+    divisor: u32,
+    #[define(N)] _dtype: StorageType,
+) {
+    if ABSOLUTE_POS >= len {
+        terminate!();
+    }
+
+    let pos = offset + ABSOLUTE_POS;
+    output[pos] = output[pos] / Line::new(N::cast_from(divisor));
+}
+
+/// A group of clients, usually on different devices of the same runtime, taking part in
+/// collective operations.
+///
+/// The tensors of a collective are given in the same order as the clients, the `i`-th tensor
+/// living on the `i`-th client, which is called its rank. Data moves between clients with
+/// [to_client](ComputeClient::to_client), so the collectives work on every runtime, using direct
+/// device-to-device copies when the server supports them.
+///
+/// Reductions and gathers use the ring algorithm, which splits the tensors in one chunk per rank
+/// and sends every chunk `size - 1` times between neighbours, so each client sends and receives
+/// about twice the size of a tensor regardless of the size of the group. Broadcasts use a binomial
+/// tree, completing in `log2(size)` rounds.
+pub struct CollectiveGroup<R: Runtime> {
+    clients: Vec<ComputeClient<R>>,
+}
+
+impl<R: Runtime> Clone for CollectiveGroup<R> {
+    fn clone(&self) -> Self {
+        Self {
+            clients: self.clients.clone(),
+        }
+    }
+}
+
+impl<R: Runtime> CollectiveGroup<R> {
+    /// Create a group from its clients, ordered by rank.
+    pub fn new(clients: Vec<ComputeClient<R>>) -> Self {
+        assert!(
+            !clients.is_empty(),
+            "A collective group needs at least one client"
+        );
+
+        Self { clients }
+    }
+
+    /// The clients of the group, ordered by rank.
+    pub fn clients(&self) -> &[ComputeClient<R>] {
+        &self.clients
+    }
+
+    /// The number of clients in the group.
+    pub fn size(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Reduce the `inputs` of every rank with `op`, and return the result on every rank.
+    ///
+    /// The inputs must be contiguous and have the same shape.
+    pub fn all_reduce(
+        &self,
+        inputs: &[TensorHandleRef<'_, R>],
+        op: ReduceOp,
+        dtype: StorageType,
+    ) -> Result<Vec<TensorHandle<R>>, LaunchError> {
+        self.assert_inputs(inputs);
+
+        let len = num_elems(inputs[0].shape);
+        let line_size = self.line_size(dtype, len);
+        let chunk = (len / line_size).div_ceil(self.size()) * line_size;
+        let chunks = (0..self.size())
+            .map(|rank| {
+                (
+                    Ord::min(rank * chunk, len),
+                    Ord::min((rank + 1) * chunk, len),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let outputs = self.copy_inputs(inputs, dtype)?;
+        self.ring_reduce_scatter(&outputs, &chunks, line_size, op, dtype)?;
+        self.ring_all_gather(&outputs, &chunks, line_size, dtype)?;
+
+        Ok(outputs)
+    }
+
+    /// Reduce the `inputs` of every rank with `op`, and split the result along the first dimension
+    /// so each rank only receives its part.
+    ///
+    /// The inputs must be contiguous and have the same shape, with a first dimension divisible by
+    /// the size of the group.
+    pub fn reduce_scatter(
+        &self,
+        inputs: &[TensorHandleRef<'_, R>],
+        op: ReduceOp,
+        dtype: StorageType,
+    ) -> Result<Vec<TensorHandle<R>>, LaunchError> {
+        self.assert_inputs(inputs);
+        let shape = inputs[0].shape;
+        assert!(
+            !shape.is_empty() && shape[0].is_multiple_of(self.size()),
+            "The first dimension should be divisible by the size of the group"
+        );
+
+        let len = num_elems(shape);
+        let chunk = len / self.size();
+        let line_size = self.line_size(dtype, chunk);
+        let chunks = (0..self.size())
+            .map(|rank| (rank * chunk, (rank + 1) * chunk))
+            .collect::<Vec<_>>();
+
+        let buffers = self.copy_inputs(inputs, dtype)?;
+        self.ring_reduce_scatter(&buffers, &chunks, line_size, op, dtype)?;
+
+        let mut shape = shape.to_vec();
+        shape[0] /= self.size();
+
+        Ok(buffers
+            .into_iter()
+            .zip(chunks)
+            .map(|(buffer, range)| {
+                let handle = slice(&buffer.handle, range, len, dtype);
+                TensorHandle::new_contiguous(shape.clone(), handle, dtype)
+            })
+            .collect())
+    }
+
+    /// Concatenate the `inputs` of every rank along the first dimension, ordered by rank, and
+    /// return the result on every rank.
+    ///
+    /// The inputs must be contiguous and have the same shape.
+    pub fn all_gather(
+        &self,
+        inputs: &[TensorHandleRef<'_, R>],
+        dtype: StorageType,
+    ) -> Result<Vec<TensorHandle<R>>, LaunchError> {
+        self.assert_inputs(inputs);
+        let shape = inputs[0].shape;
+        assert!(!shape.is_empty(), "Can't gather scalars");
+
+        let chunk = num_elems(shape);
+        let line_size = self.line_size(dtype, chunk);
+        let chunks = (0..self.size())
+            .map(|rank| (rank * chunk, (rank + 1) * chunk))
+            .collect::<Vec<_>>();
+
+        let mut output_shape = shape.to_vec();
+        output_shape[0] *= self.size();
+
+        let mut outputs = Vec::with_capacity(self.size());
+        for ((client, input), range) in self.clients.iter().zip(inputs).zip(&chunks) {
+            let output = empty_contiguous(client, output_shape.clone(), dtype);
+            copy_chunk(
+                client,
+                input.handle,
+                &output.handle,
+                *range,
+                line_size,
+                dtype,
+            )?;
+            outputs.push(output);
+        }
+
+        self.ring_all_gather(&outputs, &chunks, line_size, dtype)?;
+
+        Ok(outputs)
+    }
+
+    /// Send the `input` of the `root` rank to every rank.
+    ///
+    /// The input must be contiguous. The tensor returned for the root is the input itself.
+    pub fn broadcast(
+        &self,
+        input: &TensorHandleRef<'_, R>,
+        root: usize,
+        dtype: StorageType,
+    ) -> Result<Vec<TensorHandle<R>>, LaunchError> {
+        assert!(root < self.size(), "The root should be a rank of the group");
+        assert!(
+            is_contiguous(input.shape, input.strides),
+            "Input should be contiguous"
+        );
+
+        let size = self.size();
+        let mut handles: Vec<Option<Handle>> = vec![None; size];
+        handles[root] = Some(input.handle.clone());
+
+        // Every rank that already has the tensor sends it to the rank `distance` after it, relative
+        // to the root, doubling the number of ranks with the tensor each round.
+        let mut distance = 1;
+        while distance < size {
+            for relative in 0..Ord::min(distance, size - distance) {
+                let src = (root + relative) % size;
+                let dst = (root + relative + distance) % size;
+                let handle = handles[src].clone().expect("Source should have the tensor");
+                handles[dst] = Some(
+                    self.clients[src]
+                        .to_client(handle, &self.clients[dst])
+                        .handle,
+                );
+            }
+            distance *= 2;
+        }
+
+        Ok(handles
+            .into_iter()
+            .map(|handle| {
+                let handle = handle.expect("Every rank should have the tensor");
+                TensorHandle::new_contiguous(input.shape.to_vec(), handle, dtype)
+            })
+            .collect())
+    }
+
+    /// Reduce every chunk over the ring, so that the `i`-th rank ends with the `i`-th chunk
+    /// reduced over the group.
+    ///
+    /// At each step, every rank sends the chunk it just reduced to the next rank, which reduces it
+    /// with its own version of the chunk.
+    fn ring_reduce_scatter(
+        &self,
+        buffers: &[TensorHandle<R>],
+        chunks: &[Chunk],
+        line_size: LineSize,
+        op: ReduceOp,
+        dtype: StorageType,
+    ) -> Result<(), LaunchError> {
+        let size = self.size();
+        let len = chunks[size - 1].1;
+
+        for step in 0..size - 1 {
+            let received = self.ring_step(buffers, chunks, len, dtype, |rank| {
+                (rank + 2 * size - step - 1) % size
+            });
+
+            for (dst, handle, range) in received {
+                reduce_chunk(
+                    &self.clients[dst],
+                    &handle,
+                    &buffers[dst].handle,
+                    range,
+                    line_size,
+                    op,
+                    dtype,
+                )?;
+            }
+        }
+
+        if op == ReduceOp::Mean {
+            for (rank, (client, buffer)) in self.clients.iter().zip(buffers).enumerate() {
+                divide_chunk(
+                    client,
+                    &buffer.handle,
+                    chunks[rank],
+                    size as u32,
+                    line_size,
+                    dtype,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gather every chunk over the ring, starting with the `i`-th chunk on the `i`-th rank.
+    ///
+    /// At each step, every rank sends the chunk it just received to the next rank.
+    fn ring_all_gather(
+        &self,
+        buffers: &[TensorHandle<R>],
+        chunks: &[Chunk],
+        line_size: LineSize,
+        dtype: StorageType,
+    ) -> Result<(), LaunchError> {
+        let size = self.size();
+        let len = chunks[size - 1].1;
+
+        for step in 0..size - 1 {
+            let received = self.ring_step(buffers, chunks, len, dtype, |rank| {
+                (rank + size - step) % size
+            });
+
+            for (dst, handle, range) in received {
+                copy_chunk(
+                    &self.clients[dst],
+                    &handle,
+                    &buffers[dst].handle,
+                    range,
+                    line_size,
+                    dtype,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send the chunk selected by `chunk_of` from every rank to the next rank.
+    ///
+    /// Returns the received chunks with their destination rank and range. Empty chunks aren't sent.
+    fn ring_step(
+        &self,
+        buffers: &[TensorHandle<R>],
+        chunks: &[Chunk],
+        len: usize,
+        dtype: StorageType,
+        chunk_of: impl Fn(usize) -> usize,
+    ) -> Vec<(usize, Handle, Chunk)> {
+        let size = self.size();
+
+        (0..size)
+            .filter_map(|src| {
+                let range = chunks[chunk_of(src)];
+                if range.0 == range.1 {
+                    return None;
+                }
+
+                let dst = (src + 1) % size;
+                let handle = slice(&buffers[src].handle, range, len, dtype);
+                let received = self.clients[src].to_client(handle, &self.clients[dst]);
+
+                Some((dst, received.handle, range))
+            })
+            .collect()
+    }
+
+    /// Copy the inputs into new contiguous tensors, used as working buffers.
+    fn copy_inputs(
+        &self,
+        inputs: &[TensorHandleRef<'_, R>],
+        dtype: StorageType,
+    ) -> Result<Vec<TensorHandle<R>>, LaunchError> {
+        let len = num_elems(inputs[0].shape);
+        let line_size = self.line_size(dtype, len);
+
+        self.clients
+            .iter()
+            .zip(inputs)
+            .map(|(client, input)| {
+                let output = empty_contiguous(client, input.shape.to_vec(), dtype);
+                copy_chunk(
+                    client,
+                    input.handle,
+                    &output.handle,
+                    (0, len),
+                    line_size,
+                    dtype,
+                )?;
+                Ok(output)
+            })
+            .collect()
+    }
+
+    /// The largest line size dividing `len`.
+    fn line_size(&self, dtype: StorageType, len: usize) -> LineSize {
+        self.clients[0]
+            .io_optimized_line_sizes(&dtype)
+            .filter(|line_size| len.is_multiple_of(*line_size))
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn assert_inputs(&self, inputs: &[TensorHandleRef<'_, R>]) {
+        assert_eq!(
+            inputs.len(),
+            self.size(),
+            "Expected one input for each rank"
+        );
+        for input in inputs {
+            assert_eq!(
+                input.shape, inputs[0].shape,
+                "Inputs should have the same shape"
+            );
+            assert!(
+                is_contiguous(input.shape, input.strides),
+                "Inputs should be contiguous"
+            );
+        }
+    }
+}
+
+fn num_elems(shape: &[usize]) -> usize {
+    shape.iter().product()
+}
+
+/// The part of a buffer of `len` elements covering the elements in `range`.
+fn slice(handle: &Handle, range: Chunk, len: usize, dtype: StorageType) -> Handle {
+    let elem_size = dtype.size() as u64;
+
+    handle
+        .clone()
+        .offset_start(range.0 as u64 * elem_size)
+        .offset_end((len - range.1) as u64 * elem_size)
+}
+
+/// Copy `input` into the elements of `output` in `range`.
+fn copy_chunk<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &Handle,
+    output: &Handle,
+    range: Chunk,
+    line_size: LineSize,
+    dtype: StorageType,
+) -> Result<(), LaunchError> {
+    if range.0 == range.1 {
+        return Ok(());
+    }
+
+    let (cube_count, cube_dim) = launch_dims(client, range, line_size);
+    let len = range.1 - range.0;
+    let output_len = output.size() as usize / dtype.size();
+
+    unsafe {
+        copy_chunk_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            ArrayArg::from_raw_parts_and_size(input, len, line_size, dtype.size()),
+            ArrayArg::from_raw_parts_and_size(output, output_len, line_size, dtype.size()),
+            ScalarArg::new(range.0 / line_size),
+            dtype,
+        )
+    }
+}
+
+/// Reduce `input` into the elements of `output` in `range`.
+fn reduce_chunk<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &Handle,
+    output: &Handle,
+    range: Chunk,
+    line_size: LineSize,
+    op: ReduceOp,
+    dtype: StorageType,
+) -> Result<(), LaunchError> {
+    if range.0 == range.1 {
+        return Ok(());
+    }
+
+    let (cube_count, cube_dim) = launch_dims(client, range, line_size);
+    let len = range.1 - range.0;
+    let output_len = output.size() as usize / dtype.size();
+
+    unsafe {
+        reduce_chunk_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            ArrayArg::from_raw_parts_and_size(input, len, line_size, dtype.size()),
+            ArrayArg::from_raw_parts_and_size(output, output_len, line_size, dtype.size()),
+            ScalarArg::new(range.0 / line_size),
+            op,
+            dtype,
+        )
+    }
+}
+
+/// Divide the elements of `output` in `range` by `divisor`.
+fn divide_chunk<R: Runtime>(
+    client: &ComputeClient<R>,
+    output: &Handle,
+    range: Chunk,
+    divisor: u32,
+    line_size: LineSize,
+    dtype: StorageType,
+) -> Result<(), LaunchError> {
+    if range.0 == range.1 {
+        return Ok(());
+    }
+
+    let (cube_count, cube_dim) = launch_dims(client, range, line_size);
+    let output_len = output.size() as usize / dtype.size();
+
+    unsafe {
+        divide_chunk_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            ArrayArg::from_raw_parts_and_size(output, output_len, line_size, dtype.size()),
+            ScalarArg::new(range.0 / line_size),
+            ScalarArg::new((range.1 - range.0) / line_size),
+            ScalarArg::new(divisor),
+            dtype,
+        )
+    }
+}
+
+fn launch_dims<R: Runtime>(
+    client: &ComputeClient<R>,
+    range: Chunk,
+    line_size: LineSize,
+) -> (CubeCount, CubeDim) {
+    let working_units = (range.1 - range.0) / line_size;
+    let cube_dim = CubeDim::new(client, working_units);
+    let cube_count = calculate_cube_count_elemwise(client, working_units, cube_dim);
+
+    (cube_count, cube_dim)
+}
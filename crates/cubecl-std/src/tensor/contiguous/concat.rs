@@ -0,0 +1,263 @@
+use cubecl::prelude::*;
+use cubecl_core::{
+    self as cubecl, calculate_cube_count_elemwise, ir::LineSize, tensor_line_size_parallel,
+};
+
+use crate::tensor::{TensorHandle, index_offset_with_layout, is_contiguous};
+
+#[cube(launch_unchecked)]
+fn concat_kernel<N: Numeric>(
+    inputs: &Sequence<Tensor<Line<N>>>,
+    output: &mut Tensor<Line<N>>,
+    dim: usize,
+    #[define(N)] _dtype: StorageType,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    let rank = output.rank();
+    let line_size = output.line_size();
+    let coord = (ABSOLUTE_POS * line_size / output.stride(dim)) % output.shape(dim);
+    let mut start = 0;
+
+    #[unroll]
+    for i in 0..inputs.len() {
+        let input = &inputs[i];
+        let end = start + input.shape(dim);
+
+        if coord >= start && coord < end {
+            let offset = index_offset_with_layout(input, output, ABSOLUTE_POS, 0, dim, false)
+                + index_offset_with_layout(input, output, ABSOLUTE_POS, dim + 1, rank, false)
+                + (coord - start) * input.stride(dim) / line_size;
+            output[ABSOLUTE_POS] = input[offset];
+        }
+
+        start = end;
+    }
+}
+
+#[cube(launch_unchecked)]
+fn split_kernel<N: Numeric>(
+    input: &Tensor<Line<N>>,
+    outputs: &mut Sequence<Tensor<Line<N>>>,
+    dim: usize,
+    #[define(N)] _dtype: StorageType,
+) {
+    if ABSOLUTE_POS >= input.len() {
+        terminate!();
+    }
+
+    let rank = input.rank();
+    let line_size = input.line_size();
+    // Units are assigned to the outputs one after the other.
+    let mut first_pos = 0;
+    let mut start = 0;
+
+    #[unroll]
+    for i in 0..outputs.len() {
+        let output = outputs.index_mut(i);
+        let end_pos = first_pos + output.len();
+
+        if ABSOLUTE_POS >= first_pos && ABSOLUTE_POS < end_pos {
+            let pos = ABSOLUTE_POS - first_pos;
+            let coord = (pos * line_size / output.stride(dim)) % output.shape(dim);
+            let offset = index_offset_with_layout(input, output, pos, 0, dim, false)
+                + index_offset_with_layout(input, output, pos, dim + 1, rank, false)
+                + (start + coord) * input.stride(dim) / line_size;
+            output[pos] = input[offset];
+        }
+
+        first_pos = end_pos;
+        start += output.shape(dim);
+    }
+}
+
+/// Concatenate `inputs` along `dim` in a single launch.
+///
+/// All inputs must have the same shape, except for `dim`. The output is contiguous.
+pub fn concat<R: Runtime>(
+    client: &ComputeClient<R>,
+    inputs: &[TensorHandleRef<'_, R>],
+    dim: usize,
+    dtype: StorageType,
+) -> Result<TensorHandle<R>, LaunchError> {
+    assert!(
+        !inputs.is_empty(),
+        "Concatenation requires at least one input"
+    );
+
+    let mut shape = inputs[0].shape.to_vec();
+    shape[dim] = inputs.iter().map(|input| input.shape[dim]).sum();
+
+    let output = empty_contiguous(client, shape, dtype);
+    concat_ref(client, inputs, &output.as_ref(), dim, dtype)?;
+
+    Ok(output)
+}
+
+/// Concatenate `inputs` along `dim` into `output`, which must be contiguous.
+///
+/// See [concat] for more details.
+pub fn concat_ref<R: Runtime>(
+    client: &ComputeClient<R>,
+    inputs: &[TensorHandleRef<'_, R>],
+    output: &TensorHandleRef<'_, R>,
+    dim: usize,
+    dtype: StorageType,
+) -> Result<(), LaunchError> {
+    assert_pieces_shapes(inputs, output, dim);
+    assert!(
+        is_contiguous(output.shape, output.strides),
+        "Output should be contiguous"
+    );
+
+    let line_size = pieces_line_size(client, inputs, output, dtype);
+    let num_elems: usize = output.shape.iter().product();
+    let working_units = num_elems / line_size;
+    let cube_dim = CubeDim::new(client, working_units);
+    let cube_count = calculate_cube_count_elemwise(client, working_units, cube_dim);
+
+    let inputs = inputs
+        .iter()
+        .map(|input| input.as_tensor_arg(line_size))
+        .collect();
+
+    unsafe {
+        concat_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            inputs,
+            output.as_tensor_arg(line_size),
+            ScalarArg::new(dim),
+            dtype,
+        )
+    }
+}
+
+/// Split `input` along `dim` into contiguous tensors of the given `sizes` in a single launch.
+///
+/// The sizes must add up to the size of `input` along `dim`.
+pub fn split<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    sizes: &[usize],
+    dim: usize,
+    dtype: StorageType,
+) -> Result<Vec<TensorHandle<R>>, LaunchError> {
+    let outputs = sizes
+        .iter()
+        .map(|size| {
+            let mut shape = input.shape.to_vec();
+            shape[dim] = *size;
+            empty_contiguous(client, shape, dtype)
+        })
+        .collect::<Vec<_>>();
+
+    let outputs_ref = outputs.iter().map(|it| it.as_ref()).collect::<Vec<_>>();
+    split_ref(client, input, &outputs_ref, dim, dtype)?;
+
+    Ok(outputs)
+}
+
+/// Split `input` along `dim` into `outputs`, which must be contiguous.
+///
+/// See [split] for more details.
+pub fn split_ref<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    outputs: &[TensorHandleRef<'_, R>],
+    dim: usize,
+    dtype: StorageType,
+) -> Result<(), LaunchError> {
+    assert_pieces_shapes(outputs, input, dim);
+    for (i, output) in outputs.iter().enumerate() {
+        assert!(
+            is_contiguous(output.shape, output.strides),
+            "Output {i} should be contiguous"
+        );
+    }
+
+    let line_size = pieces_line_size(client, outputs, input, dtype);
+    let num_elems: usize = input.shape.iter().product();
+    let working_units = num_elems / line_size;
+    let cube_dim = CubeDim::new(client, working_units);
+    let cube_count = calculate_cube_count_elemwise(client, working_units, cube_dim);
+
+    let outputs = outputs
+        .iter()
+        .map(|output| output.as_tensor_arg(line_size))
+        .collect();
+
+    unsafe {
+        split_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(line_size),
+            outputs,
+            ScalarArg::new(dim),
+            dtype,
+        )
+    }
+}
+
+/// Allocate a tensor with compact strides, which is required by the layout of the kernels
+/// indexing with [index_offset_with_layout].
+pub(crate) fn empty_contiguous<R: Runtime>(
+    client: &ComputeClient<R>,
+    shape: Vec<usize>,
+    dtype: StorageType,
+) -> TensorHandle<R> {
+    let num_elems: usize = shape.iter().product();
+    let handle = client.empty(num_elems * dtype.size());
+    TensorHandle::new_contiguous(shape, handle, dtype)
+}
+
+/// Validate that `pieces` concatenated along `dim` have the shape of `whole`.
+fn assert_pieces_shapes<R: Runtime>(
+    pieces: &[TensorHandleRef<'_, R>],
+    whole: &TensorHandleRef<'_, R>,
+    dim: usize,
+) {
+    let rank = whole.shape.len();
+    assert!(dim < rank, "Dim {dim} is out of bounds");
+
+    let mut size = 0;
+    for (i, piece) in pieces.iter().enumerate() {
+        assert_eq!(piece.shape.len(), rank, "Invalid rank for tensor {i}");
+        for axis in (0..rank).filter(|axis| *axis != dim) {
+            assert_eq!(
+                piece.shape[axis], whole.shape[axis],
+                "Invalid shape on axis {axis} for tensor {i}"
+            );
+        }
+        size += piece.shape[dim];
+    }
+    assert_eq!(size, whole.shape[dim], "Invalid total size on axis {dim}");
+}
+
+/// Find the line size usable by all the tensors along their last dimension.
+fn pieces_line_size<R: Runtime>(
+    client: &ComputeClient<R>,
+    pieces: &[TensorHandleRef<'_, R>],
+    whole: &TensorHandleRef<'_, R>,
+    dtype: StorageType,
+) -> LineSize {
+    let rank = whole.shape.len();
+
+    pieces
+        .iter()
+        .chain([whole])
+        .map(|tensor| {
+            tensor_line_size_parallel(
+                client.io_optimized_line_sizes(&dtype),
+                tensor.shape,
+                tensor.strides,
+                rank - 1,
+            )
+        })
+        .min()
+        .unwrap_or(1)
+}
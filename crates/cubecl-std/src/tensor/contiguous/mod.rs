@@ -1,7 +1,11 @@
 mod base;
+mod concat;
 mod launch;
+mod pad;
 mod perpendicular;
 
 pub use base::*;
+pub use concat::*;
 pub use launch::*;
+pub use pad::*;
 pub use perpendicular::*;
@@ -0,0 +1,181 @@
+use cubecl::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise, tensor_line_size_parallel};
+
+use crate::tensor::{TensorHandle, empty_contiguous, is_contiguous};
+
+/// How the padded positions are filled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PadMode {
+    /// Fill with a constant value.
+    Constant(f64),
+    /// Mirror the input without repeating the edge, so `[1, 2, 3]` padded by 2 on each side
+    /// gives `[3, 2, 1, 2, 3, 2, 1]`. Padding must be smaller than the input size.
+    Reflect,
+    /// Repeat the edge value, so `[1, 2, 3]` padded by 2 on each side gives
+    /// `[1, 1, 1, 2, 3, 3, 3]`.
+    Replicate,
+}
+
+/// Comptime version of [PadMode], without the constant value.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+enum PadKind {
+    Constant,
+    Reflect,
+    Replicate,
+}
+
+#[cube(launch_unchecked)]
+fn pad_kernel<N: Numeric>(
+    input: &Tensor<Line<N>>,
+    output: &mut Tensor<Line<N>>,
+    padding_before: Sequence<usize>,
+    value: InputScalar,
+    #[comptime] kind: PadKind,
+    #[define(N)] _dtype: StorageType,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    let line_size = output.line_size();
+    let offset_ref = ABSOLUTE_POS * line_size;
+    let mut offset = 0;
+    let mut in_bounds = true;
+
+    #[unroll]
+    for i in 0..padding_before.len() {
+        let before = padding_before[i];
+        let size = input.shape(i);
+        let coord = (offset_ref / output.stride(i)) % output.shape(i);
+
+        let coord_input = match kind {
+            PadKind::Constant => {
+                in_bounds &= coord >= before && coord < before + size;
+                // Clamped so coordinates in the padding before the input don't underflow.
+                Max::max(coord, before) - before
+            }
+            PadKind::Reflect => {
+                if coord < before {
+                    before - coord
+                } else if coord - before >= size {
+                    2 * (size - 1) - (coord - before)
+                } else {
+                    coord - before
+                }
+            }
+            PadKind::Replicate => Min::min(Max::max(coord, before), before + size - 1) - before,
+        };
+
+        offset += coord_input * input.stride(i);
+    }
+
+    if in_bounds {
+        output[ABSOLUTE_POS] = input[offset / line_size];
+    } else {
+        output[ABSOLUTE_POS] = Line::empty(line_size).fill(value.get::<N>());
+    }
+}
+
+/// Pad `input` with `padding[d] = (before, after)` elements on each side of each dimension `d`.
+///
+/// The output is contiguous.
+pub fn pad<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    padding: &[(usize, usize)],
+    mode: PadMode,
+    dtype: StorageType,
+) -> Result<TensorHandle<R>, LaunchError> {
+    let shape = input
+        .shape
+        .iter()
+        .zip(padding)
+        .map(|(size, (before, after))| before + size + after)
+        .collect();
+
+    let output = empty_contiguous(client, shape, dtype);
+    pad_ref(client, input, &output.as_ref(), padding, mode, dtype)?;
+
+    Ok(output)
+}
+
+/// Pad `input` into `output`, which must be contiguous.
+///
+/// See [pad] for more details.
+pub fn pad_ref<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    output: &TensorHandleRef<'_, R>,
+    padding: &[(usize, usize)],
+    mode: PadMode,
+    dtype: StorageType,
+) -> Result<(), LaunchError> {
+    let rank = input.shape.len();
+    assert_eq!(padding.len(), rank, "Padding should be given for each axis");
+    assert!(
+        is_contiguous(output.shape, output.strides),
+        "Output should be contiguous"
+    );
+    for (axis, (size, (before, after))) in input.shape.iter().zip(padding).enumerate() {
+        assert_eq!(
+            before + size + after,
+            output.shape[axis],
+            "Invalid output shape on axis {axis}"
+        );
+        match mode {
+            PadMode::Constant(_) => {}
+            PadMode::Reflect => assert!(
+                *before < *size && *after < *size,
+                "Reflect padding should be smaller than the input on axis {axis}"
+            ),
+            PadMode::Replicate => assert!(*size > 0, "Can't replicate empty axis {axis}"),
+        }
+    }
+
+    // Lines can't be used when they would contain both padding and values.
+    let line_size = match padding[rank - 1] {
+        (0, 0) => [input, output]
+            .iter()
+            .map(|tensor| {
+                tensor_line_size_parallel(
+                    client.io_optimized_line_sizes(&dtype),
+                    tensor.shape,
+                    tensor.strides,
+                    rank - 1,
+                )
+            })
+            .min()
+            .unwrap_or(1),
+        _ => 1,
+    };
+
+    let (kind, value) = match mode {
+        PadMode::Constant(value) => (PadKind::Constant, value),
+        PadMode::Reflect => (PadKind::Reflect, 0.0),
+        PadMode::Replicate => (PadKind::Replicate, 0.0),
+    };
+
+    let num_elems: usize = output.shape.iter().product();
+    let working_units = num_elems / line_size;
+    let cube_dim = CubeDim::new(client, working_units);
+    let cube_count = calculate_cube_count_elemwise(client, working_units, cube_dim);
+
+    let padding_before = padding
+        .iter()
+        .map(|(before, _)| ScalarArg::new(*before))
+        .collect();
+
+    unsafe {
+        pad_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(line_size),
+            output.as_tensor_arg(line_size),
+            padding_before,
+            InputScalar::new(value, dtype),
+            kind,
+            dtype,
+        )
+    }
+}
@@ -0,0 +1,298 @@
+use cubecl::prelude::*;
+use cubecl_core::{self as cubecl, server::CubeCountSelection, tensor_line_size_parallel};
+
+use crate::{
+    CubeOption, CubeOptionExpand,
+    tensor::{
+        TensorHandle,
+        layout::linear::{LinearView, linear_view},
+    },
+};
+
+use super::NUM_SM_APPROX;
+
+/// Number of cubes launched per streaming multiprocessor. Each cube loops over the input, so more
+/// cubes only means more bins to merge in the privatized version.
+const CUBES_PER_SM: u32 = 4;
+
+/// How values are mapped to bins.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum BinMapping {
+    /// Values are split into equal width bins over a `[min, max]` range. Values outside the range
+    /// are ignored, and `max` belongs to the last bin.
+    Range,
+    /// Integer values are used as the bin index. Values outside of `[0, num_bins)` are ignored.
+    Index,
+}
+
+/// Where the bins are accumulated during the computation.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum HistogramStrategy {
+    /// Each cube accumulates into its own bins in shared memory, which are then merged into the
+    /// output with global atomics.
+    Privatized,
+    /// All units accumulate directly into the output with global atomics.
+    Global,
+}
+
+impl HistogramStrategy {
+    /// Select the privatized strategy when the bins fit in shared memory.
+    pub fn new<R: Runtime>(client: &ComputeClient<R>, num_bins: usize, count: StorageType) -> Self {
+        let max_shared = client.properties().hardware.max_shared_memory_size;
+
+        match num_bins * count.size() <= max_shared {
+            true => HistogramStrategy::Privatized,
+            false => HistogramStrategy::Global,
+        }
+    }
+}
+
+#[cube(launch_unchecked)]
+fn histogram_kernel<N: Numeric, C: Numeric>(
+    input: &LinearView<Line<N>>,
+    weights: &CubeOption<LinearView<Line<C>>>,
+    output: &mut Tensor<Atomic<C>>,
+    min: f32,
+    max: f32,
+    #[comptime] num_bins: usize,
+    #[comptime] mapping: BinMapping,
+    #[comptime] strategy: HistogramStrategy,
+    #[define(N, C)] _dtypes: [StorageType; 2],
+) {
+    match strategy {
+        HistogramStrategy::Privatized => {
+            let mut bins = SharedMemory::<Atomic<C>>::new(num_bins);
+
+            let mut bin = UNIT_POS as usize;
+            while bin < num_bins {
+                Atomic::store(&bins[bin], C::from_int(0));
+                bin += CUBE_DIM as usize;
+            }
+
+            sync_cube();
+
+            accumulate(
+                input,
+                weights,
+                &mut bins.to_slice_mut(),
+                min,
+                max,
+                num_bins,
+                mapping,
+            );
+
+            sync_cube();
+
+            let mut bin = UNIT_POS as usize;
+            while bin < num_bins {
+                let count = Atomic::load(&bins[bin]);
+                if count != C::from_int(0) {
+                    Atomic::add(&output[bin], count);
+                }
+                bin += CUBE_DIM as usize;
+            }
+        }
+        HistogramStrategy::Global => {
+            accumulate(
+                input,
+                weights,
+                &mut output.to_slice_mut(),
+                min,
+                max,
+                num_bins,
+                mapping,
+            );
+        }
+    }
+}
+
+/// Accumulate the values processed by this unit into `bins`, looping over the input with a
+/// stride of the total number of units.
+#[cube]
+fn accumulate<N: Numeric, C: Numeric>(
+    input: &LinearView<Line<N>>,
+    weights: &CubeOption<LinearView<Line<C>>>,
+    bins: &mut SliceMut<Atomic<C>>,
+    min: f32,
+    max: f32,
+    #[comptime] num_bins: usize,
+    #[comptime] mapping: BinMapping,
+) {
+    let line_size = input.line_size();
+    let num_units = CUBE_COUNT * CUBE_DIM as usize;
+    let scale = num_bins as f32 / (max - min);
+
+    let mut pos = ABSOLUTE_POS;
+    while pos < input.shape() {
+        let value = input[pos];
+        let weight = match weights {
+            CubeOption::Some(weights) => weights[pos],
+            CubeOption::None => Line::empty(line_size).fill(C::from_int(1)),
+        };
+
+        #[unroll]
+        for k in 0..line_size {
+            let (bin, in_range) = match mapping {
+                BinMapping::Range => {
+                    let value = f32::cast_from(value[k]);
+                    let bin = u32::cast_from((value - min) * scale) as usize;
+                    // `max` itself belongs to the last bin.
+                    (Min::min(bin, num_bins - 1), value >= min && value <= max)
+                }
+                BinMapping::Index => {
+                    // Negative values wrap around and are filtered as out of range.
+                    let bin = usize::cast_from(value[k]);
+                    (bin, bin < num_bins)
+                }
+            };
+
+            if in_range {
+                Atomic::add(&bins[bin], weight[k]);
+            }
+        }
+
+        pos += num_units;
+    }
+}
+
+/// Compute the histogram of `input` with `num_bins` equal width bins over `[min, max]`.
+///
+/// Values outside of the range, including `NaN`, are ignored. Returns the `u32` count of each
+/// bin.
+pub fn histogram<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    num_bins: usize,
+    min: f32,
+    max: f32,
+    dtype: StorageType,
+) -> Result<TensorHandle<R>, LaunchError> {
+    assert!(min < max, "Histogram range should not be empty");
+
+    let count = u32::as_type_native_unchecked();
+    let output = TensorHandle::zeros(client, vec![num_bins], count);
+    let strategy = HistogramStrategy::new(client, num_bins, count);
+
+    launch_ref(
+        client,
+        input,
+        None,
+        &output.as_ref(),
+        (min, max),
+        BinMapping::Range,
+        strategy,
+        [dtype, count],
+    )?;
+
+    Ok(output)
+}
+
+/// Count the occurrences of each integer value of `input` in `[0, num_bins)`, optionally
+/// weighted by `weights`.
+///
+/// Values outside of the range are ignored. Returns the `u32` count of each bin when unweighted,
+/// or the sum of the weights in each bin with the type `weight_dtype`.
+pub fn bincount<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    weights: Option<&TensorHandleRef<'_, R>>,
+    num_bins: usize,
+    dtype: StorageType,
+    weight_dtype: StorageType,
+) -> Result<TensorHandle<R>, LaunchError> {
+    let count = match weights {
+        Some(_) => weight_dtype,
+        None => u32::as_type_native_unchecked(),
+    };
+    let output = TensorHandle::zeros(client, vec![num_bins], count);
+    let strategy = HistogramStrategy::new(client, num_bins, count);
+
+    launch_ref(
+        client,
+        input,
+        weights,
+        &output.as_ref(),
+        (0.0, 0.0),
+        BinMapping::Index,
+        strategy,
+        [dtype, count],
+    )?;
+
+    Ok(output)
+}
+
+/// Accumulate the histogram of `input` into `output`, which must be a 1D tensor of
+/// `num_bins` elements.
+///
+/// The output is not cleared, so multiple inputs can be accumulated into the same bins.
+/// `dtypes` contains the type of the input and the type of the bins, which must also be the type
+/// of the weights when present. The device must support atomic adds on the bin type.
+#[allow(clippy::too_many_arguments)]
+pub fn launch_ref<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    weights: Option<&TensorHandleRef<'_, R>>,
+    output: &TensorHandleRef<'_, R>,
+    (min, max): (f32, f32),
+    mapping: BinMapping,
+    strategy: HistogramStrategy,
+    dtypes: [StorageType; 2],
+) -> Result<(), LaunchError> {
+    assert_eq!(output.shape.len(), 1, "Bins should be a 1D tensor");
+    let num_bins = output.shape[0];
+    let rank = input.shape.len();
+
+    let mut line_size = tensor_line_size_parallel(
+        client.io_optimized_line_sizes(&dtypes[0]),
+        input.shape,
+        input.strides,
+        rank - 1,
+    );
+    if let Some(weights) = weights {
+        assert_eq!(
+            input.shape, weights.shape,
+            "Weights should have the same shape as the input"
+        );
+        let weights_line_size = tensor_line_size_parallel(
+            client.io_optimized_line_sizes(&dtypes[1]),
+            weights.shape,
+            weights.strides,
+            rank - 1,
+        );
+        line_size = Ord::min(line_size, weights_line_size);
+    }
+
+    let num_elems: usize = input.shape.iter().product();
+    let working_units = num_elems / line_size;
+    let cube_dim = CubeDim::new(client, working_units);
+
+    let num_sm = client
+        .properties()
+        .hardware
+        .num_streaming_multiprocessors
+        .unwrap_or(NUM_SM_APPROX);
+    let num_cubes = Ord::min(
+        working_units.div_ceil(cube_dim.num_elems() as usize),
+        (num_sm * CUBES_PER_SM) as usize,
+    );
+    let cube_count = CubeCountSelection::new(client, num_cubes as u32).cube_count();
+
+    let weights = weights.map(|weights| linear_view(client, weights, line_size));
+
+    unsafe {
+        histogram_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            linear_view(client, input, line_size),
+            weights.into(),
+            output.as_tensor_arg(1),
+            ScalarArg::new(min),
+            ScalarArg::new(max),
+            num_bins,
+            mapping,
+            strategy,
+            dtypes,
+        )
+    }
+}
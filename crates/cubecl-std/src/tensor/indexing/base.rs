@@ -0,0 +1,100 @@
+use cubecl::prelude::*;
+use cubecl_core::{self as cubecl, ir::LineSize, tensor_line_size_parallel};
+
+/// The reduction applied when multiple values are scattered to the same position.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ScatterReduction {
+    /// Accumulate all values with the existing value.
+    Add,
+    /// Keep the maximum between all values and the existing value.
+    Max,
+    /// Keep the minimum between all values and the existing value.
+    Min,
+}
+
+/// Decomposes the linear position `pos` of the `reference` tensor into offsets in `first` and
+/// `second`, where `second` skips the indexed `dim`.
+///
+/// Returns `(offset_reference, offset_first, offset_second)`, in number of elements.
+#[cube]
+pub(crate) fn indexed_offsets<A: CubePrimitive, B: CubePrimitive, C: CubePrimitive>(
+    reference: &Tensor<Line<A>>,
+    first: &Tensor<Line<B>>,
+    second: &Tensor<C>,
+    pos: usize,
+    dim: usize,
+) -> (usize, usize, usize) {
+    let rank = reference.rank();
+    let mut remainder = pos * reference.line_size();
+    let mut offset_reference = 0;
+    let mut offset_first = 0;
+    let mut offset_second = 0;
+
+    for i in 0..rank {
+        let d = rank - i - 1;
+        let shape = reference.shape(d);
+        let coord = remainder % shape;
+        remainder /= shape;
+
+        offset_reference += coord * reference.stride(d);
+        offset_first += coord * first.stride(d);
+        if d != dim {
+            offset_second += coord * second.stride(d);
+        }
+    }
+
+    (offset_reference, offset_first, offset_second)
+}
+
+/// Find the line size usable by all the given tensors along their last dimension, each tensor
+/// being paired with its own element type.
+///
+/// Lines are only used when the indexed dimension isn't the innermost one, since each lane of a
+/// line would otherwise point to a different indexed position.
+pub(crate) fn indexed_line_size<R: Runtime>(
+    client: &ComputeClient<R>,
+    tensors: &[(&TensorHandleRef<'_, R>, StorageType)],
+    dim: usize,
+) -> LineSize {
+    let rank = tensors[0].0.shape.len();
+    if dim == rank - 1 {
+        return 1;
+    }
+
+    tensors
+        .iter()
+        .map(|(tensor, dtype)| {
+            tensor_line_size_parallel(
+                client.io_optimized_line_sizes(dtype),
+                tensor.shape,
+                tensor.strides,
+                rank - 1,
+            )
+        })
+        .min()
+        .unwrap_or(1)
+}
+
+/// Validate the shapes of the tensors involved in a gather or scatter along `dim`.
+pub(crate) fn assert_indexed_shapes(data: &[usize], indices: &[usize], dim: usize) {
+    assert_eq!(
+        data.len(),
+        indices.len(),
+        "Indices should have the same rank as the indexed tensor"
+    );
+    assert!(dim < data.len(), "Dim {dim} is out of bounds");
+    for (axis, (data, index)) in data.iter().zip(indices).enumerate() {
+        assert!(
+            axis == dim || index <= data,
+            "Indices shape {indices:?} is larger than {data:?} on axis {axis}"
+        );
+    }
+}
+
+/// Validate that the scattered values cover the shape of the indices.
+pub(crate) fn assert_values_shape(values: &[usize], indices: &[usize]) {
+    assert!(
+        values.len() == indices.len() && values.iter().zip(indices).all(|(v, i)| i <= v),
+        "Values shape {values:?} should be at least as large as indices shape {indices:?}"
+    );
+}
@@ -0,0 +1,100 @@
+use cubecl::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use super::{assert_indexed_shapes, indexed_line_size, indexed_offsets};
+use crate::tensor::TensorHandle;
+
+#[cube(launch_unchecked)]
+fn gather_kernel<N: Numeric, I: Int>(
+    input: &Tensor<N>,
+    indices: &Tensor<Line<I>>,
+    output: &mut Tensor<Line<N>>,
+    dim: usize,
+    #[define(N, I)] _dtypes: [StorageType; 2],
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    let line_size = output.line_size();
+    let (offset_output, offset_indices, offset_input) =
+        indexed_offsets(output, indices, input, ABSOLUTE_POS, dim);
+
+    let stride_dim = input.stride(dim);
+    let stride_last = input.stride(input.rank() - 1);
+    let index = indices[offset_indices / line_size];
+    let mut value = Line::empty(line_size);
+
+    #[unroll]
+    for k in 0..line_size {
+        let pos = usize::cast_from(index[k]);
+        value[k] = input[offset_input + k * stride_last + pos * stride_dim];
+    }
+
+    output[offset_output / line_size] = value;
+}
+
+/// Gather values of `input` along `dim` at the positions given by `indices`.
+///
+/// The output has the shape of `indices`, with
+/// `output[i][j][k] = input[indices[i][j][k]][j][k]` when `dim == 0`.
+/// Indices must be non-negative and smaller than `input.shape[dim]`.
+pub fn gather<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    indices: &TensorHandleRef<'_, R>,
+    dim: usize,
+    dtype: StorageType,
+    index_dtype: StorageType,
+) -> Result<TensorHandle<R>, LaunchError> {
+    let output = TensorHandle::empty(client, indices.shape.to_vec(), dtype);
+    gather_ref(
+        client,
+        input,
+        indices,
+        &output.as_ref(),
+        dim,
+        dtype,
+        index_dtype,
+    )?;
+
+    Ok(output)
+}
+
+/// Gather values of `input` along `dim` at the positions given by `indices` into `output`.
+///
+/// See [gather] for more details.
+pub fn gather_ref<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    indices: &TensorHandleRef<'_, R>,
+    output: &TensorHandleRef<'_, R>,
+    dim: usize,
+    dtype: StorageType,
+    index_dtype: StorageType,
+) -> Result<(), LaunchError> {
+    assert_indexed_shapes(input.shape, indices.shape, dim);
+    assert_eq!(
+        indices.shape, output.shape,
+        "Output should have the shape of the indices"
+    );
+
+    let line_size = indexed_line_size(client, &[(output, dtype), (indices, index_dtype)], dim);
+    let num_elems: usize = output.shape.iter().product();
+    let working_units = num_elems / line_size;
+    let cube_dim = CubeDim::new(client, working_units);
+    let cube_count = calculate_cube_count_elemwise(client, working_units, cube_dim);
+
+    unsafe {
+        gather_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            indices.as_tensor_arg(line_size),
+            output.as_tensor_arg(line_size),
+            ScalarArg::new(dim),
+            [dtype, index_dtype],
+        )
+    }
+}
@@ -0,0 +1,9 @@
+mod base;
+mod gather;
+mod scatter;
+mod select;
+
+pub use base::*;
+pub use gather::*;
+pub use scatter::*;
+pub use select::*;
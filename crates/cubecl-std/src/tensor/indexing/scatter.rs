@@ -0,0 +1,154 @@
+use cubecl::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use super::{
+    ScatterReduction, assert_indexed_shapes, assert_values_shape, indexed_line_size,
+    indexed_offsets,
+};
+
+#[cube(launch_unchecked)]
+fn scatter_kernel<N: Numeric, I: Int>(
+    indices: &Tensor<Line<I>>,
+    values: &Tensor<Line<N>>,
+    output: &mut Tensor<N>,
+    dim: usize,
+    #[define(N, I)] _dtypes: [StorageType; 2],
+) {
+    if ABSOLUTE_POS >= indices.len() {
+        terminate!();
+    }
+
+    let line_size = indices.line_size();
+    let (offset_indices, offset_values, offset_output) =
+        indexed_offsets(indices, values, output, ABSOLUTE_POS, dim);
+
+    let stride_dim = output.stride(dim);
+    let stride_last = output.stride(output.rank() - 1);
+    let index = indices[offset_indices / line_size];
+    let value = values[offset_values / line_size];
+
+    #[unroll]
+    for k in 0..line_size {
+        let pos = usize::cast_from(index[k]);
+        output[offset_output + k * stride_last + pos * stride_dim] = value[k];
+    }
+}
+
+#[cube(launch_unchecked)]
+fn scatter_reduce_kernel<N: Numeric, I: Int>(
+    indices: &Tensor<Line<I>>,
+    values: &Tensor<Line<N>>,
+    output: &mut Tensor<Atomic<N>>,
+    dim: usize,
+    #[comptime] reduction: ScatterReduction,
+    #[define(N, I)] _dtypes: [StorageType; 2],
+) {
+    if ABSOLUTE_POS >= indices.len() {
+        terminate!();
+    }
+
+    let line_size = indices.line_size();
+    let (offset_indices, offset_values, offset_output) =
+        indexed_offsets(indices, values, output, ABSOLUTE_POS, dim);
+
+    let stride_dim = output.stride(dim);
+    let stride_last = output.stride(output.rank() - 1);
+    let index = indices[offset_indices / line_size];
+    let value = values[offset_values / line_size];
+
+    #[unroll]
+    for k in 0..line_size {
+        let pos = usize::cast_from(index[k]);
+        let out = &output[offset_output + k * stride_last + pos * stride_dim];
+
+        match reduction {
+            ScatterReduction::Add => {
+                Atomic::add(out, value[k]);
+            }
+            ScatterReduction::Max => {
+                Atomic::max(out, value[k]);
+            }
+            ScatterReduction::Min => {
+                Atomic::min(out, value[k]);
+            }
+        }
+    }
+}
+
+/// Write `values` into `output` along `dim` at the positions given by `indices`.
+///
+/// For `dim == 0`, this computes `output[indices[i][j][k]][j][k] = values[i][j][k]`.
+/// `values` must have at least the shape of `indices`. When multiple indices point to the same
+/// position, which value is written is unspecified. Use [scatter_reduce_ref] to combine them.
+pub fn scatter_ref<R: Runtime>(
+    client: &ComputeClient<R>,
+    output: &TensorHandleRef<'_, R>,
+    indices: &TensorHandleRef<'_, R>,
+    values: &TensorHandleRef<'_, R>,
+    dim: usize,
+    dtype: StorageType,
+    index_dtype: StorageType,
+) -> Result<(), LaunchError> {
+    assert_indexed_shapes(output.shape, indices.shape, dim);
+    assert_values_shape(values.shape, indices.shape);
+
+    let line_size = indexed_line_size(client, &[(indices, index_dtype), (values, dtype)], dim);
+    let num_elems: usize = indices.shape.iter().product();
+    let working_units = num_elems / line_size;
+    let cube_dim = CubeDim::new(client, working_units);
+    let cube_count = calculate_cube_count_elemwise(client, working_units, cube_dim);
+
+    unsafe {
+        scatter_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            indices.as_tensor_arg(line_size),
+            values.as_tensor_arg(line_size),
+            output.as_tensor_arg(1),
+            ScalarArg::new(dim),
+            [dtype, index_dtype],
+        )
+    }
+}
+
+/// Combine `values` into `output` along `dim` at the positions given by `indices`, using
+/// atomics to apply the `reduction`.
+///
+/// See [scatter_ref] for the indexing semantics. The device must support the atomic operation
+/// required by the `reduction` on `dtype`, see
+/// [TypeUsage](cubecl_core::ir::features::TypeUsage).
+#[allow(clippy::too_many_arguments)]
+pub fn scatter_reduce_ref<R: Runtime>(
+    client: &ComputeClient<R>,
+    output: &TensorHandleRef<'_, R>,
+    indices: &TensorHandleRef<'_, R>,
+    values: &TensorHandleRef<'_, R>,
+    dim: usize,
+    reduction: ScatterReduction,
+    dtype: StorageType,
+    index_dtype: StorageType,
+) -> Result<(), LaunchError> {
+    assert_indexed_shapes(output.shape, indices.shape, dim);
+    assert_values_shape(values.shape, indices.shape);
+
+    let line_size = indexed_line_size(client, &[(indices, index_dtype), (values, dtype)], dim);
+    let num_elems: usize = indices.shape.iter().product();
+    let working_units = num_elems / line_size;
+    let cube_dim = CubeDim::new(client, working_units);
+    let cube_count = calculate_cube_count_elemwise(client, working_units, cube_dim);
+
+    unsafe {
+        scatter_reduce_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            indices.as_tensor_arg(line_size),
+            values.as_tensor_arg(line_size),
+            output.as_tensor_arg(1),
+            ScalarArg::new(dim),
+            reduction,
+            [dtype, index_dtype],
+        )
+    }
+}
@@ -0,0 +1,113 @@
+use cubecl::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use super::indexed_line_size;
+use crate::tensor::TensorHandle;
+
+#[cube(launch_unchecked)]
+fn index_select_kernel<N: Numeric, I: Int>(
+    input: &Tensor<Line<N>>,
+    indices: &Tensor<I>,
+    output: &mut Tensor<Line<N>>,
+    dim: usize,
+    #[define(N, I)] _dtypes: [StorageType; 2],
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    let rank = output.rank();
+    let line_size = output.line_size();
+    let mut remainder = ABSOLUTE_POS * line_size;
+    let mut offset_input = 0;
+    let mut offset_output = 0;
+
+    for i in 0..rank {
+        let d = rank - i - 1;
+        let shape = output.shape(d);
+        let coord = remainder % shape;
+        remainder /= shape;
+
+        offset_output += coord * output.stride(d);
+        if d == dim {
+            let pos = usize::cast_from(indices[coord * indices.stride(0)]);
+            offset_input += pos * input.stride(d);
+        } else {
+            offset_input += coord * input.stride(d);
+        }
+    }
+
+    output[offset_output / line_size] = input[offset_input / line_size];
+}
+
+/// Select the slices of `input` along `dim` at the positions given by the 1D `indices` tensor.
+///
+/// The output has the shape of `input`, except for `dim` which has the length of `indices`.
+/// Indices must be non-negative and smaller than `input.shape[dim]`.
+pub fn index_select<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    indices: &TensorHandleRef<'_, R>,
+    dim: usize,
+    dtype: StorageType,
+    index_dtype: StorageType,
+) -> Result<TensorHandle<R>, LaunchError> {
+    let mut shape = input.shape.to_vec();
+    shape[dim] = indices.shape[0];
+
+    let output = TensorHandle::empty(client, shape, dtype);
+    index_select_ref(
+        client,
+        input,
+        indices,
+        &output.as_ref(),
+        dim,
+        dtype,
+        index_dtype,
+    )?;
+
+    Ok(output)
+}
+
+/// Select the slices of `input` along `dim` at the positions given by `indices` into `output`.
+///
+/// See [index_select] for more details.
+pub fn index_select_ref<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    indices: &TensorHandleRef<'_, R>,
+    output: &TensorHandleRef<'_, R>,
+    dim: usize,
+    dtype: StorageType,
+    index_dtype: StorageType,
+) -> Result<(), LaunchError> {
+    assert_eq!(indices.shape.len(), 1, "Indices should be a 1D tensor");
+    assert!(dim < input.shape.len(), "Dim {dim} is out of bounds");
+    for (axis, (input, output)) in input.shape.iter().zip(output.shape).enumerate() {
+        let expected = if axis == dim {
+            indices.shape[0]
+        } else {
+            *input
+        };
+        assert_eq!(expected, *output, "Invalid output shape on axis {axis}");
+    }
+
+    let line_size = indexed_line_size(client, &[(input, dtype), (output, dtype)], dim);
+    let num_elems: usize = output.shape.iter().product();
+    let working_units = num_elems / line_size;
+    let cube_dim = CubeDim::new(client, working_units);
+    let cube_count = calculate_cube_count_elemwise(client, working_units, cube_dim);
+
+    unsafe {
+        index_select_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(line_size),
+            indices.as_tensor_arg(1),
+            output.as_tensor_arg(line_size),
+            ScalarArg::new(dim),
+            [dtype, index_dtype],
+        )
+    }
+}
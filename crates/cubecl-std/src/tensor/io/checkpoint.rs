@@ -0,0 +1,353 @@
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use cubecl_core::prelude::*;
+use cubecl_core::server::{CopyDescriptor, Handle};
+
+use super::{TensorFileError, safetensors};
+use crate::tensor::{TensorHandle, into_contiguous_pitched_ref, is_contiguous_pitched};
+
+/// Default maximum number of bytes read back at once, see [TensorCheckpoint::chunk_size].
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024 * 1024;
+
+/// How the tensors of a checkpoint are laid out in the file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CheckpointFormat {
+    /// The contiguous data of all tensors, one after the other, without any header.
+    Raw,
+    /// A safetensors file, which can be loaded back with [TensorFile](super::TensorFile).
+    #[default]
+    Safetensors,
+}
+
+/// Writes device tensors to a file without blocking the compute stream.
+///
+/// The tensors are read back in chunks of at most [chunk_size](Self::chunk_size) bytes on a
+/// background thread, with at most [max_in_flight](Self::max_in_flight) chunks in host memory at
+/// once. Small enough chunks are read into pinned staging buffers by the runtimes that support
+/// them.
+///
+/// Tensors in the layout created by the runtime are read back directly, see
+/// [ComputeClient::read_tensor], while permuted or otherwise strided tensors are first copied to
+/// a contiguous tensor. They can't be modified in place until the checkpoint is written, since
+/// the checkpoint keeps a reference to their memory.
+///
+/// # Example
+///
+/// ```ignore
+/// let written = TensorCheckpoint::new("model.safetensors")
+///     .tensor("weight", &weight)
+///     .tensor("bias", &bias)
+///     .write(&client);
+/// // Keep launching kernels, then wait for the file to be durable.
+/// written.await?;
+/// ```
+pub struct TensorCheckpoint<R: Runtime> {
+    path: PathBuf,
+    format: CheckpointFormat,
+    chunk_size: usize,
+    max_in_flight: usize,
+    tensors: Vec<(String, TensorHandle<R>)>,
+}
+
+impl<R: Runtime> TensorCheckpoint<R> {
+    /// Create a checkpoint written to the given path.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            format: CheckpointFormat::default(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_in_flight: 2,
+            tensors: Vec::new(),
+        }
+    }
+
+    /// The layout of the file.
+    pub fn format(mut self, format: CheckpointFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// The maximum number of bytes read back at once.
+    ///
+    /// Tensors bigger than the chunk size are read in slices of their first dimension.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// The maximum number of chunks read back but not yet written to the file.
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = Ord::max(max_in_flight, 1);
+        self
+    }
+
+    /// Add a tensor to the checkpoint.
+    pub fn tensor<N: Into<String>>(mut self, name: N, tensor: &TensorHandle<R>) -> Self {
+        self.tensors.push((name.into(), tensor.clone()));
+        self
+    }
+
+    /// Start writing the checkpoint on a background thread.
+    ///
+    /// The returned future resolves once all the data is written and synced to disk.
+    pub fn write(self, client: &ComputeClient<R>) -> CheckpointFuture {
+        let header = match self.format {
+            CheckpointFormat::Raw => Vec::new(),
+            CheckpointFormat::Safetensors => {
+                let tensors = self
+                    .tensors
+                    .iter()
+                    .map(|(name, tensor)| (name.clone(), tensor.shape.clone(), tensor.dtype))
+                    .collect::<Vec<_>>();
+                match safetensors::write_header(&tensors) {
+                    Ok(header) => header,
+                    Err(err) => return CheckpointFuture::ready(Err(err)),
+                }
+            }
+        };
+
+        let mut tensors = Vec::with_capacity(self.tensors.len());
+        for (name, tensor) in self.tensors {
+            match contiguous_rows(client, tensor) {
+                Ok(tensor) => tensors.push((name, tensor)),
+                Err(err) => return CheckpointFuture::ready(Err(err)),
+            }
+        }
+
+        let chunks = plan_chunks(&tensors, self.chunk_size);
+        let state = Arc::new(Mutex::new(CheckpointState::default()));
+        let future = CheckpointFuture {
+            state: state.clone(),
+        };
+        let client = client.clone();
+
+        std::thread::spawn(move || {
+            let result = write_chunks(&client, &self.path, &header, &chunks, self.max_in_flight);
+            let mut state = state.lock().unwrap();
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+
+        future
+    }
+}
+
+impl<R: Runtime> TensorHandle<R> {
+    /// Write the contiguous data of the tensor to a file without blocking the compute stream.
+    ///
+    /// See [TensorCheckpoint] to write multiple tensors or use the safetensors format.
+    pub fn checkpoint<P: AsRef<Path>>(
+        &self,
+        client: &ComputeClient<R>,
+        path: P,
+    ) -> CheckpointFuture {
+        TensorCheckpoint::new(path)
+            .format(CheckpointFormat::Raw)
+            .tensor("tensor", self)
+            .write(client)
+    }
+}
+
+/// Resolves when a checkpoint is durably written to disk.
+#[must_use = "the checkpoint isn't guaranteed to be written until the future resolves"]
+pub struct CheckpointFuture {
+    state: Arc<Mutex<CheckpointState>>,
+}
+
+#[derive(Default)]
+struct CheckpointState {
+    result: Option<Result<(), TensorFileError>>,
+    waker: Option<Waker>,
+}
+
+impl CheckpointFuture {
+    fn ready(result: Result<(), TensorFileError>) -> Self {
+        let state = CheckpointState {
+            result: Some(result),
+            waker: None,
+        };
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+}
+
+impl Future for CheckpointFuture {
+    type Output = Result<(), TensorFileError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Make the rows of the tensor contiguous, since the chunks are sliced along the first dimension
+/// and read back with a pitched layout.
+fn contiguous_rows<R: Runtime>(
+    client: &ComputeClient<R>,
+    tensor: TensorHandle<R>,
+) -> Result<TensorHandle<R>, TensorFileError> {
+    if tensor.shape.is_empty() || is_contiguous_pitched(&tensor.shape, &tensor.strides) {
+        return Ok(tensor);
+    }
+
+    Ok(into_contiguous_pitched_ref(
+        client,
+        &tensor.as_ref(),
+        tensor.dtype,
+    )?)
+}
+
+/// A contiguous part of a tensor, read back with a single copy.
+struct ChunkPart {
+    handle: Handle,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+    elem_size: usize,
+}
+
+/// Group the tensors in chunks of at most `chunk_size` bytes, splitting the tensors that don't fit
+/// along their first dimension.
+fn plan_chunks<R: Runtime>(
+    tensors: &[(String, TensorHandle<R>)],
+    chunk_size: usize,
+) -> Vec<Vec<ChunkPart>> {
+    let mut chunks = Vec::new();
+    let mut current = Vec::new();
+    let mut current_size = 0;
+
+    for (_, tensor) in tensors {
+        let elem_size = tensor.dtype.size();
+        // Scalars are read as a single element tensor.
+        let (shape, strides) = match tensor.shape.is_empty() {
+            true => (vec![1], vec![1]),
+            false => (tensor.shape.clone(), tensor.strides.clone()),
+        };
+        let row_size = shape[1..].iter().product::<usize>() * elem_size;
+        if row_size == 0 {
+            continue;
+        }
+        let rows_per_chunk = Ord::max(chunk_size / row_size, 1);
+        let row_pitch = (strides[0] * elem_size) as u64;
+
+        let mut row = 0;
+        while row < shape[0] {
+            let remaining = chunk_size.saturating_sub(current_size) / row_size;
+            let rows = match remaining >= shape[0] - row || remaining >= rows_per_chunk {
+                true => Ord::min(shape[0] - row, remaining),
+                false if current.is_empty() => Ord::min(shape[0] - row, rows_per_chunk),
+                false => {
+                    chunks.push(core::mem::take(&mut current));
+                    current_size = 0;
+                    continue;
+                }
+            };
+
+            let mut handle = tensor.handle.clone();
+            if rows != shape[0] {
+                let start = row as u64 * row_pitch;
+                let end = (row + rows) as u64 * row_pitch;
+                let end_offset = tensor.handle.size().saturating_sub(end);
+                handle = handle.offset_start(start).offset_end(end_offset);
+            }
+
+            let mut part_shape = shape.clone();
+            part_shape[0] = rows;
+            current.push(ChunkPart {
+                handle,
+                shape: part_shape,
+                strides: strides.clone(),
+                elem_size,
+            });
+            current_size += rows * row_size;
+            row += rows;
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// Read back the chunks and write them to the file, keeping at most `max_in_flight` chunks in
+/// host memory.
+fn write_chunks<R: Runtime>(
+    client: &ComputeClient<R>,
+    path: &Path,
+    header: &[u8],
+    chunks: &[Vec<ChunkPart>],
+    max_in_flight: usize,
+) -> Result<(), TensorFileError> {
+    // The checkpoint is written next to the destination and moved in place once complete, so an
+    // interrupted checkpoint never replaces a valid one.
+    let mut path_tmp = path.as_os_str().to_owned();
+    path_tmp.push(".tmp");
+
+    let mut file = BufWriter::new(File::create(&path_tmp)?);
+    file.write_all(header)?;
+
+    let mut chunks = chunks.iter();
+    let mut in_flight = VecDeque::new();
+
+    loop {
+        while in_flight.len() < max_in_flight
+            && let Some(chunk) = chunks.next()
+        {
+            let descriptors = chunk
+                .iter()
+                .map(|part| {
+                    part.handle
+                        .copy_descriptor(&part.shape, &part.strides, part.elem_size)
+                })
+                .collect::<Vec<CopyDescriptor<'_>>>();
+            in_flight.push_back(client.read_tensor_async(descriptors));
+        }
+
+        let Some(read) = in_flight.pop_front() else {
+            break;
+        };
+        for data in cubecl_common::reader::read_sync(read)? {
+            file.write_all(&data)?;
+        }
+    }
+
+    let file = file.into_inner().map_err(|err| err.into_error())?;
+    file.sync_all()?;
+    core::mem::drop(file);
+    std::fs::rename(&path_tmp, path)?;
+    sync_parent_dir(path)?;
+
+    Ok(())
+}
+
+/// Sync the directory containing `path`, so the rename of the checkpoint is durable.
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> std::io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(parent)?.sync_all()
+}
+
+/// Directories can't be opened on this platform, the rename is durable once the file is synced.
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
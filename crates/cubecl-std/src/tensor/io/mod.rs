@@ -0,0 +1,260 @@
+//! Loading of tensors stored in common file formats directly into device memory.
+//!
+//! Only the headers of the files are parsed on the host, the data is streamed to the device in
+//! chunks of file-backed [Bytes] that are moved to staging buffers right before being uploaded.
+//! Device tensors are written back to files with [TensorCheckpoint], which streams them to disk
+//! on a background thread.
+
+mod checkpoint;
+mod numpy;
+mod safetensors;
+
+pub use checkpoint::*;
+pub use numpy::npy_dtype;
+pub use safetensors::{safetensors_dtype, safetensors_dtype_name};
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use cubecl_common::bytes::Bytes;
+use cubecl_core::ir::StorageType;
+use cubecl_core::prelude::*;
+use cubecl_core::server::{AllocationDescriptor, IoError, LaunchError};
+
+use crate::tensor::TensorHandle;
+
+/// Default maximum number of bytes uploaded at once, see [TensorFile::with_chunk_size].
+const DEFAULT_CHUNK_SIZE: usize = 256 * 1024 * 1024;
+
+/// Error when reading or writing a tensor file.
+#[derive(Debug, thiserror::Error)]
+pub enum TensorFileError {
+    /// The file couldn't be read.
+    #[error("can't read the tensor file: {0}")]
+    Io(#[from] std::io::Error),
+    /// The header of the file is malformed.
+    #[error("invalid tensor file header: {0}")]
+    InvalidHeader(String),
+    /// The data type of a tensor has no corresponding [StorageType].
+    #[error("unsupported data type {0}")]
+    UnsupportedDtype(String),
+    /// The file uses a feature of its format that isn't supported.
+    #[error("unsupported tensor file: {0}")]
+    Unsupported(String),
+    /// No tensor with that name exists in the file.
+    #[error("tensor {0} not found")]
+    MissingTensor(String),
+    /// The data of a tensor couldn't be read back from the device.
+    #[error("can't read the tensor from the device: {0}")]
+    Device(#[from] IoError),
+    /// A kernel preparing the tensor for a transfer couldn't be launched.
+    #[error("can't launch the kernel preparing the tensor: {0}")]
+    Launch(#[from] LaunchError),
+}
+
+/// A contiguous, row-major tensor stored in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTensor {
+    /// The name of the tensor in the file.
+    pub name: String,
+    /// The shape of the tensor.
+    pub shape: Vec<usize>,
+    /// The type of the elements of the tensor.
+    pub dtype: StorageType,
+    /// The offset of the data in the file, in bytes.
+    pub offset: u64,
+    /// The size of the data, in bytes.
+    pub size: u64,
+}
+
+impl FileTensor {
+    /// The size in bytes of a tensor of `shape` and `dtype`, or `None` if it overflows.
+    fn byte_size(shape: &[usize], dtype: StorageType) -> Option<u64> {
+        shape
+            .iter()
+            .try_fold(dtype.size(), |size, dim| size.checked_mul(*dim))
+            .map(|size| size as u64)
+    }
+
+    // Sizes and offsets come from the header, so overflows are treated like any other corruption.
+    fn validate(self, file_len: u64) -> Result<Self, TensorFileError> {
+        if Self::byte_size(&self.shape, self.dtype) != Some(self.size) {
+            return Err(TensorFileError::InvalidHeader(format!(
+                "tensor {} of shape {:?} and type {} can't have {} bytes",
+                self.name, self.shape, self.dtype, self.size
+            )));
+        }
+        if self
+            .offset
+            .checked_add(self.size)
+            .is_none_or(|end| end > file_len)
+        {
+            return Err(TensorFileError::InvalidHeader(format!(
+                "tensor {} is out of the bounds of the file",
+                self.name
+            )));
+        }
+        Ok(self)
+    }
+}
+
+/// The tensors stored in a safetensors, numpy `.npy` or uncompressed numpy `.npz` file.
+#[derive(Debug, Clone)]
+pub struct TensorFile {
+    path: PathBuf,
+    tensors: Vec<FileTensor>,
+    metadata: BTreeMap<String, String>,
+    chunk_size: usize,
+}
+
+impl TensorFile {
+    /// Read the header of the file, using its extension to find the format.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, TensorFileError> {
+        let path = path.as_ref();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("safetensors") => Self::safetensors(path),
+            Some("npy") => Self::npy(path),
+            Some("npz") => Self::npz(path),
+            ext => Err(TensorFileError::Unsupported(format!(
+                "unknown extension {ext:?}"
+            ))),
+        }
+    }
+
+    /// Read the header of a safetensors file.
+    pub fn safetensors<P: AsRef<Path>>(path: P) -> Result<Self, TensorFileError> {
+        let (tensors, metadata) = safetensors::read_header(path.as_ref())?;
+        Ok(Self::new(path.as_ref(), tensors, metadata))
+    }
+
+    /// Read the header of a numpy `.npy` file, containing a single tensor named after the file.
+    pub fn npy<P: AsRef<Path>>(path: P) -> Result<Self, TensorFileError> {
+        let tensor = numpy::read_npy_header(path.as_ref())?;
+        Ok(Self::new(path.as_ref(), vec![tensor], BTreeMap::new()))
+    }
+
+    /// Read the headers of the arrays of a numpy `.npz` archive.
+    ///
+    /// Only archives saved without compression (`numpy.savez`) are supported, since compressed
+    /// arrays can't be streamed from the file.
+    pub fn npz<P: AsRef<Path>>(path: P) -> Result<Self, TensorFileError> {
+        let tensors = numpy::read_npz_headers(path.as_ref())?;
+        Ok(Self::new(path.as_ref(), tensors, BTreeMap::new()))
+    }
+
+    fn new(path: &Path, tensors: Vec<FileTensor>, metadata: BTreeMap<String, String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            tensors,
+            metadata,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Set the maximum number of bytes staged and uploaded at once.
+    ///
+    /// Tensors bigger than the chunk size are uploaded on their own.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// The path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All tensors of the file, in the order of their data.
+    pub fn tensors(&self) -> &[FileTensor] {
+        &self.tensors
+    }
+
+    /// The tensor with the given name.
+    pub fn tensor(&self, name: &str) -> Option<&FileTensor> {
+        self.tensors.iter().find(|tensor| tensor.name == name)
+    }
+
+    /// The free-form metadata of the file, only available for safetensors.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Load all tensors of the file on the device, in the same order as [tensors](Self::tensors).
+    pub fn load<R: Runtime>(&self, client: &ComputeClient<R>) -> Vec<TensorHandle<R>> {
+        let tensors = self.tensors.iter().collect::<Vec<_>>();
+        self.upload(client, &tensors)
+    }
+
+    /// Load the tensors with the given names on the device.
+    pub fn load_tensors<R: Runtime>(
+        &self,
+        client: &ComputeClient<R>,
+        names: &[&str],
+    ) -> Result<Vec<TensorHandle<R>>, TensorFileError> {
+        let tensors = names
+            .iter()
+            .map(|name| {
+                self.tensor(name)
+                    .ok_or_else(|| TensorFileError::MissingTensor(name.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.upload(client, &tensors))
+    }
+
+    fn upload<R: Runtime>(
+        &self,
+        client: &ComputeClient<R>,
+        tensors: &[&FileTensor],
+    ) -> Vec<TensorHandle<R>> {
+        let mut handles = Vec::with_capacity(tensors.len());
+        let mut start = 0;
+
+        while start < tensors.len() {
+            let mut end = start + 1;
+            let mut size = tensors[start].size;
+            while end < tensors.len() && size + tensors[end].size <= self.chunk_size as u64 {
+                size += tensors[end].size;
+                end += 1;
+            }
+
+            let chunk = &tensors[start..end];
+            let mut data = chunk
+                .iter()
+                .map(|tensor| Bytes::from_file(&self.path, tensor.size, tensor.offset))
+                .collect::<Vec<_>>();
+            client.staging(data.iter_mut(), true);
+
+            let descriptors = chunk
+                .iter()
+                .zip(data)
+                .map(|(tensor, data)| {
+                    // Scalars are allocated as a single element tensor.
+                    let shape = if tensor.shape.is_empty() {
+                        &[1]
+                    } else {
+                        tensor.shape.as_slice()
+                    };
+                    let descriptor = AllocationDescriptor::optimized(shape, tensor.dtype.size());
+                    (descriptor, data)
+                })
+                .collect();
+
+            let allocations = client.create_tensors(descriptors);
+            handles.extend(chunk.iter().zip(allocations).map(|(tensor, allocation)| {
+                let mut strides = allocation.strides;
+                strides.truncate(tensor.shape.len());
+                TensorHandle::new(
+                    allocation.handle,
+                    tensor.shape.clone(),
+                    strides,
+                    tensor.dtype,
+                )
+            }));
+
+            start = end;
+        }
+
+        handles
+    }
+}
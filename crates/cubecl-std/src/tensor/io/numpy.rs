@@ -0,0 +1,293 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use cubecl_core::ir::{ElemType, FloatKind, IntKind, StorageType, UIntKind};
+
+use super::{FileTensor, TensorFileError};
+
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+const ZIP_END: &[u8] = b"PK\x05\x06";
+const ZIP64_END: &[u8] = b"PK\x06\x06";
+const ZIP64_LOCATOR: &[u8] = b"PK\x06\x07";
+const ZIP_CENTRAL_ENTRY: &[u8] = b"PK\x01\x02";
+const ZIP_LOCAL_ENTRY: &[u8] = b"PK\x03\x04";
+/// Size of the end of central directory record without its comment.
+const ZIP_END_LEN: usize = 22;
+const ZIP_CENTRAL_ENTRY_LEN: usize = 46;
+const ZIP_LOCAL_ENTRY_LEN: usize = 30;
+
+/// The [StorageType] corresponding to a numpy array-protocol type string, e.g. `<f4`.
+///
+/// Only little-endian types are supported.
+pub fn npy_dtype(descr: &str) -> Option<StorageType> {
+    let (order, kind) = descr.split_at_checked(1)?;
+    let (kind, size) = kind.split_at_checked(1)?;
+    let size: usize = size.parse().ok()?;
+
+    match order {
+        "<" | "=" => {}
+        "|" | ">" if size == 1 => {}
+        _ => return None,
+    }
+
+    let elem = match (kind, size) {
+        ("b", 1) => ElemType::Bool,
+        ("u", 1) => ElemType::UInt(UIntKind::U8),
+        ("u", 2) => ElemType::UInt(UIntKind::U16),
+        ("u", 4) => ElemType::UInt(UIntKind::U32),
+        ("u", 8) => ElemType::UInt(UIntKind::U64),
+        ("i", 1) => ElemType::Int(IntKind::I8),
+        ("i", 2) => ElemType::Int(IntKind::I16),
+        ("i", 4) => ElemType::Int(IntKind::I32),
+        ("i", 8) => ElemType::Int(IntKind::I64),
+        ("f", 2) => ElemType::Float(FloatKind::F16),
+        ("f", 4) => ElemType::Float(FloatKind::F32),
+        ("f", 8) => ElemType::Float(FloatKind::F64),
+        _ => return None,
+    };
+    Some(StorageType::Scalar(elem))
+}
+
+/// Read the header of a `.npy` file, naming the tensor after the file.
+pub(crate) fn read_npy_header(path: &Path) -> Result<FileTensor, TensorFileError> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let name = path
+        .file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    read_npy_at(&mut file, 0, name)?.validate(file_len)
+}
+
+/// Read the headers of all arrays stored in a `.npz` archive.
+pub(crate) fn read_npz_headers(path: &Path) -> Result<Vec<FileTensor>, TensorFileError> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+
+    let (num_entries, directory_offset, directory_size) = read_zip_end(&mut file, file_len)?;
+    if directory_offset
+        .checked_add(directory_size)
+        .is_none_or(|end| end > file_len)
+    {
+        return Err(invalid_zip("central directory out of bounds"));
+    }
+
+    let mut directory = vec![0; directory_size as usize];
+    file.seek(SeekFrom::Start(directory_offset))?;
+    file.read_exact(&mut directory)?;
+
+    let mut tensors = Vec::with_capacity(num_entries as usize);
+    let mut pos = 0;
+
+    for _ in 0..num_entries {
+        let entry = directory
+            .get(pos..pos + ZIP_CENTRAL_ENTRY_LEN)
+            .filter(|entry| entry.starts_with(ZIP_CENTRAL_ENTRY))
+            .ok_or_else(|| invalid_zip("invalid central directory entry"))?;
+
+        let flags = le_u16(entry, 8);
+        let method = le_u16(entry, 10);
+        let mut compressed_size = le_u32(entry, 20) as u64;
+        let mut size = le_u32(entry, 24) as u64;
+        let name_len = le_u16(entry, 28) as usize;
+        let extra_len = le_u16(entry, 30) as usize;
+        let comment_len = le_u16(entry, 32) as usize;
+        let mut local_offset = le_u32(entry, 42) as u64;
+
+        let name_start = pos + ZIP_CENTRAL_ENTRY_LEN;
+        let extra_start = name_start + name_len;
+        let next = extra_start + extra_len + comment_len;
+        if next > directory.len() {
+            return Err(invalid_zip("invalid central directory entry"));
+        }
+        let name = String::from_utf8_lossy(&directory[name_start..extra_start]).into_owned();
+
+        // Sizes and offsets that don't fit in 32 bits are stored in the ZIP64 extra field, in
+        // that order.
+        let mut extra = &directory[extra_start..extra_start + extra_len];
+        while extra.len() >= 4 {
+            let id = le_u16(extra, 0);
+            let len = (le_u16(extra, 2) as usize).min(extra.len() - 4);
+            if id == 0x0001 {
+                let mut fields = extra[4..4 + len].chunks_exact(8).map(|f| le_u64(f, 0));
+                for value in [&mut size, &mut compressed_size, &mut local_offset] {
+                    if *value == u32::MAX as u64 {
+                        *value = fields.next().unwrap_or(*value);
+                    }
+                }
+            }
+            extra = &extra[4 + len..];
+        }
+        pos = next;
+
+        let Some(name) = name.strip_suffix(".npy") else {
+            continue;
+        };
+        if method != 0 || flags & 1 != 0 || compressed_size != size {
+            return Err(TensorFileError::Unsupported(format!(
+                "array {name} is compressed or encrypted, use `numpy.savez` instead of \
+                 `numpy.savez_compressed`"
+            )));
+        }
+
+        let mut local = [0; ZIP_LOCAL_ENTRY_LEN];
+        file.seek(SeekFrom::Start(local_offset))?;
+        file.read_exact(&mut local)?;
+        if !local.starts_with(ZIP_LOCAL_ENTRY) {
+            return Err(invalid_zip("invalid local entry"));
+        }
+        let data_offset = local_offset
+            + ZIP_LOCAL_ENTRY_LEN as u64
+            + le_u16(&local, 26) as u64
+            + le_u16(&local, 28) as u64;
+
+        let tensor = read_npy_at(&mut file, data_offset, name.to_string())?;
+        let entry_end = data_offset.checked_add(size);
+        let tensor_end = tensor.offset.checked_add(tensor.size);
+        if entry_end
+            .zip(tensor_end)
+            .is_none_or(|(entry_end, tensor_end)| tensor_end > entry_end)
+        {
+            return Err(TensorFileError::InvalidHeader(format!(
+                "array {name} is bigger than its entry"
+            )));
+        }
+        tensors.push(tensor.validate(file_len)?);
+    }
+
+    Ok(tensors)
+}
+
+/// Read the number of entries, offset and size of the central directory of a zip file.
+fn read_zip_end(file: &mut File, file_len: u64) -> Result<(u64, u64, u64), TensorFileError> {
+    // The end record is followed by a comment of at most `u16::MAX` bytes.
+    let tail_len = file_len.min((ZIP_END_LEN + u16::MAX as usize) as u64);
+    let tail_start = file_len - tail_len;
+    let mut tail = vec![0; tail_len as usize];
+    file.seek(SeekFrom::Start(tail_start))?;
+    file.read_exact(&mut tail)?;
+
+    let end_pos = tail
+        .windows(ZIP_END.len())
+        .rposition(|window| window == ZIP_END)
+        .filter(|pos| pos + ZIP_END_LEN <= tail.len())
+        .ok_or_else(|| invalid_zip("end of central directory not found"))?;
+    let end = &tail[end_pos..];
+
+    let num_entries = le_u16(end, 10) as u64;
+    let directory_size = le_u32(end, 12) as u64;
+    let directory_offset = le_u32(end, 16) as u64;
+
+    let is_zip64 = num_entries == u16::MAX as u64
+        || directory_size == u32::MAX as u64
+        || directory_offset == u32::MAX as u64;
+    if !is_zip64 {
+        return Ok((num_entries, directory_offset, directory_size));
+    }
+
+    let locator = end_pos
+        .checked_sub(20)
+        .map(|pos| &tail[pos..end_pos])
+        .filter(|locator| locator.starts_with(ZIP64_LOCATOR))
+        .ok_or_else(|| invalid_zip("ZIP64 locator not found"))?;
+
+    let mut end64 = [0; 56];
+    file.seek(SeekFrom::Start(le_u64(locator, 8)))?;
+    file.read_exact(&mut end64)?;
+    if !end64.starts_with(ZIP64_END) {
+        return Err(invalid_zip("invalid ZIP64 end of central directory"));
+    }
+
+    Ok((le_u64(&end64, 32), le_u64(&end64, 48), le_u64(&end64, 40)))
+}
+
+/// Read the header of a `.npy` file starting at `offset`.
+///
+/// The header is made of a magic string, a version, the length of the header and a python
+/// dictionary literal, e.g. `{'descr': '<f4', 'fortran_order': False, 'shape': (3, 4), }`.
+fn read_npy_at(file: &mut File, offset: u64, name: String) -> Result<FileTensor, TensorFileError> {
+    let mut prefix = [0; 12];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut prefix[..10])?;
+    if !prefix.starts_with(NPY_MAGIC) {
+        return Err(invalid_npy(&name, "missing magic string"));
+    }
+
+    let (header_start, header_len) = match prefix[6] {
+        1 => (10, le_u16(&prefix, 8) as usize),
+        2 | 3 => {
+            file.read_exact(&mut prefix[10..])?;
+            (12, le_u32(&prefix, 8) as usize)
+        }
+        version => return Err(invalid_npy(&name, &format!("unknown version {version}"))),
+    };
+
+    let mut header = vec![0; header_len];
+    file.read_exact(&mut header)?;
+    let header = String::from_utf8_lossy(&header);
+
+    let descr = dict_value(&header, "descr")
+        .and_then(|value| value.strip_prefix('\''))
+        .and_then(|value| value.split('\'').next())
+        .ok_or_else(|| invalid_npy(&name, "missing descr"))?;
+    let fortran_order = dict_value(&header, "fortran_order")
+        .map(|value| value.starts_with("True"))
+        .ok_or_else(|| invalid_npy(&name, "missing fortran_order"))?;
+    let shape = dict_value(&header, "shape")
+        .and_then(|value| value.strip_prefix('('))
+        .and_then(|value| value.split(')').next())
+        .ok_or_else(|| invalid_npy(&name, "missing shape"))?
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| dim.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| invalid_npy(&name, &err.to_string()))?;
+
+    if fortran_order {
+        return Err(TensorFileError::Unsupported(format!(
+            "array {name} is stored in column-major order"
+        )));
+    }
+    let dtype =
+        npy_dtype(descr).ok_or_else(|| TensorFileError::UnsupportedDtype(descr.to_string()))?;
+
+    let size = FileTensor::byte_size(&shape, dtype)
+        .ok_or_else(|| invalid_npy(&name, "shape is too large"))?;
+    Ok(FileTensor {
+        name,
+        shape,
+        dtype,
+        offset: offset + (header_start + header_len) as u64,
+        size,
+    })
+}
+
+/// The text following `'key':` in a python dictionary literal.
+fn dict_value<'a>(dict: &'a str, key: &str) -> Option<&'a str> {
+    let start = dict.find(&format!("'{key}'"))? + key.len() + 2;
+    let value = dict[start..].trim_start().strip_prefix(':')?;
+    Some(value.trim_start())
+}
+
+fn invalid_npy(name: &str, reason: &str) -> TensorFileError {
+    TensorFileError::InvalidHeader(format!("array {name}: {reason}"))
+}
+
+fn invalid_zip(reason: &str) -> TensorFileError {
+    TensorFileError::InvalidHeader(format!("invalid npz archive: {reason}"))
+}
+
+fn le_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn le_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn le_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
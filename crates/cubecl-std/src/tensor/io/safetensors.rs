@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use cubecl_core::ir::{ElemType, FloatKind, IntKind, StorageType, UIntKind};
+use serde::Deserialize;
+
+use super::{FileTensor, TensorFileError};
+
+/// Headers bigger than this are rejected, like the reference implementation does.
+const MAX_HEADER_SIZE: u64 = 100_000_000;
+
+#[derive(Deserialize)]
+struct TensorInfo {
+    dtype: String,
+    shape: Vec<usize>,
+    data_offsets: [u64; 2],
+}
+
+/// The [StorageType] corresponding to a safetensors data type.
+pub fn safetensors_dtype(dtype: &str) -> Option<StorageType> {
+    let elem = match dtype {
+        "BOOL" => ElemType::Bool,
+        "U8" => ElemType::UInt(UIntKind::U8),
+        "U16" => ElemType::UInt(UIntKind::U16),
+        "U32" => ElemType::UInt(UIntKind::U32),
+        "U64" => ElemType::UInt(UIntKind::U64),
+        "I8" => ElemType::Int(IntKind::I8),
+        "I16" => ElemType::Int(IntKind::I16),
+        "I32" => ElemType::Int(IntKind::I32),
+        "I64" => ElemType::Int(IntKind::I64),
+        "F8_E4M3" => ElemType::Float(FloatKind::E4M3),
+        "F8_E5M2" => ElemType::Float(FloatKind::E5M2),
+        "F8_E8M0" => ElemType::Float(FloatKind::UE8M0),
+        "F16" => ElemType::Float(FloatKind::F16),
+        "BF16" => ElemType::Float(FloatKind::BF16),
+        "F32" => ElemType::Float(FloatKind::F32),
+        "F64" => ElemType::Float(FloatKind::F64),
+        _ => return None,
+    };
+    Some(StorageType::Scalar(elem))
+}
+
+/// The safetensors data type corresponding to a [StorageType].
+pub fn safetensors_dtype_name(dtype: StorageType) -> Option<&'static str> {
+    let StorageType::Scalar(elem) = dtype else {
+        return None;
+    };
+    let name = match elem {
+        ElemType::Bool => "BOOL",
+        ElemType::UInt(UIntKind::U8) => "U8",
+        ElemType::UInt(UIntKind::U16) => "U16",
+        ElemType::UInt(UIntKind::U32) => "U32",
+        ElemType::UInt(UIntKind::U64) => "U64",
+        ElemType::Int(IntKind::I8) => "I8",
+        ElemType::Int(IntKind::I16) => "I16",
+        ElemType::Int(IntKind::I32) => "I32",
+        ElemType::Int(IntKind::I64) => "I64",
+        ElemType::Float(FloatKind::E4M3) => "F8_E4M3",
+        ElemType::Float(FloatKind::E5M2) => "F8_E5M2",
+        ElemType::Float(FloatKind::UE8M0) => "F8_E8M0",
+        ElemType::Float(FloatKind::F16) => "F16",
+        ElemType::Float(FloatKind::BF16) => "BF16",
+        ElemType::Float(FloatKind::F32) => "F32",
+        ElemType::Float(FloatKind::F64) => "F64",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Write the header of a safetensors file containing the given tensors, stored contiguously in
+/// the same order.
+pub(crate) fn write_header(
+    tensors: &[(String, Vec<usize>, StorageType)],
+) -> Result<Vec<u8>, TensorFileError> {
+    let mut header = serde_json::Map::new();
+    let mut offset = 0;
+
+    for (name, shape, dtype) in tensors {
+        let dtype_name = safetensors_dtype_name(*dtype)
+            .ok_or_else(|| TensorFileError::UnsupportedDtype(dtype.to_string()))?;
+        let size = shape.iter().product::<usize>() * dtype.size();
+        let info = serde_json::json!({
+            "dtype": dtype_name,
+            "shape": shape,
+            "data_offsets": [offset, offset + size],
+        });
+        if name == "__metadata__" || header.insert(name.clone(), info).is_some() {
+            return Err(TensorFileError::InvalidHeader(format!(
+                "invalid or duplicated tensor name {name}"
+            )));
+        }
+        offset += size;
+    }
+
+    // The data is aligned to 8 bytes by padding the header with spaces.
+    let mut header = serde_json::to_vec(&header)
+        .map_err(|err| TensorFileError::InvalidHeader(err.to_string()))?;
+    header.resize(header.len().next_multiple_of(8), b' ');
+
+    let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+    bytes.extend(header);
+    Ok(bytes)
+}
+
+/// Read the tensors and metadata from the header of a safetensors file.
+///
+/// The header is an 8 bytes little-endian length followed by a JSON object mapping the tensor
+/// names to their type, shape and data offsets relative to the end of the header.
+pub(crate) fn read_header(
+    path: &Path,
+) -> Result<(Vec<FileTensor>, BTreeMap<String, String>), TensorFileError> {
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+
+    let mut header_len = [0; 8];
+    file.read_exact(&mut header_len)?;
+    let header_len = u64::from_le_bytes(header_len);
+    if header_len > MAX_HEADER_SIZE || 8 + header_len > file_len {
+        return Err(TensorFileError::InvalidHeader(format!(
+            "invalid header size {header_len}"
+        )));
+    }
+
+    let mut header = vec![0; header_len as usize];
+    file.read_exact(&mut header)?;
+    let header: BTreeMap<String, serde_json::Value> = serde_json::from_slice(&header)
+        .map_err(|err| TensorFileError::InvalidHeader(err.to_string()))?;
+
+    let data_start = 8 + header_len;
+    let mut metadata = BTreeMap::new();
+    let mut tensors = Vec::with_capacity(header.len());
+
+    for (name, value) in header {
+        if name == "__metadata__" {
+            metadata = serde_json::from_value(value)
+                .map_err(|err| TensorFileError::InvalidHeader(err.to_string()))?;
+            continue;
+        }
+
+        let info: TensorInfo = serde_json::from_value(value)
+            .map_err(|err| TensorFileError::InvalidHeader(format!("{name}: {err}")))?;
+        let dtype = safetensors_dtype(&info.dtype)
+            .ok_or_else(|| TensorFileError::UnsupportedDtype(info.dtype.clone()))?;
+        let [begin, end] = info.data_offsets;
+        let offset = data_start.checked_add(begin).filter(|_| begin <= end);
+        let Some(offset) = offset else {
+            return Err(TensorFileError::InvalidHeader(format!(
+                "{name}: invalid data offsets {begin}..{end}"
+            )));
+        };
+
+        let tensor = FileTensor {
+            name,
+            shape: info.shape,
+            dtype,
+            offset,
+            size: end - begin,
+        };
+        tensors.push(tensor.validate(file_len)?);
+    }
+
+    tensors.sort_by_key(|tensor| tensor.offset);
+
+    Ok((tensors, metadata))
+}
@@ -1,12 +1,18 @@
+pub mod collective;
 mod contiguous;
 
 mod handle;
+pub mod histogram;
 pub mod identity;
+mod indexing;
+pub mod io;
 mod matrix_batch_layout;
+pub mod topk;
 
 pub use contiguous::*;
 pub use handle::*;
 pub use identity::*;
+pub use indexing::*;
 pub use matrix_batch_layout::*;
 pub use view::*;
 
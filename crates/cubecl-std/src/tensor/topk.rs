@@ -0,0 +1,523 @@
+use cubecl::prelude::*;
+use cubecl_common::backtrace::BackTrace;
+use cubecl_core::{
+    self as cubecl, CompilationError,
+    ir::{ElemType, features::TypeUsage},
+    server::CubeCountSelection,
+};
+
+use crate::tensor::TensorHandle;
+
+/// Largest `k` where sorting the whole row is preferred over radix select.
+const BITONIC_MAX_K: usize = 32;
+
+/// Number of elements loaded per iteration of the bitonic sort, in addition to the current
+/// top-k.
+const BITONIC_CHUNK_SIZE: usize = 1024;
+
+/// Number of units working on a single row.
+const MAX_ROW_UNITS: usize = 256;
+
+/// Number of bits sorted by each pass of the radix select.
+const RADIX_BITS: u32 = 8;
+const RADIX_SIZE: usize = 1 << RADIX_BITS;
+const RADIX_MASK: u32 = RADIX_SIZE as u32 - 1;
+
+/// The algorithm used to select the top-k values of each row. Both return the same results.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum TopKStrategy {
+    /// Merge each chunk of the row with the current top-k using a bitonic sort in shared memory.
+    /// Efficient for small `k`.
+    BitonicSort,
+    /// Find the k-th value with a radix select using shared memory histograms, then sort the
+    /// selected values. Efficient for large `k`, but requires shared atomic adds on `u32`.
+    RadixSelect,
+}
+
+impl TopKStrategy {
+    /// Select the best strategy for the given `k`.
+    pub fn new<R: Runtime>(client: &ComputeClient<R>, k: usize) -> Self {
+        let atomic_add = client
+            .properties()
+            .type_usage(Atomic::<u32>::as_type_native_unchecked())
+            .contains(TypeUsage::AtomicAdd);
+
+        match k <= BITONIC_MAX_K || !atomic_add {
+            true => TopKStrategy::BitonicSort,
+            false => TopKStrategy::RadixSelect,
+        }
+    }
+}
+
+/// How the values are converted to an unsigned key with the same ordering.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+enum KeyKind {
+    Float,
+    Signed,
+    Unsigned,
+}
+
+impl KeyKind {
+    fn new(dtype: StorageType) -> Result<Self, LaunchError> {
+        let kind = match dtype.elem_type() {
+            _ if dtype.size() > 4 => None,
+            ElemType::Float(_) => Some(KeyKind::Float),
+            ElemType::Int(_) => Some(KeyKind::Signed),
+            ElemType::UInt(_) => Some(KeyKind::Unsigned),
+            ElemType::Bool => None,
+        };
+
+        kind.ok_or_else(|| {
+            CompilationError::UnsupportedInstruction {
+                reason: format!(
+                    "Top-k only supports numeric types of 32 bits or less, got {dtype}"
+                ),
+                backtrace: BackTrace::capture(),
+            }
+            .into()
+        })
+    }
+}
+
+#[cube(launch_unchecked)]
+fn topk_kernel<N: Numeric, I: Int>(
+    input: &Tensor<N>,
+    values: &mut Tensor<N>,
+    indices: &mut Tensor<I>,
+    dim: usize,
+    #[comptime] strategy: TopKStrategy,
+    #[comptime] buffer_size: usize,
+    #[comptime] largest: bool,
+    #[comptime] kind: KeyKind,
+    #[define(N, I)] _dtypes: [StorageType; 2],
+) {
+    let row = CUBE_POS;
+    if row * input.shape(dim) >= input.len() {
+        terminate!();
+    }
+
+    let input_offset = row_offset(input, row, dim);
+    let mut keys = SharedMemory::<u32>::new(buffer_size);
+    let mut positions = SharedMemory::<u32>::new(buffer_size);
+
+    match strategy {
+        TopKStrategy::BitonicSort => bitonic_topk(
+            input,
+            input_offset,
+            values.shape(dim),
+            dim,
+            &mut keys,
+            &mut positions,
+            buffer_size,
+            largest,
+            kind,
+        ),
+        TopKStrategy::RadixSelect => radix_topk(
+            input,
+            input_offset,
+            values.shape(dim),
+            dim,
+            &mut keys,
+            &mut positions,
+            buffer_size,
+            largest,
+            kind,
+        ),
+    }
+
+    let k = values.shape(dim);
+    let values_offset = row_offset(values, row, dim);
+    let indices_offset = row_offset(indices, row, dim);
+
+    let mut slot = UNIT_POS as usize;
+    while slot < k {
+        let pos = positions[slot] as usize;
+        values[values_offset + slot * values.stride(dim)] =
+            input[input_offset + pos * input.stride(dim)];
+        indices[indices_offset + slot * indices.stride(dim)] = I::cast_from(pos);
+        slot += CUBE_DIM as usize;
+    }
+}
+
+/// Sort the row chunk by chunk, keeping the current top-k at the start of the buffer.
+#[cube]
+#[allow(clippy::too_many_arguments)]
+fn bitonic_topk<N: Numeric>(
+    input: &Tensor<N>,
+    input_offset: usize,
+    k: usize,
+    dim: usize,
+    keys: &mut SharedMemory<u32>,
+    positions: &mut SharedMemory<u32>,
+    #[comptime] buffer_size: usize,
+    #[comptime] largest: bool,
+    #[comptime] kind: KeyKind,
+) {
+    let len = input.shape(dim);
+    let stride = input.stride(dim);
+    let chunk_size = buffer_size - k;
+
+    clear_slots(keys, positions, 0, buffer_size);
+    sync_cube();
+
+    let mut start = 0usize;
+    while start < len {
+        let mut slot = UNIT_POS as usize;
+        while slot < chunk_size {
+            let pos = start + slot;
+            if pos < len {
+                let value = input[input_offset + pos * stride];
+                keys[k + slot] = sort_key::<N>(value, largest, kind);
+                positions[k + slot] = pos as u32;
+            } else {
+                keys[k + slot] = 0;
+                positions[k + slot] = u32::MAX;
+            }
+            slot += CUBE_DIM as usize;
+        }
+        sync_cube();
+
+        bitonic_sort(keys, positions, buffer_size);
+        start += chunk_size;
+    }
+}
+
+/// Select the top-k by finding the key of the k-th element one radix digit at a time, then sort
+/// the selected elements.
+///
+/// Ties are resolved by continuing the radix select on the inverted positions, so exactly `k`
+/// elements rank at or above the selected threshold.
+#[cube]
+#[allow(clippy::too_many_arguments)]
+fn radix_topk<N: Numeric>(
+    input: &Tensor<N>,
+    input_offset: usize,
+    k: usize,
+    dim: usize,
+    keys: &mut SharedMemory<u32>,
+    positions: &mut SharedMemory<u32>,
+    #[comptime] buffer_size: usize,
+    #[comptime] largest: bool,
+    #[comptime] kind: KeyKind,
+) {
+    let len = input.shape(dim);
+    let stride = input.stride(dim);
+
+    let histogram = SharedMemory::<Atomic<u32>>::new(RADIX_SIZE);
+    // The selected digit and the remaining number of elements to select
+    let mut state = SharedMemory::<u32>::new(2usize);
+
+    let key_passes = comptime![32 / RADIX_BITS];
+    let position_bits = 32 - u32::leading_zeros(len as u32 - 1);
+    let position_passes = position_bits.div_ceil(RADIX_BITS);
+
+    let mut key_prefix = 0u32;
+    let mut key_mask = 0u32;
+    let mut position_prefix = 0u32;
+    let mut position_mask = 0u32;
+    let mut remaining = k as u32;
+
+    for pass in 0..key_passes + position_passes {
+        let is_key = pass < key_passes;
+        let shift = select(
+            is_key,
+            (key_passes - 1 - pass) * RADIX_BITS,
+            (key_passes + position_passes - 1 - pass) * RADIX_BITS,
+        );
+
+        let mut bin = UNIT_POS as usize;
+        while bin < RADIX_SIZE {
+            Atomic::store(&histogram[bin], 0);
+            bin += CUBE_DIM as usize;
+        }
+        sync_cube();
+
+        let mut pos = UNIT_POS as usize;
+        while pos < len {
+            let key = sort_key::<N>(input[input_offset + pos * stride], largest, kind);
+            let inverted = (len - 1 - pos) as u32;
+            if (key & key_mask) == key_prefix && (inverted & position_mask) == position_prefix {
+                let digit = select(is_key, key >> shift, inverted >> shift) & RADIX_MASK;
+                Atomic::add(&histogram[digit as usize], 1);
+            }
+            pos += CUBE_DIM as usize;
+        }
+        sync_cube();
+
+        if UNIT_POS == 0 {
+            // Find the digit of the k-th element, starting from the highest
+            let mut count = 0u32;
+            let mut digit = comptime![RADIX_MASK + 1].runtime();
+            let mut found = false;
+            while !found {
+                digit -= 1;
+                let digit_count = Atomic::load(&histogram[digit as usize]);
+                if count + digit_count >= remaining {
+                    found = true;
+                } else {
+                    count += digit_count;
+                }
+            }
+            state[0usize] = digit;
+            state[1usize] = remaining - count;
+        }
+        sync_cube();
+
+        let digit = state[0usize];
+        remaining = state[1usize];
+        if is_key {
+            key_prefix |= digit << shift;
+            key_mask |= RADIX_MASK << shift;
+        } else {
+            position_prefix |= digit << shift;
+            position_mask |= RADIX_MASK << shift;
+        }
+        sync_cube();
+    }
+
+    // Gather the selected elements, the order doesn't matter since they're sorted afterward
+    let counter = SharedMemory::<Atomic<u32>>::new(1usize);
+    if UNIT_POS == 0 {
+        Atomic::store(&counter[0usize], 0);
+    }
+    clear_slots(keys, positions, k, buffer_size);
+    sync_cube();
+
+    let mut pos = UNIT_POS as usize;
+    while pos < len {
+        let key = sort_key::<N>(input[input_offset + pos * stride], largest, kind);
+        let inverted = (len - 1 - pos) as u32;
+        if key > key_prefix || (key == key_prefix && inverted >= position_prefix) {
+            let slot = Atomic::add(&counter[0usize], 1) as usize;
+            keys[slot] = key;
+            positions[slot] = pos as u32;
+        }
+        pos += CUBE_DIM as usize;
+    }
+    sync_cube();
+
+    bitonic_sort(keys, positions, buffer_size);
+}
+
+/// Fill the slots in `[start, end)` with a sentinel that ranks after all elements.
+#[cube]
+fn clear_slots(
+    keys: &mut SharedMemory<u32>,
+    positions: &mut SharedMemory<u32>,
+    start: usize,
+    end: usize,
+) {
+    let mut slot = start + UNIT_POS as usize;
+    while slot < end {
+        keys[slot] = 0;
+        positions[slot] = u32::MAX;
+        slot += CUBE_DIM as usize;
+    }
+}
+
+/// Sort the buffer so the highest keys come first, with ties ordered by increasing position.
+/// `buffer_size` must be a power of two.
+#[cube]
+fn bitonic_sort(
+    keys: &mut SharedMemory<u32>,
+    positions: &mut SharedMemory<u32>,
+    #[comptime] buffer_size: usize,
+) {
+    let num_pairs = comptime![buffer_size / 2];
+
+    let mut size = 2usize.runtime();
+    while size <= buffer_size {
+        let mut stride = size / 2;
+        while stride > 0 {
+            let mut pair = UNIT_POS as usize;
+            while pair < num_pairs {
+                let first = 2 * stride * (pair / stride) + pair % stride;
+                let second = first + stride;
+                let descending = (first & size) == 0;
+
+                let (key_first, key_second) = (keys[first], keys[second]);
+                let (pos_first, pos_second) = (positions[first], positions[second]);
+                let first_ranks_higher =
+                    key_first > key_second || (key_first == key_second && pos_first < pos_second);
+
+                if first_ranks_higher != descending {
+                    keys[first] = key_second;
+                    keys[second] = key_first;
+                    positions[first] = pos_second;
+                    positions[second] = pos_first;
+                }
+                pair += CUBE_DIM as usize;
+            }
+            sync_cube();
+            stride /= 2;
+        }
+        size *= 2;
+    }
+}
+
+/// Convert a value to an unsigned key, where higher keys rank first.
+///
+/// Floats are ordered by their bits, so `-0.0` ranks after `0.0` and `NaN` ranks before every
+/// other value when selecting the largest values.
+#[cube]
+fn sort_key<N: Numeric>(value: N, #[comptime] largest: bool, #[comptime] kind: KeyKind) -> u32 {
+    let key = match kind {
+        KeyKind::Float => {
+            let bits = u32::reinterpret(f32::cast_from(value));
+            // Flip all the bits of negative values and only the sign of positive values
+            let mask = select(bits >= 0x8000_0000u32, 0xFFFF_FFFFu32, 0x8000_0000u32);
+            bits ^ mask
+        }
+        KeyKind::Signed => u32::reinterpret(i32::cast_from(value)) ^ 0x8000_0000u32,
+        KeyKind::Unsigned => u32::cast_from(value),
+    };
+
+    if largest { key } else { u32::MAX - key }
+}
+
+/// Offset of the first element of the row along `dim`, where `row` counts all other positions.
+#[cube]
+fn row_offset<E: CubePrimitive>(tensor: &Tensor<E>, row: usize, dim: usize) -> usize {
+    let rank = tensor.rank();
+    let mut remaining = row;
+    let mut offset = 0;
+
+    for i in 0..rank {
+        let d = rank - i - 1;
+        if d != dim {
+            offset += (remaining % tensor.shape(d)) * tensor.stride(d);
+            remaining /= tensor.shape(d);
+        }
+    }
+
+    offset
+}
+
+/// Select the `k` largest values of `input` along `dim`, or the `k` smallest when `largest` is
+/// false.
+///
+/// Returns the values and their positions along `dim`, sorted so the first value is the largest
+/// (or the smallest). Equal values are ordered by increasing position, so the results are
+/// identical on every runtime.
+///
+/// # Errors
+///
+/// Returns an error when `dtype` is a boolean or a type wider than 32 bits.
+///
+/// # Panics
+///
+/// Panics when `dim` is out of range, or when `k` is zero, larger than the size of `dim` or too
+/// large for the shared memory of the device.
+pub fn topk<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    k: usize,
+    dim: usize,
+    largest: bool,
+    dtype: StorageType,
+    index_dtype: StorageType,
+) -> Result<(TensorHandle<R>, TensorHandle<R>), LaunchError> {
+    let rank = input.shape.len();
+    assert!(dim < rank, "Dimension {dim} out of range for rank {rank}");
+
+    let mut shape = input.shape.to_vec();
+    shape[dim] = k;
+
+    let values = TensorHandle::empty(client, shape.clone(), dtype);
+    let indices = TensorHandle::empty(client, shape, index_dtype);
+    let strategy = TopKStrategy::new(client, k);
+
+    topk_ref(
+        client,
+        input,
+        &values.as_ref(),
+        &indices.as_ref(),
+        dim,
+        largest,
+        strategy,
+        dtype,
+        index_dtype,
+    )?;
+
+    Ok((values, indices))
+}
+
+/// Select the top-k values of `input` along `dim` into `values` and `indices`, where `k` is the
+/// size of the outputs along `dim`.
+///
+/// See [topk] for more details.
+///
+/// # Errors
+///
+/// Returns an error when `dtype` is a boolean or a type wider than 32 bits.
+///
+/// # Panics
+///
+/// Panics when `dim` is out of range, when the output shapes don't match the input, or when `k`
+/// is too large for the shared memory of the device.
+#[allow(clippy::too_many_arguments)]
+pub fn topk_ref<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorHandleRef<'_, R>,
+    values: &TensorHandleRef<'_, R>,
+    indices: &TensorHandleRef<'_, R>,
+    dim: usize,
+    largest: bool,
+    strategy: TopKStrategy,
+    dtype: StorageType,
+    index_dtype: StorageType,
+) -> Result<(), LaunchError> {
+    let kind = KeyKind::new(dtype)?;
+    let rank = input.shape.len();
+    assert!(dim < rank, "Dimension {dim} out of range for rank {rank}");
+
+    let len = input.shape[dim];
+    let k = values.shape[dim];
+    assert!(k > 0 && k <= len, "k should be in [1, {len}], got {k}");
+    assert_eq!(
+        values.shape, indices.shape,
+        "Outputs should have the same shape"
+    );
+    for d in (0..rank).filter(|d| *d != dim) {
+        assert_eq!(
+            input.shape[d], values.shape[d],
+            "Outputs should match the input shape outside of dimension {dim}"
+        );
+    }
+
+    let buffer_size = match strategy {
+        TopKStrategy::BitonicSort => Ord::max(2 * k, Ord::min(len, BITONIC_CHUNK_SIZE) + k),
+        TopKStrategy::RadixSelect => k,
+    }
+    .next_power_of_two();
+    let max_shared = client.properties().hardware.max_shared_memory_size;
+    assert!(
+        buffer_size * 2 * size_of::<u32>() + RADIX_SIZE * size_of::<u32>() <= max_shared,
+        "k = {k} is too large to fit in shared memory"
+    );
+
+    let num_rows = input.shape.iter().product::<usize>() / len;
+    let num_units = match strategy {
+        TopKStrategy::BitonicSort => (buffer_size / 2).clamp(32, MAX_ROW_UNITS),
+        TopKStrategy::RadixSelect => MAX_ROW_UNITS,
+    };
+    let cube_dim = CubeDim::new_1d(num_units as u32);
+    let cube_count = CubeCountSelection::new(client, num_rows as u32).cube_count();
+
+    unsafe {
+        topk_kernel::launch_unchecked(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            values.as_tensor_arg(1),
+            indices.as_tensor_arg(1),
+            ScalarArg::new(dim),
+            strategy,
+            buffer_size,
+            largest,
+            kind,
+            [dtype, index_dtype],
+        )
+    }
+}
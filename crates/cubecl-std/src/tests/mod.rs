@@ -1,4 +1,5 @@
 pub mod event;
+pub mod quantize;
 pub mod reinterpret_slice;
 pub mod tensor;
 pub mod trigonometry;
@@ -0,0 +1,232 @@
+use cubecl::prelude::*;
+use cubecl_common::{
+    quant::scheme::{QuantLevel, QuantParam, QuantScheme, QuantStore, QuantValue},
+    ue8m0,
+};
+use cubecl_core::{self as cubecl};
+
+use crate::{
+    quant::{self, Calibration},
+    tensor::TensorHandle,
+};
+
+fn create<R: Runtime, F: Float + CubeElement>(
+    client: &ComputeClient<R>,
+    data: &[f32],
+    shape: &[usize],
+) -> TensorHandle<R> {
+    let data = data.iter().map(|it| F::new(*it)).collect::<Vec<_>>();
+    let handle = client.create_from_slice(F::as_bytes(&data));
+    TensorHandle::new_contiguous(shape.to_vec(), handle, F::as_type_native_unchecked())
+}
+
+fn read<R: Runtime, E: CubeElement>(client: &ComputeClient<R>, tensor: &TensorHandle<R>) -> Vec<E> {
+    let bytes = client.read_one_tensor(tensor.handle.clone().copy_descriptor(
+        &tensor.shape,
+        &tensor.strides,
+        size_of::<E>(),
+    ));
+    E::from_bytes(&bytes).to_vec()
+}
+
+/// Quantized values for groups of `group` elements. The first value of each group is the largest
+/// quantized value, so the absolute maximum of each group maps exactly to the scale.
+fn make_quantized(len: usize, group: usize, value: QuantValue) -> Vec<i32> {
+    let q_max = value.range().1 as i32;
+    (0..len)
+        .map(|i| match i % group {
+            0 => q_max,
+            _ => (i as i32 * 7) % (2 * q_max + 1) - q_max,
+        })
+        .collect()
+}
+
+fn dequantize_cpu(values: &[i32], scale: f32) -> Vec<f32> {
+    values.iter().map(|it| *it as f32 * scale).collect()
+}
+
+/// Pack the quantized values in `u32` with the lowest bits first.
+fn pack_cpu(values: &[i32], value: QuantValue) -> Vec<u32> {
+    let bits = value.size_bits();
+    let mask = (1u32 << bits) - 1;
+    values
+        .chunks(32 / bits)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0, |acc, (i, it)| acc | ((*it as u32 & mask) << (i * bits)))
+        })
+        .collect()
+}
+
+pub fn test_quantize_per_tensor<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let scheme = QuantScheme::default().with_value(QuantValue::Q8F);
+    let quantized = make_quantized(128, 128, scheme.value);
+    let input = create::<R, F>(&client, &dequantize_cpu(&quantized, 0.125), &[4, 32]);
+
+    let (values, scales) = quant::quantize(
+        &client,
+        &input.as_ref(),
+        &scheme,
+        Calibration::AbsMax,
+        F::as_type_native_unchecked(),
+    )
+    .unwrap();
+
+    assert_eq!(scales.shape, [1, 1]);
+    assert_eq!(read::<R, f32>(&client, &scales), [0.125]);
+    assert_eq!(
+        read::<R, u32>(&client, &values),
+        pack_cpu(&quantized, scheme.value)
+    );
+}
+
+pub fn test_quantize_per_block<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let scheme = QuantScheme::default()
+        .with_value(QuantValue::Q4S)
+        .with_level(QuantLevel::block([16]));
+    let quantized = make_quantized(128, 16, scheme.value);
+    let input = create::<R, F>(&client, &dequantize_cpu(&quantized, 0.125), &[4, 32]);
+
+    let (values, scales) = quant::quantize(
+        &client,
+        &input.as_ref(),
+        &scheme,
+        Calibration::AbsMax,
+        F::as_type_native_unchecked(),
+    )
+    .unwrap();
+
+    assert_eq!(scales.shape, [4, 2]);
+    assert_eq!(read::<R, f32>(&client, &scales), [0.125; 8]);
+    assert_eq!(
+        read::<R, u32>(&client, &values),
+        pack_cpu(&quantized, scheme.value)
+    );
+}
+
+pub fn test_quantize_native<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    if !client
+        .properties()
+        .supports_type(i8::as_type_native_unchecked())
+    {
+        return;
+    }
+
+    let scheme = QuantScheme::default()
+        .with_value(QuantValue::Q8S)
+        .with_store(QuantStore::Native)
+        .with_level(QuantLevel::block([1, 32]));
+    let quantized = make_quantized(128, 32, scheme.value);
+    let input = create::<R, F>(&client, &dequantize_cpu(&quantized, 0.125), &[4, 32]);
+
+    let (values, scales) = quant::quantize(
+        &client,
+        &input.as_ref(),
+        &scheme,
+        Calibration::AbsMax,
+        F::as_type_native_unchecked(),
+    )
+    .unwrap();
+    let expected = quantized.iter().map(|it| *it as i8).collect::<Vec<_>>();
+
+    assert_eq!(values.shape, [4, 32]);
+    assert_eq!(read::<R, f32>(&client, &scales), [0.125; 4]);
+    assert_eq!(read::<R, i8>(&client, &values), expected);
+}
+
+pub fn test_quantize_percentile<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let scheme = QuantScheme::default().with_value(QuantValue::Q8S);
+    // A single outlier, which the percentile should ignore.
+    let mut data = (0..256).map(|i| (i % 32) as f32 / 8.0).collect::<Vec<_>>();
+    data[100] = 1000.0;
+    let input = create::<R, F>(&client, &data, &[8, 32]);
+
+    let scales = quant::compute_scales(
+        &client,
+        &input.as_ref(),
+        &scheme,
+        Calibration::Percentile(99.0),
+        F::as_type_native_unchecked(),
+    )
+    .unwrap();
+
+    let clip = read::<R, f32>(&client, &scales)[0] * 127.0;
+    let expected = 31.0 / 8.0;
+    assert!(
+        (clip - expected).abs() < 1e-3,
+        "Expected clipping value {expected}, got {clip}"
+    );
+}
+
+pub fn test_quantize_ue8m0_scales<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    if !client
+        .properties()
+        .supports_type(ue8m0::as_type_native_unchecked())
+    {
+        return;
+    }
+
+    let scheme = QuantScheme::default()
+        .with_value(QuantValue::Q8S)
+        .with_param(QuantParam::UE8M0)
+        .with_level(QuantLevel::block([32]));
+    let quantized = make_quantized(128, 32, scheme.value);
+    // The exact scale is `3 / 16`, which should be rounded up to the next power of two.
+    let input = create::<R, F>(&client, &dequantize_cpu(&quantized, 0.1875), &[4, 32]);
+
+    let scales = quant::compute_scales(
+        &client,
+        &input.as_ref(),
+        &scheme,
+        Calibration::AbsMax,
+        F::as_type_native_unchecked(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        read::<R, ue8m0>(&client, &scales),
+        [ue8m0::from_f32(0.25); 4]
+    );
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_quantize {
+    ($ty: ty) => {
+        mod quantize {
+            use super::*;
+
+            #[test]
+            fn test_quantize_per_tensor() {
+                let client = TestRuntime::client(&Default::default());
+                cubecl_std::tests::quantize::test_quantize_per_tensor::<TestRuntime, $ty>(client);
+            }
+
+            #[test]
+            fn test_quantize_per_block() {
+                let client = TestRuntime::client(&Default::default());
+                cubecl_std::tests::quantize::test_quantize_per_block::<TestRuntime, $ty>(client);
+            }
+
+            #[test]
+            fn test_quantize_native() {
+                let client = TestRuntime::client(&Default::default());
+                cubecl_std::tests::quantize::test_quantize_native::<TestRuntime, $ty>(client);
+            }
+
+            #[test]
+            fn test_quantize_percentile() {
+                let client = TestRuntime::client(&Default::default());
+                cubecl_std::tests::quantize::test_quantize_percentile::<TestRuntime, $ty>(client);
+            }
+
+            #[test]
+            fn test_quantize_ue8m0_scales() {
+                let client = TestRuntime::client(&Default::default());
+                cubecl_std::tests::quantize::test_quantize_ue8m0_scales::<TestRuntime, $ty>(client);
+            }
+        }
+    };
+}
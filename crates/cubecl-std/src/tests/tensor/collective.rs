@@ -0,0 +1,153 @@
+use cubecl_common::device::{Device, DeviceId};
+use cubecl_core::{
+    CubeElement,
+    prelude::{ComputeClient, Float, Runtime},
+};
+
+use crate::{
+    tensor::{
+        TensorHandle,
+        collective::{CollectiveGroup, ReduceOp},
+    },
+    tests::tensor::test_utils::{create, read},
+};
+
+fn group<R: Runtime>(size: usize) -> CollectiveGroup<R> {
+    let clients = (0..size)
+        .map(|index| R::client(&R::Device::from_id(DeviceId::new(0, index as u32))))
+        .collect();
+    CollectiveGroup::new(clients)
+}
+
+/// Element `i` of rank `r` is `3 * i + r`, so the mean over three ranks is exact.
+fn inputs<R: Runtime, F: Float + CubeElement>(
+    group: &CollectiveGroup<R>,
+    shape: &[usize],
+) -> Vec<TensorHandle<R>> {
+    let len = shape.iter().product::<usize>();
+    group
+        .clients()
+        .iter()
+        .enumerate()
+        .map(|(rank, client)| {
+            let data: Vec<F> = (0..len).map(|i| F::new((3 * i + rank) as f32)).collect();
+            create(client, &data, shape)
+        })
+        .collect()
+}
+
+fn read_all<R: Runtime, F: Float + CubeElement>(
+    clients: &[ComputeClient<R>],
+    outputs: &[TensorHandle<R>],
+) -> Vec<Vec<F>> {
+    clients
+        .iter()
+        .zip(outputs)
+        .map(|(client, output)| read::<R, F>(client, output))
+        .collect()
+}
+
+pub fn test_all_reduce<R: Runtime, F: Float + CubeElement>(op: ReduceOp) {
+    let size = 3;
+    let shape = [2, 5];
+    let group = group::<R>(size);
+    let inputs = inputs::<R, F>(&group, &shape);
+    let inputs = inputs
+        .iter()
+        .map(|input| input.as_ref())
+        .collect::<Vec<_>>();
+
+    let outputs = group
+        .all_reduce(&inputs, op, F::as_type_native_unchecked())
+        .unwrap();
+
+    let expected: Vec<F> = (0..10)
+        .map(|i| {
+            let values = (0..size).map(|rank| (3 * i + rank) as f32);
+            F::new(match op {
+                ReduceOp::Sum => values.sum(),
+                ReduceOp::Mean => values.sum::<f32>() / size as f32,
+                ReduceOp::Max => values.fold(f32::MIN, f32::max),
+            })
+        })
+        .collect();
+    for (rank, actual) in read_all::<R, F>(group.clients(), &outputs)
+        .into_iter()
+        .enumerate()
+    {
+        assert_eq!(actual, expected, "Rank {rank}");
+    }
+}
+
+pub fn test_reduce_scatter<R: Runtime, F: Float + CubeElement>() {
+    let size = 3;
+    let shape = [6, 2];
+    let group = group::<R>(size);
+    let inputs = inputs::<R, F>(&group, &shape);
+    let inputs = inputs
+        .iter()
+        .map(|input| input.as_ref())
+        .collect::<Vec<_>>();
+
+    let outputs = group
+        .reduce_scatter(&inputs, ReduceOp::Sum, F::as_type_native_unchecked())
+        .unwrap();
+
+    for (rank, (actual, output)) in read_all::<R, F>(group.clients(), &outputs)
+        .into_iter()
+        .zip(&outputs)
+        .enumerate()
+    {
+        assert_eq!(output.shape, [2, 2]);
+        let expected: Vec<F> = (4 * rank..4 * (rank + 1))
+            .map(|i| F::new((9 * i + 3) as f32))
+            .collect();
+        assert_eq!(actual, expected, "Rank {rank}");
+    }
+}
+
+pub fn test_all_gather<R: Runtime, F: Float + CubeElement>() {
+    let size = 3;
+    let shape = [2, 3];
+    let group = group::<R>(size);
+    let inputs = inputs::<R, F>(&group, &shape);
+    let inputs = inputs
+        .iter()
+        .map(|input| input.as_ref())
+        .collect::<Vec<_>>();
+
+    let outputs = group
+        .all_gather(&inputs, F::as_type_native_unchecked())
+        .unwrap();
+
+    let expected: Vec<F> = (0..size)
+        .flat_map(|rank| (0..6).map(move |i| F::new((3 * i + rank) as f32)))
+        .collect();
+    for (rank, (actual, output)) in read_all::<R, F>(group.clients(), &outputs)
+        .into_iter()
+        .zip(&outputs)
+        .enumerate()
+    {
+        assert_eq!(output.shape, [6, 3]);
+        assert_eq!(actual, expected, "Rank {rank}");
+    }
+}
+
+pub fn test_broadcast<R: Runtime, F: Float + CubeElement>() {
+    let size = 5;
+    let root = 2;
+    let group = group::<R>(size);
+    let data: Vec<F> = (0..8).map(|i| F::new(i as f32)).collect();
+    let input = create(&group.clients()[root], &data, &[8]);
+
+    let outputs = group
+        .broadcast(&input.as_ref(), root, F::as_type_native_unchecked())
+        .unwrap();
+
+    for (rank, actual) in read_all::<R, F>(group.clients(), &outputs)
+        .into_iter()
+        .enumerate()
+    {
+        assert_eq!(actual, data, "Rank {rank}");
+    }
+}
@@ -0,0 +1,164 @@
+use cubecl_core::{
+    CubeElement,
+    prelude::{ComputeClient, Float, Runtime},
+};
+
+use crate::{
+    tensor::{self, TensorHandle},
+    tests::tensor::test_utils::{create, ravel, read, unravel},
+};
+
+fn make_data<F: Float>(len: usize, offset: usize) -> Vec<F> {
+    (0..len).map(|i| F::new((i + offset) as f32)).collect()
+}
+
+/// Create a tensor with the last two dimensions transposed in memory.
+fn create_transposed<R: Runtime, F: Float + CubeElement>(
+    client: &ComputeClient<R>,
+    data: &[F],
+    shape: &[usize],
+) -> TensorHandle<R> {
+    let rank = shape.len();
+    let mut physical_shape = shape.to_vec();
+    physical_shape.swap(rank - 1, rank - 2);
+
+    let mut physical = data.to_vec();
+    for (pos, value) in data.iter().enumerate() {
+        let mut coords = unravel(pos, shape);
+        coords.swap(rank - 1, rank - 2);
+        physical[ravel(&coords, &physical_shape)] = *value;
+    }
+
+    let tensor = create(client, &physical, &physical_shape);
+    let mut strides = tensor.strides.clone();
+    strides.swap(rank - 1, rank - 2);
+    TensorHandle::new(tensor.handle, shape.to_vec(), strides, tensor.dtype)
+}
+
+fn concat_cpu<F: Float>(inputs: &[(Vec<F>, Vec<usize>)], dim: usize) -> Vec<F> {
+    let mut shape = inputs[0].1.clone();
+    shape[dim] = inputs.iter().map(|(_, shape)| shape[dim]).sum();
+
+    let mut output = vec![F::new(0.0); shape.iter().product()];
+    let mut start = 0;
+    for (data, input_shape) in inputs {
+        for (pos, value) in data.iter().enumerate() {
+            let mut coords = unravel(pos, input_shape);
+            coords[dim] += start;
+            output[ravel(&coords, &shape)] = *value;
+        }
+        start += input_shape[dim];
+    }
+    output
+}
+
+pub fn test_concat<R: Runtime, F: Float + CubeElement>(device: &R::Device, dim: usize) {
+    let client = R::client(device);
+
+    let inputs = [4, 2, 8]
+        .into_iter()
+        .enumerate()
+        .map(|(i, size)| {
+            let mut shape = vec![3, 4, 8];
+            shape[dim] = size;
+            let data = make_data::<F>(shape.iter().product(), 500 * i);
+            (data, shape)
+        })
+        .collect::<Vec<_>>();
+    let handles = inputs
+        .iter()
+        .map(|(data, shape)| create(&client, data, shape))
+        .collect::<Vec<_>>();
+    let handles_ref = handles.iter().map(|it| it.as_ref()).collect::<Vec<_>>();
+
+    let output = tensor::concat(&client, &handles_ref, dim, F::as_type_native_unchecked()).unwrap();
+
+    let mut expected_shape = vec![3, 4, 8];
+    expected_shape[dim] = 14;
+    assert_eq!(output.shape, expected_shape);
+    assert_eq!(read::<R, F>(&client, &output), concat_cpu(&inputs, dim));
+}
+
+pub fn test_concat_strided<R: Runtime, F: Float + CubeElement>(device: &R::Device) {
+    let client = R::client(device);
+
+    let first = (make_data::<F>(2 * 3 * 4, 0), vec![2, 3, 4]);
+    let second = (make_data::<F>(2 * 5 * 4, 1000), vec![2, 5, 4]);
+    let handles = [
+        create(&client, &first.0, &first.1),
+        create_transposed(&client, &second.0, &second.1),
+    ];
+    let handles_ref = handles.iter().map(|it| it.as_ref()).collect::<Vec<_>>();
+
+    let output = tensor::concat(&client, &handles_ref, 1, F::as_type_native_unchecked()).unwrap();
+
+    assert_eq!(
+        read::<R, F>(&client, &output),
+        concat_cpu(&[first, second], 1)
+    );
+}
+
+pub fn test_split<R: Runtime, F: Float + CubeElement>(device: &R::Device, dim: usize) {
+    let client = R::client(device);
+
+    let mut shape = vec![3, 4, 8];
+    shape[dim] = 12;
+    let data = make_data::<F>(shape.iter().product(), 0);
+    let input = create(&client, &data, &shape);
+    let sizes = [4, 1, 7];
+
+    let outputs = tensor::split(
+        &client,
+        &input.as_ref(),
+        &sizes,
+        dim,
+        F::as_type_native_unchecked(),
+    )
+    .unwrap();
+
+    let mut start = 0;
+    for (output, size) in outputs.iter().zip(sizes) {
+        let mut expected_shape = shape.clone();
+        expected_shape[dim] = size;
+        let expected = (0..expected_shape.iter().product())
+            .map(|pos| {
+                let mut coords = unravel(pos, &expected_shape);
+                coords[dim] += start;
+                data[ravel(&coords, &shape)]
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(output.shape, expected_shape);
+        assert_eq!(read::<R, F>(&client, output), expected);
+        start += size;
+    }
+}
+
+pub fn test_split_strided<R: Runtime, F: Float + CubeElement>(device: &R::Device) {
+    let client = R::client(device);
+
+    let shape = [2, 3, 8];
+    let data = make_data::<F>(shape.iter().product(), 0);
+    let input = create_transposed(&client, &data, &shape);
+
+    let outputs = tensor::split(
+        &client,
+        &input.as_ref(),
+        &[4, 4],
+        2,
+        F::as_type_native_unchecked(),
+    )
+    .unwrap();
+
+    for (i, output) in outputs.iter().enumerate() {
+        let expected = (0..2 * 3 * 4)
+            .map(|pos| {
+                let mut coords = unravel(pos, &[2, 3, 4]);
+                coords[2] += 4 * i;
+                data[ravel(&coords, &shape)]
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(read::<R, F>(&client, output), expected);
+    }
+}
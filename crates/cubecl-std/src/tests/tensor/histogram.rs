@@ -0,0 +1,144 @@
+use cubecl_core::{
+    CubeElement,
+    ir::features::TypeUsage,
+    prelude::{Atomic, CubePrimitive, Float, Numeric, Runtime},
+};
+
+use crate::{
+    tensor::{
+        TensorHandle,
+        histogram::{self, BinMapping, HistogramStrategy},
+    },
+    tests::tensor::test_utils::{create, read},
+};
+
+fn supports_atomic_add<R: Runtime, N: CubePrimitive>(
+    client: &cubecl_core::prelude::ComputeClient<R>,
+) -> bool {
+    let atomic_ty = Atomic::<N>::as_type_native_unchecked();
+    let supported = client
+        .properties()
+        .type_usage(atomic_ty)
+        .contains(TypeUsage::AtomicAdd);
+    if !supported {
+        println!("{atomic_ty} atomic add not supported - skipped");
+    }
+    supported
+}
+
+pub fn test_histogram<R: Runtime, F: Float + CubeElement>(device: &R::Device) {
+    let client = R::client(device);
+    if !supports_atomic_add::<R, u32>(&client) {
+        return;
+    }
+
+    let shape = [6, 64];
+    let num_bins = 10;
+    let (min, max) = (-2.0, 3.0);
+
+    let len = shape.iter().product();
+    // Exactly representable values spanning more than the range, including `max` and the bin
+    // boundaries.
+    let data: Vec<f32> = (0..len).map(|i| (i % 40) as f32 / 4.0 - 3.0).collect();
+    let input_data: Vec<F> = data.iter().map(|v| F::new(*v)).collect();
+    let input = create(&client, &input_data, &shape);
+
+    let output = histogram::histogram(
+        &client,
+        &input.as_ref(),
+        num_bins,
+        min,
+        max,
+        F::as_type_native_unchecked(),
+    )
+    .unwrap();
+    let actual = read::<R, u32>(&client, &output);
+
+    let mut expected = vec![0u32; num_bins];
+    for value in data {
+        if (min..=max).contains(&value) {
+            let bin = ((value - min) * num_bins as f32 / (max - min)) as usize;
+            expected[bin.min(num_bins - 1)] += 1;
+        }
+    }
+
+    assert_eq!(actual, expected);
+}
+
+pub fn test_bincount<R: Runtime>(device: &R::Device, strategy: HistogramStrategy) {
+    let client = R::client(device);
+    if !supports_atomic_add::<R, u32>(&client) {
+        return;
+    }
+
+    let shape = [4, 5, 16];
+    let num_bins = 17;
+
+    let len = shape.iter().product();
+    // Includes negative and too large values, which should be ignored.
+    let input_data: Vec<i32> = (0..len).map(|i| (i as i32 * 7) % 23 - 3).collect();
+    let input = create(&client, &input_data, &shape);
+
+    let output = TensorHandle::<R>::zeros(&client, vec![num_bins], u32::as_type_native_unchecked());
+    histogram::launch_ref(
+        &client,
+        &input.as_ref(),
+        None,
+        &output.as_ref(),
+        (0.0, 0.0),
+        BinMapping::Index,
+        strategy,
+        [
+            i32::as_type_native_unchecked(),
+            u32::as_type_native_unchecked(),
+        ],
+    )
+    .unwrap();
+    let actual = read::<R, u32>(&client, &output);
+
+    let mut expected = vec![0u32; num_bins];
+    for value in input_data {
+        if (0..num_bins as i32).contains(&value) {
+            expected[value as usize] += 1;
+        }
+    }
+
+    assert_eq!(actual, expected);
+}
+
+pub fn test_bincount_weighted<R: Runtime, N: Numeric + CubeElement>(device: &R::Device) {
+    let client = R::client(device);
+    if !supports_atomic_add::<R, N>(&client) {
+        return;
+    }
+
+    let shape = [8, 32];
+    let num_bins = 12;
+
+    let len = shape.iter().product();
+    let input_data: Vec<u32> = (0..len).map(|i| (i as u32 * 5) % 13).collect();
+    let weights_data: Vec<N> = (0..len).map(|i| N::from_int(i as i64 % 3)).collect();
+    let input = create(&client, &input_data, &shape);
+    let weights = create(&client, &weights_data, &shape);
+
+    let output = histogram::bincount(
+        &client,
+        &input.as_ref(),
+        Some(&weights.as_ref()),
+        num_bins,
+        u32::as_type_native_unchecked(),
+        N::as_type_native_unchecked(),
+    )
+    .unwrap();
+    let actual = read::<R, N>(&client, &output);
+
+    let mut expected = vec![0i64; num_bins];
+    for (i, value) in input_data.iter().enumerate() {
+        if (*value as usize) < num_bins {
+            expected[*value as usize] += i as i64 % 3;
+        }
+    }
+    let expected: Vec<N> = expected.into_iter().map(N::from_int).collect();
+
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,218 @@
+use cubecl_core::{
+    CubeElement,
+    ir::features::TypeUsage,
+    prelude::{Atomic, CubePrimitive, Numeric, Runtime},
+};
+
+use crate::{
+    tensor::{self, ScatterReduction},
+    tests::tensor::test_utils::{create, ravel, read, unravel},
+};
+
+/// Deterministic pseudo-random indices in `0..bound`.
+fn make_indices(num_elems: usize, bound: usize) -> Vec<i32> {
+    (0..num_elems)
+        .map(|i| ((i * 7 + 3) % bound) as i32)
+        .collect()
+}
+
+pub fn test_gather<R: Runtime, F: Numeric + CubeElement>(device: &R::Device, dim: usize) {
+    gather_with_shape::<R, F>(device, [3, 5, 8], dim);
+}
+
+/// Gathers narrow data with wider indices, so the line size of the data can't be used for the
+/// indices.
+pub fn test_gather_mixed_widths<R: Runtime>(device: &R::Device) {
+    let client = R::client(device);
+    let data_ty = u8::as_type_native_unchecked();
+    if !client
+        .properties()
+        .type_usage(data_ty)
+        .contains(TypeUsage::Buffer)
+    {
+        println!("{data_ty} not supported - skipped");
+        return;
+    }
+
+    gather_with_shape::<R, u8>(device, [2, 4, 32], 0);
+}
+
+fn gather_with_shape<R: Runtime, F: Numeric + CubeElement>(
+    device: &R::Device,
+    input_shape: [usize; 3],
+    dim: usize,
+) {
+    let client = R::client(device);
+
+    let mut indices_shape = input_shape;
+    indices_shape[dim] = 4;
+
+    let input_len = input_shape.iter().product();
+    let indices_len = indices_shape.iter().product();
+    let input_data: Vec<F> = (0..input_len).map(|i| F::from_int(i as i64)).collect();
+    let indices_data = make_indices(indices_len, input_shape[dim]);
+
+    let input = create(&client, &input_data, &input_shape);
+    let indices = create(&client, &indices_data, &indices_shape);
+
+    let output = tensor::gather(
+        &client,
+        &input.as_ref(),
+        &indices.as_ref(),
+        dim,
+        F::as_type_native_unchecked(),
+        i32::as_type_native_unchecked(),
+    )
+    .unwrap();
+
+    let expected: Vec<F> = (0..indices_len)
+        .map(|pos| {
+            let mut coords = unravel(pos, &indices_shape);
+            coords[dim] = indices_data[pos] as usize;
+            input_data[ravel(&coords, &input_shape)]
+        })
+        .collect();
+
+    assert_eq!(read::<R, F>(&client, &output), expected);
+}
+
+pub fn test_index_select<R: Runtime, F: Numeric + CubeElement>(device: &R::Device, dim: usize) {
+    let client = R::client(device);
+
+    let input_shape = [3, 5, 8];
+    let num_indices = 6;
+    let mut output_shape = input_shape;
+    output_shape[dim] = num_indices;
+
+    let input_len = input_shape.iter().product();
+    let output_len = output_shape.iter().product();
+    let input_data: Vec<F> = (0..input_len).map(|i| F::from_int(i as i64)).collect();
+    let indices_data = make_indices(num_indices, input_shape[dim]);
+
+    let input = create(&client, &input_data, &input_shape);
+    let indices = create(&client, &indices_data, &[num_indices]);
+
+    let output = tensor::index_select(
+        &client,
+        &input.as_ref(),
+        &indices.as_ref(),
+        dim,
+        F::as_type_native_unchecked(),
+        i32::as_type_native_unchecked(),
+    )
+    .unwrap();
+
+    let expected: Vec<F> = (0..output_len)
+        .map(|pos| {
+            let mut coords = unravel(pos, &output_shape);
+            coords[dim] = indices_data[coords[dim]] as usize;
+            input_data[ravel(&coords, &input_shape)]
+        })
+        .collect();
+
+    assert_eq!(read::<R, F>(&client, &output), expected);
+}
+
+pub fn test_scatter<R: Runtime, F: Numeric + CubeElement>(device: &R::Device, dim: usize) {
+    let client = R::client(device);
+
+    let output_shape = [4, 6, 8];
+    let output_len = output_shape.iter().product();
+    let output_data = vec![F::from_int(0); output_len];
+
+    // A permutation along `dim`, so no two values are written to the same position.
+    let indices_shape = output_shape;
+    let indices_data: Vec<i32> = (0..output_len)
+        .map(|pos| {
+            let coords = unravel(pos, &indices_shape);
+            ((coords[dim] + 1) % output_shape[dim]) as i32
+        })
+        .collect();
+    let values_data: Vec<F> = (0..output_len).map(|i| F::from_int(i as i64 + 1)).collect();
+
+    let output = create(&client, &output_data, &output_shape);
+    let indices = create(&client, &indices_data, &indices_shape);
+    let values = create(&client, &values_data, &indices_shape);
+
+    tensor::scatter_ref(
+        &client,
+        &output.as_ref(),
+        &indices.as_ref(),
+        &values.as_ref(),
+        dim,
+        F::as_type_native_unchecked(),
+        i32::as_type_native_unchecked(),
+    )
+    .unwrap();
+
+    let mut expected = output_data;
+    for pos in 0..output_len {
+        let mut coords = unravel(pos, &indices_shape);
+        coords[dim] = indices_data[pos] as usize;
+        expected[ravel(&coords, &output_shape)] = values_data[pos];
+    }
+
+    assert_eq!(read::<R, F>(&client, &output), expected);
+}
+
+pub fn test_scatter_reduce<R: Runtime, N: Numeric + CubeElement + PartialOrd>(
+    device: &R::Device,
+    dim: usize,
+    reduction: ScatterReduction,
+) {
+    let client = R::client(device);
+
+    let usage = match reduction {
+        ScatterReduction::Add => TypeUsage::AtomicAdd,
+        ScatterReduction::Max | ScatterReduction::Min => TypeUsage::AtomicMinMax,
+    };
+    let atomic_ty = Atomic::<N>::as_type_native_unchecked();
+    if !client.properties().type_usage(atomic_ty).contains(usage) {
+        println!("{atomic_ty} {reduction:?} not supported - skipped");
+        return;
+    }
+
+    let output_shape = [4, 4, 8];
+    let mut indices_shape = output_shape;
+    indices_shape[dim] = 9;
+
+    let output_len = output_shape.iter().product();
+    let indices_len = indices_shape.iter().product();
+    let output_data: Vec<N> = (0..output_len).map(|i| N::from_int(i as i64 % 5)).collect();
+    let indices_data = make_indices(indices_len, output_shape[dim]);
+    let values_data: Vec<N> = (0..indices_len)
+        .map(|i| N::from_int(i as i64 % 11))
+        .collect();
+
+    let output = create(&client, &output_data, &output_shape);
+    let indices = create(&client, &indices_data, &indices_shape);
+    let values = create(&client, &values_data, &indices_shape);
+
+    tensor::scatter_reduce_ref(
+        &client,
+        &output.as_ref(),
+        &indices.as_ref(),
+        &values.as_ref(),
+        dim,
+        reduction,
+        N::as_type_native_unchecked(),
+        i32::as_type_native_unchecked(),
+    )
+    .unwrap();
+
+    let mut expected = output_data;
+    for pos in 0..indices_len {
+        let mut coords = unravel(pos, &indices_shape);
+        coords[dim] = indices_data[pos] as usize;
+        let out = &mut expected[ravel(&coords, &output_shape)];
+        let value = values_data[pos];
+        *out = match reduction {
+            ScatterReduction::Add => *out + value,
+            ScatterReduction::Max if value > *out => value,
+            ScatterReduction::Min if value < *out => value,
+            _ => *out,
+        };
+    }
+
+    assert_eq!(read::<R, N>(&client, &output), expected);
+}
@@ -0,0 +1,278 @@
+use std::path::PathBuf;
+
+use cubecl_common::reader::read_sync;
+use cubecl_core::prelude::{ComputeClient, Runtime};
+
+use crate::{
+    tensor::{
+        TensorHandle,
+        io::{CheckpointFormat, TensorCheckpoint, TensorFile, TensorFileError},
+    },
+    tests::tensor::test_utils::{create, read},
+};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cubecl-io-{}-{name}", std::process::id()))
+}
+
+fn make_data(len: usize, offset: usize) -> Vec<f32> {
+    (0..len).map(|i| (i + offset) as f32).collect()
+}
+
+fn test_tensors() -> Vec<(&'static str, Vec<usize>, Vec<f32>)> {
+    vec![
+        ("weight", vec![4, 8], make_data(32, 0)),
+        ("bias", vec![8], make_data(8, 100)),
+        ("scale", vec![1], make_data(1, 200)),
+        ("embedding", vec![3, 5, 2], make_data(30, 300)),
+    ]
+}
+
+fn as_bytes(data: &[f32]) -> Vec<u8> {
+    data.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+fn write_safetensors(path: &PathBuf, tensors: &[(&str, Vec<usize>, Vec<f32>)]) {
+    let mut header = serde_json::Map::new();
+    let mut data = Vec::new();
+    header.insert(
+        "__metadata__".to_string(),
+        serde_json::json!({ "format": "pt" }),
+    );
+    for (name, shape, values) in tensors {
+        let start = data.len();
+        data.extend(as_bytes(values));
+        header.insert(
+            name.to_string(),
+            serde_json::json!({
+                "dtype": "F32",
+                "shape": shape,
+                "data_offsets": [start, data.len()],
+            }),
+        );
+    }
+
+    let mut header = serde_json::to_vec(&header).unwrap();
+    header.resize(header.len().next_multiple_of(8), b' ');
+
+    let mut file = (header.len() as u64).to_le_bytes().to_vec();
+    file.extend(header);
+    file.extend(data);
+    std::fs::write(path, file).unwrap();
+}
+
+fn npy_bytes(shape: &[usize], values: &[f32]) -> Vec<u8> {
+    let shape = shape
+        .iter()
+        .map(|dim| format!("{dim},"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut header = format!("{{'descr': '<f4', 'fortran_order': False, 'shape': ({shape}), }}");
+    header.push_str(&" ".repeat(63 - (header.len() + 10) % 64));
+    header.push('\n');
+
+    let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+    bytes.extend((header.len() as u16).to_le_bytes());
+    bytes.extend(header.as_bytes());
+    bytes.extend(as_bytes(values));
+    bytes
+}
+
+/// Writes an uncompressed zip archive, like `numpy.savez`.
+fn write_npz(path: &PathBuf, tensors: &[(&str, Vec<usize>, Vec<f32>)]) {
+    let mut file = Vec::new();
+    let mut directory = Vec::new();
+
+    for (name, shape, values) in tensors {
+        let name = format!("{name}.npy");
+        let data = npy_bytes(shape, values);
+        let offset = file.len() as u32;
+
+        file.extend(b"PK\x03\x04");
+        file.extend([20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        file.extend((data.len() as u32).to_le_bytes());
+        file.extend((data.len() as u32).to_le_bytes());
+        file.extend((name.len() as u16).to_le_bytes());
+        file.extend([0, 0]);
+        file.extend(name.as_bytes());
+        file.extend(&data);
+
+        directory.extend(b"PK\x01\x02");
+        directory.extend([20, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        directory.extend((data.len() as u32).to_le_bytes());
+        directory.extend((data.len() as u32).to_le_bytes());
+        directory.extend((name.len() as u16).to_le_bytes());
+        directory.extend([0; 12]);
+        directory.extend(offset.to_le_bytes());
+        directory.extend(name.as_bytes());
+    }
+
+    let directory_offset = file.len() as u32;
+    file.extend(&directory);
+    file.extend(b"PK\x05\x06");
+    file.extend([0, 0, 0, 0]);
+    file.extend((tensors.len() as u16).to_le_bytes());
+    file.extend((tensors.len() as u16).to_le_bytes());
+    file.extend((directory.len() as u32).to_le_bytes());
+    file.extend(directory_offset.to_le_bytes());
+    file.extend([0, 0]);
+    std::fs::write(path, file).unwrap();
+}
+
+fn assert_tensor<R: Runtime>(
+    client: &ComputeClient<R>,
+    tensor: &TensorHandle<R>,
+    shape: &[usize],
+    expected: &[f32],
+) {
+    assert_eq!(tensor.shape, shape);
+    assert_eq!(read::<R, f32>(client, tensor), expected);
+}
+
+pub fn test_load_safetensors<R: Runtime>(device: &R::Device) {
+    let client = R::client(device);
+    let tensors = test_tensors();
+    let path = temp_path("model.safetensors");
+    write_safetensors(&path, &tensors);
+
+    // A small chunk size uploads the tensors in multiple batches.
+    let file = TensorFile::open(&path).unwrap().with_chunk_size(160);
+    assert_eq!(
+        file.metadata().get("format").map(String::as_str),
+        Some("pt")
+    );
+    let names = file
+        .tensors()
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["weight", "bias", "scale", "embedding"]);
+
+    let handles = file.load(&client);
+    for ((_, shape, values), handle) in tensors.iter().zip(&handles) {
+        assert_tensor(&client, handle, shape, values);
+    }
+
+    let handles = file.load_tensors(&client, &["embedding", "bias"]).unwrap();
+    assert_tensor(&client, &handles[0], &tensors[3].1, &tensors[3].2);
+    assert_tensor(&client, &handles[1], &tensors[1].1, &tensors[1].2);
+    assert!(file.load_tensors(&client, &["missing"]).is_err());
+
+    std::fs::remove_file(path).ok();
+}
+
+pub fn test_load_npy<R: Runtime>(device: &R::Device) {
+    let client = R::client(device);
+    let (_, shape, values) = test_tensors().remove(3);
+    let path = temp_path("embedding.npy");
+    std::fs::write(&path, npy_bytes(&shape, &values)).unwrap();
+
+    let file = TensorFile::open(&path).unwrap();
+    assert_eq!(
+        file.tensors()[0].name,
+        format!("cubecl-io-{}-embedding", std::process::id())
+    );
+
+    let handles = file.load(&client);
+    assert_tensor(&client, &handles[0], &shape, &values);
+
+    std::fs::remove_file(path).ok();
+}
+
+pub fn test_load_npz<R: Runtime>(device: &R::Device) {
+    let client = R::client(device);
+    let tensors = test_tensors();
+    let path = temp_path("arrays.npz");
+    write_npz(&path, &tensors);
+
+    let file = TensorFile::open(&path).unwrap();
+    let handles = file.load(&client);
+    for ((name, shape, values), handle) in tensors.iter().zip(&handles) {
+        assert_eq!(file.tensor(name).unwrap().shape, *shape);
+        assert_tensor(&client, handle, shape, values);
+    }
+
+    std::fs::remove_file(path).ok();
+}
+
+pub fn test_overflowing_header() {
+    let shape = vec![usize::MAX, 2];
+    let values = make_data(2, 0);
+
+    let path = temp_path("overflow.safetensors");
+    write_safetensors(&path, &[("huge", shape.clone(), values.clone())]);
+    let result = TensorFile::open(&path);
+    assert!(matches!(result, Err(TensorFileError::InvalidHeader(_))));
+    std::fs::remove_file(path).ok();
+
+    let path = temp_path("overflow.npy");
+    std::fs::write(&path, npy_bytes(&shape, &values)).unwrap();
+    let result = TensorFile::open(&path);
+    assert!(matches!(result, Err(TensorFileError::InvalidHeader(_))));
+    std::fs::remove_file(path).ok();
+}
+
+pub fn test_checkpoint<R: Runtime>(device: &R::Device) {
+    let client = R::client(device);
+    let tensors = test_tensors();
+    let handles = tensors
+        .iter()
+        .map(|(_, shape, values)| create(&client, values, shape))
+        .collect::<Vec<_>>();
+
+    // A small chunk size splits the bigger tensors and groups the smaller ones.
+    let path = temp_path("checkpoint.safetensors");
+    let checkpoint = tensors
+        .iter()
+        .zip(&handles)
+        .fold(
+            TensorCheckpoint::new(&path),
+            |checkpoint, ((name, ..), handle)| checkpoint.tensor(*name, handle),
+        )
+        .chunk_size(48)
+        .max_in_flight(3);
+    read_sync(checkpoint.write(&client)).unwrap();
+
+    let file = TensorFile::open(&path).unwrap();
+    let loaded = file.load(&client);
+    for ((name, shape, values), handle) in tensors.iter().zip(&loaded) {
+        assert_eq!(file.tensor(name).unwrap().shape, *shape);
+        assert_tensor(&client, handle, shape, values);
+    }
+    std::fs::remove_file(path).ok();
+
+    let path = temp_path("checkpoint.bin");
+    let checkpoint = TensorCheckpoint::new(&path)
+        .format(CheckpointFormat::Raw)
+        .tensor("bias", &handles[1])
+        .tensor("embedding", &handles[3])
+        .chunk_size(40);
+    read_sync(checkpoint.write(&client)).unwrap();
+    let mut expected = as_bytes(&tensors[1].2);
+    expected.extend(as_bytes(&tensors[3].2));
+    assert_eq!(std::fs::read(&path).unwrap(), expected);
+
+    read_sync(handles[0].checkpoint(&client, &path)).unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), as_bytes(&tensors[0].2));
+
+    // A transposed view is written in its logical row-major order.
+    let weight = &handles[0];
+    let transposed = TensorHandle::<R>::new(
+        weight.handle.clone(),
+        vec![weight.shape[1], weight.shape[0]],
+        vec![weight.strides[1], weight.strides[0]],
+        weight.dtype,
+    );
+    read_sync(transposed.checkpoint(&client, &path)).unwrap();
+    let (rows, cols) = (tensors[0].1[0], tensors[0].1[1]);
+    let expected = (0..rows * cols)
+        .map(|i| tensors[0].2[(i % rows) * cols + i / rows])
+        .collect::<Vec<_>>();
+    assert_eq!(std::fs::read(&path).unwrap(), as_bytes(&expected));
+    std::fs::remove_file(path).ok();
+
+    let duplicated = TensorCheckpoint::new(temp_path("duplicated.safetensors"))
+        .tensor("bias", &handles[1])
+        .tensor("bias", &handles[1]);
+    assert!(read_sync(duplicated.write(&client)).is_err());
+}
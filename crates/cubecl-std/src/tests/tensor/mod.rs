@@ -1,4 +1,11 @@
+pub mod collective;
+pub mod concat;
+pub mod histogram;
 pub mod identity;
+pub mod indexing;
+pub mod io;
+pub mod pad;
+pub mod topk;
 
 mod test_macros;
 mod test_utils;
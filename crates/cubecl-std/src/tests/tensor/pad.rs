@@ -0,0 +1,84 @@
+use cubecl_core::{
+    CubeElement,
+    prelude::{Float, Runtime},
+};
+
+use crate::{
+    tensor::{self, PadMode},
+    tests::tensor::test_utils::{create, ravel, read, unravel},
+};
+
+fn pad_cpu<F: Float>(
+    data: &[F],
+    shape: &[usize],
+    padding: &[(usize, usize)],
+    mode: PadMode,
+) -> Vec<F> {
+    let out_shape = shape
+        .iter()
+        .zip(padding)
+        .map(|(size, (before, after))| before + size + after)
+        .collect::<Vec<_>>();
+
+    (0..out_shape.iter().product())
+        .map(|pos| {
+            let mut coords = Vec::new();
+            for ((coord, size), (before, _)) in
+                unravel(pos, &out_shape).into_iter().zip(shape).zip(padding)
+            {
+                let coord = coord as isize - *before as isize;
+                let size = *size as isize;
+                let coord = match mode {
+                    PadMode::Constant(value) if coord < 0 || coord >= size => {
+                        return F::new(value as f32);
+                    }
+                    PadMode::Constant(_) => coord,
+                    PadMode::Reflect if coord < 0 => -coord,
+                    PadMode::Reflect if coord >= size => 2 * (size - 1) - coord,
+                    PadMode::Reflect => coord,
+                    PadMode::Replicate => coord.clamp(0, size - 1),
+                };
+                coords.push(coord as usize);
+            }
+            data[ravel(&coords, shape)]
+        })
+        .collect()
+}
+
+fn run_pad<R: Runtime, F: Float + CubeElement>(
+    device: &R::Device,
+    shape: &[usize],
+    padding: &[(usize, usize)],
+    mode: PadMode,
+) {
+    let client = R::client(device);
+
+    let data = (0..shape.iter().product())
+        .map(|i: usize| F::new(i as f32))
+        .collect::<Vec<_>>();
+    let input = create(&client, &data, shape);
+
+    let output = tensor::pad(
+        &client,
+        &input.as_ref(),
+        padding,
+        mode,
+        F::as_type_native_unchecked(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        read::<R, F>(&client, &output),
+        pad_cpu(&data, shape, padding, mode)
+    );
+}
+
+/// Padding on the outer dimensions only, which keeps lines on the last dimension.
+pub fn test_pad_outer<R: Runtime, F: Float + CubeElement>(device: &R::Device, mode: PadMode) {
+    run_pad::<R, F>(device, &[3, 5, 8], &[(1, 2), (2, 1), (0, 0)], mode);
+}
+
+/// Padding on every dimension, including the innermost one.
+pub fn test_pad_all<R: Runtime, F: Float + CubeElement>(device: &R::Device, mode: PadMode) {
+    run_pad::<R, F>(device, &[3, 5, 4], &[(0, 2), (4, 1), (3, 2)], mode);
+}
@@ -0,0 +1,60 @@
+#![allow(missing_docs)]
+
+/// Collectives need several devices, so only runtimes with virtual devices should run them.
+#[macro_export]
+macro_rules! testgen_tensor_collective {
+    () => {
+        mod collective {
+            $crate::testgen_tensor_collective!(f32);
+        }
+    };
+    ($float:ident) => {
+            use super::*;
+            use $crate::tensor::collective::ReduceOp;
+            use $crate::tests::tensor::collective::*;
+
+            pub type FloatT = $float;
+
+            #[test]
+            pub fn test_all_reduce_sum() {
+                test_all_reduce::<TestRuntime, FloatT>(ReduceOp::Sum);
+            }
+
+            #[test]
+            pub fn test_all_reduce_mean() {
+                test_all_reduce::<TestRuntime, FloatT>(ReduceOp::Mean);
+            }
+
+            #[test]
+            pub fn test_all_reduce_max() {
+                test_all_reduce::<TestRuntime, FloatT>(ReduceOp::Max);
+            }
+
+            #[test]
+            pub fn test_reduce_scatter_sum() {
+                test_reduce_scatter::<TestRuntime, FloatT>();
+            }
+
+            #[test]
+            pub fn test_all_gather_ranks() {
+                test_all_gather::<TestRuntime, FloatT>();
+            }
+
+            #[test]
+            pub fn test_broadcast_tree() {
+                test_broadcast::<TestRuntime, FloatT>();
+            }
+    };
+    ([$($float:ident),*]) => {
+        mod collective {
+            use super::*;
+            ::paste::paste! {
+                $(mod [<$float _ty>] {
+                    use super::*;
+
+                    $crate::testgen_tensor_collective!($float);
+                })*
+            }
+        }
+    };
+}
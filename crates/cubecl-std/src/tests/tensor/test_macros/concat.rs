@@ -0,0 +1,63 @@
+#![allow(missing_docs)]
+
+#[macro_export]
+macro_rules! testgen_tensor_concat {
+    () => {
+        mod concat {
+            $crate::testgen_tensor_concat!(f32);
+        }
+    };
+    ($float:ident) => {
+            use super::*;
+            use $crate::tests::tensor::concat::*;
+
+            pub type FloatT = $float;
+
+            #[test]
+            pub fn test_concat_first_dim() {
+                test_concat::<TestRuntime, FloatT>(&Default::default(), 0);
+            }
+
+            #[test]
+            pub fn test_concat_middle_dim() {
+                test_concat::<TestRuntime, FloatT>(&Default::default(), 1);
+            }
+
+            #[test]
+            pub fn test_concat_last_dim() {
+                test_concat::<TestRuntime, FloatT>(&Default::default(), 2);
+            }
+
+            #[test]
+            pub fn test_concat_strided_input() {
+                test_concat_strided::<TestRuntime, FloatT>(&Default::default());
+            }
+
+            #[test]
+            pub fn test_split_first_dim() {
+                test_split::<TestRuntime, FloatT>(&Default::default(), 0);
+            }
+
+            #[test]
+            pub fn test_split_last_dim() {
+                test_split::<TestRuntime, FloatT>(&Default::default(), 2);
+            }
+
+            #[test]
+            pub fn test_split_strided_input() {
+                test_split_strided::<TestRuntime, FloatT>(&Default::default());
+            }
+    };
+    ([$($float:ident),*]) => {
+        mod concat {
+            use super::*;
+            ::paste::paste! {
+                $(mod [<$float _ty>] {
+                    use super::*;
+
+                    $crate::testgen_tensor_concat!($float);
+                })*
+            }
+        }
+    };
+}
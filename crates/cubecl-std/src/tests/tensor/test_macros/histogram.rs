@@ -0,0 +1,54 @@
+#![allow(missing_docs)]
+
+#[macro_export]
+macro_rules! testgen_tensor_histogram {
+    () => {
+        mod histogram {
+            $crate::testgen_tensor_histogram!(f32);
+        }
+    };
+    ($float:ident) => {
+            use super::*;
+            use $crate::tensor::histogram::HistogramStrategy;
+            use $crate::tests::tensor::histogram::*;
+
+            pub type FloatT = $float;
+
+            #[test]
+            pub fn test_histogram_range() {
+                test_histogram::<TestRuntime, FloatT>(&Default::default());
+            }
+
+            #[test]
+            pub fn test_bincount_privatized() {
+                test_bincount::<TestRuntime>(&Default::default(), HistogramStrategy::Privatized);
+            }
+
+            #[test]
+            pub fn test_bincount_global() {
+                test_bincount::<TestRuntime>(&Default::default(), HistogramStrategy::Global);
+            }
+
+            #[test]
+            pub fn test_bincount_weighted_float() {
+                test_bincount_weighted::<TestRuntime, f32>(&Default::default());
+            }
+
+            #[test]
+            pub fn test_bincount_weighted_int() {
+                test_bincount_weighted::<TestRuntime, u32>(&Default::default());
+            }
+    };
+    ([$($float:ident),*]) => {
+        mod histogram {
+            use super::*;
+            ::paste::paste! {
+                $(mod [<$float _ty>] {
+                    use super::*;
+
+                    $crate::testgen_tensor_histogram!($float);
+                })*
+            }
+        }
+    };
+}
@@ -0,0 +1,100 @@
+#![allow(missing_docs)]
+
+#[macro_export]
+macro_rules! testgen_tensor_indexing {
+    () => {
+        mod indexing {
+            $crate::testgen_tensor_indexing!(f32);
+        }
+    };
+    ($numeric:ident) => {
+            use super::*;
+            use $crate::tensor::ScatterReduction;
+            use $crate::tests::tensor::indexing::*;
+
+            pub type NumericT = $numeric;
+
+            #[test]
+            pub fn test_gather_outer() {
+                test_gather::<TestRuntime, NumericT>(&Default::default(), 0);
+            }
+
+            #[test]
+            pub fn test_gather_inner() {
+                test_gather::<TestRuntime, NumericT>(&Default::default(), 2);
+            }
+
+            #[test]
+            pub fn test_gather_narrow_data() {
+                test_gather_mixed_widths::<TestRuntime>(&Default::default());
+            }
+
+            #[test]
+            pub fn test_index_select_outer() {
+                test_index_select::<TestRuntime, NumericT>(&Default::default(), 1);
+            }
+
+            #[test]
+            pub fn test_index_select_inner() {
+                test_index_select::<TestRuntime, NumericT>(&Default::default(), 2);
+            }
+
+            #[test]
+            pub fn test_scatter_outer() {
+                test_scatter::<TestRuntime, NumericT>(&Default::default(), 0);
+            }
+
+            #[test]
+            pub fn test_scatter_inner() {
+                test_scatter::<TestRuntime, NumericT>(&Default::default(), 2);
+            }
+
+            #[test]
+            pub fn test_scatter_add() {
+                test_scatter_reduce::<TestRuntime, NumericT>(
+                    &Default::default(),
+                    1,
+                    ScatterReduction::Add,
+                );
+            }
+
+            #[test]
+            pub fn test_scatter_add_int() {
+                test_scatter_reduce::<TestRuntime, i32>(
+                    &Default::default(),
+                    2,
+                    ScatterReduction::Add,
+                );
+            }
+
+            #[test]
+            pub fn test_scatter_max() {
+                test_scatter_reduce::<TestRuntime, i32>(
+                    &Default::default(),
+                    0,
+                    ScatterReduction::Max,
+                );
+            }
+
+            #[test]
+            pub fn test_scatter_min() {
+                test_scatter_reduce::<TestRuntime, i32>(
+                    &Default::default(),
+                    1,
+                    ScatterReduction::Min,
+                );
+            }
+    };
+    ([$($numeric:ident),*]) => {
+        mod indexing {
+            use super::*;
+            ::paste::paste! {
+                $(mod [<$numeric _ty>] {
+                    use super::*;
+
+                    $crate::testgen_tensor_indexing!($numeric);
+                })*
+            }
+        }
+    };
+}
@@ -0,0 +1,36 @@
+#![allow(missing_docs)]
+
+#[macro_export]
+macro_rules! testgen_tensor_io {
+    () => {
+        mod io {
+            use super::*;
+            use $crate::tests::tensor::io::*;
+
+            #[test]
+            pub fn test_load_safetensors_file() {
+                test_load_safetensors::<TestRuntime>(&Default::default());
+            }
+
+            #[test]
+            pub fn test_load_npy_file() {
+                test_load_npy::<TestRuntime>(&Default::default());
+            }
+
+            #[test]
+            pub fn test_load_npz_file() {
+                test_load_npz::<TestRuntime>(&Default::default());
+            }
+
+            #[test]
+            pub fn test_overflowing_header_file() {
+                test_overflowing_header();
+            }
+
+            #[test]
+            pub fn test_checkpoint_tensors() {
+                test_checkpoint::<TestRuntime>(&Default::default());
+            }
+        }
+    };
+}
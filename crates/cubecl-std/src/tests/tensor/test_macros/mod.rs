@@ -1 +1,8 @@
+mod collective;
+mod concat;
+mod histogram;
 mod identity;
+mod indexing;
+mod io;
+mod pad;
+mod topk;
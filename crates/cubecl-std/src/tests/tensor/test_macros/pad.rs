@@ -0,0 +1,59 @@
+#![allow(missing_docs)]
+
+#[macro_export]
+macro_rules! testgen_tensor_pad {
+    () => {
+        mod pad {
+            $crate::testgen_tensor_pad!(f32);
+        }
+    };
+    ($float:ident) => {
+            use super::*;
+            use $crate::tensor::PadMode;
+            use $crate::tests::tensor::pad::*;
+
+            pub type FloatT = $float;
+
+            #[test]
+            pub fn test_pad_outer_constant() {
+                test_pad_outer::<TestRuntime, FloatT>(&Default::default(), PadMode::Constant(-1.5));
+            }
+
+            #[test]
+            pub fn test_pad_outer_reflect() {
+                test_pad_outer::<TestRuntime, FloatT>(&Default::default(), PadMode::Reflect);
+            }
+
+            #[test]
+            pub fn test_pad_outer_replicate() {
+                test_pad_outer::<TestRuntime, FloatT>(&Default::default(), PadMode::Replicate);
+            }
+
+            #[test]
+            pub fn test_pad_all_constant() {
+                test_pad_all::<TestRuntime, FloatT>(&Default::default(), PadMode::Constant(0.0));
+            }
+
+            #[test]
+            pub fn test_pad_all_reflect() {
+                test_pad_all::<TestRuntime, FloatT>(&Default::default(), PadMode::Reflect);
+            }
+
+            #[test]
+            pub fn test_pad_all_replicate() {
+                test_pad_all::<TestRuntime, FloatT>(&Default::default(), PadMode::Replicate);
+            }
+    };
+    ([$($float:ident),*]) => {
+        mod pad {
+            use super::*;
+            ::paste::paste! {
+                $(mod [<$float _ty>] {
+                    use super::*;
+
+                    $crate::testgen_tensor_pad!($float);
+                })*
+            }
+        }
+    };
+}
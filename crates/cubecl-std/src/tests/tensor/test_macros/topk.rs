@@ -0,0 +1,84 @@
+#![allow(missing_docs)]
+
+#[macro_export]
+macro_rules! testgen_tensor_topk {
+    () => {
+        mod topk {
+            $crate::testgen_tensor_topk!(f32);
+        }
+    };
+    ($float:ident) => {
+            use super::*;
+            use $crate::tensor::topk::TopKStrategy;
+            use $crate::tests::tensor::topk::*;
+
+            pub type FloatT = $float;
+
+            #[test]
+            pub fn test_topk_last_dim_bitonic() {
+                test_topk_last_dim::<TestRuntime, FloatT>(&Default::default(), TopKStrategy::BitonicSort, 7);
+            }
+
+            #[test]
+            pub fn test_topk_last_dim_radix() {
+                test_topk_last_dim::<TestRuntime, FloatT>(&Default::default(), TopKStrategy::RadixSelect, 300);
+            }
+
+            #[test]
+            pub fn test_topk_first_dim_bitonic() {
+                test_topk_first_dim::<TestRuntime, FloatT>(&Default::default(), TopKStrategy::BitonicSort, 16);
+            }
+
+            #[test]
+            pub fn test_topk_first_dim_radix() {
+                test_topk_first_dim::<TestRuntime, FloatT>(&Default::default(), TopKStrategy::RadixSelect, 100);
+            }
+
+            #[test]
+            pub fn test_topk_smallest_bitonic() {
+                test_topk_smallest::<TestRuntime, FloatT>(&Default::default(), TopKStrategy::BitonicSort, 10);
+            }
+
+            #[test]
+            pub fn test_topk_smallest_radix() {
+                test_topk_smallest::<TestRuntime, FloatT>(&Default::default(), TopKStrategy::RadixSelect, 90);
+            }
+
+            #[test]
+            pub fn test_topk_int_bitonic() {
+                test_topk_int::<TestRuntime>(&Default::default(), TopKStrategy::BitonicSort, 20);
+            }
+
+            #[test]
+            pub fn test_topk_int_radix() {
+                test_topk_int::<TestRuntime>(&Default::default(), TopKStrategy::RadixSelect, 200);
+            }
+
+            #[test]
+            pub fn test_topk_unsupported_types() {
+                test_topk_unsupported_dtype::<TestRuntime>(&Default::default());
+            }
+
+            #[test]
+            pub fn test_topk_full_row_bitonic() {
+                test_topk_full_row::<TestRuntime, FloatT>(&Default::default(), TopKStrategy::BitonicSort);
+            }
+
+            #[test]
+            pub fn test_topk_full_row_radix() {
+                test_topk_full_row::<TestRuntime, FloatT>(&Default::default(), TopKStrategy::RadixSelect);
+            }
+    };
+    ([$($float:ident),*]) => {
+        mod topk {
+            use super::*;
+            ::paste::paste! {
+                $(mod [<$float _ty>] {
+                    use super::*;
+
+                    $crate::testgen_tensor_topk!($float);
+                })*
+            }
+        }
+    };
+}
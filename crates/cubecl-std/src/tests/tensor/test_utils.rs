@@ -1,4 +1,9 @@
-use cubecl_core::{CubeElement, prelude::Numeric};
+use cubecl_core::{
+    CubeElement,
+    prelude::{ComputeClient, CubePrimitive, Numeric, Runtime},
+};
+
+use crate::tensor::TensorHandle;
 
 pub(crate) fn identity_cpu<E: Numeric + CubeElement>(dim: usize) -> Vec<E> {
     let num_elements = dim * dim;
@@ -12,3 +17,49 @@ pub(crate) fn identity_cpu<E: Numeric + CubeElement>(dim: usize) -> Vec<E> {
 
     result
 }
+
+pub(crate) fn create<R: Runtime, E: CubePrimitive + CubeElement>(
+    client: &ComputeClient<R>,
+    data: &[E],
+    shape: &[usize],
+) -> TensorHandle<R> {
+    let handle = client.create_from_slice(E::as_bytes(data));
+    TensorHandle::new_contiguous(shape.to_vec(), handle, E::as_type_native_unchecked())
+}
+
+pub(crate) fn read<R: Runtime, E: CubeElement>(
+    client: &ComputeClient<R>,
+    tensor: &TensorHandle<R>,
+) -> Vec<E> {
+    let bytes = client.read_one_tensor(tensor.handle.clone().copy_descriptor(
+        &tensor.shape,
+        &tensor.strides,
+        size_of::<E>(),
+    ));
+    E::from_bytes(&bytes).to_vec()
+}
+
+pub(crate) fn contiguous_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for i in (0..shape.len() - 1).rev() {
+        strides[i] = strides[i + 1] * shape[i + 1];
+    }
+    strides
+}
+
+pub(crate) fn unravel(mut pos: usize, shape: &[usize]) -> Vec<usize> {
+    let mut coords = vec![0; shape.len()];
+    for d in (0..shape.len()).rev() {
+        coords[d] = pos % shape[d];
+        pos /= shape[d];
+    }
+    coords
+}
+
+pub(crate) fn ravel(coords: &[usize], shape: &[usize]) -> usize {
+    coords
+        .iter()
+        .zip(contiguous_strides(shape))
+        .map(|(c, s)| c * s)
+        .sum()
+}
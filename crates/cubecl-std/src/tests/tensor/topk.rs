@@ -0,0 +1,216 @@
+use cubecl_core::{
+    CubeElement,
+    ir::features::TypeUsage,
+    prelude::{Atomic, ComputeClient, CubePrimitive, Float, Runtime},
+};
+
+use crate::{
+    tensor::{
+        TensorHandle,
+        topk::{self, TopKStrategy},
+    },
+    tests::tensor::test_utils::{contiguous_strides, create, read},
+};
+
+fn supports_strategy<R: Runtime>(client: &ComputeClient<R>, strategy: TopKStrategy) -> bool {
+    let supported = match strategy {
+        TopKStrategy::BitonicSort => true,
+        TopKStrategy::RadixSelect => client
+            .properties()
+            .type_usage(Atomic::<u32>::as_type_native_unchecked())
+            .contains(TypeUsage::AtomicAdd),
+    };
+    if !supported {
+        println!("{strategy:?} not supported - skipped");
+    }
+    supported
+}
+
+/// Top-k of each row along `dim` on the CPU, with ties ordered by increasing position.
+fn topk_cpu<E: Copy + PartialOrd>(
+    data: &[E],
+    shape: &[usize],
+    k: usize,
+    dim: usize,
+    largest: bool,
+) -> (Vec<E>, Vec<u32>) {
+    let strides = contiguous_strides(shape);
+    let mut out_shape = shape.to_vec();
+    out_shape[dim] = k;
+    let out_strides = contiguous_strides(&out_shape);
+
+    let num_elems = out_shape.iter().product();
+    let mut values = vec![data[0]; num_elems];
+    let mut indices = vec![0; num_elems];
+
+    let num_rows = num_elems / k;
+    for row in 0..num_rows {
+        let (mut input_offset, mut output_offset, mut remaining) = (0, 0, row);
+        for d in (0..shape.len()).rev().filter(|d| *d != dim) {
+            input_offset += (remaining % shape[d]) * strides[d];
+            output_offset += (remaining % shape[d]) * out_strides[d];
+            remaining /= shape[d];
+        }
+
+        let value = |pos: usize| data[input_offset + pos * strides[dim]];
+        let mut positions = (0..shape[dim]).collect::<Vec<_>>();
+        positions.sort_by(|a, b| {
+            let ordering = value(*b).partial_cmp(&value(*a)).unwrap();
+            match largest {
+                true => ordering,
+                false => ordering.reverse(),
+            }
+            .then(a.cmp(b))
+        });
+
+        for (i, pos) in positions.into_iter().take(k).enumerate() {
+            values[output_offset + i * out_strides[dim]] = value(pos);
+            indices[output_offset + i * out_strides[dim]] = pos as u32;
+        }
+    }
+
+    (values, indices)
+}
+
+fn run_topk<R: Runtime, E: CubePrimitive + CubeElement + PartialOrd + Copy + core::fmt::Debug>(
+    client: &ComputeClient<R>,
+    data: &[E],
+    shape: &[usize],
+    k: usize,
+    dim: usize,
+    largest: bool,
+    strategy: TopKStrategy,
+) {
+    let input = create(client, data, shape);
+    let mut out_shape = shape.to_vec();
+    out_shape[dim] = k;
+
+    let dtype = E::as_type_native_unchecked();
+    let index_dtype = u32::as_type_native_unchecked();
+    let values = TensorHandle::<R>::empty(client, out_shape.clone(), dtype);
+    let indices = TensorHandle::<R>::empty(client, out_shape, index_dtype);
+
+    topk::topk_ref(
+        client,
+        &input.as_ref(),
+        &values.as_ref(),
+        &indices.as_ref(),
+        dim,
+        largest,
+        strategy,
+        dtype,
+        index_dtype,
+    )
+    .unwrap();
+
+    let (expected_values, expected_indices) = topk_cpu(data, shape, k, dim, largest);
+    assert_eq!(read::<R, E>(client, &values), expected_values);
+    assert_eq!(read::<R, u32>(client, &indices), expected_indices);
+}
+
+/// Exactly representable values with many ties.
+fn tied_data<F: Float>(len: usize) -> Vec<F> {
+    (0..len)
+        .map(|i| F::new(((i * 37) % 50) as f32 / 4.0 - 6.0))
+        .collect()
+}
+
+pub fn test_topk_last_dim<R: Runtime, F: Float + CubeElement>(
+    device: &R::Device,
+    strategy: TopKStrategy,
+    k: usize,
+) {
+    let client = R::client(device);
+    if !supports_strategy(&client, strategy) {
+        return;
+    }
+
+    let shape = [3, 2500];
+    let data = tied_data::<F>(shape.iter().product());
+
+    run_topk(&client, &data, &shape, k, 1, true, strategy);
+}
+
+pub fn test_topk_first_dim<R: Runtime, F: Float + CubeElement>(
+    device: &R::Device,
+    strategy: TopKStrategy,
+    k: usize,
+) {
+    let client = R::client(device);
+    if !supports_strategy(&client, strategy) {
+        return;
+    }
+
+    let shape = [700, 2, 3];
+    let data = tied_data::<F>(shape.iter().product());
+
+    run_topk(&client, &data, &shape, k, 0, true, strategy);
+}
+
+pub fn test_topk_smallest<R: Runtime, F: Float + CubeElement>(
+    device: &R::Device,
+    strategy: TopKStrategy,
+    k: usize,
+) {
+    let client = R::client(device);
+    if !supports_strategy(&client, strategy) {
+        return;
+    }
+
+    let shape = [4, 600];
+    let data = tied_data::<F>(shape.iter().product());
+
+    run_topk(&client, &data, &shape, k, 1, false, strategy);
+}
+
+pub fn test_topk_int<R: Runtime>(device: &R::Device, strategy: TopKStrategy, k: usize) {
+    let client = R::client(device);
+    if !supports_strategy(&client, strategy) {
+        return;
+    }
+
+    let shape = [2, 1000];
+    let data = (0..2000)
+        .map(|i| (i * 7919 % 301) - 150)
+        .collect::<Vec<i32>>();
+
+    run_topk(&client, &data, &shape, k, 1, true, strategy);
+}
+
+pub fn test_topk_unsupported_dtype<R: Runtime>(device: &R::Device) {
+    let client = R::client(device);
+    let shape = [2, 16];
+
+    for dtype in [
+        i64::as_type_native_unchecked(),
+        f64::as_type_native_unchecked(),
+        bool::as_type_native_unchecked(),
+    ] {
+        let input = TensorHandle::<R>::empty(&client, shape.to_vec(), dtype);
+        let result = topk::topk(
+            &client,
+            &input.as_ref(),
+            4,
+            1,
+            true,
+            dtype,
+            u32::as_type_native_unchecked(),
+        );
+        assert!(result.is_err(), "Top-k on {dtype} should fail");
+    }
+}
+
+pub fn test_topk_full_row<R: Runtime, F: Float + CubeElement>(
+    device: &R::Device,
+    strategy: TopKStrategy,
+) {
+    let client = R::client(device);
+    if !supports_strategy(&client, strategy) {
+        return;
+    }
+
+    let shape = [5, 100];
+    let data = tied_data::<F>(shape.iter().product());
+
+    run_topk(&client, &data, &shape, 100, 1, true, strategy);
+}
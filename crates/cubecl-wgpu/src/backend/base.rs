@@ -1,12 +1,13 @@
 use super::wgsl;
 use crate::{AutoCompiler, AutoRepresentation, WgpuServer};
 use cubecl_core::{ExecutionMode, WgpuCompilationOptions, prelude::CompiledKernel};
-use cubecl_ir::DeviceProperties;
+use cubecl_ir::{ConstantValue, DeviceProperties, Sampler, TextureDim};
 use cubecl_runtime::compiler::CompilationError;
 use std::{borrow::Cow, sync::Arc};
 use wgpu::{
     Adapter, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType, BufferBindingType,
-    ComputePipeline, Device, PipelineLayoutDescriptor, Queue, ShaderModuleDescriptor, ShaderStages,
+    ComputePipeline, Device, PipelineLayoutDescriptor, Queue, SamplerBindingType,
+    ShaderModuleDescriptor, ShaderStages, TextureSampleType, TextureViewDimension,
 };
 
 #[cfg(not(target_family = "wasm"))]
@@ -23,13 +24,14 @@ use cubecl_cpp::metal as cpp_metal;
 impl WgpuServer {
     pub fn create_pipeline(
         &mut self,
-        kernel: CompiledKernel<AutoCompiler>,
+        kernel: &CompiledKernel<AutoCompiler>,
         mode: ExecutionMode,
+        spec_constants: &[ConstantValue],
     ) -> Result<Arc<ComputePipeline>, CompilationError> {
         let module = match &kernel.repr {
             #[cfg(feature = "spirv")]
             Some(AutoRepresentation::SpirV(repr)) => {
-                let spirv = repr.assemble();
+                let spirv = repr.specialize(spec_constants);
                 unsafe {
                     self.device.create_shader_module_passthrough(
                         wgpu::ShaderModuleDescriptorPassthrough::SpirV(
@@ -104,30 +106,47 @@ impl WgpuServer {
         };
 
         let layout = bindings_info.map(|bindings| {
-            let (mut bindings, meta) = bindings;
+            let (mut bindings, meta, textures) = bindings;
             // When slices are shared, it needs to be read-write if ANY of the slices is read-write,
             // and since we can't be sure, we'll assume everything is read-write.
             if !cfg!(exclusive_memory_only) {
                 bindings.fill(cubecl_runtime::kernel::Visibility::ReadWrite);
             }
 
-            let bindings = bindings
+            let buffers = bindings
                 .into_iter()
                 .chain(meta)
+                .map(|visibility| BindingType::Buffer {
+                    ty: BufferBindingType::Storage {
+                        read_only: matches!(visibility, cubecl_runtime::kernel::Visibility::Read),
+                    },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                });
+            // Textures are followed by one sampler per sampler state.
+            let samplers = match textures.is_empty() {
+                true => 0,
+                false => Sampler::COUNT,
+            };
+            let samplers =
+                (0..samplers).map(|_| BindingType::Sampler(SamplerBindingType::Filtering));
+            let textures = textures.into_iter().map(|dim| BindingType::Texture {
+                sample_type: TextureSampleType::Float { filterable: true },
+                view_dimension: match dim {
+                    TextureDim::D2 => TextureViewDimension::D2,
+                    TextureDim::D3 => TextureViewDimension::D3,
+                },
+                multisampled: false,
+            });
+
+            let bindings = buffers
+                .chain(textures)
+                .chain(samplers)
                 .enumerate()
-                .map(|(i, visibility)| BindGroupLayoutEntry {
+                .map(|(i, ty)| BindGroupLayoutEntry {
                     binding: i as u32,
                     visibility: ShaderStages::COMPUTE,
-                    ty: BindingType::Buffer {
-                        ty: BufferBindingType::Storage {
-                            read_only: matches!(
-                                visibility,
-                                cubecl_runtime::kernel::Visibility::Read
-                            ),
-                        },
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
+                    ty,
                     count: None,
                 })
                 .collect::<Vec<_>>();
@@ -145,6 +164,24 @@ impl WgpuServer {
                 })
         });
 
+        // Only WGSL takes the constants at pipeline creation, SPIR-V is specialized when the module
+        // is created.
+        let constants: Vec<(String, f64)> = match &kernel.repr {
+            Some(AutoRepresentation::Wgsl(repr)) => repr
+                .spec_constants
+                .iter()
+                .filter_map(|(id, _)| {
+                    let value = spec_constants.get(*id as usize)?;
+                    Some((id.to_string(), value.as_f64()))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let constants = constants
+            .iter()
+            .map(|(id, value)| (id.as_str(), *value))
+            .collect::<Vec<_>>();
+
         let pipeline = self
             .device
             .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
@@ -153,8 +190,8 @@ impl WgpuServer {
                 module: &module,
                 entry_point: Some(&kernel.entrypoint_name),
                 compilation_options: wgpu::PipelineCompilationOptions {
+                    constants: &constants,
                     zero_initialize_workgroup_memory: false,
-                    ..Default::default()
                 },
                 cache: None,
             });
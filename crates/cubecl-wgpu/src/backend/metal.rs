@@ -82,6 +82,8 @@ fn register_features(
     props.features.alignment = true;
     props.features.plane.insert(Plane::Ops);
     props.features.plane.insert(Plane::Sync);
+    // Float atomics can only be added natively.
+    props.features.atomic_polyfills = true;
 }
 
 fn register_types(props: &mut DeviceProperties) {
@@ -4,7 +4,7 @@ use cubecl_core::{
     prelude::{CompiledKernel, Visibility},
     server::ComputeServer,
 };
-use cubecl_ir::{DeviceProperties, features::*};
+use cubecl_ir::{DeviceProperties, TextureDim, features::*};
 use cubecl_runtime::compiler::CompilationError;
 use cubecl_spirv::{GLCompute, SpirvCompiler, SpirvKernel};
 use features::ExtendedFeatures;
@@ -26,14 +26,14 @@ mod features;
 
 pub type VkSpirvCompiler = SpirvCompiler<GLCompute>;
 
-pub fn bindings(repr: &SpirvKernel) -> (Vec<Visibility>, Vec<Visibility>) {
+pub fn bindings(repr: &SpirvKernel) -> (Vec<Visibility>, Vec<Visibility>, Vec<TextureDim>) {
     let bindings: Vec<_> = repr.bindings.iter().map(|it| it.visibility).collect();
     let mut meta = vec![];
     if repr.has_metadata {
         meta.push(Visibility::Read);
     }
     meta.extend(repr.scalars.iter().map(|_| Visibility::Read));
-    (bindings, meta)
+    (bindings, meta, repr.textures.clone())
 }
 
 pub async fn request_vulkan_device(adapter: &wgpu::Adapter) -> (wgpu::Device, wgpu::Queue) {
@@ -159,6 +159,8 @@ fn register_features(
     register_types(props, &extended_feat);
     comp_options.supports_u64 = true;
     props.features.plane.insert(Plane::Sync);
+    props.features.spec_constants = true;
+    props.features.atomic_polyfills = true;
 
     if let Some(float_controls2) = &extended_feat.float_controls2
         && float_controls2.shader_float_controls2 == TRUE
@@ -214,6 +216,12 @@ fn register_types(props: &mut DeviceProperties, ext_feat: &ExtendedFeatures<'_>)
         register(StorageType::Atomic(ty), TypeUsage::all_atomic())
     }
 
+    // Lowered to pairs of `f32`, see `Df64EmulationProcessor`.
+    register(
+        ElemType::Float(FloatKind::DF64).into(),
+        TypeUsage::Conversion | TypeUsage::Arithmetic | TypeUsage::Buffer,
+    );
+
     if ext_feat.float16_int8.shader_float16 == TRUE {
         register(
             ElemType::Float(FloatKind::F16).into(),
@@ -1,4 +1,4 @@
-use cubecl_core::{Compiler, prelude::Visibility};
+use cubecl_core::{Compiler, ir::TextureDim, prelude::Visibility};
 #[cfg(not(all(target_os = "macos", feature = "msl")))]
 use cubecl_core::{
     WgpuCompilationOptions,
@@ -13,7 +13,7 @@ use crate::WgslCompiler;
 
 pub fn bindings(
     repr: &<WgslCompiler as Compiler>::Representation,
-) -> (Vec<Visibility>, Vec<Visibility>) {
+) -> (Vec<Visibility>, Vec<Visibility>, Vec<TextureDim>) {
     let bindings = repr
         .buffers
         .iter()
@@ -24,7 +24,7 @@ pub fn bindings(
         meta.push(Visibility::Read);
     }
     meta.extend(repr.scalars.iter().map(|_| Visibility::Read));
-    (bindings, meta)
+    (bindings, meta, repr.textures.clone())
 }
 
 #[cfg(not(all(target_os = "macos", feature = "msl")))]
@@ -61,7 +61,9 @@ pub fn register_wgsl_features(
     comp_options: &mut WgpuCompilationOptions,
 ) {
     register_types(props, adapter);
-    if props.supports_type(ElemType::UInt(UIntKind::U64)) {
+    props.features.spec_constants = true;
+    props.features.atomic_polyfills = true;
+    if props.supports_type(ElemType::UInt(UIntKind::U64)) && !props.features.int64_emulation {
         comp_options.supports_u64 = true;
     }
 }
@@ -92,10 +94,7 @@ pub fn register_types(props: &mut DeviceProperties, adapter: &wgpu::Adapter) {
     }
 
     for ty in supported_atomic_types {
-        register(
-            StorageType::Atomic(ty),
-            TypeUsage::AtomicLoadStore | TypeUsage::AtomicAdd,
-        )
+        register(StorageType::Atomic(ty), TypeUsage::all_atomic())
     }
 
     let feats = adapter.features();
@@ -106,7 +105,17 @@ pub fn register_types(props: &mut DeviceProperties, adapter: &wgpu::Adapter) {
             ElemType::UInt(UIntKind::U64).into(),
             TypeUsage::all_scalar(),
         );
+    } else {
+        // Lowered to pairs of 32-bit words, see `Int64EmulationProcessor`.
+        let emulated = TypeUsage::Conversion | TypeUsage::Arithmetic | TypeUsage::Buffer;
+        register(ElemType::Int(IntKind::I64).into(), emulated);
+        register(ElemType::UInt(UIntKind::U64).into(), emulated);
     }
+    // Lowered to pairs of `f32`, see `Df64EmulationProcessor`.
+    register(
+        ElemType::Float(FloatKind::DF64).into(),
+        TypeUsage::Conversion | TypeUsage::Arithmetic | TypeUsage::Buffer,
+    );
     if feats.contains(wgpu::Features::SHADER_F64) {
         register(
             ElemType::Float(FloatKind::F64).into(),
@@ -125,4 +134,20 @@ pub fn register_types(props: &mut DeviceProperties, adapter: &wgpu::Adapter) {
             TypeUsage::AtomicLoadStore | TypeUsage::AtomicAdd,
         );
     }
+
+    // Narrow types are packed into `u32` words in buffers and computed on in 32-bit registers, so
+    // they can only be stored and converted.
+    let packed_types = [
+        ElemType::UInt(UIntKind::U8),
+        ElemType::Int(IntKind::I8),
+        ElemType::UInt(UIntKind::U16),
+        ElemType::Int(IntKind::I16),
+        ElemType::Float(FloatKind::BF16),
+    ];
+
+    for ty in packed_types {
+        register(ty.into(), TypeUsage::Conversion | TypeUsage::Buffer);
+    }
+
+    props.features.int64_emulation = !feats.contains(wgpu::Features::SHADER_INT64);
 }
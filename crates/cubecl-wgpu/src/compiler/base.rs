@@ -8,7 +8,7 @@ use cubecl_core::{
 };
 #[cfg(feature = "msl")]
 use cubecl_cpp::shared::MslComputeKernel;
-use cubecl_runtime::compiler::CompilationError;
+use cubecl_runtime::{compiler::CompilationError, kernel::KernelResourceUsage};
 use derive_more::derive::From;
 
 use crate::{WgpuServer, WgslCompiler};
@@ -102,6 +102,24 @@ impl Compiler for AutoCompiler {
         Ok(kernel)
     }
 
+    fn resource_usage(&self, kernel: &Self::Representation) -> KernelResourceUsage {
+        match (self, kernel) {
+            (AutoCompiler::Wgsl(wgsl_compiler), AutoRepresentation::Wgsl(shader)) => {
+                wgsl_compiler.resource_usage(shader)
+            }
+            #[cfg(feature = "spirv")]
+            (AutoCompiler::SpirV(spirv_compiler), AutoRepresentation::SpirV(kernel)) => {
+                spirv_compiler.resource_usage(kernel)
+            }
+            #[cfg(feature = "msl")]
+            (AutoCompiler::Msl(msl_compiler), AutoRepresentation::Msl(kernel)) => {
+                msl_compiler.resource_usage(kernel)
+            }
+            #[allow(unreachable_patterns)]
+            _ => KernelResourceUsage::default(),
+        }
+    }
+
     fn elem_size(&self, elem: cubecl_core::ir::ElemType) -> usize {
         match self {
             AutoCompiler::Wgsl(wgsl_compiler) => wgsl_compiler.elem_size(elem),
@@ -1,3 +1,4 @@
+use super::{Packing, fmt_packed_scalar};
 use crate::compiler::wgsl::Item::Scalar;
 use cubecl_core::ir::{ConstantValue, Id};
 use std::fmt::Display;
@@ -7,6 +8,9 @@ pub enum Variable {
     GlobalInputArray(Id, Item),
     GlobalOutputArray(Id, Item),
     GlobalScalar(Id, Elem),
+    GlobalPackedScalar(Id, Packing),
+    /// Emulated 64-bit integer or `df64` scalar, read as a `vec2` of its 32-bit words.
+    GlobalScalar64(Id, Elem),
     Constant(ConstantValue, Item),
     LocalMut {
         id: Id,
@@ -30,6 +34,8 @@ pub enum Variable {
     SharedValue(Id, Item),
     ConstantArray(Id, Item, u32),
     LocalArray(Id, Item, u32),
+    /// Texture binding, read as texels of `Item`.
+    Texture(Id, Item),
     Id,
     LocalInvocationIndex,
     LocalInvocationIdX,
@@ -87,6 +93,8 @@ impl Variable {
     pub fn is_always_scalar(&self) -> bool {
         match self {
             Variable::GlobalScalar(_, _) => true,
+            Variable::GlobalPackedScalar(_, _) => true,
+            Variable::GlobalScalar64(_, _) => false,
             Variable::Constant(_, _) => true,
             Variable::LocalScalar { .. } => true,
             Variable::Id => true,
@@ -100,6 +108,7 @@ impl Variable {
             Variable::SharedValue(_, _) => false,
             Variable::ConstantArray(_, _, _) => false,
             Variable::LocalArray(_, _, _) => false,
+            Variable::Texture(_, _) => false,
             Variable::LocalMut { .. } => false,
             Variable::LocalConst { .. } => false,
             Variable::Named { .. } => false,
@@ -150,11 +159,14 @@ impl Variable {
             Self::SharedValue(_, e) => *e,
             Self::ConstantArray(_, e, _) => *e,
             Self::LocalArray(_, e, _) => *e,
+            Self::Texture(_, e) => *e,
             Self::LocalMut { item, .. } => *item,
             Self::LocalConst { item, .. } => *item,
             Self::Named { item, .. } => *item,
             Self::Constant(_, item) => *item,
             Self::GlobalScalar(_, e) => Item::Scalar(*e),
+            Self::GlobalPackedScalar(_, packing) => Item::Scalar(packing.register()),
+            Self::GlobalScalar64(_, word) => Item::Vec2(*word),
             Self::Id => Item::Scalar(Elem::U32),
             Self::LocalInvocationIndex => Item::Scalar(Elem::U32),
             Self::LocalInvocationIdX => Item::Scalar(Elem::U32),
@@ -221,6 +233,10 @@ impl Item {
         }
     }
 
+    pub fn size(&self) -> usize {
+        self.elem().size() * self.vectorization_factor()
+    }
+
     pub fn with_elem(self, elem: Elem) -> Self {
         match self {
             Item::Vec4(_) => Item::Vec4(elem),
@@ -319,6 +335,14 @@ impl Display for Variable {
             Variable::GlobalScalar(number, elem) => {
                 write!(f, "scalars_{elem}[{number}]")
             }
+            Variable::GlobalPackedScalar(number, packing) => {
+                fmt_packed_scalar(f, *packing, *number)
+            }
+            Variable::GlobalScalar64(number, word) => match word {
+                Elem::I32 => write!(f, "scalars_i64[{number}]"),
+                Elem::F32 => write!(f, "scalars_df64[{number}]"),
+                _ => write!(f, "scalars_u64[{number}]"),
+            },
             Variable::Constant(val, item) => {
                 write!(f, "{item}({val}{})", item.elem().literal_suffix())
             }
@@ -329,6 +353,7 @@ impl Display for Variable {
             Variable::LocalArray(number, _, _) => {
                 write!(f, "a_{number}")
             }
+            Variable::Texture(number, _) => write!(f, "texture_{number}"),
             Variable::Id => f.write_str("id"),
             Variable::LocalInvocationIndex => f.write_str("local_idx"),
             Variable::LocalInvocationIdX => f.write_str("local_invocation_id.x"),
@@ -5,7 +5,12 @@ use crate::compiler::wgsl::{self, SharedValue};
 
 use cubecl_common::backtrace::BackTrace;
 use cubecl_core::post_processing::{
-    checked_io::CheckedIoProcessor, saturating::SaturatingArithmeticProcessor,
+    atomic::AtomicPolyfillProcessor,
+    checked_io::CheckedIoProcessor,
+    df64::{self, Df64EmulationProcessor},
+    dynamic_shared_memory::DynamicSharedMemoryProcessor,
+    int64::{self, Int64EmulationProcessor},
+    saturating::SaturatingArithmeticProcessor,
 };
 use cubecl_core::prelude::*;
 use cubecl_core::{
@@ -43,6 +48,13 @@ pub struct WgslCompiler {
     shared_values: Vec<SharedValue>,
     const_arrays: Vec<ConstantArray>,
     local_arrays: Vec<LocalArray>,
+    spec_constants: Vec<(cube::Id, wgsl::Elem)>,
+    /// Packing of each global buffer, by id.
+    packed_buffers: Vec<Option<wgsl::PackedBuffer>>,
+    /// Lowering of the dynamic shared memory, shared by all the scopes of the kernel.
+    dynamic_shared_memory: Option<DynamicSharedMemoryProcessor>,
+    /// Features of the device the kernel is compiled for, when known.
+    features: Option<cube::features::Features>,
     #[allow(dead_code)]
     compilation_options: WgpuCompilationOptions,
     strategy: ExecutionMode,
@@ -71,6 +83,14 @@ impl cubecl_core::Compiler for WgslCompiler {
         self.compile_shader(shader, mode, address_type)
     }
 
+    fn resource_usage(&self, shader: &Self::Representation) -> kernel::KernelResourceUsage {
+        kernel::KernelResourceUsage {
+            shared_memory: shader.shared_memory_size(),
+            local_arrays: shader.local_array_sizes(),
+            ..Default::default()
+        }
+    }
+
     fn elem_size(&self, elem: cube::ElemType) -> usize {
         elem.size()
     }
@@ -87,21 +107,14 @@ impl WgslCompiler {
         mode: ExecutionMode,
         address_type: StorageType,
     ) -> Result<wgsl::ComputeShader, CompilationError> {
-        let errors = value.body.pop_errors();
-        if !errors.is_empty() {
-            let mut reason = "Can't compile wgsl kernel".to_string();
-            for error in errors {
-                reason += error.as_str();
-                reason += "\n";
-            }
-
-            return Err(CompilationError::Validation {
-                reason,
-                backtrace: BackTrace::capture(),
-            });
-        }
+        validate(&mut value.body)?;
 
         self.strategy = mode;
+        self.features = value
+            .body
+            .properties
+            .as_ref()
+            .map(|properties| properties.features.clone());
 
         let num_meta = value.buffers.len();
 
@@ -116,10 +129,42 @@ impl WgslCompiler {
         }
 
         self.metadata = Metadata::new(num_meta as u32, num_ext);
+        self.dynamic_shared_memory = value
+            .dynamic_shared_memory
+            .map(|size| DynamicSharedMemoryProcessor::new(&value.body, size));
+
+        self.packed_buffers = value
+            .buffers
+            .iter()
+            .map(|binding| {
+                wgsl::Packing::from_storage_type(binding.ty.storage_type()).map(|packing| {
+                    wgsl::PackedBuffer {
+                        packing,
+                        atomic: binding.visibility == Visibility::ReadWrite,
+                    }
+                })
+            })
+            .collect();
 
         let address_type = self.compile_storage_type(address_type);
         let instructions = self.compile_scope(&mut value.body);
-        let extensions = register_extensions(&instructions);
+        let functions: Vec<_> = value
+            .body
+            .functions
+            .take()
+            .into_iter()
+            .map(|function| self.compile_function(function, address_type))
+            .collect();
+        // Processors can reject instructions they can't lower.
+        validate(&mut value.body)?;
+        let mut extensions = register_extensions(&instructions);
+        for function in functions.iter() {
+            for extension in register_extensions(&function.body.instructions) {
+                if !extensions.contains(&extension) {
+                    extensions.push(extension);
+                }
+            }
+        }
         let body = wgsl::Body {
             instructions,
             id: self.id,
@@ -132,6 +177,7 @@ impl WgslCompiler {
                 .buffers
                 .into_iter()
                 .map(|mut it| {
+                    it.ty = df64::lower_type(self.lower_int64(it.ty));
                     // This is safe when combined with the unroll transform that adjusts all indices.
                     // Must not be used alone
                     if it.ty.line_size() > MAX_LINE_SIZE {
@@ -143,12 +189,20 @@ impl WgslCompiler {
             scalars: value
                 .scalars
                 .into_iter()
-                .map(|binding| (self.compile_storage_type(binding.ty), binding.count))
+                .map(|binding| wgsl::ScalarBinding {
+                    elem: self.compile_storage_type(binding.ty),
+                    len: binding.count,
+                    packing: wgsl::Packing::from_storage_type(binding.ty),
+                    words: (self.emulates_int64() && int64::is_int64(binding.ty))
+                        || df64::is_df64(binding.ty),
+                })
                 .collect(),
+            textures: value.textures.iter().map(|texture| texture.dim).collect(),
             shared_arrays: self.shared_arrays.clone(),
             shared_values: self.shared_values.clone(),
             constant_arrays: self.const_arrays.clone(),
             local_arrays: self.local_arrays.clone(),
+            spec_constants: self.spec_constants.clone(),
             has_metadata: self.metadata.static_len() > 0,
             workgroup_size: value.cube_dim,
             global_invocation_id: self.global_invocation_id || self.id,
@@ -161,6 +215,7 @@ impl WgslCompiler {
             workgroup_id: self.workgroup_id || self.workgroup_id_no_axis,
             subgroup_size: self.subgroup_size,
             subgroup_invocation_id: self.subgroup_invocation_id,
+            functions,
             body,
             extensions,
             num_workgroups_no_axis: self.num_workgroup_no_axis,
@@ -172,8 +227,37 @@ impl WgslCompiler {
         })
     }
 
+    /// Compile a function body on its own, since local arrays are declared in the function that
+    /// uses them.
+    fn compile_function(
+        &mut self,
+        function: cube::Function,
+        address_type: wgsl::Elem,
+    ) -> wgsl::Function {
+        let local_arrays = core::mem::take(&mut self.local_arrays);
+        let mut scope = function.body;
+        let instructions = self.compile_scope(&mut scope);
+        let local_arrays = core::mem::replace(&mut self.local_arrays, local_arrays);
+
+        wgsl::Function {
+            name: function.name,
+            params: function
+                .params
+                .into_iter()
+                .map(|param| self.compile_variable(param))
+                .collect(),
+            out: function.out.map(|out| self.compile_variable(out)),
+            local_arrays,
+            body: wgsl::Body {
+                instructions,
+                id: false,
+                address_type,
+            },
+        }
+    }
+
     fn compile_type(&mut self, item: cube::Type) -> Item {
-        match item {
+        match df64::lower_type(self.lower_int64(item)) {
             cube::Type::Scalar(ty) => wgsl::Item::Scalar(self.compile_storage_type(ty)),
             cube::Type::Line(ty, size) => {
                 let elem = self.compile_storage_type(ty);
@@ -191,6 +275,12 @@ impl WgslCompiler {
     fn compile_storage_type(&mut self, ty: cube::StorageType) -> wgsl::Elem {
         match ty {
             cube::StorageType::Scalar(ty) => self.compile_elem(ty),
+            // Polyfilled atomics are only accessed through an integer view, see
+            // `AtomicPolyfillProcessor`.
+            cube::StorageType::Atomic(ty) if self.atomic_polyfill_type(ty).is_some() => {
+                let int = self.atomic_polyfill_type(ty).unwrap();
+                self.compile_storage_type(cube::StorageType::Atomic(int))
+            }
             cube::StorageType::Atomic(ty) => match ty {
                 cube::ElemType::Float(i) => match i {
                     cube::FloatKind::F32 => wgsl::Elem::AtomicF32,
@@ -217,6 +307,27 @@ impl WgslCompiler {
         }
     }
 
+    fn emulates_int64(&self) -> bool {
+        self.features
+            .as_ref()
+            .is_some_and(|features| features.int64_emulation)
+    }
+
+    /// Lowers 64-bit integers to their 32-bit words when they're emulated, see
+    /// `Int64EmulationProcessor`.
+    fn lower_int64(&self, ty: cube::Type) -> cube::Type {
+        match self.emulates_int64() {
+            true => int64::lower_type(ty),
+            false => ty,
+        }
+    }
+
+    fn atomic_polyfill_type(&self, elem: cube::ElemType) -> Option<cube::ElemType> {
+        self.features
+            .as_ref()
+            .and_then(|features| features.atomic_polyfill_type(elem))
+    }
+
     fn compile_elem(&mut self, value: cube::ElemType) -> wgsl::Elem {
         match value {
             cube::ElemType::Float(f) => match f {
@@ -230,21 +341,24 @@ impl WgslCompiler {
                     self.f16_used = true;
                     wgsl::Elem::F16
                 }
-                cube::FloatKind::BF16 => panic!("bf16 is not a valid WgpuElement"),
+                // Packed in buffers, see `Packing`.
+                cube::FloatKind::BF16 => wgsl::Elem::F32,
                 cube::FloatKind::TF32 => panic!("tf32 is not a valid WgpuElement"),
                 cube::FloatKind::Flex32 => wgsl::Elem::F32,
                 cube::FloatKind::F32 => wgsl::Elem::F32,
+                // Lowered to pairs of `f32`, see `Df64EmulationProcessor`.
+                cube::FloatKind::DF64 => wgsl::Elem::F32,
                 cube::FloatKind::F64 => wgsl::Elem::F64,
             },
             cube::ElemType::Int(i) => match i {
+                cube::IntKind::I8 | cube::IntKind::I16 => wgsl::Elem::I32,
                 cube::IntKind::I32 => wgsl::Elem::I32,
                 cube::IntKind::I64 => wgsl::Elem::I64,
-                kind => panic!("{kind:?} is not a valid WgpuElement"),
             },
             cube::ElemType::UInt(kind) => match kind {
+                cube::UIntKind::U8 | cube::UIntKind::U16 => wgsl::Elem::U32,
                 cube::UIntKind::U32 => wgsl::Elem::U32,
                 cube::UIntKind::U64 => wgsl::Elem::U64,
-                kind => panic!("{kind:?} is not a valid WgpuElement"),
             },
             cube::ElemType::Bool => wgsl::Elem::Bool,
         }
@@ -261,8 +375,31 @@ impl WgslCompiler {
             cube::VariableKind::GlobalInputArray(id) => {
                 wgsl::Variable::GlobalInputArray(id, self.compile_type(item))
             }
+            cube::VariableKind::GlobalScalar(id)
+                if int64::lowered_scalar(item).is_some()
+                    || df64::lowered_scalar(item).is_some() =>
+            {
+                wgsl::Variable::GlobalScalar64(id, self.compile_storage_type(item.storage_type()))
+            }
             cube::VariableKind::GlobalScalar(id) => {
-                wgsl::Variable::GlobalScalar(id, self.compile_storage_type(item.storage_type()))
+                match wgsl::Packing::from_storage_type(item.storage_type()) {
+                    Some(packing) => wgsl::Variable::GlobalPackedScalar(id, packing),
+                    None => wgsl::Variable::GlobalScalar(
+                        id,
+                        self.compile_storage_type(item.storage_type()),
+                    ),
+                }
+            }
+            cube::VariableKind::SpecConstant(id) => {
+                let elem = self.compile_storage_type(item.storage_type());
+                if !self.spec_constants.iter().any(|(index, _)| *index == id) {
+                    self.spec_constants.push((id, elem));
+                }
+                wgsl::Variable::Named {
+                    name: format!("spec_constant_{id}"),
+                    item: wgsl::Item::Scalar(elem),
+                    is_array: false,
+                }
             }
             cube::VariableKind::LocalMut { id } | cube::VariableKind::Versioned { id, .. } => {
                 wgsl::Variable::LocalMut {
@@ -423,6 +560,10 @@ impl WgslCompiler {
             }
             cube::VariableKind::TensorMapInput(_) => panic!("Tensor map not supported."),
             cube::VariableKind::TensorMapOutput(_) => panic!("Tensor map not supported."),
+            cube::VariableKind::Texture(id) => wgsl::Variable::Texture(id, self.compile_type(item)),
+            cube::VariableKind::DynamicSharedArray { .. } => {
+                panic!("Views of the dynamic shared memory can only be indexed on this runtime.")
+            }
         }
     }
 
@@ -452,7 +593,27 @@ impl WgslCompiler {
         let checked_io: Box<dyn Processor> = Box::new(CheckedIoProcessor::new(self.strategy));
         let unroll = Box::new(UnrollProcessor::new(MAX_LINE_SIZE));
         let saturating = Box::new(SaturatingArithmeticProcessor::new(true));
-        let processing = scope.process([&*unroll, &*checked_io, &*saturating]);
+        let dynamic_shared_memory = self.dynamic_shared_memory.clone();
+        let mut processors: Vec<&dyn Processor> = vec![&*unroll, &*saturating];
+        if let Some(dynamic_shared_memory) = &dynamic_shared_memory {
+            processors.push(dynamic_shared_memory);
+        }
+        // Lowered 64-bit lines can be twice as wide, so they're unrolled again before bounds
+        // checks move the accesses to nested scopes. `df64` is never native in WGSL.
+        let df64 = Df64EmulationProcessor::new();
+        let int64 = Int64EmulationProcessor::new();
+        let unroll_lowered = UnrollProcessor::new(MAX_LINE_SIZE);
+        processors.push(&df64);
+        if self.emulates_int64() {
+            processors.push(&int64);
+        }
+        processors.push(&unroll_lowered);
+        processors.push(&*checked_io);
+        let atomic = scope.properties.clone().map(AtomicPolyfillProcessor::new);
+        if let Some(atomic) = &atomic {
+            processors.push(atomic);
+        }
+        let processing = scope.process(processors);
 
         for mut var in processing.variables {
             if var.ty.line_size() > MAX_LINE_SIZE {
@@ -507,7 +668,51 @@ impl WgslCompiler {
                 panic!("Barrier isn't supported on wgpu.")
             }
             cube::Operation::Tma(_) => panic!("TMA isn't supported on wgpu."),
+            cube::Operation::Texture(op) => instructions.push(self.compile_texture(op, out)),
             cube::Operation::Marker(_) => {}
+            cube::Operation::Call(call) => instructions.push(wgsl::Instruction::Call {
+                name: call.name,
+                args: call
+                    .args
+                    .into_iter()
+                    .map(|arg| self.compile_variable(arg))
+                    .collect(),
+                out: out.map(|out| self.compile_variable(out)),
+            }),
+        }
+    }
+
+    fn compile_texture(
+        &mut self,
+        op: cube::TextureOps,
+        out: Option<cube::Variable>,
+    ) -> wgsl::Instruction {
+        let out = self.compile_variable(out.unwrap());
+        match op {
+            cube::TextureOps::Sample {
+                texture,
+                sampler,
+                coordinates,
+            } => wgsl::Instruction::TextureSample {
+                texture: self.compile_variable(texture),
+                sampler: sampler.index(),
+                coordinates: coordinates
+                    .into_iter()
+                    .map(|coord| self.compile_variable(coord))
+                    .collect(),
+                out,
+            },
+            cube::TextureOps::Load {
+                texture,
+                coordinates,
+            } => wgsl::Instruction::TextureLoad {
+                texture: self.compile_variable(texture),
+                coordinates: coordinates
+                    .into_iter()
+                    .map(|coord| self.compile_variable(coord))
+                    .collect(),
+                out,
+            },
         }
     }
 
@@ -625,6 +830,7 @@ impl WgslCompiler {
             }),
             cube::Branch::Return => instructions.push(wgsl::Instruction::Return),
             cube::Branch::Break => instructions.push(wgsl::Instruction::Break),
+            cube::Branch::Continue => instructions.push(wgsl::Instruction::Continue),
             cube::Branch::RangeLoop(mut range_loop) => {
                 instructions.push(wgsl::Instruction::RangeLoop {
                     i: self.compile_variable(range_loop.i),
@@ -1081,23 +1287,48 @@ impl WgslCompiler {
     ) {
         let out = out.unwrap();
         match value {
-            cube::Operator::Cast(op) => instructions.push(wgsl::Instruction::Assign {
-                input: self.compile_variable(op.input),
-                out: self.compile_variable(out),
-            }),
+            cube::Operator::Cast(op) => {
+                match wgsl::Packing::from_storage_type(out.ty.storage_type()) {
+                    Some(packing) => instructions.push(wgsl::Instruction::NarrowingCast {
+                        input: self.compile_variable(op.input),
+                        out: self.compile_variable(out),
+                        packing,
+                    }),
+                    None => instructions.push(wgsl::Instruction::Assign {
+                        input: self.compile_variable(op.input),
+                        out: self.compile_variable(out),
+                    }),
+                }
+            }
             cube::Operator::Index(op) | cube::Operator::UncheckedIndex(op) => {
-                instructions.push(wgsl::Instruction::Index {
-                    lhs: self.compile_variable(op.list),
-                    rhs: self.compile_variable(op.index),
-                    out: self.compile_variable(out),
-                });
+                match self.packed_buffer(&op.list) {
+                    Some(packed) => instructions.push(wgsl::Instruction::PackedIndex {
+                        buffer: self.compile_variable(op.list),
+                        packed,
+                        index: self.compile_variable(op.index),
+                        out: self.compile_variable(out),
+                    }),
+                    None => instructions.push(wgsl::Instruction::Index {
+                        lhs: self.compile_variable(op.list),
+                        rhs: self.compile_variable(op.index),
+                        out: self.compile_variable(out),
+                    }),
+                }
             }
             cube::Operator::IndexAssign(op) | cube::Operator::UncheckedIndexAssign(op) => {
-                instructions.push(wgsl::Instruction::IndexAssign {
-                    index: self.compile_variable(op.index),
-                    rhs: self.compile_variable(op.value),
-                    out: self.compile_variable(out),
-                })
+                match self.packed_buffer(&out) {
+                    Some(packed) => instructions.push(wgsl::Instruction::PackedIndexAssign {
+                        buffer: self.compile_variable(out),
+                        packed,
+                        index: self.compile_variable(op.index),
+                        value: self.compile_variable(op.value),
+                    }),
+                    None => instructions.push(wgsl::Instruction::IndexAssign {
+                        index: self.compile_variable(op.index),
+                        rhs: self.compile_variable(op.value),
+                        out: self.compile_variable(out),
+                    }),
+                }
             }
             cube::Operator::And(op) => instructions.push(wgsl::Instruction::And {
                 lhs: self.compile_variable(op.lhs),
@@ -1125,19 +1356,51 @@ impl WgslCompiler {
                     .collect(),
                 out: self.compile_variable(out),
             }),
-            cube::Operator::CopyMemory(op) => instructions.push(wgsl::Instruction::Copy {
-                input: self.compile_variable(op.input),
-                in_index: self.compile_variable(op.in_index),
-                out: self.compile_variable(out),
-                out_index: self.compile_variable(op.out_index),
-            }),
-            cube::Operator::CopyMemoryBulk(op) => instructions.push(wgsl::Instruction::CopyBulk {
-                input: self.compile_variable(op.input),
-                in_index: self.compile_variable(op.in_index),
-                out: self.compile_variable(out),
-                out_index: self.compile_variable(op.out_index),
-                len: op.len as u32,
-            }),
+            cube::Operator::CopyMemory(op) => {
+                let in_packed = self.packed_buffer(&op.input);
+                let out_packed = self.packed_buffer(&out);
+                if in_packed.is_some() || out_packed.is_some() {
+                    instructions.push(wgsl::Instruction::PackedCopy {
+                        input: self.compile_variable(op.input),
+                        in_packed,
+                        in_index: self.compile_variable(op.in_index),
+                        out: self.compile_variable(out),
+                        out_packed,
+                        out_index: self.compile_variable(op.out_index),
+                        len: None,
+                    })
+                } else {
+                    instructions.push(wgsl::Instruction::Copy {
+                        input: self.compile_variable(op.input),
+                        in_index: self.compile_variable(op.in_index),
+                        out: self.compile_variable(out),
+                        out_index: self.compile_variable(op.out_index),
+                    })
+                }
+            }
+            cube::Operator::CopyMemoryBulk(op) => {
+                let in_packed = self.packed_buffer(&op.input);
+                let out_packed = self.packed_buffer(&out);
+                if in_packed.is_some() || out_packed.is_some() {
+                    instructions.push(wgsl::Instruction::PackedCopy {
+                        input: self.compile_variable(op.input),
+                        in_packed,
+                        in_index: self.compile_variable(op.in_index),
+                        out: self.compile_variable(out),
+                        out_packed,
+                        out_index: self.compile_variable(op.out_index),
+                        len: Some(op.len as u32),
+                    })
+                } else {
+                    instructions.push(wgsl::Instruction::CopyBulk {
+                        input: self.compile_variable(op.input),
+                        in_index: self.compile_variable(op.in_index),
+                        out: self.compile_variable(out),
+                        out_index: self.compile_variable(op.out_index),
+                        len: op.len as u32,
+                    })
+                }
+            }
             cube::Operator::Select(op) => instructions.push(wgsl::Instruction::Select {
                 cond: self.compile_variable(op.cond),
                 then: self.compile_variable(op.then),
@@ -1225,8 +1488,37 @@ impl WgslCompiler {
             location: Self::compile_location(value.location),
             item: self.compile_type(value.ty),
             size: value.size,
+            packed: self.packed_buffers[value.id as usize],
         }
     }
+
+    /// Packing of `var` if it's a global buffer of a packed type.
+    fn packed_buffer(&self, var: &cube::Variable) -> Option<wgsl::PackedBuffer> {
+        match var.kind {
+            cube::VariableKind::GlobalInputArray(id)
+            | cube::VariableKind::GlobalOutputArray(id) => self.packed_buffers[id as usize],
+            _ => None,
+        }
+    }
+}
+
+/// Fails with the validation errors pushed to the kernel, if any.
+fn validate(body: &mut Scope) -> Result<(), CompilationError> {
+    let errors = body.pop_errors();
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    let mut reason = "Can't compile wgsl kernel".to_string();
+    for error in errors {
+        reason += error.as_str();
+        reason += "\n";
+    }
+
+    Err(CompilationError::Validation {
+        reason,
+        backtrace: BackTrace::capture(),
+    })
 }
 
 fn register_extensions(instructions: &[wgsl::Instruction]) -> Vec<wgsl::Extension> {
@@ -1258,6 +1550,13 @@ fn register_extensions(instructions: &[wgsl::Instruction]) -> Vec<wgsl::Extensio
                 register_extension(wgsl::Extension::IsInfPrimitive(input.elem()));
                 register_extension(wgsl::Extension::IsInf(input.item(), out.item()));
             }
+            wgsl::Instruction::NarrowingCast {
+                out,
+                packing: wgsl::Packing::BF16,
+                ..
+            } => {
+                register_extension(wgsl::Extension::RoundBf16(out.item()));
+            }
             wgsl::Instruction::If { instructions, .. } => {
                 for extension in register_extensions(instructions) {
                     register_extension(extension);
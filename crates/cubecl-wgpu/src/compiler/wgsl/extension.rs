@@ -1,4 +1,5 @@
 use super::base::{Elem, Item, Variable};
+use super::format_round_bf16;
 use std::fmt::Display;
 
 /// Not all functions are native to WGSL, so this struct allows to support more functions.
@@ -15,6 +16,7 @@ pub enum Extension {
     IsNan(Item, Item),
     IsInfPrimitive(Elem),
     IsInf(Item, Item),
+    RoundBf16(Item),
 }
 
 impl Display for Extension {
@@ -77,6 +79,7 @@ impl Display for Extension {
                 }],
                 *out_item,
             ),
+            Extension::RoundBf16(item) => format_round_bf16(f, *item),
             Extension::IsInfPrimitive(elem) => format_is_inf_primitive(f, elem),
             Extension::IsInf(in_item, out_item) => construct_vector(
                 f,
@@ -1,5 +1,5 @@
 use super::{
-    Elem, Subgroup,
+    Elem, PackedBuffer, Packing, Subgroup,
     base::{Item, Variable},
 };
 use std::fmt::Display;
@@ -54,8 +54,27 @@ pub enum Instruction {
     },
     Return,
     Break,
+    Continue,
+    Call {
+        name: String,
+        args: Vec<Variable>,
+        out: Option<Variable>,
+    },
     WorkgroupBarrier,
     StorageBarrier,
+    /// Sample a texture at normalized coordinates with the sampler bound at `sampler`.
+    TextureSample {
+        texture: Variable,
+        sampler: usize,
+        coordinates: Vec<Variable>,
+        out: Variable,
+    },
+    /// Load a single texel at integer coordinates.
+    TextureLoad {
+        texture: Variable,
+        coordinates: Vec<Variable>,
+        out: Variable,
+    },
     // Index handles casting to correct local variable.
     Index {
         lhs: Variable,
@@ -68,6 +87,20 @@ pub enum Instruction {
         rhs: Variable,
         out: Variable,
     },
+    // Index into a buffer of packed elements, casting to the output variable.
+    PackedIndex {
+        buffer: Variable,
+        packed: PackedBuffer,
+        index: Variable,
+        out: Variable,
+    },
+    // Index assign into a buffer of packed elements.
+    PackedIndexAssign {
+        buffer: Variable,
+        packed: PackedBuffer,
+        index: Variable,
+        value: Variable,
+    },
     // Index handles casting to correct local variable.
     Assign {
         input: Variable,
@@ -441,6 +474,22 @@ pub enum Instruction {
         out_index: Variable,
         len: u32,
     },
+    // Copy where either side is a buffer of packed elements.
+    PackedCopy {
+        input: Variable,
+        in_packed: Option<PackedBuffer>,
+        in_index: Variable,
+        out: Variable,
+        out_packed: Option<PackedBuffer>,
+        out_index: Variable,
+        len: Option<u32>,
+    },
+    // Cast to a packed type, wrapping the value to its range.
+    NarrowingCast {
+        input: Variable,
+        out: Variable,
+        packing: Packing,
+    },
     Comment {
         content: String,
     },
@@ -564,6 +613,87 @@ impl Display for Instruction {
                 rhs,
                 out,
             } => index_assign(f, lhs, rhs, out, None),
+            Instruction::PackedIndex {
+                buffer,
+                packed,
+                index,
+                out,
+            } => {
+                let line_size = buffer.item().vectorization_factor();
+                let item = buffer.item().with_elem(packed.packing.register());
+                let mut value = packed.load(buffer, &format!("{index}"), line_size);
+                if item != out.item() {
+                    value = item.fmt_cast_to(out.item(), value);
+                }
+                let out = out.fmt_left();
+                writeln!(f, "{out} = {value};")
+            }
+            Instruction::PackedIndexAssign {
+                buffer,
+                packed,
+                index,
+                value,
+            } => {
+                let line_size = buffer.item().vectorization_factor();
+                let item = Item::Scalar(packed.packing.register());
+                let values = (0..line_size)
+                    .map(|i| value.index(i).fmt_cast(item))
+                    .collect::<Vec<_>>();
+                packed.store(f, buffer, &format!("{index}"), &values)
+            }
+            Instruction::PackedCopy {
+                input,
+                in_packed,
+                in_index,
+                out,
+                out_packed,
+                out_index,
+                len,
+            } => {
+                let line_size = input.item().vectorization_factor();
+                let offsets = match len {
+                    Some(len) => (0..*len).map(|i| format!(" + {i}")).collect(),
+                    None => vec![String::new()],
+                };
+                for offset in offsets {
+                    let in_index = format!("{in_index}{offset}");
+                    let out_index = format!("{out_index}{offset}");
+                    let value = match in_packed {
+                        Some(packed) => packed.load(input, &in_index, line_size),
+                        None => format!("{input}[{in_index}]"),
+                    };
+                    writeln!(f, "{{")?;
+                    writeln!(f, "let value = {value};")?;
+                    match out_packed {
+                        Some(packed) => {
+                            let elem = packed.packing.register();
+                            let values = (0..line_size)
+                                .map(|i| match line_size {
+                                    1 => format!("{elem}(value)"),
+                                    _ => format!("{elem}(value[{i}])"),
+                                })
+                                .collect::<Vec<_>>();
+                            packed.store(f, out, &out_index, &values)?;
+                        }
+                        None => {
+                            let item = out.item();
+                            writeln!(f, "{out}[{out_index}] = {item}(value);")?;
+                        }
+                    }
+                    writeln!(f, "}}")?;
+                }
+                Ok(())
+            }
+            Instruction::NarrowingCast {
+                input,
+                out,
+                packing,
+            } => {
+                let item = out.item();
+                let value = packing.narrow(&input.fmt_cast_to(item), item);
+                let out = out.fmt_left();
+                writeln!(f, "{out} = {value};")
+            }
             Instruction::Copy {
                 input,
                 in_index,
@@ -870,6 +1000,51 @@ for (var {i}: {i_ty} = {start}; {i} {cmp} {end}; {increment}) {{
             }
             Instruction::Return => f.write_str("return;\n"),
             Instruction::Break => f.write_str("break;\n"),
+            Instruction::Continue => f.write_str("continue;\n"),
+            Instruction::Call { name, args, out } => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                match out {
+                    Some(out) => writeln!(f, "{} = {name}({args});", out.fmt_left()),
+                    None => writeln!(f, "{name}({args});"),
+                }
+            }
+            Instruction::TextureSample {
+                texture,
+                sampler,
+                coordinates,
+                out,
+            } => {
+                let coordinates = fmt_texture_coordinates(coordinates, Elem::F32);
+                let texel = format!(
+                    "textureSampleLevel({texture}, sampler_{sampler}, {coordinates}, 0.0){}",
+                    texel_swizzle(out.item())
+                );
+                let texel = out
+                    .item()
+                    .with_elem(Elem::F32)
+                    .fmt_cast_to(out.item(), texel);
+                writeln!(f, "{} = {texel};", out.fmt_left())
+            }
+            Instruction::TextureLoad {
+                texture,
+                coordinates,
+                out,
+            } => {
+                let coordinates = fmt_texture_coordinates(coordinates, Elem::I32);
+                let texel = format!(
+                    "textureLoad({texture}, {coordinates}, 0){}",
+                    texel_swizzle(out.item())
+                );
+                let texel = out
+                    .item()
+                    .with_elem(Elem::F32)
+                    .fmt_cast_to(out.item(), texel);
+                writeln!(f, "{} = {texel};", out.fmt_left())
+            }
             Instruction::WorkgroupBarrier => f.write_str("workgroupBarrier();\n"),
             Instruction::StorageBarrier => f.write_str("storageBarrier();\n"),
             Instruction::Length { var, out } => {
@@ -970,10 +1145,31 @@ for (var {i}: {i_ty} = {start}; {i} {cmp} {end}; {increment}) {{
                 writeln!(f, "{out} = trunc({input});")
             }
             Instruction::Subgroup(op) => write!(f, "{op}"),
+            // Atomics are pointers, and only reinterpreted to the type they're declared as.
+            Instruction::Bitcast { input, out } if out.elem().is_atomic() => {
+                writeln!(f, "let {out} = {input};")
+            }
+            // Naga can't bitcast between vector sizes, so `f16` pairs go through `f32`, which
+            // represents every `f16` exactly.
             Instruction::Bitcast { input, out } => {
-                let elem = out.item();
+                let item = out.item();
+                let value = match (input.item(), item) {
+                    (Item::Scalar(int), Item::Vec2(Elem::F16)) if int.size() == 4 => {
+                        format!("{item}(unpack2x16float(bitcast<u32>({input})))")
+                    }
+                    (Item::Vec2(int), Item::Vec4(Elem::F16)) if int.size() == 4 => format!(
+                        "{item}(unpack2x16float(bitcast<u32>({input}.x)), unpack2x16float(bitcast<u32>({input}.y)))"
+                    ),
+                    (Item::Vec2(Elem::F16), Item::Scalar(int)) if int.size() == 4 => {
+                        format!("bitcast<{item}>(pack2x16float(vec2<f32>({input})))")
+                    }
+                    (Item::Vec4(Elem::F16), Item::Vec2(int)) if int.size() == 4 => format!(
+                        "bitcast<{item}>(vec2<u32>(pack2x16float(vec2<f32>({input}.xy)), pack2x16float(vec2<f32>({input}.zw))))"
+                    ),
+                    _ => format!("bitcast<{item}>({input})"),
+                };
                 let out = out.fmt_left();
-                writeln!(f, "{out} = bitcast<{elem}>({input});")
+                writeln!(f, "{out} = {value};")
             }
             Instruction::AtomicLoad { input, out } => {
                 let out = out.fmt_left();
@@ -1020,12 +1216,19 @@ for (var {i}: {i_ty} = {start}; {i} {cmp} {end}; {increment}) {{
                 value,
                 out,
             } => {
-                let out = out.fmt_left();
+                // For compatibility with cuda, only return old_value, and retry spurious failures
+                // so a returned value equal to `cmp` means the exchange happened.
+                let result = format!("{out}_cas");
+                let exchange = format!("atomicCompareExchangeWeak({lhs}, {cmp}, {value})");
+                writeln!(f, "var {result} = {exchange};")?;
                 writeln!(
                     f,
-                    // For compatibility with cuda, only return old_value
-                    "{out} = atomicCompareExchangeWeak({lhs}, {cmp}, {value}).old_value;"
-                )
+                    "while !{result}.exchanged && {result}.old_value == {cmp} {{"
+                )?;
+                writeln!(f, "{result} = {exchange};")?;
+                writeln!(f, "}}")?;
+                let out = out.fmt_left();
+                writeln!(f, "{out} = {result}.old_value;")
             }
             Instruction::Negate { input, out } => {
                 let out = out.fmt_left();
@@ -1257,3 +1460,27 @@ fn index_assign(
         }
     }
 }
+
+/// Texture coordinates as a vector of `elem`, the type expected by the texture builtins.
+fn fmt_texture_coordinates(coordinates: &[Variable], elem: Elem) -> String {
+    let coordinates = coordinates
+        .iter()
+        .map(|coord| coord.fmt_cast_to(Item::Scalar(elem)))
+        .collect::<Vec<_>>();
+    format!(
+        "vec{}<{elem}>({})",
+        coordinates.len(),
+        coordinates.join(", ")
+    )
+}
+
+/// Swizzle selecting the channels of `item` from the `vec4<f32>` texel returned by the texture
+/// builtins.
+fn texel_swizzle(item: Item) -> &'static str {
+    match item {
+        Item::Scalar(_) => ".x",
+        Item::Vec2(_) => ".xy",
+        Item::Vec3(_) => ".xyz",
+        Item::Vec4(_) => "",
+    }
+}
@@ -0,0 +1,257 @@
+use super::{Elem, Item, Variable};
+use cubecl_core::ir as cube;
+use std::fmt::Display;
+
+/// Storage types narrower than 32 bits, which WGSL can't address.
+///
+/// Values of these types are held in a 32-bit register type in the kernel, and packed into
+/// `u32` words in buffers. Buffers that are written to are declared as `array<atomic<u32>>`, since
+/// neighbouring elements of the same word can be written by different invocations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Packing {
+    U8,
+    I8,
+    U16,
+    I16,
+    BF16,
+}
+
+/// A global buffer of a [packed](Packing) type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedBuffer {
+    pub packing: Packing,
+    /// Whether the words are atomics, when the buffer is written to.
+    pub atomic: bool,
+}
+
+impl Packing {
+    pub fn from_elem(elem: cube::ElemType) -> Option<Self> {
+        match elem {
+            cube::ElemType::UInt(cube::UIntKind::U8) => Some(Self::U8),
+            cube::ElemType::Int(cube::IntKind::I8) => Some(Self::I8),
+            cube::ElemType::UInt(cube::UIntKind::U16) => Some(Self::U16),
+            cube::ElemType::Int(cube::IntKind::I16) => Some(Self::I16),
+            cube::ElemType::Float(cube::FloatKind::BF16) => Some(Self::BF16),
+            _ => None,
+        }
+    }
+
+    pub fn from_storage_type(ty: cube::StorageType) -> Option<Self> {
+        match ty {
+            cube::StorageType::Scalar(elem) => Self::from_elem(elem),
+            _ => None,
+        }
+    }
+
+    /// The type holding values in the kernel.
+    pub fn register(&self) -> Elem {
+        match self {
+            Packing::U8 | Packing::U16 => Elem::U32,
+            Packing::I8 | Packing::I16 => Elem::I32,
+            Packing::BF16 => Elem::F32,
+        }
+    }
+
+    pub fn bits(&self) -> u32 {
+        match self {
+            Packing::U8 | Packing::I8 => 8,
+            Packing::U16 | Packing::I16 | Packing::BF16 => 16,
+        }
+    }
+
+    pub fn per_word(&self) -> u32 {
+        32 / self.bits()
+    }
+
+    fn mask(&self) -> &'static str {
+        match self.bits() {
+            8 => "0xffu",
+            _ => "0xffffu",
+        }
+    }
+
+    /// Value of the type from an `u32` expression holding its bits in the low bits.
+    fn unpack(&self, bits: &str) -> String {
+        match self {
+            Packing::U8 | Packing::U16 => bits.to_string(),
+            Packing::I8 | Packing::I16 => {
+                let shift = 32 - self.bits();
+                format!("(bitcast<i32>({bits} << {shift}u) >> {shift}u)")
+            }
+            Packing::BF16 => format!("bitcast<f32>({bits} << 16u)"),
+        }
+    }
+
+    /// Bits of a register value, in the low bits of an `u32`.
+    fn pack(&self, value: &str) -> String {
+        let mask = self.mask();
+        match self {
+            Packing::U8 | Packing::U16 => format!("({value} & {mask})"),
+            Packing::I8 | Packing::I16 => format!("(bitcast<u32>({value}) & {mask})"),
+            Packing::BF16 => format!("(bitcast<u32>({value}) >> 16u)"),
+        }
+    }
+
+    /// Wraps a register value of type `item` to the range of the type, as done when casting.
+    pub fn narrow(&self, value: &str, item: Item) -> String {
+        let bits = item.with_elem(Elem::U32);
+        match self {
+            Packing::U8 | Packing::U16 => format!("({value} & {item}({}))", self.mask()),
+            Packing::I8 | Packing::I16 => {
+                let shift = 32 - self.bits();
+                format!("(({value} << {bits}({shift}u)) >> {bits}({shift}u))")
+            }
+            Packing::BF16 => format!("{}({value})", round_bf16_name(item)),
+        }
+    }
+}
+
+impl Display for Packing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packing::U8 => f.write_str("u8"),
+            Packing::I8 => f.write_str("i8"),
+            Packing::U16 => f.write_str("u16"),
+            Packing::I16 => f.write_str("i16"),
+            Packing::BF16 => f.write_str("bf16"),
+        }
+    }
+}
+
+impl PackedBuffer {
+    /// Type of the words of the buffer.
+    pub fn word(&self) -> &'static str {
+        match self.atomic {
+            true => "atomic<u32>",
+            false => "u32",
+        }
+    }
+
+    /// Expression of the word at `index` in `buffer`.
+    fn load_word(&self, buffer: &Variable, index: &str) -> String {
+        match self.atomic {
+            true => format!("atomicLoad(&{buffer}[{index}])"),
+            false => format!("{buffer}[{index}]"),
+        }
+    }
+
+    /// Expression of the element at `index`, an `u32` expression.
+    fn element(&self, buffer: &Variable, index: &str) -> String {
+        let packing = self.packing;
+        let per_word = packing.per_word();
+        let word = self.load_word(buffer, &format!("{index} / {per_word}u"));
+        let bits = format!(
+            "(({word} >> (({index} % {per_word}u) * {}u)) & {})",
+            packing.bits(),
+            packing.mask()
+        );
+        packing.unpack(&bits)
+    }
+
+    /// Expression of the line at `index` in `buffer`, with `line_size` elements.
+    pub fn load(&self, buffer: &Variable, index: &str, line_size: usize) -> String {
+        if line_size == 1 {
+            return self.element(buffer, &format!("u32({index})"));
+        }
+
+        let item = Item::Scalar(self.packing.register());
+        let elements = (0..line_size)
+            .map(|i| self.element(buffer, &format!("(u32({index}) * {line_size}u + {i}u)")))
+            .collect::<Vec<_>>();
+        format!("vec{line_size}<{item}>({})", elements.join(", "))
+    }
+
+    /// Write the line `values` at `index` in `buffer`. Lines covering whole words are stored
+    /// directly, other elements are merged into their word.
+    pub fn store(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        buffer: &Variable,
+        index: &str,
+        values: &[String],
+    ) -> std::fmt::Result {
+        let packing = self.packing;
+        let line_size = values.len() as u32;
+        let per_word = packing.per_word();
+
+        if line_size.is_multiple_of(per_word) {
+            let words = line_size / per_word;
+            for w in 0..words {
+                let word = (0..per_word)
+                    .map(|i| {
+                        let value = packing.pack(&values[(w * per_word + i) as usize]);
+                        format!("({value} << {}u)", i * packing.bits())
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+                let index = format!("u32({index}) * {words}u + {w}u");
+                match self.atomic {
+                    true => writeln!(f, "atomicStore(&{buffer}[{index}], {word});")?,
+                    false => writeln!(f, "{buffer}[{index}] = {word};")?,
+                }
+            }
+            return Ok(());
+        }
+
+        for (i, value) in values.iter().enumerate() {
+            let element = match line_size {
+                1 => format!("u32({index})"),
+                _ => format!("(u32({index}) * {line_size}u + {i}u)"),
+            };
+            let word = format!("{element} / {per_word}u");
+            let shift = format!("(({element} % {per_word}u) * {}u)", packing.bits());
+            let mask = format!("({} << {shift})", packing.mask());
+            let bits = format!("({} << {shift})", packing.pack(value));
+
+            match self.atomic {
+                true => {
+                    writeln!(f, "atomicAnd(&{buffer}[{word}], ~{mask});")?;
+                    writeln!(f, "atomicOr(&{buffer}[{word}], {bits});")?;
+                }
+                false => writeln!(
+                    f,
+                    "{buffer}[{word}] = ({buffer}[{word}] & ~{mask}) | {bits};"
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Expression of the scalar at `index` of a packed scalar binding.
+pub fn fmt_packed_scalar(
+    f: &mut std::fmt::Formatter<'_>,
+    packing: Packing,
+    index: u32,
+) -> std::fmt::Result {
+    let per_word = packing.per_word();
+    let word = index / per_word;
+    let shift = (index % per_word) * packing.bits();
+    let bits = format!(
+        "((scalars_{packing}[{word}] >> {shift}u) & {})",
+        packing.mask()
+    );
+    f.write_str(&packing.unpack(&bits))
+}
+
+fn round_bf16_name(item: Item) -> String {
+    format!("round_bf16_{}", item.vectorization_factor())
+}
+
+/// Rounds `f32` values to the nearest `bf16`, ties to even, keeping NaNs.
+pub fn format_round_bf16(f: &mut std::fmt::Formatter<'_>, item: Item) -> std::fmt::Result {
+    let name = round_bf16_name(item);
+    let bits = item.with_elem(Elem::U32);
+    write!(
+        f,
+        "
+fn {name}(x: {item}) -> {item} {{
+    let bits = bitcast<{bits}>(x);
+    let rounded = (bits + {bits}(0x7fffu) + ((bits >> {bits}(16u)) & {bits}(1u))) & {bits}(0xffff0000u);
+    let is_nan = (bits & {bits}(0x7fffffffu)) > {bits}(0x7f800000u);
+    return bitcast<{item}>(select(rounded, bits | {bits}(0x400000u), is_nan));
+}}
+"
+    )
+}
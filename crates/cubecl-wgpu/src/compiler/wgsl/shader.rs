@@ -1,5 +1,9 @@
-use super::{Body, Elem, Extension, Item, Variable};
-use cubecl_core::{CubeDim, ir::Id, prelude::Visibility};
+use super::{Body, Elem, Extension, Item, PackedBuffer, Packing, Variable};
+use cubecl_core::{
+    CubeDim,
+    ir::{Id, Sampler, TextureDim},
+    prelude::Visibility,
+};
 use std::fmt::Display;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -15,6 +19,20 @@ pub struct Binding {
     pub visibility: Visibility,
     pub item: Item,
     pub size: Option<usize>,
+    /// Set when the elements are packed into `u32` words.
+    pub packed: Option<PackedBuffer>,
+}
+
+/// Binding of the scalars of one type.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ScalarBinding {
+    pub elem: Elem,
+    pub len: usize,
+    /// Set when the scalars are packed into `u32` words.
+    pub packing: Option<Packing>,
+    /// Set when the scalars are emulated 64-bit integers or `df64`, stored as a `vec2` of their
+    /// words.
+    pub words: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -76,14 +94,29 @@ impl LocalArray {
     }
 }
 
+/// A non-inlined function called from the kernel. Builtins are passed in by the caller.
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<Variable>,
+    pub out: Option<Variable>,
+    pub local_arrays: Vec<LocalArray>,
+    pub body: Body,
+}
+
 #[derive(Debug, Clone)]
 pub struct ComputeShader {
     pub buffers: Vec<Binding>,
-    pub scalars: Vec<(Elem, usize)>,
+    pub scalars: Vec<ScalarBinding>,
+    /// Dimensionality of the textures, bound after the scalars and followed by one sampler per
+    /// [sampler state](cubecl_core::ir::Sampler::index).
+    pub textures: Vec<TextureDim>,
     pub shared_arrays: Vec<SharedArray>,
     pub shared_values: Vec<SharedValue>,
     pub constant_arrays: Vec<ConstantArray>,
     pub local_arrays: Vec<LocalArray>,
+    /// Pipeline-overridable constants, by id.
+    pub spec_constants: Vec<(Id, Elem)>,
     pub has_metadata: bool,
     pub workgroup_size: CubeDim,
     pub address_type: Elem,
@@ -97,6 +130,7 @@ pub struct ComputeShader {
     pub num_workgroups_no_axis: bool,
     pub workgroup_id_no_axis: bool,
     pub workgroup_size_no_axis: bool,
+    pub functions: Vec<Function>,
     pub body: Body,
     pub extensions: Vec<Extension>,
     pub kernel_name: String,
@@ -121,20 +155,72 @@ impl Display for ComputeShader {
 
         let mut offset = self.buffers.len();
         if self.has_metadata {
-            Self::format_scalar_binding(f, "info", self.address_type, None, offset)?;
+            Self::format_scalar_binding(f, "info", Item::Scalar(self.address_type), None, offset)?;
             offset += 1;
         }
 
-        for (i, (elem, len)) in self.scalars.iter().enumerate() {
-            Self::format_scalar_binding(
+        for (i, scalars) in self.scalars.iter().enumerate() {
+            match scalars.packing {
+                Some(packing) => Self::format_scalar_binding(
+                    f,
+                    &format!("scalars_{packing}"),
+                    Item::Scalar(Elem::U32),
+                    Some(scalars.len.div_ceil(packing.per_word() as usize)),
+                    offset + i,
+                )?,
+                None if scalars.words => {
+                    // `df64` is lowered to `f32` words
+                    let (word, name) = match scalars.elem {
+                        Elem::I64 => (Elem::I32, "scalars_i64".to_string()),
+                        Elem::F32 => (Elem::F32, "scalars_df64".to_string()),
+                        elem => (Elem::U32, format!("scalars_{elem}")),
+                    };
+                    Self::format_scalar_binding(
+                        f,
+                        &name,
+                        Item::Vec2(word),
+                        Some(scalars.len),
+                        offset + i,
+                    )?
+                }
+                None => Self::format_scalar_binding(
+                    f,
+                    &format!("scalars_{}", scalars.elem),
+                    Item::Scalar(scalars.elem),
+                    Some(scalars.len),
+                    offset + i,
+                )?,
+            }
+        }
+
+        let offset = offset + self.scalars.len();
+        for (i, dim) in self.textures.iter().enumerate() {
+            let ty = match dim {
+                TextureDim::D2 => "texture_2d<f32>",
+                TextureDim::D3 => "texture_3d<f32>",
+            };
+            write!(
                 f,
-                &format!("scalars_{elem}"),
-                *elem,
-                Some(*len),
-                offset + i,
+                "@group(0)\n@binding({})\nvar texture_{i}: {ty};\n\n",
+                offset + i
             )?;
         }
 
+        if !self.textures.is_empty() {
+            let offset = offset + self.textures.len();
+            for i in 0..Sampler::COUNT {
+                write!(
+                    f,
+                    "@group(0)\n@binding({})\nvar sampler_{i}: sampler;\n\n",
+                    offset + i
+                )?;
+            }
+        }
+
+        for (id, elem) in self.spec_constants.iter() {
+            write!(f, "@id({id}) override spec_constant_{id}: {elem};\n\n")?;
+        }
+
         for array in self.shared_arrays.iter() {
             write!(
                 f,
@@ -172,6 +258,10 @@ const WORKGROUP_SIZE_Z = {}u;\n",
             self.workgroup_size.x, self.workgroup_size.y, self.workgroup_size.z
         )?;
 
+        for function in self.functions.iter() {
+            write!(f, "\n{function}")?;
+        }
+
         write!(
             f,
             "
@@ -254,7 +344,53 @@ fn {}(
     }
 }
 
+impl Display for Function {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "fn {}(", self.name)?;
+        for (i, param) in self.params.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{param}: {}", param.item())?;
+        }
+        f.write_str(")")?;
+        if let Some(out) = &self.out {
+            write!(f, " -> {}", out.item())?;
+        }
+        f.write_str(" {\n")?;
+
+        for array in self.local_arrays.iter() {
+            writeln!(
+                f,
+                "var a_{}: array<{}, {}>;\n",
+                array.index, array.item, array.size
+            )?;
+        }
+
+        write!(f, "{}", self.body)?;
+        if let Some(out) = &self.out {
+            writeln!(f, "return {out};")?;
+        }
+        f.write_str("}\n")
+    }
+}
+
 impl ComputeShader {
+    /// Size in bytes of all the shared arrays and values of the shader.
+    pub fn shared_memory_size(&self) -> usize {
+        let arrays = self.shared_arrays.iter();
+        let arrays = arrays.map(|it| it.item.size() * it.size as usize);
+        let values = self.shared_values.iter().map(|it| it.item.size());
+        arrays.chain(values).sum()
+    }
+
+    /// Size in bytes of every local array of the shader and its functions.
+    pub fn local_array_sizes(&self) -> Vec<usize> {
+        let functions = self.functions.iter().flat_map(|f| f.local_arrays.iter());
+        let arrays = self.local_arrays.iter().chain(functions);
+        arrays.map(|it| it.item.size() * it.size as usize).collect()
+    }
+
     fn format_bindings(
         f: &mut core::fmt::Formatter<'_>,
         prefix: &str,
@@ -279,9 +415,15 @@ impl ComputeShader {
         binding: &Binding,
         num_entry: usize,
     ) -> core::fmt::Result {
-        let ty = match binding.size {
-            Some(size) => format!("array<{}, {}>", binding.item, size),
-            None => format!("array<{}>", binding.item),
+        let ty = match (binding.size, binding.packed) {
+            (Some(size), Some(packed)) => {
+                let words = (size * binding.item.vectorization_factor())
+                    .div_ceil(packed.packing.per_word() as usize);
+                format!("array<{}, {words}>", packed.word())
+            }
+            (None, Some(packed)) => format!("array<{}>", packed.word()),
+            (Some(size), None) => format!("array<{}, {}>", binding.item, size),
+            (None, None) => format!("array<{}>", binding.item),
         };
 
         let visibility = match binding.visibility {
@@ -305,7 +447,7 @@ var<{}, {}> {}: {};
     fn format_scalar_binding(
         f: &mut core::fmt::Formatter<'_>,
         name: &str,
-        elem: Elem,
+        item: Item,
         len: Option<usize>,
         num_entry: usize,
     ) -> core::fmt::Result {
@@ -313,8 +455,8 @@ var<{}, {}> {}: {};
         // Really, they SHOULD be marked as <uniform> but that requires an alignment of 16 bytes currently,
         // and that would complicate generating the shader code.
         let ty = match len {
-            Some(size) => format!("array<{elem}, {size}>"),
-            None => format!("array<{elem}>"),
+            Some(size) => format!("array<{item}, {size}>"),
+            None => format!("array<{item}>"),
         };
         let location = Location::Storage;
         let visibility = "read";
@@ -0,0 +1,178 @@
+use crate::{WgpuResource, schedule::GraphDispatch, texture::bind_group_entries};
+use alloc::sync::Arc;
+use cubecl_core::CubeCount;
+use wgpu::{BufferUsages, ComputePipeline};
+
+/// A [launch graph](cubecl_runtime::graph::LaunchGraph) recorded on a stream.
+///
+/// Command buffers can only be submitted once, so the recording keeps everything else the
+/// dispatches of the graph need between replays: the metadata and scalars are written to uniforms
+/// owned by the recording, and a bind group is only created again when the buffers or textures
+/// bound to its dispatch change.
+#[derive(Debug, Default)]
+pub(crate) struct RecordedGraph {
+    dispatches: Vec<RecordedDispatch>,
+}
+
+#[derive(Debug)]
+struct RecordedDispatch {
+    pipeline: Arc<ComputePipeline>,
+    count: CubeCount,
+    resources: Vec<WgpuResource>,
+    uniforms: Vec<WgpuResource>,
+    textures: Vec<wgpu::TextureView>,
+    bind_group: wgpu::BindGroup,
+}
+
+impl RecordedGraph {
+    /// Update the recording with the dispatches of a replay, with the texture views bound by each
+    /// dispatch and the samplers bound along them.
+    ///
+    /// The uniforms are written with the queue, so the previous replay must already be submitted.
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        dispatches: Vec<GraphDispatch>,
+        textures: Vec<Vec<wgpu::TextureView>>,
+        samplers: &[wgpu::Sampler],
+    ) {
+        self.dispatches.truncate(dispatches.len());
+
+        for (index, (dispatch, textures)) in dispatches.into_iter().zip(textures).enumerate() {
+            let bindings = dispatch.resources;
+            let metadata = match bindings.metadata.data.is_empty() {
+                true => None,
+                false => Some(bytemuck::cast_slice::<u64, u8>(&bindings.metadata.data)),
+            };
+            let data = metadata
+                .into_iter()
+                .chain(bindings.scalars.values().map(|scalar| scalar.data()))
+                .collect::<Vec<_>>();
+
+            let reusable = self.dispatches.get(index).is_some_and(|recorded| {
+                recorded.matches(&dispatch.pipeline, &bindings.resources, &textures, &data)
+            });
+
+            if !reusable {
+                let recorded = RecordedDispatch::new(
+                    device,
+                    dispatch.pipeline,
+                    bindings.resources,
+                    textures,
+                    samplers,
+                    &data,
+                );
+                match index < self.dispatches.len() {
+                    true => self.dispatches[index] = recorded,
+                    false => self.dispatches.push(recorded),
+                }
+            }
+
+            // Graphs launching the same kernels share their recording, but not their cube counts.
+            let recorded = &mut self.dispatches[index];
+            recorded.count = dispatch.count;
+            for (uniform, data) in recorded.uniforms.iter().zip(data) {
+                write_uniform(queue, uniform, data);
+            }
+        }
+    }
+
+    /// The pipelines, bind groups and cube counts to dispatch, in submission order.
+    pub fn dispatches(
+        &self,
+    ) -> impl Iterator<Item = (&Arc<ComputePipeline>, &wgpu::BindGroup, &CubeCount)> {
+        self.dispatches
+            .iter()
+            .map(|dispatch| (&dispatch.pipeline, &dispatch.bind_group, &dispatch.count))
+    }
+}
+
+impl RecordedDispatch {
+    fn new(
+        device: &wgpu::Device,
+        pipeline: Arc<ComputePipeline>,
+        resources: Vec<WgpuResource>,
+        textures: Vec<wgpu::TextureView>,
+        samplers: &[wgpu::Sampler],
+        data: &[&[u8]],
+    ) -> Self {
+        let uniforms = data
+            .iter()
+            .map(|data| {
+                let size = data.len() as u64;
+                let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("CubeCL Graph Uniform"),
+                    size: size.next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
+                    usage: BufferUsages::UNIFORM | BufferUsages::STORAGE | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                WgpuResource::new(buffer, 0, size)
+            })
+            .collect::<Vec<_>>();
+
+        let samplers: &[wgpu::Sampler] = match textures.is_empty() {
+            true => &[],
+            false => samplers,
+        };
+        let entries =
+            bind_group_entries(resources.iter().chain(uniforms.iter()), &textures, samplers);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        });
+
+        Self {
+            pipeline,
+            count: CubeCount::Static(0, 0, 0),
+            resources,
+            uniforms,
+            textures,
+            bind_group,
+        }
+    }
+
+    /// Whether the bind group of the dispatch can be reused for the given bindings.
+    fn matches(
+        &self,
+        pipeline: &Arc<ComputePipeline>,
+        resources: &[WgpuResource],
+        textures: &[wgpu::TextureView],
+        data: &[&[u8]],
+    ) -> bool {
+        Arc::ptr_eq(&self.pipeline, pipeline)
+            && self.resources.len() == resources.len()
+            && self
+                .resources
+                .iter()
+                .zip(resources)
+                .all(|(recorded, resource)| {
+                    recorded.buffer == resource.buffer
+                        && recorded.offset == resource.offset
+                        && recorded.size == resource.size
+                })
+            && self.textures == textures
+            && self.uniforms.len() == data.len()
+            && self
+                .uniforms
+                .iter()
+                .zip(data)
+                .all(|(uniform, data)| uniform.size == data.len() as u64)
+    }
+}
+
+fn write_uniform(queue: &wgpu::Queue, uniform: &WgpuResource, data: &[u8]) {
+    // Writes have to be a multiple of 4 bytes, the uniforms are padded on creation.
+    let size = data
+        .len()
+        .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT as usize);
+    match size == data.len() {
+        true => queue.write_buffer(&uniform.buffer, uniform.offset, data),
+        false => {
+            let mut padded = data.to_vec();
+            padded.resize(size, 0);
+            queue.write_buffer(&uniform.buffer, uniform.offset, &padded);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub(crate) mod controller;
 pub(crate) mod errors;
+pub(crate) mod graph;
 
 mod storage;
 
@@ -8,6 +9,7 @@ pub(super) mod poll;
 pub(super) mod schedule;
 mod server;
 pub(super) mod stream;
+pub(super) mod texture;
 pub(super) mod timings;
 
 pub use server::*;
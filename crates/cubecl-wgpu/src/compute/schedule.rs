@@ -1,4 +1,4 @@
-use crate::{WgpuResource, stream::WgpuStream};
+use crate::{WgpuResource, stream::WgpuStream, texture::TextureResource};
 use alloc::sync::Arc;
 use cubecl_common::{bytes::Bytes, profile::TimingMethod};
 use cubecl_core::{
@@ -8,6 +8,7 @@ use cubecl_core::{
 };
 use cubecl_ir::MemoryDeviceProperties;
 use cubecl_runtime::{
+    id::KernelId,
     logging::ServerLogger,
     stream::{StreamFactory, scheduler::SchedulerStreamBackend},
 };
@@ -32,6 +33,24 @@ pub enum ScheduleTask {
         /// The resources (bindings) required for execution.
         resources: BindingsResource,
     },
+    /// Represents a task to execute the compute pipelines of a launch graph.
+    ExecuteGraph {
+        /// The kernels of the graph, identifying its recording on the stream.
+        kernels: Vec<KernelId>,
+        /// The dispatches of the graph, in submission order.
+        dispatches: Vec<GraphDispatch>,
+    },
+}
+
+/// A compute pipeline dispatched by a launch graph.
+#[derive(Debug)]
+pub struct GraphDispatch {
+    /// The compute pipeline to execute.
+    pub pipeline: Arc<wgpu::ComputePipeline>,
+    /// The number of workgroups to dispatch.
+    pub count: CubeCount,
+    /// The resources (bindings) required for execution.
+    pub resources: BindingsResource,
 }
 
 /// Represents a collection of resources and bindings for a compute task.
@@ -43,6 +62,8 @@ pub struct BindingsResource {
     pub metadata: MetadataBinding,
     /// Scalar values mapped by their storage type.
     pub scalars: BTreeMap<StorageType, ScalarBinding>,
+    /// Textures, bound after the uniforms.
+    pub textures: Vec<TextureResource>,
 }
 
 /// Represents a WGPU backend for scheduling tasks on streams.
@@ -1,6 +1,7 @@
 use super::storage::{WgpuResource, WgpuStorage};
 use crate::AutoCompiler;
-use crate::schedule::{BindingsResource, ScheduleTask, ScheduledWgpuBackend};
+use crate::schedule::{BindingsResource, GraphDispatch, ScheduleTask, ScheduledWgpuBackend};
+use crate::texture::TextureResource;
 use alloc::sync::Arc;
 use cubecl_common::{
     backtrace::BackTrace,
@@ -17,10 +18,12 @@ use cubecl_core::{
         IoError, LaunchError, ProfileError, ProfilingToken, ServerCommunication, ServerUtilities,
     },
 };
-use cubecl_ir::MemoryDeviceProperties;
+use cubecl_ir::{ConstantValue, MemoryDeviceProperties};
 use cubecl_runtime::{
     compiler::{CompilationError, CubeTask},
     config::GlobalConfig,
+    graph::GraphLaunch,
+    kernel::{CompiledKernel, KernelResourceUsage},
     logging::ServerLogger,
     memory_management::{MemoryAllocationMode, offset_handles},
     server::ComputeServer,
@@ -34,13 +37,26 @@ use wgpu::ComputePipeline;
 #[derive(Debug)]
 pub struct WgpuServer {
     pub(crate) device: wgpu::Device,
-    pipelines: HashMap<KernelId, Arc<ComputePipeline>>,
+    pipelines: HashMap<(KernelId, Vec<ConstantValue>), Arc<ComputePipeline>>,
+    specializable: SpecializableKernels,
+    resource_usages: HashMap<KernelId, KernelResourceUsage>,
     scheduler: SchedulerMultiStream<ScheduledWgpuBackend>,
     pub compilation_options: WgpuCompilationOptions,
     pub(crate) backend: wgpu::Backend,
     pub(crate) utilities: Arc<ServerUtilities<Self>>,
 }
 
+/// Compiled kernels declaring specialization constants, kept to create a pipeline for each new
+/// set of values without compiling the kernel again.
+#[derive(Default)]
+struct SpecializableKernels(HashMap<KernelId, Arc<CompiledKernel<AutoCompiler>>>);
+
+impl core::fmt::Debug for SpecializableKernels {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.0.keys()).finish()
+    }
+}
+
 impl ServerCommunication for WgpuServer {
     const SERVER_COMM_ENABLED: bool = false;
 }
@@ -76,6 +92,8 @@ impl WgpuServer {
             compilation_options,
             device,
             pipelines: HashMap::new(),
+            specializable: SpecializableKernels::default(),
+            resource_usages: HashMap::new(),
             scheduler: SchedulerMultiStream::new(
                 utilities.logger.clone(),
                 backend_scheduler,
@@ -101,11 +119,25 @@ impl WgpuServer {
                 stream.mem_manage.get_resource(b.clone()).unwrap()
             })
             .collect::<Vec<_>>();
+        let textures = bindings
+            .textures
+            .into_iter()
+            .map(|texture| {
+                let stream = self.scheduler.stream(&texture.binding.stream);
+                let memory = texture.binding.memory.downgrade();
+                TextureResource {
+                    resource: stream.mem_manage.get_resource(texture.binding).unwrap(),
+                    meta: texture.meta,
+                    memory,
+                }
+            })
+            .collect();
 
         BindingsResource {
             resources,
             metadata: bindings.metadata,
             scalars: bindings.scalars,
+            textures,
         }
     }
 
@@ -113,13 +145,22 @@ impl WgpuServer {
         &mut self,
         kernel: <Self as ComputeServer>::Kernel,
         mode: ExecutionMode,
+        spec_constants: &[ConstantValue],
     ) -> Result<Arc<ComputePipeline>, CompilationError> {
         let mut kernel_id = kernel.id();
         kernel_id.mode(mode);
 
-        if let Some(pipeline) = self.pipelines.get(&kernel_id) {
+        let key = (kernel_id, spec_constants.to_vec());
+        if let Some(pipeline) = self.pipelines.get(&key) {
             return Ok(pipeline.clone());
         }
+        let kernel_id = &key.0;
+
+        if let Some(compiled) = self.specializable.0.get(kernel_id).cloned() {
+            let pipeline = self.create_pipeline(&compiled, mode, spec_constants)?;
+            self.pipelines.insert(key, pipeline.clone());
+            return Ok(pipeline);
+        }
 
         let mut compiler = compiler(self.backend);
         let mut compile = compiler.compile(self, kernel, mode)?;
@@ -131,6 +172,8 @@ impl WgpuServer {
             ));
         }
         self.scheduler.logger.log_compilation(&compile);
+        self.resource_usages
+            .insert(kernel_id.clone(), compile.resource_usage.clone());
         // /!\ Do not delete the following commented code.
         // This is useful while working on the metal compiler.
         // Also the errors are printed nicely which is not the case when this is the runtime
@@ -153,8 +196,13 @@ impl WgpuServer {
         //         .expect("should launch the command");
         //     // std::process::exit(status.code().unwrap());
         // }
-        let pipeline = self.create_pipeline(compile, mode)?;
-        self.pipelines.insert(kernel_id.clone(), pipeline.clone());
+        let pipeline = self.create_pipeline(&compile, mode, spec_constants)?;
+        if !spec_constants.is_empty() {
+            self.specializable
+                .0
+                .insert(kernel_id.clone(), Arc::new(compile));
+        }
+        self.pipelines.insert(key, pipeline.clone());
 
         Ok(pipeline)
     }
@@ -289,8 +337,8 @@ impl ComputeServer for WgpuServer {
         mode: ExecutionMode,
         stream_id: StreamId,
     ) -> Result<(), LaunchError> {
-        let pipeline = self.pipeline(kernel, mode)?;
-        let buffers = bindings.buffers.clone();
+        let pipeline = self.pipeline(kernel, mode, &bindings.spec_constants)?;
+        let buffers = bindings_buffers(&bindings);
         let resources = self.prepare_bindings(bindings);
         let task = ScheduleTask::Execute {
             pipeline,
@@ -303,6 +351,40 @@ impl ComputeServer for WgpuServer {
         Ok(())
     }
 
+    unsafe fn launch_graph(
+        &mut self,
+        launches: Vec<GraphLaunch<Self::Kernel>>,
+        stream_id: StreamId,
+    ) -> Result<(), LaunchError> {
+        let mut kernels = Vec::with_capacity(launches.len());
+        let mut dispatches = Vec::with_capacity(launches.len());
+        let mut buffers = Vec::new();
+
+        for launch in launches {
+            let mut kernel_id = launch.kernel.id();
+            kernel_id.mode(launch.mode);
+            kernels.push(kernel_id);
+
+            let pipeline =
+                self.pipeline(launch.kernel, launch.mode, &launch.bindings.spec_constants)?;
+            buffers.extend(bindings_buffers(&launch.bindings));
+            dispatches.push(GraphDispatch {
+                pipeline,
+                count: launch.count,
+                resources: self.prepare_bindings(launch.bindings),
+            });
+        }
+
+        // The graph is enqueued as a single task, so the stream can replay its recording.
+        let task = ScheduleTask::ExecuteGraph {
+            kernels,
+            dispatches,
+        };
+        self.scheduler.register(stream_id, task, buffers.iter());
+
+        Ok(())
+    }
+
     fn flush(&mut self, stream_id: StreamId) {
         self.scheduler.execute_streams(vec![stream_id]);
         let stream = self.scheduler.stream(&stream_id);
@@ -352,6 +434,10 @@ impl ComputeServer for WgpuServer {
         let stream = self.scheduler.stream(&stream_id);
         stream.mem_manage.mode(mode);
     }
+
+    fn resource_usage(&mut self, kernel_id: &KernelId) -> Option<KernelResourceUsage> {
+        self.resource_usages.get(kernel_id).cloned()
+    }
 }
 
 fn compiler(backend: wgpu::Backend) -> AutoCompiler {
@@ -372,3 +458,9 @@ pub(crate) fn contiguous_strides(shape: &[usize]) -> Vec<usize> {
     }
     strides
 }
+
+/// Buffers bound by a launch, including the ones holding the texels of its textures.
+fn bindings_buffers(bindings: &Bindings) -> Vec<Binding> {
+    let textures = bindings.textures.iter().map(|texture| &texture.binding);
+    bindings.buffers.iter().chain(textures).cloned().collect()
+}
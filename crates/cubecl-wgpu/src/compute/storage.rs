@@ -36,12 +36,13 @@ pub struct WgpuResource {
 impl WgpuResource {
     /// Return the binding view of the buffer.
     pub fn as_wgpu_bind_resource(&self) -> wgpu::BindingResource<'_> {
+        // Storage bindings must be a multiple of 4 bytes, which isn't the case for tensors of
+        // packed types like `u8`. Slices are aligned, so the padding is always in the page.
+        let size = self.size.next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
         let binding = wgpu::BufferBinding {
             buffer: &self.buffer,
             offset: self.offset,
-            size: Some(
-                NonZeroU64::new(self.size).expect("0 size resources are not yet supported."),
-            ),
+            size: Some(NonZeroU64::new(size).expect("0 size resources are not yet supported.")),
         };
         wgpu::BindingResource::Buffer(binding)
     }
@@ -3,7 +3,9 @@ use crate::{
     WgpuResource,
     controller::WgpuAllocController,
     errors::{fetch_error, track_error},
+    graph::RecordedGraph,
     schedule::ScheduleTask,
+    texture::{TextureCache, TextureResource, bind_group_entries},
 };
 use cubecl_common::{
     backtrace::BackTrace,
@@ -17,7 +19,8 @@ use cubecl_core::{
     server::{ExecutionError, Handle, IoError, ProfileError, ProfilingToken},
 };
 use cubecl_ir::MemoryDeviceProperties;
-use cubecl_runtime::{logging::ServerLogger, timestamp_profiler::TimestampProfiler};
+use cubecl_runtime::{id::KernelId, logging::ServerLogger, timestamp_profiler::TimestampProfiler};
+use hashbrown::HashMap;
 use std::{future::Future, num::NonZero, pin::Pin, sync::Arc};
 use wgpu::ComputePipeline;
 
@@ -39,6 +42,8 @@ pub struct WgpuStream {
     encoder: wgpu::CommandEncoder,
     poll: WgpuPoll,
     submission_load: SubmissionLoad,
+    graphs: HashMap<Vec<KernelId>, RecordedGraph>,
+    textures: TextureCache,
 }
 
 impl WgpuStream {
@@ -86,6 +91,8 @@ impl WgpuStream {
             tasks_max,
             poll,
             submission_load: SubmissionLoad::default(),
+            graphs: HashMap::new(),
+            textures: TextureCache::default(),
         }
     }
 
@@ -106,10 +113,38 @@ impl WgpuStream {
             ScheduleTask::Execute {
                 pipeline,
                 count,
-                resources,
+                mut resources,
             } => {
+                let textures = self.prepare_textures(core::mem::take(&mut resources.textures));
                 let resources = resources.into_resources(self);
-                self.register_pipeline(pipeline, resources.iter(), &count);
+                self.register_pipeline(pipeline, resources.iter(), &textures, &count);
+            }
+            ScheduleTask::ExecuteGraph {
+                kernels,
+                dispatches,
+            } => {
+                // The uniforms of a recorded graph are written into the QUEUE, so the previous
+                // replay has to be submitted before they are overwritten.
+                self.flush();
+
+                let mut dispatches = dispatches;
+                let textures = dispatches
+                    .iter_mut()
+                    .map(|dispatch| {
+                        self.prepare_textures(core::mem::take(&mut dispatch.resources.textures))
+                    })
+                    .collect::<Vec<_>>();
+                let samplers: &[wgpu::Sampler] = match textures.iter().all(Vec::is_empty) {
+                    true => &[],
+                    false => self.textures.samplers(&self.device),
+                };
+
+                let mut graph = self.graphs.remove(&kernels).unwrap_or_default();
+                graph.update(&self.device, &self.queue, dispatches, textures, samplers);
+                for (pipeline, bind_group, count) in graph.dispatches() {
+                    self.dispatch(pipeline, bind_group, count);
+                }
+                self.graphs.insert(kernels, graph);
             }
         }
     }
@@ -380,20 +415,53 @@ impl WgpuStream {
         self.tasks_count = 0;
     }
 
+    /// Record the copies of the texels of the textures of a dispatch, returning the views to
+    /// bind.
+    fn prepare_textures(&mut self, textures: Vec<TextureResource>) -> Vec<wgpu::TextureView> {
+        if textures.is_empty() {
+            return Vec::new();
+        }
+
+        // Copies can't be recorded while a compute pass is open.
+        self.compute_pass = None;
+        textures
+            .into_iter()
+            .map(|texture| {
+                self.textures
+                    .prepare(&self.device, &mut self.encoder, texture)
+            })
+            .collect()
+    }
+
     fn register_pipeline<'a>(
         &mut self,
         pipeline: Arc<ComputePipeline>,
         resources: impl Iterator<Item = &'a WgpuResource>,
+        textures: &[wgpu::TextureView],
         dispatch: &CubeCount,
     ) {
-        let entries = resources
-            .enumerate()
-            .map(|(i, r)| wgpu::BindGroupEntry {
-                binding: i as u32,
-                resource: r.as_wgpu_bind_resource(),
-            })
-            .collect::<Vec<_>>();
+        let samplers: &[wgpu::Sampler] = match textures.is_empty() {
+            true => &[],
+            false => self.textures.samplers(&self.device),
+        };
+        let entries = bind_group_entries(resources, textures, samplers);
 
+        let group_layout = pipeline.get_bind_group_layout(0);
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &group_layout,
+            entries: &entries,
+        });
+
+        self.dispatch(&pipeline, &bind_group, dispatch);
+    }
+
+    fn dispatch(
+        &mut self,
+        pipeline: &ComputePipeline,
+        bind_group: &wgpu::BindGroup,
+        dispatch: &CubeCount,
+    ) {
         // Start a new compute pass if needed. The forget_lifetime allows
         // to store this with a 'static lifetime, but the compute pass must
         // be dropped before the encoder. This isn't unsafe - it's still checked at runtime.
@@ -419,15 +487,8 @@ impl WgpuStream {
 
         self.tasks_count += 1;
 
-        let group_layout = pipeline.get_bind_group_layout(0);
-        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: None,
-            layout: &group_layout,
-            entries: &entries,
-        });
-
-        pass.set_pipeline(&pipeline);
-        pass.set_bind_group(0, &bind_group, &[]);
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
 
         match dispatch.clone() {
             CubeCount::Static(x, y, z) => {
@@ -0,0 +1,226 @@
+use crate::WgpuResource;
+use cubecl_core::{
+    ir::{AddressMode, ElemType, FilterMode, FloatKind, Sampler, TextureDim},
+    server::TextureMeta,
+};
+use cubecl_runtime::id::WeakBindingRef;
+use hashbrown::HashMap;
+
+/// A texture bound to a kernel, with the buffer holding its texels.
+#[derive(Debug)]
+pub struct TextureResource {
+    /// The buffer holding the texels.
+    pub resource: WgpuResource,
+    /// The layout of the texels.
+    pub meta: TextureMeta,
+    /// The memory of the buffer, to drop the texture once the buffer is freed.
+    pub memory: WeakBindingRef,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct TextureKey {
+    buffer: wgpu::Buffer,
+    offset: u64,
+    dim: TextureDim,
+    extent: [usize; 3],
+    format: wgpu::TextureFormat,
+}
+
+#[derive(Debug)]
+struct Texture {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    memory: WeakBindingRef,
+}
+
+/// Textures and samplers created for the launches of a stream.
+///
+/// Textures can't alias a buffer, so each texture owns a copy of the texels that is refreshed from
+/// the bound buffer on every launch. Textures are reused while the buffer backing them is alive,
+/// and dropped once it is freed.
+#[derive(Debug, Default)]
+pub(crate) struct TextureCache {
+    textures: HashMap<TextureKey, Texture>,
+    samplers: Vec<wgpu::Sampler>,
+}
+
+impl TextureCache {
+    /// Record the copy of the texels of `texture` with `encoder`, and return the view to bind.
+    ///
+    /// The encoder can't have a compute pass open while the copy is recorded.
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: TextureResource,
+    ) -> wgpu::TextureView {
+        // Dropped textures are only destroyed by wgpu once the submissions using them are done.
+        self.textures.retain(|_, texture| !texture.memory.is_free());
+
+        let key = TextureKey {
+            buffer: texture.resource.buffer.clone(),
+            offset: texture.resource.offset,
+            dim: texture.meta.dim,
+            extent: texture.meta.extent,
+            format: texture_format(&texture.meta),
+        };
+        let cached = self
+            .textures
+            .entry(key)
+            .or_insert_with_key(|key| create_texture(device, key, texture.memory.clone()));
+        cached.memory = texture.memory;
+        copy_texels(encoder, &texture.resource, &texture.meta, &cached.texture);
+
+        cached.view.clone()
+    }
+
+    /// One sampler per sampler state, ordered by [index](Sampler::index).
+    pub fn samplers(&mut self, device: &wgpu::Device) -> &[wgpu::Sampler] {
+        if self.samplers.is_empty() {
+            self.samplers = Sampler::all()
+                .map(|sampler| create_sampler(device, sampler))
+                .collect();
+        }
+        &self.samplers
+    }
+}
+
+/// Entries of the bind group of a dispatch, the buffers followed by the textures and their
+/// samplers.
+pub(crate) fn bind_group_entries<'a>(
+    resources: impl Iterator<Item = &'a WgpuResource>,
+    textures: &'a [wgpu::TextureView],
+    samplers: &'a [wgpu::Sampler],
+) -> Vec<wgpu::BindGroupEntry<'a>> {
+    let resources = resources.map(|r| r.as_wgpu_bind_resource());
+    let textures = textures.iter().map(wgpu::BindingResource::TextureView);
+    let samplers = samplers.iter().map(wgpu::BindingResource::Sampler);
+    resources
+        .chain(textures)
+        .chain(samplers)
+        .enumerate()
+        .map(|(i, resource)| wgpu::BindGroupEntry {
+            binding: i as u32,
+            resource,
+        })
+        .collect()
+}
+
+fn create_texture(device: &wgpu::Device, key: &TextureKey, memory: WeakBindingRef) -> Texture {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("CubeCL Texture"),
+        size: extent(key.extent),
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: match key.dim {
+            TextureDim::D2 => wgpu::TextureDimension::D2,
+            TextureDim::D3 => wgpu::TextureDimension::D3,
+        },
+        format: key.format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&Default::default());
+
+    Texture {
+        texture,
+        view,
+        memory,
+    }
+}
+
+/// Copy the contiguous texels of the buffer into the texture.
+fn copy_texels(
+    encoder: &mut wgpu::CommandEncoder,
+    resource: &WgpuResource,
+    meta: &TextureMeta,
+    texture: &wgpu::Texture,
+) {
+    let [width, height, depth] = meta.extent;
+    let row_size = (width * meta.channels * meta.storage_ty.size()) as u64;
+    let mut copy = |offset: u64, bytes_per_row: Option<u32>, origin: wgpu::Origin3d, size| {
+        encoder.copy_buffer_to_texture(
+            wgpu::TexelCopyBufferInfo {
+                buffer: &resource.buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: resource.offset + offset,
+                    bytes_per_row,
+                    rows_per_image: bytes_per_row.map(|_| height as u32),
+                },
+            },
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin,
+                aspect: wgpu::TextureAspect::All,
+            },
+            size,
+        )
+    };
+
+    if row_size.is_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64) {
+        copy(
+            0,
+            Some(row_size as u32),
+            wgpu::Origin3d::ZERO,
+            extent(meta.extent),
+        );
+        return;
+    }
+
+    // Copies of more than one row need aligned rows, so unaligned rows are copied one by one.
+    for z in 0..depth {
+        for y in 0..height {
+            let origin = wgpu::Origin3d {
+                x: 0,
+                y: y as u32,
+                z: z as u32,
+            };
+            let offset = (z * height + y) as u64 * row_size;
+            copy(offset, None, origin, extent([width, 1, 1]));
+        }
+    }
+}
+
+fn create_sampler(device: &wgpu::Device, sampler: Sampler) -> wgpu::Sampler {
+    let address_mode = match sampler.address {
+        AddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+        AddressMode::Repeat => wgpu::AddressMode::Repeat,
+        AddressMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+    };
+    let filter = match sampler.filter {
+        FilterMode::Nearest => wgpu::FilterMode::Nearest,
+        FilterMode::Linear => wgpu::FilterMode::Linear,
+    };
+
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("CubeCL Sampler"),
+        address_mode_u: address_mode,
+        address_mode_v: address_mode,
+        address_mode_w: address_mode,
+        mag_filter: filter,
+        min_filter: filter,
+        ..Default::default()
+    })
+}
+
+fn extent([width, height, depth]: [usize; 3]) -> wgpu::Extent3d {
+    wgpu::Extent3d {
+        width: width as u32,
+        height: height as u32,
+        depth_or_array_layers: depth as u32,
+    }
+}
+
+/// Texture format of the texels, the frontend only binds `f32` and `f16` texels natively.
+fn texture_format(meta: &TextureMeta) -> wgpu::TextureFormat {
+    match (meta.storage_ty.elem_type(), meta.channels) {
+        (ElemType::Float(FloatKind::F32), 1) => wgpu::TextureFormat::R32Float,
+        (ElemType::Float(FloatKind::F32), 2) => wgpu::TextureFormat::Rg32Float,
+        (ElemType::Float(FloatKind::F32), _) => wgpu::TextureFormat::Rgba32Float,
+        (ElemType::Float(FloatKind::F16), 1) => wgpu::TextureFormat::R16Float,
+        (ElemType::Float(FloatKind::F16), 2) => wgpu::TextureFormat::Rg16Float,
+        (ElemType::Float(FloatKind::F16), _) => wgpu::TextureFormat::Rgba16Float,
+        (elem, _) => panic!("Unsupported texture format {elem}"),
+    }
+}
@@ -33,7 +33,14 @@ mod tests {
     cubecl_core::testgen_all!();
     cubecl_std::testgen!();
     cubecl_std::testgen_tensor_identity!([flex32, f32, u32]);
+    cubecl_std::testgen_tensor_indexing!([f32, u32]);
+    cubecl_std::testgen_tensor_histogram!([f32]);
+    cubecl_std::testgen_tensor_topk!([f32]);
+    cubecl_std::testgen_tensor_concat!([f32]);
+    cubecl_std::testgen_tensor_pad!([f32]);
+    cubecl_std::testgen_tensor_io!();
     cubecl_std::testgen_quantized_view!(f32);
+    cubecl_std::testgen_quantize!(f32);
 }
 
 #[cfg(all(test, feature = "spirv"))]
@@ -46,7 +53,14 @@ mod tests_spirv {
     cubecl_core::testgen_all!(f32: [f16, flex32, f32], i32: [i8, i16, i32, i64], u32: [u8, u16, u32, u64]);
     cubecl_std::testgen!();
     cubecl_std::testgen_tensor_identity!([f16, flex32, f32, u32]);
+    cubecl_std::testgen_tensor_indexing!([f32, u32]);
+    cubecl_std::testgen_tensor_histogram!([f32]);
+    cubecl_std::testgen_tensor_topk!([f32]);
+    cubecl_std::testgen_tensor_concat!([f32]);
+    cubecl_std::testgen_tensor_pad!([f32]);
+    cubecl_std::testgen_tensor_io!();
     cubecl_std::testgen_quantized_view!(f16);
+    cubecl_std::testgen_quantize!(f16);
 }
 
 #[cfg(all(test, feature = "msl"))]
@@ -58,5 +72,12 @@ mod tests_msl {
     cubecl_core::testgen_all!(f32: [f16, f32], i32: [i16, i32], u32: [u16, u32]);
     cubecl_std::testgen!();
     cubecl_std::testgen_tensor_identity!([f16, flex32, f32, u32]);
+    cubecl_std::testgen_tensor_indexing!([f32, u32]);
+    cubecl_std::testgen_tensor_histogram!([f32]);
+    cubecl_std::testgen_tensor_topk!([f32]);
+    cubecl_std::testgen_tensor_concat!([f32]);
+    cubecl_std::testgen_tensor_pad!([f32]);
+    cubecl_std::testgen_tensor_io!();
     cubecl_std::testgen_quantized_view!(f16);
+    cubecl_std::testgen_quantize!(f16);
 }
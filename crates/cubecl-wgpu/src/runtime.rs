@@ -244,6 +244,10 @@ pub(crate) fn create_server(setup: WgpuSetup, options: RuntimeOptions) -> WgpuSe
             adapter_limits.max_compute_workgroup_size_z,
         ),
         num_streaming_multiprocessors: None,
+        max_cubes_per_multiprocessor: None,
+        max_units_per_multiprocessor: None,
+        max_shared_memory_per_multiprocessor: None,
+        registers_per_multiprocessor: None,
         num_tensor_cores: None,
         min_tensor_cores_dim: None,
         num_cpu_cores: None, // TODO: Check if device is CPU.
@@ -277,6 +281,15 @@ pub(crate) fn create_server(setup: WgpuSetup, options: RuntimeOptions) -> WgpuSe
         }
     }
 
+    // Textures are bound as filterable, which `f32` textures only are with this feature.
+    if setup
+        .device
+        .features()
+        .contains(wgpu::Features::FLOAT32_FILTERABLE)
+    {
+        device_props.register_semantic_type(cubecl_ir::SemanticType::Texture);
+    }
+
     #[cfg(any(feature = "spirv", feature = "msl"))]
     device_props
         .features